@@ -1,56 +1,149 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use hashlink::lru_cache::LruCache;
 
 /// A cache for prepared statements. When full, the least recently used
-/// statement gets removed.
+/// statement gets removed, or the oldest one with [`StatementCacheEviction::MaxAge`].
 #[derive(Debug)]
 pub struct StatementCache<T> {
     inner: LruCache<String, T>,
+    // the statements in the order they were inserted, with `StatementCacheEviction::MaxAge`
+    inserted: VecDeque<(String, Instant)>,
+    eviction: StatementCacheEviction,
+    stats: StatementCacheStats,
+}
+
+/// How a statement cache chooses the statements to remove.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatementCacheEviction {
+    /// When the cache is full, remove the least recently used statement.
+    #[default]
+    Lru,
+
+    /// Remove statements once they were prepared longer than this ago, and when the cache is
+    /// full, remove the oldest statement.
+    ///
+    /// This bounds how long the server keeps a statement, e.g. with a plan made for
+    /// data that has since changed.
+    MaxAge(Duration),
+}
+
+/// Statistics of the statement cache of a connection, since it was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StatementCacheStats {
+    /// The number of queries whose statement was in the cache.
+    pub hits: u64,
+
+    /// The number of queries whose statement was not in the cache, and had to be prepared.
+    pub misses: u64,
+
+    /// The number of statements removed from the cache because it was full, or because they
+    /// were too old.
+    pub evictions: u64,
 }
 
 impl<T> StatementCache<T> {
     /// Create a new cache with the given capacity.
     pub fn new(capacity: usize) -> Self {
+        Self::with_eviction(capacity, StatementCacheEviction::Lru)
+    }
+
+    /// Create a new cache with the given capacity, removing statements with `eviction`.
+    pub fn with_eviction(capacity: usize, eviction: StatementCacheEviction) -> Self {
         Self {
             inner: LruCache::new(capacity),
+            inserted: VecDeque::new(),
+            eviction,
+            stats: StatementCacheStats::default(),
         }
     }
 
     /// Returns a mutable reference to the value corresponding to the given key
     /// in the cache, if any.
     pub fn get_mut(&mut self, k: &str) -> Option<&mut T> {
-        self.inner.get_mut(k)
+        match self.inner.get_mut(k) {
+            Some(value) => {
+                self.stats.hits += 1;
+                Some(value)
+            }
+
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
     }
 
     /// Inserts a new statement to the cache, returning the least recently used
-    /// statement id if the cache is full, or if inserting with an existing key,
-    /// the replaced existing statement.
+    /// statement id (or the oldest one with [`StatementCacheEviction::MaxAge`]) if the cache
+    /// is full, or if inserting with an existing key, the replaced existing statement.
     pub fn insert(&mut self, k: &str, v: T) -> Option<T> {
         let mut lru_item = None;
 
         if self.capacity() == self.len() && !self.contains_key(k) {
             lru_item = self.remove_lru();
+            self.stats.evictions += u64::from(lru_item.is_some());
         } else if self.contains_key(k) {
-            lru_item = self.inner.remove(k);
+            lru_item = self.remove(k);
         }
 
         self.inner.insert(k.into(), v);
 
+        if matches!(self.eviction, StatementCacheEviction::MaxAge(_)) && self.is_enabled() {
+            self.inserted.push_back((k.into(), Instant::now()));
+        }
+
         lru_item
     }
 
+    /// Removes the oldest statement if it is older than allowed by
+    /// [`StatementCacheEviction::MaxAge`].
+    ///
+    /// Call this until it returns `None` to remove all of them.
+    pub fn remove_expired(&mut self) -> Option<T> {
+        let StatementCacheEviction::MaxAge(max_age) = self.eviction else {
+            return None;
+        };
+
+        // statements that were used recently expire as well
+        let (_, inserted) = self.inserted.front()?;
+
+        if inserted.elapsed() < max_age {
+            return None;
+        }
+
+        self.stats.evictions += 1;
+        self.remove_lru()
+    }
+
     /// The number of statements in the cache.
     pub fn len(&self) -> usize {
         self.inner.len()
     }
 
-    /// Removes the least recently used item from the cache.
+    /// Removes the least recently used item from the cache, or the oldest one with
+    /// [`StatementCacheEviction::MaxAge`].
     pub fn remove_lru(&mut self) -> Option<T> {
-        self.inner.remove_lru().map(|(_, v)| v)
+        match self.eviction {
+            StatementCacheEviction::Lru => self.inner.remove_lru().map(|(_, v)| v),
+            StatementCacheEviction::MaxAge(_) => {
+                let (k, _) = self.inserted.pop_front()?;
+                self.inner.remove(&k)
+            }
+        }
+    }
+
+    fn remove(&mut self, k: &str) -> Option<T> {
+        self.inserted.retain(|(key, _)| key != k);
+        self.inner.remove(k)
     }
 
     /// Clear all cached statements from the cache.
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.inserted.clear();
     }
 
     /// True if cache has a value for the given key.
@@ -68,4 +161,71 @@ impl<T> StatementCache<T> {
     pub fn is_enabled(&self) -> bool {
         self.capacity() > 0
     }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> StatementCacheStats {
+        self.stats
+    }
+}
+
+#[test]
+fn test_lru_eviction() {
+    let mut cache = StatementCache::new(2);
+
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+    assert_eq!(cache.get_mut("a"), Some(&mut 1));
+
+    // "b" is the least recently used
+    assert_eq!(cache.insert("c", 3), Some(2));
+    assert_eq!(cache.get_mut("b"), None);
+
+    assert_eq!(
+        cache.stats(),
+        StatementCacheStats {
+            hits: 1,
+            misses: 1,
+            evictions: 1,
+        }
+    );
+}
+
+#[test]
+fn test_max_age_eviction() {
+    let mut cache =
+        StatementCache::with_eviction(2, StatementCacheEviction::MaxAge(Duration::ZERO));
+
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+    assert_eq!(cache.get_mut("a"), Some(&mut 1));
+
+    // "a" is the oldest, even though it was used
+    assert_eq!(cache.insert("c", 3), Some(1));
+
+    assert_eq!(cache.remove_expired(), Some(2));
+    assert_eq!(cache.remove_expired(), Some(3));
+    assert_eq!(cache.remove_expired(), None);
+    assert_eq!(cache.stats().evictions, 3);
+
+    let mut cache =
+        StatementCache::with_eviction(2, StatementCacheEviction::MaxAge(Duration::from_secs(3600)));
+
+    cache.insert("a", 1);
+    assert_eq!(cache.remove_expired(), None);
+}
+
+#[test]
+fn test_max_age_expires_recently_used() {
+    let mut cache =
+        StatementCache::with_eviction(2, StatementCacheEviction::MaxAge(Duration::from_millis(50)));
+
+    cache.insert("a", 1);
+    std::thread::sleep(Duration::from_millis(60));
+    cache.insert("b", 2);
+
+    // "a" is the most recently used, but prepared too long ago
+    assert_eq!(cache.get_mut("a"), Some(&mut 1));
+    assert_eq!(cache.remove_expired(), Some(1));
+    assert_eq!(cache.remove_expired(), None);
+    assert_eq!(cache.get_mut("b"), Some(&mut 2));
 }
@@ -82,6 +82,10 @@ pub enum Error {
     #[error("error occurred while decoding: {0}")]
     Decode(#[source] BoxDynError),
 
+    /// Error occurred while encoding a value.
+    #[error("error occurred while encoding a value: {0}")]
+    Encode(#[source] BoxDynError),
+
     /// Error occurred within the `Any` driver mapping to/from the native driver.
     #[error("error in Any driver mapping: {0}")]
     AnyDriverError(#[source] BoxDynError),
@@ -100,6 +104,18 @@ pub enum Error {
     #[error("attempted to acquire a connection on a closed pool")]
     PoolClosed,
 
+    /// [`Pool::warm_up`] failed to open one or more connections.
+    ///
+    /// Contains the error for each connection that could not be opened.
+    ///
+    /// [`Pool::warm_up`]: crate::pool::Pool::warm_up
+    #[error(
+        "failed to open {} connection(s) while warming up the pool{}",
+        .0.len(),
+        first_error(.0)
+    )]
+    PoolWarmUp(Vec<Error>),
+
     /// A background worker has crashed.
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed,
@@ -147,6 +163,12 @@ impl Error {
     pub fn decode(err: impl Into<Box<dyn StdError + Send + Sync + 'static>>) -> Self {
         Error::Decode(err.into())
     }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn encode(err: impl Into<Box<dyn StdError + Send + Sync + 'static>>) -> Self {
+        Error::Encode(err.into())
+    }
 }
 
 pub fn mismatched_types<DB: Database, T: Type<DB>>(ty: &DB::TypeInfo) -> BoxDynError {
@@ -160,6 +182,13 @@ pub fn mismatched_types<DB: Database, T: Type<DB>>(ty: &DB::TypeInfo) -> BoxDynE
     .into()
 }
 
+// the first of `errors`, if any, to append to the message of an error that groups them
+fn first_error(errors: &[Error]) -> String {
+    errors
+        .first()
+        .map_or_else(String::new, |error| format!("; first error: {error}"))
+}
+
 /// The error kind.
 ///
 /// This enum is to be used to identify frequent errors that can be handled by the program.
@@ -4,6 +4,8 @@ mod buf_mut;
 mod decode;
 mod encode;
 mod read_buf;
+#[cfg(feature = "geo-types")]
+pub mod wkb;
 // mod write_and_flush;
 
 pub use buf::BufExt;
@@ -24,3 +26,15 @@ pub use futures_util::io::AsyncReadExt;
 
 #[cfg(feature = "_rt-tokio")]
 pub use tokio::io::AsyncReadExt;
+
+#[cfg(not(feature = "_rt-tokio"))]
+pub use futures_io::AsyncWrite;
+
+#[cfg(feature = "_rt-tokio")]
+pub use tokio::io::AsyncWrite;
+
+#[cfg(not(feature = "_rt-tokio"))]
+pub use futures_util::io::AsyncWriteExt;
+
+#[cfg(feature = "_rt-tokio")]
+pub use tokio::io::AsyncWriteExt;
@@ -0,0 +1,370 @@
+//! The WKB (well-known binary) representation of the geometries of `geo-types`, which the
+//! spatial types of MySQL and PostGIS are built on.
+//!
+//! Extended WKB (EWKB), the variant of PostGIS, is decoded as well; its SRID is skipped since
+//! it is not part of `geo-types`.
+//!
+//! <https://libgeos.org/specifications/wkb/>
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+
+use crate::error::BoxDynError;
+
+const WKB_BIG_ENDIAN: u8 = 0;
+const WKB_LITTLE_ENDIAN: u8 = 1;
+
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTI_POINT: u32 = 4;
+const WKB_MULTI_LINE_STRING: u32 = 5;
+const WKB_MULTI_POLYGON: u32 = 6;
+const WKB_GEOMETRY_COLLECTION: u32 = 7;
+
+// the flags EWKB sets in the type of a geometry
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+// geometries nested deeper than this are rejected, instead of overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+/// Decode a geometry from its WKB or EWKB representation.
+pub fn decode(wkb: &[u8]) -> Result<Geometry<f64>, BoxDynError> {
+    let mut reader = WkbReader { buf: wkb };
+    let geometry = reader.read_geometry(0)?;
+
+    if !reader.buf.is_empty() {
+        return Err("invalid geometry: trailing bytes after the WKB".into());
+    }
+
+    Ok(geometry)
+}
+
+/// A geometry that can be written in WKB.
+pub trait WriteWkb {
+    /// Append the little-endian WKB of this geometry to `buf`.
+    fn write_wkb(&self, buf: &mut Vec<u8>);
+}
+
+fn write_header(buf: &mut Vec<u8>, ty: u32) {
+    buf.push(WKB_LITTLE_ENDIAN);
+    buf.extend_from_slice(&ty.to_le_bytes());
+}
+
+fn write_coord(buf: &mut Vec<u8>, coord: Coord<f64>) {
+    buf.extend_from_slice(&coord.x.to_le_bytes());
+    buf.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_len(buf: &mut Vec<u8>, len: usize) {
+    buf.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn write_coords(buf: &mut Vec<u8>, line: &LineString<f64>) {
+    write_len(buf, line.0.len());
+
+    for &coord in &line.0 {
+        write_coord(buf, coord);
+    }
+}
+
+impl WriteWkb for Point<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_POINT);
+        write_coord(buf, self.0);
+    }
+}
+
+impl WriteWkb for LineString<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_LINE_STRING);
+        write_coords(buf, self);
+    }
+}
+
+impl WriteWkb for Polygon<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_POLYGON);
+
+        // an empty exterior ring is written as a polygon without rings; MySQL rejects the former
+        let rings = std::iter::once(self.exterior())
+            .filter(|ring| !ring.0.is_empty())
+            .chain(self.interiors());
+
+        write_len(buf, rings.clone().count());
+
+        for ring in rings {
+            write_coords(buf, ring);
+        }
+    }
+}
+
+impl WriteWkb for MultiPoint<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_MULTI_POINT);
+        write_len(buf, self.0.len());
+
+        for point in &self.0 {
+            point.write_wkb(buf);
+        }
+    }
+}
+
+impl WriteWkb for MultiLineString<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_MULTI_LINE_STRING);
+        write_len(buf, self.0.len());
+
+        for line in &self.0 {
+            line.write_wkb(buf);
+        }
+    }
+}
+
+impl WriteWkb for MultiPolygon<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_MULTI_POLYGON);
+        write_len(buf, self.0.len());
+
+        for polygon in &self.0 {
+            polygon.write_wkb(buf);
+        }
+    }
+}
+
+impl WriteWkb for GeometryCollection<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        write_header(buf, WKB_GEOMETRY_COLLECTION);
+        write_len(buf, self.0.len());
+
+        for geometry in &self.0 {
+            geometry.write_wkb(buf);
+        }
+    }
+}
+
+impl WriteWkb for Geometry<f64> {
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        match self {
+            Geometry::Point(point) => point.write_wkb(buf),
+            Geometry::LineString(line) => line.write_wkb(buf),
+            Geometry::Polygon(polygon) => polygon.write_wkb(buf),
+            Geometry::MultiPoint(points) => points.write_wkb(buf),
+            Geometry::MultiLineString(lines) => lines.write_wkb(buf),
+            Geometry::MultiPolygon(polygons) => polygons.write_wkb(buf),
+            Geometry::GeometryCollection(geometries) => geometries.write_wkb(buf),
+
+            // the types of `geo-types` without a WKB equivalent
+            Geometry::Line(line) => LineString::from(*line).write_wkb(buf),
+            Geometry::Rect(rect) => rect.to_polygon().write_wkb(buf),
+            Geometry::Triangle(triangle) => triangle.to_polygon().write_wkb(buf),
+        }
+    }
+}
+
+struct WkbReader<'a> {
+    buf: &'a [u8],
+}
+
+impl WkbReader<'_> {
+    fn read_geometry(&mut self, depth: usize) -> Result<Geometry<f64>, BoxDynError> {
+        if depth > MAX_DEPTH {
+            return Err("invalid geometry: nested too deeply".into());
+        }
+
+        let big_endian = match self.read_bytes::<1>()?[0] {
+            WKB_BIG_ENDIAN => true,
+            WKB_LITTLE_ENDIAN => false,
+            order => return Err(format!("invalid geometry: unknown byte order {order}").into()),
+        };
+
+        let mut ty = self.read_u32(big_endian)?;
+
+        if ty & (EWKB_Z | EWKB_M) != 0 {
+            return Err("invalid geometry: Z and M coordinates are not supported".into());
+        }
+
+        if ty & EWKB_SRID != 0 {
+            // the SRID is not part of `geo-types`
+            self.read_u32(big_endian)?;
+            ty &= !EWKB_SRID;
+        }
+
+        Ok(match ty {
+            WKB_POINT => Geometry::Point(Point(self.read_coord(big_endian)?)),
+            WKB_LINE_STRING => Geometry::LineString(self.read_coords(big_endian)?),
+
+            WKB_POLYGON => {
+                let len = self.read_len(big_endian, 4)?;
+                let mut rings = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    rings.push(self.read_coords(big_endian)?);
+                }
+
+                let mut rings = rings.into_iter();
+                let exterior = rings.next().unwrap_or_else(|| LineString(Vec::new()));
+
+                Geometry::Polygon(Polygon::new(exterior, rings.collect()))
+            }
+
+            WKB_MULTI_POINT
+            | WKB_MULTI_LINE_STRING
+            | WKB_MULTI_POLYGON
+            | WKB_GEOMETRY_COLLECTION => {
+                // each element is a complete geometry with its own header
+                let len = self.read_len(big_endian, 5)?;
+                let mut geometries = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    geometries.push(self.read_geometry(depth + 1)?);
+                }
+
+                match ty {
+                    WKB_MULTI_POINT => Geometry::MultiPoint(MultiPoint(
+                        geometries
+                            .into_iter()
+                            .map(Point::try_from)
+                            .collect::<Result<_, _>>()?,
+                    )),
+                    WKB_MULTI_LINE_STRING => Geometry::MultiLineString(MultiLineString(
+                        geometries
+                            .into_iter()
+                            .map(LineString::try_from)
+                            .collect::<Result<_, _>>()?,
+                    )),
+                    WKB_MULTI_POLYGON => Geometry::MultiPolygon(MultiPolygon(
+                        geometries
+                            .into_iter()
+                            .map(Polygon::try_from)
+                            .collect::<Result<_, _>>()?,
+                    )),
+                    _ => Geometry::GeometryCollection(GeometryCollection(geometries)),
+                }
+            }
+
+            _ => return Err(format!("invalid geometry: unsupported WKB type {ty}").into()),
+        })
+    }
+
+    fn read_coords(&mut self, big_endian: bool) -> Result<LineString<f64>, BoxDynError> {
+        let len = self.read_len(big_endian, 16)?;
+        let mut coords = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            coords.push(self.read_coord(big_endian)?);
+        }
+
+        Ok(LineString(coords))
+    }
+
+    fn read_coord(&mut self, big_endian: bool) -> Result<Coord<f64>, BoxDynError> {
+        Ok(Coord {
+            x: self.read_f64(big_endian)?,
+            y: self.read_f64(big_endian)?,
+        })
+    }
+
+    // read the number of elements, each of which takes at least `min_size` bytes
+    fn read_len(&mut self, big_endian: bool, min_size: usize) -> Result<usize, BoxDynError> {
+        let len = self.read_u32(big_endian)? as usize;
+
+        if len.saturating_mul(min_size) > self.buf.len() {
+            return Err("invalid geometry: unexpected end of the WKB".into());
+        }
+
+        Ok(len)
+    }
+
+    fn read_u32(&mut self, big_endian: bool) -> Result<u32, BoxDynError> {
+        let bytes = self.read_bytes()?;
+
+        Ok(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self, big_endian: bool) -> Result<f64, BoxDynError> {
+        let bytes = self.read_bytes()?;
+
+        Ok(if big_endian {
+            f64::from_be_bytes(bytes)
+        } else {
+            f64::from_le_bytes(bytes)
+        })
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], BoxDynError> {
+        if self.buf.len() < N {
+            return Err("invalid geometry: unexpected end of the WKB".into());
+        }
+
+        let (bytes, rest) = self.buf.split_at(N);
+        self.buf = rest;
+
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+#[test]
+fn test_wkb_roundtrip() {
+    use geo_types::{line_string, point, polygon};
+
+    let geometries: Vec<Geometry<f64>> = vec![
+        point!(x: 1.5, y: -2.0).into(),
+        line_string![(x: 0.0, y: 0.0), (x: 1.0, y: 1.0)].into(),
+        polygon![(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 0.0)].into(),
+        Geometry::GeometryCollection(GeometryCollection(vec![
+            point!(x: 1.0, y: 2.0).into(),
+            MultiPoint(vec![point!(x: 3.0, y: 4.0)]).into(),
+        ])),
+    ];
+
+    for geometry in geometries {
+        let mut wkb = Vec::new();
+        geometry.write_wkb(&mut wkb);
+
+        let mut reader = WkbReader { buf: &wkb };
+        assert_eq!(reader.read_geometry(0).unwrap(), geometry);
+        assert!(reader.buf.is_empty());
+    }
+
+    // POINT(1 2) in big endian
+    let wkb =
+        b"\x00\x00\x00\x00\x01\x3f\xf0\x00\x00\x00\x00\x00\x00\x40\x00\x00\x00\x00\x00\x00\x00";
+    let mut reader = WkbReader { buf: wkb };
+    assert_eq!(
+        reader.read_geometry(0).unwrap(),
+        Geometry::Point(point!(x: 1.0, y: 2.0))
+    );
+
+    // a line with more points than the WKB contains
+    let mut reader = WkbReader {
+        buf: b"\x01\x02\x00\x00\x00\xff\xff\xff\xff",
+    };
+    assert!(reader.read_geometry(0).is_err());
+}
+
+#[test]
+fn test_ewkb_decode() {
+    use geo_types::point;
+
+    // SRID=4326;POINT(1 2) in EWKB
+    let ewkb = b"\x01\x01\x00\x00\x20\xe6\x10\x00\x00\
+        \x00\x00\x00\x00\x00\x00\xf0\x3f\x00\x00\x00\x00\x00\x00\x00\x40";
+    assert_eq!(
+        decode(ewkb).unwrap(),
+        Geometry::Point(point!(x: 1.0, y: 2.0))
+    );
+
+    // POINT Z(1 2 3) in EWKB
+    let ewkb = b"\x01\x01\x00\x00\x80\x00\x00\x00\x00\x00\x00\xf0\x3f\
+        \x00\x00\x00\x00\x00\x00\x00\x40\x00\x00\x00\x00\x00\x00\x08\x40";
+    assert!(decode(ewkb).is_err());
+}
@@ -1,5 +1,7 @@
 use crate::net::Socket;
 use bytes::BytesMut;
+use futures_core::ready;
+use std::task::{Context, Poll};
 use std::{cmp, io};
 
 use crate::error::Error;
@@ -49,6 +51,32 @@ impl<S: Socket> BufferedSocket<S> {
         self.read_buf.read(len, &mut self.socket).await
     }
 
+    /// Poll-based equivalent of [`Self::read_buffered()`].
+    ///
+    /// Nothing is consumed until `len` bytes are available, so unlike the `async` version, it
+    /// is fine to stop polling this at any point.
+    pub fn poll_read_buffered(
+        &mut self,
+        cx: &mut Context<'_>,
+        len: usize,
+    ) -> Poll<io::Result<BytesMut>> {
+        ready!(self.read_buf.poll_fill(cx, len, &mut self.socket))?;
+
+        Poll::Ready(Ok(self.read_buf.drain(len)))
+    }
+
+    /// Wait until at least `len` bytes are buffered, and return all buffered bytes without
+    /// consuming them.
+    pub fn poll_fill_buf(&mut self, cx: &mut Context<'_>, len: usize) -> Poll<io::Result<&[u8]>> {
+        ready!(self.read_buf.poll_fill(cx, len, &mut self.socket))?;
+
+        Poll::Ready(Ok(&self.read_buf.read))
+    }
+
+    pub fn socket(&self) -> &S {
+        &self.socket
+    }
+
     pub fn write_buffer(&self) -> &WriteBuffer {
         &self.write_buf
     }
@@ -99,6 +127,25 @@ impl<S: Socket> BufferedSocket<S> {
         Ok(())
     }
 
+    /// Poll-based equivalent of [`Self::flush()`], for use in `poll_*` trait implementations.
+    pub fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            match self.socket.try_write(self.write_buf.get()) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    ready!(self.socket.poll_write_ready(cx))?;
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+                Ok(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Ok(written) => {
+                    self.write_buf.consume(written);
+                    self.write_buf.sanity_check();
+                }
+            }
+        }
+
+        self.socket.poll_flush(cx)
+    }
+
     pub async fn shutdown(&mut self) -> io::Result<()> {
         self.flush().await?;
         self.socket.shutdown().await
@@ -246,6 +293,37 @@ impl ReadBuffer {
         Ok(self.drain(len))
     }
 
+    fn poll_fill(
+        &mut self,
+        cx: &mut Context<'_>,
+        len: usize,
+        socket: &mut impl Socket,
+    ) -> Poll<io::Result<()>> {
+        while self.read.len() < len {
+            self.reserve(len - self.read.len());
+
+            match socket.try_read(&mut self.available) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    ready!(socket.poll_read_ready(cx))?;
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+                Ok(0) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!(
+                            "expected to read {} bytes, got {} bytes at EOF",
+                            len,
+                            self.read.len()
+                        ),
+                    )))
+                }
+                Ok(read) => self.advance(read),
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
     fn reserve(&mut self, amt: usize) {
         if let Some(additional) = amt.checked_sub(self.available.capacity()) {
             self.available.reserve(additional);
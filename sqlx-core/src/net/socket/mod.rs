@@ -29,6 +29,11 @@ pub trait Socket: Send + Sync + Unpin + 'static {
 
     fn poll_shutdown(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
 
+    /// Returns the DER-encoded certificate of the peer, if the socket is secured with TLS.
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        None
+    }
+
     fn read<'a, B: ReadBuf>(&'a mut self, buf: &'a mut B) -> Read<'a, Self, B>
     where
         Self: Sized,
@@ -176,6 +181,10 @@ impl<S: Socket + ?Sized> Socket for Box<S> {
     fn poll_shutdown(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         (**self).poll_shutdown(cx)
     }
+
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        (**self).peer_certificate()
+    }
 }
 
 pub async fn connect_tcp<Ws: WithSocket>(
@@ -258,3 +267,45 @@ pub async fn connect_uds<P: AsRef<Path>, Ws: WithSocket>(
         crate::rt::missing_rt((path, with_socket))
     }
 }
+
+/// Connect to the Windows named pipe `\\.\pipe\{name}` on the local machine.
+///
+/// Returns an error if named pipes are not supported on this platform or runtime.
+pub async fn connect_named_pipe<Ws: WithSocket>(
+    name: &str,
+    with_socket: Ws,
+) -> crate::Result<Ws::Output> {
+    #[cfg(all(windows, feature = "_rt-tokio"))]
+    if crate::rt::rt_tokio::available() {
+        use std::time::Duration;
+        use tokio::net::windows::named_pipe::ClientOptions;
+
+        // ERROR_PIPE_BUSY
+        const PIPE_BUSY: i32 = 231;
+
+        let path = format!(r"\\.\pipe\{name}");
+
+        let client = loop {
+            match ClientOptions::new().open(&path) {
+                Ok(client) => break client,
+                // every instance of the pipe is connected to another client
+                Err(e) if e.raw_os_error() == Some(PIPE_BUSY) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+
+        return Ok(with_socket.with_socket(client));
+    }
+
+    let _ = (name, with_socket);
+
+    let message = if cfg!(windows) {
+        "named pipes are only supported with the Tokio runtime"
+    } else {
+        "named pipes are not supported on this platform"
+    };
+
+    Err(io::Error::new(io::ErrorKind::Unsupported, message).into())
+}
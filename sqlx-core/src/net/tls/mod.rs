@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
 use std::path::PathBuf;
+#[cfg(feature = "_tls-rustls")]
+use std::sync::Arc;
 
 use crate::error::Error;
 use crate::net::socket::WithSocket;
@@ -15,7 +17,7 @@ mod tls_native_tls;
 mod util;
 
 /// X.509 Certificate input, either a file path or a PEM encoded inline certificate(s).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CertificateInput {
     /// PEM encoded certificate(s)
     Inline(Vec<u8>),
@@ -56,6 +58,24 @@ impl std::fmt::Display for CertificateInput {
     }
 }
 
+/// The TLS sessions of the connections opened with the same connect options, to resume them
+/// with a session ticket or id instead of a full handshake when another connection to the
+/// server is opened, e.g. by a pool.
+///
+/// Clones share the sessions. Only `rustls` resumes sessions; with `native-tls` every connection
+/// does a full handshake.
+#[derive(Clone, Default)]
+pub struct TlsSessionCache {
+    #[cfg(feature = "_tls-rustls")]
+    store: Arc<once_cell::sync::OnceCell<tls_rustls::SessionStore>>,
+}
+
+impl std::fmt::Debug for TlsSessionCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsSessionCache").finish_non_exhaustive()
+    }
+}
+
 pub struct TlsConfig<'a> {
     pub accept_invalid_certs: bool,
     pub accept_invalid_hostnames: bool,
@@ -63,6 +83,7 @@ pub struct TlsConfig<'a> {
     pub root_cert_path: Option<&'a CertificateInput>,
     pub client_cert_path: Option<&'a CertificateInput>,
     pub client_key_path: Option<&'a CertificateInput>,
+    pub session_cache: &'a TlsSessionCache,
 }
 
 pub async fn handshake<S, Ws>(
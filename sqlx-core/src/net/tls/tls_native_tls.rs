@@ -36,6 +36,11 @@ impl<S: Socket> Socket for NativeTlsSocket<S> {
             ready => Poll::Ready(ready),
         }
     }
+
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        let certificate = self.stream.peer_certificate().ok()??;
+        certificate.to_der().ok()
+    }
 }
 
 pub async fn handshake<S: Socket>(
@@ -61,6 +66,8 @@ pub async fn handshake<S: Socket>(
         builder.identity(identity);
     }
 
+    // NOTE: `native-tls` has no API to resume sessions, so unlike with `rustls` every connection
+    //       does a full handshake
     let connector = builder.build().map_err(Error::tls)?;
 
     let mut mid_handshake = match connector.connect(config.hostname, StdSocket::new(socket)) {
@@ -5,7 +5,10 @@ use std::task::{Context, Poll};
 use std::time::SystemTime;
 
 use rustls::{
-    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    client::{
+        ClientSessionMemoryCache, Resumption, ServerCertVerified, ServerCertVerifier,
+        WebPkiVerifier,
+    },
     CertificateError, ClientConfig, ClientConnection, Error as TlsError, OwnedTrustAnchor,
     RootCertStore, ServerName,
 };
@@ -13,9 +16,31 @@ use rustls::{
 use crate::error::Error;
 use crate::io::ReadBuf;
 use crate::net::tls::util::StdSocket;
-use crate::net::tls::TlsConfig;
+use crate::net::tls::{CertificateInput, TlsConfig, TlsSessionCache};
 use crate::net::Socket;
 
+// the sessions stored per server, the default of rustls
+const SESSIONS_PER_STORE: usize = 256;
+
+// The sessions of a `TlsSessionCache`.
+//
+// A resumed session skips the verification of the certificate of the server, so they are only
+// resumed by connections that verify the same way and present the same client certificate as
+// the first connection, e.g. not by the clones of connect options with another `ssl_mode`.
+pub(super) struct SessionStore {
+    key: SessionStoreKey,
+    sessions: Arc<ClientSessionMemoryCache>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct SessionStoreKey {
+    accept_invalid_certs: bool,
+    accept_invalid_hostnames: bool,
+    root_cert_path: Option<CertificateInput>,
+    client_cert_path: Option<CertificateInput>,
+    client_key_path: Option<CertificateInput>,
+}
+
 pub struct RustlsSocket<S: Socket> {
     inner: StdSocket<S>,
     state: ClientConnection,
@@ -73,6 +98,12 @@ impl<S: Socket> Socket for RustlsSocket<S> {
         futures_util::ready!(self.poll_complete_io(cx))?;
         self.inner.socket.poll_shutdown(cx)
     }
+
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        // the certificate of the server comes first
+        let certificate = self.state.peer_certificates()?.first()?;
+        Some(certificate.0.clone())
+    }
 }
 
 pub async fn handshake<S>(socket: S, tls_config: TlsConfig<'_>) -> Result<RustlsSocket<S>, Error>
@@ -96,7 +127,7 @@ where
         }
     };
 
-    let config = if tls_config.accept_invalid_certs {
+    let mut config = if tls_config.accept_invalid_certs {
         if let Some(user_auth) = user_auth {
             config
                 .with_custom_certificate_verifier(Arc::new(DummyTlsVerifier))
@@ -155,6 +186,8 @@ where
         }
     };
 
+    config.resumption = resumption(&tls_config);
+
     let host = rustls::ServerName::try_from(tls_config.hostname).map_err(Error::tls)?;
 
     let mut socket = RustlsSocket {
@@ -169,6 +202,29 @@ where
     Ok(socket)
 }
 
+fn resumption(tls_config: &TlsConfig<'_>) -> Resumption {
+    let key = SessionStoreKey {
+        accept_invalid_certs: tls_config.accept_invalid_certs,
+        accept_invalid_hostnames: tls_config.accept_invalid_hostnames,
+        root_cert_path: tls_config.root_cert_path.cloned(),
+        client_cert_path: tls_config.client_cert_path.cloned(),
+        client_key_path: tls_config.client_key_path.cloned(),
+    };
+
+    let TlsSessionCache { store } = tls_config.session_cache;
+
+    let store = store.get_or_init(|| SessionStore {
+        key: key.clone(),
+        sessions: Arc::new(ClientSessionMemoryCache::new(SESSIONS_PER_STORE)),
+    });
+
+    if store.key == key {
+        Resumption::store(store.sessions.clone())
+    } else {
+        Resumption::disabled()
+    }
+}
+
 fn certs_from_pem(pem: Vec<u8>) -> Result<Vec<rustls::Certificate>, Error> {
     let cur = Cursor::new(pem);
     let mut reader = BufReader::new(cur);
@@ -244,3 +300,151 @@ impl ServerCertVerifier for NoHostnameTlsVerifier {
         }
     }
 }
+
+#[cfg(all(test, feature = "_rt-tokio"))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rustls::server::{ServerSessionMemoryCache, StoresServerSessions};
+    use rustls::{ServerConfig, ServerConnection};
+
+    use super::*;
+    use crate::net::tls::TlsSessionCache;
+
+    // counts the sessions a client resumed
+    struct CountResumed {
+        sessions: Arc<ServerSessionMemoryCache>,
+        resumed: Arc<AtomicUsize>,
+    }
+
+    impl StoresServerSessions for CountResumed {
+        fn put(&self, key: Vec<u8>, value: Vec<u8>) -> bool {
+            self.sessions.put(key, value)
+        }
+
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.count(self.sessions.get(key))
+        }
+
+        fn take(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.count(self.sessions.take(key))
+        }
+
+        fn can_cache(&self) -> bool {
+            true
+        }
+    }
+
+    impl CountResumed {
+        fn count(&self, session: Option<Vec<u8>>) -> Option<Vec<u8>> {
+            if session.is_some() {
+                self.resumed.fetch_add(1, Ordering::SeqCst);
+            }
+
+            session
+        }
+    }
+
+    // accept TLS connections until the test ends, writing a byte after the handshake so
+    // clients read the session tickets sent with it
+    fn serve(listener: std::net::TcpListener, resumed: Arc<AtomicUsize>) {
+        let cert_chain =
+            certs_from_pem(include_bytes!("../../../../tests/certs/server.crt").to_vec()).unwrap();
+        let key =
+            private_key_from_pem(include_bytes!("../../../../tests/keys/server.key").to_vec())
+                .unwrap();
+
+        let mut config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, key)
+            .unwrap();
+
+        config.session_storage = Arc::new(CountResumed {
+            sessions: ServerSessionMemoryCache::new(16),
+            resumed,
+        });
+
+        let config = Arc::new(config);
+
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut conn = ServerConnection::new(config.clone()).unwrap();
+
+            while conn.is_handshaking() {
+                conn.complete_io(&mut stream).unwrap();
+            }
+
+            conn.writer().write_all(&[1]).unwrap();
+            conn.complete_io(&mut stream).unwrap();
+        }
+    }
+
+    async fn connect(
+        port: u16,
+        ca: &CertificateInput,
+        accept_invalid_hostnames: bool,
+        session_cache: &TlsSessionCache,
+    ) {
+        let socket = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+
+        let mut socket = handshake(
+            socket,
+            TlsConfig {
+                accept_invalid_certs: false,
+                accept_invalid_hostnames,
+                hostname: "sqlx.rs",
+                root_cert_path: Some(ca),
+                client_cert_path: None,
+                client_key_path: None,
+                session_cache,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut buf = bytes::BytesMut::with_capacity(1);
+        crate::net::Socket::read(&mut socket, &mut buf)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn it_resumes_sessions_of_the_same_cache() {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let resumed = Arc::new(AtomicUsize::new(0));
+
+        std::thread::spawn({
+            let resumed = resumed.clone();
+            move || serve(listener, resumed)
+        });
+
+        let ca =
+            CertificateInput::Inline(include_bytes!("../../../../tests/certs/ca.crt").to_vec());
+        let cache = TlsSessionCache::default();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            connect(port, &ca, false, &cache).await;
+            assert_eq!(resumed.load(Ordering::SeqCst), 0);
+
+            connect(port, &ca, false, &cache.clone()).await;
+            assert_eq!(resumed.load(Ordering::SeqCst), 1);
+
+            // another cache, e.g. of another pool
+            connect(port, &ca, false, &TlsSessionCache::default()).await;
+            assert_eq!(resumed.load(Ordering::SeqCst), 1);
+
+            // a connection that verifies the server another way
+            connect(port, &ca, true, &cache).await;
+            assert_eq!(resumed.load(Ordering::SeqCst), 1);
+        });
+    }
+}
@@ -82,6 +82,7 @@ impl<DB: Database> PoolConnection<DB> {
     /// [`.close()`]: Connection::close
     pub async fn close(mut self) -> Result<(), Error> {
         let floating = self.take_live().float(self.pool.clone());
+        floating.guard.pool.metrics.connection_closed();
         floating.inner.raw.close().await
     }
 
@@ -165,7 +166,7 @@ impl<'c, DB: Database> crate::acquire::Acquire<'c> for &'c mut PoolConnection<DB
 impl<DB: Database> Drop for PoolConnection<DB> {
     fn drop(&mut self) {
         // We still need to spawn a task to maintain `min_connections`.
-        if self.live.is_some() || self.pool.options.min_connections > 0 {
+        if self.live.is_some() || self.pool.min_connections() > 0 {
             crate::rt::spawn(self.return_to_pool());
         }
     }
@@ -239,6 +240,12 @@ impl<DB: Database> Floating<DB, Live<DB>> {
             return false;
         }
 
+        // The pool was shrunk with `Pool::set_max_connections()`.
+        if self.guard.pool.size() > self.guard.pool.max_connections() {
+            self.close().await;
+            return false;
+        }
+
         if let Some(test) = &self.guard.pool.options.after_release {
             let meta = self.metadata();
             match (test)(&mut self.inner.raw, meta).await {
@@ -281,6 +288,7 @@ impl<DB: Database> Floating<DB, Live<DB>> {
     }
 
     pub async fn close(self) {
+        self.guard.pool.metrics.connection_closed();
         // This isn't used anywhere that we care about the return value
         let _ = self.inner.raw.close().await;
 
@@ -288,10 +296,12 @@ impl<DB: Database> Floating<DB, Live<DB>> {
     }
 
     pub async fn close_hard(self) {
+        self.guard.pool.metrics.connection_closed();
         let _ = self.inner.raw.close_hard().await;
     }
 
     pub fn detach(self) -> DB::Connection {
+        self.guard.pool.metrics.connection_closed();
         self.inner.raw
     }
 
@@ -334,6 +344,7 @@ impl<DB: Database> Floating<DB, Idle<DB>> {
     }
 
     pub async fn close(self) -> DecrementSizeGuard<DB> {
+        self.guard.pool.metrics.connection_closed();
         if let Err(error) = self.inner.live.raw.close().await {
             tracing::debug!(%error, "error occurred while closing the pool connection");
         }
@@ -341,6 +352,7 @@ impl<DB: Database> Floating<DB, Idle<DB>> {
     }
 
     pub async fn close_hard(self) -> DecrementSizeGuard<DB> {
+        self.guard.pool.metrics.connection_closed();
         let _ = self.inner.live.raw.close_hard().await;
 
         self.guard
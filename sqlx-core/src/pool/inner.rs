@@ -3,15 +3,17 @@ use crate::connection::ConnectOptions;
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
+use crate::pool::metrics::{MetricsRecorder, PoolMetrics};
+use crate::pool::options::{HealthCheck, ReusePolicy};
 use crate::pool::{deadline_as_timeout, CloseEvent, Pool, PoolOptions};
-use crossbeam_queue::ArrayQueue;
+use crossbeam_queue::SegQueue;
 
 use crate::sync::{AsyncSemaphore, AsyncSemaphoreReleaser};
 
 use std::cmp;
 use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::task::Poll;
 
 use crate::pool::options::PoolConnectionMetadata;
@@ -21,12 +23,18 @@ use std::time::{Duration, Instant};
 
 pub(crate) struct PoolInner<DB: Database> {
     pub(super) connect_options: RwLock<Arc<<DB::Connection as Connection>::Options>>,
-    pub(super) idle_conns: ArrayQueue<Idle<DB>>,
+    pub(super) idle_conns: IdleQueue<DB>,
     pub(super) semaphore: AsyncSemaphore,
     pub(super) size: AtomicU32,
     pub(super) num_idle: AtomicUsize,
+    max_connections: AtomicU32,
+    min_connections: AtomicU32,
+    /// The number of permits of `semaphore`, which lags behind `max_connections` while the pool
+    /// shrinks. The lock is also held while the connection limits change.
+    permits: Mutex<u32>,
     is_closed: AtomicBool,
     pub(super) on_closed: event_listener::Event,
+    pub(super) metrics: MetricsRecorder,
     pub(super) options: PoolOptions<DB>,
 }
 
@@ -48,12 +56,16 @@ impl<DB: Database> PoolInner<DB> {
 
         let pool = Self {
             connect_options: RwLock::new(Arc::new(connect_options)),
-            idle_conns: ArrayQueue::new(capacity),
+            idle_conns: IdleQueue::new(options.reuse_policy),
             semaphore: AsyncSemaphore::new(options.fair, semaphore_capacity),
             size: AtomicU32::new(0),
             num_idle: AtomicUsize::new(0),
+            max_connections: AtomicU32::new(options.max_connections),
+            min_connections: AtomicU32::new(options.min_connections),
+            permits: Mutex::new(semaphore_capacity as u32),
             is_closed: AtomicBool::new(false),
             on_closed: event_listener::Event::new(),
+            metrics: MetricsRecorder::new(options.metrics_observer.clone()),
             options,
         };
 
@@ -77,6 +89,140 @@ impl<DB: Database> PoolInner<DB> {
         self.num_idle.load(Ordering::Acquire)
     }
 
+    pub(super) fn max_connections(&self) -> u32 {
+        self.max_connections.load(Ordering::Acquire)
+    }
+
+    pub(super) fn min_connections(&self) -> u32 {
+        self.min_connections.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_max_connections(self: &Arc<Self>, max: u32) -> Result<(), Error> {
+        let mut permits = self.lock_permits();
+
+        if max == 0 {
+            return Err(Error::Configuration(
+                "`max_connections` must be at least 1".into(),
+            ));
+        }
+
+        let min = self.min_connections();
+        if max < min {
+            return Err(Error::Configuration(
+                format!("`max_connections` ({max}) is less than `min_connections` ({min})").into(),
+            ));
+        }
+
+        if let Some(parent) = self.parent() {
+            let parent_max = parent.max_connections();
+            if max > parent_max {
+                return Err(Error::Configuration(
+                    format!(
+                        "`max_connections` ({max}) is greater than that of the parent pool ({parent_max})"
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        self.max_connections.store(max, Ordering::Release);
+
+        if self.is_closed() {
+            return Ok(());
+        }
+
+        // The permits of a child pool are stolen from the parent instead.
+        if self.parent().is_some() {
+            let pool = self.clone();
+            crate::rt::spawn(async move { pool.retire_idle().await });
+            return Ok(());
+        }
+
+        if max > *permits {
+            // This also offsets the permits a shrink in progress has yet to remove.
+            self.semaphore.release((max - *permits) as usize);
+            *permits = max;
+        } else if max < *permits {
+            let pool = self.clone();
+            let mut close_event = self.close_event();
+
+            crate::rt::spawn(async move {
+                let _ = close_event.do_until(pool.shrink()).await;
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn set_min_connections(self: &Arc<Self>, min: u32) -> Result<(), Error> {
+        let _permits = self.lock_permits();
+
+        let max = self.max_connections();
+        if min > max {
+            return Err(Error::Configuration(
+                format!("`min_connections` ({min}) is greater than `max_connections` ({max})")
+                    .into(),
+            ));
+        }
+
+        let prev = self.min_connections.swap(min, Ordering::AcqRel);
+
+        if min > prev {
+            let pool = self.clone();
+            crate::rt::spawn(async move { pool.min_connections_maintenance(None).await });
+        }
+
+        Ok(())
+    }
+
+    fn lock_permits(&self) -> MutexGuard<'_, u32> {
+        self.permits
+            .lock()
+            .expect("BUG: panicked while holding a lock")
+    }
+
+    /// Remove permits from the semaphore down to `max_connections`, as the connections that hold
+    /// them are returned, and close the idle connections above `max_connections`.
+    ///
+    /// Stops early if `max_connections` is raised again in the meantime.
+    async fn shrink(self: &Arc<Self>) {
+        loop {
+            self.retire_idle().await;
+
+            if *self.lock_permits() <= self.max_connections() {
+                break;
+            }
+
+            let permit = self.semaphore.acquire(1).await;
+
+            let mut permits = self.lock_permits();
+
+            if *permits <= self.max_connections() {
+                break;
+            }
+
+            permit.disarm();
+            *permits -= 1;
+        }
+
+        self.retire_idle().await;
+    }
+
+    /// Close idle connections while the pool has more than `max_connections`.
+    async fn retire_idle(self: &Arc<Self>) {
+        while self.size() > self.max_connections() {
+            let Some(idle) = self.try_acquire() else {
+                return;
+            };
+
+            let _ = idle.close().await;
+        }
+    }
+
+    pub(super) fn metrics(&self) -> PoolMetrics {
+        self.metrics.snapshot(self.size(), self.num_idle())
+    }
+
     pub(super) fn is_closed(&self) -> bool {
         self.is_closed.load(Ordering::Acquire)
     }
@@ -90,7 +236,7 @@ impl<DB: Database> PoolInner<DB> {
         self.mark_closed();
 
         async move {
-            for permits in 1..=self.options.max_connections {
+            for permits in 1..=self.max_connections() {
                 // Close any currently idle connections in the pool.
                 while let Some(idle) = self.idle_conns.pop() {
                     let _ = idle.live.float((*self).clone()).close().await;
@@ -121,7 +267,7 @@ impl<DB: Database> PoolInner<DB> {
             .parent()
             // If we're already at the max size, we shouldn't try to steal from the parent.
             // This is just going to cause unnecessary churn in `acquire()`.
-            .filter(|_| self.size() < self.options.max_connections);
+            .filter(|_| self.size() < self.max_connections());
 
         let acquire_self = self.semaphore.acquire(1).fuse();
         let mut close_event = self.close_event();
@@ -201,9 +347,7 @@ impl<DB: Database> PoolInner<DB> {
 
         let Floating { inner: idle, guard } = floating.into_idle();
 
-        if !self.idle_conns.push(idle).is_ok() {
-            panic!("BUG: connection queue overflow in release()");
-        }
+        self.idle_conns.push(idle);
 
         // NOTE: we need to make sure we drop the permit *after* we push to the idle queue
         // don't decrease the size
@@ -227,7 +371,7 @@ impl<DB: Database> PoolInner<DB> {
                 }
 
                 size.checked_add(1)
-                    .filter(|size| size <= &self.options.max_connections)
+                    .filter(|size| size <= &self.max_connections())
             }) {
             // we successfully incremented the size
             Ok(_) => Ok(DecrementSizeGuard::from_permit((*self).clone(), permit)),
@@ -236,15 +380,19 @@ impl<DB: Database> PoolInner<DB> {
         }
     }
 
-    pub(super) async fn acquire(self: &Arc<Self>) -> Result<Floating<DB, Live<DB>>, Error> {
+    pub(super) async fn acquire(
+        self: &Arc<Self>,
+        timeout: Duration,
+    ) -> Result<Floating<DB, Live<DB>>, Error> {
         if self.is_closed() {
             return Err(Error::PoolClosed);
         }
 
-        let deadline = Instant::now() + self.options.acquire_timeout;
+        let started_at = Instant::now();
+        let deadline = started_at + timeout;
 
-        crate::rt::timeout(
-            self.options.acquire_timeout,
+        let res = crate::rt::timeout(
+            timeout,
             async {
                 loop {
                     // Handles the close-event internally
@@ -286,7 +434,16 @@ impl<DB: Database> PoolInner<DB> {
             }
         )
             .await
-            .map_err(|_| Error::PoolTimedOut)?
+            .map_err(|_| Error::PoolTimedOut)
+            .and_then(|res| res);
+
+        match &res {
+            Ok(_) => self.metrics.acquired(started_at.elapsed()),
+            Err(Error::PoolTimedOut) => self.metrics.acquire_timed_out(started_at.elapsed()),
+            Err(_) => (),
+        }
+
+        res
     }
 
     pub(super) async fn connect(
@@ -330,9 +487,13 @@ impl<DB: Database> PoolInner<DB> {
                     };
 
                     match res {
-                        Ok(()) => return Ok(Floating::new_live(raw, guard)),
+                        Ok(()) => {
+                            self.metrics.connection_opened();
+                            return Ok(Floating::new_live(raw, guard));
+                        }
                         Err(error) => {
                             tracing::error!(%error, "error returned from after_connect");
+                            self.metrics.connect_failed(&error);
                             // The connection is broken, don't try to close nicely.
                             let _ = raw.close_hard().await;
 
@@ -341,15 +502,21 @@ impl<DB: Database> PoolInner<DB> {
                     }
                 }
 
-                // an IO error while connecting is assumed to be the system starting up
-                Ok(Err(Error::Io(e))) if e.kind() == std::io::ErrorKind::ConnectionRefused => (),
+                Ok(Err(error)) => {
+                    self.metrics.connect_failed(&error);
+
+                    match error {
+                        // an IO error while connecting is assumed to be the system starting up
+                        Error::Io(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => (),
 
-                // We got a transient database error, retry.
-                Ok(Err(Error::Database(error))) if error.is_transient_in_connect_phase() => (),
+                        // We got a transient database error, retry.
+                        Error::Database(e) if e.is_transient_in_connect_phase() => (),
 
-                // Any other error while connection should immediately
-                // terminate and bubble the error up
-                Ok(Err(e)) => return Err(e),
+                        // Any other error while connection should immediately
+                        // terminate and bubble the error up
+                        e => return Err(e),
+                    }
+                }
 
                 // timed out
                 Err(_) => return Err(Error::PoolTimedOut),
@@ -365,19 +532,19 @@ impl<DB: Database> PoolInner<DB> {
 
     /// Try to maintain `min_connections`, returning any errors (including `PoolTimedOut`).
     pub async fn try_min_connections(self: &Arc<Self>, deadline: Instant) -> Result<(), Error> {
-        while self.size() < self.options.min_connections {
+        while self.size() < self.min_connections() {
             // Don't wait for a semaphore permit.
             //
             // If no extra permits are available then we shouldn't be trying to spin up
             // connections anyway.
             let Some(permit) = self.semaphore.try_acquire(1) else {
-				return Ok(());
-			};
+                return Ok(());
+            };
 
             // We must always obey `max_connections`.
             let Some(guard) = self.try_increment_size(permit).ok() else {
-				return Ok(());
-			};
+                return Ok(());
+            };
 
             // We skip `after_release` since the connection was never provided to user code
             // besides `after_connect`, if they set it.
@@ -387,6 +554,97 @@ impl<DB: Database> PoolInner<DB> {
         Ok(())
     }
 
+    /// Like `connect()`, but makes a single attempt instead of retrying transient errors.
+    async fn connect_once(
+        self: &Arc<Self>,
+        deadline: Instant,
+        guard: DecrementSizeGuard<DB>,
+    ) -> Result<Floating<DB, Live<DB>>, Error> {
+        if self.is_closed() {
+            return Err(Error::PoolClosed);
+        }
+
+        let timeout = deadline_as_timeout::<DB>(deadline)?;
+
+        let connect_options = self
+            .connect_options
+            .read()
+            .expect("write-lock holder panicked")
+            .clone();
+
+        let mut raw = match crate::rt::timeout(timeout, connect_options.connect()).await {
+            Ok(Ok(raw)) => raw,
+            Ok(Err(error)) => {
+                self.metrics.connect_failed(&error);
+                return Err(error);
+            }
+            Err(_) => return Err(Error::PoolTimedOut),
+        };
+
+        // See comment on `PoolOptions::after_connect`
+        let meta = PoolConnectionMetadata {
+            age: Duration::ZERO,
+            idle_for: Duration::ZERO,
+        };
+
+        if let Some(callback) = &self.options.after_connect {
+            if let Err(error) = callback(&mut raw, meta).await {
+                self.metrics.connect_failed(&error);
+                // The connection is broken, don't try to close nicely.
+                let _ = raw.close_hard().await;
+                return Err(error);
+            }
+        }
+
+        self.metrics.connection_opened();
+        Ok(Floating::new_live(raw, guard))
+    }
+
+    /// Open connections up to `min_connections` concurrently, making a single attempt for each
+    /// and returning every error.
+    pub(super) async fn warm_up(self: &Arc<Self>, deadline: Instant) -> Result<(), Error> {
+        if self.is_closed() {
+            return Err(Error::PoolClosed);
+        }
+
+        let mut guards = Vec::new();
+
+        while self.size() < self.min_connections() {
+            // Same as `try_min_connections()`, don't wait for permits.
+            let Some(permit) = self.semaphore.try_acquire(1) else {
+                break;
+            };
+
+            let Ok(guard) = self.try_increment_size(permit) else {
+                break;
+            };
+
+            guards.push(guard);
+        }
+
+        let results = futures_util::future::join_all(
+            guards
+                .into_iter()
+                .map(|guard| self.connect_once(deadline, guard)),
+        )
+        .await;
+
+        let mut errors = Vec::new();
+
+        for res in results {
+            match res {
+                Ok(conn) => self.release(conn),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::PoolWarmUp(errors))
+        }
+    }
+
     /// Attempt to maintain `min_connections`, logging if unable.
     pub async fn min_connections_maintenance(self: &Arc<Self>, deadline: Option<Instant>) {
         let deadline = deadline.unwrap_or_else(|| {
@@ -440,13 +698,23 @@ async fn check_idle_conn<DB: Database>(
         return Err(conn.close().await);
     }
 
-    if options.test_before_acquire {
+    let recently_used = options
+        .health_check_after_idle
+        .map_or(false, |after_idle| conn.idle_since.elapsed() < after_idle);
+
+    if !recently_used {
         // Check that the connection is still live
-        if let Err(error) = conn.ping().await {
+        let res = match &options.health_check {
+            HealthCheck::Skip => Ok(()),
+            HealthCheck::Ping => conn.ping().await,
+            HealthCheck::Query { execute, .. } => execute(&mut conn.live.raw).await,
+        };
+
+        if let Err(error) = res {
             // an error here means the other end has hung up or we lost connectivity
             // either way we're fine to just discard the connection
             // the error itself here isn't necessarily unexpected so WARN is too strong
-            tracing::info!(%error, "ping on idle connection returned error");
+            tracing::info!(%error, "health check on idle connection returned error");
             // connection is broken so don't try to close nicely
             return Err(conn.close_hard().await);
         }
@@ -485,7 +753,7 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
         (Some(a), Some(b)) => cmp::min(a, b),
 
         (None, None) => {
-            if pool.options.min_connections > 0 {
+            if pool.min_connections() > 0 {
                 crate::rt::spawn(async move {
                     if let Some(pool) = pool_weak.upgrade() {
                         pool.min_connections_maintenance(None).await;
@@ -540,7 +808,7 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
 
 async fn do_reap<DB: Database>(pool: &Arc<PoolInner<DB>>) {
     // reap at most the current size minus the minimum idle
-    let max_reaped = pool.size().saturating_sub(pool.options.min_connections);
+    let max_reaped = pool.size().saturating_sub(pool.min_connections());
 
     // collect connections to reap
     let (reap, keep) = (0..max_reaped)
@@ -551,8 +819,14 @@ async fn do_reap<DB: Database>(pool: &Arc<PoolInner<DB>>) {
                 || is_beyond_max_lifetime(conn, &pool.options)
         });
 
+    // return valid connections to the pool first, in the order they were in
+    let keep: Box<dyn Iterator<Item = _>> = match pool.options.reuse_policy {
+        ReusePolicy::Fifo => Box::new(keep.into_iter()),
+        // the most recently used connections were popped first
+        ReusePolicy::Lifo => Box::new(keep.into_iter().rev()),
+    };
+
     for conn in keep {
-        // return valid connections to the pool first
         pool.release(conn.into_live());
     }
 
@@ -561,6 +835,55 @@ async fn do_reap<DB: Database>(pool: &Arc<PoolInner<DB>>) {
     }
 }
 
+/// The idle connections, handed out in the order given by
+/// [`PoolOptions::reuse_policy`][crate::pool::PoolOptions::reuse_policy].
+///
+/// Neither queue is bounded by `max_connections`, which can change.
+pub(super) enum IdleQueue<DB: Database> {
+    // `SegQueue` is much larger than `Mutex<Vec<_>>`
+    Fifo(Box<SegQueue<Idle<DB>>>),
+    Lifo(Mutex<Vec<Idle<DB>>>),
+}
+
+impl<DB: Database> IdleQueue<DB> {
+    fn new(policy: ReusePolicy) -> Self {
+        match policy {
+            ReusePolicy::Fifo => IdleQueue::Fifo(Box::new(SegQueue::new())),
+            ReusePolicy::Lifo => IdleQueue::Lifo(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub(super) fn push(&self, idle: Idle<DB>) {
+        match self {
+            IdleQueue::Fifo(queue) => queue.push(idle),
+            IdleQueue::Lifo(stack) => stack
+                .lock()
+                .expect("BUG: panicked while holding a lock")
+                .push(idle),
+        }
+    }
+
+    pub(super) fn pop(&self) -> Option<Idle<DB>> {
+        match self {
+            IdleQueue::Fifo(queue) => queue.pop(),
+            IdleQueue::Lifo(stack) => stack
+                .lock()
+                .expect("BUG: panicked while holding a lock")
+                .pop(),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        match self {
+            IdleQueue::Fifo(queue) => queue.is_empty(),
+            IdleQueue::Lifo(stack) => stack
+                .lock()
+                .expect("BUG: panicked while holding a lock")
+                .is_empty(),
+        }
+    }
+}
+
 /// RAII guard returned by `Pool::try_increment_size()` and others.
 ///
 /// Will decrement the pool size if dropped, to avoid semantically "leaking" connections
@@ -0,0 +1,240 @@
+//! Counters and histograms of what a pool does, for monitoring.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// The upper bounds of the buckets of [`AcquireWaitHistogram`], after which is a last bucket
+/// without a bound.
+const ACQUIRE_WAIT_BOUNDS: [Duration; 12] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// Receives the events of a pool as they happen, to bridge them to a metrics library such as
+/// `metrics` or `prometheus`. Set with
+/// [`PoolOptions::metrics_observer()`][crate::pool::PoolOptions::metrics_observer].
+///
+/// All methods do nothing by default. They are called from the tasks that use the pool, so
+/// they should return quickly, and not block.
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use sqlx::pool::PoolMetricsObserver;
+///
+/// struct Metrics;
+///
+/// impl PoolMetricsObserver for Metrics {
+///     fn acquired(&self, wait: Duration) {
+///         metrics::histogram!("db_pool_acquire_seconds").record(wait.as_secs_f64());
+///     }
+///
+///     fn acquire_timed_out(&self, _wait: Duration) {
+///         metrics::counter!("db_pool_acquire_timeouts_total").increment(1);
+///     }
+/// }
+///
+/// let pool = PgPoolOptions::new()
+///     .metrics_observer(Metrics)
+///     .connect("postgres://...")
+///     .await?;
+/// ```
+pub trait PoolMetricsObserver: Send + Sync + 'static {
+    /// A connection was acquired after waiting for `wait`, including the time to open it, if
+    /// it was opened for this acquire.
+    fn acquired(&self, wait: Duration) {
+        let _ = wait;
+    }
+
+    /// An acquire failed with [`Error::PoolTimedOut`] after waiting for `wait`.
+    fn acquire_timed_out(&self, wait: Duration) {
+        let _ = wait;
+    }
+
+    /// A new connection was opened.
+    fn connection_opened(&self) {}
+
+    /// A connection of the pool was closed, or detached from the pool.
+    fn connection_closed(&self) {}
+
+    /// An attempt to open a new connection failed with `error`, including an error of
+    /// [`after_connect`][crate::pool::PoolOptions::after_connect]. The attempt may be retried.
+    fn connect_failed(&self, error: &Error) {
+        let _ = error;
+    }
+}
+
+/// The metrics of a pool, returned by [`Pool::metrics()`][crate::pool::Pool::metrics].
+///
+/// The counters are totals since the pool was created.
+#[derive(Debug, Clone)]
+#[non_exhaustive] // So we can safely add fields in the future.
+pub struct PoolMetrics {
+    /// The number of connections, idle or in use.
+    pub size: u32,
+
+    /// The number of idle connections.
+    pub num_idle: usize,
+
+    /// The number of connections in use.
+    pub in_use: u32,
+
+    /// The number of connections that were acquired.
+    pub acquired: u64,
+
+    /// The number of acquires that failed with [`Error::PoolTimedOut`].
+    pub acquire_timeouts: u64,
+
+    /// The number of connections that were opened.
+    pub connections_opened: u64,
+
+    /// The number of connections that were closed, or detached from the pool.
+    pub connections_closed: u64,
+
+    /// The number of attempts to open a connection that failed.
+    pub connect_errors: u64,
+
+    /// How long the acquired connections were waited for.
+    pub acquire_wait: AcquireWaitHistogram,
+}
+
+/// A histogram of how long acquired connections were waited for.
+#[derive(Debug, Clone)]
+pub struct AcquireWaitHistogram {
+    buckets: Vec<(Option<Duration>, u64)>,
+    sum: Duration,
+}
+
+impl AcquireWaitHistogram {
+    /// Returns the upper bound of each bucket, which is `None` for the last one, and the number
+    /// of waits in the bucket that are longer than the bound of the bucket before.
+    pub fn buckets(&self) -> &[(Option<Duration>, u64)] {
+        &self.buckets
+    }
+
+    /// Returns the number of waits.
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Returns the total time of the waits.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+}
+
+/// Records the metrics of a pool, and passes the events on to the observer.
+#[derive(Default)]
+pub(crate) struct MetricsRecorder {
+    acquired: AtomicU64,
+    acquire_timeouts: AtomicU64,
+    connections_opened: AtomicU64,
+    connections_closed: AtomicU64,
+    connect_errors: AtomicU64,
+    acquire_wait: [AtomicU64; ACQUIRE_WAIT_BOUNDS.len() + 1],
+    acquire_wait_nanos: AtomicU64,
+    observer: Option<Arc<dyn PoolMetricsObserver>>,
+}
+
+impl MetricsRecorder {
+    pub(crate) fn new(observer: Option<Arc<dyn PoolMetricsObserver>>) -> Self {
+        MetricsRecorder {
+            observer,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn acquired(&self, wait: Duration) {
+        self.acquired.fetch_add(1, Ordering::Relaxed);
+
+        let bucket = ACQUIRE_WAIT_BOUNDS
+            .iter()
+            .position(|bound| wait <= *bound)
+            .unwrap_or(ACQUIRE_WAIT_BOUNDS.len());
+
+        self.acquire_wait[bucket].fetch_add(1, Ordering::Relaxed);
+        self.acquire_wait_nanos.fetch_add(
+            u64::try_from(wait.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+
+        if let Some(observer) = &self.observer {
+            observer.acquired(wait);
+        }
+    }
+
+    pub(crate) fn acquire_timed_out(&self, wait: Duration) {
+        self.acquire_timeouts.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(observer) = &self.observer {
+            observer.acquire_timed_out(wait);
+        }
+    }
+
+    pub(crate) fn connection_opened(&self) {
+        self.connections_opened.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(observer) = &self.observer {
+            observer.connection_opened();
+        }
+    }
+
+    pub(crate) fn connection_closed(&self) {
+        self.connections_closed.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(observer) = &self.observer {
+            observer.connection_closed();
+        }
+    }
+
+    pub(crate) fn connect_failed(&self, error: &Error) {
+        self.connect_errors.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(observer) = &self.observer {
+            observer.connect_failed(error);
+        }
+    }
+
+    pub(crate) fn snapshot(&self, size: u32, num_idle: usize) -> PoolMetrics {
+        let bounds = ACQUIRE_WAIT_BOUNDS.iter().copied().map(Some).chain([None]);
+
+        PoolMetrics {
+            size,
+            num_idle,
+            in_use: size.saturating_sub(num_idle as u32),
+            acquired: self.acquired.load(Ordering::Relaxed),
+            acquire_timeouts: self.acquire_timeouts.load(Ordering::Relaxed),
+            connections_opened: self.connections_opened.load(Ordering::Relaxed),
+            connections_closed: self.connections_closed.load(Ordering::Relaxed),
+            connect_errors: self.connect_errors.load(Ordering::Relaxed),
+            acquire_wait: AcquireWaitHistogram {
+                buckets: bounds
+                    .zip(&self.acquire_wait)
+                    .map(|(bound, count)| (bound, count.load(Ordering::Relaxed)))
+                    .collect(),
+                sum: Duration::from_nanos(self.acquire_wait_nanos.load(Ordering::Relaxed)),
+            },
+        }
+    }
+}
+
+impl Debug for MetricsRecorder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsRecorder")
+            .field("observer", &self.observer.is_some())
+            .finish_non_exhaustive()
+    }
+}
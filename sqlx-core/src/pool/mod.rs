@@ -53,6 +53,11 @@
 //! A connection or transaction may also be manually acquired with
 //! [`Pool::acquire`] or
 //! [`Pool::begin`].
+//!
+//! # Read replicas
+//!
+//! [`ReplicaPool`] combines the pool of a primary database with the pools of its read replicas,
+//! handing out connections to the replicas for reads and to the primary for writes.
 
 use self::inner::PoolInner;
 #[cfg(all(
@@ -87,10 +92,14 @@ pub mod maybe;
 
 mod connection;
 mod inner;
+mod metrics;
 mod options;
+mod replica;
 
 pub use self::connection::PoolConnection;
-pub use self::options::{PoolConnectionMetadata, PoolOptions};
+pub use self::metrics::{AcquireWaitHistogram, PoolMetrics, PoolMetricsObserver};
+pub use self::options::{PoolConnectionMetadata, PoolOptions, ReusePolicy};
+pub use self::replica::ReplicaPool;
 
 #[doc(hidden)]
 pub use self::maybe::MaybePoolConnection;
@@ -343,6 +352,7 @@ impl<DB: Database> Pool<DB> {
     /// The total time this method is allowed to execute is capped by
     /// [`PoolOptions::acquire_timeout`].
     /// If that timeout elapses, this will return [`Error::PoolClosed`].
+    /// Use [`acquire_timeout()`][Self::acquire_timeout] to override it for a single call.
     ///
     /// ### Note: Cancellation/Timeout May Drop Connections
     /// If `acquire` is cancelled or times out after it acquires a connection from the idle queue or
@@ -362,8 +372,24 @@ impl<DB: Database> Pool<DB> {
     /// This should eliminate any potential `.await` points between acquiring a connection and
     /// returning it.
     pub fn acquire(&self) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
+        self.acquire_timeout(self.0.options.acquire_timeout)
+    }
+
+    /// Retrieves a connection from the pool, waiting at most `timeout` instead of
+    /// [`PoolOptions::acquire_timeout`].
+    ///
+    /// This lets latency-sensitive code paths give up early while others sharing the pool
+    /// are willing to wait longer. If `timeout` elapses, this will return
+    /// [`Error::PoolTimedOut`].
+    ///
+    /// Otherwise, this behaves the same as [`acquire()`][Self::acquire], including the note on
+    /// cancellation.
+    pub fn acquire_timeout(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
         let shared = self.0.clone();
-        async move { shared.acquire().await.map(|conn| conn.reattach()) }
+        async move { shared.acquire(timeout).await.map(|conn| conn.reattach()) }
     }
 
     /// Attempts to retrieve a connection from the pool if there is one available.
@@ -371,7 +397,24 @@ impl<DB: Database> Pool<DB> {
     /// Returns `None` immediately if there are no idle connections available in the pool
     /// or there are tasks waiting for a connection which have yet to wake.
     pub fn try_acquire(&self) -> Option<PoolConnection<DB>> {
-        self.0.try_acquire().map(|conn| conn.into_live().reattach())
+        let conn = self.0.try_acquire()?;
+        self.0.metrics.acquired(Duration::ZERO);
+        Some(conn.into_live().reattach())
+    }
+
+    /// Opens connections up to [`min_connections()`][Self::min_connections] concurrently, so the
+    /// first requests served by the pool don't have to wait for them.
+    ///
+    /// Unlike the background task maintaining `min_connections`, this fails fast: each
+    /// connection is attempted once, without retrying errors such as a refused connection, and
+    /// if any attempt failed, [`Error::PoolWarmUp`] is returned with all of their errors. The
+    /// connections that were opened successfully are kept in the pool either way.
+    ///
+    /// The total time this method is allowed to execute is capped by
+    /// [`PoolOptions::acquire_timeout`].
+    pub async fn warm_up(&self) -> Result<(), Error> {
+        let deadline = Instant::now() + self.0.options.acquire_timeout;
+        self.0.warm_up(deadline).await
     }
 
     /// Retrieves a connection and immediately begins a new transaction.
@@ -512,6 +555,15 @@ impl<DB: Database> Pool<DB> {
         self.0.num_idle()
     }
 
+    /// Returns the number of connections, the counters of what the pool did since it was created,
+    /// and a histogram of how long acquired connections were waited for.
+    ///
+    /// To record these in a metrics library as they happen instead, set
+    /// [`PoolOptions::metrics_observer()`].
+    pub fn metrics(&self) -> PoolMetrics {
+        self.0.metrics()
+    }
+
     /// Gets a clone of the connection options for this pool
     pub fn connect_options(&self) -> Arc<<DB::Connection as Connection>::Options> {
         self.0
@@ -534,7 +586,55 @@ impl<DB: Database> Pool<DB> {
         *guard = Arc::new(connect_options);
     }
 
+    /// Returns the maximum number of connections of the pool, which is
+    /// [`PoolOptions::max_connections`] unless it was changed with
+    /// [`set_max_connections()`][Self::set_max_connections].
+    pub fn max_connections(&self) -> u32 {
+        self.0.max_connections()
+    }
+
+    /// Changes the maximum number of connections of the pool, without closing it.
+    ///
+    /// If `max` is greater than before, more connections can be acquired right away.
+    ///
+    /// If it is less, the idle connections above `max` are closed, and so are the connections
+    /// in use as they are returned, until the pool has at most `max` connections. Meanwhile,
+    /// [`size()`][Self::size] can be greater than `max`, but no new connections are opened.
+    ///
+    /// [`options()`][Self::options] keeps returning the options the pool was created with.
+    ///
+    /// Returns [`Error::Configuration`], leaving the pool unchanged, if `max` is zero, less than
+    /// [`min_connections()`][Self::min_connections], or greater than the `max_connections()` of
+    /// the [parent pool][PoolOptions::parent], if any.
+    pub fn set_max_connections(&self, max: u32) -> Result<(), Error> {
+        self.0.set_max_connections(max)
+    }
+
+    /// Returns the minimum number of connections of the pool, which is
+    /// [`PoolOptions::min_connections`] unless it was changed with
+    /// [`set_min_connections()`][Self::set_min_connections].
+    pub fn min_connections(&self) -> u32 {
+        self.0.min_connections()
+    }
+
+    /// Changes the minimum number of connections to maintain at all times.
+    ///
+    /// If `min` is greater than before, a task is spawned to open connections up to `min`, on
+    /// a best-effort basis; if it is less, the excess idle connections are closed by
+    /// [`idle_timeout`][PoolOptions::idle_timeout] and
+    /// [`max_lifetime`][PoolOptions::max_lifetime] as usual.
+    ///
+    /// Returns [`Error::Configuration`], leaving the pool unchanged, if `min` is greater than
+    /// [`max_connections()`][Self::max_connections].
+    pub fn set_min_connections(&self, min: u32) -> Result<(), Error> {
+        self.0.set_min_connections(min)
+    }
+
     /// Get the options for this pool
+    ///
+    /// These are the options the pool was created with; see
+    /// [`max_connections()`][Self::max_connections] and
+    /// [`min_connections()`][Self::min_connections] for the current limits.
     pub fn options(&self) -> &PoolOptions<DB> {
         &self.0.options
     }
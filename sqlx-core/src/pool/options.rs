@@ -1,7 +1,9 @@
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::pool::inner::PoolInner;
+use crate::pool::metrics::PoolMetricsObserver;
 use crate::pool::Pool;
 use futures_core::future::BoxFuture;
 use std::fmt::{self, Debug, Formatter};
@@ -42,7 +44,8 @@ use std::time::{Duration, Instant};
 /// so having the closure return `Pin<Box<dyn Future>` directly is the path of least resistance from
 /// the perspectives of both API designer and consumer.
 pub struct PoolOptions<DB: Database> {
-    pub(crate) test_before_acquire: bool,
+    pub(crate) health_check: HealthCheck<DB>,
+    pub(crate) health_check_after_idle: Option<Duration>,
     pub(crate) after_connect: Option<
         Arc<
             dyn Fn(&mut DB::Connection, PoolConnectionMetadata) -> BoxFuture<'_, Result<(), Error>>
@@ -79,6 +82,9 @@ pub struct PoolOptions<DB: Database> {
     pub(crate) max_lifetime: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
     pub(crate) fair: bool,
+    pub(crate) warm_up: bool,
+    pub(crate) reuse_policy: ReusePolicy,
+    pub(crate) metrics_observer: Option<Arc<dyn PoolMetricsObserver>>,
 
     pub(crate) parent_pool: Option<Pool<DB>>,
 }
@@ -89,7 +95,8 @@ pub struct PoolOptions<DB: Database> {
 impl<DB: Database> Clone for PoolOptions<DB> {
     fn clone(&self) -> Self {
         PoolOptions {
-            test_before_acquire: self.test_before_acquire,
+            health_check: self.health_check.clone(),
+            health_check_after_idle: self.health_check_after_idle,
             after_connect: self.after_connect.clone(),
             before_acquire: self.before_acquire.clone(),
             after_release: self.after_release.clone(),
@@ -99,11 +106,67 @@ impl<DB: Database> Clone for PoolOptions<DB> {
             max_lifetime: self.max_lifetime,
             idle_timeout: self.idle_timeout,
             fair: self.fair,
+            warm_up: self.warm_up,
+            reuse_policy: self.reuse_policy,
+            metrics_observer: self.metrics_observer.clone(),
             parent_pool: self.parent_pool.as_ref().map(Pool::clone),
         }
     }
 }
 
+/// How an idle connection is checked before [`Pool::acquire()`] gives it out.
+pub(crate) enum HealthCheck<DB: Database> {
+    Skip,
+    Ping,
+    Query {
+        sql: Arc<str>,
+        execute: Arc<
+            dyn Fn(&mut DB::Connection) -> BoxFuture<'_, Result<(), Error>> + 'static + Send + Sync,
+        >,
+    },
+}
+
+impl<DB: Database> Clone for HealthCheck<DB> {
+    fn clone(&self) -> Self {
+        match self {
+            HealthCheck::Skip => HealthCheck::Skip,
+            HealthCheck::Ping => HealthCheck::Ping,
+            HealthCheck::Query { sql, execute } => HealthCheck::Query {
+                sql: sql.clone(),
+                execute: execute.clone(),
+            },
+        }
+    }
+}
+
+impl<DB: Database> Debug for HealthCheck<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthCheck::Skip => f.write_str("Skip"),
+            HealthCheck::Ping => f.write_str("Ping"),
+            HealthCheck::Query { sql, .. } => f.debug_tuple("Query").field(sql).finish(),
+        }
+    }
+}
+
+/// The order in which a pool reuses its idle connections.
+///
+/// See [`PoolOptions::reuse_policy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReusePolicy {
+    /// Hand out the connection that has been idle the longest.
+    ///
+    /// This spreads the load evenly across all connections of the pool.
+    #[default]
+    Fifo,
+
+    /// Hand out the connection that was returned to the pool most recently.
+    ///
+    /// This keeps a small working set of connections busy, and lets the others reach
+    /// [`idle_timeout`][PoolOptions::idle_timeout] and be closed when the load drops.
+    Lifo,
+}
+
 /// Metadata for the connection being processed by a [`PoolOptions`] callback.
 #[derive(Debug)] // Don't want to commit to any other trait impls yet.
 #[non_exhaustive] // So we can safely add fields in the future.
@@ -139,7 +202,8 @@ impl<DB: Database> PoolOptions<DB> {
             after_connect: None,
             before_acquire: None,
             after_release: None,
-            test_before_acquire: true,
+            health_check: HealthCheck::Ping,
+            health_check_after_idle: None,
             // A production application will want to set a higher limit than this.
             max_connections: 10,
             min_connections: 0,
@@ -147,6 +211,9 @@ impl<DB: Database> PoolOptions<DB> {
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
             fair: true,
+            warm_up: false,
+            reuse_policy: ReusePolicy::Fifo,
+            metrics_observer: None,
             parent_pool: None,
         }
     }
@@ -156,6 +223,8 @@ impl<DB: Database> PoolOptions<DB> {
     /// Be mindful of the connection limits for your database as well as other applications
     /// which may want to connect to the same database (or even multiple instances of the same
     /// application in high-availability deployments).
+    ///
+    /// This can be changed later with [`Pool::set_max_connections()`].
     pub fn max_connections(mut self, max: u32) -> Self {
         self.max_connections = max;
         self
@@ -185,6 +254,8 @@ impl<DB: Database> PoolOptions<DB> {
     /// then it should be checking this condition itself and returning
     /// a nicer error than a panic anyway.
     ///
+    /// This can be changed later with [`Pool::set_min_connections()`].
+    ///
     /// [`max_lifetime`]: Self::max_lifetime
     /// [`idle_timeout`]: Self::idle_timeout
     /// [`max_connections`]: Self::max_connections
@@ -211,6 +282,8 @@ impl<DB: Database> PoolOptions<DB> {
     /// * If a new connection needs to be opened, that will obviously require I/O, handshaking,
     ///   and initialization commands.
     ///     * If [`after_connect`][Self::after_connect] is set, that will also be executed.
+    ///
+    /// This can be overridden for a single call with [`Pool::acquire_timeout()`].
     pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
         self.acquire_timeout = timeout;
         self
@@ -263,15 +336,76 @@ impl<DB: Database> PoolOptions<DB> {
     /// If true, the health of a connection will be verified by a call to [`Connection::ping`]
     /// before returning the connection.
     ///
+    /// Setting this to `true` replaces any query set with
+    /// [`health_check_query`][Self::health_check_query]; setting it to `false` disables
+    /// health checks entirely.
+    ///
     /// Defaults to `true`.
     pub fn test_before_acquire(mut self, test: bool) -> Self {
-        self.test_before_acquire = test;
+        self.health_check = if test {
+            HealthCheck::Ping
+        } else {
+            HealthCheck::Skip
+        };
         self
     }
 
     /// Get's whether `test_before_acquire` is currently set.
+    ///
+    /// This is also `true` if a [`health_check_query`][Self::health_check_query] is set.
     pub fn get_test_before_acquire(&self) -> bool {
-        self.test_before_acquire
+        !matches!(self.health_check, HealthCheck::Skip)
+    }
+
+    /// Verify the health of a connection by executing `sql` before returning the connection,
+    /// instead of calling [`Connection::ping`].
+    ///
+    /// Some proxies and load balancers in front of the database don't forward the protocol-level
+    /// pings, or answer them themselves, so a real query like `SELECT 1` is the only reliable
+    /// check. If the query returns an error, the connection is closed and
+    /// [`Pool::acquire()`] tries another one.
+    ///
+    /// This replaces [`test_before_acquire`][Self::test_before_acquire].
+    pub fn health_check_query(mut self, sql: impl Into<Arc<str>>) -> Self
+    where
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let sql = sql.into();
+        let query = sql.clone();
+
+        self.health_check = HealthCheck::Query {
+            sql,
+            execute: Arc::new(move |conn| {
+                let query = query.clone();
+                Box::pin(async move { conn.execute(&*query).await.map(|_| ()) })
+            }),
+        };
+        self
+    }
+
+    /// Get the query set with [`health_check_query`][Self::health_check_query], if any.
+    pub fn get_health_check_query(&self) -> Option<&str> {
+        match &self.health_check {
+            HealthCheck::Query { sql, .. } => Some(sql),
+            _ => None,
+        }
+    }
+
+    /// Only check the health of connections that have been idle for at least `idle_for`.
+    ///
+    /// Connections that were returned to the pool more recently than that are handed out
+    /// without a ping or [`health_check_query`][Self::health_check_query], saving a round-trip
+    /// on busy pools where they are very likely to still be alive.
+    ///
+    /// Defaults to `None`, checking every connection.
+    pub fn health_check_after_idle(mut self, idle_for: impl Into<Option<Duration>>) -> Self {
+        self.health_check_after_idle = idle_for.into();
+        self
+    }
+
+    /// Get the idle duration after which connections are checked, if set.
+    pub fn get_health_check_after_idle(&self) -> Option<Duration> {
+        self.health_check_after_idle
     }
 
     /// If set to `true`, calls to `acquire()` are fair and connections  are issued
@@ -292,6 +426,40 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// If set to `true`, [`connect()`][Self::connect] and [`connect_with()`][Self::connect_with]
+    /// open [`min_connections`][Self::min_connections] concurrently with [`Pool::warm_up()`]
+    /// instead of one after the other, returning [`Error::PoolWarmUp`] if any of them fail.
+    ///
+    /// Has no effect on [`connect_lazy()`][Self::connect_lazy].
+    ///
+    /// Defaults to `false`.
+    pub fn warm_up(mut self, warm_up: bool) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// Get whether `warm_up` is set.
+    pub fn get_warm_up(&self) -> bool {
+        self.warm_up
+    }
+
+    /// Set the order in which idle connections are reused.
+    ///
+    /// [`ReusePolicy::Fifo`] spreads queries across every connection, which suits proxies and
+    /// servers that balance or cache per connection. [`ReusePolicy::Lifo`] keeps reusing the
+    /// same few connections, so the pool shrinks back to what the load actually needs.
+    ///
+    /// Defaults to [`ReusePolicy::Fifo`].
+    pub fn reuse_policy(mut self, policy: ReusePolicy) -> Self {
+        self.reuse_policy = policy;
+        self
+    }
+
+    /// Get the order in which idle connections are reused.
+    pub fn get_reuse_policy(&self) -> ReusePolicy {
+        self.reuse_policy
+    }
+
     /// Perform an asynchronous action after connecting to the database.
     ///
     /// If the operation returns with an error then the error is logged, the connection is closed
@@ -361,7 +529,8 @@ impl<DB: Database> PoolOptions<DB> {
     ///
     /// # Example: Custom `test_before_acquire` Logic
     /// If you only want to ping connections if they've been idle a certain amount of time,
-    /// you can implement your own logic here:
+    /// you can use [`health_check_after_idle`][Self::health_check_after_idle], or implement
+    /// your own logic here:
     ///
     /// This example is written for Postgres but should be trivially adaptable to other databases.
     /// ```no_run
@@ -453,6 +622,16 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Pass the events of the pool, such as how long each acquire waited, to `observer` as they
+    /// happen, e.g. to record them in a metrics library.
+    ///
+    /// The pool keeps counters and a histogram of these events either way, which are returned
+    /// by [`Pool::metrics()`].
+    pub fn metrics_observer(mut self, observer: impl PoolMetricsObserver) -> Self {
+        self.metrics_observer = Some(Arc::new(observer));
+        self
+    }
+
     /// Set the parent `Pool` from which the new pool will inherit its semaphore.
     ///
     /// This is currently an internal-only API.
@@ -487,6 +666,9 @@ impl<DB: Database> PoolOptions<DB> {
     /// This ensures the configuration is correct.
     ///
     /// The total number of connections opened is <code>min(1, [min_connections][Self::min_connections])</code>.
+    ///
+    /// If [`warm_up`][Self::warm_up] is set, the connections up to `min_connections` are opened
+    /// concurrently, as with [`Pool::warm_up()`].
     pub async fn connect_with(
         self,
         options: <DB::Connection as Connection>::Options,
@@ -496,7 +678,9 @@ impl<DB: Database> PoolOptions<DB> {
 
         let inner = PoolInner::new_arc(self, options);
 
-        if inner.options.min_connections > 0 {
+        if inner.options.warm_up {
+            inner.warm_up(deadline).await?;
+        } else if inner.options.min_connections > 0 {
             // If the idle reaper is spawned then this will race with the call from that task
             // and may not report any connection errors.
             inner.try_min_connections(deadline).await?;
@@ -504,7 +688,7 @@ impl<DB: Database> PoolOptions<DB> {
 
         // If `min_connections` is nonzero then we'll likely just pull a connection
         // from the idle queue here, but it should at least get tested first.
-        let conn = inner.acquire().await?;
+        let conn = inner.acquire(inner.options.acquire_timeout).await?;
         inner.release(conn);
 
         Ok(Pool(inner))
@@ -543,7 +727,11 @@ impl<DB: Database> Debug for PoolOptions<DB> {
             .field("connect_timeout", &self.acquire_timeout)
             .field("max_lifetime", &self.max_lifetime)
             .field("idle_timeout", &self.idle_timeout)
-            .field("test_before_acquire", &self.test_before_acquire)
+            .field("health_check", &self.health_check)
+            .field("health_check_after_idle", &self.health_check_after_idle)
+            .field("warm_up", &self.warm_up)
+            .field("reuse_policy", &self.reuse_policy)
+            .field("metrics_observer", &self.metrics_observer.is_some())
             .finish()
     }
 }
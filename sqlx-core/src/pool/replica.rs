@@ -0,0 +1,366 @@
+//! A primary pool and read replica pools, with reads routed to the replicas.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_core::future::BoxFuture;
+
+use crate::database::Database;
+use crate::error::Error;
+use crate::pool::{Pool, PoolConnection};
+
+/// A [`Pool`] for the primary database and a `Pool` for each of its read replicas.
+///
+/// Which queries are safe to run on a replica is up to the application, so connections are
+/// acquired explicitly:
+///
+/// * [`write()`][Self::write] always acquires a connection from the primary.
+/// * [`read()`][Self::read] acquires a connection from the replicas in turn, skipping the
+///   ones that can't give one out within
+///   [`replica_acquire_timeout`][Self::replica_acquire_timeout] or lag too far behind (see
+///   [`max_replica_lag()`][Self::max_replica_lag]), and fails over to the primary if none can.
+///   A replica that is down or lags is not tried again until
+///   [`replica_check_interval`][Self::replica_check_interval] has passed.
+///
+/// Like `Pool`, this is cheap to clone: all clones share the same pools.
+///
+/// ```no_run
+/// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+/// use sqlx::pool::ReplicaPool;
+/// use sqlx::postgres::PgPool;
+///
+/// let pool = ReplicaPool::new(
+///     PgPool::connect("postgres://primary/app").await?,
+///     [PgPool::connect_lazy("postgres://replica-1/app")?],
+/// );
+///
+/// let mut conn = pool.write().await?;
+/// sqlx::query("INSERT INTO users (name) VALUES ('alice')").execute(&mut *conn).await?;
+///
+/// let mut conn = pool.read().await?;
+/// let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM users")
+///     .fetch_one(&mut *conn)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplicaPool<DB: Database>(Arc<ReplicaPoolInner<DB>>);
+
+struct ReplicaPoolInner<DB: Database> {
+    primary: Pool<DB>,
+    replicas: Vec<Replica<DB>>,
+    next_replica: AtomicUsize,
+    lag_check: Option<LagCheck<DB>>,
+    check_interval: Duration,
+    acquire_timeout: Duration,
+}
+
+struct Replica<DB: Database> {
+    pool: Pool<DB>,
+    /// The replica could not open a connection or lagged too far behind when last checked.
+    unhealthy: AtomicBool,
+    last_check: Mutex<Option<Instant>>,
+}
+
+struct LagCheck<DB: Database> {
+    max_lag: Duration,
+    check: Arc<
+        dyn Fn(&mut DB::Connection) -> BoxFuture<'_, Result<Duration, Error>>
+            + 'static
+            + Send
+            + Sync,
+    >,
+}
+
+impl<DB: Database> ReplicaPool<DB> {
+    /// Create a new `ReplicaPool` from the pool of the primary database and the pools of its
+    /// replicas.
+    ///
+    /// With no replicas, every connection is acquired from the primary.
+    pub fn new(primary: Pool<DB>, replicas: impl IntoIterator<Item = Pool<DB>>) -> Self {
+        let replicas = replicas
+            .into_iter()
+            .map(|pool| Replica {
+                pool,
+                unhealthy: AtomicBool::new(false),
+                last_check: Mutex::new(None),
+            })
+            .collect();
+
+        Self(Arc::new(ReplicaPoolInner {
+            primary,
+            replicas,
+            next_replica: AtomicUsize::new(0),
+            lag_check: None,
+            check_interval: Duration::from_secs(1),
+            acquire_timeout: Duration::from_secs(1),
+        }))
+    }
+
+    /// Returns a new `ReplicaPool` of the same pools, with the settings changed by `f`.
+    ///
+    /// Existing clones are left as they are.
+    fn with_settings(&self, f: impl FnOnce(&mut ReplicaPoolInner<DB>)) -> Self {
+        let mut pool = Self::new(self.0.primary.clone(), self.replicas().cloned());
+
+        let inner = Arc::get_mut(&mut pool.0).expect("BUG: new `ReplicaPool` is shared");
+        inner.lag_check = self.0.lag_check.clone();
+        inner.check_interval = self.0.check_interval;
+        inner.acquire_timeout = self.0.acquire_timeout;
+        f(inner);
+
+        pool
+    }
+
+    /// Skip the replicas that lag more than `max_lag` behind the primary.
+    ///
+    /// `check` is called on a connection acquired by [`read()`][Self::read] and returns how far
+    /// behind the primary its replica is; how to measure that depends on the database. A replica
+    /// is checked at most once per [`replica_check_interval`][Self::replica_check_interval],
+    /// and is skipped until the next check if it lags too much or `check` returns an error.
+    ///
+    /// # Example (Postgres)
+    /// ```no_run
+    /// # async fn f(primary: sqlx::PgPool, replica: sqlx::PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    /// use sqlx::pool::ReplicaPool;
+    ///
+    /// let pool = ReplicaPool::new(primary, [replica])
+    ///     .max_replica_lag(Duration::from_secs(5), |conn| Box::pin(async move {
+    ///         let (lag,): (f64,) = sqlx::query_as(
+    ///             "SELECT coalesce(extract(epoch FROM now() - pg_last_xact_replay_timestamp()), 0)::float8",
+    ///         )
+    ///         .fetch_one(conn)
+    ///         .await?;
+    ///
+    ///         Ok(Duration::from_secs_f64(lag.max(0.0)))
+    ///     }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// For a discussion on why `Box::pin()` is required, see
+    /// [the type-level docs of `PoolOptions`][crate::pool::PoolOptions].
+    pub fn max_replica_lag<F>(self, max_lag: Duration, check: F) -> Self
+    where
+        for<'c> F: Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<Duration, Error>>
+            + 'static
+            + Send
+            + Sync,
+    {
+        self.with_settings(|inner| {
+            inner.lag_check = Some(LagCheck {
+                max_lag,
+                check: Arc::new(check),
+            });
+        })
+    }
+
+    /// Set how often the lag of each replica is checked, if
+    /// [`max_replica_lag()`][Self::max_replica_lag] is set, and how long a replica that could not
+    /// open a connection is skipped before it is tried again.
+    ///
+    /// Defaults to one second.
+    pub fn replica_check_interval(self, interval: Duration) -> Self {
+        self.with_settings(|inner| inner.check_interval = interval)
+    }
+
+    /// Set how long [`read()`][Self::read] waits for a connection from each replica before it
+    /// moves on to the next one, instead of the
+    /// [`acquire_timeout`][crate::pool::PoolOptions::acquire_timeout] of the replica's pool.
+    ///
+    /// A replica that times out because all of its connections are in use is only skipped for
+    /// that call. Keep this short, as it adds to the latency of every `read()` while replicas
+    /// are busy or down.
+    ///
+    /// Defaults to one second.
+    pub fn replica_acquire_timeout(self, timeout: Duration) -> Self {
+        self.with_settings(|inner| inner.acquire_timeout = timeout)
+    }
+
+    /// Get the pool of the primary database.
+    pub fn primary(&self) -> &Pool<DB> {
+        &self.0.primary
+    }
+
+    /// Get the pools of the replicas, in the order they were given.
+    pub fn replicas(&self) -> impl ExactSizeIterator<Item = &Pool<DB>> {
+        self.0.replicas.iter().map(|replica| &replica.pool)
+    }
+
+    /// Retrieves a connection from the primary, for queries that write or must see the latest
+    /// writes.
+    ///
+    /// Same as [`primary().acquire()`][Pool::acquire].
+    pub async fn write(&self) -> Result<PoolConnection<DB>, Error> {
+        self.0.primary.acquire().await
+    }
+
+    /// Retrieves a connection from a replica, for queries that only read and can tolerate
+    /// replication lag.
+    ///
+    /// The replicas are tried in turn, starting from the one after the replica used by the
+    /// previous call, so the load is spread across all of them. A replica is skipped if it
+    /// can't give out a connection within
+    /// [`replica_acquire_timeout`][Self::replica_acquire_timeout]. If it could not open a
+    /// connection, or it [lags too much][Self::max_replica_lag], it is also skipped for the rest
+    /// of the [`replica_check_interval`][Self::replica_check_interval], so a replica that is
+    /// down doesn't slow down every call.
+    ///
+    /// If every replica was skipped, the connection is retrieved from the primary instead.
+    pub async fn read(&self) -> Result<PoolConnection<DB>, Error> {
+        let replicas = &self.0.replicas;
+
+        if !replicas.is_empty() {
+            let start = self.0.next_replica.fetch_add(1, Ordering::Relaxed);
+
+            for i in 0..replicas.len() {
+                let replica = &replicas[start.wrapping_add(i) % replicas.len()];
+
+                if let Some(conn) = self.0.acquire_replica(replica).await {
+                    return Ok(conn);
+                }
+            }
+
+            tracing::debug!("no replica available for `ReplicaPool::read()`, using the primary");
+        }
+
+        self.0.primary.acquire().await
+    }
+
+    /// Shut down the primary pool and the replica pools.
+    ///
+    /// See [`Pool::close()`].
+    pub async fn close(&self) {
+        futures_util::future::join_all(
+            std::iter::once(&self.0.primary)
+                .chain(self.replicas())
+                .map(Pool::close),
+        )
+        .await;
+    }
+
+    /// Returns `true` if [`.close()`][Self::close] has been called, `false` otherwise.
+    pub fn is_closed(&self) -> bool {
+        self.0.primary.is_closed()
+    }
+}
+
+impl<DB: Database> ReplicaPoolInner<DB> {
+    /// Acquire a connection from `replica`, or return `None` if it should be skipped.
+    async fn acquire_replica(&self, replica: &Replica<DB>) -> Option<PoolConnection<DB>> {
+        let due = replica.claim_check(self.check_interval);
+
+        if !due && replica.unhealthy.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let mut conn = match replica.pool.acquire_timeout(self.acquire_timeout).await {
+            Ok(conn) => conn,
+            // All connections are in use, which doesn't mean the replica is down. A pool without
+            // any connection times out when it keeps failing to open one, though.
+            Err(Error::PoolTimedOut) if replica.pool.size() > 0 => {
+                tracing::debug!("timed out acquiring a connection from a replica");
+                return None;
+            }
+            Err(error) => {
+                tracing::debug!(%error, "unable to acquire a connection from a replica");
+                replica.mark_unhealthy();
+                return None;
+            }
+        };
+
+        if let Some(lag_check) = self.lag_check.as_ref().filter(|_| due) {
+            let lagging = match (lag_check.check)(&mut conn).await {
+                Ok(lag) if lag <= lag_check.max_lag => false,
+                Ok(lag) => {
+                    tracing::debug!(?lag, "replica lags too far behind the primary");
+                    true
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "error from `max_replica_lag` check");
+                    true
+                }
+            };
+
+            if lagging {
+                replica.mark_unhealthy();
+                return None;
+            }
+        }
+
+        replica.unhealthy.store(false, Ordering::Release);
+
+        Some(conn)
+    }
+}
+
+// Manually implement `Clone` to avoid requiring `DB: Clone`.
+impl<DB: Database> Clone for LagCheck<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            max_lag: self.max_lag,
+            check: self.check.clone(),
+        }
+    }
+}
+
+impl<DB: Database> Replica<DB> {
+    /// Returns `true` if this replica is due for a check, and records that it is being checked
+    /// now so concurrent calls don't check it too.
+    fn claim_check(&self, interval: Duration) -> bool {
+        let mut last_check = self
+            .last_check
+            .lock()
+            .expect("BUG: panicked while holding a lock");
+
+        let now = Instant::now();
+
+        if last_check.map_or(true, |last| now.duration_since(last) >= interval) {
+            *last_check = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skip this replica until the check interval has passed from now.
+    fn mark_unhealthy(&self) {
+        *self
+            .last_check
+            .lock()
+            .expect("BUG: panicked while holding a lock") = Some(Instant::now());
+
+        self.unhealthy.store(true, Ordering::Release);
+    }
+}
+
+/// Returns a new [ReplicaPool] tied to the same shared connection pools.
+impl<DB: Database> Clone for ReplicaPool<DB> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<DB: Database> Debug for ReplicaPool<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicaPool")
+            .field("primary", &self.0.primary)
+            .field(
+                "replicas",
+                &self
+                    .0
+                    .replicas
+                    .iter()
+                    .map(|replica| &replica.pool)
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "max_replica_lag",
+                &self.0.lag_check.as_ref().map(|lag_check| lag_check.max_lag),
+            )
+            .finish()
+    }
+}
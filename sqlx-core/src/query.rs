@@ -103,6 +103,15 @@ where
     }
 }
 
+impl<'q, DB: Database, A> Query<'q, DB, A> {
+    /// Returns the arguments bound to this query, for the database drivers to attach options
+    /// to.
+    #[doc(hidden)]
+    pub fn arguments_mut(&mut self) -> Option<&mut A> {
+        self.arguments.as_mut()
+    }
+}
+
 impl<'q, DB, A: Send> Query<'q, DB, A>
 where
     DB: Database,
@@ -253,6 +262,15 @@ where
     }
 }
 
+impl<'q, DB: Database, F, A> Map<'q, DB, F, A> {
+    /// Returns the arguments bound to this query, for the database drivers to attach options
+    /// to.
+    #[doc(hidden)]
+    pub fn arguments_mut(&mut self) -> Option<&mut A> {
+        self.inner.arguments_mut()
+    }
+}
+
 impl<'q, DB, F, O, A> Map<'q, DB, F, A>
 where
     DB: Database,
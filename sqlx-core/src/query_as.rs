@@ -75,6 +75,15 @@ where
     }
 }
 
+impl<'q, DB: Database, O, A> QueryAs<'q, DB, O, A> {
+    /// Returns the arguments bound to this query, for the database drivers to attach options
+    /// to.
+    #[doc(hidden)]
+    pub fn arguments_mut(&mut self) -> Option<&mut A> {
+        self.inner.arguments_mut()
+    }
+}
+
 // FIXME: This is very close, nearly 1:1 with `Map`
 // noinspection DuplicatedCode
 impl<'q, DB, O, A> QueryAs<'q, DB, O, A>
@@ -32,6 +32,23 @@ pub trait TransactionManager {
 
     /// Starts to abort the active transaction or restore from the most recent snapshot.
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
+
+    /// Prepare the active transaction for a two-phase commit, under the global identifier `gid`.
+    ///
+    /// Returns an error by default, for databases without two-phase commits.
+    fn prepare<'c>(
+        conn: &'c mut <Self::Database as Database>::Connection,
+        gid: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        let _ = (conn, gid);
+
+        Box::pin(async {
+            Err(err_protocol!(
+                "two-phase commits are not supported by {}",
+                <Self::Database as Database>::NAME
+            ))
+        })
+    }
 }
 
 /// An in-progress database transaction or savepoint.
@@ -93,6 +110,21 @@ where
 
         Ok(())
     }
+
+    /// Prepares this transaction for a two-phase commit, under the global identifier `gid`.
+    ///
+    /// The transaction is then no longer tied to the connection, and survives a crash of the
+    /// database. It must be finished later, possibly from another connection, by committing or
+    /// rolling back the prepared transaction with the same identifier.
+    ///
+    /// Only PostgreSQL supports this, with `max_prepared_transactions` set on the server, and only
+    /// for a transaction, not a savepoint. See `PgConnection::commit_prepared()`.
+    pub async fn prepare_2pc(mut self, gid: &str) -> Result<(), Error> {
+        DB::TransactionManager::prepare(&mut self.connection, gid).await?;
+        self.open = false;
+
+        Ok(())
+    }
 }
 
 // NOTE: fails to compile due to lack of lazy normalization
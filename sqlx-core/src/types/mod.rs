@@ -38,7 +38,7 @@ pub use uuid::{self, Uuid};
 pub mod chrono {
     #[doc(no_inline)]
     pub use chrono::{
-        DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+        DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
     };
 }
 
@@ -64,6 +64,16 @@ pub use bigdecimal::BigDecimal;
 #[doc(no_inline)]
 pub use rust_decimal::Decimal;
 
+#[cfg(feature = "geo-types")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+pub mod geo_types {
+    #[doc(no_inline)]
+    pub use geo_types::{
+        Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+        Point, Polygon,
+    };
+}
+
 #[cfg(feature = "ipnetwork")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ipnetwork")))]
 pub mod ipnetwork {
@@ -190,6 +200,21 @@ pub use json::{Json, JsonRawValue, JsonValue};
 /// }
 /// ```
 ///
+/// Add `#[sqlx(pg_array)]` to also implement `PgHasArrayType`, so arrays of the type (e.g. from
+/// `array_agg()`) can be bound and decoded as a `Vec`. The name of the array type is assumed to
+/// be the type name prefixed with an underscore, as Postgres names them. This is opt-in so an
+/// existing manual `PgHasArrayType` impl does not conflict with the derived one.
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Type)]
+/// #[sqlx(type_name = "interface_type", pg_array)]
+/// struct InterfaceType {
+///     name: String,
+///     supplier_id: i32,
+///     price: f64
+/// }
+/// ```
+///
 pub trait Type<DB: Database> {
     /// Returns the canonical SQL type for this Rust type.
     ///
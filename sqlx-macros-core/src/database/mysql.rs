@@ -13,6 +13,9 @@ impl_database_ext! {
         f32,
         f64,
 
+        // SET, which is also compatible with `String`
+        sqlx::mysql::types::MySqlSet<String>,
+
         // ordering is important here as otherwise we might infer strings to be binary
         // CHAR, VAR_CHAR, TEXT
         String,
@@ -20,6 +23,9 @@ impl_database_ext! {
         // BINARY, VAR_BINARY, BLOB
         Vec<u8>,
 
+        // VECTOR
+        sqlx::mysql::types::MySqlVector,
+
         #[cfg(all(feature = "chrono", not(feature = "time")))]
         sqlx::types::chrono::NaiveTime,
 
@@ -44,6 +50,9 @@ impl_database_ext! {
         #[cfg(feature = "time")]
         sqlx::types::time::OffsetDateTime,
 
+        // TIME, if neither `chrono` nor `time` is enabled
+        sqlx::mysql::types::MySqlTime,
+
         #[cfg(feature = "bigdecimal")]
         sqlx::types::BigDecimal,
 
@@ -52,6 +61,9 @@ impl_database_ext! {
 
         #[cfg(feature = "json")]
         sqlx::types::JsonValue,
+
+        #[cfg(feature = "geo-types")]
+        sqlx::types::geo_types::Geometry<f64>,
     },
     ParamChecking::Weak,
     feature-types: info => info.__type_feature_gate(),
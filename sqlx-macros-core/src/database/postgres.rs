@@ -23,6 +23,14 @@ impl_database_ext! {
 
         sqlx::postgres::types::PgLQuery,
 
+        sqlx::postgres::types::PgHstore,
+
+        sqlx::postgres::types::PgJsonPath,
+
+        sqlx::postgres::types::PgLsn,
+
+        sqlx::postgres::types::PgTid,
+
         #[cfg(feature = "uuid")]
         sqlx::types::Uuid,
 
@@ -74,6 +82,18 @@ impl_database_ext! {
         #[cfg(feature = "bit-vec")]
         sqlx::types::BitVec,
 
+        #[cfg(feature = "pgvector")]
+        sqlx::postgres::types::PgVector,
+
+        #[cfg(feature = "pgvector")]
+        sqlx::postgres::types::PgHalfVector,
+
+        #[cfg(feature = "pgvector")]
+        sqlx::postgres::types::PgSparseVector,
+
+        #[cfg(feature = "geo-types")]
+        sqlx::types::geo_types::Geometry<f64>,
+
         // Arrays
 
         Vec<bool> | &[bool],
@@ -87,6 +107,11 @@ impl_database_ext! {
         Vec<f64> | &[f64],
         Vec<sqlx::postgres::types::Oid> | &[sqlx::postgres::types::Oid],
         Vec<sqlx::postgres::types::PgMoney> | &[sqlx::postgres::types::PgMoney],
+        Vec<sqlx::postgres::types::PgLTree> | &[sqlx::postgres::types::PgLTree],
+        Vec<sqlx::postgres::types::PgLQuery> | &[sqlx::postgres::types::PgLQuery],
+        Vec<sqlx::postgres::types::PgJsonPath> | &[sqlx::postgres::types::PgJsonPath],
+        Vec<sqlx::postgres::types::PgLsn> | &[sqlx::postgres::types::PgLsn],
+        Vec<sqlx::postgres::types::PgTid> | &[sqlx::postgres::types::PgTid],
 
         #[cfg(feature = "uuid")]
         Vec<sqlx::types::Uuid> | &[sqlx::types::Uuid],
@@ -200,6 +225,36 @@ impl_database_ext! {
         #[cfg(feature = "time")]
         Vec<sqlx::postgres::types::PgRange<sqlx::types::time::OffsetDateTime>> |
             &[sqlx::postgres::types::PgRange<sqlx::types::time::OffsetDateTime>],
+
+        // Multiranges
+
+        sqlx::postgres::types::PgMultiRange<i32>,
+        sqlx::postgres::types::PgMultiRange<i64>,
+
+        #[cfg(feature = "bigdecimal")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::BigDecimal>,
+
+        #[cfg(feature = "rust_decimal")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::Decimal>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::chrono::NaiveDate>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::chrono::NaiveDateTime>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>> |
+            sqlx::postgres::types::PgMultiRange<sqlx::types::chrono::DateTime<_>>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::time::Date>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::time::PrimitiveDateTime>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultiRange<sqlx::types::time::OffsetDateTime>,
     },
     ParamChecking::Strong,
     feature-types: info => info.__type_feature_gate(),
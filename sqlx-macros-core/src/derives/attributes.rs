@@ -57,6 +57,7 @@ pub struct SqlxContainerAttributes {
     pub rename_all: Option<RenameAll>,
     pub repr: Option<Ident>,
     pub no_pg_array: bool,
+    pub pg_array: bool,
 }
 
 pub struct SqlxChildAttributes {
@@ -74,6 +75,7 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
     let mut type_name = None;
     let mut rename_all = None;
     let mut no_pg_array = None;
+    let mut pg_array = None;
 
     for attr in input
         .iter()
@@ -95,6 +97,10 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 try_set!(no_pg_array, true, value);
                             }
 
+                            Meta::Path(p) if p.is_ident("pg_array") => {
+                                try_set!(pg_array, true, value);
+                            }
+
                             Meta::NameValue(MetaNameValue {
                                 path,
                                 lit: Lit::Str(val),
@@ -156,6 +162,7 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
         type_name,
         rename_all,
         no_pg_array: no_pg_array.unwrap_or(false),
+        pg_array: pg_array.unwrap_or(false),
     })
 }
 
@@ -227,6 +234,12 @@ pub fn check_transparent_attributes(
         field
     );
 
+    assert_attribute!(
+        !attributes.pg_array,
+        "unused #[sqlx(pg_array)]; derive emits `PgHasArrayType` impls for transparent types by default",
+        input
+    );
+
     let ch_attributes = parse_child_attributes(&field.attrs)?;
 
     assert_attribute!(
@@ -253,6 +266,12 @@ pub fn check_enum_attributes(input: &DeriveInput) -> syn::Result<SqlxContainerAt
         input
     );
 
+    assert_attribute!(
+        !attributes.pg_array,
+        "unexpected #[sqlx(pg_array)]; derive does not emit `PgHasArrayType` impls for enums",
+        input
+    );
+
     Ok(attributes)
 }
 
@@ -314,7 +333,7 @@ pub fn check_struct_attributes<'a>(
 
     assert_attribute!(
         !attributes.no_pg_array,
-        "unused #[sqlx(no_pg_array)]; derive does not emit `PgHasArrayType` impls for custom structs",
+        "unused #[sqlx(no_pg_array)]; derive only emits `PgHasArrayType` impls for custom structs with #[sqlx(pg_array)]",
         input
     );
 
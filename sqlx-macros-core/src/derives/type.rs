@@ -224,6 +224,19 @@ fn expand_derive_has_sql_type_struct(
                 }
             }
         ));
+
+        if attributes.pg_array {
+            let array_ty_name = array_type_name(ident, attributes.type_name.as_ref());
+
+            tts.extend(quote!(
+                #[automatically_derived]
+                impl ::sqlx::postgres::PgHasArrayType for #ident {
+                    fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                        ::sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                    }
+                }
+            ));
+        }
     }
 
     Ok(tts)
@@ -235,3 +248,16 @@ fn type_name(ident: &Ident, explicit_name: Option<&TypeName>) -> TokenStream {
         quote_spanned!(ident.span()=> #s)
     })
 }
+
+/// The name of the array type of a custom type, which Postgres prefixes with an underscore
+/// (after the schema, if any).
+fn array_type_name(ident: &Ident, explicit_name: Option<&TypeName>) -> TokenStream {
+    let name = explicit_name.map_or_else(|| ident.to_string(), |tn| tn.val.clone());
+
+    let array_name = match name.rsplit_once('.') {
+        Some((schema, name)) => format!("{schema}._{name}"),
+        None => format!("_{name}"),
+    };
+
+    quote_spanned!(ident.span()=> #array_name)
+}
@@ -1,4 +1,9 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
 use crate::encode::{Encode, IsNull};
+use crate::io::AsyncRead;
+use crate::protocol::Capabilities;
 use crate::types::Type;
 use crate::{MySql, MySqlTypeInfo};
 pub(crate) use sqlx_core::arguments::*;
@@ -9,6 +14,31 @@ pub struct MySqlArguments {
     pub(crate) values: Vec<u8>,
     pub(crate) types: Vec<MySqlTypeInfo>,
     pub(crate) null_bitmap: Vec<u8>,
+    // parameters sent with `COM_STMT_SEND_LONG_DATA` instead of in `values`, by index
+    pub(crate) long_data: Vec<(u16, LongData)>,
+    // query attributes, by name
+    pub(crate) attributes: Vec<(String, String)>,
+    // the rows fetched at once through a cursor, if the rows are not sent immediately
+    pub(crate) fetch_size: Option<u32>,
+}
+
+/// The source of a parameter bound with
+/// [`MySqlQueryExt::bind_long_data`](crate::MySqlQueryExt::bind_long_data).
+///
+/// Clones of the arguments share the source, which can only be read once.
+#[derive(Clone)]
+pub(crate) struct LongData(Arc<Mutex<Option<Box<dyn AsyncRead + Send + Unpin>>>>);
+
+impl LongData {
+    pub(crate) fn take(&self) -> Option<Box<dyn AsyncRead + Send + Unpin>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl Debug for LongData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LongData").finish_non_exhaustive()
+    }
 }
 
 impl MySqlArguments {
@@ -27,6 +57,27 @@ impl MySqlArguments {
         }
     }
 
+    pub(crate) fn add_long_data(&mut self, source: impl AsyncRead + Send + Unpin + 'static) {
+        let index = self.types.len();
+
+        self.types.push(<[u8] as Type<MySql>>::type_info());
+        self.null_bitmap.resize((index / 8) + 1, 0);
+
+        // the server rejects statements with that many parameters anyway
+        let index = u16::try_from(index).unwrap_or(u16::MAX);
+
+        self.long_data.push((
+            index,
+            LongData(Arc::new(Mutex::new(Some(Box::new(source))))),
+        ));
+    }
+
+    // a cursor is only opened if the server ends the column definitions with an EOF packet for
+    // the cursor, and not for the metadata
+    pub(crate) fn uses_cursor(&self, capabilities: Capabilities) -> bool {
+        self.fetch_size.is_some() && capabilities.contains(Capabilities::DEPRECATE_EOF)
+    }
+
     #[doc(hidden)]
     pub fn len(&self) -> usize {
         self.types.len()
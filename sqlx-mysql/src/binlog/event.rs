@@ -0,0 +1,728 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, Bytes};
+
+use crate::error::Result;
+
+use super::gtid::format_sid;
+use super::value::{decode_value, is_numeric, metadata_len, MySqlBinlogValue};
+use super::{ensure_remaining, get_bytes, get_lenenc, get_str, get_u8, get_uint_le};
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_replication_binlog_event.html
+const QUERY_EVENT: u8 = 2;
+const ROTATE_EVENT: u8 = 4;
+const FORMAT_DESCRIPTION_EVENT: u8 = 15;
+const XID_EVENT: u8 = 16;
+const TABLE_MAP_EVENT: u8 = 19;
+const WRITE_ROWS_EVENT_V1: u8 = 23;
+const UPDATE_ROWS_EVENT_V1: u8 = 24;
+const DELETE_ROWS_EVENT_V1: u8 = 25;
+const HEARTBEAT_EVENT: u8 = 27;
+const WRITE_ROWS_EVENT_V2: u8 = 30;
+const UPDATE_ROWS_EVENT_V2: u8 = 31;
+const DELETE_ROWS_EVENT_V2: u8 = 32;
+const GTID_EVENT: u8 = 33;
+
+// the types of optional metadata in table map events
+const METADATA_SIGNEDNESS: u8 = 1;
+const METADATA_COLUMN_NAME: u8 = 4;
+
+/// The length of the common header of events in version 4 of the binary log.
+pub(crate) const EVENT_HEADER_LEN: usize = 19;
+
+/// An event read from the binary log, decoded by [`MySqlBinlogStream::recv`].
+///
+/// [`MySqlBinlogStream::recv`]: crate::MySqlBinlogStream::recv
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogEvent {
+    timestamp: u32,
+    server_id: u32,
+    next_position: u32,
+    data: MySqlBinlogEventData,
+}
+
+impl MySqlBinlogEvent {
+    /// The time the statement that caused the event started on the original server.
+    pub fn timestamp(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp.into())
+    }
+
+    /// The `server_id` of the server on which the event originated.
+    pub fn server_id(&self) -> u32 {
+        self.server_id
+    }
+
+    /// The position of the next event in the current binary log file.
+    ///
+    /// This is `0` for events that are not stored in the file, like heartbeats and the
+    /// rotation to the first file.
+    pub fn next_position(&self) -> u32 {
+        self.next_position
+    }
+
+    /// The contents of the event.
+    pub fn data(&self) -> &MySqlBinlogEventData {
+        &self.data
+    }
+
+    /// Returns the contents of the event.
+    pub fn into_data(self) -> MySqlBinlogEventData {
+        self.data
+    }
+}
+
+/// The contents of a [`MySqlBinlogEvent`].
+///
+/// With `binlog_format = ROW`, a transaction is a [`Gtid`][Self::Gtid] (with GTIDs enabled),
+/// a [`Query`][Self::Query] with `BEGIN`, the [`TableMap`][Self::TableMap] of each table
+/// before its row changes, and an [`Xid`][Self::Xid] when it commits. Statements that are not
+/// logged as rows, e.g. DDL, are a single [`Query`][Self::Query].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum MySqlBinlogEventData {
+    /// The server continues in another binary log file.
+    Rotate(MySqlBinlogRotate),
+
+    /// The start of a binary log file, which describes its format.
+    FormatDescription(MySqlBinlogFormatDescription),
+
+    /// The GTID of the following transaction.
+    Gtid(MySqlBinlogGtid),
+
+    /// A statement, like `BEGIN` or DDL.
+    Query(MySqlBinlogQuery),
+
+    /// The commit of a transaction.
+    Xid(MySqlBinlogXid),
+
+    /// The definition of a table used by the following row events.
+    TableMap(Arc<MySqlBinlogTable>),
+
+    /// Rows were inserted.
+    WriteRows(MySqlBinlogRows),
+
+    /// Rows were updated.
+    UpdateRows(MySqlBinlogUpdateRows),
+
+    /// Rows were deleted.
+    DeleteRows(MySqlBinlogRows),
+
+    /// The server is alive, but has no new events.
+    Heartbeat,
+
+    /// An event of a type that is not decoded, with its type code and data.
+    Other { event_type: u8, data: Bytes },
+}
+
+/// The server continues in another binary log file.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogRotate {
+    next_file: String,
+    position: u64,
+}
+
+impl MySqlBinlogRotate {
+    /// The name of the binary log file the following events are in.
+    pub fn next_file(&self) -> &str {
+        &self.next_file
+    }
+
+    /// The position of the first following event in that file.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+/// The start of a binary log file, which describes its format.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogFormatDescription {
+    binlog_version: u16,
+    server_version: String,
+}
+
+impl MySqlBinlogFormatDescription {
+    /// The version of the binary log format, `4` since MySQL 5.0.
+    pub fn binlog_version(&self) -> u16 {
+        self.binlog_version
+    }
+
+    /// The version of the server that wrote the file.
+    pub fn server_version(&self) -> &str {
+        &self.server_version
+    }
+}
+
+/// The GTID of the following transaction.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogGtid {
+    source_id: String,
+    transaction_id: u64,
+}
+
+impl MySqlBinlogGtid {
+    /// The UUID of the server on which the transaction was committed first.
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    /// The sequence number of the transaction on that server.
+    pub fn transaction_id(&self) -> u64 {
+        self.transaction_id
+    }
+}
+
+impl std::fmt::Display for MySqlBinlogGtid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source_id, self.transaction_id)
+    }
+}
+
+/// A statement, like `BEGIN` or DDL.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogQuery {
+    schema: String,
+    query: String,
+}
+
+impl MySqlBinlogQuery {
+    /// The default schema the statement ran in.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// The statement.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+}
+
+/// The commit of a transaction.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogXid {
+    xid: u64,
+}
+
+impl MySqlBinlogXid {
+    /// The transaction id, which is unique for the server.
+    pub fn xid(&self) -> u64 {
+        self.xid
+    }
+}
+
+/// The definition of a table, sent before the row events that change it.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogTable {
+    id: u64,
+    schema: String,
+    name: String,
+    columns: Vec<MySqlBinlogColumn>,
+}
+
+/// A column of a [`MySqlBinlogTable`].
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogColumn {
+    column_type: u8,
+    metadata: [u8; 2],
+    nullable: bool,
+    unsigned: bool,
+    name: Option<String>,
+}
+
+impl MySqlBinlogTable {
+    /// The id of the table in the following row events. It may be reused for another table
+    /// after the definition changed.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The schema (database) of the table.
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// The name of the table.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The columns of the table.
+    pub fn columns(&self) -> &[MySqlBinlogColumn] {
+        &self.columns
+    }
+
+    fn decode(mut buf: Bytes) -> Result<Self> {
+        let id = get_uint_le(&mut buf, 6)?;
+        let _flags = get_uint_le(&mut buf, 2)?;
+
+        let len = get_u8(&mut buf)?;
+        let schema = get_str(&mut buf, len.into())?;
+        let _nul = get_u8(&mut buf)?;
+
+        let len = get_u8(&mut buf)?;
+        let name = get_str(&mut buf, len.into())?;
+        let _nul = get_u8(&mut buf)?;
+
+        let num_columns = get_lenenc(&mut buf)? as usize;
+        let types = get_bytes(&mut buf, num_columns)?;
+
+        let mut metadata = {
+            let len = get_lenenc(&mut buf)? as usize;
+            get_bytes(&mut buf, len)?
+        };
+
+        let nullable = get_bytes(&mut buf, (num_columns + 7) / 8)?;
+
+        let mut columns = Vec::with_capacity(num_columns);
+
+        for (i, &column_type) in types.iter().enumerate() {
+            let mut meta = [0; 2];
+            let meta_len = metadata_len(column_type);
+
+            ensure_remaining(&metadata, meta_len)?;
+            metadata.copy_to_slice(&mut meta[..meta_len]);
+
+            columns.push(MySqlBinlogColumn {
+                column_type,
+                metadata: meta,
+                nullable: bit(&nullable, i),
+                unsigned: false,
+                name: None,
+            });
+        }
+
+        // the optional metadata of `binlog_row_metadata`, since MySQL 8.0.1
+        while buf.has_remaining() {
+            let field_type = get_u8(&mut buf)?;
+            let len = get_lenenc(&mut buf)? as usize;
+            let mut field = get_bytes(&mut buf, len)?;
+
+            match field_type {
+                METADATA_SIGNEDNESS => {
+                    // one bit per numeric column, starting at the highest
+                    let numeric = columns.iter_mut().filter(|c| is_numeric(c.column_type));
+
+                    for (i, column) in numeric.enumerate() {
+                        let byte = field.get(i / 8).copied().unwrap_or(0);
+                        column.unsigned = byte & (0x80 >> (i % 8)) != 0;
+                    }
+                }
+
+                METADATA_COLUMN_NAME => {
+                    for column in &mut columns {
+                        let len = get_lenenc(&mut field)? as usize;
+                        column.name = Some(get_str(&mut field, len)?);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            id,
+            schema,
+            name,
+            columns,
+        })
+    }
+}
+
+impl MySqlBinlogColumn {
+    /// The type of the column, as a `MYSQL_TYPE_*` code of the binary log.
+    ///
+    /// `CHAR`, `ENUM` and `SET` columns all have the type `MYSQL_TYPE_STRING` (`0xfe`), and
+    /// `TEXT` columns have the type of the `BLOB` of the same size.
+    pub fn column_type(&self) -> u8 {
+        self.column_type
+    }
+
+    /// Whether the column can be `NULL`.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Whether the column is an unsigned number. Only known with
+    /// `binlog_row_metadata = FULL`; otherwise this is always `false`.
+    pub fn is_unsigned(&self) -> bool {
+        self.unsigned
+    }
+
+    /// The name of the column. Only known with `binlog_row_metadata = FULL`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+/// The rows of a write (insert) or delete event.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogRows {
+    table: Arc<MySqlBinlogTable>,
+    rows: Vec<MySqlBinlogRow>,
+}
+
+impl MySqlBinlogRows {
+    /// The table the rows are in.
+    pub fn table(&self) -> &Arc<MySqlBinlogTable> {
+        &self.table
+    }
+
+    /// The inserted or deleted rows.
+    pub fn rows(&self) -> &[MySqlBinlogRow] {
+        &self.rows
+    }
+}
+
+/// The rows of an update event.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogUpdateRows {
+    table: Arc<MySqlBinlogTable>,
+    rows: Vec<(MySqlBinlogRow, MySqlBinlogRow)>,
+}
+
+impl MySqlBinlogUpdateRows {
+    /// The table the rows are in.
+    pub fn table(&self) -> &Arc<MySqlBinlogTable> {
+        &self.table
+    }
+
+    /// The updated rows, before and after the update.
+    pub fn rows(&self) -> &[(MySqlBinlogRow, MySqlBinlogRow)] {
+        &self.rows
+    }
+}
+
+/// A row image in a row event.
+///
+/// With `binlog_row_image = FULL`, the default, rows contain all columns. Otherwise, the server
+/// leaves out the columns that are not needed to identify a row, or that did not change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlBinlogRow {
+    values: Vec<Option<MySqlBinlogValue>>,
+}
+
+impl MySqlBinlogRow {
+    /// The value of the column at `index`, or `None` if the row does not contain it.
+    pub fn get(&self, index: usize) -> Option<&MySqlBinlogValue> {
+        self.values.get(index).and_then(Option::as_ref)
+    }
+
+    /// The values of all columns of the table, `None` for columns the row does not contain.
+    pub fn values(&self) -> &[Option<MySqlBinlogValue>] {
+        &self.values
+    }
+}
+
+impl MySqlBinlogEvent {
+    /// Decode an event, looking up the table of row events with `table`.
+    pub(crate) fn decode(
+        mut buf: Bytes,
+        table: impl FnOnce(u64) -> Option<Arc<MySqlBinlogTable>>,
+    ) -> Result<Self> {
+        ensure_remaining(&buf, EVENT_HEADER_LEN)?;
+
+        let timestamp = buf.get_u32_le();
+        let event_type = buf.get_u8();
+        let server_id = buf.get_u32_le();
+        let _event_size = buf.get_u32_le();
+        let next_position = buf.get_u32_le();
+        let _flags = buf.get_u16_le();
+
+        let data = match event_type {
+            ROTATE_EVENT => {
+                ensure_remaining(&buf, 8)?;
+                let position = buf.get_u64_le();
+                let len = buf.len();
+
+                MySqlBinlogEventData::Rotate(MySqlBinlogRotate {
+                    next_file: get_str(&mut buf, len)?,
+                    position,
+                })
+            }
+
+            FORMAT_DESCRIPTION_EVENT => {
+                let binlog_version = get_uint_le(&mut buf, 2)? as u16;
+                let server_version = get_bytes(&mut buf, 50)?;
+                let len = server_version.iter().position(|&b| b == 0).unwrap_or(50);
+
+                MySqlBinlogEventData::FormatDescription(MySqlBinlogFormatDescription {
+                    binlog_version,
+                    server_version: String::from_utf8_lossy(&server_version[..len]).into_owned(),
+                })
+            }
+
+            GTID_EVENT => {
+                let _flags = get_u8(&mut buf)?;
+                let source_id = format_sid(&get_bytes(&mut buf, 16)?);
+                let transaction_id = get_uint_le(&mut buf, 8)?;
+
+                MySqlBinlogEventData::Gtid(MySqlBinlogGtid {
+                    source_id,
+                    transaction_id,
+                })
+            }
+
+            QUERY_EVENT => {
+                let _thread_id = get_uint_le(&mut buf, 4)?;
+                let _exec_time = get_uint_le(&mut buf, 4)?;
+                let schema_len = get_u8(&mut buf)?;
+                let _error_code = get_uint_le(&mut buf, 2)?;
+                let status_vars_len = get_uint_le(&mut buf, 2)? as usize;
+                get_bytes(&mut buf, status_vars_len)?;
+
+                let schema = get_str(&mut buf, schema_len.into())?;
+                let _nul = get_u8(&mut buf)?;
+                let len = buf.len();
+
+                MySqlBinlogEventData::Query(MySqlBinlogQuery {
+                    schema,
+                    query: get_str(&mut buf, len)?,
+                })
+            }
+
+            XID_EVENT => MySqlBinlogEventData::Xid(MySqlBinlogXid {
+                xid: get_uint_le(&mut buf, 8)?,
+            }),
+
+            TABLE_MAP_EVENT => {
+                MySqlBinlogEventData::TableMap(Arc::new(MySqlBinlogTable::decode(buf)?))
+            }
+
+            WRITE_ROWS_EVENT_V1 | WRITE_ROWS_EVENT_V2 | DELETE_ROWS_EVENT_V1
+            | DELETE_ROWS_EVENT_V2 => {
+                let v2 = matches!(event_type, WRITE_ROWS_EVENT_V2 | DELETE_ROWS_EVENT_V2);
+                let (table, present, _, mut buf) = decode_rows_header(buf, v2, false, table)?;
+
+                let mut rows = Vec::new();
+
+                while buf.has_remaining() {
+                    rows.push(decode_row(&mut buf, &table, &present)?);
+                }
+
+                let rows = MySqlBinlogRows { table, rows };
+
+                if matches!(event_type, WRITE_ROWS_EVENT_V1 | WRITE_ROWS_EVENT_V2) {
+                    MySqlBinlogEventData::WriteRows(rows)
+                } else {
+                    MySqlBinlogEventData::DeleteRows(rows)
+                }
+            }
+
+            UPDATE_ROWS_EVENT_V1 | UPDATE_ROWS_EVENT_V2 => {
+                let v2 = event_type == UPDATE_ROWS_EVENT_V2;
+                let (table, before, after, mut buf) = decode_rows_header(buf, v2, true, table)?;
+
+                let mut rows = Vec::new();
+
+                while buf.has_remaining() {
+                    let before = decode_row(&mut buf, &table, &before)?;
+                    let after = decode_row(&mut buf, &table, &after)?;
+
+                    rows.push((before, after));
+                }
+
+                MySqlBinlogEventData::UpdateRows(MySqlBinlogUpdateRows { table, rows })
+            }
+
+            HEARTBEAT_EVENT => MySqlBinlogEventData::Heartbeat,
+
+            _ => MySqlBinlogEventData::Other {
+                event_type,
+                data: buf,
+            },
+        };
+
+        Ok(Self {
+            timestamp,
+            server_id,
+            next_position,
+            data,
+        })
+    }
+}
+
+/// Decode the header of a rows event, returning the table, the bitmaps of the columns in the
+/// row images, and the rows.
+fn decode_rows_header(
+    mut buf: Bytes,
+    v2: bool,
+    update: bool,
+    table: impl FnOnce(u64) -> Option<Arc<MySqlBinlogTable>>,
+) -> Result<(Arc<MySqlBinlogTable>, Bytes, Bytes, Bytes)> {
+    let table_id = get_uint_le(&mut buf, 6)?;
+    let _flags = get_uint_le(&mut buf, 2)?;
+
+    if v2 {
+        // the length includes itself
+        let extra_len = get_uint_le(&mut buf, 2)? as usize;
+        get_bytes(&mut buf, extra_len.saturating_sub(2))?;
+    }
+
+    let table = table(table_id).ok_or_else(|| {
+        err_protocol!(
+            "received a rows event for table {} without its table map event",
+            table_id
+        )
+    })?;
+
+    let num_columns = get_lenenc(&mut buf)? as usize;
+
+    if num_columns != table.columns.len() {
+        return Err(err_protocol!(
+            "rows event has {} columns, but table {} has {}",
+            num_columns,
+            table_id,
+            table.columns.len()
+        ));
+    }
+
+    let before = get_bytes(&mut buf, (num_columns + 7) / 8)?;
+
+    let after = if update {
+        get_bytes(&mut buf, (num_columns + 7) / 8)?
+    } else {
+        before.clone()
+    };
+
+    Ok((table, before, after, buf))
+}
+
+fn decode_row(buf: &mut Bytes, table: &MySqlBinlogTable, present: &[u8]) -> Result<MySqlBinlogRow> {
+    let num_present = (0..table.columns.len())
+        .filter(|&i| bit(present, i))
+        .count();
+    let nulls = get_bytes(buf, (num_present + 7) / 8)?;
+
+    let mut values = Vec::with_capacity(table.columns.len());
+    let mut null_index = 0;
+
+    for (i, column) in table.columns.iter().enumerate() {
+        if !bit(present, i) {
+            values.push(None);
+            continue;
+        }
+
+        let is_null = bit(&nulls, null_index);
+        null_index += 1;
+
+        values.push(Some(if is_null {
+            MySqlBinlogValue::Null
+        } else {
+            decode_value(buf, column.column_type, column.metadata, column.unsigned)?
+        }));
+    }
+
+    Ok(MySqlBinlogRow { values })
+}
+
+// bitmaps in events start at the lowest bit
+fn bit(bitmap: &[u8], index: usize) -> bool {
+    bitmap
+        .get(index / 8)
+        .map_or(false, |byte| byte & (1 << (index % 8)) != 0)
+}
+
+#[cfg(test)]
+fn encode_event(event_type: u8, body: &[u8]) -> Bytes {
+    let mut buf = Vec::new();
+    buf.extend(&1_623_764_730_u32.to_le_bytes());
+    buf.push(event_type);
+    buf.extend(&1_u32.to_le_bytes());
+    buf.extend(&((EVENT_HEADER_LEN + body.len()) as u32).to_le_bytes());
+    buf.extend(&1234_u32.to_le_bytes());
+    buf.extend(&0_u16.to_le_bytes());
+    buf.extend(body);
+    buf.into()
+}
+
+#[test]
+fn test_decode_table_map_and_rows() {
+    use super::value::{TYPE_LONG, TYPE_VARCHAR};
+
+    let mut body = vec![42, 0, 0, 0, 0, 0, 1, 0];
+    body.extend(b"\x04test\x00\x05users\x00");
+    body.extend([2, TYPE_LONG, TYPE_VARCHAR]);
+    body.extend([2, 0x40, 0x00]); // VARCHAR(64)
+    body.push(0b10); // `name` is nullable
+                     // signedness: `id` is unsigned
+    body.extend([METADATA_SIGNEDNESS, 1, 0x80]);
+    body.extend([METADATA_COLUMN_NAME, 8, 2]);
+    body.extend(b"id\x04name");
+
+    let table = match MySqlBinlogEvent::decode(encode_event(TABLE_MAP_EVENT, &body), |_| None)
+        .unwrap()
+        .into_data()
+    {
+        MySqlBinlogEventData::TableMap(table) => table,
+        other => panic!("unexpected event {other:?}"),
+    };
+
+    assert_eq!(table.id(), 42);
+    assert_eq!((table.schema(), table.name()), ("test", "users"));
+    assert_eq!(table.columns()[0].name(), Some("id"));
+    assert!(table.columns()[0].is_unsigned());
+    assert!(table.columns()[1].is_nullable());
+
+    // an update of (1, 'a') to (1, NULL)
+    let mut body = vec![42, 0, 0, 0, 0, 0, 1, 0, 2, 0];
+    body.extend([2, 0b11, 0b11]);
+    body.extend([0b00, 1, 0, 0, 0, 1, b'a']);
+    body.extend([0b10, 1, 0, 0, 0]);
+
+    let lookup = |id| (id == 42).then(|| Arc::clone(&table));
+
+    let event =
+        MySqlBinlogEvent::decode(encode_event(UPDATE_ROWS_EVENT_V2, &body), lookup).unwrap();
+
+    assert_eq!(event.next_position(), 1234);
+
+    let update = match event.into_data() {
+        MySqlBinlogEventData::UpdateRows(update) => update,
+        other => panic!("unexpected event {other:?}"),
+    };
+
+    let (before, after) = &update.rows()[0];
+
+    assert_eq!(before.get(0), Some(&MySqlBinlogValue::UInt(1)));
+    assert_eq!(
+        before.get(1),
+        Some(&MySqlBinlogValue::Bytes(Bytes::from_static(b"a")))
+    );
+    assert_eq!(after.get(1), Some(&MySqlBinlogValue::Null));
+
+    // a rows event without its table map
+    assert!(MySqlBinlogEvent::decode(encode_event(UPDATE_ROWS_EVENT_V2, &body), |_| None).is_err());
+}
+
+#[test]
+fn test_decode_gtid_and_rotate() {
+    let mut body = vec![1];
+    body.extend(hex::decode("3e11fa4771ca11e19e33c80aa9429562").unwrap());
+    body.extend(&23_u64.to_le_bytes());
+
+    match MySqlBinlogEvent::decode(encode_event(GTID_EVENT, &body), |_| None)
+        .unwrap()
+        .into_data()
+    {
+        MySqlBinlogEventData::Gtid(gtid) => {
+            assert_eq!(gtid.to_string(), "3e11fa47-71ca-11e1-9e33-c80aa9429562:23");
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    let mut body = 4_u64.to_le_bytes().to_vec();
+    body.extend(b"binlog.000002");
+
+    match MySqlBinlogEvent::decode(encode_event(ROTATE_EVENT, &body), |_| None)
+        .unwrap()
+        .into_data()
+    {
+        MySqlBinlogEventData::Rotate(rotate) => {
+            assert_eq!(
+                (rotate.next_file(), rotate.position()),
+                ("binlog.000002", 4)
+            );
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::Error;
+
+/// Encode a GTID set like `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:11,...` for
+/// `COM_BINLOG_DUMP_GTID`.
+pub(crate) fn encode_gtid_set(set: &str) -> Result<Vec<u8>, Error> {
+    let invalid =
+        |reason: &str| Error::Configuration(format!("invalid GTID set {set:?}: {reason}").into());
+
+    let mut sids = Vec::new();
+
+    for sid_set in set.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let mut parts = sid_set.split(':');
+
+        // `split` always returns at least one part
+        let sid = parse_sid(parts.next().unwrap_or_default().trim())
+            .ok_or_else(|| invalid("expected a server UUID"))?;
+
+        let mut intervals = Vec::new();
+
+        for interval in parts {
+            let (start, end) = match interval.trim().split_once('-') {
+                Some((start, end)) => (start.parse::<u64>(), end.parse::<u64>()),
+                None => (interval.trim().parse(), interval.trim().parse()),
+            };
+
+            match (start, end) {
+                (Ok(start), Ok(end)) if start >= 1 && end >= start && end < u64::MAX => {
+                    // the end of an encoded interval is exclusive
+                    intervals.push((start, end + 1));
+                }
+                _ => return Err(invalid("expected a transaction number or a range of them")),
+            }
+        }
+
+        if intervals.is_empty() {
+            return Err(invalid(
+                "expected transaction numbers after the server UUID",
+            ));
+        }
+
+        sids.push((sid, intervals));
+    }
+
+    let mut buf = Vec::new();
+    buf.extend(&(sids.len() as u64).to_le_bytes());
+
+    for (sid, intervals) in sids {
+        buf.extend(&sid);
+        buf.extend(&(intervals.len() as u64).to_le_bytes());
+
+        for (start, end) in intervals {
+            buf.extend(&start.to_le_bytes());
+            buf.extend(&end.to_le_bytes());
+        }
+    }
+
+    Ok(buf)
+}
+
+fn parse_sid(sid: &str) -> Option<[u8; 16]> {
+    let hex: String = sid.chars().filter(|&c| c != '-').collect();
+
+    if hex.len() != 32 || sid.len() != 36 {
+        return None;
+    }
+
+    let mut bytes = [0; 16];
+    hex::decode_to_slice(hex, &mut bytes).ok()?;
+
+    Some(bytes)
+}
+
+/// Format the server UUID of a GTID event.
+pub(crate) fn format_sid(sid: &[u8]) -> String {
+    let hex = hex::encode(sid);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[test]
+fn test_encode_gtid_set() {
+    let set = "3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:11,\n\
+               3E11FA47-71CA-11E1-9E33-C80AA9429563:7";
+
+    let mut expected = vec![2, 0, 0, 0, 0, 0, 0, 0];
+    expected.extend(hex::decode("3e11fa4771ca11e19e33c80aa9429562").unwrap());
+    expected.extend([2, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend([1, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend([11, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend(hex::decode("3e11fa4771ca11e19e33c80aa9429563").unwrap());
+    expected.extend([1, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend([7, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0]);
+
+    assert_eq!(encode_gtid_set(set).unwrap(), expected);
+    assert_eq!(encode_gtid_set("").unwrap(), [0; 8]);
+
+    assert!(encode_gtid_set("3e11fa47-71ca-11e1-9e33-c80aa9429562").is_err());
+    assert!(encode_gtid_set("3e11fa47-71ca-11e1-9e33-c80aa9429562:5-1").is_err());
+    assert!(encode_gtid_set("3e11fa47:1-5").is_err());
+
+    assert_eq!(
+        format_sid(&hex::decode("3e11fa4771ca11e19e33c80aa9429562").unwrap()),
+        "3e11fa47-71ca-11e1-9e33-c80aa9429562"
+    );
+}
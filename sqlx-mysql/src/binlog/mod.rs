@@ -0,0 +1,363 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::connection::Connection;
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::io::MySqlBufExt;
+use crate::protocol::replication::{BinlogDump, BinlogDumpGtid, BINLOG_DUMP_NON_BLOCK};
+use crate::MySqlConnection;
+
+pub use event::{
+    MySqlBinlogColumn, MySqlBinlogEvent, MySqlBinlogEventData, MySqlBinlogFormatDescription,
+    MySqlBinlogGtid, MySqlBinlogQuery, MySqlBinlogRotate, MySqlBinlogRow, MySqlBinlogRows,
+    MySqlBinlogTable, MySqlBinlogUpdateRows, MySqlBinlogXid,
+};
+pub use value::MySqlBinlogValue;
+
+mod event;
+mod gtid;
+mod value;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_replication.html
+
+/// The largest payload of a single packet; larger events are split over several packets.
+const MAX_PACKET_PAYLOAD: usize = 0xFF_FF_FF;
+
+/// Where a [`MySqlBinlogStream`] starts reading the binary log, and how it registers with the
+/// server.
+#[derive(Debug, Clone)]
+pub struct MySqlBinlogOptions {
+    server_id: u32,
+    start: BinlogStart,
+    non_blocking: bool,
+    heartbeat_period: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+enum BinlogStart {
+    // the start of the oldest binary log file still on the server
+    Oldest,
+    Position { file: String, position: u64 },
+    Gtid(String),
+}
+
+impl MySqlBinlogOptions {
+    /// Options for a replica with the given `server_id`, which starts at the oldest binary log
+    /// file still on the server.
+    ///
+    /// The `server_id` must differ from that of the server and all other replicas, or the
+    /// server disconnects the replica with the same id.
+    pub fn new(server_id: u32) -> Self {
+        Self {
+            server_id,
+            start: BinlogStart::Oldest,
+            non_blocking: false,
+            heartbeat_period: None,
+        }
+    }
+
+    /// Start at `position` in the binary log file `file`, as reported by
+    /// `SHOW MASTER STATUS` or an earlier [`MySqlBinlogStream::position`].
+    pub fn position(mut self, file: impl Into<String>, position: u64) -> Self {
+        self.start = BinlogStart::Position {
+            file: file.into(),
+            position,
+        };
+        self
+    }
+
+    /// Start with the first transaction that is not in the GTID set `gtid_set`, e.g.
+    /// `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5` for a replica that already has the first five
+    /// transactions of that server.
+    ///
+    /// Requires `gtid_mode = ON` on the server. An empty set starts at the oldest transaction.
+    pub fn gtid_set(mut self, gtid_set: impl Into<String>) -> Self {
+        self.start = BinlogStart::Gtid(gtid_set.into());
+        self
+    }
+
+    /// Whether the stream ends once it reached the end of the binary log, instead of waiting
+    /// for new events. Defaults to `false`.
+    pub fn non_blocking(mut self, non_blocking: bool) -> Self {
+        self.non_blocking = non_blocking;
+        self
+    }
+
+    /// Let the server send a [`Heartbeat`][MySqlBinlogEventData::Heartbeat] event when it
+    /// had no events for `period`, so a broken connection is noticed while the database is
+    /// idle.
+    pub fn heartbeat_period(mut self, period: Duration) -> Self {
+        self.heartbeat_period = Some(period);
+        self
+    }
+}
+
+/// A connection registered as a replica, which streams the events of the server's binary log.
+///
+/// The server must have binary logging enabled, and the user needs the `REPLICATION SLAVE`
+/// privilege. Row events are only decoded with `binlog_format = ROW`, the default since
+/// MySQL 8.0; the names and signedness of columns are only sent with
+/// `binlog_row_metadata = FULL`.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use sqlx::mysql::{MySqlBinlogEventData, MySqlBinlogOptions, MySqlBinlogStream};
+/// use sqlx::{Connection, MySqlConnection};
+///
+/// let conn = MySqlConnection::connect("mysql://root@localhost/app").await?;
+///
+/// let options = MySqlBinlogOptions::new(1001).position("binlog.000003", 4);
+/// let mut stream = MySqlBinlogStream::start(conn, options).await?;
+///
+/// while let Some(event) = stream.recv().await? {
+///     if let MySqlBinlogEventData::WriteRows(rows) = event.data() {
+///         for row in rows.rows() {
+///             println!("inserted into {}: {:?}", rows.table().name(), row.values());
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The stream does not track which events were processed; to continue after a restart, store
+/// [`position`][Self::position] (or the GTIDs of the processed transactions) and start from
+/// there.
+pub struct MySqlBinlogStream {
+    conn: MySqlConnection,
+
+    // the table map events of the current binary log file, by table id
+    tables: HashMap<u64, Arc<MySqlBinlogTable>>,
+
+    file: String,
+    position: u64,
+
+    done: bool,
+}
+
+impl MySqlBinlogStream {
+    /// Register `conn` as a replica and start streaming the binary log.
+    ///
+    /// The connection can only be used for the stream afterwards, as the server sends the
+    /// events until it is closed.
+    pub async fn start(mut conn: MySqlConnection, options: MySqlBinlogOptions) -> Result<Self> {
+        let gtid_set = match &options.start {
+            BinlogStart::Gtid(set) => Some(gtid::encode_gtid_set(set)?),
+            _ => None,
+        };
+
+        // the events are not checked, which is done by the server and TCP already
+        conn.execute("SET @master_binlog_checksum = 'NONE', @source_binlog_checksum = 'NONE'")
+            .await?;
+
+        if let Some(period) = options.heartbeat_period {
+            conn.execute(&*format!(
+                "SET @master_heartbeat_period = {}",
+                period.as_nanos()
+            ))
+            .await?;
+        }
+
+        conn.stream.wait_until_ready().await?;
+
+        let flags = if options.non_blocking {
+            BINLOG_DUMP_NON_BLOCK
+        } else {
+            0
+        };
+
+        let (file, position) = match &options.start {
+            BinlogStart::Oldest => (String::new(), 4),
+            BinlogStart::Position { file, position } => (file.clone(), *position),
+            BinlogStart::Gtid(_) => (String::new(), 4),
+        };
+
+        match &gtid_set {
+            Some(gtid_set) => {
+                conn.stream
+                    .send_packet(BinlogDumpGtid {
+                        flags,
+                        server_id: options.server_id,
+                        filename: &file,
+                        position,
+                        gtid_set,
+                    })
+                    .await?
+            }
+
+            None => {
+                let position = u32::try_from(position).map_err(|_| {
+                    Error::Configuration(
+                        format!("binary log position {position} is out of range").into(),
+                    )
+                })?;
+
+                conn.stream
+                    .send_packet(BinlogDump {
+                        position,
+                        flags,
+                        server_id: options.server_id,
+                        filename: &file,
+                    })
+                    .await?
+            }
+        }
+
+        Ok(Self {
+            conn,
+            tables: HashMap::new(),
+            file,
+            position,
+            done: false,
+        })
+    }
+
+    /// Receive the next event of the binary log.
+    ///
+    /// Returns `None` once the end of the binary log was reached, if the stream was started
+    /// with [`non_blocking`][MySqlBinlogOptions::non_blocking]. An error returned by the
+    /// server, e.g. because the requested position was purged already, ends the stream.
+    pub async fn recv(&mut self) -> Result<Option<MySqlBinlogEvent>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut packet = match self.recv_packet().await {
+            Ok(packet) => packet,
+            Err(e) => {
+                self.done = true;
+                return Err(e);
+            }
+        };
+
+        match packet.first() {
+            Some(0x00) => packet.advance(1),
+
+            Some(0xfe) if packet.len() < 9 => {
+                self.done = true;
+                return Ok(None);
+            }
+
+            _ => {
+                return Err(err_protocol!(
+                    "expected a binary log event, but received {:?}",
+                    packet.first()
+                ))
+            }
+        }
+
+        let tables = &self.tables;
+        let event = MySqlBinlogEvent::decode(packet, |id| tables.get(&id).cloned())?;
+
+        match event.data() {
+            MySqlBinlogEventData::Rotate(rotate) => {
+                self.file = rotate.next_file().to_owned();
+                self.position = rotate.position();
+
+                // table ids are only valid within one file
+                self.tables.clear();
+
+                return Ok(Some(event));
+            }
+
+            MySqlBinlogEventData::TableMap(table) => {
+                self.tables.insert(table.id(), Arc::clone(table));
+            }
+
+            _ => {}
+        }
+
+        if event.next_position() != 0 {
+            self.position = event.next_position().into();
+        }
+
+        Ok(Some(event))
+    }
+
+    /// The binary log file and the position in it after the last received event, from which a
+    /// new stream continues with [`MySqlBinlogOptions::position`].
+    ///
+    /// Before the first event from a GTID set or the oldest file, the file name is empty.
+    pub fn position(&self) -> (&str, u64) {
+        (&self.file, self.position)
+    }
+
+    /// Close the connection, which unregisters the replica.
+    pub async fn close(self) -> Result<()> {
+        // the server doesn't read commands while streaming, so `COM_QUIT` would be ignored
+        self.conn.close_hard().await
+    }
+
+    // receive a packet, joining events that are split over several packets
+    async fn recv_packet(&mut self) -> Result<Bytes> {
+        let packet = self.conn.stream.recv_packet().await?.0;
+
+        if packet.len() < MAX_PACKET_PAYLOAD {
+            return Ok(packet);
+        }
+
+        let mut joined = BytesMut::from(&packet[..]);
+
+        loop {
+            let packet = self.conn.stream.recv_packet().await?.0;
+            joined.extend_from_slice(&packet);
+
+            if packet.len() < MAX_PACKET_PAYLOAD {
+                return Ok(joined.freeze());
+            }
+        }
+    }
+}
+
+fn ensure_remaining(buf: &Bytes, len: usize) -> Result<()> {
+    if buf.remaining() < len {
+        return Err(err_protocol!("binary log event too short"));
+    }
+
+    Ok(())
+}
+
+fn get_u8(buf: &mut Bytes) -> Result<u8> {
+    ensure_remaining(buf, 1)?;
+    Ok(buf.get_u8())
+}
+
+fn get_uint_le(buf: &mut Bytes, len: usize) -> Result<u64> {
+    ensure_remaining(buf, len)?;
+    Ok(buf.get_uint_le(len))
+}
+
+fn get_uint_be(buf: &mut Bytes, len: usize) -> Result<u64> {
+    ensure_remaining(buf, len)?;
+    Ok(buf.get_uint(len))
+}
+
+fn get_bytes(buf: &mut Bytes, len: usize) -> Result<Bytes> {
+    ensure_remaining(buf, len)?;
+    Ok(buf.split_to(len))
+}
+
+fn get_str(buf: &mut Bytes, len: usize) -> Result<String> {
+    let bytes = get_bytes(buf, len)?;
+
+    String::from_utf8(bytes.to_vec())
+        .map_err(|e| err_protocol!("invalid UTF-8 in binary log event: {}", e))
+}
+
+fn get_lenenc(buf: &mut Bytes) -> Result<u64> {
+    let len = match buf.first() {
+        Some(0xfc) => 3,
+        Some(0xfd) => 4,
+        Some(0xfe) => 9,
+        Some(_) => 1,
+        None => 0,
+    };
+
+    ensure_remaining(buf, cmp::max(len, 1))?;
+    Ok(buf.get_uint_lenenc())
+}
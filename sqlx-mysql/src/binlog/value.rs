@@ -0,0 +1,537 @@
+use bytes::Bytes;
+
+use crate::error::Result;
+
+use super::{get_bytes, get_u8, get_uint_be, get_uint_le};
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/binlog__event_8h.html
+pub(crate) const TYPE_DECIMAL: u8 = 0x00;
+pub(crate) const TYPE_TINY: u8 = 0x01;
+pub(crate) const TYPE_SHORT: u8 = 0x02;
+pub(crate) const TYPE_LONG: u8 = 0x03;
+pub(crate) const TYPE_FLOAT: u8 = 0x04;
+pub(crate) const TYPE_DOUBLE: u8 = 0x05;
+pub(crate) const TYPE_NULL: u8 = 0x06;
+pub(crate) const TYPE_TIMESTAMP: u8 = 0x07;
+pub(crate) const TYPE_LONGLONG: u8 = 0x08;
+pub(crate) const TYPE_INT24: u8 = 0x09;
+pub(crate) const TYPE_DATE: u8 = 0x0a;
+pub(crate) const TYPE_TIME: u8 = 0x0b;
+pub(crate) const TYPE_DATETIME: u8 = 0x0c;
+pub(crate) const TYPE_YEAR: u8 = 0x0d;
+pub(crate) const TYPE_VARCHAR: u8 = 0x0f;
+pub(crate) const TYPE_BIT: u8 = 0x10;
+pub(crate) const TYPE_TIMESTAMP2: u8 = 0x11;
+pub(crate) const TYPE_DATETIME2: u8 = 0x12;
+pub(crate) const TYPE_TIME2: u8 = 0x13;
+pub(crate) const TYPE_JSON: u8 = 0xf5;
+pub(crate) const TYPE_NEWDECIMAL: u8 = 0xf6;
+pub(crate) const TYPE_ENUM: u8 = 0xf7;
+pub(crate) const TYPE_SET: u8 = 0xf8;
+pub(crate) const TYPE_TINY_BLOB: u8 = 0xf9;
+pub(crate) const TYPE_MEDIUM_BLOB: u8 = 0xfa;
+pub(crate) const TYPE_LONG_BLOB: u8 = 0xfb;
+pub(crate) const TYPE_BLOB: u8 = 0xfc;
+pub(crate) const TYPE_VAR_STRING: u8 = 0xfd;
+pub(crate) const TYPE_STRING: u8 = 0xfe;
+pub(crate) const TYPE_GEOMETRY: u8 = 0xff;
+
+/// The number of bytes used for the given number of decimal digits, below 9.
+const DIG2BYTES: [usize; 10] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4];
+
+/// A column value in a row event of the binary log.
+///
+/// The binary log stores values in the format of the storage engine, which is converted into
+/// the closest Rust type here. Strings are left in the character set of their column.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MySqlBinlogValue {
+    /// `NULL`.
+    Null,
+
+    /// An integer column, or an unsigned one if the table map does not tell its signedness.
+    ///
+    /// The binary log only includes the signedness of columns with `binlog_row_metadata = FULL`;
+    /// otherwise, cast the value to the unsigned type of the column's width, e.g. `as u8` for
+    /// a `TINYINT UNSIGNED`.
+    Int(i64),
+
+    /// An unsigned integer column.
+    UInt(u64),
+
+    /// A `FLOAT` column.
+    Float(f32),
+
+    /// A `DOUBLE` column.
+    Double(f64),
+
+    /// A `DECIMAL` column, as a decimal string like `-12.50`.
+    Decimal(String),
+
+    /// A `YEAR` column; `0` for the zero year.
+    Year(u16),
+
+    /// A `DATE` column. Zero dates have all parts set to zero.
+    Date { year: u16, month: u8, day: u8 },
+
+    /// A `TIME` column.
+    Time {
+        negative: bool,
+        hours: u32,
+        minutes: u8,
+        seconds: u8,
+        microseconds: u32,
+    },
+
+    /// A `DATETIME` column, without a time zone.
+    DateTime {
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        microsecond: u32,
+    },
+
+    /// A `TIMESTAMP` column, as the time since the Unix epoch.
+    Timestamp { seconds: u32, microseconds: u32 },
+
+    /// A string, binary string, `BLOB` or `GEOMETRY` column.
+    Bytes(Bytes),
+
+    /// A `JSON` column, in the binary JSON format of MySQL; see
+    /// [`to_json`][MySqlBinlogValue::to_json] with the `json` feature.
+    Json(Bytes),
+
+    /// The 1-based index of the value of an `ENUM` column; `0` for the empty error value.
+    Enum(u16),
+
+    /// The bit set of the values of a `SET` column, where bit `n` is the `n`th value.
+    Set(u64),
+
+    /// A `BIT` column, in big-endian order.
+    Bit(Bytes),
+}
+
+/// Decode a value of the column type `ty`, with the metadata of the column in the table map.
+pub(crate) fn decode_value(
+    buf: &mut Bytes,
+    ty: u8,
+    meta: [u8; 2],
+    unsigned: bool,
+) -> Result<MySqlBinlogValue> {
+    let int = |value: u64, bits: u32| {
+        if unsigned {
+            MySqlBinlogValue::UInt(value)
+        } else {
+            // sign-extend from the width of the column
+            let shift = 64 - bits;
+            MySqlBinlogValue::Int(((value << shift) as i64) >> shift)
+        }
+    };
+
+    Ok(match ty {
+        TYPE_NULL => MySqlBinlogValue::Null,
+
+        TYPE_TINY => int(get_uint_le(buf, 1)?, 8),
+        TYPE_SHORT => int(get_uint_le(buf, 2)?, 16),
+        TYPE_INT24 => int(get_uint_le(buf, 3)?, 24),
+        TYPE_LONG => int(get_uint_le(buf, 4)?, 32),
+        TYPE_LONGLONG => int(get_uint_le(buf, 8)?, 64),
+
+        TYPE_FLOAT => MySqlBinlogValue::Float(f32::from_bits(get_uint_le(buf, 4)? as u32)),
+        TYPE_DOUBLE => MySqlBinlogValue::Double(f64::from_bits(get_uint_le(buf, 8)?)),
+
+        TYPE_NEWDECIMAL => MySqlBinlogValue::Decimal(decode_decimal(buf, meta[0], meta[1])?),
+
+        TYPE_YEAR => match get_u8(buf)? {
+            0 => MySqlBinlogValue::Year(0),
+            year => MySqlBinlogValue::Year(1900 + u16::from(year)),
+        },
+
+        TYPE_DATE => {
+            let value = get_uint_le(buf, 3)?;
+
+            MySqlBinlogValue::Date {
+                year: (value >> 9) as u16,
+                month: ((value >> 5) & 0x0f) as u8,
+                day: (value & 0x1f) as u8,
+            }
+        }
+
+        // the formats before MySQL 5.6.4, as `HHMMSS` and `YYYYMMDDhhmmss`
+        TYPE_TIME => {
+            let value = get_uint_le(buf, 3)? as u32;
+
+            MySqlBinlogValue::Time {
+                negative: false,
+                hours: value / 10000,
+                minutes: (value / 100 % 100) as u8,
+                seconds: (value % 100) as u8,
+                microseconds: 0,
+            }
+        }
+
+        TYPE_DATETIME => {
+            let value = get_uint_le(buf, 8)?;
+            let (date, time) = (value / 1_000_000, value % 1_000_000);
+
+            MySqlBinlogValue::DateTime {
+                year: (date / 10000) as u16,
+                month: (date / 100 % 100) as u8,
+                day: (date % 100) as u8,
+                hour: (time / 10000) as u8,
+                minute: (time / 100 % 100) as u8,
+                second: (time % 100) as u8,
+                microsecond: 0,
+            }
+        }
+
+        TYPE_TIMESTAMP => MySqlBinlogValue::Timestamp {
+            seconds: get_uint_le(buf, 4)? as u32,
+            microseconds: 0,
+        },
+
+        TYPE_TIMESTAMP2 => MySqlBinlogValue::Timestamp {
+            seconds: get_uint_be(buf, 4)? as u32,
+            microseconds: decode_fraction(buf, meta[0])?,
+        },
+
+        TYPE_DATETIME2 => {
+            // the sign bit is always set, negative values are not supported
+            let packed = get_uint_be(buf, 5)? as i64 - 0x80_0000_0000;
+            let microsecond = decode_fraction(buf, meta[0])?;
+
+            let ymd = packed >> 17;
+            let ym = ymd >> 5;
+            let hms = packed & 0x1ffff;
+
+            MySqlBinlogValue::DateTime {
+                year: (ym / 13) as u16,
+                month: (ym % 13) as u8,
+                day: (ymd & 0x1f) as u8,
+                hour: (hms >> 12) as u8,
+                minute: ((hms >> 6) & 0x3f) as u8,
+                second: (hms & 0x3f) as u8,
+                microsecond,
+            }
+        }
+
+        TYPE_TIME2 => decode_time2(buf, meta[0])?,
+
+        TYPE_VARCHAR | TYPE_VAR_STRING => {
+            let max_len = u16::from_le_bytes(meta);
+            let len = get_uint_le(buf, if max_len < 256 { 1 } else { 2 })?;
+
+            MySqlBinlogValue::Bytes(get_bytes(buf, len as usize)?)
+        }
+
+        TYPE_STRING => {
+            let (mut real_type, len) = (meta[0], meta[1]);
+
+            match real_type {
+                TYPE_ENUM => MySqlBinlogValue::Enum(get_uint_le(buf, usize::from(len))? as u16),
+                TYPE_SET => MySqlBinlogValue::Set(get_uint_le(buf, usize::from(len))?),
+                _ => {
+                    // the high bits of long `CHAR` lengths are stored inverted in the type
+                    let mut max_len = u16::from(len);
+
+                    if real_type & 0x30 != 0x30 {
+                        max_len |= u16::from((real_type & 0x30) ^ 0x30) << 4;
+                        real_type |= 0x30;
+                    }
+
+                    debug_assert_eq!(real_type, TYPE_STRING);
+
+                    let len = get_uint_le(buf, if max_len < 256 { 1 } else { 2 })?;
+
+                    MySqlBinlogValue::Bytes(get_bytes(buf, len as usize)?)
+                }
+            }
+        }
+
+        TYPE_BLOB | TYPE_TINY_BLOB | TYPE_MEDIUM_BLOB | TYPE_LONG_BLOB | TYPE_GEOMETRY => {
+            let len = get_uint_le(buf, usize::from(meta[0]))?;
+
+            MySqlBinlogValue::Bytes(get_bytes(buf, len as usize)?)
+        }
+
+        TYPE_JSON => {
+            let len = get_uint_le(buf, usize::from(meta[0]))?;
+
+            MySqlBinlogValue::Json(get_bytes(buf, len as usize)?)
+        }
+
+        TYPE_BIT => {
+            let (bits, bytes) = (meta[0], meta[1]);
+            let len = usize::from(bytes) + usize::from(bits > 0);
+
+            MySqlBinlogValue::Bit(get_bytes(buf, len)?)
+        }
+
+        _ => {
+            return Err(err_protocol!(
+                "unsupported column type 0x{:02x} in binary log row",
+                ty
+            ))
+        }
+    })
+}
+
+/// The length of the metadata of a column of type `ty` in a table map event.
+pub(crate) fn metadata_len(ty: u8) -> usize {
+    match ty {
+        TYPE_FLOAT | TYPE_DOUBLE | TYPE_BLOB | TYPE_TINY_BLOB | TYPE_MEDIUM_BLOB
+        | TYPE_LONG_BLOB | TYPE_GEOMETRY | TYPE_JSON | TYPE_TIMESTAMP2 | TYPE_DATETIME2
+        | TYPE_TIME2 => 1,
+
+        TYPE_VARCHAR | TYPE_VAR_STRING | TYPE_BIT | TYPE_NEWDECIMAL | TYPE_STRING | TYPE_ENUM
+        | TYPE_SET => 2,
+
+        _ => 0,
+    }
+}
+
+/// Whether the signedness of columns of type `ty` is included in the optional metadata of a
+/// table map event.
+pub(crate) fn is_numeric(ty: u8) -> bool {
+    matches!(
+        ty,
+        TYPE_TINY
+            | TYPE_SHORT
+            | TYPE_INT24
+            | TYPE_LONG
+            | TYPE_LONGLONG
+            | TYPE_FLOAT
+            | TYPE_DOUBLE
+            | TYPE_DECIMAL
+            | TYPE_NEWDECIMAL
+    )
+}
+
+// https://github.com/mysql/mysql-server/blob/8.0/strings/decimal.cc, `bin2decimal()`
+fn decode_decimal(buf: &mut Bytes, precision: u8, scale: u8) -> Result<String> {
+    let (precision, scale) = (usize::from(precision), usize::from(scale));
+
+    if scale > precision {
+        return Err(err_protocol!(
+            "invalid DECIMAL({}, {}) in binary log",
+            precision,
+            scale
+        ));
+    }
+
+    let intg = precision - scale;
+    let (intg0, intg0x) = (intg / 9, intg % 9);
+    let (frac0, frac0x) = (scale / 9, scale % 9);
+
+    let size = intg0 * 4 + DIG2BYTES[intg0x] + frac0 * 4 + DIG2BYTES[frac0x];
+    let mut data = get_bytes(buf, size)?.to_vec();
+
+    if data.is_empty() {
+        return Err(err_protocol!("empty DECIMAL in binary log"));
+    }
+
+    // the sign bit is set for positive values, and negative ones have all bits inverted
+    let negative = data[0] & 0x80 == 0;
+    data[0] ^= 0x80;
+
+    if negative {
+        data.iter_mut().for_each(|b| *b ^= 0xff);
+    }
+
+    let mut data = Bytes::from(data);
+    let mut digits = |buf: &mut String, len: usize, width: usize| -> Result<()> {
+        let value = get_uint_be(&mut data, len)?;
+        buf.push_str(&format!("{value:0width$}"));
+        Ok(())
+    };
+
+    let mut int_part = String::new();
+
+    if intg0x > 0 {
+        digits(&mut int_part, DIG2BYTES[intg0x], intg0x)?;
+    }
+
+    for _ in 0..intg0 {
+        digits(&mut int_part, 4, 9)?;
+    }
+
+    let mut frac_part = String::new();
+
+    for _ in 0..frac0 {
+        digits(&mut frac_part, 4, 9)?;
+    }
+
+    if frac0x > 0 {
+        digits(&mut frac_part, DIG2BYTES[frac0x], frac0x)?;
+    }
+
+    let int_part = match int_part.trim_start_matches('0') {
+        "" => "0",
+        int_part => int_part,
+    };
+
+    let mut value = String::new();
+
+    if negative {
+        value.push('-');
+    }
+
+    value.push_str(int_part);
+
+    if !frac_part.is_empty() {
+        value.push('.');
+        value.push_str(&frac_part);
+    }
+
+    Ok(value)
+}
+
+/// Decode the fractional seconds of a temporal type with `fsp` digits, as microseconds.
+fn decode_fraction(buf: &mut Bytes, fsp: u8) -> Result<u32> {
+    Ok(match fsp {
+        0 => 0,
+        1 | 2 => get_uint_be(buf, 1)? as u32 * 10000,
+        3 | 4 => get_uint_be(buf, 2)? as u32 * 100,
+        _ => get_uint_be(buf, 3)? as u32,
+    })
+}
+
+// https://github.com/mysql/mysql-server/blob/8.0/mysys/my_time.cc, `my_time_packed_from_binary()`
+fn decode_time2(buf: &mut Bytes, fsp: u8) -> Result<MySqlBinlogValue> {
+    const INT_OFFSET: i64 = 0x80_0000;
+
+    let packed = match fsp {
+        0 => (get_uint_be(buf, 3)? as i64 - INT_OFFSET) << 24,
+        1 | 2 => {
+            let mut int_part = get_uint_be(buf, 3)? as i64 - INT_OFFSET;
+            let mut frac = i64::from(get_u8(buf)? as i8);
+
+            if int_part < 0 && frac != 0 {
+                int_part += 1;
+                frac -= 0x100;
+            }
+
+            (int_part << 24) + frac * 10000
+        }
+        3 | 4 => {
+            let mut int_part = get_uint_be(buf, 3)? as i64 - INT_OFFSET;
+            let mut frac = get_uint_be(buf, 2)? as i64;
+
+            if int_part < 0 && frac != 0 {
+                int_part += 1;
+                frac -= 0x10000;
+            }
+
+            (int_part << 24) + frac * 100
+        }
+        _ => get_uint_be(buf, 6)? as i64 - 0x8000_0000_0000,
+    };
+
+    let negative = packed < 0;
+    let packed = packed.unsigned_abs();
+
+    let hms = packed >> 24;
+
+    Ok(MySqlBinlogValue::Time {
+        negative,
+        hours: ((hms >> 12) & 0x3ff) as u32,
+        minutes: ((hms >> 6) & 0x3f) as u8,
+        seconds: (hms & 0x3f) as u8,
+        microseconds: (packed & 0xff_ffff) as u32,
+    })
+}
+
+impl MySqlBinlogValue {
+    /// Returns `true` for [`Null`][Self::Null].
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Decodes a [`Json`][Self::Json] value from the binary JSON format; `None` for any other
+    /// value.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Option<Result<crate::types::JsonValue>> {
+        match self {
+            Self::Json(buf) => Some(crate::types::json_binary::decode_binary_json(buf)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_decode_decimal() {
+    // DECIMAL(11, 4): 1234567.8901 and -1234567.8901
+    let mut buf = Bytes::from_static(&[0x80, 0x12, 0xd6, 0x87, 0x22, 0xc5]);
+    assert_eq!(decode_decimal(&mut buf, 11, 4).unwrap(), "1234567.8901");
+
+    let mut buf = Bytes::from_static(&[0x7f, 0xed, 0x29, 0x78, 0xdd, 0x3a]);
+    assert_eq!(decode_decimal(&mut buf, 11, 4).unwrap(), "-1234567.8901");
+
+    // DECIMAL(5, 2): 0.50
+    let mut buf = Bytes::from_static(&[0x80, 0x00, 0x32]);
+    assert_eq!(decode_decimal(&mut buf, 5, 2).unwrap(), "0.50");
+}
+
+#[test]
+fn test_decode_temporal() {
+    // DATETIME(3): 2021-06-15 13:45:30.123
+    let mut buf = Bytes::from_static(&[0x99, 0xa9, 0xde, 0xdb, 0x5e, 0x04, 0xce]);
+    assert_eq!(
+        decode_value(&mut buf, TYPE_DATETIME2, [3, 0], false).unwrap(),
+        MySqlBinlogValue::DateTime {
+            year: 2021,
+            month: 6,
+            day: 15,
+            hour: 13,
+            minute: 45,
+            second: 30,
+            microsecond: 123_000,
+        }
+    );
+
+    // TIME(0): -01:02:03
+    let mut buf = Bytes::from_static(&[0x7f, 0xef, 0x7d]);
+    assert_eq!(
+        decode_value(&mut buf, TYPE_TIME2, [0, 0], false).unwrap(),
+        MySqlBinlogValue::Time {
+            negative: true,
+            hours: 1,
+            minutes: 2,
+            seconds: 3,
+            microseconds: 0,
+        }
+    );
+
+    // DATE: 2021-06-15
+    let mut buf = Bytes::from_static(&[0xcf, 0xca, 0x0f]);
+    assert_eq!(
+        decode_value(&mut buf, TYPE_DATE, [0, 0], false).unwrap(),
+        MySqlBinlogValue::Date {
+            year: 2021,
+            month: 6,
+            day: 15,
+        }
+    );
+}
+
+#[test]
+fn test_decode_integers() {
+    let mut buf = Bytes::from_static(&[0xc8, 0xc8]);
+    assert_eq!(
+        decode_value(&mut buf, TYPE_TINY, [0, 0], false).unwrap(),
+        MySqlBinlogValue::Int(-56)
+    );
+    assert_eq!(
+        decode_value(&mut buf, TYPE_TINY, [0, 0], true).unwrap(),
+        MySqlBinlogValue::UInt(200)
+    );
+
+    let mut buf = Bytes::from_static(&[0xff, 0xff, 0x7f]);
+    assert_eq!(
+        decode_value(&mut buf, TYPE_INT24, [0, 0], false).unwrap(),
+        MySqlBinlogValue::Int(8_388_607)
+    );
+}
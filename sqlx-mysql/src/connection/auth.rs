@@ -1,12 +1,18 @@
 use bytes::buf::Chain;
 use bytes::Bytes;
+use curve25519_dalek::scalar::{clamp_integer, Scalar};
+use curve25519_dalek::EdwardsPoint;
 use digest::{Digest, OutputSizeUser};
 use generic_array::GenericArray;
 use rand::thread_rng;
 use rsa::{pkcs8::DecodePublicKey, Oaep, RsaPublicKey};
 use sha1::Sha1;
-use sha2::Sha256;
+use sha2::{Sha256, Sha512};
 
+#[cfg(feature = "gssapi")]
+use crate::connection::gssapi;
+#[cfg(feature = "ldap-sasl")]
+use crate::connection::sasl;
 use crate::connection::stream::MySqlStream;
 use crate::error::Error;
 use crate::protocol::auth::AuthPlugin;
@@ -33,6 +39,58 @@ impl AuthPlugin {
                 pw_bytes.push(0); // null terminate
                 Ok(pw_bytes)
             }
+
+            // https://mariadb.com/kb/en/authentication-plugin-ed25519/
+            AuthPlugin::Ed25519 => Ok(sign_ed25519(password, nonce).to_vec()),
+
+            // these start with a message of the server, see `exchange`
+            AuthPlugin::LdapSasl | AuthPlugin::Kerberos => Ok(Vec::new()),
+        }
+    }
+
+    /// Run the exchange of a plugin that needs several round trips, after the server switched
+    /// to it with `data` as its first message.
+    ///
+    /// Returns `false` for plugins that answer the switch with [`scramble`][Self::scramble].
+    #[cfg_attr(
+        not(all(feature = "ldap-sasl", feature = "gssapi")),
+        allow(unused_variables)
+    )]
+    pub(super) async fn exchange(
+        self,
+        stream: &mut MySqlStream,
+        data: &Bytes,
+        username: &str,
+        password: &str,
+    ) -> Result<bool, Error> {
+        match self {
+            AuthPlugin::LdapSasl => {
+                #[cfg(feature = "ldap-sasl")]
+                return sasl::authenticate(stream, data, username, password)
+                    .await
+                    .map(|()| true);
+
+                #[cfg(not(feature = "ldap-sasl"))]
+                return Err(err_protocol!(
+                    "the server requested the authentication_ldap_sasl_client plugin, \
+                     which requires the `mysql-ldap-sasl` feature"
+                ));
+            }
+
+            AuthPlugin::Kerberos => {
+                #[cfg(feature = "gssapi")]
+                return gssapi::authenticate(stream, data.clone())
+                    .await
+                    .map(|()| true);
+
+                #[cfg(not(feature = "gssapi"))]
+                return Err(err_protocol!(
+                    "the server requested the authentication_kerberos_client plugin, \
+                     which requires the `mysql-gssapi` feature"
+                ));
+            }
+
+            _ => Ok(false),
         }
     }
 
@@ -74,6 +132,23 @@ impl AuthPlugin {
     }
 }
 
+/// Receive the data of the next `AuthMoreData` packet of a multi-step plugin.
+#[cfg(any(feature = "ldap-sasl", feature = "gssapi"))]
+pub(super) async fn recv_auth_more_data(stream: &mut MySqlStream) -> Result<Bytes, Error> {
+    let mut packet = stream.recv_packet().await?.0;
+
+    if packet.first() != Some(&0x01) {
+        return Err(err_protocol!(
+            "expected 0x01 (AuthMoreData) but found {:?} during authentication",
+            packet.first()
+        ));
+    }
+
+    let _ = packet.split_to(1);
+
+    Ok(packet)
+}
+
 fn scramble_sha1(
     password: &str,
     nonce: &Chain<Bytes, Bytes>,
@@ -129,6 +204,44 @@ fn scramble_sha256(
     pw_hash
 }
 
+fn sign_ed25519(password: &str, nonce: &Chain<Bytes, Bytes>) -> [u8; 64] {
+    // an Ed25519 signature of the nonce, but with SHA512( password ) as the expanded secret key,
+    // where Ed25519 itself hashes a 32-byte seed
+    // https://github.com/MariaDB/server/blob/10.11/plugin/auth_ed25519/ref10/sign.c
+
+    let hash = Sha512::digest(password);
+    let (secret, prefix) = hash.split_at(32);
+
+    let a = Scalar::from_bytes_mod_order(clamp_integer(secret.try_into().unwrap()));
+    let public_key = EdwardsPoint::mul_base(&a).compress();
+
+    let r = scalar_from_sha512(&[prefix, nonce.first_ref(), nonce.last_ref()]);
+    let r_point = EdwardsPoint::mul_base(&r).compress();
+
+    let k = scalar_from_sha512(&[
+        r_point.as_bytes(),
+        public_key.as_bytes(),
+        nonce.first_ref(),
+        nonce.last_ref(),
+    ]);
+
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(r_point.as_bytes());
+    signature[32..].copy_from_slice((k * a + r).as_bytes());
+
+    signature
+}
+
+fn scalar_from_sha512(data: &[&[u8]]) -> Scalar {
+    let mut ctx = Sha512::new();
+
+    for data in data {
+        ctx.update(data);
+    }
+
+    Scalar::from_bytes_mod_order_wide(&ctx.finalize().into())
+}
+
 async fn encrypt_rsa<'s>(
     stream: &'s mut MySqlStream,
     public_key_request_id: u8,
@@ -195,3 +308,18 @@ fn parse_rsa_pub_key(key: &[u8]) -> Result<RsaPublicKey, Error> {
 
     RsaPublicKey::from_public_key_pem(&pem).map_err(Error::protocol)
 }
+
+#[test]
+fn test_sign_ed25519() {
+    use bytes::Buf;
+
+    // with a 32-byte password, this is a plain Ed25519 signature with the password as seed
+    let nonce = Bytes::from_static(b"abcdefghijabcdefghijabcdefghij01").chain(Bytes::new());
+    let signature = sign_ed25519("correct horse battery staple 32b", &nonce);
+
+    assert_eq!(
+        hex::encode(signature),
+        "5f12fc6cbbbcbdc872b54d50500ba84f75bb3c4f2c210360157d52b688678d8c\
+         a5c9b2b1d4c7cc781ff0e0e2deffe6ea0328c27c43b152ee529575250d4f5708"
+    );
+}
@@ -0,0 +1,87 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::connection::Connection;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::{MySqlConnectOptions, MySqlConnection};
+
+impl MySqlConnection {
+    /// The id of this connection on the server, as returned by `CONNECTION_ID()` and listed
+    /// by `SHOW PROCESSLIST`.
+    pub fn connection_id(&self) -> u32 {
+        self.stream.connection_id
+    }
+
+    /// Returns a token that cancels the query running on this connection, from another task.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let token = conn.cancel_token();
+    ///
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_secs(5)).await;
+    ///     token.cancel().await
+    /// });
+    ///
+    /// // fails with `ER_QUERY_INTERRUPTED` (1317) after 5 seconds
+    /// let res = sqlx::query("SELECT COUNT(*) FROM huge_table").execute(conn).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cancel_token(&self) -> MySqlCancellationToken {
+        MySqlCancellationToken {
+            options: Arc::clone(&self.options),
+            connection_id: self.stream.connection_id,
+        }
+    }
+}
+
+/// Cancels the query running on a [`MySqlConnection`], created by
+/// [`MySqlConnection::cancel_token`].
+///
+/// The connection itself stays open: the query fails with an `ER_QUERY_INTERRUPTED` error, and
+/// the connection can be used for the next query. `SLEEP()` is an exception, which returns 1
+/// when it is interrupted.
+#[derive(Clone)]
+pub struct MySqlCancellationToken {
+    options: Arc<MySqlConnectOptions>,
+    connection_id: u32,
+}
+
+impl MySqlCancellationToken {
+    /// The id of the connection on the server.
+    pub fn connection_id(&self) -> u32 {
+        self.connection_id
+    }
+
+    /// Ask the server to cancel the query that is running on the connection, with
+    /// `KILL QUERY`.
+    ///
+    /// This opens a separate connection to the server with the same options, to send the
+    /// statement, which requires the same user as the connection or the `CONNECTION_ADMIN`
+    /// privilege. If no query is running, nothing happens, and the query may also complete
+    /// before the statement is processed.
+    ///
+    /// Note that a query is cancelled even if it is not the one that was running when the
+    /// token was created.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let mut conn = MySqlConnection::establish(&self.options).await?;
+
+        conn.execute(&*format!("KILL QUERY {}", self.connection_id))
+            .await?;
+
+        conn.close().await
+    }
+}
+
+impl Debug for MySqlCancellationToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // the options are left out on purpose
+        f.debug_struct("MySqlCancellationToken")
+            .field("connection_id", &self.connection_id)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,326 @@
+// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_basic_compression.html
+// https://mariadb.com/kb/en/0-packet/#compressed-packet
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::error::Error;
+use crate::io::Encode;
+use crate::net::{BufferedSocket, Socket};
+use crate::protocol::Capabilities;
+use crate::MySqlCompression;
+
+/// Payloads smaller than this are not worth compressing and are sent as-is,
+/// same as the server does.
+const MIN_COMPRESS_LENGTH: usize = 50;
+
+/// The largest payload of a single compressed packet, before compression.
+const MAX_PAYLOAD_LENGTH: usize = 0xFF_FF_FF;
+
+/// The zstd compression level which is requested from the server and used by the client.
+///
+/// This is the default level of the MySQL client.
+pub(crate) const ZSTD_COMPRESSION_LEVEL: u8 = 3;
+
+/// State of the compressed protocol, which wraps all packets sent and received
+/// once the connection is authenticated.
+pub(crate) struct Compression {
+    codec: Codec,
+
+    // the sequence id of compressed packets, which is tracked apart from the
+    // sequence id of the packets they contain
+    pub(crate) sequence_id: u8,
+
+    // uncompressed packets waiting to be compressed
+    write_buf: Vec<u8>,
+
+    // decompressed data which was not read yet
+    read_buf: BytesMut,
+}
+
+enum Codec {
+    #[cfg(feature = "compression-zlib")]
+    Zlib {
+        compress: flate2::Compress,
+        decompress: flate2::Decompress,
+    },
+
+    #[cfg(feature = "compression-zstd")]
+    Zstd {
+        compress: zstd::bulk::Compressor<'static>,
+        decompress: zstd::bulk::Decompressor<'static>,
+    },
+}
+
+impl MySqlCompression {
+    pub(crate) fn capabilities(self) -> Capabilities {
+        match self {
+            MySqlCompression::Disabled => Capabilities::empty(),
+            MySqlCompression::Zlib => Capabilities::COMPRESS,
+            MySqlCompression::Zstd => Capabilities::ZSTD_COMPRESSION_ALGORITHM,
+        }
+    }
+
+    pub(crate) fn error_if_unavailable(self) -> Result<(), Error> {
+        let available = match self {
+            MySqlCompression::Disabled => true,
+            MySqlCompression::Zlib => cfg!(feature = "compression-zlib"),
+            MySqlCompression::Zstd => cfg!(feature = "compression-zstd"),
+        };
+
+        if !available {
+            return Err(Error::Configuration(
+                format!("{self:?} compression support not compiled in").into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Compression {
+    /// Set up the compressed protocol for the algorithm that was negotiated with the server,
+    /// if any.
+    pub(crate) fn negotiated(capabilities: Capabilities) -> Result<Option<Self>, Error> {
+        Ok(Codec::negotiated(capabilities)?.map(Self::new))
+    }
+
+    fn new(codec: Codec) -> Self {
+        Self {
+            codec,
+            sequence_id: 0,
+            write_buf: Vec::new(),
+            read_buf: BytesMut::new(),
+        }
+    }
+
+    /// Write a packet to the write buffer of `socket`, wrapped in one or more compressed packets.
+    pub(crate) fn write_packet<'en, S: Socket, T, C>(
+        &mut self,
+        socket: &mut BufferedSocket<S>,
+        packet: T,
+        context: C,
+    ) where
+        T: Encode<'en, C>,
+    {
+        self.write_buf.clear();
+        packet.encode_with(&mut self.write_buf, context);
+
+        socket.write_with(
+            CompressedPackets(&self.write_buf),
+            (&mut self.codec, &mut self.sequence_id),
+        );
+    }
+
+    /// Read `len` bytes of decompressed data, receiving more compressed packets as needed.
+    pub(crate) async fn read<S: Socket>(
+        &mut self,
+        socket: &mut BufferedSocket<S>,
+        len: usize,
+    ) -> Result<Bytes, Error> {
+        while self.read_buf.len() < len {
+            self.recv_compressed_packet(socket).await?;
+        }
+
+        Ok(self.read_buf.split_to(len).freeze())
+    }
+
+    async fn recv_compressed_packet<S: Socket>(
+        &mut self,
+        socket: &mut BufferedSocket<S>,
+    ) -> Result<(), Error> {
+        let mut header: Bytes = socket.read(7).await?;
+
+        let compressed_len = header.get_uint_le(3) as usize;
+        let sequence_id = header.get_u8();
+        let uncompressed_len = header.get_uint_le(3) as usize;
+
+        self.sequence_id = sequence_id.wrapping_add(1);
+
+        let payload: Bytes = socket.read(compressed_len).await?;
+
+        // a length of 0 signals a payload that was sent without compressing it
+        if uncompressed_len == 0 {
+            self.read_buf.extend_from_slice(&payload);
+            return Ok(());
+        }
+
+        let offset = self.read_buf.len();
+        self.read_buf.resize(offset + uncompressed_len, 0);
+
+        let decompressed = self
+            .codec
+            .decompress(&payload, &mut self.read_buf[offset..]);
+
+        if decompressed != Some(uncompressed_len) {
+            return Err(err_protocol!(
+                "failed to decompress packet of {} bytes to the expected {} bytes",
+                compressed_len,
+                uncompressed_len
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// without any compression features, there are no codecs to use the arguments
+#[cfg_attr(
+    not(any(feature = "compression-zlib", feature = "compression-zstd")),
+    allow(unused_variables)
+)]
+impl Codec {
+    fn negotiated(capabilities: Capabilities) -> Result<Option<Self>, Error> {
+        #[cfg(feature = "compression-zstd")]
+        if capabilities.contains(Capabilities::ZSTD_COMPRESSION_ALGORITHM) {
+            return Ok(Some(Codec::Zstd {
+                compress: zstd::bulk::Compressor::new(ZSTD_COMPRESSION_LEVEL.into())?,
+                decompress: zstd::bulk::Decompressor::new()?,
+            }));
+        }
+
+        #[cfg(feature = "compression-zlib")]
+        if capabilities.contains(Capabilities::COMPRESS) {
+            return Ok(Some(Codec::Zlib {
+                compress: flate2::Compress::new(flate2::Compression::default(), true),
+                decompress: flate2::Decompress::new(true),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Compress `input` into `output`, returning the compressed length.
+    ///
+    /// Returns `None` if the compressed data does not fit in `output`.
+    fn compress(&mut self, input: &[u8], output: &mut [u8]) -> Option<usize> {
+        match *self {
+            #[cfg(feature = "compression-zlib")]
+            Codec::Zlib {
+                ref mut compress, ..
+            } => {
+                compress.reset();
+
+                let status = compress
+                    .compress(input, output, flate2::FlushCompress::Finish)
+                    .ok()?;
+
+                (status == flate2::Status::StreamEnd).then(|| compress.total_out() as usize)
+            }
+
+            #[cfg(feature = "compression-zstd")]
+            Codec::Zstd {
+                ref mut compress, ..
+            } => compress.compress_to_buffer(input, output).ok(),
+        }
+    }
+
+    /// Decompress `input` into `output`, returning the decompressed length.
+    fn decompress(&mut self, input: &[u8], output: &mut [u8]) -> Option<usize> {
+        match *self {
+            #[cfg(feature = "compression-zlib")]
+            Codec::Zlib {
+                ref mut decompress, ..
+            } => {
+                decompress.reset(true);
+
+                let status = decompress
+                    .decompress(input, output, flate2::FlushDecompress::Finish)
+                    .ok()?;
+
+                (status == flate2::Status::StreamEnd).then(|| decompress.total_out() as usize)
+            }
+
+            #[cfg(feature = "compression-zstd")]
+            Codec::Zstd {
+                ref mut decompress, ..
+            } => decompress.decompress_to_buffer(input, output).ok(),
+        }
+    }
+}
+
+struct CompressedPackets<'a>(&'a [u8]);
+
+impl Encode<'_, (&'_ mut Codec, &'_ mut u8)> for CompressedPackets<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, (codec, sequence_id): (&mut Codec, &mut u8)) {
+        for chunk in self.0.chunks(MAX_PAYLOAD_LENGTH) {
+            // reserve space to write the header
+            let offset = buf.len();
+            buf.extend(&[0_u8; 7]);
+
+            let mut uncompressed_len = 0;
+
+            if chunk.len() >= MIN_COMPRESS_LENGTH {
+                // only keep the compressed data if it is smaller than the original
+                buf.resize(offset + 7 + chunk.len() - 1, 0);
+
+                match codec.compress(chunk, &mut buf[offset + 7..]) {
+                    Some(len) => {
+                        buf.truncate(offset + 7 + len);
+                        uncompressed_len = chunk.len();
+                    }
+
+                    None => buf.truncate(offset + 7),
+                }
+            }
+
+            if uncompressed_len == 0 {
+                buf.extend_from_slice(chunk);
+            }
+
+            let compressed_len = buf.len() - offset - 7;
+            let header = &mut buf[offset..];
+
+            header[..3].copy_from_slice(&(compressed_len as u32).to_le_bytes()[..3]);
+            header[3] = *sequence_id;
+            header[4..7].copy_from_slice(&(uncompressed_len as u32).to_le_bytes()[..3]);
+
+            *sequence_id = sequence_id.wrapping_add(1);
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "compression-zlib", feature = "compression-zstd")))]
+fn assert_round_trip(capabilities: Capabilities) {
+    let mut codec = Codec::negotiated(capabilities).unwrap().unwrap();
+    let mut sequence_id = 0;
+    let mut buf = Vec::new();
+
+    let payload = b"SELECT 1, 2, 3 UNION ALL ".repeat(100);
+    CompressedPackets(&payload).encode_with(&mut buf, (&mut codec, &mut sequence_id));
+
+    let compressed_len = u32::from_le_bytes([buf[0], buf[1], buf[2], 0]) as usize;
+    let uncompressed_len = u32::from_le_bytes([buf[4], buf[5], buf[6], 0]) as usize;
+
+    assert_eq!(compressed_len, buf.len() - 7);
+    assert!(compressed_len < payload.len());
+    assert_eq!(buf[3], 0);
+    assert_eq!(uncompressed_len, payload.len());
+    assert_eq!(sequence_id, 1);
+
+    let mut decompressed = vec![0; uncompressed_len];
+    assert_eq!(
+        codec.decompress(&buf[7..], &mut decompressed),
+        Some(payload.len())
+    );
+    assert_eq!(decompressed, payload);
+
+    // small payloads are sent without compressing them
+    buf.clear();
+    CompressedPackets(b"\x01\x00\x00\x00\x0e")
+        .encode_with(&mut buf, (&mut codec, &mut sequence_id));
+
+    assert_eq!(buf, b"\x05\x00\x00\x01\x00\x00\x00\x01\x00\x00\x00\x0e");
+    assert_eq!(sequence_id, 2);
+}
+
+#[cfg(feature = "compression-zlib")]
+#[test]
+fn test_zlib_compressed_packets() {
+    assert_round_trip(Capabilities::COMPRESS);
+}
+
+#[cfg(feature = "compression-zstd")]
+#[test]
+fn test_zstd_compressed_packets() {
+    assert_round_trip(Capabilities::ZSTD_COMPRESSION_ALGORITHM);
+}
@@ -1,25 +1,34 @@
-use bytes::buf::Buf;
+use std::sync::Arc;
+
+use bytes::buf::{Buf, Chain};
 use bytes::Bytes;
 use futures_core::future::BoxFuture;
 
 use crate::collation::{CharSet, Collation};
 use crate::common::StatementCache;
-use crate::connection::{tls, MySqlStream, MAX_PACKET_SIZE};
+use crate::connection::compression::{Compression, ZSTD_COMPRESSION_LEVEL};
+use crate::connection::infile::MAX_MYSQL_PACKET_SIZE;
+use crate::connection::{tls, MySqlSessionState, MySqlStream, MAX_PACKET_SIZE};
 use crate::error::Error;
 use crate::net::{Socket, WithSocket};
+use crate::protocol::auth::AuthPlugin;
 use crate::protocol::connect::{
     AuthSwitchRequest, AuthSwitchResponse, Handshake, HandshakeResponse,
 };
+use crate::protocol::text::ChangeUser;
 use crate::protocol::Capabilities;
-use crate::{MySqlConnectOptions, MySqlConnection, MySqlSslMode};
+use crate::{MySqlCompression, MySqlConnectOptions, MySqlConnection, MySqlSslMode};
 
 impl MySqlConnection {
     pub(crate) async fn establish(options: &MySqlConnectOptions) -> Result<Self, Error> {
         let do_handshake = DoHandshake::new(options)?;
 
-        let handshake = match &options.socket {
-            Some(path) => crate::net::connect_uds(path, do_handshake).await?,
-            None => crate::net::connect_tcp(&options.host, options.port, do_handshake).await?,
+        let handshake = match (&options.pipe, &options.socket) {
+            (Some(name), _) => crate::net::connect_named_pipe(name, do_handshake).await?,
+            (None, Some(path)) => crate::net::connect_uds(path, do_handshake).await?,
+            (None, None) => {
+                crate::net::connect_tcp(&options.host, options.port, do_handshake).await?
+            }
         };
 
         let stream = handshake.await?;
@@ -29,8 +38,83 @@ impl MySqlConnection {
             transaction_depth: 0,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             log_settings: options.log_settings.clone(),
+            local_infile_handler: options.local_infile_handler.clone(),
+            options: Arc::new(options.clone()),
+            fetch_warnings: options.fetch_warnings,
+            // recorded once the connection is configured
+            session_setup: None,
+            // updated once the connection is configured
+            max_allowed_packet: MAX_MYSQL_PACKET_SIZE,
+            response_received: false,
         })
     }
+
+    /// Authenticate this connection as the user of `options`, with `COM_CHANGE_USER`.
+    ///
+    /// This resets the session as if the connection was opened for the user: the user
+    /// variables, temporary tables and prepared statements are dropped, and an open transaction
+    /// is rolled back. Only the user, password, database and character set of `options` are
+    /// used; the connection keeps its host, TLS and compression.
+    ///
+    /// If the server rejects the user, the connection is closed.
+    pub async fn change_user(&mut self, options: &MySqlConnectOptions) -> Result<(), Error> {
+        let DoHandshake {
+            charset, collation, ..
+        } = DoHandshake::new(options)?;
+
+        self.stream.wait_until_ready().await?;
+
+        let stream = &mut self.stream;
+
+        stream.charset = charset;
+        stream.collation = collation;
+
+        // the server expects the response to the nonce of the initial handshake
+        let plugin = stream.auth_plugin;
+        let nonce = stream
+            .auth_nonce
+            .first_ref()
+            .clone()
+            .chain(stream.auth_nonce.last_ref().clone());
+
+        let auth_response = if let (Some(plugin), Some(password)) = (plugin, &options.password) {
+            Some(plugin.scramble(stream, password, &nonce).await?)
+        } else {
+            None
+        };
+
+        stream.reset_sequence_id();
+        stream.write_packet(ChangeUser {
+            username: &options.username,
+            auth_response: auth_response.as_deref(),
+            database: options.database.as_deref(),
+            collation: stream.collation as u8,
+            auth_plugin: plugin,
+            connect_attributes: &options.all_connect_attributes(),
+        });
+
+        stream.flush().await?;
+
+        // a cancellation has to be sent by the new user
+        let token_options = Arc::make_mut(&mut self.options);
+        token_options.username = options.username.clone();
+        token_options.password = options.password.clone();
+        token_options.database = options.database.clone();
+
+        // the statements were closed by the server
+        self.cache_statement.clear();
+        self.transaction_depth = 0;
+        self.stream.session_state = MySqlSessionState::default();
+
+        authenticate(&mut self.stream, options, plugin, &nonce).await?;
+
+        // the session setup of the previous user was reset as well
+        self.session_setup = None;
+        self.configure(options).await?;
+        self.start_session_setup();
+
+        Ok(())
+    }
 }
 
 struct DoHandshake<'a> {
@@ -58,6 +142,8 @@ impl<'a> DoHandshake<'a> {
             log::warn!("Security warning: sending cleartext passwords without requiring SSL");
         }
 
+        options.compression.error_if_unavailable()?;
+
         Ok(Self {
             options,
             charset,
@@ -79,7 +165,7 @@ impl<'a> DoHandshake<'a> {
 
         let handshake: Handshake = stream.recv_packet().await?.decode()?;
 
-        let mut plugin = handshake.auth_plugin;
+        let plugin = handshake.auth_plugin;
         let nonce = handshake.auth_plugin_data;
 
         // FIXME: server version parse is a bit ugly
@@ -129,59 +215,105 @@ impl<'a> DoHandshake<'a> {
             database: options.database.as_deref(),
             auth_plugin: plugin,
             auth_response: auth_response.as_deref(),
+            connect_attributes: &options.all_connect_attributes(),
+            zstd_compression_level: ZSTD_COMPRESSION_LEVEL,
         });
 
         stream.flush().await?;
 
-        loop {
-            let packet = stream.recv_packet().await?;
-            match packet[0] {
-                0x00 => {
-                    let _ok = packet.ok()?;
+        authenticate(&mut stream, options, plugin, &nonce).await?;
 
-                    break;
-                }
+        stream.connection_id = handshake.connection_id;
+        stream.auth_plugin = handshake.auth_plugin;
+        stream.auth_nonce = nonce;
 
-                0xfe => {
-                    let switch: AuthSwitchRequest =
-                        packet.decode_with(self.options.enable_cleartext_plugin)?;
+        // all packets after authentication use the compressed protocol, if negotiated
+        stream.compression = Compression::negotiated(stream.capabilities)?;
 
-                    plugin = Some(switch.plugin);
-                    let nonce = switch.data.chain(Bytes::new());
+        if stream.compression.is_none() && options.compression != MySqlCompression::Disabled {
+            tracing::debug!(
+                "not compressing the connection: {:?} compression unsupported by server",
+                options.compression
+            );
+        }
+
+        Ok(stream)
+    }
+}
 
-                    let response = switch
-                        .plugin
-                        .scramble(
-                            &mut stream,
-                            options.password.as_deref().unwrap_or_default(),
-                            &nonce,
-                        )
-                        .await?;
+// answer the authentication requests of the server after a handshake response or a
+// `COM_CHANGE_USER`, until the server accepts or rejects the user
+async fn authenticate(
+    stream: &mut MySqlStream,
+    options: &MySqlConnectOptions,
+    mut plugin: Option<AuthPlugin>,
+    nonce: &Chain<Bytes, Bytes>,
+) -> Result<(), Error> {
+    loop {
+        let packet = stream.recv_packet().await?;
+        match packet[0] {
+            0x00 => {
+                let ok = packet.ok()?;
+                stream.session_state.apply(ok.session_state)?;
+                stream.status = ok.status;
+
+                break;
+            }
 
-                    stream.write_packet(AuthSwitchResponse(response));
-                    stream.flush().await?;
+            0xfe => {
+                let switch: AuthSwitchRequest =
+                    packet.decode_with(options.enable_cleartext_plugin)?;
+
+                plugin = Some(switch.plugin);
+
+                // the server answers with OK or ERR once the exchange is done
+                if switch
+                    .plugin
+                    .exchange(
+                        stream,
+                        &switch.data,
+                        &options.username,
+                        options.password.as_deref().unwrap_or_default(),
+                    )
+                    .await?
+                {
+                    continue;
                 }
 
-                id => {
-                    if let (Some(plugin), Some(password)) = (plugin, &options.password) {
-                        if plugin.handle(&mut stream, packet, password, &nonce).await? {
-                            // plugin signaled authentication is ok
-                            break;
-                        }
-
-                        // plugin signaled to continue authentication
-                    } else {
-                        return Err(err_protocol!(
-                            "unexpected packet 0x{:02x} during authentication",
-                            id
-                        ));
+                let nonce = switch.data.chain(Bytes::new());
+
+                let response = switch
+                    .plugin
+                    .scramble(
+                        stream,
+                        options.password.as_deref().unwrap_or_default(),
+                        &nonce,
+                    )
+                    .await?;
+
+                stream.write_packet(AuthSwitchResponse(response));
+                stream.flush().await?;
+            }
+
+            id => {
+                if let (Some(plugin), Some(password)) = (plugin, &options.password) {
+                    if plugin.handle(stream, packet, password, nonce).await? {
+                        // plugin signaled authentication is ok
+                        break;
                     }
+
+                    // plugin signaled to continue authentication
+                } else {
+                    return Err(err_protocol!(
+                        "unexpected packet 0x{:02x} during authentication",
+                        id
+                    ));
                 }
             }
         }
-
-        Ok(stream)
     }
+
+    Ok(())
 }
 
 impl<'a> WithSocket for DoHandshake<'a> {
@@ -5,24 +5,34 @@ use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::ext::ustr::UStr;
 use crate::io::MySqlBufExt;
+use crate::io::{AsyncRead, AsyncReadExt};
 use crate::logger::QueryLogger;
 use crate::protocol::response::Status;
 use crate::protocol::statement::{
-    BinaryRow, Execute as StatementExecute, Prepare, PrepareOk, StmtClose,
+    BinaryRow, Execute as StatementExecute, Prepare, PrepareOk, SendLongData, StmtClose, StmtFetch,
+    StmtReset,
 };
 use crate::protocol::text::{ColumnDefinition, ColumnFlags, Query, TextRow};
+use crate::protocol::Capabilities;
+use crate::row::Row;
 use crate::statement::{MySqlStatement, MySqlStatementMetadata};
 use crate::HashMap;
 use crate::{
     MySql, MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlRow, MySqlTypeInfo,
-    MySqlValueFormat,
+    MySqlValueFormat, MySqlWarning, MySqlWarningLevel,
 };
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_core::Stream;
 use futures_util::{pin_mut, TryStreamExt};
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, cmp, sync::Arc};
+
+use super::infile::MAX_MYSQL_PACKET_SIZE;
+use super::reconnect::is_connection_lost;
+
+/// The most data we send in one `COM_STMT_SEND_LONG_DATA` packet.
+const LONG_DATA_CHUNK_SIZE: usize = 1024 * 1024;
 
 impl MySqlConnection {
     async fn get_or_prepare<'c>(
@@ -82,6 +92,58 @@ impl MySqlConnection {
         Ok((id, metadata))
     }
 
+    /// Send the parameters bound with `bind_long_data`, which the server keeps for the next
+    /// execution of the statement.
+    async fn send_long_data(
+        &mut self,
+        statement: u32,
+        arguments: &MySqlArguments,
+    ) -> Result<(), Error> {
+        // leave room for the command, statement id and parameter index
+        let chunk_size = cmp::min(
+            LONG_DATA_CHUNK_SIZE,
+            cmp::min(self.max_allowed_packet, MAX_MYSQL_PACKET_SIZE) - 7,
+        );
+
+        let mut buf = vec![0; chunk_size];
+
+        for (param, long_data) in &arguments.long_data {
+            let mut source = long_data.take().ok_or_else(|| {
+                Error::Protocol(format!(
+                    "the source of long data parameter {} was already read",
+                    param + 1
+                ))
+            })?;
+
+            // send at least one packet, as the server otherwise expects the value in `Execute`
+            let mut first = true;
+
+            loop {
+                let len = read_chunk(&mut source, &mut buf).await?;
+
+                if len == 0 && !first {
+                    break;
+                }
+
+                first = false;
+
+                self.stream
+                    .send_packet(SendLongData {
+                        statement,
+                        param: *param,
+                        data: &buf[..len],
+                    })
+                    .await?;
+
+                if len < buf.len() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::needless_lifetimes)]
     pub(crate) async fn run<'e, 'c: 'e, 'q: 'e>(
         &'c mut self,
@@ -92,6 +154,7 @@ impl MySqlConnection {
     {
         let mut logger = QueryLogger::new(sql, self.log_settings.clone());
 
+        self.response_received = false;
         self.stream.wait_until_ready().await?;
         self.stream.waiting.push_back(Waiting::Result);
 
@@ -101,13 +164,36 @@ impl MySqlConnection {
             // to re-use this memory freely between result sets
             let mut columns = Arc::new(Vec::new());
 
-            let (mut column_names, format, mut needs_metadata) = if let Some(arguments) = arguments {
+            // the columns of the prepared statement, which a `CALL` doesn't know in advance
+            let (mut column_names, format, mut prepared_columns, mut cursor) = if let Some(arguments) = arguments {
+                if !arguments.attributes.is_empty()
+                    && !self.stream.capabilities.contains(Capabilities::QUERY_ATTRIBUTES)
+                {
+                    self.stream.waiting.pop_front();
+
+                    return Err(Error::Configuration(
+                        "query attributes are not supported by the server, they require MySQL 8.0.23 or later".into(),
+                    ));
+                }
+
                 let (id, metadata) = self.get_or_prepare(
                     sql,
                     persistent,
                 )
                 .await?;
 
+                if !arguments.long_data.is_empty() {
+                    if let Err(e) = self.send_long_data(id, &arguments).await {
+                        // no response is coming for this query, and the data sent so far would
+                        // be used by the next execution of the statement
+                        self.stream.waiting.pop_front();
+                        self.stream.send_packet(StmtReset { statement: id }).await?;
+                        self.stream.recv_ok().await?;
+
+                        return Err(e);
+                    }
+                }
+
                 // https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
                 self.stream
                     .send_packet(StatementExecute {
@@ -116,39 +202,70 @@ impl MySqlConnection {
                     })
                     .await?;
 
-                (metadata.column_names, MySqlValueFormat::Binary, false)
+                // the statement and the number of rows to fetch at once, if a cursor is opened
+                let cursor = arguments
+                    .fetch_size
+                    .filter(|_| arguments.uses_cursor(self.stream.capabilities))
+                    .map(|rows| (id, rows));
+
+                (metadata.column_names, MySqlValueFormat::Binary, Some(metadata.columns.len()), cursor)
             } else {
                 // https://dev.mysql.com/doc/internals/en/com-query.html
                 self.stream.send_packet(Query(sql)).await?;
 
-                (Arc::default(), MySqlValueFormat::Text, true)
+                (Arc::default(), MySqlValueFormat::Text, None, None)
             };
 
             loop {
                 // query response is a meta-packet which may be one of:
-                //  Ok, Err, ResultSet, or (unhandled) LocalInfileRequest
-                let mut packet = self.stream.recv_packet().await?;
+                //  Ok, Err, ResultSet, or LocalInfileRequest
+                let mut packet = match self.stream.recv_packet().await {
+                    // the connection was closed without a response, or with the error the
+                    // server sends before it closes an idle connection
+                    Err(e) if is_connection_lost(&e) => return Err(e),
+                    packet => {
+                        self.response_received = true;
+                        packet?
+                    }
+                };
+
+                if packet[0] == 0xfb {
+                    // the server asks for the contents of a local file, which the handler set on
+                    // the connection provides; the response to the statement follows the file
+                    let filename = String::from_utf8_lossy(&packet[1..]).into_owned();
+
+                    packet = self.respond_to_local_infile(filename).await?;
+                }
 
                 if packet[0] == 0x00 || packet[0] == 0xff {
                     // first packet in a query response is OK or ERR
                     // this indicates either a successful query with no rows at all or a failed query
                     let ok = packet.ok()?;
+                    self.stream.session_state.apply(ok.session_state)?;
+                    self.stream.status = ok.status;
 
                     let rows_affected = ok.affected_rows;
                     logger.increase_rows_affected(rows_affected);
-                    let done = MySqlQueryResult {
+                    let mut done = MySqlQueryResult {
                         rows_affected,
                         last_insert_id: ok.last_insert_id,
+                        warning_count: ok.warnings,
+                        warnings: Vec::new(),
                     };
 
-                    r#yield!(Either::Left(done));
-
                     if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         // more result sets exist, continue to the next one
+                        r#yield!(Either::Left(done));
                         continue;
                     }
 
                     self.stream.waiting.pop_front();
+
+                    if self.fetch_warnings && done.warning_count > 0 {
+                        done.warnings = self.show_warnings().await?;
+                    }
+
+                    r#yield!(Either::Left(done));
                     return Ok(());
                 }
 
@@ -157,14 +274,12 @@ impl MySqlConnection {
 
                 let num_columns = packet.get_uint_lenenc() as usize; // column count
 
-                if needs_metadata {
-                    column_names = Arc::new(recv_result_metadata(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?);
-                } else {
-                    // next time we hit here, it'll be a new result set and we'll need the
-                    // full metadata
-                    needs_metadata = true;
-
+                // next time we hit here, it'll be a new result set and we'll need the
+                // full metadata
+                if prepared_columns.take() == Some(num_columns) {
                     recv_result_columns(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?;
+                } else {
+                    column_names = Arc::new(recv_result_metadata(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?);
                 }
 
                 // finally, there will be none or many result-rows
@@ -173,19 +288,43 @@ impl MySqlConnection {
 
                     if packet[0] == 0xfe && packet.len() < 9 {
                         let eof = packet.eof(self.stream.capabilities)?;
+                        self.stream.session_state.apply(eof.session_state)?;
+                        self.stream.status = eof.status;
+
+                        // the cursor has more rows, which are sent once they're fetched
+                        if eof.status.contains(Status::SERVER_STATUS_CURSOR_EXISTS)
+                            && !eof.status.contains(Status::SERVER_STATUS_LAST_ROW_SENT)
+                        {
+                            if let Some((statement, rows)) = cursor {
+                                self.stream.send_packet(StmtFetch { statement, rows }).await?;
+                                continue;
+                            }
+                        }
+
+                        // only the first result set is fetched through the cursor
+                        cursor = None;
 
-                        r#yield!(Either::Left(MySqlQueryResult {
+                        let mut done = MySqlQueryResult {
                             rows_affected: 0,
                             last_insert_id: 0,
-                        }));
+                            warning_count: eof.warnings,
+                            warnings: Vec::new(),
+                        };
 
                         if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                             // more result sets exist, continue to the next one
+                            r#yield!(Either::Left(done));
                             *self.stream.waiting.front_mut().unwrap() = Waiting::Result;
                             break;
                         }
 
                         self.stream.waiting.pop_front();
+
+                        if self.fetch_warnings && done.warning_count > 0 {
+                            done.warnings = self.show_warnings().await?;
+                        }
+
+                        r#yield!(Either::Left(done));
                         return Ok(());
                     }
 
@@ -210,6 +349,29 @@ impl MySqlConnection {
     }
 }
 
+impl MySqlConnection {
+    // the warnings of the last statement, which are kept by the server until the next statement
+    async fn show_warnings(&mut self) -> Result<Vec<MySqlWarning>, Error> {
+        // a prepared statement would clear the warnings
+        let rows = self.fetch_all("SHOW WARNINGS").await;
+
+        // the statement the warnings belong to was executed, even if this failed
+        self.response_received = true;
+        let rows = rows?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(MySqlWarning {
+                    level: MySqlWarningLevel::parse(row.try_get(0)?),
+                    // `INT UNSIGNED`, but not flagged as such by every server
+                    code: row.try_get_unchecked(1)?,
+                    message: row.try_get(2)?,
+                })
+            })
+            .collect()
+    }
+}
+
 impl<'c> Executor<'c> for &'c mut MySqlConnection {
     type Database = MySql;
 
@@ -226,14 +388,55 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         let persistent = query.persistent();
 
         Box::pin(try_stream! {
-            let s = self.run(sql, arguments, persistent).await?;
-            pin_mut!(s);
+            // with `auto_reconnect`, the statement is run once more if the connection was lost
+            // before the server responded to it, as the server did not execute it then; long
+            // data cannot be sent again
+            let mut retry = self.can_reconnect()
+                && arguments.as_ref().map_or(true, |arguments| arguments.long_data.is_empty());
+
+            let mut arguments = arguments;
+            let recorded_arguments = if self.is_recording_session_setup() {
+                arguments.clone()
+            } else {
+                None
+            };
 
-            while let Some(v) = s.try_next().await? {
-                r#yield!(v);
-            }
+            loop {
+                let retry_arguments = if retry { arguments.clone() } else { None };
+
+                let lost = 'run: {
+                    let s = match self.run(sql, arguments, persistent).await {
+                        Err(e) if retry && is_connection_lost(&e) => break 'run Some(e),
+                        s => s?,
+                    };
+                    pin_mut!(s);
+
+                    loop {
+                        match s.try_next().await {
+                            Ok(Some(v)) => r#yield!(v),
+                            Ok(None) => break 'run None,
+                            Err(e) if retry && is_connection_lost(&e) => break 'run Some(e),
+                            Err(e) => return Err(e),
+                        }
+                    }
+                };
 
-            Ok(())
+                let Some(lost) = lost else {
+                    self.record_session_setup(sql, recorded_arguments.as_ref());
+                    return Ok(());
+                };
+
+                if self.response_received {
+                    return Err(lost);
+                }
+
+                log::warn!("reconnecting after the connection was lost: {}", lost);
+
+                self.reconnect().await?;
+
+                retry = false;
+                arguments = retry_arguments;
+            }
         })
     }
 
@@ -371,3 +574,20 @@ async fn recv_result_metadata(
 
     Ok(column_names)
 }
+
+/// Fill `buf` from `source`, returning less than its length only at the end of `source`.
+async fn read_chunk(
+    source: &mut (impl AsyncRead + Unpin + ?Sized),
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let mut len = 0;
+
+    while len < buf.len() {
+        match source.read(&mut buf[len..]).await? {
+            0 => break,
+            read => len += read,
+        }
+    }
+
+    Ok(len)
+}
@@ -0,0 +1,328 @@
+//! The `authentication_kerberos_client` plugin of MySQL Enterprise, through the system's GSSAPI
+//! library (MIT Kerberos or Heimdal).
+//!
+//! <https://dev.mysql.com/doc/refman/8.0/en/kerberos-pluggable-authentication.html>
+
+use std::ffi::c_void;
+use std::ptr;
+
+use bytes::{Buf, Bytes};
+
+use crate::connection::auth::recv_auth_more_data;
+use crate::connection::stream::MySqlStream;
+use crate::error::Error;
+
+/// Run the GSSAPI exchange with the service principal in `data`, the first message of the
+/// plugin on the server, until the security context is established.
+///
+/// The server sends an OK packet afterwards.
+pub(crate) async fn authenticate(stream: &mut MySqlStream, mut data: Bytes) -> Result<(), Error> {
+    // the server sends the name of its service principal and its realm, each with a 2-byte
+    // length; the GSSAPI library finds the credentials of the user in the ticket cache
+    if data.remaining() < 2 {
+        return Err(err_protocol!(
+            "missing service principal name for Kerberos authentication"
+        ));
+    }
+
+    let len = usize::from(data.get_u16_le());
+
+    if data.remaining() < len {
+        return Err(err_protocol!(
+            "missing service principal name for Kerberos authentication"
+        ));
+    }
+
+    let target = std::str::from_utf8(&data[..len]).map_err(Error::protocol)?;
+
+    let mut context = SecurityContext::new(target)?;
+    let mut input = Vec::new();
+
+    loop {
+        let (token, complete) = context.step(&input)?;
+
+        if !token.is_empty() {
+            stream.write_packet(&*token);
+            stream.flush().await?;
+        }
+
+        if complete {
+            return Ok(());
+        }
+
+        input = recv_auth_more_data(stream).await?.to_vec();
+    }
+}
+
+/// A security context of the GSSAPI library, which is released on drop.
+struct SecurityContext {
+    name: ffi::gss_name_t,
+    context: ffi::gss_ctx_id_t,
+    flags: ffi::OM_uint32,
+}
+
+// SAFETY: the handles are only used through `&mut self`, and GSSAPI allows them to be used
+// from another thread than the one that created them
+unsafe impl Send for SecurityContext {}
+
+impl SecurityContext {
+    fn new(target: &str) -> Result<Self, Error> {
+        let mut minor = 0;
+        let mut name = ptr::null_mut();
+
+        let mut buffer = ffi::gss_buffer_desc {
+            length: target.len(),
+            value: target.as_ptr() as *mut c_void,
+        };
+
+        let mut name_type = ffi::gss_OID_desc {
+            length: ffi::NT_PRINCIPAL_NAME.len() as ffi::OM_uint32,
+            elements: ffi::NT_PRINCIPAL_NAME.as_ptr() as *mut c_void,
+        };
+
+        // SAFETY: the buffer and the OID point to memory that outlives the call, and the name
+        // is released on drop
+        let major =
+            unsafe { ffi::gss_import_name(&mut minor, &mut buffer, &mut name_type, &mut name) };
+
+        if ffi::is_error(major) {
+            return Err(error(
+                "failed to import the name of the server",
+                major,
+                minor,
+            ));
+        }
+
+        Ok(Self {
+            name,
+            context: ptr::null_mut(),
+            // the server proves its identity as well
+            flags: ffi::GSS_C_MUTUAL_FLAG,
+        })
+    }
+
+    /// Process a token from the server, and return the token to send to the server and whether
+    /// the context is established.
+    fn step(&mut self, input: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+        let mut minor = 0;
+
+        let mut input_buffer = ffi::gss_buffer_desc {
+            length: input.len(),
+            value: input.as_ptr() as *mut c_void,
+        };
+
+        // `GSS_C_NO_BUFFER` for the first step
+        let input_token = if input.is_empty() {
+            ptr::null_mut()
+        } else {
+            &mut input_buffer as *mut _
+        };
+
+        let mut output = ffi::gss_buffer_desc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        // SAFETY: the handles are valid or null for the first step, and the output buffer is
+        // released below
+        let major = unsafe {
+            ffi::gss_init_sec_context(
+                &mut minor,
+                ptr::null_mut(),
+                &mut self.context,
+                self.name,
+                ptr::null_mut(),
+                self.flags,
+                0,
+                ptr::null_mut(),
+                input_token,
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+
+        let token = if output.value.is_null() {
+            Vec::new()
+        } else {
+            // SAFETY: the library returned a buffer of `output.length` bytes
+            let token =
+                unsafe { std::slice::from_raw_parts(output.value as *const u8, output.length) }
+                    .to_vec();
+
+            let mut release_minor = 0;
+
+            // SAFETY: the buffer was allocated by the library and isn't used anymore
+            unsafe { ffi::gss_release_buffer(&mut release_minor, &mut output) };
+
+            token
+        };
+
+        if ffi::is_error(major) {
+            return Err(error(
+                "failed to initialize the security context",
+                major,
+                minor,
+            ));
+        }
+
+        Ok((token, major & ffi::GSS_S_CONTINUE_NEEDED == 0))
+    }
+}
+
+impl Drop for SecurityContext {
+    fn drop(&mut self) {
+        let mut minor = 0;
+
+        // SAFETY: the handles were created by the library, and are not used afterwards
+        unsafe {
+            if !self.context.is_null() {
+                ffi::gss_delete_sec_context(&mut minor, &mut self.context, ptr::null_mut());
+            }
+
+            if !self.name.is_null() {
+                ffi::gss_release_name(&mut minor, &mut self.name);
+            }
+        }
+    }
+}
+
+/// Create an error with the messages of the GSSAPI library for the status codes.
+fn error(context: &str, major: ffi::OM_uint32, minor: ffi::OM_uint32) -> Error {
+    let mut message = format!("GSSAPI error: {context}");
+
+    for (status, status_type) in [(major, ffi::GSS_C_GSS_CODE), (minor, ffi::GSS_C_MECH_CODE)] {
+        let mut message_context = 0;
+
+        loop {
+            let mut minor = 0;
+
+            let mut buffer = ffi::gss_buffer_desc {
+                length: 0,
+                value: ptr::null_mut(),
+            };
+
+            // SAFETY: the buffer is released below
+            let major = unsafe {
+                ffi::gss_display_status(
+                    &mut minor,
+                    status,
+                    status_type,
+                    ptr::null_mut(),
+                    &mut message_context,
+                    &mut buffer,
+                )
+            };
+
+            if ffi::is_error(major) || buffer.value.is_null() {
+                break;
+            }
+
+            // SAFETY: the library returned a buffer of `buffer.length` bytes
+            let text =
+                unsafe { std::slice::from_raw_parts(buffer.value as *const u8, buffer.length) };
+
+            message.push_str(": ");
+            message.push_str(&String::from_utf8_lossy(text));
+
+            // SAFETY: the buffer was allocated by the library and isn't used anymore
+            unsafe { ffi::gss_release_buffer(&mut minor, &mut buffer) };
+
+            if message_context == 0 {
+                break;
+            }
+        }
+    }
+
+    err_protocol!("{}", message)
+}
+
+/// The subset of the GSSAPI C bindings (RFC 2744) that is used for authentication.
+#[allow(non_camel_case_types)]
+mod ffi {
+    use std::ffi::c_void;
+
+    pub type OM_uint32 = u32;
+    pub type gss_name_t = *mut c_void;
+    pub type gss_ctx_id_t = *mut c_void;
+    pub type gss_cred_id_t = *mut c_void;
+    pub type gss_channel_bindings_t = *mut c_void;
+
+    #[repr(C)]
+    pub struct gss_buffer_desc {
+        pub length: usize,
+        pub value: *mut c_void,
+    }
+
+    #[repr(C)]
+    pub struct gss_OID_desc {
+        pub length: OM_uint32,
+        pub elements: *mut c_void,
+    }
+
+    pub const GSS_C_MUTUAL_FLAG: OM_uint32 = 2;
+
+    pub const GSS_C_GSS_CODE: i32 = 1;
+    pub const GSS_C_MECH_CODE: i32 = 2;
+
+    pub const GSS_S_CONTINUE_NEEDED: OM_uint32 = 1;
+
+    /// `GSS_KRB5_NT_PRINCIPAL_NAME`, the OID 1.2.840.113554.1.2.2.1 for Kerberos principal
+    /// names of the form `service/host@REALM`.
+    pub const NT_PRINCIPAL_NAME: &[u8] = b"\x2a\x86\x48\x86\xf7\x12\x01\x02\x02\x01";
+
+    /// `GSS_ERROR`: whether the calling error or the routine error of a status is set.
+    pub fn is_error(major: OM_uint32) -> bool {
+        major & 0xffff_0000 != 0
+    }
+
+    #[cfg_attr(target_os = "macos", link(name = "GSS", kind = "framework"))]
+    #[cfg_attr(not(target_os = "macos"), link(name = "gssapi_krb5"))]
+    extern "C" {
+        pub fn gss_import_name(
+            minor_status: *mut OM_uint32,
+            input_name_buffer: *mut gss_buffer_desc,
+            input_name_type: *mut gss_OID_desc,
+            output_name: *mut gss_name_t,
+        ) -> OM_uint32;
+
+        pub fn gss_release_name(minor_status: *mut OM_uint32, name: *mut gss_name_t) -> OM_uint32;
+
+        pub fn gss_init_sec_context(
+            minor_status: *mut OM_uint32,
+            initiator_cred_handle: gss_cred_id_t,
+            context_handle: *mut gss_ctx_id_t,
+            target_name: gss_name_t,
+            mech_type: *mut gss_OID_desc,
+            req_flags: OM_uint32,
+            time_req: OM_uint32,
+            input_chan_bindings: gss_channel_bindings_t,
+            input_token: *mut gss_buffer_desc,
+            actual_mech_type: *mut *mut gss_OID_desc,
+            output_token: *mut gss_buffer_desc,
+            ret_flags: *mut OM_uint32,
+            time_rec: *mut OM_uint32,
+        ) -> OM_uint32;
+
+        pub fn gss_delete_sec_context(
+            minor_status: *mut OM_uint32,
+            context_handle: *mut gss_ctx_id_t,
+            output_token: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        pub fn gss_release_buffer(
+            minor_status: *mut OM_uint32,
+            buffer: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        pub fn gss_display_status(
+            minor_status: *mut OM_uint32,
+            status_value: OM_uint32,
+            status_type: i32,
+            mech_type: *mut gss_OID_desc,
+            message_context: *mut OM_uint32,
+            status_string: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+    }
+}
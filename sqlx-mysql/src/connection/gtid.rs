@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use crate::connection::MySqlConnection;
+use crate::error::Result;
+use crate::pool::{Pool, PoolConnection};
+use crate::query_scalar::query_scalar;
+use crate::MySql;
+
+impl MySqlConnection {
+    /// The GTIDs of the last transaction committed on this connection, to wait for on a replica
+    /// with [`wait_for_gtid`][Self::wait_for_gtid].
+    ///
+    /// Requires [`MySqlConnectOptions::track_gtids`](crate::MySqlConnectOptions::track_gtids),
+    /// or `session_track_gtids` set otherwise; see [`MySqlSessionState::gtids`].
+    ///
+    /// [`MySqlSessionState::gtids`]: crate::MySqlSessionState::gtids
+    pub fn last_gtid(&self) -> Option<&str> {
+        self.stream.session_state.gtids()
+    }
+
+    /// Wait until the server executed all transactions of `gtid_set`, e.g. a replica that
+    /// should see the writes of another connection on the source, as reported by its
+    /// [`last_gtid`][Self::last_gtid].
+    ///
+    /// Returns `false` if the transactions were not executed within `timeout`. This uses
+    /// `WAIT_FOR_EXECUTED_GTID_SET()`, which requires MySQL 5.7.5 or later with
+    /// `gtid_mode = ON`.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     source: &mut sqlx::mysql::MySqlConnection,
+    /// #     replica: &mut sqlx::mysql::MySqlConnection,
+    /// # ) -> sqlx::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// sqlx::query("INSERT INTO users (name) VALUES ('Alice')")
+    ///     .execute(&mut *source)
+    ///     .await?;
+    ///
+    /// let gtid = source.last_gtid().unwrap_or_default().to_owned();
+    ///
+    /// if replica.wait_for_gtid(&gtid, Duration::from_secs(1)).await? {
+    ///     let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+    ///         .fetch_one(&mut *replica)
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_gtid(&mut self, gtid_set: &str, timeout: Duration) -> Result<bool> {
+        // a timeout of 0 would wait indefinitely
+        let timeout = timeout.as_secs_f64().max(0.001);
+
+        let timed_out: i64 =
+            query_scalar("SELECT CAST(WAIT_FOR_EXECUTED_GTID_SET(?, ?) AS SIGNED)")
+                .bind(gtid_set)
+                .bind(timeout)
+                .fetch_one(self)
+                .await?;
+
+        Ok(timed_out == 0)
+    }
+}
+
+/// Implements acquiring a connection that is consistent with a write to the source on a
+/// [`MySqlPool`][crate::MySqlPool] of replicas.
+pub trait MySqlPoolGtidExt {
+    /// Acquire a connection and wait until it executed all transactions of `gtid_set`, as with
+    /// [`MySqlConnection::wait_for_gtid`].
+    ///
+    /// Returns `None` if the transactions were not executed within `timeout`, in which case
+    /// the connection is released again; the caller can then read from the source instead.
+    fn acquire_after_gtid<'a>(
+        &'a self,
+        gtid_set: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Option<PoolConnection<MySql>>>>;
+}
+
+impl MySqlPoolGtidExt for Pool<MySql> {
+    fn acquire_after_gtid<'a>(
+        &'a self,
+        gtid_set: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Option<PoolConnection<MySql>>>> {
+        Box::pin(async move {
+            let mut conn = self.acquire().await?;
+
+            if conn.wait_for_gtid(gtid_set, timeout).await? {
+                Ok(Some(conn))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+use crate::connection::MySqlConnection;
+use crate::error::Result;
+
+use super::{MySqlInfileCsvWriter, MySqlInfileResult, MySqlInfileRow, MySqlLocalInfile};
+
+impl MySqlConnection {
+    /// Load `rows` into the given columns of `table` with a `LOAD DATA LOCAL INFILE` statement.
+    ///
+    /// The statement is generated from `table` and `columns`, which are quoted as identifiers.
+    /// A table name may be qualified with a database name, as in `"db.users"`. Each row is a
+    /// tuple of values, written with [`MySqlInfileCsvWriter::write_row`].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+    /// let users = vec![(1_i32, "Alice"), (2_i32, "Bob")];
+    ///
+    /// let result = conn.bulk_load("users", &["id", "name"], users).await?;
+    /// assert_eq!(result.rows_affected(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// If a row fails to encode, the upload is stopped with [`MySqlLocalInfile::abort`] and
+    /// the error is returned; rows that were already sent may still be loaded. Run the load
+    /// in a transaction to be able to roll those back.
+    ///
+    /// The same requirements as for [`MySqlConnection::load_local_infile`] apply.
+    pub async fn bulk_load<R: MySqlInfileRow>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<MySqlInfileResult> {
+        let statement = bulk_load_statement(table, columns);
+
+        let mut infile = self.load_local_infile(&statement).await?;
+
+        match write_rows(&mut infile, rows).await {
+            Ok(()) => infile.finish().await,
+            Err(e) => {
+                infile.abort().await?;
+                Err(e)
+            }
+        }
+    }
+}
+
+async fn write_rows<R: MySqlInfileRow>(
+    infile: &mut MySqlLocalInfile<&mut MySqlConnection>,
+    rows: impl IntoIterator<Item = R>,
+) -> Result<()> {
+    let mut writer = MySqlInfileCsvWriter::new(infile);
+
+    for row in rows {
+        writer.write_row(row).await?;
+    }
+
+    Ok(())
+}
+
+/// Build the statement for [`MySqlConnection::bulk_load`].
+///
+/// The field and line options are left at their defaults, which match those of
+/// [`MySqlInfileCsvWriter::new`]. The character set is fixed, as it would otherwise be taken
+/// from the database rather than the connection.
+fn bulk_load_statement(table: &str, columns: &[&str]) -> String {
+    let mut statement = String::from("LOAD DATA LOCAL INFILE 'sqlx-bulk-load' INTO TABLE ");
+
+    for (i, part) in table.split('.').enumerate() {
+        if i > 0 {
+            statement.push('.');
+        }
+
+        push_identifier(&mut statement, part);
+    }
+
+    statement.push_str(" CHARACTER SET utf8mb4 (");
+
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            statement.push_str(", ");
+        }
+
+        push_identifier(&mut statement, column);
+    }
+
+    statement.push(')');
+    statement
+}
+
+fn push_identifier(statement: &mut String, identifier: &str) {
+    let _ = write!(statement, "`{}`", identifier.replace('`', "``"));
+}
+
+#[test]
+fn test_bulk_load_statement() {
+    assert_eq!(
+        bulk_load_statement("users", &["id", "name"]),
+        "LOAD DATA LOCAL INFILE 'sqlx-bulk-load' INTO TABLE `users` \
+         CHARACTER SET utf8mb4 (`id`, `name`)"
+    );
+
+    assert_eq!(
+        bulk_load_statement("db.we`ird", &["a`b"]),
+        "LOAD DATA LOCAL INFILE 'sqlx-bulk-load' INTO TABLE `db`.`we``ird` \
+         CHARACTER SET utf8mb4 (`a``b`)"
+    );
+}
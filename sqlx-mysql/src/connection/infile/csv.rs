@@ -0,0 +1,525 @@
+use std::io::Write;
+use std::ops::DerefMut;
+
+use bytes::{Buf, Bytes};
+
+use crate::connection::MySqlConnection;
+use crate::encode::{Encode, IsNull};
+use crate::error::Result;
+use crate::io::MySqlBufExt;
+use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo};
+
+use super::MySqlLocalInfile;
+
+/// Writes rows of typed values into a [`MySqlLocalInfile`], formatted and escaped according
+/// to the `FIELDS` and `LINES` options of `LOAD DATA`.
+///
+/// The defaults match those of `LOAD DATA` itself:
+///
+/// ```sql
+/// FIELDS TERMINATED BY '\t' ENCLOSED BY '' ESCAPED BY '\\'
+/// LINES TERMINATED BY '\n'
+/// ```
+///
+/// If the statement passed to [`MySqlConnection::load_local_infile`] specifies different
+/// options, configure the writer to match.
+///
+/// `NULL` values are written as `\N` (using the configured escape character).
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+/// use sqlx::mysql::MySqlInfileCsvWriter;
+///
+/// let mut infile = conn
+///     .load_local_infile(
+///         "LOAD DATA LOCAL INFILE 'users.csv' INTO TABLE users \
+///          FIELDS TERMINATED BY ',' ENCLOSED BY '\"' (id, name)",
+///     )
+///     .await?;
+///
+/// let mut writer = MySqlInfileCsvWriter::new(&mut infile)
+///     .field_terminator(",")
+///     .enclosed_by(Some(b'"'));
+///
+/// writer.write_row((1_i32, "Alice")).await?;
+/// writer.write_row((2_i32, None::<&str>)).await?;
+///
+/// infile.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MySqlInfileCsvWriter<'w, C: DerefMut<Target = MySqlConnection>> {
+    infile: &'w mut MySqlLocalInfile<C>,
+    format: InfileFormat,
+    #[cfg(feature = "serde")]
+    columns: Option<Vec<String>>,
+    row: Vec<u8>,
+    value: Vec<u8>,
+    num_fields: usize,
+}
+
+impl<'w, C: DerefMut<Target = MySqlConnection>> MySqlInfileCsvWriter<'w, C> {
+    /// Create a writer using the default `LOAD DATA` field and line options.
+    pub fn new(infile: &'w mut MySqlLocalInfile<C>) -> Self {
+        MySqlInfileCsvWriter {
+            infile,
+            format: InfileFormat::default(),
+            #[cfg(feature = "serde")]
+            columns: None,
+            row: Vec::new(),
+            value: Vec::new(),
+            num_fields: 0,
+        }
+    }
+
+    /// Set the string written between fields (`FIELDS TERMINATED BY`).
+    ///
+    /// The default is `"\t"`.
+    ///
+    /// ### Panics
+    /// If `terminator` is empty.
+    pub fn field_terminator(mut self, terminator: impl Into<Vec<u8>>) -> Self {
+        self.format.field_terminator = terminator.into();
+        assert!(!self.format.field_terminator.is_empty());
+        self
+    }
+
+    /// Set the string written after each row (`LINES TERMINATED BY`).
+    ///
+    /// The default is `"\n"`.
+    ///
+    /// ### Panics
+    /// If `terminator` is empty.
+    pub fn line_terminator(mut self, terminator: impl Into<Vec<u8>>) -> Self {
+        self.format.line_terminator = terminator.into();
+        assert!(!self.format.line_terminator.is_empty());
+        self
+    }
+
+    /// Set the character every field is enclosed by (`FIELDS ENCLOSED BY`).
+    ///
+    /// The default is `None`.
+    pub fn enclosed_by(mut self, enclosed_by: Option<u8>) -> Self {
+        self.format.enclosed_by = enclosed_by;
+        self
+    }
+
+    /// Set the escape character (`FIELDS ESCAPED BY`).
+    ///
+    /// The default is `b'\\'`.
+    pub fn escaped_by(mut self, escaped_by: u8) -> Self {
+        self.format.escaped_by = escaped_by;
+        self
+    }
+
+    /// Set the order in which the fields of records passed to [`Self::write_record`] are
+    /// written, by name.
+    ///
+    /// This should match the column list of the `LOAD DATA` statement. If not set, fields are
+    /// written in the order they are serialized, i.e. declaration order for derived impls.
+    #[cfg(feature = "serde")]
+    pub fn columns<I>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a field to the current row.
+    ///
+    /// The row is not written until [`Self::end_row`] is called.
+    pub fn push<'q, T>(&mut self, value: T) -> Result<&mut Self>
+    where
+        T: Encode<'q, MySql> + Type<MySql>,
+    {
+        if self.num_fields > 0 {
+            self.row.extend_from_slice(&self.format.field_terminator);
+        }
+
+        self.num_fields += 1;
+
+        let ty = value.produces().unwrap_or_else(T::type_info);
+
+        self.value.clear();
+
+        match value.encode(&mut self.value) {
+            IsNull::Yes => self.format.write_null(&mut self.row),
+            IsNull::No => {
+                let value = Bytes::copy_from_slice(&self.value);
+                self.format.write_value(&ty, value, &mut self.row)?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Terminate the current row and write it to the infile stream.
+    pub async fn end_row(&mut self) -> Result<()> {
+        self.row.extend_from_slice(&self.format.line_terminator);
+        self.num_fields = 0;
+
+        let res = self.infile.write(&self.row).await;
+        self.row.clear();
+
+        res
+    }
+
+    /// Write a complete row from a value implementing [`serde::Serialize`].
+    ///
+    /// Structs and maps produce one field per entry, in the order given by [`Self::columns`]
+    /// if set. Tuples and sequences produce one field per element. Field values must be
+    /// scalars, options, or fieldless enum variants (which are written as the variant name,
+    /// matching `ENUM` columns).
+    ///
+    /// Requires the `json` Cargo feature flag, which enables `serde`.
+    ///
+    /// ### Panics
+    /// If a row was started with [`Self::push`] but not ended with [`Self::end_row`].
+    #[cfg(feature = "serde")]
+    pub async fn write_record<T: serde::Serialize + ?Sized>(&mut self, record: &T) -> Result<()> {
+        assert_eq!(
+            self.num_fields, 0,
+            "write_record: a row is already in progress"
+        );
+
+        let mut res = super::record::write_record(
+            &self.format,
+            self.columns.as_deref(),
+            record,
+            &mut self.row,
+        );
+
+        if res.is_ok() {
+            res = self.infile.write(&self.row).await;
+        }
+
+        self.row.clear();
+
+        res
+    }
+
+    /// Write a complete row, given as a tuple of values.
+    pub async fn write_row(&mut self, row: impl MySqlInfileRow) -> Result<()> {
+        row.push_to(self)?;
+        self.end_row().await
+    }
+}
+
+/// A row of values that can be written by [`MySqlInfileCsvWriter::write_row`].
+///
+/// This is implemented for tuples of up to 16 values.
+pub trait MySqlInfileRow {
+    /// Push every value of the row to `writer` with [`MySqlInfileCsvWriter::push`].
+    fn push_to<C: DerefMut<Target = MySqlConnection>>(
+        self,
+        writer: &mut MySqlInfileCsvWriter<'_, C>,
+    ) -> Result<()>;
+}
+
+macro_rules! impl_infile_row_for_tuple {
+    ($($T:ident),+) => {
+        impl<'q, $($T,)+> MySqlInfileRow for ($($T,)+)
+        where
+            $($T: Encode<'q, MySql> + Type<MySql>,)+
+        {
+            #[allow(non_snake_case)]
+            fn push_to<C: DerefMut<Target = MySqlConnection>>(
+                self,
+                writer: &mut MySqlInfileCsvWriter<'_, C>,
+            ) -> Result<()> {
+                let ($($T,)+) = self;
+                $(writer.push($T)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_infile_row_for_tuple!(T1);
+impl_infile_row_for_tuple!(T1, T2);
+impl_infile_row_for_tuple!(T1, T2, T3);
+impl_infile_row_for_tuple!(T1, T2, T3, T4);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_infile_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+/// The `FIELDS` and `LINES` options of a `LOAD DATA` statement.
+///
+/// <https://dev.mysql.com/doc/refman/8.0/en/load-data.html#load-data-field-line-handling>
+#[derive(Debug, Clone)]
+pub(crate) struct InfileFormat {
+    pub(crate) field_terminator: Vec<u8>,
+    pub(crate) line_terminator: Vec<u8>,
+    pub(crate) enclosed_by: Option<u8>,
+    pub(crate) escaped_by: u8,
+}
+
+impl Default for InfileFormat {
+    fn default() -> Self {
+        InfileFormat {
+            field_terminator: b"\t".to_vec(),
+            line_terminator: b"\n".to_vec(),
+            enclosed_by: None,
+            escaped_by: b'\\',
+        }
+    }
+}
+
+impl InfileFormat {
+    pub(crate) fn write_null(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[self.escaped_by, b'N']);
+    }
+
+    /// Write a value in the binary protocol encoding produced by [`Encode`] as text.
+    pub(crate) fn write_value(
+        &self,
+        ty: &MySqlTypeInfo,
+        mut value: Bytes,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        let unsigned = ty.flags.contains(ColumnFlags::UNSIGNED);
+
+        // numbers never contain characters that need escaping
+        match (ty.r#type, value.len()) {
+            (ColumnType::Tiny, 1) if unsigned => write_display(out, value.get_u8()),
+            (ColumnType::Tiny, 1) => write_display(out, value.get_i8()),
+            (ColumnType::Short | ColumnType::Year, 2) if unsigned => {
+                write_display(out, value.get_u16_le())
+            }
+            (ColumnType::Short | ColumnType::Year, 2) => write_display(out, value.get_i16_le()),
+            (ColumnType::Long | ColumnType::Int24, 4) if unsigned => {
+                write_display(out, value.get_u32_le())
+            }
+            (ColumnType::Long | ColumnType::Int24, 4) => write_display(out, value.get_i32_le()),
+            (ColumnType::LongLong, 8) if unsigned => write_display(out, value.get_u64_le()),
+            (ColumnType::LongLong, 8) => write_display(out, value.get_i64_le()),
+            (ColumnType::Float, 4) => write_display(out, value.get_f32_le()),
+            (ColumnType::Double, 8) => write_display(out, value.get_f64_le()),
+
+            (ColumnType::Date | ColumnType::Datetime | ColumnType::Timestamp, _) => {
+                write_datetime(ty.r#type, value, out)?
+            }
+            (ColumnType::Time, _) => write_time(value, out)?,
+
+            (
+                ColumnType::Tiny
+                | ColumnType::Short
+                | ColumnType::Year
+                | ColumnType::Long
+                | ColumnType::Int24
+                | ColumnType::LongLong
+                | ColumnType::Float
+                | ColumnType::Double,
+                len,
+            ) => {
+                return Err(err_protocol!(
+                    "unexpected encoded length {} for a value of type {}",
+                    len,
+                    ty
+                ));
+            }
+
+            // everything else is encoded as a length-encoded byte string
+            _ => {
+                let bytes = value.get_bytes_lenenc();
+                self.write_escaped(&bytes, out);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn write_escaped(&self, text: &[u8], out: &mut Vec<u8>) {
+        let esc = self.escaped_by;
+
+        if let Some(enclosed_by) = self.enclosed_by {
+            out.push(enclosed_by);
+        }
+
+        for &b in text {
+            match b {
+                0 => out.extend_from_slice(&[esc, b'0']),
+                b'\n' => out.extend_from_slice(&[esc, b'n']),
+                b'\r' => out.extend_from_slice(&[esc, b'r']),
+                b'\t' => out.extend_from_slice(&[esc, b't']),
+                0x1a => out.extend_from_slice(&[esc, b'Z']),
+
+                _ if b == esc
+                    || Some(b) == self.enclosed_by
+                    || b == self.field_terminator[0]
+                    || b == self.line_terminator[0] =>
+                {
+                    out.extend_from_slice(&[esc, b]);
+                }
+
+                _ => out.push(b),
+            }
+        }
+
+        if let Some(enclosed_by) = self.enclosed_by {
+            out.push(enclosed_by);
+        }
+    }
+}
+
+fn write_display(out: &mut Vec<u8>, value: impl std::fmt::Display) {
+    write!(out, "{value}").expect("BUG: writing to a Vec cannot fail");
+}
+
+// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_binary_resultset.html#sect_protocol_binary_resultset_row_value_date
+fn write_datetime(ty: ColumnType, mut value: Bytes, out: &mut Vec<u8>) -> Result<()> {
+    let len = value.get_u8();
+
+    let (mut year, mut month, mut day) = (0, 0, 0);
+    let (mut hour, mut minute, mut second, mut micros) = (0, 0, 0, 0);
+
+    if !matches!(len, 0 | 4 | 7 | 11) || value.len() != len as usize {
+        return Err(err_protocol!(
+            "unexpected encoded length {} for a {:?}",
+            len,
+            ty
+        ));
+    }
+
+    if len >= 4 {
+        year = value.get_u16_le();
+        month = value.get_u8();
+        day = value.get_u8();
+    }
+
+    if len >= 7 {
+        hour = value.get_u8();
+        minute = value.get_u8();
+        second = value.get_u8();
+    }
+
+    if len == 11 {
+        micros = value.get_u32_le();
+    }
+
+    write!(out, "{year:04}-{month:02}-{day:02}").expect("BUG: writing to a Vec cannot fail");
+
+    if ty != ColumnType::Date {
+        write!(out, " {hour:02}:{minute:02}:{second:02}")
+            .expect("BUG: writing to a Vec cannot fail");
+
+        if micros > 0 {
+            write!(out, ".{micros:06}").expect("BUG: writing to a Vec cannot fail");
+        }
+    }
+
+    Ok(())
+}
+
+// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_binary_resultset.html#sect_protocol_binary_resultset_row_value_time
+fn write_time(mut value: Bytes, out: &mut Vec<u8>) -> Result<()> {
+    let len = value.get_u8();
+
+    if !matches!(len, 0 | 8 | 12) || value.len() != len as usize {
+        return Err(err_protocol!(
+            "unexpected encoded length {} for a TIME",
+            len
+        ));
+    }
+
+    if len == 0 {
+        out.extend_from_slice(b"00:00:00");
+        return Ok(());
+    }
+
+    let is_negative = value.get_u8() != 0;
+    let days = value.get_u32_le();
+    let hours = u64::from(days) * 24 + u64::from(value.get_u8());
+    let minutes = value.get_u8();
+    let seconds = value.get_u8();
+    let micros = if len == 12 { value.get_u32_le() } else { 0 };
+
+    if is_negative {
+        out.push(b'-');
+    }
+
+    write!(out, "{hours:02}:{minutes:02}:{seconds:02}").expect("BUG: writing to a Vec cannot fail");
+
+    if micros > 0 {
+        write!(out, ".{micros:06}").expect("BUG: writing to a Vec cannot fail");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+fn format_value<'q, T: Encode<'q, MySql> + Type<MySql>>(format: &InfileFormat, value: T) -> String {
+    let ty = value.produces().unwrap_or_else(T::type_info);
+    let mut buf = Vec::new();
+    let mut out = Vec::new();
+
+    match value.encode(&mut buf) {
+        IsNull::Yes => format.write_null(&mut out),
+        IsNull::No => format.write_value(&ty, buf.into(), &mut out).unwrap(),
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_infile_format_numbers() {
+    let format = InfileFormat::default();
+
+    assert_eq!(format_value(&format, -5_i8), "-5");
+    assert_eq!(format_value(&format, 250_u8), "250");
+    assert_eq!(format_value(&format, true), "1");
+    assert_eq!(format_value(&format, -30_000_i16), "-30000");
+    assert_eq!(format_value(&format, 4_000_000_000_u32), "4000000000");
+    assert_eq!(format_value(&format, i64::MIN), "-9223372036854775808");
+    assert_eq!(format_value(&format, 1.5_f64), "1.5");
+    assert_eq!(format_value(&format, None::<i32>), "\\N");
+}
+
+#[test]
+fn test_infile_format_escapes_strings() {
+    let format = InfileFormat::default();
+
+    assert_eq!(format_value(&format, "plain"), "plain");
+    assert_eq!(format_value(&format, "a\tb\nc"), "a\\tb\\nc");
+    assert_eq!(format_value(&format, "back\\slash"), "back\\\\slash");
+    assert_eq!(format_value(&format, "\\N"), "\\\\N");
+    assert_eq!(format_value(&format, &b"\0\x1a"[..]), "\\0\\Z");
+
+    let csv = InfileFormat {
+        field_terminator: b",".to_vec(),
+        line_terminator: b"\r\n".to_vec(),
+        enclosed_by: Some(b'"'),
+        escaped_by: b'\\',
+    };
+
+    assert_eq!(format_value(&csv, "a,\"b\""), "\"a\\,\\\"b\\\"\"");
+    assert_eq!(format_value(&csv, None::<String>), "\\N");
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_infile_format_time() {
+    use time::macros::{date, datetime, time};
+
+    let format = InfileFormat::default();
+
+    assert_eq!(format_value(&format, date!(2023 - 07 - 04)), "2023-07-04");
+    assert_eq!(format_value(&format, time!(13:05:09)), "13:05:09");
+    assert_eq!(
+        format_value(&format, datetime!(2023-07-04 13:05:09.25)),
+        "2023-07-04 13:05:09.250000"
+    );
+}
@@ -0,0 +1,667 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::DerefMut;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::{cmp, io};
+
+use bytes::Bytes;
+use futures_core::future::BoxFuture;
+use futures_core::ready;
+use futures_util::future::poll_fn;
+
+use crate::acquire::Acquire;
+use crate::connection::{MySqlConnection, Waiting};
+use crate::error::{Error, Result};
+use crate::io::{AsyncRead, AsyncReadExt};
+#[cfg(doc)]
+use crate::pool::PoolConnection;
+use crate::protocol::response::Status;
+use crate::protocol::text::Query;
+use crate::protocol::{Capabilities, Packet};
+use crate::{MySql, MySqlQueryResult};
+
+pub use csv::{MySqlInfileCsvWriter, MySqlInfileRow};
+
+mod bulk;
+mod csv;
+#[cfg(feature = "serde")]
+mod record;
+
+/// The largest payload that fits in a single MySQL packet.
+///
+/// A packet with a payload of exactly `0xFF_FF_FF` bytes signals that the payload continues in
+/// the next packet, so the data we send must always stay below that.
+pub(crate) const MAX_MYSQL_PACKET_SIZE: usize = 0xFF_FF_FE;
+
+/// How much we try to read from a source at once in [`MySqlLocalInfile::send_from_reader`].
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+impl MySqlConnection {
+    /// Issue a `LOAD DATA LOCAL INFILE` statement and transition the connection to streaming
+    /// file contents to MySQL. This is a much more efficient way to import data as compared to
+    /// `INSERT`.
+    ///
+    /// If `statement` does not cause the server to request a local file, an error is returned.
+    ///
+    /// The server must have `local_infile` enabled for it to accept the statement, and the
+    /// connection must have been opened with
+    /// [`enable_local_infile(true)`][crate::MySqlConnectOptions::enable_local_infile], since
+    /// LOCAL INFILE is disabled by default.
+    /// The accepted statement syntax is shown here:
+    /// https://dev.mysql.com/doc/refman/8.0/en/load-data.html
+    ///
+    /// ### Transactions
+    /// This method is also available on a [`Transaction`][crate::transaction::Transaction]
+    /// through `DerefMut`, in which case the loaded rows are committed or rolled back together
+    /// with the transaction (for tables using a transactional storage engine such as InnoDB):
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: &sqlx::mysql::MySqlPool) -> sqlx::Result<()> {
+    /// let mut tx = pool.begin().await?;
+    ///
+    /// sqlx::query("DELETE FROM users").execute(&mut *tx).await?;
+    ///
+    /// let mut infile = tx
+    ///     .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users")
+    ///     .await?;
+    /// infile.write(b"1\tAlice\n").await?;
+    /// infile.finish().await?;
+    ///
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ### Note
+    /// [MySqlLocalInfile::finish] should be called when finished. If the returned
+    /// [MySqlLocalInfile] is dropped instead, the upload is cut short as with
+    /// [MySqlLocalInfile::abort] the next time the connection is used.
+    pub async fn load_local_infile(
+        &mut self,
+        statement: &str,
+    ) -> Result<MySqlLocalInfile<&mut Self>> {
+        MySqlLocalInfile::begin(self, statement).await
+    }
+
+    fn local_infile_enabled(&self) -> bool {
+        self.stream.capabilities.contains(Capabilities::LOCAL_FILES)
+    }
+
+    /// The largest payload of a packet of file contents that the server accepts.
+    fn max_packet_size(&self) -> usize {
+        cmp::min(self.max_allowed_packet, MAX_MYSQL_PACKET_SIZE)
+    }
+}
+
+/// The function behind a [`LocalInfileHandler`].
+type LocalInfileHandlerFn = dyn for<'a, 'c> Fn(&'a mut MySqlLocalInfile<&'c mut MySqlConnection>) -> BoxFuture<'a, Result<()>>
+    + 'static
+    + Send
+    + Sync;
+
+/// A callback answering `LOCAL INFILE` requests the server sends in response to a query.
+///
+/// Set with [`MySqlConnectOptions::local_infile_handler`][crate::MySqlConnectOptions::local_infile_handler].
+#[derive(Clone)]
+pub(crate) struct LocalInfileHandler(pub(crate) Arc<LocalInfileHandlerFn>);
+
+impl Debug for LocalInfileHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalInfileHandler").finish_non_exhaustive()
+    }
+}
+
+impl MySqlConnection {
+    /// Answer a `LOCAL INFILE` request received while running a query, using the handler
+    /// configured on the connection.
+    ///
+    /// The request is always answered, even if there is no handler or the handler fails, so
+    /// the connection stays usable. In those cases, the error is returned once the server
+    /// has responded to the end of the file.
+    ///
+    /// Returns the `OK` packet the server sends in response to the statement.
+    pub(crate) async fn respond_to_local_infile(
+        &mut self,
+        filename: String,
+    ) -> Result<Packet<Bytes>> {
+        let handler = self.local_infile_handler.clone();
+        let packet_size = self.max_packet_size();
+        let local_infile_enabled = self.local_infile_enabled();
+
+        let mut infile = MySqlLocalInfile {
+            conn: Some(&mut *self),
+            filename,
+            buf: Vec::new(),
+            packet_size,
+            bytes_sent: 0,
+            packets_sent: 0,
+        };
+
+        let res = match &handler {
+            // never hand out local files if we did not allow the server to ask for them
+            _ if !local_infile_enabled => Err(err_protocol!(
+                "LOCAL INFILE request received but LOCAL INFILE is disabled for this connection"
+            )),
+            Some(handler) => (handler.0)(&mut infile).await,
+            None => Err(err_protocol!(
+                "LOCAL INFILE request received but no handler is set; use \
+                 `MySqlConnection::load_local_infile()` or \
+                 `MySqlConnectOptions::local_infile_handler()`"
+            )),
+        };
+
+        let res = match res {
+            Ok(()) => infile.flush().await,
+            Err(e) => {
+                // end the file at the last packet that was sent
+                infile.buf.clear();
+                Err(e)
+            }
+        };
+
+        // end the file right here instead of on drop, to receive the response
+        infile.conn = None;
+        drop(infile);
+
+        let packet = end_of_file(self).await;
+
+        match res {
+            Ok(()) => packet,
+            Err(e) => {
+                if let Ok(ok) = packet.and_then(Packet::ok) {
+                    if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                        self.stream.waiting.pop_front();
+                    }
+                }
+
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Implements methods for directly executing `LOAD DATA LOCAL INFILE` on anything that can
+/// provide a MySQL connection through [`Acquire`]: a [`MySqlPool`], a [`PoolConnection`],
+/// a [`MySqlConnection`] or a [`Transaction`].
+///
+/// ```rust,no_run
+/// # async fn example(pool: &sqlx::mysql::MySqlPool) -> sqlx::Result<()> {
+/// use sqlx::mysql::MySqlInfileExt;
+///
+/// // checks out a connection for the duration of the upload
+/// let mut infile = pool
+///     .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users")
+///     .await?;
+/// infile.write(b"1\tAlice\n").await?;
+/// infile.finish().await?;
+///
+/// // the rows are committed or rolled back together with the transaction
+/// let mut tx = pool.begin().await?;
+///
+/// let mut infile = tx
+///     .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users")
+///     .await?;
+/// infile.write(b"2\tBob\n").await?;
+/// infile.finish().await?;
+///
+/// tx.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MySqlPool`]: crate::MySqlPool
+/// [`Transaction`]: crate::transaction::Transaction
+pub trait MySqlInfileExt<'c>: Acquire<'c, Database = MySql> {
+    /// Issue a `LOAD DATA LOCAL INFILE` statement and begin streaming file contents to MySQL.
+    ///
+    /// For a pool, a single connection will be checked out for the duration.
+    ///
+    /// If `statement` does not cause the server to request a local file, an error is returned.
+    ///
+    /// ### Note
+    /// [MySqlLocalInfile::finish] should be called when finished. If the returned
+    /// [MySqlLocalInfile] is dropped instead, the upload is cut short as with
+    /// [MySqlLocalInfile::abort] the next time the connection is used.
+    fn load_local_infile(
+        self,
+        statement: &'c str,
+    ) -> BoxFuture<'c, Result<MySqlLocalInfile<Self::Connection>>>;
+}
+
+impl<'c, A> MySqlInfileExt<'c> for A
+where
+    A: Acquire<'c, Database = MySql> + Send + 'c,
+{
+    fn load_local_infile(
+        self,
+        statement: &'c str,
+    ) -> BoxFuture<'c, Result<MySqlLocalInfile<Self::Connection>>> {
+        Box::pin(async move { MySqlLocalInfile::begin(self.acquire().await?, statement).await })
+    }
+}
+
+/// A connection in streaming `LOAD DATA LOCAL INFILE` mode.
+///
+/// Data written is buffered and sent to the server in packets of up to 16 MiB, or the server's
+/// `max_allowed_packet` if that is smaller; see [`set_packet_size`][Self::set_packet_size].
+/// Besides the inherent [`write`][Self::write] and [`flush`][Self::flush] methods, this type
+/// implements `futures::io::AsyncWrite` and, with the `runtime-tokio` feature,
+/// `tokio::io::AsyncWrite`, so it can be used as the sink of any generic copy routine.
+///
+/// Created by [MySqlConnection::load_local_infile] or [MySqlInfileExt::load_local_infile].
+///
+/// ### Note
+/// [MySqlLocalInfile::finish] should be called when finished. Closing it through `AsyncWrite`
+/// only flushes the buffered data.
+///
+/// If it is dropped without calling [finish][Self::finish] or [abort][Self::abort], e.g. because
+/// an error occurred while producing the data, the file is ended at the last packet that was
+/// already sent and any buffered data is discarded. As the response of the server cannot be
+/// awaited on drop, it is received the next time the connection is used; for a pool connection,
+/// that happens before it is returned to the pool, which closes the connection instead if the
+/// server responds with an error. This is the same as how dropped transactions are rolled back.
+#[must_use = "the upload is cut short if `.finish()` is not called"]
+pub struct MySqlLocalInfile<C: DerefMut<Target = MySqlConnection>> {
+    conn: Option<C>,
+    filename: String,
+    buf: Vec<u8>,
+    packet_size: usize,
+    bytes_sent: u64,
+    packets_sent: u64,
+}
+
+impl<C: DerefMut<Target = MySqlConnection>> MySqlLocalInfile<C> {
+    async fn begin(mut conn: C, statement: &str) -> Result<Self> {
+        if !conn.local_infile_enabled() {
+            return Err(Error::Configuration(
+                "LOCAL INFILE is disabled for this connection or not supported by the server"
+                    .into(),
+            ));
+        }
+
+        conn.stream.wait_until_ready().await?;
+        conn.stream.send_packet(Query(statement)).await?;
+
+        let packet = conn.stream.recv_packet().await?;
+
+        match packet[0] {
+            // https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_com_query_response_local_infile_request.html
+            0xfb => {
+                let filename = String::from_utf8_lossy(&packet[1..]).into_owned();
+
+                let packet_size = conn.max_packet_size();
+
+                Ok(MySqlLocalInfile {
+                    conn: Some(conn),
+                    filename,
+                    buf: Vec::new(),
+                    packet_size,
+                    bytes_sent: 0,
+                    packets_sent: 0,
+                })
+            }
+
+            0x00 => {
+                let ok = packet.ok()?;
+                conn.stream.status = ok.status;
+
+                if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                    conn.stream.waiting.push_back(Waiting::Result);
+                }
+
+                Err(err_protocol!(
+                    "expected LOCAL INFILE request but the statement completed without one"
+                ))
+            }
+
+            _ => {
+                // the statement returned a result set; let the stream discard it
+                conn.stream.waiting.push_back(Waiting::Row);
+                conn.stream.skip_result_metadata(packet).await?;
+
+                Err(err_protocol!(
+                    "expected LOCAL INFILE request but the statement returned a result set"
+                ))
+            }
+        }
+    }
+
+    /// Returns the file name given in the `LOAD DATA LOCAL INFILE` statement, as requested
+    /// by the server.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Set the size of the packets the file contents are sent in.
+    ///
+    /// Data is buffered until a full packet is available, so this bounds the memory used for
+    /// buffering, at the cost of sending more packets.
+    ///
+    /// Defaults to the largest size the server accepts, as given by its `max_allowed_packet`
+    /// setting, up to 16 MiB. Larger values are capped to that size.
+    ///
+    /// Takes effect for the next packet that is started.
+    ///
+    /// ### Panics
+    /// If `size` is zero.
+    pub fn set_packet_size(&mut self, size: usize) -> &mut Self {
+        assert_ne!(size, 0, "packet size must be greater than zero");
+
+        self.packet_size = cmp::min(size, self.conn_mut().max_packet_size());
+        self
+    }
+
+    /// Returns the size of the packets the file contents are sent in.
+    ///
+    /// See [`set_packet_size`][Self::set_packet_size].
+    pub fn packet_size(&self) -> usize {
+        self.packet_size
+    }
+
+    /// Returns the number of bytes of file contents sent to the server so far.
+    ///
+    /// Data counts as sent once it is written to the connection as a complete packet,
+    /// which happens when a packet is full or on [`flush`][Self::flush]. Data that is
+    /// buffered in `MySqlLocalInfile` is not included.
+    ///
+    /// Combined with the expected size of the file, this can be used to report progress.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Returns the number of packets of file contents sent to the server so far.
+    ///
+    /// See [`bytes_sent`][Self::bytes_sent] for when data counts as sent.
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    /// Write a chunk of file contents.
+    ///
+    /// Data is buffered until a full packet is available; call [Self::flush] to send
+    /// a partial packet immediately.
+    pub async fn write(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            let written = poll_fn(|cx| self.poll_write_data(cx, data)).await?;
+            data = &data[written..];
+        }
+
+        Ok(())
+    }
+
+    /// Stream the contents of `source` to the server without requiring an intermediate buffer.
+    ///
+    /// `source` will be read to the end. Data is chunked into packets internally.
+    ///
+    /// ### Note: Completion Step Required
+    /// You must still call [Self::finish] to complete the process.
+    ///
+    /// ### Note: Runtime Features
+    /// This method uses the `AsyncRead` trait which is re-exported from either Tokio or `async-std`
+    /// depending on which runtime feature is used.
+    ///
+    /// The runtime features _used_ to be mutually exclusive, but are no longer.
+    /// If both `runtime-async-std` and `runtime-tokio` features are enabled, the Tokio version
+    /// takes precedent.
+    pub async fn send_from_reader(&mut self, mut source: impl AsyncRead + Unpin) -> Result<()> {
+        loop {
+            if self.buf.len() >= self.packet_size {
+                self.flush().await?;
+            }
+
+            let len = self.buf.len();
+            let chunk = cmp::min(self.packet_size - len, READ_CHUNK_SIZE);
+
+            let read = match () {
+                // Tokio lets us read into the buffer without zeroing first
+                #[cfg(feature = "_rt-tokio")]
+                _ => {
+                    use bytes::BufMut;
+
+                    self.buf.reserve(chunk);
+                    source.read_buf(&mut (&mut self.buf).limit(chunk)).await?
+                }
+                #[cfg(not(feature = "_rt-tokio"))]
+                _ => {
+                    self.buf.resize(len + chunk, 0);
+                    let read = source.read(&mut self.buf[len..]).await;
+                    self.buf.truncate(len + *read.as_ref().unwrap_or(&0));
+                    read?
+                }
+            };
+
+            if read == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Serialize `record` as one line of file contents, using the default `LOAD DATA` field
+    /// and line options.
+    ///
+    /// Fields are written in serialization order, i.e. declaration order for derived impls.
+    /// To match a different column order or field format, use
+    /// [`MySqlInfileCsvWriter::write_record`] instead.
+    ///
+    /// Requires the `json` Cargo feature flag, which enables `serde`.
+    #[cfg(feature = "serde")]
+    pub async fn send_record<T: serde::Serialize + ?Sized>(&mut self, record: &T) -> Result<()> {
+        MySqlInfileCsvWriter::new(self).write_record(record).await
+    }
+
+    /// Send any buffered data to the server.
+    pub async fn flush(&mut self) -> Result<()> {
+        poll_fn(|cx| self.poll_flush_data(cx)).await?;
+
+        Ok(())
+    }
+
+    /// Signal that the file is complete.
+    ///
+    /// The response of the server to the statement is returned, see [`MySqlInfileResult`].
+    pub async fn finish(mut self) -> Result<MySqlInfileResult> {
+        self.flush().await?;
+
+        let mut conn = self
+            .conn
+            .take()
+            .expect("MySqlLocalInfile::finish: conn taken illegally");
+
+        let ok = end_of_file(&mut conn).await?.ok()?;
+        conn.stream.status = ok.status;
+
+        if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+            conn.stream.waiting.push_back(Waiting::Result);
+        }
+
+        Ok(MySqlInfileResult {
+            rows_affected: ok.affected_rows,
+            last_insert_id: ok.last_insert_id,
+            warnings: ok.warnings,
+            status: ok.status,
+        })
+    }
+
+    /// Stop the upload early, discarding any data that is still buffered.
+    ///
+    /// The protocol has no way to cancel a `LOAD DATA LOCAL INFILE` statement, so this ends
+    /// the file at the last packet that was already sent. The server will load the rows it
+    /// received so far unless it rejects the statement; run the upload inside a transaction
+    /// to be able to roll those back.
+    ///
+    /// The connection is safe for reuse afterwards. An error from the server in response to
+    /// the truncated file is not returned; only _unexpected_ errors are.
+    pub async fn abort(mut self) -> Result<()> {
+        let mut conn = self
+            .conn
+            .take()
+            .expect("MySqlLocalInfile::abort: conn taken illegally");
+
+        self.buf.clear();
+
+        match end_of_file(&mut conn).await.and_then(Packet::ok) {
+            Ok(ok) => {
+                if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                    conn.stream.waiting.push_back(Waiting::Result);
+                }
+
+                Ok(())
+            }
+            Err(Error::Database(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn conn_mut(&mut self) -> &mut MySqlConnection {
+        self.conn
+            .as_deref_mut()
+            .expect("MySqlLocalInfile: conn taken")
+    }
+
+    /// Move the buffered data into a packet in the write buffer of the connection.
+    fn write_buffered_packet(&mut self) {
+        if self.buf.is_empty() {
+            return;
+        }
+
+        let buf = std::mem::take(&mut self.buf);
+        self.conn_mut().stream.write_packet(&buf[..]);
+
+        self.bytes_sent += buf.len() as u64;
+        self.packets_sent += 1;
+
+        // keep the allocation around for the next packet
+        self.buf = buf;
+        self.buf.clear();
+    }
+
+    fn poll_write_data(&mut self, cx: &mut Context<'_>, data: &[u8]) -> Poll<io::Result<usize>> {
+        if self.buf.len() >= self.packet_size {
+            self.write_buffered_packet();
+        }
+
+        // only ever keep one packet pending in the write buffer of the connection
+        if !self.conn_mut().stream.write_buffer().is_empty() {
+            ready!(self.conn_mut().stream.socket.poll_flush(cx))?;
+        }
+
+        let len = cmp::min(data.len(), self.packet_size - self.buf.len());
+        self.buf.extend_from_slice(&data[..len]);
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush_data(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.write_buffered_packet();
+        self.conn_mut().stream.socket.poll_flush(cx)
+    }
+}
+
+impl<C: DerefMut<Target = MySqlConnection>> Drop for MySqlLocalInfile<C> {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            // end the file at the last packet that was sent; like a rollback of a dropped
+            // transaction, the response is received the next time the connection is used
+            conn.stream.write_packet(&[][..]);
+            conn.stream.waiting.push_back(Waiting::Result);
+        }
+    }
+}
+
+/// The response of the server to a `LOAD DATA LOCAL INFILE` statement, returned by
+/// [`MySqlLocalInfile::finish`].
+///
+/// Rows that could not be loaded as-is, e.g. because a value was truncated or a line had too
+/// few fields, do not fail the statement but cause warnings instead. Check
+/// [`warnings`][Self::warnings] and run `SHOW WARNINGS` on the same connection for the details.
+#[derive(Debug, Clone)]
+pub struct MySqlInfileResult {
+    rows_affected: u64,
+    last_insert_id: u64,
+    warnings: u16,
+    status: Status,
+}
+
+impl MySqlInfileResult {
+    /// Returns the number of rows loaded.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Returns the first value generated for an `AUTO_INCREMENT` column, if any.
+    pub fn last_insert_id(&self) -> u64 {
+        self.last_insert_id
+    }
+
+    /// Returns the number of warnings raised by the statement.
+    pub fn warnings(&self) -> u16 {
+        self.warnings
+    }
+
+    /// Returns the status flags of the server after the statement.
+    ///
+    /// See [`SERVER_STATUS_flags_enum`](https://dev.mysql.com/doc/dev/mysql-server/latest/mysql__com_8h.html#a1d854e841086925be1883e4d7b4e8cad)
+    /// for their meaning.
+    pub fn status_flags(&self) -> u16 {
+        self.status.bits()
+    }
+}
+
+impl From<MySqlInfileResult> for MySqlQueryResult {
+    fn from(result: MySqlInfileResult) -> Self {
+        MySqlQueryResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: result.last_insert_id,
+            warning_count: result.warnings,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Signal the end of the file and receive the response to the `LOAD DATA` statement.
+async fn end_of_file(conn: &mut MySqlConnection) -> Result<Packet<Bytes>> {
+    // an empty packet marks the end of the file; this also writes out any packet that is
+    // still partially pending in the write buffer
+    conn.stream.write_packet(&[][..]);
+    conn.stream.flush().await?;
+
+    conn.stream.recv_packet().await
+}
+
+impl<C: DerefMut<Target = MySqlConnection> + Unpin> futures_io::AsyncWrite for MySqlLocalInfile<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_data(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_data(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_data(cx)
+    }
+}
+
+#[cfg(feature = "_rt-tokio")]
+impl<C: DerefMut<Target = MySqlConnection> + Unpin> tokio::io::AsyncWrite for MySqlLocalInfile<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_data(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_data(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_data(cx)
+    }
+}
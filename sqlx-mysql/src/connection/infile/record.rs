@@ -0,0 +1,517 @@
+use std::fmt::{self, Display};
+use std::io::Write;
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::error::{Error, Result};
+
+use super::csv::InfileFormat;
+
+/// Serialize `record` as one line of `LOAD DATA` input.
+///
+/// Structs and maps produce one field per entry, written in `columns` order if given or in
+/// serialization order otherwise. Tuples and sequences produce one field per element.
+pub(crate) fn write_record<T: Serialize + ?Sized>(
+    format: &InfileFormat,
+    columns: Option<&[String]>,
+    record: &T,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let mut fields = Vec::new();
+
+    record
+        .serialize(RecordSerializer {
+            fields: &mut fields,
+        })
+        .map_err(Error::encode)?;
+
+    if let Some(columns) = columns {
+        let mut ordered = Vec::with_capacity(columns.len());
+
+        for column in columns {
+            let pos = fields
+                .iter()
+                .position(|(name, _)| name.as_deref() == Some(column.as_str()))
+                .ok_or_else(|| {
+                    Error::encode(RecordError(format!(
+                        "record does not contain a field named {column:?}"
+                    )))
+                })?;
+
+            ordered.push(fields.swap_remove(pos));
+        }
+
+        fields = ordered;
+    }
+
+    for (i, (_, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            out.extend_from_slice(&format.field_terminator);
+        }
+
+        match value {
+            Some(text) => format.write_escaped(text, out),
+            None => format.write_null(out),
+        }
+    }
+
+    out.extend_from_slice(&format.line_terminator);
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub(crate) struct RecordError(String);
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl ser::Error for RecordError {
+    fn custom<T: Display>(msg: T) -> Self {
+        RecordError(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str) -> RecordError {
+    RecordError(format!("{what} cannot be written as a LOAD DATA field"))
+}
+
+/// A field name (if known) and its textual value (`None` for `NULL`).
+type Field = (Option<String>, Option<Vec<u8>>);
+
+/// Serializes the top-level record into a list of fields.
+struct RecordSerializer<'a> {
+    fields: &'a mut Vec<Field>,
+}
+
+/// Collects the fields of a compound record.
+struct FieldsSerializer<'a> {
+    fields: &'a mut Vec<Field>,
+    key: Option<String>,
+}
+
+impl FieldsSerializer<'_> {
+    fn push<T: Serialize + ?Sized>(
+        &mut self,
+        name: Option<String>,
+        value: &T,
+    ) -> Result<(), RecordError> {
+        let value = value.serialize(FieldSerializer)?;
+        self.fields.push((name, value));
+        Ok(())
+    }
+}
+
+macro_rules! record_must_be_compound {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(unsupported("a scalar value"))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for RecordSerializer<'a> {
+    type Ok = ();
+    type Error = RecordError;
+
+    type SerializeSeq = FieldsSerializer<'a>;
+    type SerializeTuple = FieldsSerializer<'a>;
+    type SerializeTupleStruct = FieldsSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), RecordError>;
+    type SerializeMap = FieldsSerializer<'a>;
+    type SerializeStruct = FieldsSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), RecordError>;
+
+    record_must_be_compound! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        Ok(FieldsSerializer {
+            fields: self.fields,
+            key: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RecordError> {
+        self.serialize_seq(None)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+}
+
+impl ser::SerializeSeq for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(None, value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), RecordError> {
+        let key = key
+            .serialize(FieldSerializer)?
+            .ok_or_else(|| RecordError("map keys cannot be null".into()))?;
+
+        self.key = Some(String::from_utf8_lossy(&key).into_owned());
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        let key = self.key.take();
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), RecordError> {
+        self.push(Some(key.to_owned()), value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+/// Serializes a single field into its unescaped textual representation.
+struct FieldSerializer;
+
+impl FieldSerializer {
+    fn display(value: impl Display) -> Result<Option<Vec<u8>>, RecordError> {
+        let mut text = Vec::new();
+        write!(text, "{value}").expect("BUG: writing to a Vec cannot fail");
+        Ok(Some(text))
+    }
+}
+
+impl ser::Serializer for FieldSerializer {
+    type Ok = Option<Vec<u8>>;
+    type Error = RecordError;
+
+    type SerializeSeq = Impossible<Self::Ok, RecordError>;
+    type SerializeTuple = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, RecordError>;
+    type SerializeMap = Impossible<Self::Ok, RecordError>;
+    type SerializeStruct = Impossible<Self::Ok, RecordError>;
+    type SerializeStructVariant = Impossible<Self::Ok, RecordError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, RecordError> {
+        Self::display(v as u8)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, RecordError> {
+        Ok(Some(v.as_bytes().to_vec()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, RecordError> {
+        Ok(Some(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, RecordError> {
+        // fieldless enums map naturally onto `ENUM` columns
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        Err(unsupported("a nested sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, RecordError> {
+        Err(unsupported("a nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RecordError> {
+        Err(unsupported("a nested tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RecordError> {
+        Err(unsupported("a nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, RecordError> {
+        Err(unsupported("a nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+}
+
+#[cfg(test)]
+fn record_to_string<T: Serialize + ?Sized>(columns: Option<&[String]>, record: &T) -> String {
+    let mut out = Vec::new();
+    write_record(&InfileFormat::default(), columns, record, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_write_record_tuple() {
+    assert_eq!(
+        record_to_string(None, &(1, "two\tthree", None::<i32>, true, 1.5)),
+        "1\ttwo\\tthree\t\\N\t1\t1.5\n"
+    );
+}
+
+#[test]
+fn test_write_record_map_with_columns() {
+    use std::collections::BTreeMap;
+
+    let record: BTreeMap<&str, Option<&str>> =
+        [("a", Some("first")), ("b", None), ("c", Some("third"))].into();
+
+    assert_eq!(record_to_string(None, &record), "first\t\\N\tthird\n");
+
+    let columns = ["c".to_owned(), "a".to_owned()];
+    assert_eq!(record_to_string(Some(&columns), &record), "third\tfirst\n");
+
+    let columns = ["d".to_owned()];
+    let mut out = Vec::new();
+    assert!(write_record(&InfileFormat::default(), Some(&columns), &record, &mut out).is_err());
+}
@@ -1,9 +1,18 @@
 use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
 
+pub use cancel::MySqlCancellationToken;
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
+pub use gtid::MySqlPoolGtidExt;
+pub(crate) use infile::LocalInfileHandler;
+pub use infile::{
+    MySqlInfileCsvWriter, MySqlInfileExt, MySqlInfileResult, MySqlInfileRow, MySqlLocalInfile,
+};
+pub use session_state::MySqlSessionState;
 pub(crate) use sqlx_core::connection::*;
 pub(crate) use stream::{MySqlStream, Waiting};
+pub use xa::MySqlXid;
 
 use crate::common::StatementCache;
 use crate::error::Error;
@@ -14,10 +23,21 @@ use crate::transaction::Transaction;
 use crate::{MySql, MySqlConnectOptions};
 
 mod auth;
+mod cancel;
+mod compression;
 mod establish;
 mod executor;
+#[cfg(feature = "gssapi")]
+mod gssapi;
+mod gtid;
+mod infile;
+mod reconnect;
+#[cfg(feature = "ldap-sasl")]
+mod sasl;
+mod session_state;
 mod stream;
 mod tls;
+mod xa;
 
 const MAX_PACKET_SIZE: u32 = 1024;
 
@@ -35,6 +55,25 @@ pub struct MySqlConnection {
     cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
 
     log_settings: LogSettings,
+
+    // answers `LOCAL INFILE` requests received while running a query
+    local_infile_handler: Option<LocalInfileHandler>,
+
+    // the options the connection was opened with, for the side connection of a cancellation
+    pub(crate) options: Arc<MySqlConnectOptions>,
+
+    // whether `SHOW WARNINGS` is run after a statement that raised warnings
+    fetch_warnings: bool,
+
+    // the statements to run again after a lost connection is opened again, if enabled
+    session_setup: Option<reconnect::SessionSetup>,
+
+    // whether the server responded to the running statement; a connection that is lost before,
+    // e.g. closed by the server after `wait_timeout`, did not execute it
+    response_received: bool,
+
+    // the largest packet the server accepts, as reported by `@@max_allowed_packet`
+    pub(crate) max_allowed_packet: usize,
 }
 
 impl Debug for MySqlConnection {
@@ -43,6 +82,29 @@ impl Debug for MySqlConnection {
     }
 }
 
+impl MySqlConnection {
+    /// The state of the session, as last reported by the server.
+    ///
+    /// See [`MySqlSessionState`] for which parts of the state are tracked.
+    pub fn session_state(&self) -> &MySqlSessionState {
+        &self.stream.session_state
+    }
+
+    /// Clear the flag returned by [`MySqlSessionState::is_changed`], e.g. after resetting the
+    /// session.
+    pub fn clear_session_changed(&mut self) {
+        self.stream.session_state.clear_changed();
+    }
+
+    async fn ping_once(&mut self) -> Result<(), Error> {
+        self.stream.wait_until_ready().await?;
+        self.stream.send_packet(Ping).await?;
+        self.stream.recv_ok().await?;
+
+        Ok(())
+    }
+}
+
 impl Connection for MySqlConnection {
     type Database = MySql;
 
@@ -66,11 +128,16 @@ impl Connection for MySqlConnection {
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
-            self.stream.wait_until_ready().await?;
-            self.stream.send_packet(Ping).await?;
-            self.stream.recv_ok().await?;
+            match self.ping_once().await {
+                Err(e) if self.can_reconnect() && reconnect::is_connection_lost(&e) => {
+                    log::warn!("reconnecting after the connection was lost: {}", e);
 
-            Ok(())
+                    self.reconnect().await?;
+                    self.ping_once().await
+                }
+
+                result => result,
+            }
         })
     }
 
@@ -0,0 +1,187 @@
+//! Opening a lost connection again, see [`MySqlConnectOptions::auto_reconnect`].
+
+use std::io;
+use std::mem;
+use std::sync::Arc;
+
+use futures_util::TryStreamExt;
+
+use crate::arguments::MySqlArguments;
+use crate::connection::MySqlConnection;
+use crate::error::Error;
+use crate::protocol::response::Status;
+#[cfg(doc)]
+use crate::MySqlConnectOptions;
+use crate::MySqlDatabaseError;
+
+// ER_CLIENT_INTERACTION_TIMEOUT, sent by MySQL 8.0.24 and later before it closes a connection
+// that was idle for longer than `wait_timeout`
+const ER_CLIENT_INTERACTION_TIMEOUT: u16 = 4031;
+
+/// The statements that set up the session of a connection, run again on the new connection.
+#[derive(Debug, Default)]
+pub(crate) struct SessionSetup {
+    statements: Vec<(String, Option<MySqlArguments>)>,
+
+    // whether the connection only ran statements that set up the session so far
+    recording: bool,
+}
+
+impl MySqlConnection {
+    /// Start recording the session setup, once the connection is configured.
+    pub(crate) fn start_session_setup(&mut self) {
+        self.session_setup = self.options.auto_reconnect.then(|| SessionSetup {
+            statements: Vec::new(),
+            recording: true,
+        });
+    }
+
+    /// Whether a statement or ping that failed because the connection was lost may open the
+    /// connection again, and try once more.
+    pub(crate) fn can_reconnect(&self) -> bool {
+        // the changes of a transaction are lost with the connection, which includes a
+        // transaction started with a statement rather than `Connection::begin()`
+        self.session_setup.is_some()
+            && self.transaction_depth == 0
+            && !self.stream.status.contains(Status::SERVER_STATUS_IN_TRANS)
+    }
+
+    /// Whether the statements that complete are recorded as part of the session setup.
+    pub(crate) fn is_recording_session_setup(&self) -> bool {
+        self.session_setup
+            .as_ref()
+            .map_or(false, |setup| setup.recording)
+    }
+
+    /// Record a statement that completed, as part of the session setup if no other statement
+    /// ran before it.
+    pub(crate) fn record_session_setup(&mut self, sql: &str, arguments: Option<&MySqlArguments>) {
+        let Some(setup) = self.session_setup.as_mut().filter(|setup| setup.recording) else {
+            return;
+        };
+
+        // reading the state of the session, e.g. with `SHOW WARNINGS`, does not end the setup
+        if starts_with_keyword(sql, "SHOW") {
+            return;
+        }
+
+        // long data is read only once
+        let replayable = arguments.map_or(true, |arguments| arguments.long_data.is_empty());
+
+        if replayable && is_session_setup(sql) {
+            setup.statements.push((sql.to_owned(), arguments.cloned()));
+        } else {
+            setup.recording = false;
+        }
+    }
+
+    /// Replace the lost connection with a new one, configured the same way.
+    pub(crate) async fn reconnect(&mut self) -> Result<(), Error> {
+        let options = Arc::clone(&self.options);
+
+        let mut conn = MySqlConnection::establish(&options).await?;
+        conn.configure(&options).await?;
+
+        let statements = self
+            .session_setup
+            .as_ref()
+            .map_or(&[][..], |setup| &setup.statements);
+
+        for (sql, arguments) in statements {
+            let s = conn.run(sql, arguments.clone(), false).await?;
+            futures_util::pin_mut!(s);
+
+            while s.try_next().await?.is_some() {}
+        }
+
+        // the socket of the lost connection is closed, so it is dropped without a `COM_QUIT`
+        let lost = mem::replace(self, conn);
+
+        self.options = lost.options;
+        self.session_setup = lost.session_setup;
+        self.local_infile_handler = lost.local_infile_handler;
+        self.fetch_warnings = lost.fetch_warnings;
+
+        Ok(())
+    }
+}
+
+/// Whether `error` means that the connection to the server was lost.
+pub(crate) fn is_connection_lost(error: &Error) -> bool {
+    match error {
+        Error::Io(e) => matches!(
+            e.kind(),
+            io::ErrorKind::BrokenPipe
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::UnexpectedEof
+        ),
+
+        Error::Database(e) => e
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .map_or(false, |e| e.number() == ER_CLIENT_INTERACTION_TIMEOUT),
+
+        _ => false,
+    }
+}
+
+// whether `sql` is a single statement that sets session variables, e.g. `SET time_zone = ?`
+fn is_session_setup(sql: &str) -> bool {
+    if !starts_with_keyword(sql, "SET") {
+        return false;
+    }
+
+    // a statement after the `SET`
+    if sql.trim_end().trim_end_matches(';').contains(';') {
+        return false;
+    }
+
+    let sql = sql.to_ascii_uppercase();
+    let next_word = sql.split_whitespace().nth(1).unwrap_or_default();
+
+    // statements that only affect the next transaction, or outlive the session
+    let not_session = matches!(
+        next_word,
+        "TRANSACTION" | "GLOBAL" | "PERSIST" | "PERSIST_ONLY" | "PASSWORD" | "DEFAULT"
+    );
+
+    !not_session && !sql.contains("@@GLOBAL.") && !sql.contains("@@PERSIST")
+}
+
+fn starts_with_keyword(sql: &str, keyword: &str) -> bool {
+    sql.split_whitespace()
+        .next()
+        .map_or(false, |word| word.eq_ignore_ascii_case(keyword))
+}
+
+#[test]
+fn test_is_session_setup() {
+    assert!(is_session_setup("SET time_zone = 'Europe/Amsterdam'"));
+    assert!(is_session_setup("set @tenant = ?;"));
+    assert!(is_session_setup(
+        "SET SESSION sql_mode = 'ANSI', NAMES utf8mb4"
+    ));
+
+    assert!(!is_session_setup("SELECT 1"));
+    assert!(!is_session_setup(
+        "SET TRANSACTION ISOLATION LEVEL SERIALIZABLE"
+    ));
+    assert!(!is_session_setup("SET GLOBAL max_connections = 1000"));
+    assert!(!is_session_setup("SET @@global.max_connections = 1000"));
+    assert!(!is_session_setup("SET @a = 1; DELETE FROM t"));
+}
+
+#[test]
+fn test_is_connection_lost() {
+    assert!(is_connection_lost(&Error::Io(
+        io::ErrorKind::BrokenPipe.into()
+    )));
+    assert!(is_connection_lost(&Error::Io(
+        io::ErrorKind::UnexpectedEof.into()
+    )));
+    assert!(!is_connection_lost(&Error::Io(
+        io::ErrorKind::TimedOut.into()
+    )));
+    assert!(!is_connection_lost(&Error::RowNotFound));
+}
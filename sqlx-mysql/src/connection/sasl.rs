@@ -0,0 +1,253 @@
+//! The SCRAM mechanisms of the `authentication_ldap_sasl_client` plugin of MySQL Enterprise,
+//! which authenticates against an LDAP server.
+//!
+//! <https://dev.mysql.com/doc/refman/8.0/en/ldap-pluggable-authentication.html>
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::Sha256;
+use stringprep::saslprep;
+
+use crate::connection::auth::recv_auth_more_data;
+use crate::connection::stream::MySqlStream;
+use crate::error::Error;
+
+// the client does not support channel binding
+const GS2_HEADER: &str = "n,,";
+
+/// Run the SASL exchange for `mechanism`, the first message of the plugin on the server.
+///
+/// The server sends an OK packet afterwards.
+pub(crate) async fn authenticate(
+    stream: &mut MySqlStream,
+    mechanism: &[u8],
+    username: &str,
+    password: &str,
+) -> Result<(), Error> {
+    let mechanism = mechanism.strip_suffix(b"\0").unwrap_or(mechanism);
+
+    match mechanism {
+        b"SCRAM-SHA-1" => scram::<Sha1>(stream, username, password).await,
+        b"SCRAM-SHA-256" => scram::<Sha256>(stream, username, password).await,
+
+        // `GSSAPI` needs the security layer negotiation of RFC 4752 on top of Kerberos
+        _ => Err(err_protocol!(
+            "unsupported SASL authentication mechanism: {}",
+            String::from_utf8_lossy(mechanism)
+        )),
+    }
+}
+
+// https://www.rfc-editor.org/rfc/rfc5802
+async fn scram<D: Digest + BlockSizeUser>(
+    stream: &mut MySqlStream,
+    username: &str,
+    password: &str,
+) -> Result<(), Error> {
+    let client_nonce = gen_nonce();
+    let client_first_message_bare = client_first_message_bare(username, &client_nonce)?;
+
+    stream.write_packet(format!("{GS2_HEADER}{client_first_message_bare}").as_bytes());
+    stream.flush().await?;
+
+    let server_first_message = recv_auth_more_data(stream).await?;
+    let server_first_message =
+        std::str::from_utf8(&server_first_message).map_err(Error::protocol)?;
+
+    let (client_final_message, server_signature) = client_final_message::<D>(
+        password,
+        &client_first_message_bare,
+        &client_nonce,
+        server_first_message,
+    )?;
+
+    stream.write_packet(client_final_message.as_bytes());
+    stream.flush().await?;
+
+    let server_final_message = recv_auth_more_data(stream).await?;
+
+    verify_server_final_message(&server_final_message, &server_signature)
+}
+
+// client-first-message-bare = username "," nonce
+fn client_first_message_bare(username: &str, nonce: &str) -> Result<String, Error> {
+    let username = saslprep(username).map_err(|_| {
+        Error::Configuration("the username is invalid for SCRAM authentication".into())
+    })?;
+
+    // "=" and "," have to be escaped in a saslname
+    let username = username.replace('=', "=3D").replace(',', "=2C");
+
+    Ok(format!("n={username},r={nonce}"))
+}
+
+/// Compute the client-final-message for the server-first-message, and the signature the
+/// server has to answer with.
+fn client_final_message<D: Digest + BlockSizeUser>(
+    password: &str,
+    client_first_message_bare: &str,
+    client_nonce: &str,
+    server_first_message: &str,
+) -> Result<(String, Vec<u8>), Error> {
+    let mut nonce = None;
+    let mut salt = None;
+    let mut iterations = None;
+
+    for attribute in server_first_message.split(',') {
+        match attribute.split_once('=') {
+            Some(("r", value)) => nonce = Some(value),
+            Some(("s", value)) => {
+                salt = Some(BASE64_STANDARD.decode(value).map_err(Error::protocol)?)
+            }
+            Some(("i", value)) => iterations = Some(value.parse::<u32>().map_err(Error::protocol)?),
+            _ => {}
+        }
+    }
+
+    let (Some(nonce), Some(salt), Some(iterations)) = (nonce, salt, iterations) else {
+        return Err(err_protocol!(
+            "invalid SCRAM server-first-message: {:?}",
+            server_first_message
+        ));
+    };
+
+    if !nonce.starts_with(client_nonce) || iterations == 0 {
+        return Err(err_protocol!(
+            "invalid SCRAM server-first-message: {:?}",
+            server_first_message
+        ));
+    }
+
+    let password = saslprep(password).map_err(|_| {
+        Error::Configuration("the password is invalid for SCRAM authentication".into())
+    })?;
+
+    // SaltedPassword := Hi(Normalize(password), salt, i)
+    let salted_password = hi::<D>(password.as_bytes(), &salt, iterations)?;
+
+    // ClientKey := HMAC(SaltedPassword, "Client Key")
+    let client_key = hmac::<D>(&salted_password, b"Client Key")?;
+
+    // StoredKey := H(ClientKey)
+    let stored_key = D::digest(&client_key);
+
+    // client-final-message-without-proof = channel-binding "," nonce
+    let mut client_final_message = String::from("c=");
+    BASE64_STANDARD.encode_string(GS2_HEADER, &mut client_final_message);
+    client_final_message.push_str(",r=");
+    client_final_message.push_str(nonce);
+
+    // AuthMessage := client-first-message-bare + "," + server-first-message + "," +
+    //                client-final-message-without-proof
+    let auth_message =
+        format!("{client_first_message_bare},{server_first_message},{client_final_message}");
+
+    // ClientSignature := HMAC(StoredKey, AuthMessage)
+    let client_signature = hmac::<D>(&stored_key, auth_message.as_bytes())?;
+
+    // ClientProof := ClientKey XOR ClientSignature
+    let client_proof: Vec<u8> = client_key
+        .iter()
+        .zip(&client_signature)
+        .map(|(&a, &b)| a ^ b)
+        .collect();
+
+    client_final_message.push_str(",p=");
+    BASE64_STANDARD.encode_string(client_proof, &mut client_final_message);
+
+    // ServerSignature := HMAC(HMAC(SaltedPassword, "Server Key"), AuthMessage)
+    let server_key = hmac::<D>(&salted_password, b"Server Key")?;
+    let server_signature = hmac::<D>(&server_key, auth_message.as_bytes())?;
+
+    Ok((client_final_message, server_signature))
+}
+
+fn verify_server_final_message(message: &[u8], server_signature: &[u8]) -> Result<(), Error> {
+    let message = String::from_utf8_lossy(message);
+
+    if let Some(error) = message.strip_prefix("e=") {
+        return Err(err_protocol!("SCRAM authentication failed: {}", error));
+    }
+
+    let verifier = message
+        .strip_prefix("v=")
+        .and_then(|v| BASE64_STANDARD.decode(v).ok())
+        .ok_or_else(|| err_protocol!("invalid SCRAM server-final-message: {:?}", message))?;
+
+    // authentication is only considered valid if the server knows the password as well
+    if verifier != server_signature {
+        return Err(err_protocol!(
+            "SCRAM authentication failed: the signature of the server does not match"
+        ));
+    }
+
+    Ok(())
+}
+
+// Hi(str, salt, i)
+fn hi<D: Digest + BlockSizeUser>(s: &[u8], salt: &[u8], iter_count: u32) -> Result<Vec<u8>, Error> {
+    let mut u = hmac::<D>(s, &[salt, &1u32.to_be_bytes()].concat())?;
+    let mut hi = u.clone();
+
+    for _ in 1..iter_count {
+        u = hmac::<D>(s, &u)?;
+        hi.iter_mut().zip(&u).for_each(|(a, &b)| *a ^= b);
+    }
+
+    Ok(hi)
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut mac = SimpleHmac::<D>::new_from_slice(key).map_err(Error::protocol)?;
+    mac.update(data);
+
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+// nonce is a sequence of random printable bytes, except ","
+fn gen_nonce() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..32)
+        .map(|_| loop {
+            let c = rng.gen_range(0x21..0x7F_u8);
+
+            if c != b',' {
+                return c as char;
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_scram_sha1() {
+    // the example exchange of RFC 5802
+    let client_first_message_bare =
+        client_first_message_bare("user", "fyko+d2lbbFgONRv9qkxdawL").unwrap();
+
+    assert_eq!(
+        client_first_message_bare,
+        "n=user,r=fyko+d2lbbFgONRv9qkxdawL"
+    );
+
+    let (client_final_message, server_signature) = client_final_message::<Sha1>(
+        "pencil",
+        &client_first_message_bare,
+        "fyko+d2lbbFgONRv9qkxdawL",
+        "r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,s=QSXCR+Q6sek8bf92,i=4096",
+    )
+    .unwrap();
+
+    assert_eq!(
+        client_final_message,
+        "c=biws,r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,p=v0X8v3Bz2T0CJGbJQyF0X+HI4Ts="
+    );
+
+    verify_server_final_message(b"v=rmF9pqV8S7suAoZWja4dJRkFsKQ=", &server_signature).unwrap();
+    verify_server_final_message(b"v=AAAAAAAAAAAAAAAAAAAAAAAAAAA=", &server_signature).unwrap_err();
+    verify_server_final_message(b"e=invalid-proof", &server_signature).unwrap_err();
+}
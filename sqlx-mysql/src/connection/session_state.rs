@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use bytes::{Buf, Bytes};
+
+use crate::error::Error;
+use crate::io::MySqlBufExt;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/mysql__com_8h.html#a4ed4bd8ae4513ab9af4a3a9e29cc3f9a
+const SESSION_TRACK_SYSTEM_VARIABLES: u8 = 0;
+const SESSION_TRACK_SCHEMA: u8 = 1;
+const SESSION_TRACK_STATE_CHANGE: u8 = 2;
+const SESSION_TRACK_GTIDS: u8 = 3;
+const SESSION_TRACK_TRANSACTION_CHARACTERISTICS: u8 = 4;
+const SESSION_TRACK_TRANSACTION_STATE: u8 = 5;
+
+/// The state of the session of a connection, as last reported by the server.
+///
+/// The server reports changes of the session after each statement, for the parts of the state
+/// that are tracked. Which parts those are is set with the server variables
+/// `session_track_system_variables`, `session_track_schema`, `session_track_state_change`,
+/// `session_track_gtids` and `session_track_transaction_info`, which can also be set for a
+/// single session:
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+/// use sqlx::Executor;
+///
+/// conn.execute("SET SESSION session_track_gtids = OWN_GTID").await?;
+/// conn.execute("INSERT INTO users (name) VALUES ('Alice')").await?;
+///
+/// // wait for this transaction on a replica, to read our own write there
+/// if let Some(gtids) = conn.session_state().gtids() {
+///     println!("SELECT WAIT_FOR_EXECUTED_GTID_SET('{gtids}')");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Parts that are not tracked are `None`, or in the case of system variables, missing.
+/// Servers that do not support session tracking, like MySQL before 5.7 and some MariaDB
+/// versions, never report any changes.
+#[derive(Debug, Clone, Default)]
+pub struct MySqlSessionState {
+    system_variables: BTreeMap<String, String>,
+    schema: Option<String>,
+    changed: bool,
+    gtids: Option<String>,
+    transaction_characteristics: Option<String>,
+    transaction_state: Option<String>,
+}
+
+impl MySqlSessionState {
+    /// The value of a tracked system variable, if it was reported since the connection was
+    /// opened.
+    ///
+    /// By default, MySQL tracks `autocommit`, `time_zone` and the `character_set_*` variables.
+    pub fn system_variable(&self, name: &str) -> Option<&str> {
+        self.system_variables.get(name).map(String::as_str)
+    }
+
+    /// All tracked system variables reported since the connection was opened, by name.
+    pub fn system_variables(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.system_variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The current default schema, if `session_track_schema` is enabled and it was reported.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Whether the server reported any change of the session state, e.g. of a variable or a
+    /// temporary table, since the flag was cleared with
+    /// [`MySqlConnection::clear_session_changed`](crate::MySqlConnection::clear_session_changed).
+    ///
+    /// Requires `session_track_state_change`, which is disabled by default. A pool can use this
+    /// to discard connections whose session was modified.
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// The GTIDs of the last transaction committed in the session, if `session_track_gtids`
+    /// is enabled.
+    pub fn gtids(&self) -> Option<&str> {
+        self.gtids.as_deref()
+    }
+
+    /// The statements that recreate the characteristics of the current transaction, such as
+    /// its isolation level, if `session_track_transaction_info` is `CHARACTERISTICS`.
+    pub fn transaction_characteristics(&self) -> Option<&str> {
+        self.transaction_characteristics.as_deref()
+    }
+
+    /// The state of the current transaction as eight flag characters, if
+    /// `session_track_transaction_info` is enabled, e.g. `T_______` for an explicitly started
+    /// transaction that didn't do anything yet.
+    pub fn transaction_state(&self) -> Option<&str> {
+        self.transaction_state.as_deref()
+    }
+
+    pub(crate) fn clear_changed(&mut self) {
+        self.changed = false;
+    }
+
+    /// Apply the session state changes of an OK packet.
+    pub(crate) fn apply(&mut self, mut changes: Bytes) -> Result<(), Error> {
+        while changes.has_remaining() {
+            let kind = changes.get_u8();
+            let mut data = get_bytes_lenenc(&mut changes)?;
+
+            match kind {
+                SESSION_TRACK_SYSTEM_VARIABLES => {
+                    let name = get_str_lenenc(&mut data)?;
+                    let value = get_str_lenenc(&mut data)?;
+
+                    self.system_variables.insert(name, value);
+                }
+
+                SESSION_TRACK_SCHEMA => {
+                    self.schema = Some(get_str_lenenc(&mut data)?);
+                }
+
+                SESSION_TRACK_STATE_CHANGE => {
+                    self.changed |= get_str_lenenc(&mut data)? == "1";
+                }
+
+                SESSION_TRACK_GTIDS => {
+                    if !data.has_remaining() {
+                        return Err(err_protocol!("missing GTID encoding in session state"));
+                    }
+
+                    // the encoding specification, of which only 0 exists
+                    data.advance(1);
+                    self.gtids = Some(get_str_lenenc(&mut data)?);
+                }
+
+                SESSION_TRACK_TRANSACTION_CHARACTERISTICS => {
+                    self.transaction_characteristics = Some(get_str_lenenc(&mut data)?);
+                }
+
+                SESSION_TRACK_TRANSACTION_STATE => {
+                    self.transaction_state = Some(get_str_lenenc(&mut data)?);
+                }
+
+                // added in later versions
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// like `MySqlBufExt::get_bytes_lenenc()`, but fails instead of panicking on incomplete data
+fn get_bytes_lenenc(buf: &mut Bytes) -> Result<Bytes, Error> {
+    if !buf.has_remaining() {
+        return Err(err_protocol!("unexpected end of session state"));
+    }
+
+    let prefix_len = match buf[0] {
+        0xfc => 3,
+        0xfd => 4,
+        0xfe => 9,
+        _ => 1,
+    };
+
+    if buf.len() < prefix_len {
+        return Err(err_protocol!("unexpected end of session state"));
+    }
+
+    let len = buf.clone().get_uint_lenenc() as usize;
+
+    if buf.len() - prefix_len < len {
+        return Err(err_protocol!("unexpected end of session state"));
+    }
+
+    Ok(buf.get_bytes_lenenc())
+}
+
+fn get_str_lenenc(buf: &mut Bytes) -> Result<String, Error> {
+    let bytes = get_bytes_lenenc(buf)?;
+
+    String::from_utf8(bytes.to_vec())
+        .map_err(|e| err_protocol!("invalid UTF-8 in session state: {}", e))
+}
+
+#[test]
+fn test_apply_session_state() {
+    let mut state = MySqlSessionState::default();
+
+    // one change of each kind, followed by one of an unknown kind
+    let changes = Bytes::from_static(
+        b"\x00\x11\x09time_zone\x06+00:00\
+          \x01\x05\x04test\
+          \x02\x02\x011\
+          \x03\x29\x00\x273e11fa47-71ca-11e1-9e33-c80aa9429562:23\
+          \x05\x09\x08T_______\
+          \x2a\x01\x00",
+    );
+
+    state.apply(changes).unwrap();
+
+    assert_eq!(state.system_variable("time_zone"), Some("+00:00"));
+    assert_eq!(state.schema(), Some("test"));
+    assert!(state.is_changed());
+    assert_eq!(
+        state.gtids(),
+        Some("3e11fa47-71ca-11e1-9e33-c80aa9429562:23")
+    );
+    assert_eq!(state.transaction_state(), Some("T_______"));
+
+    state.clear_changed();
+    assert!(!state.is_changed());
+
+    assert!(state.apply(Bytes::from_static(b"\x01\x05\x04te")).is_err());
+}
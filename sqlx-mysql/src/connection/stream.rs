@@ -1,13 +1,17 @@
 use std::collections::VecDeque;
 use std::ops::{Deref, DerefMut};
 
+use bytes::buf::Chain;
 use bytes::{Buf, Bytes};
 
 use crate::collation::{CharSet, Collation};
+use crate::connection::compression::Compression;
+use crate::connection::MySqlSessionState;
 use crate::error::Error;
 use crate::io::MySqlBufExt;
 use crate::io::{Decode, Encode};
 use crate::net::{BufferedSocket, Socket};
+use crate::protocol::auth::AuthPlugin;
 use crate::protocol::response::{EofPacket, ErrPacket, OkPacket, Status};
 use crate::protocol::{Capabilities, Packet};
 use crate::{MySqlConnectOptions, MySqlDatabaseError};
@@ -22,6 +26,18 @@ pub struct MySqlStream<S = Box<dyn Socket>> {
     pub(crate) charset: CharSet,
     pub(crate) collation: Collation,
     pub(crate) is_tls: bool,
+    pub(crate) compression: Option<Compression>,
+    pub(crate) session_state: MySqlSessionState,
+
+    // the status flags of the last OK or EOF packet, e.g. whether a transaction is open
+    pub(crate) status: Status,
+
+    // the id of the connection on the server, as returned by `CONNECTION_ID()`
+    pub(crate) connection_id: u32,
+
+    // the plugin and nonce of the initial handshake, which `COM_CHANGE_USER` authenticates with
+    pub(crate) auth_plugin: Option<AuthPlugin>,
+    pub(crate) auth_nonce: Chain<Bytes, Bytes>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -47,16 +63,28 @@ impl<S: Socket> MySqlStream<S> {
             | Capabilities::TRANSACTIONS
             | Capabilities::SECURE_CONNECTION
             | Capabilities::PLUGIN_AUTH_LENENC_DATA
-            | Capabilities::MULTI_STATEMENTS
             | Capabilities::MULTI_RESULTS
             | Capabilities::PLUGIN_AUTH
             | Capabilities::PS_MULTI_RESULTS
+            | Capabilities::SESSION_TRACK
+            | Capabilities::QUERY_ATTRIBUTES
+            | Capabilities::CONNECT_ATTRS
             | Capabilities::SSL;
 
         if options.database.is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
         }
 
+        if options.multi_statements {
+            capabilities |= Capabilities::MULTI_STATEMENTS;
+        }
+
+        if options.enable_local_infile {
+            capabilities |= Capabilities::LOCAL_FILES;
+        }
+
+        capabilities |= options.compression.capabilities();
+
         Self {
             waiting: VecDeque::new(),
             capabilities,
@@ -66,6 +94,12 @@ impl<S: Socket> MySqlStream<S> {
             charset,
             socket: BufferedSocket::new(socket),
             is_tls: false,
+            compression: None,
+            session_state: MySqlSessionState::default(),
+            status: Status::empty(),
+            connection_id: 0,
+            auth_plugin: None,
+            auth_nonce: Bytes::new().chain(Bytes::new()),
         }
     }
 
@@ -80,6 +114,8 @@ impl<S: Socket> MySqlStream<S> {
 
                 if !packet.is_empty() && packet[0] == 0xfe && packet.len() < 9 {
                     let eof = packet.eof(self.capabilities)?;
+                    self.session_state.apply(eof.session_state)?;
+                    self.status = eof.status;
 
                     if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         *self.waiting.front_mut().unwrap() = Waiting::Result;
@@ -94,6 +130,8 @@ impl<S: Socket> MySqlStream<S> {
 
                 if !packet.is_empty() && (packet[0] == 0x00 || packet[0] == 0xff) {
                     let ok = packet.ok()?;
+                    self.session_state.apply(ok.session_state)?;
+                    self.status = ok.status;
 
                     if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         self.waiting.pop_front();
@@ -112,7 +150,7 @@ impl<S: Socket> MySqlStream<S> {
     where
         T: Encode<'en, Capabilities>,
     {
-        self.sequence_id = 0;
+        self.reset_sequence_id();
         self.write_packet(payload);
         self.flush().await?;
         Ok(())
@@ -122,8 +160,30 @@ impl<S: Socket> MySqlStream<S> {
     where
         T: Encode<'en, Capabilities>,
     {
-        self.socket
-            .write_with(Packet(payload), (self.capabilities, &mut self.sequence_id));
+        let context = (self.capabilities, &mut self.sequence_id);
+
+        match &mut self.compression {
+            Some(compression) => {
+                compression.write_packet(&mut self.socket, Packet(payload), context)
+            }
+            None => self.socket.write_with(Packet(payload), context),
+        }
+    }
+
+    /// Reset the sequence ids before writing the first packet of a command.
+    pub(crate) fn reset_sequence_id(&mut self) {
+        self.sequence_id = 0;
+
+        if let Some(compression) = &mut self.compression {
+            compression.sequence_id = 0;
+        }
+    }
+
+    async fn read_payload(&mut self, len: usize) -> Result<Bytes, Error> {
+        match &mut self.compression {
+            Some(compression) => compression.read(&mut self.socket, len).await,
+            None => self.socket.read(len).await,
+        }
     }
 
     // receive the next packet from the database server
@@ -132,16 +192,15 @@ impl<S: Socket> MySqlStream<S> {
         // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
         // https://mariadb.com/kb/en/library/0-packet/#standard-packet
 
-        let mut header: Bytes = self.socket.read(4).await?;
+        let mut header: Bytes = self.read_payload(4).await?;
 
         let packet_size = header.get_uint_le(3) as usize;
         let sequence_id = header.get_u8();
 
         self.sequence_id = sequence_id.wrapping_add(1);
 
-        let payload: Bytes = self.socket.read(packet_size).await?;
+        let payload: Bytes = self.read_payload(packet_size).await?;
 
-        // TODO: packet compression
         // TODO: packet joining
 
         if payload
@@ -180,7 +239,10 @@ impl<S: Socket> MySqlStream<S> {
         }
     }
 
-    async fn skip_result_metadata(&mut self, mut packet: Packet<Bytes>) -> Result<(), Error> {
+    pub(crate) async fn skip_result_metadata(
+        &mut self,
+        mut packet: Packet<Bytes>,
+    ) -> Result<(), Error> {
         let num_columns: u64 = packet.get_uint_lenenc(); // column count
 
         for _ in 0..num_columns {
@@ -202,6 +264,12 @@ impl<S: Socket> MySqlStream<S> {
             charset: self.charset,
             collation: self.collation,
             is_tls: self.is_tls,
+            compression: self.compression,
+            session_state: self.session_state,
+            status: self.status,
+            connection_id: self.connection_id,
+            auth_plugin: self.auth_plugin,
+            auth_nonce: self.auth_nonce,
         }
     }
 }
@@ -4,8 +4,10 @@ use crate::error::Error;
 use crate::net::tls::TlsConfig;
 use crate::net::{tls, BufferedSocket, Socket, WithSocket};
 use crate::protocol::connect::SslRequest;
+use crate::protocol::response::Status;
 use crate::protocol::Capabilities;
 use crate::{MySqlConnectOptions, MySqlSslMode};
+use bytes::{Buf, Bytes};
 use std::collections::VecDeque;
 
 struct MapStream {
@@ -66,6 +68,7 @@ pub(super) async fn maybe_upgrade<S: Socket>(
         root_cert_path: options.ssl_ca.as_ref(),
         client_cert_path: options.ssl_client_cert.as_ref(),
         client_key_path: options.ssl_client_key.as_ref(),
+        session_cache: &options.tls_session_cache,
     };
 
     // Request TLS upgrade
@@ -104,6 +107,14 @@ impl WithSocket for MapStream {
             charset: self.charset,
             collation: self.collation,
             is_tls: true,
+            // compression is only enabled once the connection is authenticated
+            compression: None,
+            session_state: Default::default(),
+            status: Status::empty(),
+            // set once the connection is authenticated
+            connection_id: 0,
+            auth_plugin: None,
+            auth_nonce: Bytes::new().chain(Bytes::new()),
         }
     }
 }
@@ -0,0 +1,262 @@
+//! XA transactions, the two-phase commit of MySQL for transactions that span several resources.
+//!
+//! <https://dev.mysql.com/doc/refman/8.0/en/xa.html>
+
+use std::fmt::Write;
+
+use crate::connection::MySqlConnection;
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::row::Row;
+
+// the largest global transaction id and branch qualifier
+const MAX_PART_LEN: usize = 64;
+
+/// The identifier of an XA transaction branch: the global transaction id, shared by all
+/// resources that take part in the transaction, a branch qualifier unique to this resource,
+/// and a format id that identifies the scheme of the two.
+///
+/// The transaction manager which coordinates the resources chooses the id, and has to store
+/// it before preparing any branch, so it can commit or roll back the branches that were
+/// prepared after a crash; see [`MySqlConnection::xa_recover`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MySqlXid {
+    gtrid: Vec<u8>,
+    bqual: Vec<u8>,
+    format_id: u32,
+}
+
+impl MySqlXid {
+    /// The id of a branch of the global transaction `gtrid`, with an empty branch qualifier and
+    /// format id 1, the default of MySQL.
+    ///
+    /// Returns an error if `gtrid` is empty or longer than 64 bytes.
+    pub fn new(gtrid: impl Into<Vec<u8>>) -> Result<Self> {
+        let gtrid = gtrid.into();
+
+        if gtrid.is_empty() || gtrid.len() > MAX_PART_LEN {
+            return Err(Error::Configuration(
+                format!("XA global transaction id must have 1 to {MAX_PART_LEN} bytes").into(),
+            ));
+        }
+
+        Ok(Self {
+            gtrid,
+            bqual: Vec::new(),
+            format_id: 1,
+        })
+    }
+
+    /// Sets the branch qualifier, which distinguishes the branches of one global transaction.
+    ///
+    /// Returns an error if `bqual` is longer than 64 bytes.
+    pub fn with_branch(mut self, bqual: impl Into<Vec<u8>>) -> Result<Self> {
+        let bqual = bqual.into();
+
+        if bqual.len() > MAX_PART_LEN {
+            return Err(Error::Configuration(
+                format!("XA branch qualifier must have at most {MAX_PART_LEN} bytes").into(),
+            ));
+        }
+
+        self.bqual = bqual;
+        Ok(self)
+    }
+
+    /// Sets the format id.
+    pub fn with_format_id(mut self, format_id: u32) -> Self {
+        self.format_id = format_id;
+        self
+    }
+
+    /// Returns the global transaction id.
+    pub fn gtrid(&self) -> &[u8] {
+        &self.gtrid
+    }
+
+    /// Returns the branch qualifier.
+    pub fn bqual(&self) -> &[u8] {
+        &self.bqual
+    }
+
+    /// Returns the format id.
+    pub fn format_id(&self) -> u32 {
+        self.format_id
+    }
+
+    // the xid as it is written in an `XA` statement, e.g. `X'6731',X'',1`
+    fn to_sql(&self) -> String {
+        let mut sql = String::with_capacity(2 * (self.gtrid.len() + self.bqual.len()) + 20);
+
+        // hex literals, as the parts are arbitrary bytes
+        let _ = write!(
+            sql,
+            "X'{}',X'{}',{}",
+            hex::encode(&self.gtrid),
+            hex::encode(&self.bqual),
+            self.format_id
+        );
+
+        sql
+    }
+
+    // an xid of `XA RECOVER`, whose data is the gtrid followed by the bqual
+    fn from_recovered(format_id: i64, gtrid_len: i64, bqual_len: i64, data: &[u8]) -> Result<Self> {
+        let (Ok(format_id), Ok(gtrid_len), Ok(bqual_len)) = (
+            u32::try_from(format_id),
+            usize::try_from(gtrid_len),
+            usize::try_from(bqual_len),
+        ) else {
+            return Err(err_protocol!(
+                "invalid XA RECOVER row: format id {}",
+                format_id
+            ));
+        };
+
+        if gtrid_len + bqual_len != data.len() {
+            return Err(err_protocol!(
+                "invalid XA RECOVER row: {} + {} bytes of {}",
+                gtrid_len,
+                bqual_len,
+                data.len()
+            ));
+        }
+
+        let (gtrid, bqual) = data.split_at(gtrid_len);
+
+        Ok(Self {
+            gtrid: gtrid.to_vec(),
+            bqual: bqual.to_vec(),
+            format_id,
+        })
+    }
+}
+
+impl MySqlConnection {
+    /// Start the XA transaction branch `xid` with `XA START`.
+    ///
+    /// The statements that follow are part of the branch, until [`xa_end`][Self::xa_end]. An
+    /// XA transaction cannot be started inside of a local transaction, or the other way around.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+    /// use sqlx::mysql::MySqlXid;
+    ///
+    /// let xid = MySqlXid::new("order-1234")?.with_branch("inventory")?;
+    ///
+    /// conn.xa_start(&xid).await?;
+    /// sqlx::query("UPDATE stock SET count = count - 1 WHERE item = ?")
+    ///     .bind(42)
+    ///     .execute(&mut *conn)
+    ///     .await?;
+    /// conn.xa_end(&xid).await?;
+    ///
+    /// // the branch survives a crash of the server or the connection from here on
+    /// conn.xa_prepare(&xid).await?;
+    ///
+    /// // once every resource prepared its branch
+    /// conn.xa_commit(&xid).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn xa_start(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA START", xid, "").await
+    }
+
+    /// End the statements of the XA transaction branch `xid` with `XA END`.
+    pub async fn xa_end(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA END", xid, "").await
+    }
+
+    /// Prepare the ended XA transaction branch `xid` with `XA PREPARE`, the first phase of
+    /// the commit.
+    ///
+    /// Once prepared, the branch is kept by the server until it is committed or rolled back,
+    /// even if the connection is closed. It can then be finished from any connection.
+    pub async fn xa_prepare(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA PREPARE", xid, "").await
+    }
+
+    /// Commit the prepared XA transaction branch `xid` with `XA COMMIT`, the second phase of
+    /// the commit.
+    pub async fn xa_commit(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA COMMIT", xid, "").await
+    }
+
+    /// Commit the ended XA transaction branch `xid` without preparing it first, with
+    /// `XA COMMIT ... ONE PHASE`, if it is the only branch of the global transaction.
+    pub async fn xa_commit_one_phase(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA COMMIT", xid, " ONE PHASE").await
+    }
+
+    /// Roll back the XA transaction branch `xid`, which is ended or prepared, with
+    /// `XA ROLLBACK`.
+    pub async fn xa_rollback(&mut self, xid: &MySqlXid) -> Result<()> {
+        self.xa("XA ROLLBACK", xid, "").await
+    }
+
+    /// List the prepared XA transaction branches of the server with `XA RECOVER`, to commit or
+    /// roll them back after a crash of the transaction manager.
+    ///
+    /// Requires the `XA_RECOVER_ADMIN` privilege on MySQL 8.0 or later to see the branches of
+    /// other users.
+    pub async fn xa_recover(&mut self) -> Result<Vec<MySqlXid>> {
+        let rows = self.fetch_all("XA RECOVER").await?;
+
+        rows.iter()
+            .map(|row| {
+                // the data is binary, but sent as a string
+                let data: Vec<u8> = row.try_get_unchecked("data")?;
+
+                MySqlXid::from_recovered(
+                    row.try_get_unchecked("formatID")?,
+                    row.try_get_unchecked("gtrid_length")?,
+                    row.try_get_unchecked("bqual_length")?,
+                    &data,
+                )
+            })
+            .collect()
+    }
+
+    async fn xa(&mut self, statement: &str, xid: &MySqlXid, suffix: &str) -> Result<()> {
+        // XA statements cannot be prepared
+        let sql = format!("{statement} {}{suffix}", xid.to_sql());
+
+        self.execute(&*sql).await?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_xid_to_sql() {
+    let xid = MySqlXid::new("g1").unwrap();
+    assert_eq!(xid.to_sql(), "X'6731',X'',1");
+
+    let xid = MySqlXid::new(vec![0, 0xff])
+        .unwrap()
+        .with_branch("b")
+        .unwrap()
+        .with_format_id(7);
+    assert_eq!(xid.to_sql(), "X'00ff',X'62',7");
+
+    assert!(MySqlXid::new("").is_err());
+    assert!(MySqlXid::new([0; 65]).is_err());
+    assert!(MySqlXid::new("g").unwrap().with_branch([0; 65]).is_err());
+}
+
+#[test]
+fn test_xid_from_recovered() {
+    let xid = MySqlXid::from_recovered(1, 2, 1, b"g1b").unwrap();
+    assert_eq!(
+        xid,
+        MySqlXid::new("g1")
+            .unwrap()
+            .with_branch("b")
+            .unwrap()
+            .with_format_id(1)
+    );
+
+    assert!(MySqlXid::from_recovered(1, 2, 2, b"g1b").is_err());
+    assert!(MySqlXid::from_recovered(-1, 2, 1, b"g1b").is_err());
+}
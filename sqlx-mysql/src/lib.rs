@@ -11,6 +11,7 @@ pub(crate) use sqlx_core::driver_prelude::*;
 pub mod any;
 
 mod arguments;
+mod binlog;
 mod collation;
 mod column;
 mod connection;
@@ -19,6 +20,7 @@ mod error;
 mod io;
 mod options;
 mod protocol;
+mod query_ext;
 mod query_result;
 mod row;
 mod statement;
@@ -34,12 +36,23 @@ mod migrate;
 mod testing;
 
 pub use arguments::MySqlArguments;
+pub use binlog::{
+    MySqlBinlogColumn, MySqlBinlogEvent, MySqlBinlogEventData, MySqlBinlogFormatDescription,
+    MySqlBinlogGtid, MySqlBinlogOptions, MySqlBinlogQuery, MySqlBinlogRotate, MySqlBinlogRow,
+    MySqlBinlogRows, MySqlBinlogStream, MySqlBinlogTable, MySqlBinlogUpdateRows, MySqlBinlogValue,
+    MySqlBinlogXid,
+};
 pub use column::MySqlColumn;
-pub use connection::MySqlConnection;
+pub use connection::{
+    MySqlCancellationToken, MySqlConnection, MySqlInfileCsvWriter, MySqlInfileExt,
+    MySqlInfileResult, MySqlInfileRow, MySqlLocalInfile, MySqlPoolGtidExt, MySqlSessionState,
+    MySqlXid,
+};
 pub use database::MySql;
 pub use error::MySqlDatabaseError;
-pub use options::{MySqlConnectOptions, MySqlSslMode};
-pub use query_result::MySqlQueryResult;
+pub use options::{MySqlCompression, MySqlConnectOptions, MySqlSslMode};
+pub use query_ext::MySqlQueryExt;
+pub use query_result::{MySqlQueryResult, MySqlWarning, MySqlWarningLevel};
 pub use row::MySqlRow;
 pub use statement::MySqlStatement;
 pub use transaction::MySqlTransactionManager;
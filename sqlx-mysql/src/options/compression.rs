@@ -0,0 +1,45 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for compressing the traffic between the client and the MySQL server.
+///
+/// It is used by the [`compression`](super::MySqlConnectOptions::compression) method.
+///
+/// Compression is only used if the server supports the selected algorithm; otherwise the
+/// connection falls back to being uncompressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MySqlCompression {
+    /// Do not compress the connection.
+    ///
+    /// This is the default if `compression` is not specified.
+    #[default]
+    Disabled,
+
+    /// Compress the connection using zlib, which is supported by MySQL and MariaDB.
+    ///
+    /// Requires the `mysql-compression-zlib` feature.
+    Zlib,
+
+    /// Compress the connection using zstd, which is supported by MySQL 8.0.18 and later.
+    ///
+    /// Requires the `mysql-compression-zstd` feature.
+    Zstd,
+}
+
+impl FromStr for MySqlCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "disabled" | "uncompressed" => MySqlCompression::Disabled,
+            "zlib" => MySqlCompression::Zlib,
+            "zstd" => MySqlCompression::Zstd,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {s:?} for `compression`").into(),
+                ));
+            }
+        })
+    }
+}
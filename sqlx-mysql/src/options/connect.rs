@@ -1,6 +1,7 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::executor::Executor;
+use crate::row::Row;
 use crate::{MySqlConnectOptions, MySqlConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
@@ -21,59 +22,79 @@ impl ConnectOptions for MySqlConnectOptions {
         Box::pin(async move {
             let mut conn = MySqlConnection::establish(self).await?;
 
-            // After the connection is established, we initialize by configuring a few
-            // connection parameters
+            conn.configure(self).await?;
+            conn.start_session_setup();
 
-            // https://mariadb.com/kb/en/sql-mode/
+            Ok(conn)
+        })
+    }
 
-            // PIPES_AS_CONCAT - Allows using the pipe character (ASCII 124) as string concatenation operator.
-            //                   This means that "A" || "B" can be used in place of CONCAT("A", "B").
+    fn log_statements(mut self, level: LevelFilter) -> Self {
+        self.log_settings.log_statements(level);
+        self
+    }
 
-            // NO_ENGINE_SUBSTITUTION - If not set, if the available storage engine specified by a CREATE TABLE is
-            //                          not available, a warning is given and the default storage
-            //                          engine is used instead.
+    fn log_slow_statements(mut self, level: LevelFilter, duration: Duration) -> Self {
+        self.log_settings.log_slow_statements(level, duration);
+        self
+    }
+}
 
-            // NO_ZERO_DATE - Don't allow '0000-00-00'. This is invalid in Rust.
+impl MySqlConnection {
+    pub(crate) async fn configure(&mut self, options: &MySqlConnectOptions) -> Result<(), Error> {
+        // After the connection is established, we initialize by configuring a few
+        // connection parameters
 
-            // NO_ZERO_IN_DATE - Don't allow 'YYYY-00-00'. This is invalid in Rust.
+        // https://mariadb.com/kb/en/sql-mode/
 
-            // --
+        // PIPES_AS_CONCAT - Allows using the pipe character (ASCII 124) as string concatenation operator.
+        //                   This means that "A" || "B" can be used in place of CONCAT("A", "B").
 
-            // Setting the time zone allows us to assume that the output
-            // from a TIMESTAMP field is UTC
+        // NO_ENGINE_SUBSTITUTION - If not set, if the available storage engine specified by a CREATE TABLE is
+        //                          not available, a warning is given and the default storage
+        //                          engine is used instead.
 
-            // --
+        // NO_ZERO_DATE - Don't allow '0000-00-00'. This is invalid in Rust.
 
-            // https://mathiasbynens.be/notes/mysql-utf8mb4
+        // NO_ZERO_IN_DATE - Don't allow 'YYYY-00-00'. This is invalid in Rust.
 
-            let mut options = String::new();
-            if self.pipes_as_concat {
-                options.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
-            } else {
-                options.push_str(
-                    r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',NO_ENGINE_SUBSTITUTION')),"#,
-                );
-            }
-            options.push_str(r#"time_zone='+00:00',"#);
-            options.push_str(&format!(
-                r#"NAMES {} COLLATE {};"#,
-                conn.stream.charset.as_str(),
-                conn.stream.collation.as_str()
-            ));
+        // --
 
-            conn.execute(&*options).await?;
+        // Setting the time zone allows us to assume that the output
+        // from a TIMESTAMP field is UTC
 
-            Ok(conn)
-        })
-    }
+        // --
 
-    fn log_statements(mut self, level: LevelFilter) -> Self {
-        self.log_settings.log_statements(level);
-        self
-    }
+        // https://mathiasbynens.be/notes/mysql-utf8mb4
 
-    fn log_slow_statements(mut self, level: LevelFilter, duration: Duration) -> Self {
-        self.log_settings.log_slow_statements(level, duration);
-        self
+        // --
+
+        // The server rejects packets larger than `max_allowed_packet`,
+        // which limits the size of the packets we send for `LOAD DATA LOCAL INFILE`
+
+        let mut init = String::new();
+        if options.pipes_as_concat {
+            init.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
+        } else {
+            init.push_str(
+                r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',NO_ENGINE_SUBSTITUTION')),"#,
+            );
+        }
+        init.push_str(r#"time_zone='+00:00',"#);
+        if options.track_gtids {
+            init.push_str(r#"session_track_gtids='OWN_GTID',"#);
+        }
+        init.push_str(&format!(
+            r#"NAMES {} COLLATE {};"#,
+            self.stream.charset.as_str(),
+            self.stream.collation.as_str()
+        ));
+
+        init.push_str(r#"SELECT CAST(@@max_allowed_packet AS UNSIGNED);"#);
+
+        let max_allowed_packet: u64 = self.fetch_one(&*init).await?.try_get(0)?;
+        self.max_allowed_packet = usize::try_from(max_allowed_packet).unwrap_or(usize::MAX);
+
+        Ok(())
     }
 }
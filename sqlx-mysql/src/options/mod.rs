@@ -1,10 +1,18 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use futures_core::future::BoxFuture;
+
+mod compression;
 mod connect;
 mod parse;
 mod ssl_mode;
 
-use crate::{connection::LogSettings, net::tls::CertificateInput};
+use crate::connection::{LocalInfileHandler, LogSettings};
+use crate::error::Error;
+use crate::net::tls::{CertificateInput, TlsSessionCache};
+use crate::{MySqlConnection, MySqlLocalInfile};
+pub use compression::MySqlCompression;
 pub use ssl_mode::MySqlSslMode;
 
 /// Options and flags which can be used to configure a MySQL connection.
@@ -29,6 +37,12 @@ pub use ssl_mode::MySqlSslMode;
 /// | `ssl-ca` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
 /// | `statement-cache-capacity` | `100` | The maximum number of prepared statements stored in the cache. Set to `0` to disable. |
 /// | `socket` | `None` | Path to the unix domain socket, which will be used instead of TCP if set. |
+/// | `pipe` | `None` | Name of the Windows named pipe, which will be used instead of TCP if set. Defaults to `MySQL` if empty. See [`MySqlConnectOptions::pipe`]. |
+/// | `local-infile` | `false` | Whether the server may request local files, for `LOAD DATA LOCAL INFILE`. See [`MySqlConnectOptions::enable_local_infile`]. |
+/// | `multi-statements` | `true` | Whether a query without arguments may consist of several statements separated by semicolons. See [`MySqlConnectOptions::multi_statements`]. |
+/// | `fetch-warnings` | `false` | Whether the warnings of a query are fetched with `SHOW WARNINGS`. See [`MySqlConnectOptions::fetch_warnings`]. |
+/// | `auto-reconnect` | `false` | Whether a lost connection is reopened outside of a transaction. See [`MySqlConnectOptions::auto_reconnect`]. |
+/// | `compression` | `DISABLED` | Determines which algorithm is used to compress the connection, if supported by the server. See [`MySqlCompression`]. |
 ///
 /// # Example
 ///
@@ -64,6 +78,7 @@ pub struct MySqlConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
     pub(crate) socket: Option<PathBuf>,
+    pub(crate) pipe: Option<String>,
     pub(crate) username: String,
     pub(crate) password: Option<String>,
     pub(crate) database: Option<String>,
@@ -71,12 +86,22 @@ pub struct MySqlConnectOptions {
     pub(crate) ssl_ca: Option<CertificateInput>,
     pub(crate) ssl_client_cert: Option<CertificateInput>,
     pub(crate) ssl_client_key: Option<CertificateInput>,
+    // the TLS sessions shared by the connections opened with clones of these options
+    pub(crate) tls_session_cache: TlsSessionCache,
+    pub(crate) compression: MySqlCompression,
     pub(crate) statement_cache_capacity: usize,
     pub(crate) charset: String,
     pub(crate) collation: Option<String>,
     pub(crate) log_settings: LogSettings,
     pub(crate) pipes_as_concat: bool,
     pub(crate) enable_cleartext_plugin: bool,
+    pub(crate) enable_local_infile: bool,
+    pub(crate) multi_statements: bool,
+    pub(crate) local_infile_handler: Option<LocalInfileHandler>,
+    pub(crate) connect_attributes: Vec<(String, String)>,
+    pub(crate) track_gtids: bool,
+    pub(crate) fetch_warnings: bool,
+    pub(crate) auto_reconnect: bool,
 }
 
 impl Default for MySqlConnectOptions {
@@ -92,6 +117,7 @@ impl MySqlConnectOptions {
             port: 3306,
             host: String::from("localhost"),
             socket: None,
+            pipe: None,
             username: String::from("root"),
             password: None,
             database: None,
@@ -101,10 +127,19 @@ impl MySqlConnectOptions {
             ssl_ca: None,
             ssl_client_cert: None,
             ssl_client_key: None,
+            tls_session_cache: TlsSessionCache::default(),
+            compression: MySqlCompression::Disabled,
             statement_cache_capacity: 100,
             log_settings: Default::default(),
             pipes_as_concat: true,
             enable_cleartext_plugin: false,
+            enable_local_infile: false,
+            multi_statements: true,
+            local_infile_handler: None,
+            connect_attributes: Vec::new(),
+            track_gtids: false,
+            fetch_warnings: false,
+            auto_reconnect: false,
         }
     }
 
@@ -134,6 +169,22 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Sets the name of a Windows named pipe, `\\.\pipe\{name}`, on the local machine. This
+    /// changes the connection stream from TCP to the named pipe, which takes precedence over
+    /// [`socket`][Self::socket].
+    ///
+    /// The server listens on the pipe `MySQL` if started with `named_pipe = ON`. Named pipes are
+    /// only supported on Windows, with the Tokio runtime.
+    ///
+    /// The shared-memory transport of MySQL on Windows (`--protocol=MEMORY`) is not supported:
+    /// connect to a server that has `shared_memory = ON` over TCP or a named pipe instead.
+    ///
+    /// By default set to `None`.
+    pub fn pipe(mut self, name: &str) -> Self {
+        self.pipe = Some(name.to_owned());
+        self
+    }
+
     /// Sets the username to connect as.
     pub fn username(mut self, username: &str) -> Self {
         self.username = username.to_owned();
@@ -158,6 +209,10 @@ impl MySqlConnectOptions {
     /// By default, the SSL mode is [`Preferred`](MySqlSslMode::Preferred), and the client will
     /// first attempt an SSL connection but fallback to a non-SSL connection on failure.
     ///
+    /// With the `rustls` TLS backend, the connections opened with clones of these options, e.g. by
+    /// a pool, resume the TLS session of an earlier connection to the server instead of doing a
+    /// full handshake. `native-tls` has no API to resume sessions.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -230,6 +285,26 @@ impl MySqlConnectOptions {
         self
     }
 
+    /// Sets the algorithm used to compress the traffic between the client and the server.
+    ///
+    /// Compression reduces the amount of data sent over the network at the cost of CPU time,
+    /// which pays off for large result sets and bulk loads over slow links.
+    ///
+    /// By default, the connection is not compressed. If the server does not support the
+    /// selected algorithm, the connection falls back to being uncompressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::{MySqlCompression, MySqlConnectOptions};
+    /// let options = MySqlConnectOptions::new()
+    ///     .compression(MySqlCompression::Zstd);
+    /// ```
+    pub fn compression(mut self, compression: MySqlCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Sets the capacity of the connection's statement cache in a number of stored
     /// distinct statements. Caching is handled using LRU, meaning when the
     /// amount of queries hits the defined limit, the oldest statement will get
@@ -283,4 +358,224 @@ impl MySqlConnectOptions {
         self.enable_cleartext_plugin = flag_val;
         self
     }
+
+    /// Sets whether the server reports the GTIDs of each transaction committed on the
+    /// connection, by setting `session_track_gtids` to `OWN_GTID`, which are then available
+    /// from [`MySqlConnection::last_gtid()`].
+    ///
+    /// This is used to read one's own writes from a replica, see
+    /// [`MySqlConnection::wait_for_gtid()`]. Requires MySQL 5.7.6 or later; other servers fail to
+    /// connect. The default value is set to false.
+    pub fn track_gtids(mut self, flag_val: bool) -> Self {
+        self.track_gtids = flag_val;
+        self
+    }
+
+    /// Sets whether `SHOW WARNINGS` is run after a query that raised warnings, to return them
+    /// in [`MySqlQueryResult::warnings`](crate::MySqlQueryResult::warnings).
+    ///
+    /// Without strict SQL mode, MySQL truncates or converts invalid values with a warning
+    /// instead of failing the statement; this makes those warnings available to the
+    /// application. It costs another round trip for each query with warnings. The default
+    /// value is set to false.
+    pub fn fetch_warnings(mut self, flag_val: bool) -> Self {
+        self.fetch_warnings = flag_val;
+        self
+    }
+
+    /// Sets whether a connection that was lost outside of a transaction, e.g. closed by the
+    /// server after `wait_timeout`, is opened again when a ping or statement fails.
+    ///
+    /// The new connection is configured like the lost one, and the `SET` statements run on
+    /// the connection before any other statement, e.g. in
+    /// [`PoolOptions::after_connect`][crate::pool::PoolOptions::after_connect], are run
+    /// again. The ping is then retried once, and so is a statement if the connection was lost
+    /// before the server responded to it: the connection was closed, or the server sent the
+    /// error it closes an idle connection with. Once the server responded, the error is returned
+    /// instead, as the statement may have been executed.
+    ///
+    /// The rest of the session is lost: user variables set later, temporary tables, locks and
+    /// prepared statements. A connection is not opened again inside a transaction, whether it
+    /// was started with `Connection::begin()` or with a statement. The default value is set to
+    /// false.
+    pub fn auto_reconnect(mut self, flag_val: bool) -> Self {
+        self.auto_reconnect = flag_val;
+        self
+    }
+
+    /// Sets whether the server is allowed to request local files from the client, which is
+    /// required for `LOAD DATA LOCAL INFILE` statements.
+    ///
+    /// Unless enabled, the client does not advertise the capability to the server, so that
+    /// [`MySqlConnection::load_local_infile()`] fails and the
+    /// [`local_infile_handler`][Self::local_infile_handler] is never invoked, even if the server
+    /// sends a request anyway. This keeps a malicious or compromised server from asking for
+    /// local files.
+    ///
+    /// The default value is set to false. Even when enabled, local files are only ever sent
+    /// through [`MySqlConnection::load_local_infile()`] or the `local_infile_handler`, never
+    /// read from the file system by SQLx itself.
+    pub fn enable_local_infile(mut self, flag_val: bool) -> Self {
+        self.enable_local_infile = flag_val;
+        self
+    }
+
+    /// Sets whether a query may consist of several statements separated by semicolons, such as
+    /// a schema script, which the server then runs one after another.
+    ///
+    /// This only applies to queries without arguments, e.g. a `&str` passed to
+    /// [`Executor::execute()`][crate::Executor::execute], since prepared statements can never
+    /// contain more than one statement. The server splits the script itself, so semicolons in
+    /// string literals or in the body of a trigger or procedure are handled correctly.
+    /// The result of each statement can be iterated with
+    /// [`Executor::execute_many()`][crate::Executor::execute_many], or together with the rows
+    /// with [`Executor::fetch_many()`][crate::Executor::fetch_many]. If a statement fails, the
+    /// remaining ones are not run.
+    ///
+    /// Disabling this makes the server reject any query with more than one statement, which
+    /// limits the damage of SQL injection. Migrations still need it for scripts with several
+    /// statements.
+    ///
+    /// The default value is set to true.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use futures::TryStreamExt;
+    /// use sqlx::{ConnectOptions, Executor};
+    /// use sqlx::mysql::MySqlConnectOptions;
+    ///
+    /// let mut conn = MySqlConnectOptions::new()
+    ///     .multi_statements(true)
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// let mut results = conn.execute_many(
+    ///     "CREATE TABLE notes (id INT PRIMARY KEY, body TEXT);
+    ///      INSERT INTO notes VALUES (1, 'a; b'), (2, 'c');",
+    /// );
+    ///
+    /// while let Some(result) = results.try_next().await? {
+    ///     println!("{} rows affected", result.rows_affected());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn multi_statements(mut self, flag_val: bool) -> Self {
+        self.multi_statements = flag_val;
+        self
+    }
+
+    /// Sets a handler which provides the file contents whenever the server requests a local
+    /// file in response to a query, e.g. for `LOAD DATA LOCAL INFILE` statements run through
+    /// [`query()`][crate::query] or [`Executor::execute()`][crate::Executor::execute].
+    ///
+    /// Requires [`enable_local_infile(true)`][Self::enable_local_infile].
+    ///
+    /// The handler is given a [`MySqlLocalInfile`] to write the contents of the file to.
+    /// [`MySqlLocalInfile::filename()`] returns the file name given in the statement, as
+    /// requested by the server; the handler is responsible for deciding which files may be read.
+    /// Any data still buffered is sent once the handler returns, followed by the end of the file.
+    ///
+    /// If the handler returns an error, the file is ended at the last packet that was already
+    /// sent and the query fails with that error. Wrap the statement in a transaction to be able
+    /// to roll back any rows loaded up to that point.
+    ///
+    /// Without a handler, local file requests are answered with an empty file and the query
+    /// fails with an error. [`MySqlConnection::load_local_infile()`] works regardless of the
+    /// handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use sqlx::{ConnectOptions, Error};
+    /// use sqlx::mysql::MySqlConnectOptions;
+    ///
+    /// let mut conn = MySqlConnectOptions::new()
+    ///     .enable_local_infile(true)
+    ///     .local_infile_handler(|infile| Box::pin(async move {
+    ///         match infile.filename() {
+    ///             "users.tsv" => infile.write(b"1\tAlice\n2\tBob\n").await,
+    ///             other => Err(Error::Protocol(format!("refusing to send {other:?}"))),
+    ///         }
+    ///     }))
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// sqlx::query("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users")
+    ///     .execute(&mut conn)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// For a discussion on why `Box::pin()` is required, see
+    /// [`PoolOptions`][crate::pool::PoolOptions].
+    pub fn local_infile_handler<F>(mut self, handler: F) -> Self
+    where
+        for<'a, 'c> F: Fn(
+                &'a mut MySqlLocalInfile<&'c mut MySqlConnection>,
+            ) -> BoxFuture<'a, Result<(), Error>>
+            + 'static
+            + Send
+            + Sync,
+    {
+        self.local_infile_handler = Some(LocalInfileHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets a connection attribute, which is sent to the server when connecting and shown in
+    /// `performance_schema.session_connect_attrs`, e.g. to identify the service that opened
+    /// the connection.
+    ///
+    /// The attributes `_client_name`, `_client_version`, `_os`, `_platform`, `_pid` and
+    /// `program_name` are always sent, but can be overridden. Setting an attribute again
+    /// replaces its value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sqlx::mysql::MySqlConnectOptions;
+    ///
+    /// let options = MySqlConnectOptions::new()
+    ///     .connect_attribute("program_name", "billing")
+    ///     .connect_attribute("deployment", "eu-west-1");
+    /// ```
+    pub fn connect_attribute(mut self, key: &str, value: &str) -> Self {
+        match self.connect_attributes.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self
+                .connect_attributes
+                .push((key.to_owned(), value.to_owned())),
+        }
+
+        self
+    }
+
+    /// The connection attributes sent to the server, the standard ones followed by those set
+    /// with [`connect_attribute`][Self::connect_attribute].
+    pub(crate) fn all_connect_attributes(&self) -> Vec<(String, String)> {
+        let program_name = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+
+        let standard = [
+            ("_client_name", "sqlx".to_owned()),
+            ("_client_version", env!("CARGO_PKG_VERSION").to_owned()),
+            ("_os", std::env::consts::OS.to_owned()),
+            ("_platform", std::env::consts::ARCH.to_owned()),
+            ("_pid", std::process::id().to_string()),
+            ("program_name", program_name),
+        ];
+
+        standard
+            .into_iter()
+            .filter(|(key, _)| !self.connect_attributes.iter().any(|(k, _)| k == key))
+            .map(|(key, value)| (key.to_owned(), value))
+            .chain(self.connect_attributes.iter().cloned())
+            .collect()
+    }
 }
@@ -8,6 +8,9 @@ pub enum AuthPlugin {
     CachingSha2Password,
     Sha256Password,
     MySqlClearPassword,
+    Ed25519,
+    LdapSasl,
+    Kerberos,
 }
 
 impl AuthPlugin {
@@ -17,6 +20,9 @@ impl AuthPlugin {
             AuthPlugin::CachingSha2Password => "caching_sha2_password",
             AuthPlugin::Sha256Password => "sha256_password",
             AuthPlugin::MySqlClearPassword => "mysql_clear_password",
+            AuthPlugin::Ed25519 => "client_ed25519",
+            AuthPlugin::LdapSasl => "authentication_ldap_sasl_client",
+            AuthPlugin::Kerberos => "authentication_kerberos_client",
         }
     }
 }
@@ -30,6 +36,9 @@ impl FromStr for AuthPlugin {
             "caching_sha2_password" => Ok(AuthPlugin::CachingSha2Password),
             "sha256_password" => Ok(AuthPlugin::Sha256Password),
             "mysql_clear_password" => Ok(AuthPlugin::MySqlClearPassword),
+            "client_ed25519" => Ok(AuthPlugin::Ed25519),
+            "authentication_ldap_sasl_client" => Ok(AuthPlugin::LdapSasl),
+            "authentication_kerberos_client" => Ok(AuthPlugin::Kerberos),
 
             _ => Err(err_protocol!("unknown authentication plugin: {}", s)),
         }
@@ -75,6 +75,9 @@ bitflags::bitflags! {
         // Support ZSTD protocol compression
         const ZSTD_COMPRESSION_ALGORITHM = (1 << 26);
 
+        // Can send query attributes with COM_QUERY and COM_STMT_EXECUTE
+        const QUERY_ATTRIBUTES = (1 << 27);
+
         // Verify server certificate
         const SSL_VERIFY_SERVER_CERT = (1 << 30);
 
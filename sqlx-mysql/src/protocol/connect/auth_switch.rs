@@ -41,6 +41,24 @@ impl Decode<'_, bool> for AuthSwitchRequest {
             });
         }
 
+        if matches!(plugin, AuthPlugin::LdapSasl | AuthPlugin::Kerberos) {
+            // the first message of the plugin on the server, instead of a scramble
+            return Ok(Self { plugin, data: buf });
+        }
+
+        if matches!(plugin, AuthPlugin::Ed25519) {
+            // MariaDB's ed25519 plugin sends its own 32-byte nonce, without a NUL-terminator
+            // See: https://mariadb.com/kb/en/connection/#client_ed25519-plugin
+            if buf.len() != 32 {
+                return Err(err_protocol!(
+                    "expected 32 bytes but found {} bytes",
+                    buf.len()
+                ));
+            }
+
+            return Ok(Self { plugin, data: buf });
+        }
+
         // See: https://github.com/mysql/mysql-server/blob/ea7d2e2d16ac03afdd9cb72a972a95981107bf51/sql/auth/sha2_password.cc#L942
         if buf.len() != 21 {
             return Err(err_protocol!(
@@ -74,6 +92,26 @@ fn test_decode_auth_switch_packet_data() {
     assert_eq!(p.data, &b"abcdefghijabcdefghij"[..]);
 }
 
+#[test]
+fn test_decode_auth_switch_ed25519() {
+    const AUTH_SWITCH_ED25519: &[u8] = b"\xfeclient_ed25519\x00abcdefghijabcdefghijabcdefghij01";
+
+    let p = AuthSwitchRequest::decode_with(AUTH_SWITCH_ED25519.into(), true).unwrap();
+
+    assert!(matches!(p.plugin, AuthPlugin::Ed25519));
+    assert_eq!(p.data, &b"abcdefghijabcdefghijabcdefghij01"[..]);
+}
+
+#[test]
+fn test_decode_auth_switch_ldap_sasl() {
+    const AUTH_SWITCH_LDAP_SASL: &[u8] = b"\xfeauthentication_ldap_sasl_client\x00SCRAM-SHA-256";
+
+    let p = AuthSwitchRequest::decode_with(AUTH_SWITCH_LDAP_SASL.into(), true).unwrap();
+
+    assert!(matches!(p.plugin, AuthPlugin::LdapSasl));
+    assert_eq!(p.data, &b"SCRAM-SHA-256"[..]);
+}
+
 #[test]
 fn test_decode_auth_switch_cleartext_disabled() {
     const AUTH_SWITCH_CLEARTEXT: &[u8] = b"\xfemysql_clear_password\x00abcdefghijabcdefghij\x00";
@@ -25,6 +25,12 @@ pub struct HandshakeResponse<'a> {
 
     /// Opaque authentication response
     pub auth_response: Option<&'a [u8]>,
+
+    /// Key-value pairs which identify the client
+    pub connect_attributes: &'a [(String, String)],
+
+    /// Level of zstd compression, if the zstd compression algorithm is used
+    pub zstd_compression_level: u8,
 }
 
 impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
@@ -69,5 +75,39 @@ impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
                 buf.push(0);
             }
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            put_connect_attributes(buf, self.connect_attributes);
+        }
+
+        if capabilities.contains(Capabilities::ZSTD_COMPRESSION_ALGORITHM) {
+            buf.push(self.zstd_compression_level);
+        }
+    }
+}
+
+/// Write the connection attributes, as sent in the handshake response and `COM_CHANGE_USER`.
+pub(crate) fn put_connect_attributes(buf: &mut Vec<u8>, attributes: &[(String, String)]) {
+    let mut pairs = Vec::new();
+
+    for (key, value) in attributes {
+        pairs.put_str_lenenc(key);
+        pairs.put_str_lenenc(value);
     }
+
+    buf.put_bytes_lenenc(&pairs);
+}
+
+#[test]
+fn test_encode_connect_attributes() {
+    let mut buf = Vec::new();
+    put_connect_attributes(
+        &mut buf,
+        &[
+            ("_client_name".into(), "sqlx".into()),
+            ("a".into(), "".into()),
+        ],
+    );
+
+    assert_eq!(buf, b"\x15\x0c_client_name\x04sqlx\x01a\x00");
 }
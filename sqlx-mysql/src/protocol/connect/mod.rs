@@ -9,5 +9,5 @@ mod ssl_request;
 
 pub(crate) use auth_switch::{AuthSwitchRequest, AuthSwitchResponse};
 pub(crate) use handshake::Handshake;
-pub(crate) use handshake_response::HandshakeResponse;
+pub(crate) use handshake_response::{put_connect_attributes, HandshakeResponse};
 pub(crate) use ssl_request::SslRequest;
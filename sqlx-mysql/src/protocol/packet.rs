@@ -67,6 +67,7 @@ impl Packet<Bytes> {
             Ok(EofPacket {
                 warnings: ok.warnings,
                 status: ok.status,
+                session_state: ok.session_state,
             })
         } else {
             self.decode_with(capabilities)
@@ -0,0 +1,53 @@
+use crate::io::Encode;
+use crate::protocol::Capabilities;
+
+/// Return an EOF packet at the end of the binary log, instead of waiting for more events.
+pub(crate) const BINLOG_DUMP_NON_BLOCK: u16 = 0x01;
+
+/// Start after the transactions in the GTID set, instead of at the file and position.
+const BINLOG_THROUGH_GTID: u16 = 0x04;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_binlog_dump.html
+
+#[derive(Debug)]
+pub(crate) struct BinlogDump<'a> {
+    pub(crate) position: u32,
+    pub(crate) flags: u16,
+    pub(crate) server_id: u32,
+    pub(crate) filename: &'a str,
+}
+
+impl Encode<'_, Capabilities> for BinlogDump<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        buf.push(0x12); // COM_BINLOG_DUMP
+        buf.extend(&self.position.to_le_bytes());
+        buf.extend(&self.flags.to_le_bytes());
+        buf.extend(&self.server_id.to_le_bytes());
+        buf.extend(self.filename.as_bytes());
+    }
+}
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_binlog_dump_gtid.html
+
+#[derive(Debug)]
+pub(crate) struct BinlogDumpGtid<'a> {
+    pub(crate) flags: u16,
+    pub(crate) server_id: u32,
+    pub(crate) filename: &'a str,
+    pub(crate) position: u64,
+    // the encoded GTID set
+    pub(crate) gtid_set: &'a [u8],
+}
+
+impl Encode<'_, Capabilities> for BinlogDumpGtid<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        buf.push(0x1e); // COM_BINLOG_DUMP_GTID
+        buf.extend(&(self.flags | BINLOG_THROUGH_GTID).to_le_bytes());
+        buf.extend(&self.server_id.to_le_bytes());
+        buf.extend(&(self.filename.len() as u32).to_le_bytes());
+        buf.extend(self.filename.as_bytes());
+        buf.extend(&self.position.to_le_bytes());
+        buf.extend(&(self.gtid_set.len() as u32).to_le_bytes());
+        buf.extend(self.gtid_set);
+    }
+}
@@ -0,0 +1,3 @@
+mod binlog_dump;
+
+pub(crate) use binlog_dump::{BinlogDump, BinlogDumpGtid, BINLOG_DUMP_NON_BLOCK};
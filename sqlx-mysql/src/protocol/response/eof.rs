@@ -15,6 +15,8 @@ use crate::protocol::Capabilities;
 pub struct EofPacket {
     pub warnings: u16,
     pub status: Status,
+    // the changes of the session state, only sent in the OK packet replacing this one
+    pub session_state: Bytes,
 }
 
 impl Decode<'_, Capabilities> for EofPacket {
@@ -30,6 +32,10 @@ impl Decode<'_, Capabilities> for EofPacket {
         let warnings = buf.get_u16_le();
         let status = Status::from_bits_truncate(buf.get_u16_le());
 
-        Ok(Self { status, warnings })
+        Ok(Self {
+            status,
+            warnings,
+            session_state: Bytes::new(),
+        })
     }
 }
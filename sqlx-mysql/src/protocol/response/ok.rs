@@ -12,6 +12,8 @@ pub struct OkPacket {
     pub last_insert_id: u64,
     pub status: Status,
     pub warnings: u16,
+    // the changes of the session state, if session tracking is enabled
+    pub session_state: Bytes,
 }
 
 impl Decode<'_> for OkPacket {
@@ -29,11 +31,26 @@ impl Decode<'_> for OkPacket {
         let status = Status::from_bits_truncate(buf.get_u16_le());
         let warnings = buf.get_u16_le();
 
+        // only sent with `CLIENT_SESSION_TRACK`, after the human readable info
+        let session_state =
+            if status.contains(Status::SERVER_SESSION_STATE_CHANGED) && buf.has_remaining() {
+                let _info = buf.get_bytes_lenenc();
+
+                if buf.has_remaining() {
+                    buf.get_bytes_lenenc()
+                } else {
+                    Bytes::new()
+                }
+            } else {
+                Bytes::new()
+            };
+
         Ok(Self {
             affected_rows,
             last_insert_id,
             status,
             warnings,
+            session_state,
         })
     }
 }
@@ -50,3 +67,12 @@ fn test_decode_ok_packet() {
     assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
     assert!(p.status.contains(Status::SERVER_SESSION_STATE_CHANGED));
 }
+
+#[test]
+fn test_decode_ok_packet_with_session_state() {
+    const DATA: &[u8] = b"\x00\x00\x00\x02@\x00\x00\x00\x07\x01\x05\x04test";
+
+    let p = OkPacket::decode(DATA.into()).unwrap();
+
+    assert_eq!(&p.session_state[..], b"\x01\x05\x04test");
+}
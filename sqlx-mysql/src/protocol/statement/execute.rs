@@ -1,9 +1,9 @@
-use crate::io::Encode;
-use crate::protocol::text::ColumnFlags;
+use crate::io::{Encode, MySqlBufMutExt};
+use crate::protocol::text::{ColumnFlags, ColumnType};
 use crate::protocol::Capabilities;
 use crate::MySqlArguments;
 
-// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_execute.html
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_stmt_execute.html
 
 #[derive(Debug)]
 pub struct Execute<'q> {
@@ -12,14 +12,41 @@ pub struct Execute<'q> {
 }
 
 impl<'q> Encode<'_, Capabilities> for Execute<'q> {
-    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode_with(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
+        let query_attributes = capabilities.contains(Capabilities::QUERY_ATTRIBUTES);
+
+        // attributes are sent as named parameters after the positional ones
+        let attributes: &[(String, String)] = if query_attributes {
+            &self.arguments.attributes
+        } else {
+            &[]
+        };
+
+        let count = self.arguments.types.len() + attributes.len();
+
         buf.push(0x17); // COM_STMT_EXECUTE
         buf.extend(&self.statement.to_le_bytes());
-        buf.push(0); // NO_CURSOR
+
+        // NO_CURSOR, or CURSOR_TYPE_READ_ONLY to fetch the rows with `COM_STMT_FETCH`
+        let mut flags = u8::from(self.arguments.uses_cursor(capabilities));
+
+        if query_attributes {
+            flags |= 0x08; // PARAMETER_COUNT_AVAILABLE
+        }
+
+        buf.push(flags);
+
         buf.extend(&1_u32.to_le_bytes()); // iterations (always 1): int<4>
 
-        if !self.arguments.types.is_empty() {
+        if query_attributes {
+            buf.put_uint_lenenc(count as u64);
+        }
+
+        if count > 0 {
+            let null_bitmap_len = buf.len();
             buf.extend(&*self.arguments.null_bitmap);
+            buf.resize(null_bitmap_len + (count + 7) / 8, 0);
+
             buf.push(1); // send type to server
 
             for ty in &self.arguments.types {
@@ -30,9 +57,71 @@ impl<'q> Encode<'_, Capabilities> for Execute<'q> {
                 } else {
                     0
                 });
+
+                if query_attributes {
+                    buf.put_str_lenenc(""); // positional parameters have no name
+                }
+            }
+
+            for (name, _) in attributes {
+                buf.push(ColumnType::VarString as u8);
+                buf.push(0);
+                buf.put_str_lenenc(name);
             }
 
             buf.extend(&*self.arguments.values);
+
+            for (_, value) in attributes {
+                buf.put_str_lenenc(value);
+            }
         }
     }
 }
+
+#[test]
+fn test_encode_execute_with_attributes() {
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i8);
+    arguments.attributes.push(("trace_id".into(), "abc".into()));
+
+    let mut buf = Vec::new();
+    Execute {
+        statement: 1,
+        arguments: &arguments,
+    }
+    .encode_with(&mut buf, Capabilities::QUERY_ATTRIBUTES);
+
+    assert_eq!(
+        buf,
+        b"\x17\x01\x00\x00\x00\x08\x01\x00\x00\x00\x02\x00\x01\
+          \x01\x00\x00\xfd\x00\x08trace_id\x01\x03abc"
+    );
+
+    // attributes are left out if the server doesn't support them
+    let mut buf = Vec::new();
+    Execute {
+        statement: 1,
+        arguments: &arguments,
+    }
+    .encode_with(&mut buf, Capabilities::empty());
+
+    assert_eq!(
+        buf,
+        b"\x17\x01\x00\x00\x00\x00\x01\x00\x00\x00\x00\x01\x01\x00\x01"
+    );
+}
+
+#[test]
+fn test_encode_execute_with_cursor() {
+    let mut arguments = MySqlArguments::default();
+    arguments.fetch_size = Some(100);
+
+    let mut buf = Vec::new();
+    Execute {
+        statement: 1,
+        arguments: &arguments,
+    }
+    .encode_with(&mut buf, Capabilities::DEPRECATE_EOF);
+
+    assert_eq!(buf, b"\x17\x01\x00\x00\x00\x01\x01\x00\x00\x00");
+}
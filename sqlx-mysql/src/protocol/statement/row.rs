@@ -59,6 +59,7 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
                 | ColumnType::Bit
                 | ColumnType::Decimal
                 | ColumnType::Json
+                | ColumnType::Vector
                 | ColumnType::NewDecimal => buf.get_uint_lenenc() as usize,
 
                 ColumnType::LongLong => 8,
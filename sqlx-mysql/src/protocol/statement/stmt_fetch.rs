@@ -0,0 +1,18 @@
+use crate::io::Encode;
+use crate::protocol::Capabilities;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_stmt_fetch.html
+
+#[derive(Debug)]
+pub struct StmtFetch {
+    pub statement: u32,
+    pub rows: u32,
+}
+
+impl Encode<'_, Capabilities> for StmtFetch {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        buf.push(0x1c); // COM_STMT_FETCH
+        buf.extend(&self.statement.to_le_bytes());
+        buf.extend(&self.rows.to_le_bytes());
+    }
+}
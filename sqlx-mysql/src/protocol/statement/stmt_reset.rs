@@ -0,0 +1,16 @@
+use crate::io::Encode;
+use crate::protocol::Capabilities;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_reset.html
+
+#[derive(Debug)]
+pub struct StmtReset {
+    pub statement: u32,
+}
+
+impl Encode<'_, Capabilities> for StmtReset {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        buf.push(0x1a); // COM_STMT_RESET
+        buf.extend(&self.statement.to_le_bytes());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::io::{BufMutExt, Encode};
+use crate::protocol::auth::AuthPlugin;
+use crate::protocol::connect::put_connect_attributes;
+use crate::protocol::Capabilities;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_change_user.html
+// https://mariadb.com/kb/en/com_change_user/
+
+#[derive(Debug)]
+pub(crate) struct ChangeUser<'a> {
+    pub(crate) username: &'a str,
+    pub(crate) auth_response: Option<&'a [u8]>,
+    pub(crate) database: Option<&'a str>,
+    pub(crate) collation: u8,
+    pub(crate) auth_plugin: Option<AuthPlugin>,
+    pub(crate) connect_attributes: &'a [(String, String)],
+}
+
+impl Encode<'_, Capabilities> for ChangeUser<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
+        buf.push(0x11); // COM_CHANGE_USER
+        buf.put_str_nul(self.username);
+
+        let response = self.auth_response.unwrap_or_default();
+
+        if capabilities.contains(Capabilities::SECURE_CONNECTION) {
+            buf.push(response.len() as u8);
+            buf.extend(response);
+        } else {
+            buf.extend(response);
+            buf.push(0);
+        }
+
+        buf.put_str_nul(self.database.unwrap_or_default());
+        buf.extend(&u16::from(self.collation).to_le_bytes());
+
+        if capabilities.contains(Capabilities::PLUGIN_AUTH) {
+            buf.put_str_nul(self.auth_plugin.map_or("", |plugin| plugin.name()));
+        }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            put_connect_attributes(buf, self.connect_attributes);
+        }
+    }
+}
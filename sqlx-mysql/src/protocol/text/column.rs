@@ -83,6 +83,7 @@ pub enum ColumnType {
     Year = 0x0d,
     VarChar = 0x0f,
     Bit = 0x10,
+    Vector = 0xf2,
     Json = 0xf5,
     NewDecimal = 0xf6,
     Enum = 0xf7,
@@ -174,6 +175,7 @@ impl ColumnType {
         let is_binary = char_set == 63;
         let is_unsigned = flags.contains(ColumnFlags::UNSIGNED);
         let is_enum = flags.contains(ColumnFlags::ENUM);
+        let is_set = flags.contains(ColumnFlags::SET);
 
         match self {
             ColumnType::Tiny if max_size == Some(1) => "BOOLEAN",
@@ -201,9 +203,11 @@ impl ColumnType {
             ColumnType::Decimal | ColumnType::NewDecimal => "DECIMAL",
             ColumnType::Geometry => "GEOMETRY",
             ColumnType::Json => "JSON",
+            ColumnType::Vector => "VECTOR",
 
             ColumnType::String if is_binary => "BINARY",
             ColumnType::String if is_enum => "ENUM",
+            ColumnType::String if is_set => "SET",
             ColumnType::VarChar | ColumnType::VarString if is_binary => "VARBINARY",
 
             ColumnType::String => "CHAR",
@@ -245,6 +249,7 @@ impl ColumnType {
             // [internal] 0x11 => ColumnType::Timestamp2,
             // [internal] 0x12 => ColumnType::Datetime2,
             // [internal] 0x13 => ColumnType::Time2,
+            0xf2 => ColumnType::Vector,
             0xf5 => ColumnType::Json,
             0xf6 => ColumnType::NewDecimal,
             0xf7 => ColumnType::Enum,
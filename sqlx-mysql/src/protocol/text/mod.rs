@@ -1,9 +1,11 @@
+mod change_user;
 mod column;
 mod ping;
 mod query;
 mod quit;
 mod row;
 
+pub(crate) use change_user::ChangeUser;
 pub(crate) use column::{ColumnDefinition, ColumnFlags, ColumnType};
 pub(crate) use ping::Ping;
 pub(crate) use query::Query;
@@ -1,4 +1,4 @@
-use crate::io::Encode;
+use crate::io::{Encode, MySqlBufMutExt};
 use crate::protocol::Capabilities;
 
 // https://dev.mysql.com/doc/internals/en/com-query.html
@@ -7,8 +7,15 @@ use crate::protocol::Capabilities;
 pub(crate) struct Query<'q>(pub(crate) &'q str);
 
 impl Encode<'_, Capabilities> for Query<'_> {
-    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode_with(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
         buf.push(0x03); // COM_QUERY
+
+        if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            // queries without arguments never have attributes
+            buf.put_uint_lenenc(0); // parameter_count
+            buf.put_uint_lenenc(1); // parameter_set_count (always 1)
+        }
+
         buf.extend(self.0.as_bytes())
     }
 }
@@ -0,0 +1,157 @@
+use std::cmp;
+
+use sqlx_core::query::{Map, Query};
+use sqlx_core::query_as::QueryAs;
+use sqlx_core::query_scalar::QueryScalar;
+
+use crate::io::AsyncRead;
+use crate::{MySql, MySqlArguments};
+
+/// Extension trait for binding parameters whose value is streamed to the server, for attaching
+/// query attributes, and for fetching rows in batches.
+///
+/// A parameter bound with [`bind_long_data`][Self::bind_long_data] is sent in chunks with
+/// `COM_STMT_SEND_LONG_DATA` before the statement is executed, so a large `BLOB` or `TEXT` value
+/// never has to be held in memory at once. The server still collects the whole value before
+/// executing the statement, so it may not be larger than its `max_allowed_packet`.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+/// use sqlx::mysql::MySqlQueryExt;
+///
+/// let file = tokio::fs::File::open("backup.tar").await?;
+///
+/// sqlx::query("INSERT INTO attachments (name, data) VALUES (?, ?)")
+///     .bind("backup.tar")
+///     .bind_long_data(file)
+///     .with_attribute("trace_id", "4bf92f3577b34da6")
+///     .execute(conn)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ### Note: Runtime Features
+/// This uses the `AsyncRead` trait which is re-exported from either Tokio or `async-std`
+/// depending on which runtime feature is used; if both are enabled, the Tokio version takes
+/// precedence.
+pub trait MySqlQueryExt: Sized {
+    /// Bind the next parameter to the contents of `source`, which are read to the end once the
+    /// query is executed.
+    ///
+    /// The parameter is sent as a `BLOB`. `source` can only be read once, so a query that is
+    /// executed again, e.g. by retrying, returns an error. If reading `source` fails, the query
+    /// fails with that error.
+    fn bind_long_data(self, source: impl AsyncRead + Send + Unpin + 'static) -> Self;
+
+    /// Attach the query attribute `name` with `value` to this query, which the server makes
+    /// available to the statement, e.g. to plugins and through
+    /// `mysql_query_attribute_string(name)`, for the duration of the query.
+    ///
+    /// Query attributes require MySQL 8.0.23 or later, and the `query_attributes` component to
+    /// read them with `mysql_query_attribute_string()`. Other servers fail the query with an
+    /// error.
+    fn with_attribute(self, name: impl Into<String>, value: impl Into<String>) -> Self;
+
+    /// Fetch the rows of this query through a read-only cursor, `rows` at a time, instead of
+    /// letting the server send all rows immediately.
+    ///
+    /// The server keeps the rest of the result set until it is fetched, so a large result set
+    /// can be streamed without the server blocking on a slow reader. It may store the result
+    /// set in a temporary table for the cursor. Only the first result set of a query can be
+    /// fetched with a cursor; `rows` is at least 1.
+    fn fetch_size(self, rows: u32) -> Self;
+}
+
+fn add_long_data(
+    arguments: Option<&mut MySqlArguments>,
+    source: impl AsyncRead + Send + Unpin + 'static,
+) {
+    if let Some(arguments) = arguments {
+        arguments.add_long_data(source);
+    }
+}
+
+fn add_attribute(
+    arguments: Option<&mut MySqlArguments>,
+    name: impl Into<String>,
+    value: impl Into<String>,
+) {
+    if let Some(arguments) = arguments {
+        arguments.attributes.push((name.into(), value.into()));
+    }
+}
+
+fn set_fetch_size(arguments: Option<&mut MySqlArguments>, rows: u32) {
+    if let Some(arguments) = arguments {
+        arguments.fetch_size = Some(cmp::max(rows, 1));
+    }
+}
+
+impl<'q> MySqlQueryExt for Query<'q, MySql, MySqlArguments> {
+    fn bind_long_data(mut self, source: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        add_long_data(self.arguments_mut(), source);
+        self
+    }
+
+    fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        add_attribute(self.arguments_mut(), name, value);
+        self
+    }
+
+    fn fetch_size(mut self, rows: u32) -> Self {
+        set_fetch_size(self.arguments_mut(), rows);
+        self
+    }
+}
+
+impl<'q, O> MySqlQueryExt for QueryAs<'q, MySql, O, MySqlArguments> {
+    fn bind_long_data(mut self, source: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        add_long_data(self.arguments_mut(), source);
+        self
+    }
+
+    fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        add_attribute(self.arguments_mut(), name, value);
+        self
+    }
+
+    fn fetch_size(mut self, rows: u32) -> Self {
+        set_fetch_size(self.arguments_mut(), rows);
+        self
+    }
+}
+
+impl<'q, O> MySqlQueryExt for QueryScalar<'q, MySql, O, MySqlArguments> {
+    fn bind_long_data(mut self, source: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        add_long_data(self.arguments_mut(), source);
+        self
+    }
+
+    fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        add_attribute(self.arguments_mut(), name, value);
+        self
+    }
+
+    fn fetch_size(mut self, rows: u32) -> Self {
+        set_fetch_size(self.arguments_mut(), rows);
+        self
+    }
+}
+
+impl<'q, F> MySqlQueryExt for Map<'q, MySql, F, MySqlArguments> {
+    fn bind_long_data(mut self, source: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        add_long_data(self.arguments_mut(), source);
+        self
+    }
+
+    fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        add_attribute(self.arguments_mut(), name, value);
+        self
+    }
+
+    fn fetch_size(mut self, rows: u32) -> Self {
+        set_fetch_size(self.arguments_mut(), rows);
+        self
+    }
+}
@@ -1,9 +1,18 @@
 use std::iter::{Extend, IntoIterator};
 
+/// The result of a statement, or of one result set of a query.
+///
+/// A query may return several result sets, e.g. a script with several statements or a `CALL`
+/// of a stored procedure. [`Executor::fetch_many()`][crate::Executor::fetch_many] returns the
+/// rows of each result set, each set followed by a `MySqlQueryResult`, and the rows of each set
+/// have their own [`columns`][crate::Row::columns]. The last `MySqlQueryResult` of a `CALL` is
+/// the status of the procedure itself.
 #[derive(Debug, Default)]
 pub struct MySqlQueryResult {
-    pub(super) rows_affected: u64,
-    pub(super) last_insert_id: u64,
+    pub(crate) rows_affected: u64,
+    pub(crate) last_insert_id: u64,
+    pub(crate) warning_count: u16,
+    pub(crate) warnings: Vec<MySqlWarning>,
 }
 
 impl MySqlQueryResult {
@@ -14,6 +23,70 @@ impl MySqlQueryResult {
     pub fn rows_affected(&self) -> u64 {
         self.rows_affected
     }
+
+    /// Returns the number of warnings raised by the statement, e.g. because a value was
+    /// truncated or converted on insert.
+    pub fn warning_count(&self) -> u16 {
+        self.warning_count
+    }
+
+    /// Returns the warnings raised by the statement, as listed by `SHOW WARNINGS`.
+    ///
+    /// This is only filled in if [`MySqlConnectOptions::fetch_warnings`] is set, and only for
+    /// the last statement of a query, as the server keeps the warnings of the last statement
+    /// only. The server lists at most `max_error_count` warnings, 1024 by default, which may be
+    /// less than [`warning_count`][Self::warning_count].
+    ///
+    /// [`MySqlConnectOptions::fetch_warnings`]: crate::MySqlConnectOptions::fetch_warnings
+    pub fn warnings(&self) -> &[MySqlWarning] {
+        &self.warnings
+    }
+}
+
+/// A warning, note or error raised by a statement, as listed by `SHOW WARNINGS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MySqlWarning {
+    pub(crate) level: MySqlWarningLevel,
+    pub(crate) code: u16,
+    pub(crate) message: String,
+}
+
+impl MySqlWarning {
+    /// Returns the level of the warning.
+    pub fn level(&self) -> MySqlWarningLevel {
+        self.level
+    }
+
+    /// Returns the [error number](https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html)
+    /// of the warning, e.g. `1265` for a truncated value.
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    /// Returns the human-readable message of the warning.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The level of a [`MySqlWarning`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MySqlWarningLevel {
+    /// A note, which is not counted as a warning if `sql_notes` is disabled.
+    Note,
+    Warning,
+    /// An error that did not fail the statement, e.g. with `INSERT IGNORE`.
+    Error,
+}
+
+impl MySqlWarningLevel {
+    pub(crate) fn parse(level: &str) -> Self {
+        match level {
+            "Note" => MySqlWarningLevel::Note,
+            "Error" => MySqlWarningLevel::Error,
+            _ => MySqlWarningLevel::Warning,
+        }
+    }
 }
 
 impl Extend<MySqlQueryResult> for MySqlQueryResult {
@@ -21,6 +94,8 @@ impl Extend<MySqlQueryResult> for MySqlQueryResult {
         for elem in iter {
             self.rows_affected += elem.rows_affected;
             self.last_insert_id = elem.last_insert_id;
+            self.warning_count = self.warning_count.saturating_add(elem.warning_count);
+            self.warnings.extend(elem.warnings);
         }
     }
 }
@@ -56,7 +56,7 @@ impl TransactionManager for MySqlTransactionManager {
 
         if depth > 0 {
             conn.stream.waiting.push_back(Waiting::Result);
-            conn.stream.sequence_id = 0;
+            conn.stream.reset_sequence_id();
             conn.stream
                 .write_packet(Query(&*rollback_ansi_transaction_sql(depth)));
 
@@ -45,6 +45,7 @@ impl MySqlTypeInfo {
             }
 
             ColumnType::Json => Some("json"),
+            ColumnType::Geometry => Some("geo-types"),
             ColumnType::NewDecimal => Some("bigdecimal"),
 
             _ => None,
@@ -0,0 +1,55 @@
+use bit_vec::BitVec;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::protocol::text::ColumnType;
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+impl Type<MySql> for BitVec {
+    fn type_info() -> MySqlTypeInfo {
+        // a binary string is converted to `BIT(M)` by the server, `MYSQL_TYPE_BIT` is not
+        // accepted for parameters
+        MySqlTypeInfo::binary(ColumnType::Blob)
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.r#type == ColumnType::Bit
+    }
+}
+
+impl Encode<'_, MySql> for BitVec {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // `BitVec` pads the last byte at the end, but the value of `BIT(M)` is right-aligned
+        let padding = (8 - self.len() % 8) % 8;
+
+        let bits: BitVec = std::iter::repeat(false)
+            .take(padding)
+            .chain(self.iter())
+            .collect();
+
+        buf.put_bytes_lenenc(&bits.to_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, MySql> for BitVec {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        // NOTE: Regardless of the value format, there is raw binary data here
+        let mut bits = BitVec::from_bytes(value.as_bytes()?);
+
+        // the value is padded to whole bytes at the start, leave `M` bits of `BIT(M)`
+        if let Some(size) = value.type_info.max_size {
+            let size = size as usize;
+
+            if size < bits.len() {
+                bits = bits.split_off(bits.len() - size);
+            }
+        }
+
+        Ok(bits)
+    }
+}
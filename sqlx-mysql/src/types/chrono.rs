@@ -112,11 +112,16 @@ impl<'r> Decode<'r, MySql> for NaiveTime {
 
                 // is negative : int<1>
                 let is_negative = buf.get_u8();
-                debug_assert_eq!(is_negative, 0, "Negative dates/times are not supported");
 
-                // "date on 4 bytes little-endian format" (?)
-                // https://mariadb.com/kb/en/resultset-row/#timestamp-binary-encoding
-                buf.advance(4);
+                // days : int<4>
+                let days = buf.get_u32_le();
+
+                // a time of day is neither negative nor longer than a day, see `MySqlTime`
+                if is_negative != 0 || days != 0 {
+                    return Err(
+                        "TIME is out of range for a time of day, decode `MySqlTime` instead".into(),
+                    );
+                }
 
                 decode_time(len - 5, buf)
             }
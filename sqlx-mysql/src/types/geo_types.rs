@@ -0,0 +1,96 @@
+use geo_types::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+use sqlx_core::io::wkb::{self, WriteWkb};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::protocol::text::ColumnType;
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+// Spatial values use MySQL's internal format: a 4-byte SRID followed by the WKB (well-known
+// binary) representation of the geometry.
+// https://dev.mysql.com/doc/refman/8.0/en/gis-data-formats.html#gis-internal-format
+
+impl Type<MySql> for Geometry<f64> {
+    fn type_info() -> MySqlTypeInfo {
+        // geometries are sent as binary strings in the internal format, which the server
+        // converts for `GEOMETRY` columns
+        MySqlTypeInfo::binary(ColumnType::Blob)
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.r#type == ColumnType::Geometry
+    }
+}
+
+impl Encode<'_, MySql> for Geometry<f64> {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        encode_internal(self, buf)
+    }
+}
+
+impl Decode<'_, MySql> for Geometry<f64> {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let bytes = value.as_bytes()?;
+
+        // the SRID is not part of `geo-types`
+        let wkb = bytes
+            .get(4..)
+            .ok_or("invalid geometry: missing the SRID of the internal format")?;
+
+        wkb::decode(wkb)
+    }
+}
+
+macro_rules! impl_geometry_type {
+    ($($ty:ident),*) => {
+        $(
+            impl Type<MySql> for $ty<f64> {
+                fn type_info() -> MySqlTypeInfo {
+                    <Geometry<f64> as Type<MySql>>::type_info()
+                }
+
+                fn compatible(ty: &MySqlTypeInfo) -> bool {
+                    <Geometry<f64> as Type<MySql>>::compatible(ty)
+                }
+            }
+
+            impl Encode<'_, MySql> for $ty<f64> {
+                fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+                    encode_internal(self, buf)
+                }
+            }
+
+            impl Decode<'_, MySql> for $ty<f64> {
+                fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+                    Ok($ty::try_from(<Geometry<f64> as Decode<MySql>>::decode(value)?)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_geometry_type!(
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection
+);
+
+fn encode_internal(geometry: &impl WriteWkb, buf: &mut Vec<u8>) -> IsNull {
+    // SRID 0, the Cartesian plane; `ST_SRID(?, 4326)` assigns another one in the query
+    let mut internal = 0_u32.to_le_bytes().to_vec();
+    geometry.write_wkb(&mut internal);
+
+    buf.put_bytes_lenenc(&internal);
+
+    IsNull::No
+}
@@ -4,6 +4,7 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::protocol::text::ColumnType;
+use crate::types::json_binary::{decode_binary_json, is_binary_json};
 use crate::types::{Json, Type};
 use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
 
@@ -63,6 +64,14 @@ where
     T: 'r + Deserialize<'r>,
 {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
-        Json::decode_from_string(value.as_str()?)
+        let buf = value.as_bytes()?;
+
+        // JSON text, unless the value is in the binary JSON format of MySQL, e.g. a document
+        // from the binary log that was stored in a `BLOB`
+        match Json::decode_from_bytes(buf) {
+            Err(_) if is_binary_json(buf) => Ok(Json(T::deserialize(decode_binary_json(buf)?)?)),
+
+            result => result,
+        }
     }
 }
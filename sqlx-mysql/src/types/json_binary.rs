@@ -0,0 +1,240 @@
+//! The binary JSON format, in which MySQL stores `JSON` columns and writes them to the binary
+//! log.
+//!
+//! <https://dev.mysql.com/doc/dev/mysql-server/8.0.26/json__binary_8h.html>
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+use crate::error::Error;
+use crate::types::JsonValue;
+
+const SMALL_OBJECT: u8 = 0x00;
+const LARGE_OBJECT: u8 = 0x01;
+const SMALL_ARRAY: u8 = 0x02;
+const LARGE_ARRAY: u8 = 0x03;
+const LITERAL: u8 = 0x04;
+const INT16: u8 = 0x05;
+const UINT16: u8 = 0x06;
+const INT32: u8 = 0x07;
+const UINT32: u8 = 0x08;
+const INT64: u8 = 0x09;
+const UINT64: u8 = 0x0a;
+const DOUBLE: u8 = 0x0b;
+const STRING: u8 = 0x0c;
+const OPAQUE: u8 = 0x0f;
+
+const LITERAL_NULL: u8 = 0x00;
+const LITERAL_TRUE: u8 = 0x01;
+const LITERAL_FALSE: u8 = 0x02;
+
+// documents are nested at most 100 levels deep by the server
+const MAX_DEPTH: usize = 100;
+
+/// Whether `buf` may be a binary JSON document rather than JSON text, which never starts with
+/// one of the type bytes other than the whitespace `\t` (`INT64`) and `\n` (`UINT64`).
+pub(crate) fn is_binary_json(buf: &[u8]) -> bool {
+    matches!(buf.first(), Some(&(SMALL_OBJECT..=STRING)) | Some(&OPAQUE))
+}
+
+/// Decode a binary JSON document: a type byte, followed by the value.
+pub(crate) fn decode_binary_json(buf: &[u8]) -> Result<JsonValue, Error> {
+    match buf.split_first() {
+        Some((&ty, value)) => decode_value(ty, value, 0),
+
+        // an empty document is `null`, e.g. for a `JSON` column that was added to a table
+        None => Ok(JsonValue::Null),
+    }
+}
+
+fn decode_value(ty: u8, buf: &[u8], depth: usize) -> Result<JsonValue, Error> {
+    if depth > MAX_DEPTH {
+        return Err(err_protocol!("binary JSON document is nested too deeply"));
+    }
+
+    Ok(match ty {
+        SMALL_OBJECT => decode_object(buf, false, depth)?,
+        LARGE_OBJECT => decode_object(buf, true, depth)?,
+        SMALL_ARRAY => decode_array(buf, false, depth)?,
+        LARGE_ARRAY => decode_array(buf, true, depth)?,
+
+        LITERAL => match get(buf, 0, 1)?[0] {
+            LITERAL_NULL => JsonValue::Null,
+            LITERAL_TRUE => JsonValue::Bool(true),
+            LITERAL_FALSE => JsonValue::Bool(false),
+            literal => return Err(err_protocol!("invalid binary JSON literal: {}", literal)),
+        },
+
+        INT16 => i16::from_le_bytes(array(buf)?).into(),
+        UINT16 => u16::from_le_bytes(array(buf)?).into(),
+        INT32 => i32::from_le_bytes(array(buf)?).into(),
+        UINT32 => u32::from_le_bytes(array(buf)?).into(),
+        INT64 => i64::from_le_bytes(array(buf)?).into(),
+        UINT64 => u64::from_le_bytes(array(buf)?).into(),
+        DOUBLE => f64::from_le_bytes(array(buf)?).into(),
+
+        STRING => {
+            let (len, offset) = get_variable_length(buf)?;
+            let s = std::str::from_utf8(get(buf, offset, len)?)
+                .map_err(|e| err_protocol!("invalid UTF-8 in binary JSON string: {}", e))?;
+
+            JsonValue::String(s.to_owned())
+        }
+
+        // a value of another MySQL type, e.g. a `DECIMAL` or `DATETIME` cast to JSON, printed the
+        // way the server prints opaque values it has no text form for
+        OPAQUE => {
+            let field_type = get(buf, 0, 1)?[0];
+            let (len, offset) = get_variable_length(&buf[1..])?;
+            let data = get(buf, 1 + offset, len)?;
+
+            JsonValue::String(format!(
+                "base64:type{field_type}:{}",
+                BASE64_STANDARD.encode(data)
+            ))
+        }
+
+        ty => return Err(err_protocol!("invalid binary JSON type: 0x{:02x}", ty)),
+    })
+}
+
+// object ::= element-count size key-entry* value-entry* key* value*
+fn decode_object(buf: &[u8], large: bool, depth: usize) -> Result<JsonValue, Error> {
+    let offset_size = if large { 4 } else { 2 };
+    let count = get_offset(buf, 0, large)?;
+
+    // a key entry is the offset and the length of the key
+    let key_entries = 2 * offset_size;
+    let value_entries = key_entries + count * (offset_size + 2);
+
+    (0..count)
+        .map(|i| {
+            let entry = key_entries + i * (offset_size + 2);
+            let key_offset = get_offset(buf, entry, large)?;
+            let key_len = u16::from_le_bytes(array(get(buf, entry + offset_size, 2)?)?);
+
+            let key = std::str::from_utf8(get(buf, key_offset, key_len.into())?)
+                .map_err(|e| err_protocol!("invalid UTF-8 in binary JSON key: {}", e))?;
+
+            let value = decode_entry(buf, value_entries + i * (1 + offset_size), large, depth)?;
+
+            Ok((key.to_owned(), value))
+        })
+        .collect::<Result<_, Error>>()
+        .map(JsonValue::Object)
+}
+
+// array ::= element-count size value-entry* value*
+fn decode_array(buf: &[u8], large: bool, depth: usize) -> Result<JsonValue, Error> {
+    let offset_size = if large { 4 } else { 2 };
+    let count = get_offset(buf, 0, large)?;
+
+    (0..count)
+        .map(|i| decode_entry(buf, 2 * offset_size + i * (1 + offset_size), large, depth))
+        .collect::<Result<_, Error>>()
+        .map(JsonValue::Array)
+}
+
+// value-entry ::= type offset-or-inlined-value
+fn decode_entry(buf: &[u8], entry: usize, large: bool, depth: usize) -> Result<JsonValue, Error> {
+    let ty = get(buf, entry, 1)?[0];
+    let value = entry + 1;
+
+    // small scalars are stored in the entry instead of an offset to them
+    let inlined = match ty {
+        LITERAL | INT16 | UINT16 => true,
+        INT32 | UINT32 => large,
+        _ => false,
+    };
+
+    if inlined {
+        return decode_value(ty, &buf[value..], depth + 1);
+    }
+
+    // offsets are relative to the start of the object or array
+    let offset = get_offset(buf, value, large)?;
+
+    if offset > buf.len() {
+        return Err(err_protocol!("binary JSON value offset out of range"));
+    }
+
+    decode_value(ty, &buf[offset..], depth + 1)
+}
+
+fn get(buf: &[u8], offset: usize, len: usize) -> Result<&[u8], Error> {
+    offset
+        .checked_add(len)
+        .and_then(|end| buf.get(offset..end))
+        .ok_or_else(|| err_protocol!("binary JSON document too short"))
+}
+
+fn array<const N: usize>(buf: &[u8]) -> Result<[u8; N], Error> {
+    Ok(get(buf, 0, N)?
+        .try_into()
+        .expect("BUG: slice has the length N"))
+}
+
+// element counts, sizes and offsets are 2 bytes in small and 4 bytes in large objects and arrays
+fn get_offset(buf: &[u8], offset: usize, large: bool) -> Result<usize, Error> {
+    Ok(if large {
+        u32::from_le_bytes(array(get(buf, offset, 4)?)?) as usize
+    } else {
+        u16::from_le_bytes(array(get(buf, offset, 2)?)?).into()
+    })
+}
+
+// the length of a string: 7 bits per byte, least significant first, while the high bit is set
+fn get_variable_length(buf: &[u8]) -> Result<(usize, usize), Error> {
+    let mut len = 0_usize;
+
+    for (i, &byte) in buf.iter().take(5).enumerate() {
+        len |= usize::from(byte & 0x7f) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Ok((len, i + 1));
+        }
+    }
+
+    Err(err_protocol!("invalid length in binary JSON document"))
+}
+
+#[test]
+fn test_decode_binary_json_scalars() {
+    assert_eq!(decode_binary_json(&[]).unwrap(), JsonValue::Null);
+    assert_eq!(
+        decode_binary_json(&[LITERAL, LITERAL_TRUE]).unwrap(),
+        JsonValue::Bool(true)
+    );
+    assert_eq!(
+        decode_binary_json(&[INT16, 0xfe, 0xff]).unwrap(),
+        JsonValue::from(-2)
+    );
+    assert_eq!(
+        decode_binary_json(&[DOUBLE, 0, 0, 0, 0, 0, 0, 0xf8, 0x3f]).unwrap(),
+        JsonValue::from(1.5)
+    );
+    assert_eq!(
+        decode_binary_json(b"\x0c\x05hello").unwrap(),
+        JsonValue::from("hello")
+    );
+
+    assert!(decode_binary_json(&[INT32, 0]).is_err());
+}
+
+#[test]
+fn test_decode_binary_json_object() {
+    // {"a": 1, "b": [true, "x"]}, as written by MySQL 8.0
+    const DATA: &[u8] = b"\x00\x02\x00\x20\x00\x12\x00\x01\x00\x13\x00\x01\x00\x05\x01\x00\
+        \x02\x14\x00ab\x02\x00\x0c\x00\x04\x01\x00\x0c\x0a\x00\x01x";
+
+    assert_eq!(
+        decode_binary_json(DATA).unwrap(),
+        serde_json_value(r#"{"a": 1, "b": [true, "x"]}"#)
+    );
+}
+
+#[cfg(test)]
+fn serde_json_value(s: &str) -> JsonValue {
+    crate::types::Json::<JsonValue>::decode_from_string(s)
+        .unwrap()
+        .0
+}
@@ -12,11 +12,25 @@
 //! | `u8`                                  | TINYINT UNSIGNED                                     |
 //! | `u16`                                 | SMALLINT UNSIGNED                                    |
 //! | `u32`                                 | INT UNSIGNED                                         |
-//! | `u64`                                 | BIGINT UNSIGNED                                      |
+//! | `u64`                                 | BIGINT UNSIGNED, BIT(M)                              |
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
-//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB                              |
+//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB, BIT(M)                      |
+//! | [`MySqlVector`]                       | VECTOR                                               |
+//! | [`MySqlSet<T>`]                       | SET                                                  |
+//! | [`MySqlTime`]                         | TIME                                                 |
+//! | `std::time::Duration`                 | TIME                                                 |
+//!
+//! `BIT(M)` decodes to the unsigned integer types that have at least M bits, e.g. `u8` for
+//! `BIT(1)` to `BIT(8)`. As bytes, the value is big-endian and padded to whole bytes with leading
+//! zero bits.
+//!
+//! A [`MySqlSet<T>`] holds the members of a `SET` value as `T`, e.g. an enum deriving `sqlx::Type`.
+//!
+//! A `TIME` is a signed duration of up to 838 hours. [`MySqlTime`] and the `Duration` types hold
+//! any such value, but `std::time::Duration` fails to decode a negative time, and the time of day
+//! types of `chrono` and `time` fail to decode a negative time or one of 24 hours or more.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -29,6 +43,7 @@
 //! | `chrono::NaiveDateTime`               | DATETIME                                             |
 //! | `chrono::NaiveDate`                   | DATE                                                 |
 //! | `chrono::NaiveTime`                   | TIME                                                 |
+//! | `chrono::Duration`                    | TIME                                                 |
 //!
 //! ### [`time`](https://crates.io/crates/time)
 //!
@@ -40,6 +55,7 @@
 //! | `time::OffsetDateTime`                | TIMESTAMP                                            |
 //! | `time::Date`                          | DATE                                                 |
 //! | `time::Time`                          | TIME                                                 |
+//! | `time::Duration`                      | TIME                                                 |
 //!
 //! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
 //! Requires the `bigdecimal` Cargo feature flag.
@@ -55,6 +71,16 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `rust_decimal::Decimal`               | DECIMAL                                              |
 //!
+//! ### [`bit-vec`](https://crates.io/crates/bit-vec)
+//!
+//! Requires the `bit-vec` Cargo feature flag.
+//!
+//! | Rust type                             | MySQL type(s)                                        |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bit_vec::BitVec`                     | BIT(M)                                               |
+//!
+//! A decoded `BitVec` holds exactly M bits, with the most significant bit first.
+//!
 //! ### [`uuid`](https://crates.io/crates/uuid)
 //!
 //! Requires the `uuid` Cargo feature flag.
@@ -65,6 +91,24 @@
 //! | `uuid::fmt::Hyphenated`               | CHAR(36)                                             |
 //! | `uuid::fmt::Simple`                   | CHAR(32)                                             |
 //!
+//! ### [`geo-types`](https://crates.io/crates/geo-types)
+//!
+//! Requires the `geo-types` Cargo feature flag.
+//!
+//! | Rust type                             | MySQL type(s)                                        |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `geo_types::Geometry<f64>`            | GEOMETRY                                             |
+//! | `geo_types::Point<f64>`               | POINT                                                |
+//! | `geo_types::LineString<f64>`          | LINESTRING                                           |
+//! | `geo_types::Polygon<f64>`             | POLYGON                                              |
+//! | `geo_types::MultiPoint<f64>`          | MULTIPOINT                                           |
+//! | `geo_types::MultiLineString<f64>`     | MULTILINESTRING                                      |
+//! | `geo_types::MultiPolygon<f64>`        | MULTIPOLYGON                                         |
+//! | `geo_types::GeometryCollection<f64>`  | GEOMETRYCOLLECTION                                   |
+//!
+//! `geo-types` has no SRID, so it is dropped when decoding, and values are encoded with SRID 0.
+//! Use `ST_SRID(?, 4326)` to bind a value to a column with another SRID.
+//!
 //! ### [`json`](https://crates.io/crates/serde_json)
 //!
 //! Requires the `json` Cargo feature flag.
@@ -75,6 +119,10 @@
 //! | `serde_json::JsonValue`               | JSON                                                 |
 //! | `&serde_json::value::RawValue`        | JSON                                                 |
 //!
+//! These also decode the results of JSON functions such as `JSON_EXTRACT()`, and `LONGTEXT` or
+//! `BLOB` columns, which MariaDB uses for `JSON`, without a `CAST(... AS CHAR)`. [`Json<T>`] and
+//! `JsonValue` also accept a document in the binary JSON format MySQL stores `JSON` columns in.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -86,12 +134,18 @@ mod bool;
 mod bytes;
 mod float;
 mod int;
+mod mysql_time;
+mod set;
 mod str;
 mod uint;
+mod vector;
 
 #[cfg(feature = "json")]
 mod json;
 
+#[cfg(feature = "json")]
+pub(crate) mod json_binary;
+
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
@@ -106,3 +160,13 @@ mod time;
 
 #[cfg(feature = "uuid")]
 mod uuid;
+
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
+
+#[cfg(feature = "geo-types")]
+mod geo_types;
+
+pub use mysql_time::MySqlTime;
+pub use set::MySqlSet;
+pub use vector::MySqlVector;
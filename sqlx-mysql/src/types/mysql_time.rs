@@ -0,0 +1,475 @@
+use std::cmp;
+use std::fmt::{self, Display, Formatter};
+
+use bytes::Buf;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::protocol::text::ColumnType;
+use crate::type_info::MySqlTypeInfo;
+use crate::types::Type;
+use crate::{MySql, MySqlValueFormat, MySqlValueRef};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+
+// 838:59:59
+const MAX_MICROS: i64 = (838 * 3600 + 59 * 60 + 59) * MICROS_PER_SECOND;
+
+/// The MySQL `TIME` type: a signed duration of up to 838 hours, 59 minutes and 59 seconds,
+/// with microsecond precision.
+///
+/// Unlike `chrono::NaiveTime` and `time::Time`, which are a time of day, this holds every value
+/// of a `TIME` column, including negative values and values of 24 hours or more, e.g. the
+/// result of `TIMEDIFF()`.
+///
+/// It converts losslessly to `chrono::Duration` and `time::Duration`, and to
+/// `std::time::Duration` if it is not negative. These can also be decoded from a `TIME`
+/// directly.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MySqlTime {
+    // negative for a negative time
+    microseconds: i64,
+}
+
+impl MySqlTime {
+    /// `00:00:00`.
+    pub const ZERO: Self = Self { microseconds: 0 };
+
+    /// `838:59:59`, the largest value of a `TIME`.
+    pub const MAX: Self = Self {
+        microseconds: MAX_MICROS,
+    };
+
+    /// `-838:59:59`, the smallest value of a `TIME`.
+    pub const MIN: Self = Self {
+        microseconds: -MAX_MICROS,
+    };
+
+    /// Construct a `TIME` of `hours:minutes:seconds.microseconds`, which is negated if
+    /// `negative` is set.
+    ///
+    /// Returns an error if a field is out of range, or the value is larger than
+    /// [`MAX`][Self::MAX].
+    pub fn new(
+        negative: bool,
+        hours: u32,
+        minutes: u8,
+        seconds: u8,
+        microseconds: u32,
+    ) -> Result<Self, BoxDynError> {
+        if minutes > 59 || seconds > 59 || i64::from(microseconds) >= MICROS_PER_SECOND {
+            return Err(format!(
+                "invalid MySQL `TIME`: {hours}:{minutes:02}:{seconds:02}.{microseconds:06}"
+            )
+            .into());
+        }
+
+        let microseconds = (i64::from(hours) * 3600 + i64::from(minutes) * 60 + i64::from(seconds))
+            * MICROS_PER_SECOND
+            + i64::from(microseconds);
+
+        Self::from_microseconds(if negative {
+            -microseconds
+        } else {
+            microseconds
+        })
+    }
+
+    /// Construct a `TIME` from a signed number of microseconds.
+    ///
+    /// Returns an error if the value is outside of [`MIN`][Self::MIN] and [`MAX`][Self::MAX].
+    pub fn from_microseconds(microseconds: i64) -> Result<Self, BoxDynError> {
+        if !(-MAX_MICROS..=MAX_MICROS).contains(&microseconds) {
+            return Err(
+                format!("{microseconds} microseconds is out of range for MySQL `TIME`").into(),
+            );
+        }
+
+        Ok(Self { microseconds })
+    }
+
+    /// The signed number of microseconds of this time.
+    pub fn as_microseconds(&self) -> i64 {
+        self.microseconds
+    }
+
+    /// Whether this time is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.microseconds < 0
+    }
+
+    /// The hours of this time, regardless of the sign, up to 838.
+    pub fn hours(&self) -> u32 {
+        (self.microseconds.unsigned_abs() / 3_600_000_000) as u32
+    }
+
+    /// The minutes of this time, regardless of the sign.
+    pub fn minutes(&self) -> u8 {
+        (self.microseconds.unsigned_abs() / 60_000_000 % 60) as u8
+    }
+
+    /// The seconds of this time, regardless of the sign.
+    pub fn seconds(&self) -> u8 {
+        (self.microseconds.unsigned_abs() / 1_000_000 % 60) as u8
+    }
+
+    /// The fraction of a second of this time in microseconds, regardless of the sign.
+    pub fn microseconds(&self) -> u32 {
+        (self.microseconds.unsigned_abs() % 1_000_000) as u32
+    }
+
+    fn parse(s: &str) -> Result<Self, BoxDynError> {
+        let invalid = || format!("invalid MySQL `TIME`: {s:?}");
+
+        let (negative, time) = match s.strip_prefix('-') {
+            Some(time) => (true, time),
+            None => (false, s),
+        };
+
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+
+        let mut parts = time.splitn(3, ':');
+        let (Some(hours), Some(minutes), Some(seconds)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid().into());
+        };
+
+        if fraction.len() > 6 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid().into());
+        }
+
+        // the fraction has as many digits as the column, e.g. `.5` for `TIME(1)`
+        let microseconds = match fraction {
+            "" => 0,
+            _ => format!("{fraction:0<6}").parse().map_err(|_| invalid())?,
+        };
+
+        Self::new(
+            negative,
+            hours.parse().map_err(|_| invalid())?,
+            minutes.parse().map_err(|_| invalid())?,
+            seconds.parse().map_err(|_| invalid())?,
+            microseconds,
+        )
+    }
+}
+
+impl Display for MySqlTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.hours(),
+            self.minutes(),
+            self.seconds()
+        )?;
+
+        if self.microseconds() != 0 {
+            write!(f, ".{:06}", self.microseconds())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Type<MySql> for MySqlTime {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Time)
+    }
+}
+
+impl Encode<'_, MySql> for MySqlTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let len = Encode::<MySql>::size_hint(self) - 1;
+        buf.push(len as u8);
+
+        buf.push(self.is_negative() as u8);
+
+        // the hours are split into days and hours
+        buf.extend_from_slice(&(self.hours() / 24).to_le_bytes());
+        buf.push((self.hours() % 24) as u8);
+        buf.push(self.minutes());
+        buf.push(self.seconds());
+
+        if len > 8 {
+            buf.extend_from_slice(&self.microseconds().to_le_bytes());
+        }
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        if self.microseconds() == 0 {
+            // if micro_seconds is 0, length is 8 and micro_seconds is not sent
+            9
+        } else {
+            // otherwise length is 12
+            13
+        }
+    }
+}
+
+impl<'r> Decode<'r, MySql> for MySqlTime {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            MySqlValueFormat::Binary => {
+                let mut buf = value.as_bytes()?;
+
+                // data length, expecting 0, 8 or 12 (fractional seconds)
+                let len = buf.get_u8();
+
+                // MySQL specifies that if all of hours, minutes, seconds, microseconds
+                // are 0 then the length is 0 and no further data is send
+                // https://dev.mysql.com/doc/internals/en/binary-protocol-value.html
+                if len == 0 {
+                    return Ok(Self::ZERO);
+                }
+
+                if buf.len() < 8 {
+                    return Err(format!("expected at least 8 bytes for TIME, got {len}").into());
+                }
+
+                let negative = buf.get_u8() != 0;
+                let days = buf.get_u32_le();
+                let hours = buf.get_u8();
+                let minutes = buf.get_u8();
+                let seconds = buf.get_u8();
+
+                let microseconds = if buf.is_empty() {
+                    0
+                } else {
+                    // microseconds : int<EOF>
+                    buf.get_uint_le(cmp::min(buf.len(), 4)) as u32
+                };
+
+                let hours = days
+                    .checked_mul(24)
+                    .and_then(|hours_of_days| hours_of_days.checked_add(hours.into()))
+                    .ok_or("server returned invalid TIME: too many days")?;
+
+                Self::new(negative, hours, minutes, seconds, microseconds)
+            }
+
+            MySqlValueFormat::Text => Self::parse(value.as_str()?),
+        }
+    }
+}
+
+// `std::time::Duration`, `chrono::Duration` and `time::Duration` are encoded through `MySqlTime`
+
+impl TryFrom<std::time::Duration> for MySqlTime {
+    type Error = BoxDynError;
+
+    /// Convert a `std::time::Duration` to a `MySqlTime`.
+    ///
+    /// This returns an error if there is a loss of precision using nanoseconds or if the
+    /// duration is longer than [`MySqlTime::MAX`].
+    fn try_from(value: std::time::Duration) -> Result<Self, BoxDynError> {
+        if value.subsec_nanos() % 1000 != 0 {
+            return Err("MySQL `TIME` does not support nanoseconds precision".into());
+        }
+
+        Self::from_microseconds(value.as_micros().try_into()?)
+    }
+}
+
+impl TryFrom<MySqlTime> for std::time::Duration {
+    type Error = BoxDynError;
+
+    /// Convert a `MySqlTime` to a `std::time::Duration`.
+    ///
+    /// This returns an error if the time is negative.
+    fn try_from(value: MySqlTime) -> Result<Self, BoxDynError> {
+        let microseconds = u64::try_from(value.microseconds)
+            .map_err(|_| format!("`std::time::Duration` cannot be negative: {value}"))?;
+
+        Ok(std::time::Duration::from_micros(microseconds))
+    }
+}
+
+impl Type<MySql> for std::time::Duration {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Time)
+    }
+}
+
+impl Encode<'_, MySql> for std::time::Duration {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        MySqlTime::try_from(*self)
+            .expect("failed to encode `std::time::Duration`")
+            .encode_by_ref(buf)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for std::time::Duration {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        MySqlTime::decode(value)?.try_into()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::Duration> for MySqlTime {
+    type Error = BoxDynError;
+
+    /// Convert a `chrono::Duration` to a `MySqlTime`.
+    ///
+    /// This returns an error if there is a loss of precision using nanoseconds or if the
+    /// duration is outside of [`MySqlTime::MIN`] and [`MySqlTime::MAX`].
+    fn try_from(value: chrono::Duration) -> Result<Self, BoxDynError> {
+        let nanoseconds = value
+            .num_nanoseconds()
+            .ok_or("duration is out of range for MySQL `TIME`")?;
+
+        if nanoseconds % 1000 != 0 {
+            return Err("MySQL `TIME` does not support nanoseconds precision".into());
+        }
+
+        Self::from_microseconds(nanoseconds / 1000)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<MySqlTime> for chrono::Duration {
+    fn from(value: MySqlTime) -> Self {
+        chrono::Duration::microseconds(value.microseconds)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<MySql> for chrono::Duration {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Time)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Encode<'_, MySql> for chrono::Duration {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        MySqlTime::try_from(*self)
+            .expect("failed to encode `chrono::Duration`")
+            .encode_by_ref(buf)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'r> Decode<'r, MySql> for chrono::Duration {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(MySqlTime::decode(value)?.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::Duration> for MySqlTime {
+    type Error = BoxDynError;
+
+    /// Convert a `time::Duration` to a `MySqlTime`.
+    ///
+    /// This returns an error if there is a loss of precision using nanoseconds or if the
+    /// duration is outside of [`MySqlTime::MIN`] and [`MySqlTime::MAX`].
+    fn try_from(value: time::Duration) -> Result<Self, BoxDynError> {
+        let nanoseconds = value.whole_nanoseconds();
+
+        if nanoseconds % 1000 != 0 {
+            return Err("MySQL `TIME` does not support nanoseconds precision".into());
+        }
+
+        Self::from_microseconds(
+            (nanoseconds / 1000)
+                .try_into()
+                .map_err(|_| "duration is out of range for MySQL `TIME`")?,
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<MySqlTime> for time::Duration {
+    fn from(value: MySqlTime) -> Self {
+        time::Duration::microseconds(value.microseconds)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Type<MySql> for time::Duration {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Time)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Encode<'_, MySql> for time::Duration {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        MySqlTime::try_from(*self)
+            .expect("failed to encode `time::Duration`")
+            .encode_by_ref(buf)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'r> Decode<'r, MySql> for time::Duration {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(MySqlTime::decode(value)?.into())
+    }
+}
+
+#[test]
+fn test_encode_time() {
+    let mut buf = Vec::new();
+
+    let time = MySqlTime::new(true, 838, 59, 59, 0).unwrap();
+    assert_eq!(time, MySqlTime::MIN);
+
+    let _ = Encode::<MySql>::encode(time, &mut buf);
+    assert_eq!(buf, [8, 1, 34, 0, 0, 0, 22, 59, 59]);
+
+    buf.clear();
+
+    let time = MySqlTime::new(false, 25, 0, 1, 500).unwrap();
+
+    let _ = Encode::<MySql>::encode(time, &mut buf);
+    assert_eq!(buf, [12, 0, 1, 0, 0, 0, 1, 0, 1, 0xf4, 1, 0, 0]);
+}
+
+#[test]
+fn test_parse_time() {
+    assert_eq!(MySqlTime::parse("00:00:00").unwrap(), MySqlTime::ZERO);
+    assert_eq!(MySqlTime::parse("-838:59:59").unwrap(), MySqlTime::MIN);
+
+    let time = MySqlTime::parse("-00:00:01.5").unwrap();
+    assert_eq!(time.as_microseconds(), -1_500_000);
+    assert_eq!(time.to_string(), "-00:00:01.500000");
+
+    let time = MySqlTime::parse("123:04:05.000006").unwrap();
+    assert_eq!(
+        (
+            time.hours(),
+            time.minutes(),
+            time.seconds(),
+            time.microseconds()
+        ),
+        (123, 4, 5, 6)
+    );
+
+    assert!(MySqlTime::parse("839:00:00").is_err());
+    assert!(MySqlTime::parse("12:60:00").is_err());
+    assert!(MySqlTime::parse("12:00").is_err());
+}
+
+#[test]
+fn test_convert_time() {
+    let time = MySqlTime::new(true, 100, 0, 0, 1).unwrap();
+
+    assert!(std::time::Duration::try_from(time).is_err());
+    assert_eq!(
+        std::time::Duration::try_from(MySqlTime::MAX).unwrap(),
+        std::time::Duration::from_secs(838 * 3600 + 59 * 60 + 59)
+    );
+
+    assert!(MySqlTime::try_from(std::time::Duration::from_nanos(1)).is_err());
+    assert!(MySqlTime::try_from(std::time::Duration::from_secs(839 * 3600)).is_err());
+}
@@ -0,0 +1,197 @@
+use std::ops::Deref;
+
+use bytes::Bytes;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::{MySqlBufExt, MySqlBufMutExt};
+use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::Type;
+use crate::value::MySqlValueFormat;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+/// The members of a `SET` value, e.g. of a `SET('read','write','admin')` column.
+///
+/// MySQL sends a `SET` value as its members separated by commas, in the order of the column
+/// definition, and accepts them in any order. Each member is decoded and encoded as `T`, which
+/// can be `String`, `&str` or an enum deriving `sqlx::Type`:
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::mysql::MySqlConnection) -> sqlx::Result<()> {
+/// use sqlx::mysql::types::MySqlSet;
+///
+/// #[derive(sqlx::Type, Debug, PartialEq)]
+/// #[sqlx(rename_all = "lowercase")]
+/// enum Permission {
+///     Read,
+///     Write,
+///     Admin,
+/// }
+///
+/// let permissions: MySqlSet<Permission> = sqlx::query_scalar("SELECT permissions FROM users")
+///     .fetch_one(&mut *conn)
+///     .await?;
+///
+/// assert_eq!(permissions.as_slice(), [Permission::Read, Permission::Write]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// For a `bitflags` type, decode a `MySqlSet<String>` and collect the flags from the names of
+/// the members, e.g. with `Flags::from_name`.
+///
+/// The query macros use `MySqlSet<String>` for a `SET` column; use an override for another
+/// member type, e.g. `SELECT permissions as "permissions: MySqlSet<Permission>"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MySqlSet<T>(Vec<T>);
+
+impl<T> MySqlSet<T> {
+    /// Returns the members of this set.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Returns the members of this set.
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Default for MySqlSet<T> {
+    fn default() -> Self {
+        MySqlSet(Vec::new())
+    }
+}
+
+impl<T> From<Vec<T>> for MySqlSet<T> {
+    fn from(members: Vec<T>) -> Self {
+        MySqlSet(members)
+    }
+}
+
+impl<T> From<MySqlSet<T>> for Vec<T> {
+    fn from(set: MySqlSet<T>) -> Self {
+        set.0
+    }
+}
+
+impl<T> FromIterator<T> for MySqlSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        MySqlSet(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for MySqlSet<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> Deref for MySqlSet<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Type<MySql> for MySqlSet<T> {
+    fn type_info() -> MySqlTypeInfo {
+        // the server converts a string of the members to `SET`
+        <str as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        // a `SET` column is sent as a `CHAR` with the `SET` flag
+        ty.r#type == ColumnType::Set || ty.flags.contains(ColumnFlags::SET)
+    }
+}
+
+impl<'q, T> Encode<'q, MySql> for MySqlSet<T>
+where
+    T: Encode<'q, MySql>,
+{
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let mut members = Vec::new();
+
+        for member in &self.0 {
+            // each member is encoded as a length-encoded string
+            let mut encoded = Vec::new();
+
+            if let IsNull::Yes = member.encode_by_ref(&mut encoded) {
+                continue;
+            }
+
+            if !members.is_empty() {
+                members.push(b',');
+            }
+
+            members.extend_from_slice(&Bytes::from(encoded).get_bytes_lenenc());
+        }
+
+        buf.put_bytes_lenenc(&members);
+
+        IsNull::No
+    }
+}
+
+impl<'r, T> Decode<'r, MySql> for MySqlSet<T>
+where
+    T: Decode<'r, MySql>,
+{
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let bytes = value.as_bytes()?;
+
+        // the empty set
+        if bytes.is_empty() {
+            return Ok(MySqlSet::default());
+        }
+
+        // the members never contain a comma, which the server rejects in a `SET` definition
+        bytes
+            .split(|&b| b == b',')
+            .map(|member| {
+                T::decode(MySqlValueRef {
+                    value: Some(member),
+                    row: None,
+                    type_info: value.type_info.clone(),
+                    format: MySqlValueFormat::Text,
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_encode_set() {
+    let mut buf = Vec::new();
+    let _ = MySqlSet::from(vec!["read", "write"]).encode_by_ref(&mut buf);
+
+    assert_eq!(buf, b"\x0aread,write");
+
+    let mut buf = Vec::new();
+    let _ = MySqlSet::<&str>::default().encode_by_ref(&mut buf);
+
+    assert_eq!(buf, [0]);
+}
+
+#[test]
+fn test_decode_set() {
+    fn decode(value: &[u8]) -> MySqlSet<String> {
+        MySqlSet::decode(MySqlValueRef {
+            value: Some(value),
+            row: None,
+            type_info: MySqlTypeInfo::binary(ColumnType::Set),
+            format: MySqlValueFormat::Text,
+        })
+        .unwrap()
+    }
+
+    assert_eq!(decode(b"read,write").as_slice(), ["read", "write"]);
+    assert_eq!(decode(b"admin").as_slice(), ["admin"]);
+    assert!(decode(b"").is_empty());
+}
@@ -15,7 +15,12 @@ fn uint_type_info(ty: ColumnType) -> MySqlTypeInfo {
     }
 }
 
-fn uint_compatible(ty: &MySqlTypeInfo) -> bool {
+fn uint_compatible(ty: &MySqlTypeInfo, bits: u32) -> bool {
+    // `BIT(M)` fits if the integer has at least M bits
+    if ty.r#type == ColumnType::Bit {
+        return ty.max_size.map_or(true, |size| size <= bits);
+    }
+
     matches!(
         ty.r#type,
         ColumnType::Tiny
@@ -24,7 +29,6 @@ fn uint_compatible(ty: &MySqlTypeInfo) -> bool {
             | ColumnType::Int24
             | ColumnType::LongLong
             | ColumnType::Year
-            | ColumnType::Bit
     ) && ty.flags.contains(ColumnFlags::UNSIGNED)
 }
 
@@ -34,7 +38,7 @@ impl Type<MySql> for u8 {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        uint_compatible(ty)
+        uint_compatible(ty, 8)
     }
 }
 
@@ -44,7 +48,7 @@ impl Type<MySql> for u16 {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        uint_compatible(ty)
+        uint_compatible(ty, 16)
     }
 }
 
@@ -54,7 +58,7 @@ impl Type<MySql> for u32 {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        uint_compatible(ty)
+        uint_compatible(ty, 32)
     }
 }
 
@@ -64,7 +68,7 @@ impl Type<MySql> for u64 {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        uint_compatible(ty)
+        uint_compatible(ty, 64)
     }
 }
 
@@ -102,9 +106,15 @@ impl Encode<'_, MySql> for u64 {
 
 fn uint_decode(value: MySqlValueRef<'_>) -> Result<u64, BoxDynError> {
     if value.type_info.r#type == ColumnType::Bit {
-        // NOTE: Regardless of the value format, there is raw binary data here
+        // NOTE: Regardless of the value format, there is raw binary data here, in big-endian
+        //       order and padded to whole bytes
 
         let buf = value.as_bytes()?;
+
+        if buf.len() > 8 {
+            return Err(format!("BIT value of {} bytes is too wide for u64", buf.len()).into());
+        }
+
         let mut value: u64 = 0;
 
         for b in buf {
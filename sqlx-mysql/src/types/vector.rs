@@ -0,0 +1,109 @@
+use std::ops::Deref;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::protocol::text::ColumnType;
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+/// A vector of single-precision floats, the `VECTOR` type of MySQL 9 and MariaDB 11.7.
+///
+/// Both servers send the elements as little-endian floats, in the text protocol as well. MariaDB
+/// reports the column as `VARBINARY`, so the query macros need an override to use this type,
+/// e.g. `SELECT embedding as "embedding: MySqlVector"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MySqlVector(Vec<f32>);
+
+impl MySqlVector {
+    /// Returns the elements of this vector.
+    pub fn as_slice(&self) -> &[f32] {
+        &self.0
+    }
+
+    /// Returns the elements of this vector.
+    pub fn into_vec(self) -> Vec<f32> {
+        self.0
+    }
+}
+
+impl From<Vec<f32>> for MySqlVector {
+    fn from(elements: Vec<f32>) -> Self {
+        MySqlVector(elements)
+    }
+}
+
+impl From<&[f32]> for MySqlVector {
+    fn from(elements: &[f32]) -> Self {
+        MySqlVector(elements.to_vec())
+    }
+}
+
+impl From<MySqlVector> for Vec<f32> {
+    fn from(vector: MySqlVector) -> Self {
+        vector.0
+    }
+}
+
+impl Deref for MySqlVector {
+    type Target = [f32];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Type<MySql> for MySqlVector {
+    fn type_info() -> MySqlTypeInfo {
+        // both servers convert a binary string of the right length to `VECTOR`
+        MySqlTypeInfo::binary(ColumnType::Blob)
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        match ty.r#type {
+            ColumnType::Vector => true,
+
+            // MariaDB
+            ColumnType::VarChar | ColumnType::VarString | ColumnType::Blob => ty.char_set == 63,
+
+            _ => false,
+        }
+    }
+}
+
+impl Encode<'_, MySql> for MySqlVector {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let mut bytes = vec![0; self.0.len() * 4];
+        LittleEndian::write_f32_into(&self.0, &mut bytes);
+
+        buf.put_bytes_lenenc(&bytes);
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, MySql> for MySqlVector {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        // NOTE: Regardless of the value format, there is raw binary data here
+        let bytes = value.as_bytes()?;
+
+        if bytes.len() % 4 != 0 {
+            return Err(format!("invalid VECTOR value of {} bytes", bytes.len()).into());
+        }
+
+        Ok(MySqlVector(
+            bytes.chunks_exact(4).map(LittleEndian::read_f32).collect(),
+        ))
+    }
+}
+
+#[test]
+fn test_encode_vector() {
+    let mut buf = Vec::new();
+    let _ = MySqlVector::from(vec![1.0, -2.5]).encode_by_ref(&mut buf);
+
+    assert_eq!(buf, [8, 0, 0, 0x80, 0x3f, 0, 0, 0x20, 0xc0]);
+}
@@ -1,6 +1,7 @@
 use crate::error::Result;
+use crate::transaction::Transaction;
 use crate::Either;
-use crate::PgConnection;
+use crate::{PgConnection, Postgres};
 use hkdf::Hkdf;
 use once_cell::sync::OnceCell;
 use sha2::Sha256;
@@ -16,13 +17,12 @@ use std::ops::{Deref, DerefMut};
 /// advisory locks use, as well as RAII guards for releasing advisory locks when they fall out
 /// of scope.
 ///
-/// This API only handles session-scoped advisory locks (explicitly locked and unlocked, or
-/// automatically released when a connection is closed).
+/// Both session-scoped advisory locks (explicitly locked and unlocked, or automatically released
+/// when a connection is closed) and transaction-scoped advisory locks are supported.
 ///
-/// It is also possible to use transaction-scoped locks but those can be used by beginning a
-/// transaction and calling the appropriate lock functions (e.g. `SELECT pg_advisory_xact_lock()`)
-/// manually, and cannot be explicitly released, but are automatically released when a transaction
-/// ends (is committed or rolled back).
+/// Transaction-scoped locks are acquired with [`Self::acquire_xact()`] or
+/// [`Self::try_acquire_xact()`] inside a transaction, and cannot be explicitly released, but are
+/// automatically released when the transaction ends (is committed or rolled back).
 ///
 /// Session-level locks can be acquired either inside or outside a transaction and are not
 /// tied to transaction semantics; a lock acquired inside a transaction is still held when that
@@ -82,6 +82,21 @@ pub struct PgAdvisoryLockGuard<'lock, C: AsMut<PgConnection>> {
     conn: Option<C>,
 }
 
+/// A wrapper for a [`Transaction`] that represents a held transaction-scoped Postgres advisory lock.
+///
+/// Can be acquired by [`PgAdvisoryLock::acquire_xact()`] or
+/// [`PgAdvisoryLock::try_acquire_xact()`].
+///
+/// ### Note: The lock is not released on drop!
+/// Transaction-scoped advisory locks cannot be released explicitly, so the lock stays held until
+/// the transaction is committed or rolled back, even after this guard is dropped. The guard
+/// only borrows the transaction, so it must be dropped (or [unwrapped][Self::into_inner()])
+/// before the transaction can be committed.
+pub struct PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    lock: &'lock PgAdvisoryLock,
+    tx: &'tx mut Transaction<'c, Postgres>,
+}
+
 impl PgAdvisoryLock {
     /// Construct a `PgAdvisoryLock` using the given string as a key.
     ///
@@ -269,6 +284,84 @@ impl PgAdvisoryLock {
         }
     }
 
+    /// Acquires an exclusive transaction-scoped lock using `pg_advisory_xact_lock()`, waiting until
+    /// the lock is acquired.
+    ///
+    /// For a version that returns immediately instead of waiting, see
+    /// [`Self::try_acquire_xact()`].
+    ///
+    /// The lock is held until the transaction ends. For a nested transaction (a savepoint), that
+    /// is the end of the outermost transaction, as Postgres does not release transaction-scoped
+    /// locks when rolling back to a savepoint.
+    ///
+    /// Like session-scoped locks, transaction-scoped locks are re-entrant, and both kinds can be
+    /// held at the same time for the same key.
+    ///
+    /// See [Postgres' documentation for the Advisory Lock Functions][advisory-funcs] for details.
+    ///
+    /// [advisory-funcs]: https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS
+    pub async fn acquire_xact<'tx, 'c>(
+        &self,
+        tx: &'tx mut Transaction<'c, Postgres>,
+    ) -> Result<PgAdvisoryXactLockGuard<'_, 'tx, 'c>> {
+        match &self.key {
+            PgAdvisoryLockKey::BigInt(key) => {
+                crate::query::query("SELECT pg_advisory_xact_lock($1)")
+                    .bind(key)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            PgAdvisoryLockKey::IntPair(key1, key2) => {
+                crate::query::query("SELECT pg_advisory_xact_lock($1, $2)")
+                    .bind(key1)
+                    .bind(key2)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+
+        Ok(PgAdvisoryXactLockGuard { lock: self, tx })
+    }
+
+    /// Acquires an exclusive transaction-scoped lock using `pg_try_advisory_xact_lock()`,
+    /// returning immediately if the lock could not be acquired.
+    ///
+    /// For a version that waits until the lock is acquired, see [`Self::acquire_xact()`].
+    ///
+    /// The transaction is returned if the lock could not be acquired. Otherwise, the lock is held
+    /// until the transaction ends, as described in [`Self::acquire_xact()`].
+    ///
+    /// See [Postgres' documentation for the Advisory Lock Functions][advisory-funcs] for details.
+    ///
+    /// [advisory-funcs]: https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS
+    pub async fn try_acquire_xact<'tx, 'c>(
+        &self,
+        tx: &'tx mut Transaction<'c, Postgres>,
+    ) -> Result<Either<PgAdvisoryXactLockGuard<'_, 'tx, 'c>, &'tx mut Transaction<'c, Postgres>>>
+    {
+        let locked: bool = match &self.key {
+            PgAdvisoryLockKey::BigInt(key) => {
+                crate::query_scalar::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+                    .bind(key)
+                    .fetch_one(&mut **tx)
+                    .await?
+            }
+            PgAdvisoryLockKey::IntPair(key1, key2) => {
+                crate::query_scalar::query_scalar("SELECT pg_try_advisory_xact_lock($1, $2)")
+                    .bind(key1)
+                    .bind(key2)
+                    .fetch_one(&mut **tx)
+                    .await?
+            }
+        };
+
+        if locked {
+            Ok(Either::Left(PgAdvisoryXactLockGuard { lock: self, tx }))
+        } else {
+            Ok(Either::Right(tx))
+        }
+    }
+
     /// Execute `pg_advisory_unlock()` for this lock's key on the given connection.
     ///
     /// This is used by [`PgAdvisoryLockGuard::release_now()`] and is also provided for manually
@@ -419,3 +512,43 @@ impl<'lock, C: AsMut<PgConnection>> Drop for PgAdvisoryLockGuard<'lock, C> {
         }
     }
 }
+
+impl<'lock, 'tx, 'c> PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    /// Returns the lock that is held.
+    pub fn lock(&self) -> &'lock PgAdvisoryLock {
+        self.lock
+    }
+
+    /// Returns the borrowed transaction, e.g. to take another lock in it.
+    ///
+    /// The lock stays held until the transaction ends.
+    pub fn into_inner(self) -> &'tx mut Transaction<'c, Postgres> {
+        self.tx
+    }
+}
+
+impl<'lock, 'tx, 'c> Deref for PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.tx
+    }
+}
+
+impl<'lock, 'tx, 'c> DerefMut for PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.tx
+    }
+}
+
+impl<'lock, 'tx, 'c> AsRef<PgConnection> for PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    fn as_ref(&self) -> &PgConnection {
+        self.tx
+    }
+}
+
+impl<'lock, 'tx, 'c> AsMut<PgConnection> for PgAdvisoryXactLockGuard<'lock, 'tx, 'c> {
+    fn as_mut(&mut self) -> &mut PgConnection {
+        self.tx
+    }
+}
@@ -56,6 +56,9 @@ pub struct PgArguments {
 
     // Buffer of encoded bind parameters
     pub(crate) buffer: PgArgumentBuffer,
+
+    // Server settings while the query runs, from `PgQueryExt::with_setting`
+    pub(crate) settings: Vec<(String, String)>,
 }
 
 impl PgArguments {
@@ -167,6 +170,26 @@ impl PgArgumentBuffer {
         self.extend_from_slice(&0_u32.to_be_bytes());
         self.type_holes.push((offset, type_name.clone()));
     }
+
+    // Fill in the type holes with OIDs the connection already knows of
+    // This is for when we cannot ask postgres, e.g. in the middle of a `COPY`
+    pub(crate) fn patch_cached_types(&mut self, conn: &PgConnection) -> Result<(), Error> {
+        for (offset, name) in &self.type_holes {
+            let oid = conn.cached_type_id_by_name(name).ok_or_else(|| {
+                Error::Encode(
+                    format!(
+                        "the OID of type `{name}` cannot be looked up while in COPY mode; \
+                         use the type in a query before starting the COPY"
+                    )
+                    .into(),
+                )
+            })?;
+
+            self.buffer[*offset..(*offset + 4)].copy_from_slice(&oid.0.to_be_bytes());
+        }
+
+        Ok(())
+    }
 }
 
 impl Deref for PgArgumentBuffer {
@@ -0,0 +1,98 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::connection::stream::PgStream;
+use crate::error::Error;
+use crate::message::CancelRequest;
+use crate::{PgConnectOptions, PgConnection};
+
+// https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-CANCELING-REQUESTS
+
+impl PgConnection {
+    /// Returns a token that cancels the query running on this connection, from another task.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let token = conn.cancel_token();
+    ///
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_secs(5)).await;
+    ///     token.cancel().await
+    /// });
+    ///
+    /// // fails with `query_canceled` (57014) after 5 seconds
+    /// let res = sqlx::query("SELECT pg_sleep(60)").execute(conn).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cancel_token(&self) -> PgCancellationToken {
+        PgCancellationToken {
+            options: Arc::clone(&self.options),
+            process_id: self.process_id,
+            secret_key: self.secret_key,
+        }
+    }
+}
+
+/// Cancels the query running on a [`PgConnection`], created by
+/// [`PgConnection::cancel_token`].
+///
+/// The connection itself stays open: the query fails with a `query_canceled` error, and the
+/// connection can be used for the next query.
+#[derive(Clone)]
+pub struct PgCancellationToken {
+    options: Arc<PgConnectOptions>,
+    process_id: u32,
+    secret_key: u32,
+}
+
+impl PgCancellationToken {
+    /// The process ID of the backend of the connection.
+    ///
+    /// This is the same as the result of `pg_backend_pid()` on the connection.
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    /// The secret key of the backend of the connection, which authorizes cancel requests.
+    pub fn secret_key(&self) -> u32 {
+        self.secret_key
+    }
+
+    /// Ask the server to cancel the query that is running on the connection.
+    ///
+    /// This opens a separate connection to the server with the same options, to send the
+    /// request. The server does not report whether the request was successful: if no query
+    /// is running, nothing happens, and the query may also complete before the request is
+    /// processed.
+    ///
+    /// Note that a query is cancelled even if it is not the one that was running when the
+    /// token was created.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let mut stream = PgStream::connect(&self.options).await?;
+
+        stream
+            .send(CancelRequest {
+                process_id: self.process_id,
+                secret_key: self.secret_key,
+            })
+            .await?;
+
+        // the server closes the connection without a response once it processed the request,
+        // so wait for that like `libpq` does
+        let _ = stream.recv_unchecked().await;
+
+        Ok(())
+    }
+}
+
+impl Debug for PgCancellationToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // the options and the secret key are left out on purpose
+        f.debug_struct("PgCancellationToken")
+            .field("process_id", &self.process_id)
+            .finish_non_exhaustive()
+    }
+}
@@ -92,7 +92,7 @@ impl TryFrom<u8> for TypCategory {
 }
 
 impl PgConnection {
-    pub(super) async fn handle_row_description(
+    pub(crate) async fn handle_row_description(
         &mut self,
         desc: Option<RowDescription>,
         should_fetch: bool,
@@ -161,6 +161,15 @@ impl PgConnection {
             return Ok(info.clone());
         }
 
+        // then the cache shared with other connections, if any
+        if let Some(info) = self.shared_cache().and_then(|cache| cache.type_info(oid)) {
+            self.cache_type_info.insert(oid, info.clone());
+            self.cache_type_oid
+                .insert(info.0.name().to_string().into(), oid);
+
+            return Ok(info);
+        }
+
         // fallback to asking the database directly for a type name
         if should_fetch {
             let info = self.fetch_type_by_oid(oid).await?;
@@ -171,6 +180,10 @@ impl PgConnection {
             self.cache_type_oid
                 .insert(info.0.name().to_string().into(), oid);
 
+            if let Some(cache) = self.shared_cache() {
+                cache.insert_type_info(oid, info.clone());
+            }
+
             Ok(info)
         } else {
             // we are not in a place that *can* run a query
@@ -301,6 +314,15 @@ ORDER BY attnum
         name: String,
     ) -> BoxFuture<'_, Result<PgTypeInfo, Error>> {
         Box::pin(async move {
+            // a strict domain is opaque, so it is only compatible with types that declare it
+            if self.options.strict_domains {
+                return Ok(PgTypeInfo(PgType::Custom(Arc::new(PgCustomType {
+                    oid,
+                    name: name.into(),
+                    kind: PgTypeKind::Simple,
+                }))));
+            }
+
             let base_type = self.maybe_fetch_type_info_by_oid(base_type, true).await?;
 
             Ok(PgTypeInfo(PgType::Custom(Arc::new(PgCustomType {
@@ -339,8 +361,8 @@ WHERE rngtypid = $1
     }
 
     pub(crate) async fn fetch_type_id_by_name(&mut self, name: &str) -> Result<Oid, Error> {
-        if let Some(oid) = self.cache_type_oid.get(name) {
-            return Ok(*oid);
+        if let Some(oid) = self.cached_type_id_by_name(name) {
+            return Ok(oid);
         }
 
         // language=SQL
@@ -353,9 +375,22 @@ WHERE rngtypid = $1
             })?;
 
         self.cache_type_oid.insert(name.to_string().into(), oid);
+
+        if let Some(cache) = self.shared_cache() {
+            cache.insert_type_oid(name.to_string().into(), oid);
+        }
+
         Ok(oid)
     }
 
+    /// Look up the OID of a type by name without querying the server, if it is known already.
+    pub(crate) fn cached_type_id_by_name(&self, name: &str) -> Option<Oid> {
+        self.cache_type_oid
+            .get(name)
+            .copied()
+            .or_else(|| self.shared_cache()?.type_oid(name))
+    }
+
     pub(crate) async fn get_nullable_for_columns(
         &mut self,
         stmt_id: Oid,
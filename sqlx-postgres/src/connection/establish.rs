@@ -1,19 +1,149 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+
 use crate::HashMap;
 
 use crate::common::StatementCache;
+#[cfg(feature = "gssapi")]
+use crate::connection::gssapi;
+use crate::connection::Connection;
 use crate::connection::{sasl, stream::PgStream};
 use crate::error::Error;
 use crate::io::Decode;
 use crate::message::{
     Authentication, BackendKeyData, MessageFormat, Password, ReadyForQuery, Startup,
 };
+use crate::query_scalar::query_scalar;
 use crate::types::Oid;
-use crate::{PgConnectOptions, PgConnection};
+use crate::{PgChannelBinding, PgConnectOptions, PgConnection, PgTargetSessionAttrs};
 
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
 impl PgConnection {
+    /// Connect to the first host that has the properties of the `target_session_attrs` option,
+    /// trying the fallback hosts after the host.
+    pub(crate) async fn connect_to_target(options: &PgConnectOptions) -> Result<Self, Error> {
+        if options.fallback_hosts.is_empty()
+            && options.target_session_attrs == PgTargetSessionAttrs::Any
+        {
+            return Self::establish(options).await;
+        }
+
+        let mut hosts = vec![Cow::Borrowed(options)];
+
+        hosts.extend(options.fallback_hosts.iter().map(|(host, port)| {
+            let mut fallback = options.clone();
+            fallback.host = host.clone();
+            fallback.port = port.unwrap_or(options.port);
+            fallback.socket = None;
+
+            Cow::Owned(fallback)
+        }));
+
+        if options.load_balance_hosts {
+            hosts.shuffle(&mut rand::thread_rng());
+        }
+
+        // like libpq, `prefer-standby` accepts any server if none of them is a standby
+        let passes = match options.target_session_attrs {
+            PgTargetSessionAttrs::PreferStandby => {
+                vec![PgTargetSessionAttrs::Standby, PgTargetSessionAttrs::Any]
+            }
+            attrs => vec![attrs],
+        };
+
+        let mut last_error = None;
+
+        for attrs in passes {
+            for host in &hosts {
+                let error = match Self::establish(host).await {
+                    Ok(mut conn) => match conn.has_session_attrs(attrs).await {
+                        Ok(true) => return Ok(conn),
+
+                        Ok(false) => {
+                            let _ = conn.close_hard().await;
+
+                            err_protocol!(
+                                "the server at {}:{} does not match `target_session_attrs={}`",
+                                host.host,
+                                host.port,
+                                attrs
+                            )
+                        }
+
+                        Err(error) => error,
+                    },
+
+                    Err(error) => error,
+                };
+
+                tracing::debug!(
+                    host = %host.host,
+                    port = host.port,
+                    %error,
+                    "could not connect to a host of the connection options",
+                );
+
+                last_error = Some(error);
+            }
+        }
+
+        Err(last_error.expect("BUG: there is always a host to connect to"))
+    }
+
+    /// Whether the server of this connection has the properties of `attrs`.
+    ///
+    /// Servers since Postgres 14 report whether they are read-only, so only older servers are
+    /// queried.
+    async fn has_session_attrs(&mut self, attrs: PgTargetSessionAttrs) -> Result<bool, Error> {
+        let in_hot_standby = self
+            .parameter_status("in_hot_standby")
+            .map(|value| value == "on");
+
+        let read_only = match (
+            self.parameter_status("default_transaction_read_only"),
+            in_hot_standby,
+        ) {
+            (Some(value), Some(in_hot_standby)) => Some(value == "on" || in_hot_standby),
+            _ => None,
+        };
+
+        Ok(match attrs {
+            PgTargetSessionAttrs::Any | PgTargetSessionAttrs::PreferStandby => true,
+
+            PgTargetSessionAttrs::ReadWrite | PgTargetSessionAttrs::ReadOnly => {
+                let read_only = match read_only {
+                    Some(read_only) => read_only,
+                    None => {
+                        let value: String = query_scalar("SHOW transaction_read_only")
+                            .fetch_one(&mut *self)
+                            .await?;
+
+                        value == "on"
+                    }
+                };
+
+                read_only == (attrs == PgTargetSessionAttrs::ReadOnly)
+            }
+
+            PgTargetSessionAttrs::Primary | PgTargetSessionAttrs::Standby => {
+                let in_hot_standby = match in_hot_standby {
+                    Some(in_hot_standby) => in_hot_standby,
+                    None => {
+                        query_scalar("SELECT pg_is_in_recovery()")
+                            .fetch_one(&mut *self)
+                            .await?
+                    }
+                };
+
+                in_hot_standby == (attrs == PgTargetSessionAttrs::Standby)
+            }
+        })
+    }
+
     pub(crate) async fn establish(options: &PgConnectOptions) -> Result<Self, Error> {
         // Upgrade to TLS if we were asked to and the server supports it
         let mut stream = PgStream::connect(options).await?;
@@ -44,6 +174,11 @@ impl PgConnection {
             params.push(("options", options));
         }
 
+        if options.replication {
+            // Starts a walsender for logical replication of the database.
+            params.push(("replication", "database"));
+        }
+
         stream
             .send(Startup {
                 username: Some(&options.username),
@@ -59,8 +194,11 @@ impl PgConnection {
 
         let mut process_id = 0;
         let mut secret_key = 0;
+        let mut channel_bound = false;
         let transaction_status;
 
+        let channel_binding_required = options.channel_binding == PgChannelBinding::Require;
+
         loop {
             let message = stream.recv().await?;
             match message.format {
@@ -68,12 +206,21 @@ impl PgConnection {
                     Authentication::Ok => {
                         // the authentication exchange is successfully completed
                         // do nothing; no more information is required to continue
+
+                        if channel_binding_required && !channel_bound {
+                            // e.g. with the `trust` method, which skips authentication
+                            return Err(channel_binding_not_used());
+                        }
                     }
 
                     Authentication::CleartextPassword => {
                         // The frontend must now send a [PasswordMessage] containing the
                         // password in clear-text form.
 
+                        if channel_binding_required {
+                            return Err(channel_binding_not_used());
+                        }
+
                         stream
                             .send(Password::Cleartext(
                                 options.password.as_deref().unwrap_or_default(),
@@ -87,6 +234,10 @@ impl PgConnection {
                         // using the 4-byte random salt specified in the
                         // [AuthenticationMD5Password] message.
 
+                        if channel_binding_required {
+                            return Err(channel_binding_not_used());
+                        }
+
                         stream
                             .send(Password::Md5 {
                                 username: &options.username,
@@ -96,8 +247,26 @@ impl PgConnection {
                             .await?;
                     }
 
+                    Authentication::Gss | Authentication::Sspi => {
+                        // The frontend must now initiate a GSSAPI negotiation;
+                        // SSPI is compatible with GSSAPI over the wire.
+
+                        if channel_binding_required {
+                            return Err(channel_binding_not_used());
+                        }
+
+                        #[cfg(feature = "gssapi")]
+                        gssapi::authenticate(&mut stream, options).await?;
+
+                        #[cfg(not(feature = "gssapi"))]
+                        return Err(err_protocol!(
+                            "the server requested GSSAPI authentication, \
+                             which requires the `postgres-gssapi` feature"
+                        ));
+                    }
+
                     Authentication::Sasl(body) => {
-                        sasl::authenticate(&mut stream, options, body).await?;
+                        channel_bound = sasl::authenticate(&mut stream, options, body).await?;
                     }
 
                     method => {
@@ -139,14 +308,24 @@ impl PgConnection {
             stream,
             process_id,
             secret_key,
+            options: Arc::new(options.clone()),
             transaction_status,
             transaction_depth: 0,
             pending_ready_for_query_count: 0,
+            portal_suspended: false,
             next_statement_id: Oid(1),
-            cache_statement: StatementCache::new(options.statement_cache_capacity),
+            next_cursor_id: 1,
+            cache_statement: StatementCache::with_eviction(
+                options.statement_cache_capacity,
+                options.statement_cache_eviction,
+            ),
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
             log_settings: options.log_settings.clone(),
         })
     }
 }
+
+fn channel_binding_not_used() -> Error {
+    err_protocol!("channel binding is required, but the server authenticated without it")
+}
@@ -18,16 +18,34 @@ use futures_core::stream::BoxStream;
 use futures_core::Stream;
 use futures_util::{pin_mut, TryStreamExt};
 use sqlx_core::Either;
-use std::{borrow::Cow, sync::Arc};
+use std::fmt::Write;
+use std::{borrow::Cow, cmp, sync::Arc};
+
+/// The ID of the unnamed statement, which is replaced by the next statement that is prepared
+/// unnamed, and which is used when statements are not cached.
+pub(crate) const UNNAMED_STATEMENT: Oid = Oid(0);
 
 async fn prepare(
     conn: &mut PgConnection,
     sql: &str,
     parameters: &[PgTypeInfo],
     metadata: Option<Arc<PgStatementMetadata>>,
+    // whether to prepare a named statement, or the unnamed one
+    named: bool,
 ) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
-    let id = conn.next_statement_id;
-    conn.next_statement_id.incr_one();
+    let id = if named {
+        let id = conn.next_statement_id;
+        conn.next_statement_id.incr_one();
+
+        // the ID of the unnamed statement is skipped when the IDs wrap around
+        if conn.next_statement_id == UNNAMED_STATEMENT {
+            conn.next_statement_id.incr_one();
+        }
+
+        id
+    } else {
+        UNNAMED_STATEMENT
+    };
 
     // build a list of type OIDs to send to the database in the PARSE command
     // we have not yet started the query sequence, so we are *safe* to cleanly make
@@ -43,6 +61,9 @@ async fn prepare(
         });
     }
 
+    // another connection may have described this statement already
+    let metadata = metadata.or_else(|| conn.shared_cache()?.statement(sql, &param_types));
+
     // flush and wait until we are re-ready
     conn.wait_until_ready().await?;
 
@@ -90,11 +111,17 @@ async fn prepare(
         // continuing
         conn.wait_until_ready().await?;
 
-        Arc::new(PgStatementMetadata {
+        let metadata = Arc::new(PgStatementMetadata {
             parameters,
             columns,
             column_names: Arc::new(column_names),
-        })
+        });
+
+        if let Some(cache) = conn.shared_cache() {
+            cache.insert_statement(sql, param_types, Arc::clone(&metadata));
+        }
+
+        metadata
     };
 
     Ok((id, metadata))
@@ -160,7 +187,7 @@ impl PgConnection {
         self.pending_ready_for_query_count += 1;
     }
 
-    async fn get_or_prepare<'a>(
+    pub(crate) async fn get_or_prepare(
         &mut self,
         sql: &str,
         parameters: &[PgTypeInfo],
@@ -170,25 +197,119 @@ impl PgConnection {
         // a statement object
         metadata: Option<Arc<PgStatementMetadata>>,
     ) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
+        let mut expired = Vec::new();
+
+        while let Some((id, _)) = self.cache_statement.remove_expired() {
+            expired.push(id);
+        }
+
+        self.close_statements(expired).await?;
+
         if let Some(statement) = self.cache_statement.get_mut(sql) {
             return Ok((*statement).clone());
         }
 
-        let statement = prepare(self, sql, parameters, metadata).await?;
+        let named = store_to_cache && self.cache_statement.is_enabled();
+        let statement = prepare(self, sql, parameters, metadata, named).await?;
 
-        if store_to_cache && self.cache_statement.is_enabled() {
+        if named {
             if let Some((id, _)) = self.cache_statement.insert(sql, statement.clone()) {
-                self.stream.write(Close::Statement(id));
-                self.write_sync();
+                self.close_statements([id]).await?;
+            }
+        }
 
-                self.stream.flush().await?;
+        Ok(statement)
+    }
 
-                self.wait_for_close_complete(1).await?;
-                self.recv_ready_for_query().await?;
+    /// Write a query applying `settings` with `set_config(.., is_local => true)`.
+    ///
+    /// They last until the end of the transaction, which without `BEGIN` is the implicit one
+    /// that ends with the next `Sync`. It returns one row, which the caller has to skip.
+    fn write_settings(&mut self, settings: &[(String, String)]) {
+        let mut sql = String::from("SELECT ");
+        let mut arguments = PgArguments::default();
+
+        for (i, (name, value)) in settings.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
             }
+
+            let _ = write!(sql, "set_config(${}, ${}, true)", 2 * i + 1, 2 * i + 2);
+
+            arguments.add(name.as_str());
+            arguments.add(value.as_str());
         }
 
-        Ok(statement)
+        let param_types: Vec<Oid> = arguments.types.iter().map(|ty| ty.0.oid()).collect();
+
+        self.stream.write(Parse {
+            param_types: &param_types,
+            query: &sql,
+            statement: UNNAMED_STATEMENT,
+        });
+
+        self.stream.write(Bind {
+            portal: None,
+            statement: UNNAMED_STATEMENT,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len() as i16,
+            params: &arguments.buffer,
+            result_formats: &[PgValueFormat::Binary],
+        });
+
+        self.stream.write(message::Execute {
+            portal: None,
+            limit: 0,
+        });
+    }
+
+    /// Close prepared statements on the server.
+    async fn close_statements(&mut self, ids: impl IntoIterator<Item = Oid>) -> Result<(), Error> {
+        let mut count = 0;
+
+        for id in ids {
+            self.stream.write(Close::Statement(id));
+            count += 1;
+        }
+
+        if count > 0 {
+            self.write_sync();
+
+            self.stream.flush().await?;
+
+            self.wait_for_close_complete(count).await?;
+            self.recv_ready_for_query().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a `Parse` of the unnamed statement again, if that's the statement to bind next.
+    ///
+    /// Other queries may have replaced the unnamed statement since it was prepared, e.g. to
+    /// look up the types of its parameters. Behind a pooler in transaction mode, this also
+    /// sends it to the same server as the `Bind`.
+    pub(crate) fn write_unnamed_parse(
+        &mut self,
+        sql: &str,
+        statement: Oid,
+        metadata: &PgStatementMetadata,
+    ) {
+        if statement != UNNAMED_STATEMENT {
+            return;
+        }
+
+        let param_types: Vec<Oid> = metadata
+            .parameters
+            .iter()
+            .map(|ty| ty.oid().unwrap_or(Oid(0)))
+            .collect();
+
+        self.stream.write(Parse {
+            param_types: &param_types,
+            query: sql,
+            statement,
+        });
     }
 
     pub(crate) async fn run<'e, 'c: 'e, 'q: 'e>(
@@ -206,6 +327,9 @@ impl PgConnection {
 
         let mut metadata: Arc<PgStatementMetadata>;
 
+        // whether the result of `set_config()` comes before that of the query
+        let mut settings_pending = false;
+
         let format = if let Some(mut arguments) = arguments {
             // prepare the statement if this our first time executing it
             // always return the statement ID here
@@ -221,6 +345,13 @@ impl PgConnection {
             // consume messages till `ReadyForQuery` before bind and execute
             self.wait_until_ready().await?;
 
+            if !arguments.settings.is_empty() {
+                self.write_settings(&arguments.settings);
+                settings_pending = true;
+            }
+
+            self.write_unnamed_parse(query, statement, &metadata);
+
             // bind to attach the arguments to the statement and create a portal
             self.stream.write(Bind {
                 portal: None,
@@ -249,10 +380,8 @@ impl PgConnection {
             self.stream.write(message::Close::Portal(None));
 
             // finally, [Sync] asks postgres to process the messages that we sent and respond with
-            // a [ReadyForQuery] message when it's completely done. Theoretically, we could send
-            // dozens of queries before a [Sync] and postgres can handle that. Execution on the server
-            // is still serial but it would reduce round-trips. Some kind of builder pattern that is
-            // termed batching might suit this.
+            // a [ReadyForQuery] message when it's completely done. [PgPipeline] sends several
+            // queries before a single [Sync] to save round-trips.
             self.write_sync();
 
             // prepared statements are binary
@@ -276,6 +405,14 @@ impl PgConnection {
                 let message = self.stream.recv().await?;
 
                 match message.format {
+                    // the row of `set_config()`
+                    MessageFormat::DataRow if settings_pending => {}
+
+                    MessageFormat::CommandComplete if settings_pending => {
+                        // the settings are applied, the results of the query follow
+                        settings_pending = false;
+                    }
+
                     MessageFormat::BindComplete
                     | MessageFormat::ParseComplete
                     | MessageFormat::ParameterDescription
@@ -357,6 +494,171 @@ impl PgConnection {
     }
 }
 
+impl PgConnection {
+    /// Execute `query` and return a stream of its rows, which are fetched from the server
+    /// `chunk_size` rows at a time as the stream is consumed.
+    ///
+    /// The portal of the query is executed with a row limit, and executed again for the next
+    /// chunk only once every row of the previous chunk was taken from the stream. Unlike
+    /// [`Self::cursor()`], this doesn't need a transaction. If the stream is dropped early, the
+    /// rest of the rows is discarded the next time the connection is used.
+    ///
+    /// A chunk size of 0 is treated as 1.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use futures::TryStreamExt;
+    /// use sqlx::Row;
+    ///
+    /// let mut rows = conn.fetch_chunked(sqlx::query("SELECT id FROM events"), 10_000);
+    ///
+    /// while let Some(row) = rows.try_next().await? {
+    ///     let id: i64 = row.try_get(0)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_chunked<'e, 'q: 'e, E>(
+        &'e mut self,
+        mut query: E,
+        chunk_size: u32,
+    ) -> BoxStream<'e, Result<PgRow, Error>>
+    where
+        E: 'q + Execute<'q, Postgres>,
+    {
+        let sql = query.sql();
+        let metadata_opt = query.statement().map(|s| Arc::clone(&s.metadata));
+        // the portal needs the extended query protocol, even without arguments
+        let mut arguments = query.take_arguments().unwrap_or_default();
+        let persistent = query.persistent();
+        let chunk_size = cmp::max(chunk_size, 1);
+
+        Box::pin(try_stream! {
+            if !arguments.settings.is_empty() {
+                return Err(Error::Configuration(
+                    "query settings are not supported by `fetch_chunked`".into(),
+                ));
+            }
+
+            let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+
+            self.wait_until_ready().await?;
+
+            let (statement, metadata) = self
+                .get_or_prepare(sql, &arguments.types, persistent, metadata_opt)
+                .await?;
+
+            arguments.apply_patches(self, &metadata.parameters).await?;
+
+            self.wait_until_ready().await?;
+
+            self.write_unnamed_parse(sql, statement, &metadata);
+
+            self.stream.write(Bind {
+                portal: None,
+                statement,
+                formats: &[PgValueFormat::Binary],
+                num_params: arguments.types.len() as i16,
+                params: &arguments.buffer,
+                result_formats: &[PgValueFormat::Binary],
+            });
+
+            // a Sync would close the (unnamed) portal outside of a transaction, so Flush asks for
+            // the rows instead, until the portal is complete
+            self.stream.write(message::Execute {
+                portal: None,
+                limit: chunk_size,
+            });
+            self.stream.write(message::Flush);
+            self.portal_suspended = true;
+
+            self.stream.flush().await?;
+
+            loop {
+                let message = match self.stream.recv().await {
+                    Ok(message) => message,
+                    Err(error) => {
+                        // the server skips the messages until a Sync after an error
+                        if self.portal_suspended {
+                            self.portal_suspended = false;
+                            self.write_sync();
+                        }
+
+                        return Err(error);
+                    }
+                };
+
+                match message.format {
+                    MessageFormat::ParseComplete
+                    | MessageFormat::BindComplete
+                    | MessageFormat::CloseComplete => {}
+
+                    MessageFormat::DataRow => {
+                        logger.increment_rows_returned();
+
+                        let data: DataRow = message.decode()?;
+                        let row = PgRow {
+                            data,
+                            format: PgValueFormat::Binary,
+                            metadata: Arc::clone(&metadata),
+                        };
+
+                        r#yield!(row);
+                    }
+
+                    // the chunk is complete, and every row of it was consumed
+                    MessageFormat::PortalSuspended => {
+                        self.stream.write(message::Execute {
+                            portal: None,
+                            limit: chunk_size,
+                        });
+                        self.stream.write(message::Flush);
+
+                        self.stream.flush().await?;
+                    }
+
+                    MessageFormat::CommandComplete => {
+                        let cc: CommandComplete = message.decode()?;
+                        logger.increase_rows_affected(cc.rows_affected());
+
+                        self.finish_portal().await?;
+                    }
+
+                    MessageFormat::EmptyQueryResponse => {
+                        self.finish_portal().await?;
+                    }
+
+                    MessageFormat::ReadyForQuery => {
+                        self.handle_ready_for_query(message)?;
+                        break;
+                    }
+
+                    _ => {
+                        return Err(err_protocol!(
+                            "fetch_chunked: unexpected message: {:?}",
+                            message.format
+                        ));
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    // close the completed portal of `fetch_chunked`, and end the query with a Sync
+    async fn finish_portal(&mut self) -> Result<(), Error> {
+        self.portal_suspended = false;
+
+        self.stream.write(message::Close::Portal(None));
+        self.write_sync();
+
+        self.stream.flush().await?;
+
+        Ok(())
+    }
+}
+
 impl<'c> Executor<'c> for &'c mut PgConnection {
     type Database = Postgres;
 
@@ -442,14 +744,32 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            let (stmt_id, metadata) = self.get_or_prepare(sql, &[], true, None).await?;
+            // `EXPLAIN EXECUTE` needs a named statement, even if statements are not cached
+            let cached = self.cache_statement.is_enabled();
+
+            let (stmt_id, metadata) = if cached {
+                self.get_or_prepare(sql, &[], true, None).await?
+            } else {
+                prepare(self, sql, &[], None, true).await?
+            };
 
             let nullable = self.get_nullable_for_columns(stmt_id, &metadata).await?;
 
+            if !cached {
+                self.close_statements([stmt_id]).await?;
+            }
+
+            // parameters of a domain type are described as the base type, like the columns
+            let parameters = metadata
+                .parameters
+                .iter()
+                .map(|ty| ty.domain_base_type().unwrap_or(ty).clone())
+                .collect();
+
             Ok(Describe {
                 columns: metadata.columns.clone(),
                 nullable,
-                parameters: Some(Either::Left(metadata.parameters.clone())),
+                parameters: Some(Either::Left(parameters)),
             })
         })
     }
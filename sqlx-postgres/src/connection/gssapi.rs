@@ -0,0 +1,333 @@
+//! GSSAPI authentication, through the system's GSSAPI library (MIT Kerberos or Heimdal).
+//!
+//! This is the same exchange that `libpq` implements:
+//! <https://www.postgresql.org/docs/current/gssapi-auth.html>
+
+use std::ffi::c_void;
+use std::ptr;
+
+use crate::connection::stream::PgStream;
+use crate::error::Error;
+use crate::message::{Authentication, GssResponse, MessageFormat};
+use crate::PgConnectOptions;
+
+/// Run the GSSAPI exchange, until the security context is established.
+///
+/// The server sends [Authentication::Ok] afterwards.
+pub(crate) async fn authenticate(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+) -> Result<(), Error> {
+    // the principal of the server is `<service>/<host>@<REALM>`, with the realm picked by
+    // the GSSAPI library
+    let target = format!("{}@{}", options.krb_srv_name, options.host);
+
+    let mut context = SecurityContext::new(&target, options.gss_delegation)?;
+    let mut input = Vec::new();
+
+    loop {
+        let (token, complete) = context.step(&input)?;
+
+        if !token.is_empty() {
+            stream.send(GssResponse(&token)).await?;
+        }
+
+        if complete {
+            return Ok(());
+        }
+
+        let message = stream.recv_expect(MessageFormat::Authentication).await?;
+
+        input = match message {
+            Authentication::GssContinue(body) => body.data.to_vec(),
+
+            method => {
+                return Err(err_protocol!(
+                    "expected GSSContinue but received {:?}",
+                    method
+                ));
+            }
+        };
+    }
+}
+
+/// A security context of the GSSAPI library, which is released on drop.
+struct SecurityContext {
+    name: ffi::gss_name_t,
+    context: ffi::gss_ctx_id_t,
+    flags: ffi::OM_uint32,
+}
+
+// SAFETY: the handles are only used through `&mut self`, and GSSAPI allows them to be used
+// from another thread than the one that created them
+unsafe impl Send for SecurityContext {}
+
+impl SecurityContext {
+    fn new(target: &str, delegate: bool) -> Result<Self, Error> {
+        let mut minor = 0;
+        let mut name = ptr::null_mut();
+
+        let mut buffer = ffi::gss_buffer_desc {
+            length: target.len(),
+            value: target.as_ptr() as *mut c_void,
+        };
+
+        let mut name_type = ffi::gss_OID_desc {
+            length: ffi::NT_HOSTBASED_SERVICE.len() as ffi::OM_uint32,
+            elements: ffi::NT_HOSTBASED_SERVICE.as_ptr() as *mut c_void,
+        };
+
+        // SAFETY: the buffer and the OID point to memory that outlives the call, and the name
+        // is released on drop
+        let major =
+            unsafe { ffi::gss_import_name(&mut minor, &mut buffer, &mut name_type, &mut name) };
+
+        if ffi::is_error(major) {
+            return Err(error(
+                "failed to import the name of the server",
+                major,
+                minor,
+            ));
+        }
+
+        // the server proves its identity as well
+        let mut flags = ffi::GSS_C_MUTUAL_FLAG;
+
+        if delegate {
+            // forward the credentials of the client to the server
+            flags |= ffi::GSS_C_DELEG_FLAG;
+        }
+
+        Ok(Self {
+            name,
+            context: ptr::null_mut(),
+            flags,
+        })
+    }
+
+    /// Process a token from the server, and return the token to send to the server and whether
+    /// the context is established.
+    fn step(&mut self, input: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+        let mut minor = 0;
+
+        let mut input_buffer = ffi::gss_buffer_desc {
+            length: input.len(),
+            value: input.as_ptr() as *mut c_void,
+        };
+
+        // `GSS_C_NO_BUFFER` for the first step
+        let input_token = if input.is_empty() {
+            ptr::null_mut()
+        } else {
+            &mut input_buffer as *mut _
+        };
+
+        let mut output = ffi::gss_buffer_desc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        // SAFETY: the handles are valid or null for the first step, and the output buffer is
+        // released below
+        let major = unsafe {
+            ffi::gss_init_sec_context(
+                &mut minor,
+                ptr::null_mut(),
+                &mut self.context,
+                self.name,
+                ptr::null_mut(),
+                self.flags,
+                0,
+                ptr::null_mut(),
+                input_token,
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+
+        let token = if output.value.is_null() {
+            Vec::new()
+        } else {
+            // SAFETY: the library returned a buffer of `output.length` bytes
+            let token =
+                unsafe { std::slice::from_raw_parts(output.value as *const u8, output.length) }
+                    .to_vec();
+
+            let mut release_minor = 0;
+
+            // SAFETY: the buffer was allocated by the library and isn't used anymore
+            unsafe { ffi::gss_release_buffer(&mut release_minor, &mut output) };
+
+            token
+        };
+
+        if ffi::is_error(major) {
+            return Err(error(
+                "failed to initialize the security context",
+                major,
+                minor,
+            ));
+        }
+
+        Ok((token, major & ffi::GSS_S_CONTINUE_NEEDED == 0))
+    }
+}
+
+impl Drop for SecurityContext {
+    fn drop(&mut self) {
+        let mut minor = 0;
+
+        // SAFETY: the handles were created by the library, and are not used afterwards
+        unsafe {
+            if !self.context.is_null() {
+                ffi::gss_delete_sec_context(&mut minor, &mut self.context, ptr::null_mut());
+            }
+
+            if !self.name.is_null() {
+                ffi::gss_release_name(&mut minor, &mut self.name);
+            }
+        }
+    }
+}
+
+/// Create an error with the messages of the GSSAPI library for the status codes.
+fn error(context: &str, major: ffi::OM_uint32, minor: ffi::OM_uint32) -> Error {
+    let mut message = format!("GSSAPI error: {context}");
+
+    for (status, status_type) in [(major, ffi::GSS_C_GSS_CODE), (minor, ffi::GSS_C_MECH_CODE)] {
+        let mut message_context = 0;
+
+        loop {
+            let mut minor = 0;
+
+            let mut buffer = ffi::gss_buffer_desc {
+                length: 0,
+                value: ptr::null_mut(),
+            };
+
+            // SAFETY: the buffer is released below
+            let major = unsafe {
+                ffi::gss_display_status(
+                    &mut minor,
+                    status,
+                    status_type,
+                    ptr::null_mut(),
+                    &mut message_context,
+                    &mut buffer,
+                )
+            };
+
+            if ffi::is_error(major) || buffer.value.is_null() {
+                break;
+            }
+
+            // SAFETY: the library returned a buffer of `buffer.length` bytes
+            let text =
+                unsafe { std::slice::from_raw_parts(buffer.value as *const u8, buffer.length) };
+
+            message.push_str(": ");
+            message.push_str(&String::from_utf8_lossy(text));
+
+            // SAFETY: the buffer was allocated by the library and isn't used anymore
+            unsafe { ffi::gss_release_buffer(&mut minor, &mut buffer) };
+
+            if message_context == 0 {
+                break;
+            }
+        }
+    }
+
+    err_protocol!("{}", message)
+}
+
+/// The subset of the GSSAPI C bindings (RFC 2744) that is used for authentication.
+#[allow(non_camel_case_types)]
+mod ffi {
+    use std::ffi::c_void;
+
+    pub type OM_uint32 = u32;
+    pub type gss_name_t = *mut c_void;
+    pub type gss_ctx_id_t = *mut c_void;
+    pub type gss_cred_id_t = *mut c_void;
+    pub type gss_channel_bindings_t = *mut c_void;
+
+    #[repr(C)]
+    pub struct gss_buffer_desc {
+        pub length: usize,
+        pub value: *mut c_void,
+    }
+
+    #[repr(C)]
+    pub struct gss_OID_desc {
+        pub length: OM_uint32,
+        pub elements: *mut c_void,
+    }
+
+    pub const GSS_C_DELEG_FLAG: OM_uint32 = 1;
+    pub const GSS_C_MUTUAL_FLAG: OM_uint32 = 2;
+
+    pub const GSS_C_GSS_CODE: i32 = 1;
+    pub const GSS_C_MECH_CODE: i32 = 2;
+
+    pub const GSS_S_CONTINUE_NEEDED: OM_uint32 = 1;
+
+    /// `GSS_C_NT_HOSTBASED_SERVICE`, the OID 1.2.840.113554.1.2.1.4 for names of the form
+    /// `service@host`.
+    pub const NT_HOSTBASED_SERVICE: &[u8] = b"\x2a\x86\x48\x86\xf7\x12\x01\x02\x01\x04";
+
+    /// `GSS_ERROR`: whether the calling error or the routine error of a status is set.
+    pub fn is_error(major: OM_uint32) -> bool {
+        major & 0xffff_0000 != 0
+    }
+
+    #[cfg_attr(target_os = "macos", link(name = "GSS", kind = "framework"))]
+    #[cfg_attr(not(target_os = "macos"), link(name = "gssapi_krb5"))]
+    extern "C" {
+        pub fn gss_import_name(
+            minor_status: *mut OM_uint32,
+            input_name_buffer: *mut gss_buffer_desc,
+            input_name_type: *mut gss_OID_desc,
+            output_name: *mut gss_name_t,
+        ) -> OM_uint32;
+
+        pub fn gss_release_name(minor_status: *mut OM_uint32, name: *mut gss_name_t) -> OM_uint32;
+
+        pub fn gss_init_sec_context(
+            minor_status: *mut OM_uint32,
+            initiator_cred_handle: gss_cred_id_t,
+            context_handle: *mut gss_ctx_id_t,
+            target_name: gss_name_t,
+            mech_type: *mut gss_OID_desc,
+            req_flags: OM_uint32,
+            time_req: OM_uint32,
+            input_chan_bindings: gss_channel_bindings_t,
+            input_token: *mut gss_buffer_desc,
+            actual_mech_type: *mut *mut gss_OID_desc,
+            output_token: *mut gss_buffer_desc,
+            ret_flags: *mut OM_uint32,
+            time_rec: *mut OM_uint32,
+        ) -> OM_uint32;
+
+        pub fn gss_delete_sec_context(
+            minor_status: *mut OM_uint32,
+            context_handle: *mut gss_ctx_id_t,
+            output_token: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        pub fn gss_release_buffer(
+            minor_status: *mut OM_uint32,
+            buffer: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        pub fn gss_display_status(
+            minor_status: *mut OM_uint32,
+            status_value: OM_uint32,
+            status_type: i32,
+            mech_type: *mut gss_OID_desc,
+            message_context: *mut OM_uint32,
+            status_string: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+    }
+}
@@ -5,7 +5,7 @@ use crate::HashMap;
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 
-use crate::common::StatementCache;
+use crate::common::{StatementCache, StatementCacheStats};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::io::Decode;
@@ -19,12 +19,21 @@ use crate::{PgConnectOptions, PgTypeInfo, Postgres};
 
 pub(crate) use sqlx_core::connection::*;
 
+use self::shared_cache::PgSharedCache;
+
+pub use self::cancel::PgCancellationToken;
+pub use self::parameters::PgParameterStatus;
 pub use self::stream::PgStream;
 
+mod cancel;
 pub(crate) mod describe;
 mod establish;
 mod executor;
+#[cfg(feature = "gssapi")]
+mod gssapi;
+mod parameters;
 mod sasl;
+pub(crate) mod shared_cache;
 mod stream;
 mod tls;
 
@@ -37,18 +46,23 @@ pub struct PgConnection {
 
     // process id of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     process_id: u32,
 
     // secret key of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     secret_key: u32,
 
+    // options this connection was established with
+    // used to connect to the server again to send cancel requests
+    options: Arc<PgConnectOptions>,
+
     // sequence of statement IDs for use in preparing statements
     // in PostgreSQL, the statement is prepared to a user-supplied identifier
     next_statement_id: Oid,
 
+    // sequence of cursor IDs for use in naming cursors declared by `PgConnection::cursor`
+    pub(crate) next_cursor_id: u32,
+
     // cache statement by query string to the id and columns
     cache_statement: StatementCache<(Oid, Arc<PgStatementMetadata>)>,
 
@@ -59,11 +73,14 @@ pub struct PgConnection {
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
 
+    // whether the portal of `PgConnection::fetch_chunked` is suspended, without a Sync sent yet
+    pub(crate) portal_suspended: bool,
+
     // current transaction status
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
-    log_settings: LogSettings,
+    pub(crate) log_settings: LogSettings,
 }
 
 impl PgConnection {
@@ -72,8 +89,27 @@ impl PgConnection {
         self.stream.server_version_num
     }
 
+    /// Returns the statistics of the statement cache of this connection.
+    ///
+    /// With the cache disabled, every query with arguments is a miss.
+    pub fn statement_cache_stats(&self) -> StatementCacheStats {
+        self.cache_statement.stats()
+    }
+
+    // the cache shared with the other connections opened with the same options, if enabled
+    pub(crate) fn shared_cache(&self) -> Option<&PgSharedCache> {
+        self.options.shared_cache.as_deref()
+    }
+
     // will return when the connection is ready for another query
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.portal_suspended {
+            // the stream of `fetch_chunked` was dropped early; Sync closes the portal, and the
+            // rest of its rows are skipped below
+            self.portal_suspended = false;
+            self.write_sync();
+        }
+
         if !self.stream.write_buffer_mut().is_empty() {
             self.stream.flush().await?;
         }
@@ -101,7 +137,7 @@ impl PgConnection {
         Ok(())
     }
 
-    fn handle_ready_for_query(&mut self, message: Message) -> Result<(), Error> {
+    pub(crate) fn handle_ready_for_query(&mut self, message: Message) -> Result<(), Error> {
         self.pending_ready_for_query_count -= 1;
         self.transaction_status = ReadyForQuery::decode(message.contents)?.transaction_status;
 
@@ -178,6 +214,10 @@ impl Connection for PgConnection {
         Box::pin(async move {
             self.cache_type_oid.clear();
 
+            if let Some(cache) = self.shared_cache() {
+                cache.clear();
+            }
+
             let mut cleared = 0_usize;
 
             self.wait_until_ready().await?;
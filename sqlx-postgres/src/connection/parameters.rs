@@ -0,0 +1,75 @@
+use futures_channel::mpsc;
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+
+use crate::PgConnection;
+
+impl PgConnection {
+    /// Returns the current value of a parameter that the server reports, like `server_version`,
+    /// `TimeZone` or `standard_conforming_strings`.
+    ///
+    /// The server reports [a fixed set of parameters][reported] when connecting, and again
+    /// whenever one of them changes (e.g. after `SET TimeZone = 'UTC'`). Changes are received
+    /// while the connection is used, so the value is the last one that was seen.
+    ///
+    /// [reported]: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-ASYNC
+    pub fn parameter_status(&self, name: &str) -> Option<&str> {
+        self.stream.parameter_statuses.get(name).map(String::as_str)
+    }
+
+    /// Returns the current values of every parameter that the server reports, ordered by name.
+    ///
+    /// See [`Self::parameter_status()`].
+    pub fn parameter_statuses(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.stream
+            .parameter_statuses
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns a stream of the changes of the parameters that the server reports.
+    ///
+    /// The changes are received while the connection is used, e.g. to execute a query, and the
+    /// stream ends when the connection is closed. See [`Self::parameter_status()`].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use futures::StreamExt;
+    /// use sqlx::Executor;
+    ///
+    /// let mut changes = conn.watch_parameter_statuses();
+    ///
+    /// conn.execute("SET TimeZone = 'UTC'").await?;
+    ///
+    /// let change = changes.next().await.unwrap();
+    /// assert_eq!((change.name(), change.value()), ("TimeZone", "UTC"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_parameter_statuses(&mut self) -> BoxStream<'static, PgParameterStatus> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.stream.parameter_watchers.push(sender);
+
+        receiver.boxed()
+    }
+}
+
+/// A change of a parameter that the server reports, returned by
+/// [`PgConnection::watch_parameter_statuses()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgParameterStatus {
+    pub(crate) name: String,
+    pub(crate) value: String,
+}
+
+impl PgParameterStatus {
+    /// The name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The new value of the parameter.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
@@ -3,25 +3,35 @@ use crate::error::Error;
 use crate::message::{
     Authentication, AuthenticationSasl, MessageFormat, SaslInitialResponse, SaslResponse,
 };
-use crate::PgConnectOptions;
+use crate::net::Socket;
+use crate::{PgChannelBinding, PgConnectOptions};
 use hmac::{Hmac, Mac};
 use rand::Rng;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
 use stringprep::saslprep;
 
 use base64::prelude::{Engine as _, BASE64_STANDARD};
 
+// the client does not support channel binding
 const GS2_HEADER: &str = "n,,";
+
+// the client supports channel binding, but thinks the server does not
+const GS2_HEADER_SUPPORTED: &str = "y,,";
+
+// the client uses channel binding with the hash of the certificate of the server
+const GS2_HEADER_TLS_SERVER_END_POINT: &str = "p=tls-server-end-point,,";
+
 const CHANNEL_ATTR: &str = "c";
 const USERNAME_ATTR: &str = "n";
 const CLIENT_PROOF_ATTR: &str = "p";
 const NONCE_ATTR: &str = "r";
 
+/// Authenticate with SCRAM, and return whether channel binding was used.
 pub(crate) async fn authenticate(
     stream: &mut PgStream,
     options: &PgConnectOptions,
     data: AuthenticationSasl,
-) -> Result<(), Error> {
+) -> Result<bool, Error> {
     let mut has_sasl = false;
     let mut has_sasl_plus = false;
     let mut unknown = Vec::new();
@@ -42,16 +52,49 @@ pub(crate) async fn authenticate(
         }
     }
 
-    if !has_sasl_plus && !has_sasl {
-        return Err(err_protocol!(
-            "unsupported SASL authentication mechanisms: {}",
-            unknown.join(", ")
-        ));
+    // the certificate of the server, if the connection is secured with TLS
+    let certificate = match options.channel_binding {
+        PgChannelBinding::Disable => None,
+        _ => stream.socket().peer_certificate(),
+    };
+
+    let cbind_data = match &certificate {
+        Some(certificate) if has_sasl_plus => match certificate_hash(certificate) {
+            Ok(hash) => Some(hash),
+            Err(e) if options.channel_binding == PgChannelBinding::Require => return Err(e),
+            // fall back to authenticating without channel binding
+            Err(_) => None,
+        },
+        _ => None,
+    };
+
+    if cbind_data.is_none() {
+        if options.channel_binding == PgChannelBinding::Require {
+            return Err(err_protocol!(
+                "channel binding is required, but the server does not support it"
+            ));
+        }
+
+        if !has_sasl {
+            return Err(err_protocol!(
+                "unsupported SASL authentication mechanisms: {}",
+                unknown.join(", ")
+            ));
+        }
     }
 
+    let gs2_header = match &cbind_data {
+        Some(_) => GS2_HEADER_TLS_SERVER_END_POINT,
+        None if certificate.is_some() && !has_sasl_plus => GS2_HEADER_SUPPORTED,
+        None => GS2_HEADER,
+    };
+
     // channel-binding = "c=" base64
+    let mut cbind_input = gs2_header.as_bytes().to_vec();
+    cbind_input.extend_from_slice(cbind_data.as_deref().unwrap_or_default());
+
     let mut channel_binding = format!("{CHANNEL_ATTR}=");
-    BASE64_STANDARD.encode_string(GS2_HEADER, &mut channel_binding);
+    BASE64_STANDARD.encode_string(cbind_input, &mut channel_binding);
 
     // "n=" saslname ;; Usernames are prepared using SASLprep.
     let username = format!("{}={}", USERNAME_ATTR, options.username);
@@ -67,12 +110,12 @@ pub(crate) async fn authenticate(
     // client-first-message-bare = [reserved-mext ","] username "," nonce ["," extensions]
     let client_first_message_bare = format!("{username},{nonce}");
 
-    let client_first_message = format!("{GS2_HEADER}{client_first_message_bare}");
+    let client_first_message = format!("{gs2_header}{client_first_message_bare}");
 
     stream
         .send(SaslInitialResponse {
             response: &client_first_message,
-            plus: false,
+            plus: cbind_data.is_some(),
         })
         .await?;
 
@@ -158,7 +201,106 @@ pub(crate) async fn authenticate(
     // authentication is only considered valid if this verification passes
     mac.verify_slice(&data.verifier).map_err(Error::protocol)?;
 
-    Ok(())
+    Ok(cbind_data.is_some())
+}
+
+// the `tls-server-end-point` channel binding data is a hash of the DER-encoded certificate,
+// with the hash function of the signature algorithm of the certificate; MD5 and SHA-1 are
+// replaced with SHA-256
+// https://www.rfc-editor.org/rfc/rfc5929#section-4.1
+fn certificate_hash(certificate: &[u8]) -> Result<Vec<u8>, Error> {
+    let algorithm = signature_algorithm(certificate).ok_or_else(|| {
+        err_protocol!(
+            "channel binding: could not read the signature algorithm of the server certificate"
+        )
+    })?;
+
+    Ok(match algorithm {
+        // md5WithRSAEncryption, sha1WithRSAEncryption, sha256WithRSAEncryption
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x04 | 0x05 | 0x0b]
+        // ecdsa-with-SHA1, ecdsa-with-SHA256
+        | [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x01]
+        | [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02] => {
+            Sha256::digest(certificate).to_vec()
+        }
+
+        // sha224WithRSAEncryption, ecdsa-with-SHA224
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0e]
+        | [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x01] => Sha224::digest(certificate).to_vec(),
+
+        // sha384WithRSAEncryption, ecdsa-with-SHA384
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c]
+        | [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03] => Sha384::digest(certificate).to_vec(),
+
+        // sha512WithRSAEncryption, ecdsa-with-SHA512
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d]
+        | [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x04] => Sha512::digest(certificate).to_vec(),
+
+        _ => {
+            return Err(err_protocol!(
+                "channel binding: unsupported signature algorithm of the server certificate"
+            ))
+        }
+    })
+}
+
+// Certificate ::= SEQUENCE {
+//     tbsCertificate       TBSCertificate,
+//     signatureAlgorithm   AlgorithmIdentifier,
+//     signatureValue       BIT STRING }
+//
+// AlgorithmIdentifier ::= SEQUENCE {
+//     algorithm            OBJECT IDENTIFIER,
+//     parameters           ANY DEFINED BY algorithm OPTIONAL }
+fn signature_algorithm(certificate: &[u8]) -> Option<&[u8]> {
+    const SEQUENCE: u8 = 0x30;
+    const OBJECT_IDENTIFIER: u8 = 0x06;
+
+    let (SEQUENCE, certificate, _) = read_der(certificate)? else {
+        return None;
+    };
+
+    let (SEQUENCE, _, rest) = read_der(certificate)? else {
+        return None;
+    };
+
+    let (SEQUENCE, algorithm, _) = read_der(rest)? else {
+        return None;
+    };
+
+    match read_der(algorithm)? {
+        (OBJECT_IDENTIFIER, oid, _) => Some(oid),
+        _ => None,
+    }
+}
+
+// read a DER value, returning its tag, its contents and the bytes after it
+fn read_der(buf: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, buf) = buf.split_first()?;
+    let (&len, mut buf) = buf.split_first()?;
+
+    let len = if len & 0x80 == 0 {
+        usize::from(len)
+    } else {
+        // the long form gives the number of bytes of the length
+        let num_bytes = usize::from(len & 0x7f);
+
+        if num_bytes == 0 || num_bytes > 4 || buf.len() < num_bytes {
+            return None;
+        }
+
+        let (len, rest) = buf.split_at(num_bytes);
+        buf = rest;
+
+        len.iter().fold(0, |len, &b| (len << 8) | usize::from(b))
+    };
+
+    if buf.len() < len {
+        return None;
+    }
+
+    let (contents, rest) = buf.split_at(len);
+    Some((tag, contents, rest))
 }
 
 // nonce is a sequence of random printable bytes
@@ -207,3 +349,51 @@ fn hi<'a>(s: &'a str, salt: &'a [u8], iter_count: u32) -> Result<[u8; 32], Error
 
     Ok(hi.into())
 }
+
+#[cfg(test)]
+fn test_certificate(oid: &[u8]) -> Vec<u8> {
+    // an empty `tbsCertificate` and `signatureValue` are enough to find the algorithm
+    let mut algorithm = vec![0x30, oid.len() as u8 + 4, 0x06, oid.len() as u8];
+    algorithm.extend_from_slice(oid);
+    algorithm.extend_from_slice(&[0x05, 0x00]);
+
+    let mut contents = vec![0x30, 0x00];
+    contents.extend_from_slice(&algorithm);
+    contents.extend_from_slice(&[0x03, 0x01, 0x00]);
+
+    // use the long form of the length
+    let mut certificate = vec![0x30, 0x81, contents.len() as u8];
+    certificate.extend_from_slice(&contents);
+    certificate
+}
+
+#[test]
+fn test_certificate_hash() {
+    // sha256WithRSAEncryption
+    let certificate = test_certificate(&[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b]);
+    assert_eq!(
+        certificate_hash(&certificate).unwrap(),
+        Sha256::digest(&certificate).to_vec()
+    );
+
+    // SHA-1 is replaced with SHA-256
+    let certificate = test_certificate(&[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05]);
+    assert_eq!(
+        certificate_hash(&certificate).unwrap(),
+        Sha256::digest(&certificate).to_vec()
+    );
+
+    // ecdsa-with-SHA384
+    let certificate = test_certificate(&[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03]);
+    assert_eq!(
+        certificate_hash(&certificate).unwrap(),
+        Sha384::digest(&certificate).to_vec()
+    );
+
+    // Ed25519 does not have a separate hash function
+    let certificate = test_certificate(&[0x2b, 0x65, 0x70]);
+    assert!(certificate_hash(&certificate).is_err());
+
+    assert!(certificate_hash(&certificate[..certificate.len() - 1]).is_err());
+    assert!(certificate_hash(b"").is_err());
+}
@@ -0,0 +1,118 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::common::StatementCache;
+use crate::ext::ustr::UStr;
+use crate::statement::PgStatementMetadata;
+use crate::types::Oid;
+use crate::{HashMap, PgTypeInfo};
+
+/// Statement metadata and user-defined types, shared by the connections opened with the same
+/// (or cloned) options, such as all connections of a pool.
+///
+/// A connection that finds a statement here only has to parse it, and one that finds a type
+/// doesn't have to look it up.
+pub(crate) struct PgSharedCache {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    // the parameter types sent in `Parse`, and the metadata returned for them, by query string
+    statements: StatementCache<(Vec<Oid>, Arc<PgStatementMetadata>)>,
+    type_info: HashMap<Oid, PgTypeInfo>,
+    type_oid: HashMap<UStr, Oid>,
+}
+
+impl PgSharedCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                statements: StatementCache::new(capacity),
+                type_info: HashMap::new(),
+                type_oid: HashMap::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // the maps stay consistent even if another thread panicked
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn statement(
+        &self,
+        sql: &str,
+        param_types: &[Oid],
+    ) -> Option<Arc<PgStatementMetadata>> {
+        match self.lock().statements.get_mut(sql) {
+            Some((types, metadata)) if *types == param_types => Some(Arc::clone(metadata)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn insert_statement(
+        &self,
+        sql: &str,
+        param_types: Vec<Oid>,
+        metadata: Arc<PgStatementMetadata>,
+    ) {
+        let mut inner = self.lock();
+
+        if inner.statements.is_enabled() {
+            inner.statements.insert(sql, (param_types, metadata));
+        }
+    }
+
+    pub(crate) fn type_info(&self, oid: Oid) -> Option<PgTypeInfo> {
+        self.lock().type_info.get(&oid).cloned()
+    }
+
+    pub(crate) fn insert_type_info(&self, oid: Oid, info: PgTypeInfo) {
+        let mut inner = self.lock();
+
+        inner.type_oid.insert(info.0.name().to_string().into(), oid);
+        inner.type_info.insert(oid, info);
+    }
+
+    pub(crate) fn type_oid(&self, name: &str) -> Option<Oid> {
+        self.lock().type_oid.get(name).copied()
+    }
+
+    pub(crate) fn insert_type_oid(&self, name: UStr, oid: Oid) {
+        self.lock().type_oid.insert(name, oid);
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut inner = self.lock();
+
+        inner.statements.clear();
+        inner.type_info.clear();
+        inner.type_oid.clear();
+    }
+}
+
+impl Debug for PgSharedCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let inner = self.lock();
+
+        f.debug_struct("PgSharedCache")
+            .field("statements", &inner.statements.len())
+            .field("types", &inner.type_info.len())
+            .finish()
+    }
+}
+
+#[test]
+fn test_statements_by_parameter_types() {
+    let cache = PgSharedCache::new(10);
+    let metadata = Arc::new(PgStatementMetadata::default());
+
+    cache.insert_statement("SELECT $1", vec![Oid(25)], metadata);
+
+    assert!(cache.statement("SELECT $1", &[Oid(25)]).is_some());
+    assert!(cache.statement("SELECT $1", &[Oid(23)]).is_none());
+    assert!(cache.statement("SELECT $2", &[Oid(25)]).is_none());
+
+    cache.clear();
+    assert!(cache.statement("SELECT $1", &[Oid(25)]).is_none());
+}
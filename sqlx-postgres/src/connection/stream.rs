@@ -1,9 +1,10 @@
 use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
+use std::task::{Context, Poll};
 
 use futures_channel::mpsc::UnboundedSender;
-use futures_util::SinkExt;
+use futures_core::ready;
 use log::Level;
 use sqlx_core::bytes::{Buf, Bytes};
 
@@ -12,7 +13,8 @@ use crate::error::Error;
 use crate::io::{Decode, Encode};
 use crate::message::{Message, MessageFormat, Notice, Notification, ParameterStatus};
 use crate::net::{self, BufferedSocket, Socket};
-use crate::{PgConnectOptions, PgDatabaseError, PgSeverity};
+use crate::notice::NoticeHandler;
+use crate::{PgConnectOptions, PgDatabaseError, PgParameterStatus, PgSeverity};
 
 // the stream is a separate type from the connection to uphold the invariant where an instantiated
 // [PgConnection] is a **valid** connection to postgres
@@ -35,7 +37,13 @@ pub struct PgStream {
 
     pub(crate) parameter_statuses: BTreeMap<String, String>,
 
+    // senders of the streams of `PgConnection::watch_parameter_statuses`
+    pub(crate) parameter_watchers: Vec<UnboundedSender<PgParameterStatus>>,
+
     pub(crate) server_version_num: Option<u32>,
+
+    // the handler of `PgConnectOptions::notice_handler`, instead of logging notices
+    notice_handler: Option<NoticeHandler>,
 }
 
 impl PgStream {
@@ -51,7 +59,9 @@ impl PgStream {
             inner: BufferedSocket::new(socket),
             notifications: None,
             parameter_statuses: BTreeMap::default(),
+            parameter_watchers: Vec::new(),
             server_version_num: None,
+            notice_handler: options.notice_handler.clone(),
         })
     }
 
@@ -101,80 +111,124 @@ impl PgStream {
         loop {
             let message = self.recv_unchecked().await?;
 
-            match message.format {
-                MessageFormat::ErrorResponse => {
-                    // An error returned from the database server.
-                    return Err(PgDatabaseError(message.decode()?).into());
+            if let Some(message) = self.handle_message(message)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Poll-based equivalent of [`Self::recv()`].
+    ///
+    /// A message is only consumed once it was received completely, so it is fine to stop
+    /// polling this at any point, e.g. to send something in the meantime.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<Message, Error>> {
+        loop {
+            let header = ready!(self.inner.poll_fill_buf(cx, 5))?;
+            let size = (&header[1..5]).get_u32() as usize;
+
+            if size < 4 {
+                return Poll::Ready(Err(err_protocol!("invalid message length: {}", size)));
+            }
+
+            let mut contents = ready!(self.inner.poll_read_buffered(cx, 1 + size))?.freeze();
+
+            let format = MessageFormat::try_from_u8(contents.get_u8())?;
+            contents.advance(4);
+
+            if let Some(message) = self.handle_message(Message { format, contents })? {
+                return Poll::Ready(Ok(message));
+            }
+        }
+    }
+
+    // Handle the messages the server can send at any time, returning the other ones
+    fn handle_message(&mut self, message: Message) -> Result<Option<Message>, Error> {
+        match message.format {
+            MessageFormat::ErrorResponse => {
+                // An error returned from the database server.
+                return Err(PgDatabaseError(message.decode()?).into());
+            }
+
+            MessageFormat::NotificationResponse => {
+                if let Some(buffer) = &mut self.notifications {
+                    let notification: Notification = message.decode()?;
+                    let _ = buffer.unbounded_send(notification);
+
+                    return Ok(None);
                 }
+            }
 
-                MessageFormat::NotificationResponse => {
-                    if let Some(buffer) = &mut self.notifications {
-                        let notification: Notification = message.decode()?;
-                        let _ = buffer.send(notification).await;
+            MessageFormat::ParameterStatus => {
+                // informs the frontend about the current (initial)
+                // setting of backend parameters
 
-                        continue;
-                    }
+                let ParameterStatus { name, value } = message.decode()?;
+                // TODO: handle `client_encoding`, `DateStyle` change
+
+                if name == "server_version" {
+                    self.server_version_num = parse_server_version(&value);
                 }
 
-                MessageFormat::ParameterStatus => {
-                    // informs the frontend about the current (initial)
-                    // setting of backend parameters
+                // drop the senders of the streams that were dropped
+                self.parameter_watchers.retain(|watcher| {
+                    watcher
+                        .unbounded_send(PgParameterStatus {
+                            name: name.clone(),
+                            value: value.clone(),
+                        })
+                        .is_ok()
+                });
 
-                    let ParameterStatus { name, value } = message.decode()?;
-                    // TODO: handle `client_encoding`, `DateStyle` change
+                self.parameter_statuses.insert(name, value);
 
-                    match name.as_str() {
-                        "server_version" => {
-                            self.server_version_num = parse_server_version(&value);
-                        }
-                        _ => {
-                            self.parameter_statuses.insert(name, value);
-                        }
-                    }
+                return Ok(None);
+            }
 
-                    continue;
-                }
+            MessageFormat::NoticeResponse => {
+                // do we need this to be more configurable?
+                // if you are reading this comment and think so, open an issue
 
-                MessageFormat::NoticeResponse => {
-                    // do we need this to be more configurable?
-                    // if you are reading this comment and think so, open an issue
+                let notice: Notice = message.decode()?;
 
-                    let notice: Notice = message.decode()?;
+                if let Some(handler) = &self.notice_handler {
+                    handler.handle(notice);
 
-                    let (log_level, tracing_level) = match notice.severity() {
-                        PgSeverity::Fatal | PgSeverity::Panic | PgSeverity::Error => {
-                            (Level::Error, tracing::Level::ERROR)
-                        }
-                        PgSeverity::Warning => (Level::Warn, tracing::Level::WARN),
-                        PgSeverity::Notice => (Level::Info, tracing::Level::INFO),
-                        PgSeverity::Debug => (Level::Debug, tracing::Level::DEBUG),
-                        PgSeverity::Info | PgSeverity::Log => (Level::Trace, tracing::Level::TRACE),
-                    };
+                    return Ok(None);
+                }
 
-                    let log_is_enabled = log::log_enabled!(
-                        target: "sqlx::postgres::notice",
-                        log_level
-                    ) || sqlx_core::private_tracing_dynamic_enabled!(
+                let (log_level, tracing_level) = match notice.severity() {
+                    PgSeverity::Fatal | PgSeverity::Panic | PgSeverity::Error => {
+                        (Level::Error, tracing::Level::ERROR)
+                    }
+                    PgSeverity::Warning => (Level::Warn, tracing::Level::WARN),
+                    PgSeverity::Notice => (Level::Info, tracing::Level::INFO),
+                    PgSeverity::Debug => (Level::Debug, tracing::Level::DEBUG),
+                    PgSeverity::Info | PgSeverity::Log => (Level::Trace, tracing::Level::TRACE),
+                };
+
+                let log_is_enabled = log::log_enabled!(
+                    target: "sqlx::postgres::notice",
+                    log_level
+                ) || sqlx_core::private_tracing_dynamic_enabled!(
+                    target: "sqlx::postgres::notice",
+                    tracing_level
+                );
+                if log_is_enabled {
+                    let message = notice.message().to_string();
+                    sqlx_core::private_tracing_dynamic_event!(
                         target: "sqlx::postgres::notice",
-                        tracing_level
+                        tracing_level,
+                        message
                     );
-                    if log_is_enabled {
-                        let message = format!("{}", notice.message());
-                        sqlx_core::private_tracing_dynamic_event!(
-                            target: "sqlx::postgres::notice",
-                            tracing_level,
-                            message
-                        );
-                    }
-
-                    continue;
                 }
 
-                _ => {}
+                return Ok(None);
             }
 
-            return Ok(message);
+            _ => {}
         }
+
+        Ok(Some(message))
     }
 }
 
@@ -60,6 +60,7 @@ async fn maybe_upgrade<S: Socket>(
         root_cert_path: options.ssl_root_cert.as_ref(),
         client_cert_path: options.ssl_client_cert.as_ref(),
         client_key_path: options.ssl_client_key.as_ref(),
+        session_cache: &options.tls_session_cache,
     };
 
     tls::handshake(socket, config, SocketIntoBox).await
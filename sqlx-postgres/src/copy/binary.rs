@@ -0,0 +1,490 @@
+use std::ops::DerefMut;
+
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+use sqlx_core::bytes::{Buf, BytesMut};
+
+use crate::arguments::PgArgumentBuffer;
+use crate::connection::PgConnection;
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::{Error, Result};
+use crate::ext::async_stream::TryAsyncStream;
+use crate::message::CopyData;
+use crate::types::Type;
+use crate::value::{PgValueFormat, PgValueRef};
+use crate::Postgres;
+
+use super::{pg_begin_copy_out, PgCopyIn};
+
+// https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4
+
+/// The signature every file in the binary `COPY` format starts with.
+const SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// The header we send: the signature, no flags and an empty header extension.
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// The field count which marks the end of the file.
+const TRAILER: &[u8] = &(-1_i16).to_be_bytes();
+
+/// The header flag which signals that every tuple contains an OID (only before Postgres 12).
+const FLAG_HAS_OIDS: u32 = 1 << 16;
+
+impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
+    /// Send a row of `COPY` data in the binary format, encoding its values with their
+    /// [`Encode`] impls.
+    ///
+    /// The statement must specify `FORMAT binary`. The header of the format is sent before
+    /// the first row, and the trailer by [Self::finish]. Rows are buffered and sent in
+    /// batches; any rows still buffered are sent by [Self::finish].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// let mut copy = conn
+    ///     .copy_in_raw("COPY users (id, name) FROM STDIN (FORMAT binary)")
+    ///     .await?;
+    ///
+    /// copy.send_row((1_i32, "Alice")).await?;
+    /// copy.send_row((2_i32, None::<&str>)).await?;
+    ///
+    /// copy.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ### Note: Types Must Match
+    /// Unlike bind parameters, values are not converted by Postgres: each value must have
+    /// exactly the type of the column it is copied into, e.g. `i64` for a `BIGINT` column.
+    ///
+    /// ### Note: Custom Types
+    /// The connection cannot look up type OIDs while in `COPY` mode. Arrays and records of
+    /// custom types must have been used in a query on the connection before the `COPY`.
+    pub async fn send_row(&mut self, row: impl PgCopyInRow) -> Result<&mut Self> {
+        if self.is_textual() {
+            return Err(err_protocol!(
+                "send_row: COPY does not use the binary format"
+            ));
+        }
+
+        let mut encoder = PgCopyRowEncoder::new();
+        row.encode_row(&mut encoder);
+
+        let conn: &mut PgConnection = self.conn.as_deref_mut().expect("send_row: conn taken");
+        encoder.finish(conn)?;
+
+        if !self.binary_header_sent {
+            conn.stream.write(CopyData(HEADER));
+            self.binary_header_sent = true;
+        }
+
+        self.write_buffered(&encoder.buf).await?;
+
+        Ok(self)
+    }
+
+    /// Write the trailer of the binary format, if any rows were sent with [Self::send_row].
+    pub(super) fn write_binary_trailer(&mut self) {
+        if self.binary_header_sent {
+            self.conn
+                .as_deref_mut()
+                .expect("write_binary_trailer: conn taken")
+                .stream
+                .write(CopyData(TRAILER));
+        }
+    }
+}
+
+impl PgConnection {
+    /// Issue a `COPY ... TO STDOUT (FORMAT binary)` statement and decode the rows it returns
+    /// with their [`Decode`] impls.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut rows = conn
+    ///     .copy_out_rows::<(i32, Option<String>)>("COPY users (id, name) TO STDOUT (FORMAT binary)")
+    ///     .await?;
+    ///
+    /// while let Some((id, name)) = rows.try_next().await? {
+    ///     println!("{id}: {name:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The types are not checked against those of the columns, as binary `COPY` data does not
+    /// include them: each value must be decoded as exactly the type of its column.
+    ///
+    /// The same caveats as for [PgConnection::copy_out_raw] apply to a stream that is not read
+    /// to completion.
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn copy_out_rows<'c, R: PgCopyOutRow + Send + 'c>(
+        &'c mut self,
+        statement: &str,
+    ) -> Result<BoxStream<'c, Result<R>>> {
+        let mut data = pg_begin_copy_out(self, statement).await?;
+
+        let stream: TryAsyncStream<'c, R> = try_stream! {
+            let mut decoder = CopyOutDecoder::default();
+            let mut rows = Vec::new();
+            let mut error = None;
+
+            // the data is always read to the end, so the connection is usable after an error
+            while let Some(chunk) = data.try_next().await? {
+                if error.is_some() {
+                    continue;
+                }
+
+                if let Err(e) = decoder.decode(&chunk, &mut rows) {
+                    error = Some(e);
+                }
+
+                for row in rows.drain(..) {
+                    r#yield!(row);
+                }
+            }
+
+            if let Some(e) = error {
+                return Err(e);
+            }
+
+            if !decoder.trailer_read {
+                return Err(err_protocol!("COPY data ended without a trailer"));
+            }
+
+            Ok(())
+        };
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[derive(Default)]
+struct CopyOutDecoder {
+    buf: BytesMut,
+    header_read: bool,
+    trailer_read: bool,
+}
+
+impl CopyOutDecoder {
+    /// Decode the rows completed by `chunk` into `rows`.
+    fn decode<R: PgCopyOutRow>(&mut self, chunk: &[u8], rows: &mut Vec<R>) -> Result<()> {
+        if self.trailer_read {
+            return Ok(());
+        }
+
+        self.buf.extend_from_slice(chunk);
+
+        if !self.header_read {
+            if !read_header(&mut self.buf)? {
+                return Ok(());
+            }
+
+            self.header_read = true;
+        }
+
+        loop {
+            match read_tuple(&mut self.buf)? {
+                Tuple::Incomplete => return Ok(()),
+                Tuple::Trailer => {
+                    self.trailer_read = true;
+                    return Ok(());
+                }
+                Tuple::Fields(num_fields, data) => {
+                    let mut decoder = PgCopyRowDecoder {
+                        buf: &data,
+                        num_fields,
+                        index: 0,
+                    };
+
+                    let row = R::decode_row(&mut decoder)?;
+
+                    if decoder.index != num_fields {
+                        return Err(err_protocol!(
+                            "COPY row has {} fields but {} were decoded",
+                            num_fields,
+                            decoder.index
+                        ));
+                    }
+
+                    rows.push(row);
+                }
+            }
+        }
+    }
+}
+
+/// Read the header of the binary format from `buf`.
+///
+/// Returns `false` if more data is needed.
+fn read_header(buf: &mut BytesMut) -> Result<bool> {
+    // signature, flags and header extension length
+    let fixed_len = SIGNATURE.len() + 8;
+
+    if buf.len() < fixed_len {
+        return Ok(false);
+    }
+
+    if &buf[..SIGNATURE.len()] != SIGNATURE {
+        return Err(err_protocol!(
+            "COPY data is not in the binary format; expected `FORMAT binary`"
+        ));
+    }
+
+    let mut fixed = &buf[SIGNATURE.len()..fixed_len];
+    let flags = fixed.get_u32();
+    let extension_len = fixed.get_u32() as usize;
+
+    if flags & FLAG_HAS_OIDS != 0 {
+        return Err(err_protocol!("COPY data WITH OIDS is not supported"));
+    }
+
+    if buf.len() < fixed_len + extension_len {
+        return Ok(false);
+    }
+
+    buf.advance(fixed_len + extension_len);
+
+    Ok(true)
+}
+
+enum Tuple {
+    Incomplete,
+    Trailer,
+    Fields(usize, BytesMut),
+}
+
+/// Split the next tuple off `buf`, once it was received completely.
+fn read_tuple(buf: &mut BytesMut) -> Result<Tuple> {
+    if buf.len() < 2 {
+        return Ok(Tuple::Incomplete);
+    }
+
+    let num_fields = (&buf[..2]).get_i16();
+
+    if num_fields == -1 {
+        buf.advance(2);
+        return Ok(Tuple::Trailer);
+    }
+
+    let num_fields = usize::try_from(num_fields)
+        .map_err(|_| err_protocol!("invalid COPY field count: {}", num_fields))?;
+
+    // walk the length prefixes of the fields to find the end of the tuple
+    let mut len = 2;
+
+    for _ in 0..num_fields {
+        if buf.len() < len + 4 {
+            return Ok(Tuple::Incomplete);
+        }
+
+        let field_len = (&buf[len..len + 4]).get_i32();
+        len += 4;
+
+        if field_len > 0 {
+            len += field_len as usize;
+        } else if field_len < -1 {
+            return Err(err_protocol!("invalid COPY field length: {}", field_len));
+        }
+    }
+
+    if buf.len() < len {
+        return Ok(Tuple::Incomplete);
+    }
+
+    let mut tuple = buf.split_to(len);
+    tuple.advance(2);
+
+    Ok(Tuple::Fields(num_fields, tuple))
+}
+
+/// Encodes the values of a row for [`PgCopyIn::send_row`].
+pub struct PgCopyRowEncoder {
+    buf: PgArgumentBuffer,
+    num_fields: usize,
+}
+
+impl PgCopyRowEncoder {
+    fn new() -> Self {
+        let mut buf = PgArgumentBuffer::default();
+
+        // reserve space for the field count
+        buf.extend(&[0; 2]);
+
+        PgCopyRowEncoder { buf, num_fields: 0 }
+    }
+
+    /// Encode the next value of the row.
+    pub fn push<'q, T: Encode<'q, Postgres>>(&mut self, value: T) -> &mut Self {
+        self.buf.encode(value);
+        self.num_fields += 1;
+        self
+    }
+
+    fn finish(&mut self, conn: &PgConnection) -> Result<()> {
+        let num_fields = i16::try_from(self.num_fields)
+            .map_err(|_| err_protocol!("too many fields in COPY row: {}", self.num_fields))?;
+
+        self.buf[..2].copy_from_slice(&num_fields.to_be_bytes());
+        self.buf.patch_cached_types(conn)
+    }
+}
+
+/// Decodes the values of a row received by [`PgConnection::copy_out_rows`].
+pub struct PgCopyRowDecoder<'r> {
+    buf: &'r [u8],
+    num_fields: usize,
+    index: usize,
+}
+
+impl<'r> PgCopyRowDecoder<'r> {
+    /// Returns the number of fields in the row.
+    pub fn num_fields(&self) -> usize {
+        self.num_fields
+    }
+
+    /// Decode the next value of the row.
+    pub fn try_decode<T: Decode<'r, Postgres> + Type<Postgres>>(&mut self) -> Result<T> {
+        if self.index >= self.num_fields {
+            return Err(Error::ColumnIndexOutOfBounds {
+                index: self.index,
+                len: self.num_fields,
+            });
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        let value = PgValueRef::get(&mut self.buf, PgValueFormat::Binary, T::type_info());
+
+        T::decode(value).map_err(|source| Error::ColumnDecode {
+            index: format!("{index:?}"),
+            source,
+        })
+    }
+}
+
+/// A row that can be sent by [`PgCopyIn::send_row`].
+///
+/// This is implemented for tuples of up to 16 values. For other types, push the values in the
+/// order of the columns of the `COPY` statement:
+///
+/// ```rust
+/// use sqlx::postgres::{PgCopyInRow, PgCopyRowEncoder};
+///
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// impl PgCopyInRow for User {
+///     fn encode_row(self, encoder: &mut PgCopyRowEncoder) {
+///         encoder.push(self.id).push(self.name);
+///     }
+/// }
+/// ```
+pub trait PgCopyInRow {
+    /// Push every value of the row to `encoder` with [`PgCopyRowEncoder::push`].
+    fn encode_row(self, encoder: &mut PgCopyRowEncoder);
+}
+
+/// A row that can be received by [`PgConnection::copy_out_rows`].
+///
+/// This is implemented for tuples of up to 16 values. For other types, decode the values in
+/// the order of the columns of the `COPY` statement:
+///
+/// ```rust
+/// use sqlx::postgres::{PgCopyOutRow, PgCopyRowDecoder};
+///
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// impl PgCopyOutRow for User {
+///     fn decode_row(decoder: &mut PgCopyRowDecoder<'_>) -> sqlx::Result<Self> {
+///         Ok(User {
+///             id: decoder.try_decode()?,
+///             name: decoder.try_decode()?,
+///         })
+///     }
+/// }
+/// ```
+pub trait PgCopyOutRow: Sized {
+    /// Decode every value of the row from `decoder` with [`PgCopyRowDecoder::try_decode`].
+    fn decode_row(decoder: &mut PgCopyRowDecoder<'_>) -> Result<Self>;
+}
+
+macro_rules! impl_copy_row_for_tuple {
+    ($($T:ident),+) => {
+        impl<'q, $($T,)+> PgCopyInRow for ($($T,)+)
+        where
+            $($T: Encode<'q, Postgres>,)+
+        {
+            #[allow(non_snake_case)]
+            fn encode_row(self, encoder: &mut PgCopyRowEncoder) {
+                let ($($T,)+) = self;
+                $(encoder.push($T);)+
+            }
+        }
+
+        impl<$($T,)+> PgCopyOutRow for ($($T,)+)
+        where
+            $($T: for<'r> Decode<'r, Postgres> + Type<Postgres>,)+
+        {
+            fn decode_row(decoder: &mut PgCopyRowDecoder<'_>) -> Result<Self> {
+                Ok(($(decoder.try_decode::<$T>()?,)+))
+            }
+        }
+    };
+}
+
+impl_copy_row_for_tuple!(T1);
+impl_copy_row_for_tuple!(T1, T2);
+impl_copy_row_for_tuple!(T1, T2, T3);
+impl_copy_row_for_tuple!(T1, T2, T3, T4);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_copy_row_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+#[test]
+fn test_decode_binary_copy_out() {
+    let mut data = HEADER.to_vec();
+    data.extend_from_slice(b"\x00\x02\x00\x00\x00\x04\x00\x00\x00\x2a\xff\xff\xff\xff");
+    data.extend_from_slice(b"\x00\x02\x00\x00\x00\x04\x00\x00\x00\x2b\x00\x00\x00\x01a");
+    data.extend_from_slice(TRAILER);
+
+    // tuples may be split across chunks arbitrarily
+    let mut decoder = CopyOutDecoder::default();
+    let mut rows: Vec<(i32, Option<String>)> = Vec::new();
+
+    for byte in data.chunks(1) {
+        decoder.decode(byte, &mut rows).unwrap();
+    }
+
+    assert!(decoder.trailer_read);
+    assert_eq!(rows, [(42, None), (43, Some("a".into()))]);
+
+    // every field must be decoded
+    let mut decoder = CopyOutDecoder::default();
+    let mut rows: Vec<(i32,)> = Vec::new();
+    assert!(decoder.decode(&data, &mut rows).is_err());
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn test_read_binary_header_with_oids() {
+    let mut buf = BytesMut::from(&b"PGCOPY\n\xff\r\n\0\x00\x01\x00\x00\x00\x00\x00\x00"[..]);
+    assert!(read_header(&mut buf).is_err());
+}
@@ -0,0 +1,272 @@
+use std::ops::DerefMut;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::{ready, Stream};
+use futures_util::future::poll_fn;
+use futures_util::Sink;
+use sqlx_core::bytes::Bytes;
+
+use crate::connection::PgConnection;
+use crate::error::{Error, Result};
+use crate::message::{CopyData, CopyDone, CopyFail, CopyResponse, MessageFormat, Query};
+
+use super::FLUSH_THRESHOLD;
+
+impl PgConnection {
+    /// Issue a statement that switches the connection to the `COPY BOTH` sub-protocol, in
+    /// which both sides exchange `CopyData` messages until they end the copy.
+    ///
+    /// The server only starts this mode for `START_REPLICATION`, which requires a replication
+    /// connection, see [`PgReplicationConnection::copy_both`](crate::PgReplicationConnection::copy_both).
+    /// If `statement` starts anything else, an error is returned.
+    ///
+    /// ### Note
+    /// [`PgCopyBoth::finish`] should be called when done. If it is dropped instead, the copy is
+    /// ended and the rest of its data is discarded the next time the connection is used.
+    pub async fn copy_both(&mut self, statement: &str) -> Result<PgCopyBoth<&mut Self>> {
+        PgCopyBoth::begin(self, statement).await
+    }
+}
+
+/// A connection in the `COPY BOTH` sub-protocol, which sends and receives the data of
+/// `CopyData` messages.
+///
+/// Created by [`PgConnection::copy_both`] or [`PgReplicationConnection::copy_both`].
+///
+/// [`PgReplicationConnection::copy_both`]: crate::PgReplicationConnection::copy_both
+///
+/// Besides [`send`][Self::send] and [`recv`][Self::recv], this implements [`Stream`] for the
+/// received data and [`Sink`] for the data to send. Both halves of
+/// [`StreamExt::split`](futures_util::StreamExt::split) can be used concurrently, e.g. to
+/// answer messages from the server while waiting for the next ones:
+///
+/// ```rust,no_run
+/// # async fn example(repl: &mut sqlx::postgres::PgReplicationConnection) -> sqlx::Result<()> {
+/// use futures::{SinkExt, StreamExt, TryStreamExt};
+///
+/// let copy = repl.copy_both("START_REPLICATION SLOT app_slot LOGICAL 0/0").await?;
+/// let (mut sink, mut stream) = copy.split();
+///
+/// let receive = async {
+///     while let Some(data) = stream.try_next().await? {
+///         println!("received {} bytes", data.len());
+///     }
+///
+///     Ok::<_, sqlx::Error>(())
+/// };
+///
+/// let send = async {
+///     sink.send(b"...".as_slice()).await?;
+///     sink.close().await
+/// };
+///
+/// futures::try_join!(receive, send)?;
+///
+/// sink.reunite(stream).unwrap().finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "connection will be stuck in COPY BOTH mode until `.finish()` is called"]
+pub struct PgCopyBoth<C: DerefMut<Target = PgConnection>> {
+    conn: Option<C>,
+    // whether we sent `CopyDone`, after which we may not send more data
+    done_sent: bool,
+    // whether the server sent `CopyDone`, after which it sends no more data
+    done_received: bool,
+}
+
+impl<C: DerefMut<Target = PgConnection>> PgCopyBoth<C> {
+    async fn begin(mut conn: C, statement: &str) -> Result<Self> {
+        conn.wait_until_ready().await?;
+        conn.stream.send(Query(statement)).await?;
+
+        // the `ReadyForQuery` only comes once the copy is over
+        conn.pending_ready_for_query_count += 1;
+
+        let message = conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::CopyBothResponse => {
+                let _: CopyResponse = message.decode()?;
+            }
+            format => {
+                if format == MessageFormat::CopyInResponse {
+                    // the server would wait for the data otherwise, and answers with an error
+                    conn.stream
+                        .send(CopyFail::new("expected a COPY BOTH statement"))
+                        .await?;
+
+                    loop {
+                        match conn.stream.recv().await {
+                            Ok(message) if message.format == MessageFormat::ReadyForQuery => {
+                                conn.handle_ready_for_query(message)?;
+                                break;
+                            }
+                            Ok(_) | Err(Error::Database(_)) => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+
+                return Err(err_protocol!(
+                    "expecting CopyBothResponse but received {:?}",
+                    format
+                ));
+            }
+        }
+
+        Ok(PgCopyBoth {
+            conn: Some(conn),
+            done_sent: false,
+            done_received: false,
+        })
+    }
+
+    fn conn(&mut self) -> &mut PgConnection {
+        self.conn.as_deref_mut().expect("PgCopyBoth: conn taken")
+    }
+
+    /// Send `data` in a `CopyData` message.
+    ///
+    /// Returns an error if the copy was ended with [`end`][Self::end] already.
+    pub async fn send(&mut self, data: impl AsRef<[u8]>) -> Result<()> {
+        self.check_not_done()?;
+
+        self.conn().stream.send(CopyData(data.as_ref())).await
+    }
+
+    /// Receive the data of the next `CopyData` message.
+    ///
+    /// Returns `None` once the server ended the copy; [`finish`][Self::finish] is still needed
+    /// to return to normal operation.
+    pub async fn recv(&mut self) -> Result<Option<Bytes>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Tell the server that we are done sending data, while still receiving the remaining
+    /// data of the server.
+    pub async fn end(&mut self) -> Result<()> {
+        if !self.done_sent {
+            self.done_sent = true;
+            self.conn().stream.send(CopyDone).await?;
+        }
+
+        Ok(())
+    }
+
+    /// End the copy, discarding any data the server did not send yet.
+    ///
+    /// The connection is ready for the next statement afterwards.
+    pub async fn finish(mut self) -> Result<()> {
+        self.end().await?;
+
+        let mut conn = self.conn.take().expect("PgCopyBoth: conn taken");
+
+        // skips the remaining `CopyData`, `CopyDone` and `CommandComplete` messages
+        conn.wait_until_ready().await
+    }
+
+    fn check_not_done(&self) -> Result<()> {
+        if self.done_sent {
+            return Err(Error::Protocol(
+                "PgCopyBoth: cannot send data after the copy was ended".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Bytes>>> {
+        if self.done_received {
+            return Poll::Ready(Ok(None));
+        }
+
+        let message = match ready!(self.conn().stream.poll_recv(cx)) {
+            Ok(message) => message,
+            Err(e) => {
+                // the server left the copy mode
+                self.done_received = true;
+                self.done_sent = true;
+
+                return Poll::Ready(Err(e));
+            }
+        };
+
+        Poll::Ready(match message.format {
+            MessageFormat::CopyData => Ok(Some(message.decode::<CopyData<Bytes>>()?.0)),
+            MessageFormat::CopyDone => {
+                self.done_received = true;
+                Ok(None)
+            }
+            _ => Err(err_protocol!(
+                "unexpected message format during COPY BOTH: {:?}",
+                message.format
+            )),
+        })
+    }
+}
+
+impl<C: DerefMut<Target = PgConnection> + Unpin> Stream for PgCopyBoth<C> {
+    type Item = Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_recv(cx).map(Result::transpose)
+    }
+}
+
+impl<C, B> Sink<B> for PgCopyBoth<C>
+where
+    C: DerefMut<Target = PgConnection> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+
+        this.check_not_done()?;
+
+        if this.conn().stream.write_buffer().get().len() >= FLUSH_THRESHOLD {
+            ready!(this.conn().stream.poll_flush(cx))?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: B) -> Result<()> {
+        let this = self.get_mut();
+
+        this.check_not_done()?;
+        this.conn().stream.write(CopyData(item.as_ref()));
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(ready!(self.get_mut().conn().stream.poll_flush(cx))?))
+    }
+
+    /// Sends `CopyDone`, like [`PgCopyBoth::end`].
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+
+        if !this.done_sent {
+            this.done_sent = true;
+            this.conn().stream.write(CopyDone);
+        }
+
+        Poll::Ready(Ok(ready!(this.conn().stream.poll_flush(cx))?))
+    }
+}
+
+impl<C: DerefMut<Target = PgConnection>> Drop for PgCopyBoth<C> {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            if !self.done_sent {
+                // sent the next time the connection is used, which skips the rest of the copy
+                conn.stream.write(CopyDone);
+            }
+        }
+    }
+}
@@ -8,13 +8,24 @@ use sqlx_core::bytes::{BufMut, Bytes};
 use crate::connection::PgConnection;
 use crate::error::{Error, Result};
 use crate::ext::async_stream::TryAsyncStream;
-use crate::io::{AsyncRead, AsyncReadExt};
+use crate::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use crate::message::{
     CommandComplete, CopyData, CopyDone, CopyFail, CopyResponse, MessageFormat, Query,
 };
 use crate::pool::{Pool, PoolConnection};
 use crate::Postgres;
 
+pub use binary::{PgCopyInRow, PgCopyOutRow, PgCopyRowDecoder, PgCopyRowEncoder};
+pub use both::PgCopyBoth;
+
+mod binary;
+mod both;
+mod record;
+
+/// Data written with [`PgCopyIn::write_buffered`] is sent once at least this many bytes are
+/// waiting.
+const FLUSH_THRESHOLD: usize = 64 * 1024;
+
 impl PgConnection {
     /// Issue a `COPY FROM STDIN` statement and transition the connection to streaming data
     /// to Postgres. This is a more efficient way to import data into Postgres as compared to
@@ -59,6 +70,71 @@ impl PgConnection {
     ) -> Result<BoxStream<'c, Result<Bytes>>> {
         pg_begin_copy_out(self, statement).await
     }
+
+    /// Issue a `COPY TO STDOUT` statement and write the data it returns to `writer`.
+    ///
+    /// The data is written as it arrives, so no more than one message is buffered in memory;
+    /// a slow `writer` slows down the transfer from Postgres. `writer` is flushed at the end.
+    ///
+    /// The number of rows copied is returned.
+    ///
+    /// If writing fails, the remaining data is read and discarded before returning the
+    /// error, so the connection is safe for reuse.
+    ///
+    /// ### Note: Runtime Features
+    /// This method uses the `AsyncWrite` trait which is re-exported from either Tokio or
+    /// `async-std` depending on which runtime feature is used, as with [PgCopyIn::read_from].
+    ///
+    /// Command examples and accepted formats for `COPY` data are shown here:
+    /// https://www.postgresql.org/docs/current/sql-copy.html
+    pub async fn copy_out_to(
+        &mut self,
+        statement: &str,
+        mut writer: impl AsyncWrite + Unpin,
+    ) -> Result<u64> {
+        self.wait_until_ready().await?;
+        self.stream.send(Query(statement)).await?;
+
+        let _: CopyResponse = self
+            .stream
+            .recv_expect(MessageFormat::CopyOutResponse)
+            .await?;
+
+        let mut write_result = Ok(());
+
+        loop {
+            let msg = self.stream.recv().await?;
+
+            match msg.format {
+                MessageFormat::CopyData => {
+                    if write_result.is_ok() {
+                        let data = msg.decode::<CopyData<Bytes>>()?.0;
+                        write_result = writer.write_all(&data).await;
+                    }
+                }
+                MessageFormat::CopyDone => break,
+                _ => {
+                    return Err(err_protocol!(
+                        "unexpected message format during copy out: {:?}",
+                        msg.format
+                    ))
+                }
+            }
+        }
+
+        let cc: CommandComplete = self
+            .stream
+            .recv_expect(MessageFormat::CommandComplete)
+            .await?;
+        self.stream
+            .recv_expect(MessageFormat::ReadyForQuery)
+            .await?;
+
+        write_result?;
+        writer.flush().await?;
+
+        Ok(cc.rows_affected())
+    }
 }
 
 /// Implements methods for directly executing `COPY FROM/TO STDOUT` on a [`PgPool`].
@@ -138,6 +214,7 @@ impl PgPoolCopyExt for Pool<Postgres> {
 pub struct PgCopyIn<C: DerefMut<Target = PgConnection>> {
     conn: Option<C>,
     response: CopyResponse,
+    binary_header_sent: bool,
 }
 
 impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
@@ -156,6 +233,7 @@ impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
         Ok(PgCopyIn {
             conn: Some(conn),
             response,
+            binary_header_sent: false,
         })
     }
 
@@ -258,6 +336,22 @@ impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
         Ok(self)
     }
 
+    /// Write a chunk of `COPY` data, only sending it once enough data is buffered.
+    async fn write_buffered(&mut self, data: &[u8]) -> Result<()> {
+        let conn: &mut PgConnection = self
+            .conn
+            .as_deref_mut()
+            .expect("write_buffered: conn taken");
+
+        conn.stream.write(CopyData(data));
+
+        if conn.stream.write_buffer().get().len() >= FLUSH_THRESHOLD {
+            conn.stream.flush().await?;
+        }
+
+        Ok(())
+    }
+
     /// Signal that the `COPY` process should be aborted and any data received should be discarded.
     ///
     /// The given message can be used for indicating the reason for the abort in the database logs.
@@ -296,6 +390,8 @@ impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
     ///
     /// The number of rows affected is returned.
     pub async fn finish(mut self) -> Result<u64> {
+        self.write_binary_trailer();
+
         let mut conn = self
             .conn
             .take()
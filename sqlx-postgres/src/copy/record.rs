@@ -0,0 +1,575 @@
+use std::fmt::{self, Display};
+use std::io::Write;
+use std::ops::DerefMut;
+
+use futures_core::Stream;
+use futures_util::{pin_mut, StreamExt};
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::connection::PgConnection;
+use crate::error::{Error, Result};
+
+use super::PgCopyIn;
+
+impl<C: DerefMut<Target = PgConnection>> PgCopyIn<C> {
+    /// Serialize `record` as one line of `COPY` data in the CSV format.
+    ///
+    /// The statement must specify `FORMAT csv` and keep the default `DELIMITER`, `QUOTE`,
+    /// `ESCAPE` and `NULL` options. Structs, tuples and sequences produce one field per
+    /// element, in serialization order (declaration order for derived impls), which must
+    /// match the order of the columns of the statement. `None` is written as `NULL`.
+    ///
+    /// Lines are buffered and sent in batches; any lines still buffered are sent by
+    /// [Self::finish].
+    pub async fn send_record<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<&mut Self> {
+        if !self.is_textual() {
+            return Err(err_protocol!(
+                "send_record: COPY does not use a textual format"
+            ));
+        }
+
+        let mut line = Vec::new();
+        write_record(record, &mut line)?;
+
+        self.write_buffered(&line).await?;
+
+        Ok(self)
+    }
+}
+
+impl PgConnection {
+    /// Issue a `COPY ... FROM STDIN (FORMAT csv)` statement and send every record of `records`
+    /// with [PgCopyIn::send_record].
+    ///
+    /// The number of rows affected is returned. If a record cannot be serialized, the `COPY`
+    /// is aborted so that none of the records are copied, and the error is returned.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     id: i32,
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// let users = futures::stream::iter(vec![
+    ///     User { id: 1, name: Some("Alice".into()) },
+    ///     User { id: 2, name: None },
+    /// ]);
+    ///
+    /// let rows = conn
+    ///     .copy_in_records("COPY users (id, name) FROM STDIN (FORMAT csv)", users)
+    ///     .await?;
+    ///
+    /// assert_eq!(rows, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_in_records<T: Serialize>(
+        &mut self,
+        statement: &str,
+        records: impl Stream<Item = T>,
+    ) -> Result<u64> {
+        let mut copy = self.copy_in_raw(statement).await?;
+
+        pin_mut!(records);
+
+        while let Some(record) = records.next().await {
+            if let Err(e) = copy.send_record(&record).await {
+                copy.abort(e.to_string()).await?;
+                return Err(e);
+            }
+        }
+
+        copy.finish().await
+    }
+}
+
+/// Serialize `record` as one line of `COPY` data in the CSV format, with the default options
+/// of `FORMAT csv`.
+///
+/// Structs, tuples and sequences produce one field per element, in serialization order.
+/// Every value is quoted, so an unquoted empty field can represent `NULL`.
+fn write_record<T: Serialize + ?Sized>(record: &T, out: &mut Vec<u8>) -> Result<()> {
+    let mut fields = Vec::new();
+
+    record
+        .serialize(RecordSerializer {
+            fields: &mut fields,
+        })
+        .map_err(Error::encode)?;
+
+    for (i, value) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        }
+
+        if let Some(value) = value {
+            write_quoted(value, out);
+        }
+    }
+
+    out.push(b'\n');
+
+    Ok(())
+}
+
+fn write_quoted(value: &[u8], out: &mut Vec<u8>) {
+    out.push(b'"');
+
+    for &byte in value {
+        if byte == b'"' {
+            out.push(b'"');
+        }
+
+        out.push(byte);
+    }
+
+    out.push(b'"');
+}
+
+#[derive(Debug)]
+struct RecordError(String);
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl ser::Error for RecordError {
+    fn custom<T: Display>(msg: T) -> Self {
+        RecordError(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str) -> RecordError {
+    RecordError(format!("{what} cannot be written as a COPY field"))
+}
+
+/// The textual value of a field (`None` for `NULL`).
+type Field = Option<Vec<u8>>;
+
+/// Serializes the top-level record into a list of fields.
+struct RecordSerializer<'a> {
+    fields: &'a mut Vec<Field>,
+}
+
+/// Collects the fields of a compound record.
+struct FieldsSerializer<'a> {
+    fields: &'a mut Vec<Field>,
+}
+
+impl FieldsSerializer<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        let value = value.serialize(FieldSerializer)?;
+        self.fields.push(value);
+        Ok(())
+    }
+}
+
+macro_rules! record_must_be_compound {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(unsupported("a scalar value"))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for RecordSerializer<'a> {
+    type Ok = ();
+    type Error = RecordError;
+
+    type SerializeSeq = FieldsSerializer<'a>;
+    type SerializeTuple = FieldsSerializer<'a>;
+    type SerializeTupleStruct = FieldsSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), RecordError>;
+    type SerializeMap = Impossible<(), RecordError>;
+    type SerializeStruct = FieldsSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), RecordError>;
+
+    record_must_be_compound! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        Ok(FieldsSerializer {
+            fields: self.fields,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RecordError> {
+        // the order of the fields would not be known to match the columns
+        Err(unsupported("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, RecordError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RecordError> {
+        Err(unsupported("an enum variant"))
+    }
+}
+
+impl ser::SerializeSeq for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RecordError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for FieldsSerializer<'_> {
+    type Ok = ();
+    type Error = RecordError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), RecordError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), RecordError> {
+        Ok(())
+    }
+}
+
+/// Serializes a single field into its unquoted textual representation.
+struct FieldSerializer;
+
+impl FieldSerializer {
+    fn display(value: impl Display) -> Result<Field, RecordError> {
+        let mut text = Vec::new();
+        write!(text, "{value}").expect("BUG: writing to a Vec cannot fail");
+        Ok(Some(text))
+    }
+}
+
+impl ser::Serializer for FieldSerializer {
+    type Ok = Field;
+    type Error = RecordError;
+
+    type SerializeSeq = Impossible<Self::Ok, RecordError>;
+    type SerializeTuple = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, RecordError>;
+    type SerializeMap = Impossible<Self::Ok, RecordError>;
+    type SerializeStruct = Impossible<Self::Ok, RecordError>;
+    type SerializeStructVariant = Impossible<Self::Ok, RecordError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, RecordError> {
+        Self::display(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, RecordError> {
+        Ok(Some(v.as_bytes().to_vec()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, RecordError> {
+        // the hex format of `BYTEA`
+        let mut text = Vec::with_capacity(2 + v.len() * 2);
+        text.extend_from_slice(b"\\x");
+
+        for byte in v {
+            write!(text, "{byte:02x}").expect("BUG: writing to a Vec cannot fail");
+        }
+
+        Ok(Some(text))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, RecordError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, RecordError> {
+        // fieldless enums map naturally onto enum types
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        Err(unsupported("a nested sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, RecordError> {
+        Err(unsupported("a nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, RecordError> {
+        Err(unsupported("a nested tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, RecordError> {
+        Err(unsupported("a nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, RecordError> {
+        Err(unsupported("a nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, RecordError> {
+        Err(unsupported("an enum variant with data"))
+    }
+}
+
+#[cfg(test)]
+fn record_to_string<T: Serialize + ?Sized>(record: &T) -> String {
+    let mut out = Vec::new();
+    write_record(record, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_write_record_tuple() {
+    assert_eq!(
+        record_to_string(&(1, "say \"hi\",\nbye", None::<i32>, "", true, 1.5)),
+        "\"1\",\"say \"\"hi\"\",\nbye\",,\"\",\"true\",\"1.5\"\n"
+    );
+}
+
+#[test]
+fn test_write_record_struct() {
+    #[derive(serde::Serialize)]
+    struct User<'a> {
+        id: i64,
+        #[serde(with = "serde_bytes_compat")]
+        avatar: &'a [u8],
+        name: Option<&'a str>,
+    }
+
+    mod serde_bytes_compat {
+        pub fn serialize<S: serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+    }
+
+    let user = User {
+        id: 7,
+        avatar: &[0xde, 0xad],
+        name: None,
+    };
+
+    assert_eq!(record_to_string(&user), "\"7\",\"\\xdead\",\n");
+
+    let mut out = Vec::new();
+    assert!(write_record(&42, &mut out).is_err());
+}
@@ -0,0 +1,166 @@
+use std::cmp;
+use std::sync::Arc;
+
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::error::Result;
+use crate::executor::{Execute, Executor};
+use crate::logger::QueryLogger;
+use crate::message::{CommandComplete, DataRow, MessageFormat, Query};
+use crate::statement::PgStatementMetadata;
+use crate::{PgArguments, PgConnection, PgRow, PgValueFormat, Postgres};
+
+/// The number of rows fetched at once if [`PgCursor::fetch_size`] isn't called.
+const DEFAULT_FETCH_SIZE: u32 = 1000;
+
+impl PgConnection {
+    /// Execute `query` through a cursor, which fetches the rows from the server in batches.
+    ///
+    /// The server computes the rows as they are fetched, if the plan of the query allows it,
+    /// so this can stream result sets that are too large to be held in memory at once.
+    ///
+    /// A cursor can only be used inside a transaction.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use futures::TryStreamExt;
+    /// use sqlx::{Connection, Row};
+    ///
+    /// let mut tx = conn.begin().await?;
+    ///
+    /// let mut rows = tx
+    ///     .cursor(sqlx::query("SELECT id FROM events WHERE kind = $1").bind("click"))
+    ///     .fetch_size(10_000)
+    ///     .fetch();
+    ///
+    /// while let Some(row) = rows.try_next().await? {
+    ///     let id: i64 = row.try_get(0)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cursor<'q, E: Execute<'q, Postgres>>(&mut self, mut query: E) -> PgCursor<'_, 'q> {
+        PgCursor {
+            conn: self,
+            sql: query.sql(),
+            arguments: query.take_arguments(),
+            fetch_size: DEFAULT_FETCH_SIZE,
+        }
+    }
+}
+
+/// A query that is executed through a server-side cursor, created by
+/// [`PgConnection::cursor`].
+#[must_use = "a cursor does nothing unless `.fetch()` is called"]
+pub struct PgCursor<'c, 'q> {
+    conn: &'c mut PgConnection,
+    sql: &'q str,
+    arguments: Option<PgArguments>,
+    fetch_size: u32,
+}
+
+impl<'c, 'q: 'c> PgCursor<'c, 'q> {
+    /// Set the number of rows that are fetched at once; 1000 by default.
+    ///
+    /// A fetch size of 0 is treated as 1.
+    pub fn fetch_size(mut self, fetch_size: u32) -> Self {
+        self.fetch_size = cmp::max(fetch_size, 1);
+        self
+    }
+
+    /// Declare the cursor and return a stream of its rows.
+    ///
+    /// The cursor is closed once every row was fetched. If the stream is dropped before that,
+    /// the cursor stays open until the end of the transaction.
+    pub fn fetch(self) -> BoxStream<'c, Result<PgRow>> {
+        let conn = self.conn;
+
+        let name = format!("sqlx_cursor_{}", conn.next_cursor_id);
+        conn.next_cursor_id = conn.next_cursor_id.wrapping_add(1);
+
+        // a binary cursor returns the rows in the same format as prepared statements
+        let declare = format!("DECLARE {} BINARY NO SCROLL CURSOR FOR {}", name, self.sql);
+        let fetch = format!("FETCH FORWARD {} FROM {}", self.fetch_size, name);
+        let fetch_size = u64::from(self.fetch_size);
+        let arguments = self.arguments;
+
+        Box::pin(try_stream! {
+            // the statement is specific to this cursor, so it isn't cached
+            conn.run(&declare, arguments, 0, false, None)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            loop {
+                let mut logger = QueryLogger::new(&fetch, conn.log_settings.clone());
+
+                conn.wait_until_ready().await?;
+
+                conn.stream.write(Query(&fetch));
+                conn.pending_ready_for_query_count += 1;
+                conn.stream.flush().await?;
+
+                let mut metadata = Arc::new(PgStatementMetadata::default());
+                let mut fetched = 0;
+
+                loop {
+                    let message = conn.stream.recv().await?;
+
+                    match message.format {
+                        MessageFormat::RowDescription => {
+                            let (columns, column_names) = conn
+                                .handle_row_description(Some(message.decode()?), false)
+                                .await?;
+
+                            metadata = Arc::new(PgStatementMetadata {
+                                column_names: Arc::new(column_names),
+                                columns,
+                                parameters: Vec::default(),
+                            });
+                        }
+
+                        MessageFormat::DataRow => {
+                            logger.increment_rows_returned();
+
+                            let data: DataRow = message.decode()?;
+                            let row = PgRow {
+                                data,
+                                format: PgValueFormat::Binary,
+                                metadata: Arc::clone(&metadata),
+                            };
+
+                            r#yield!(row);
+                        }
+
+                        MessageFormat::CommandComplete => {
+                            let cc: CommandComplete = message.decode()?;
+                            fetched = cc.rows_affected();
+                        }
+
+                        MessageFormat::ReadyForQuery => {
+                            conn.handle_ready_for_query(message)?;
+                            break;
+                        }
+
+                        _ => {
+                            return Err(err_protocol!(
+                                "cursor: unexpected message: {:?}",
+                                message.format
+                            ));
+                        }
+                    }
+                }
+
+                // the last batch is short
+                if fetched < fetch_size {
+                    break;
+                }
+            }
+
+            conn.execute(&*format!("CLOSE {name}")).await?;
+
+            Ok(())
+        })
+    }
+}
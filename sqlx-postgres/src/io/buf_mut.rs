@@ -32,10 +32,13 @@ impl PgBufMutExt for Vec<u8> {
     // writes a statement name by ID
     #[inline]
     fn put_statement_name(&mut self, id: Oid) {
-        // N.B. if you change this don't forget to update it in ../describe.rs
-        self.extend(b"sqlx_s_");
+        // the unnamed statement has an empty name
+        if id.0 != 0 {
+            // N.B. if you change this don't forget to update it in ../describe.rs
+            self.extend(b"sqlx_s_");
 
-        self.extend(itoa::Buffer::new().format(id.0).as_bytes());
+            self.extend(itoa::Buffer::new().format(id.0).as_bytes());
+        }
 
         self.push(0);
     }
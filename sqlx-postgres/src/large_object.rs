@@ -0,0 +1,401 @@
+use std::cmp;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_io::{AsyncRead, AsyncSeek, AsyncWrite};
+use futures_util::future::poll_fn;
+
+use crate::error::{Error, Result};
+use crate::query_scalar::query_scalar;
+use crate::types::Oid;
+use crate::PgConnection;
+
+/// The largest number of bytes that is read or written in a single round trip.
+const MAX_CHUNK: usize = 1 << 20;
+
+// the flags of `lo_open()`, from `libpq/libpq-fs.h`
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+/// The mode in which a [`PgLargeObject`] is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgLargeObjectMode {
+    /// Open the large object for reading only.
+    ///
+    /// The contents are those of the snapshot of the transaction when it was opened, so writes
+    /// made afterwards by other transactions (or this one) are not seen.
+    Read,
+
+    /// Open the large object for writing only.
+    Write,
+
+    /// Open the large object for reading and writing.
+    ReadWrite,
+}
+
+/// An open [large object], which stores binary data that is streamed in chunks instead of being
+/// read or written at once like a `BYTEA` value.
+///
+/// A large object is identified by its [`Oid`], and is read and written through a descriptor that
+/// is only valid inside the transaction that opened it: the methods must be called on a
+/// connection that is in a transaction, which closes the descriptor when it ends.
+///
+/// Reading, writing and seeking is provided by the [`AsyncRead`], [`AsyncWrite`] and
+/// [`AsyncSeek`] traits of `futures-io`. With Tokio, they can be adapted with the `compat`
+/// module of `tokio-util`.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+/// use futures::{AsyncReadExt, AsyncWriteExt};
+/// use sqlx::postgres::{PgLargeObject, PgLargeObjectMode};
+/// use sqlx::Connection;
+///
+/// let mut tx = conn.begin().await?;
+///
+/// let mut object = PgLargeObject::create(&mut tx).await?;
+/// let oid = object.oid();
+/// object.write_all(b"a lot of data").await?;
+/// object.close().await?;
+///
+/// let mut object = PgLargeObject::open(&mut tx, oid, PgLargeObjectMode::Read).await?;
+/// let mut data = Vec::new();
+/// object.read_to_end(&mut data).await?;
+/// object.close().await?;
+///
+/// tx.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [large object]: https://www.postgresql.org/docs/current/largeobjects.html
+pub struct PgLargeObject<'c> {
+    oid: Oid,
+    fd: i32,
+    // `None` while an operation is in progress, which holds the connection
+    conn: Option<&'c mut PgConnection>,
+    pending: Option<(Op, Pending<'c>)>,
+    // the rest of a read whose result was larger than the buffer it is read into
+    unread: Vec<u8>,
+}
+
+type Pending<'c> = BoxFuture<'c, (&'c mut PgConnection, Result<Outcome>)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Read,
+    Write,
+    Seek,
+    Truncate,
+}
+
+enum Outcome {
+    Read(Vec<u8>),
+    Written(usize),
+    Position(u64),
+    Done,
+}
+
+impl<'c> PgLargeObject<'c> {
+    /// Create a new, empty large object with `lo_create()`, and open it for reading and writing.
+    pub async fn create(conn: &'c mut PgConnection) -> Result<PgLargeObject<'c>> {
+        // `0` lets the server pick the OID
+        let oid: Oid = query_scalar("SELECT lo_create(0)")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Self::open(conn, oid, PgLargeObjectMode::ReadWrite).await
+    }
+
+    /// Open an existing large object with `lo_open()`.
+    pub async fn open(
+        conn: &'c mut PgConnection,
+        oid: Oid,
+        mode: PgLargeObjectMode,
+    ) -> Result<PgLargeObject<'c>> {
+        let flags = match mode {
+            PgLargeObjectMode::Read => INV_READ,
+            PgLargeObjectMode::Write => INV_WRITE,
+            PgLargeObjectMode::ReadWrite => INV_READ | INV_WRITE,
+        };
+
+        let fd: i32 = query_scalar("SELECT lo_open($1, $2)")
+            .bind(oid)
+            .bind(flags)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(PgLargeObject {
+            oid,
+            fd,
+            conn: Some(conn),
+            pending: None,
+            unread: Vec::new(),
+        })
+    }
+
+    /// Delete the large object `oid` with `lo_unlink()`.
+    pub async fn unlink(conn: &mut PgConnection, oid: Oid) -> Result<()> {
+        let _: i32 = query_scalar("SELECT lo_unlink($1)")
+            .bind(oid)
+            .fetch_one(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the OID of the large object.
+    pub fn oid(&self) -> Oid {
+        self.oid
+    }
+
+    /// Truncate or extend (with zeroes) the large object to `len` bytes, with `lo_truncate64()`.
+    ///
+    /// The position of the descriptor is not changed.
+    pub async fn truncate(&mut self, len: u64) -> Result<()> {
+        let len = i64::try_from(len).map_err(|_| err_protocol!("length {} is too large", len))?;
+
+        poll_fn(|cx| {
+            self.poll_op(cx, Op::Truncate, move |conn, fd| {
+                Box::pin(async move {
+                    let result = query_scalar::<_, i32>("SELECT lo_truncate64($1, $2)")
+                        .bind(fd)
+                        .bind(len)
+                        .fetch_one(&mut *conn)
+                        .await;
+
+                    (conn, result.map(|_| Outcome::Done))
+                })
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Close the descriptor with `lo_close()`, and return the connection.
+    ///
+    /// Otherwise, the descriptor is closed when the transaction ends.
+    pub async fn close(mut self) -> Result<&'c mut PgConnection> {
+        let conn = self.finish_pending().await?;
+
+        let _: i32 = query_scalar("SELECT lo_close($1)")
+            .bind(self.fd)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(conn)
+    }
+
+    /// Wait for the operation in progress, and take the connection.
+    async fn finish_pending(&mut self) -> Result<&'c mut PgConnection> {
+        if let Some((_, pending)) = self.pending.take() {
+            let (conn, result) = pending.await;
+            self.conn = Some(conn);
+            result?;
+        }
+
+        Ok(self.conn.take().expect("BUG: PgLargeObject.conn taken"))
+    }
+
+    /// Poll the operation `op`, which is started by `start` unless it is already in progress.
+    ///
+    /// An operation of another kind that is in progress is finished first.
+    fn poll_op(
+        &mut self,
+        cx: &mut Context<'_>,
+        op: Op,
+        start: impl FnOnce(&'c mut PgConnection, i32) -> Pending<'c> + Send + 'c,
+    ) -> Poll<Result<Outcome>> {
+        let mut start = Some(start);
+
+        loop {
+            if let Some((pending_op, pending)) = &mut self.pending {
+                let pending_op = *pending_op;
+                let (conn, result) = ready!(pending.as_mut().poll(cx));
+
+                self.pending = None;
+                self.conn = Some(conn);
+
+                if pending_op == op {
+                    return Poll::Ready(result);
+                }
+
+                result?;
+            }
+
+            let Some(start) = start.take() else {
+                unreachable!("BUG: PgLargeObject operation started twice");
+            };
+
+            let conn = self.conn.take().expect("BUG: PgLargeObject.conn taken");
+            let fd = self.fd;
+
+            // the server is ahead of the reader by the bytes that were not returned yet
+            let unread = std::mem::take(&mut self.unread).len();
+
+            let pending = if unread > 0 {
+                Box::pin(async move {
+                    if let Err(error) = seek(conn, fd, SeekFrom::Current(-(unread as i64))).await {
+                        return (conn, Err(error));
+                    }
+
+                    start(conn, fd).await
+                })
+            } else {
+                start(conn, fd)
+            };
+
+            self.pending = Some((op, pending));
+        }
+    }
+}
+
+impl AsyncRead for PgLargeObject<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if !this.unread.is_empty() || buf.is_empty() {
+            let len = cmp::min(buf.len(), this.unread.len());
+            buf[..len].copy_from_slice(&this.unread[..len]);
+            this.unread.drain(..len);
+
+            return Poll::Ready(Ok(len));
+        }
+
+        let len = cmp::min(buf.len(), MAX_CHUNK) as i32;
+
+        let outcome = ready!(this.poll_op(cx, Op::Read, move |conn, fd| {
+            Box::pin(async move {
+                let result = query_scalar::<_, Vec<u8>>("SELECT loread($1, $2)")
+                    .bind(fd)
+                    .bind(len)
+                    .fetch_one(&mut *conn)
+                    .await;
+
+                (conn, result.map(Outcome::Read))
+            })
+        }))
+        .map_err(into_io_error)?;
+
+        let Outcome::Read(data) = outcome else {
+            unreachable!()
+        };
+
+        // the read may have been started with a larger buffer
+        let len = cmp::min(buf.len(), data.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        this.unread = data[len..].to_vec();
+
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncWrite for PgLargeObject<'_> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let data = buf[..cmp::min(buf.len(), MAX_CHUNK)].to_vec();
+
+        let outcome = ready!(self.get_mut().poll_op(cx, Op::Write, move |conn, fd| {
+            Box::pin(async move {
+                let result = query_scalar::<_, i32>("SELECT lowrite($1, $2)")
+                    .bind(fd)
+                    .bind(data)
+                    .fetch_one(&mut *conn)
+                    .await;
+
+                (
+                    conn,
+                    result.map(|written| Outcome::Written(written as usize)),
+                )
+            })
+        }))
+        .map_err(into_io_error)?;
+
+        let Outcome::Written(written) = outcome else {
+            unreachable!()
+        };
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        // writes are sent right away, so only a write in progress has to be finished
+        if let Some((Op::Write, pending)) = &mut this.pending {
+            let (conn, result) = ready!(pending.as_mut().poll(cx));
+
+            this.pending = None;
+            this.conn = Some(conn);
+
+            result.map_err(into_io_error)?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // the descriptor is closed by `close()` or when the transaction ends
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncSeek for PgLargeObject<'_> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let outcome = ready!(self.get_mut().poll_op(cx, Op::Seek, move |conn, fd| {
+            Box::pin(async move {
+                let result = seek(conn, fd, pos).await;
+
+                (conn, result.map(Outcome::Position))
+            })
+        }))
+        .map_err(into_io_error)?;
+
+        let Outcome::Position(position) = outcome else {
+            unreachable!()
+        };
+
+        Poll::Ready(Ok(position))
+    }
+}
+
+async fn seek(conn: &mut PgConnection, fd: i32, pos: SeekFrom) -> Result<u64> {
+    // the values of `whence` are those of `lseek()`
+    let (offset, whence) = match pos {
+        SeekFrom::Start(offset) => (
+            i64::try_from(offset).map_err(|_| err_protocol!("offset {} is too large", offset))?,
+            0,
+        ),
+        SeekFrom::Current(offset) => (offset, 1),
+        SeekFrom::End(offset) => (offset, 2),
+    };
+
+    let position: i64 = query_scalar("SELECT lo_lseek64($1, $2, $3)")
+        .bind(fd)
+        .bind(offset)
+        .bind(whence)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(position as u64)
+}
+
+fn into_io_error(error: Error) -> io::Error {
+    match error {
+        Error::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::Other, error),
+    }
+}
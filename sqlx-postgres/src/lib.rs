@@ -10,13 +10,19 @@ mod arguments;
 mod column;
 mod connection;
 mod copy;
+mod cursor;
 mod database;
 mod error;
 mod io;
+mod large_object;
 mod listener;
 mod message;
+mod notice;
 mod options;
+mod pipeline;
+mod query_ext;
 mod query_result;
+mod replication;
 mod row;
 mod statement;
 mod transaction;
@@ -35,20 +41,35 @@ mod testing;
 
 pub(crate) use sqlx_core::driver_prelude::*;
 
-pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
+pub use advisory_lock::{
+    PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey, PgAdvisoryXactLockGuard,
+};
 pub use arguments::{PgArgumentBuffer, PgArguments};
 pub use column::PgColumn;
-pub use connection::PgConnection;
-pub use copy::PgCopyIn;
+pub use connection::{PgCancellationToken, PgConnection, PgParameterStatus};
+pub use copy::{
+    PgCopyBoth, PgCopyIn, PgCopyInRow, PgCopyOutRow, PgCopyRowDecoder, PgCopyRowEncoder,
+};
+pub use cursor::PgCursor;
 pub use database::Postgres;
 pub use error::{PgDatabaseError, PgErrorPosition};
-pub use listener::{PgListener, PgNotification};
+pub use large_object::{PgLargeObject, PgLargeObjectMode};
+pub use listener::{PgListener, PgListenerEvent, PgNotification, PgTypedListener};
 pub use message::PgSeverity;
-pub use options::{PgConnectOptions, PgSslMode};
+pub use notice::PgNotice;
+pub use options::{PgChannelBinding, PgConnectOptions, PgSslMode, PgTargetSessionAttrs};
+pub use pipeline::{PgPipeline, PgPipelineResult};
+pub use query_ext::PgQueryExt;
 pub use query_result::PgQueryResult;
+pub use replication::{
+    PgOutputBegin, PgOutputColumn, PgOutputCommit, PgOutputDelete, PgOutputInsert, PgOutputMessage,
+    PgOutputOrigin, PgOutputRelation, PgOutputTruncate, PgOutputTuple, PgOutputType,
+    PgOutputUpdate, PgPrimaryKeepalive, PgReplicaIdentity, PgReplicationConnection,
+    PgReplicationMessage, PgReplicationSlot, PgReplicationStream, PgReplicationSystem, PgXLogData,
+};
 pub use row::PgRow;
 pub use statement::PgStatement;
-pub use transaction::PgTransactionManager;
+pub use transaction::{PgPreparedTransaction, PgTransactionManager};
 pub use type_info::{PgTypeInfo, PgTypeKind};
 pub use types::PgHasArrayType;
 pub use value::{PgValue, PgValueFormat, PgValueRef};
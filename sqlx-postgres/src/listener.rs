@@ -1,11 +1,15 @@
 use std::fmt::{self, Debug};
 use std::io;
+use std::marker::PhantomData;
+use std::mem;
 use std::str::from_utf8;
 
 use futures_channel::mpsc;
 use futures_core::future::BoxFuture;
 use futures_core::stream::{BoxStream, Stream};
 use futures_util::{FutureExt, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use sqlx_core::Either;
 
 use crate::describe::Describe;
@@ -22,6 +26,9 @@ use crate::{PgConnection, PgQueryResult, PgRow, PgStatement, PgTypeInfo, Postgre
 /// connection being used ever dies, this listener will detect that event, create a
 /// new connection, will re-subscribe to all of the originally specified channels, and will resume
 /// operations as normal.
+///
+/// Notifications sent while the connection was lost are missed, use
+/// [`recv_event`](Self::recv_event) to know when that may have happened.
 pub struct PgListener {
     pool: Pool<Postgres>,
     connection: Option<PoolConnection<Postgres>>,
@@ -29,11 +36,60 @@ pub struct PgListener {
     buffer_tx: Option<mpsc::UnboundedSender<Notification>>,
     channels: Vec<String>,
     ignore_close_event: bool,
+    resubscribed: bool,
 }
 
 /// An asynchronous notification from Postgres.
 pub struct PgNotification(Notification);
 
+/// A [`PgListener`] of notifications with JSON payloads, which are deserialized into `T`.
+///
+/// The payloads can be sent with [`PgConnection::notify_as()`].
+///
+/// ```rust,no_run
+/// # use sqlx_core::postgres::{PgConnection, PgListener, PgTypedListener};
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::connection::Connection;
+/// #
+/// # #[cfg(feature = "_rt")]
+/// # sqlx::__rt::test_block_on(async move {
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct OrderPlaced {
+///     order_id: i64,
+/// }
+///
+/// let mut listener = PgTypedListener::<OrderPlaced>::new(PgListener::connect("postgres:// ...").await?);
+/// listener.listen("orders").await?;
+///
+/// let mut conn = PgConnection::connect("postgres:// ...").await?;
+/// conn.notify_as("orders", &OrderPlaced { order_id: 1 }).await?;
+///
+/// let order: OrderPlaced = listener.recv().await?;
+/// # Result::<(), Error>::Ok(())
+/// # }).unwrap();
+/// ```
+pub struct PgTypedListener<T> {
+    listener: PgListener,
+    payload: PhantomData<fn() -> T>,
+}
+
+/// An event of a [`PgListener`], from [`PgListener::recv_event()`].
+#[derive(Debug)]
+pub enum PgListenerEvent {
+    /// A notification from one of the channels listened to.
+    Notification(PgNotification),
+
+    /// The connection was lost, and notifications sent from now until the listener is
+    /// [`Resubscribed`](Self::Resubscribed) are missed.
+    ConnectionLost,
+
+    /// The listener reconnected, and listens to all of its channels again.
+    ///
+    /// Notifications may have been missed since the connection was lost, so this is the time to
+    /// reconcile with the state the notifications are about.
+    Resubscribed,
+}
+
 impl PgListener {
     pub async fn connect(url: &str) -> Result<Self, Error> {
         // Create a pool of 1 without timeouts (as they don't apply here)
@@ -52,6 +108,11 @@ impl PgListener {
         Ok(this)
     }
 
+    /// Create a listener which acquires its connections from `pool`, including the ones it
+    /// reconnects with.
+    ///
+    /// The connection is held by the listener until it is dropped, or until the connection is
+    /// lost.
     pub async fn connect_with(pool: &Pool<Postgres>) -> Result<Self, Error> {
         // Pull out an initial connection
         let mut connection = pool.acquire().await?;
@@ -67,6 +128,7 @@ impl PgListener {
             buffer_tx: None,
             channels: Vec::new(),
             ignore_close_event: false,
+            resubscribed: false,
         })
     }
 
@@ -162,6 +224,7 @@ impl PgListener {
                 .await?;
 
             self.connection = Some(connection);
+            self.resubscribed = true;
         }
 
         Ok(())
@@ -262,7 +325,14 @@ impl PgListener {
 
                 // The connection is dead, ensure that it is dropped,
                 // update self state, and loop to try again.
-                Err(Error::Io(err)) if err.kind() == io::ErrorKind::ConnectionAborted => {
+                Err(Error::Io(err))
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::ConnectionAborted
+                            | io::ErrorKind::ConnectionReset
+                            | io::ErrorKind::UnexpectedEof
+                    ) =>
+                {
                     self.buffer_tx = self.connection().await?.stream.notifications.take();
                     self.connection = None;
 
@@ -293,6 +363,55 @@ impl PgListener {
         }
     }
 
+    /// Receives the next event of this listener: a notification, or a change of its connection.
+    ///
+    /// Like [`recv`](Self::recv), this reconnects if the connection to PostgreSQL is lost, but
+    /// returns [`PgListenerEvent::ConnectionLost`] when that happens, and
+    /// [`PgListenerEvent::Resubscribed`] once it listens to all of its channels again.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::postgres::{PgListener, PgListenerEvent};
+    /// # use sqlx_core::error::Error;
+    /// #
+    /// # #[cfg(feature = "_rt")]
+    /// # sqlx::__rt::test_block_on(async move {
+    /// # let mut listener = PgListener::connect("postgres:// ...").await?;
+    /// loop {
+    ///     match listener.recv_event().await? {
+    ///         PgListenerEvent::Notification(notification) => {
+    ///             // handle notification
+    ///         }
+    ///
+    ///         PgListenerEvent::ConnectionLost => {}
+    ///
+    ///         PgListenerEvent::Resubscribed => {
+    ///             // notifications may have been missed, reload the state they are about
+    ///         }
+    ///     }
+    /// }
+    /// # Result::<(), Error>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn recv_event(&mut self) -> Result<PgListenerEvent, Error> {
+        // notifications received before the connection was lost come first
+        if let Ok(Some(notification)) = self.buffer_rx.try_next() {
+            return Ok(PgListenerEvent::Notification(PgNotification(notification)));
+        }
+
+        self.connect_if_needed().await?;
+
+        if mem::take(&mut self.resubscribed) {
+            return Ok(PgListenerEvent::Resubscribed);
+        }
+
+        Ok(match self.try_recv().await? {
+            Some(notification) => PgListenerEvent::Notification(notification),
+            None => PgListenerEvent::ConnectionLost,
+        })
+    }
+
     /// Consume this listener, returning a `Stream` of notifications.
     ///
     /// The backing connection will be automatically reconnected should it be lost.
@@ -306,6 +425,19 @@ impl PgListener {
             }
         })
     }
+
+    /// Consume this listener, returning a `Stream` of its events.
+    ///
+    /// See [`recv_event`](PgListener::recv_event) for the events.
+    pub fn into_event_stream(
+        mut self,
+    ) -> impl Stream<Item = Result<PgListenerEvent, Error>> + Unpin {
+        Box::pin(try_stream! {
+            loop {
+                r#yield!(self.recv_event().await?);
+            }
+        })
+    }
 }
 
 impl Drop for PgListener {
@@ -386,6 +518,150 @@ impl<'c> Executor<'c> for &'c mut PgListener {
     }
 }
 
+impl<T: DeserializeOwned> PgTypedListener<T> {
+    /// Receive the notifications of `listener` as `T`.
+    pub fn new(listener: PgListener) -> Self {
+        Self {
+            listener,
+            payload: PhantomData,
+        }
+    }
+
+    /// Starts listening for notifications on a channel.
+    ///
+    /// See [`PgListener::listen()`].
+    pub async fn listen(&mut self, channel: &str) -> Result<(), Error> {
+        self.listener.listen(channel).await
+    }
+
+    /// Starts listening for notifications on all channels.
+    ///
+    /// See [`PgListener::listen_all()`].
+    pub async fn listen_all(
+        &mut self,
+        channels: impl IntoIterator<Item = &str>,
+    ) -> Result<(), Error> {
+        self.listener.listen_all(channels).await
+    }
+
+    /// Stops listening for notifications on a channel.
+    ///
+    /// See [`PgListener::unlisten()`].
+    pub async fn unlisten(&mut self, channel: &str) -> Result<(), Error> {
+        self.listener.unlisten(channel).await
+    }
+
+    /// Stops listening for notifications on all channels.
+    ///
+    /// See [`PgListener::unlisten_all()`].
+    pub async fn unlisten_all(&mut self) -> Result<(), Error> {
+        self.listener.unlisten_all().await
+    }
+
+    /// Receives the payload of the next notification from any of the subscribed channels.
+    ///
+    /// Returns [`Error::Decode`] if the payload can't be deserialized into `T`. The next call
+    /// receives the next notification.
+    ///
+    /// See [`PgListener::recv()`].
+    pub async fn recv(&mut self) -> Result<T, Error> {
+        decode_payload(&self.listener.recv().await?)
+    }
+
+    /// Receives the payload of the next notification from any of the subscribed channels, or
+    /// `None` if the connection to PostgreSQL is lost.
+    ///
+    /// See [`PgListener::try_recv()`].
+    pub async fn try_recv(&mut self) -> Result<Option<T>, Error> {
+        match self.listener.try_recv().await? {
+            Some(notification) => decode_payload(&notification).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Consume this listener, returning a `Stream` of payloads.
+    ///
+    /// See [`PgListener::into_stream()`].
+    pub fn into_stream(mut self) -> impl Stream<Item = Result<T, Error>> + Unpin
+    where
+        T: Send + 'static,
+    {
+        Box::pin(try_stream! {
+            loop {
+                r#yield!(self.recv().await?);
+            }
+        })
+    }
+
+    /// Returns the listener of the notifications.
+    pub fn into_inner(self) -> PgListener {
+        self.listener
+    }
+}
+
+impl<T: DeserializeOwned> From<PgListener> for PgTypedListener<T> {
+    fn from(listener: PgListener) -> Self {
+        Self::new(listener)
+    }
+}
+
+impl<T> Debug for PgTypedListener<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgTypedListener").finish()
+    }
+}
+
+fn decode_payload<T: DeserializeOwned>(notification: &PgNotification) -> Result<T, Error> {
+    serde_json::from_str(notification.payload()).map_err(|e| {
+        Error::Decode(
+            format!(
+                "invalid payload of a notification on channel {:?}: {e}",
+                notification.channel()
+            )
+            .into(),
+        )
+    })
+}
+
+/// The payload of a notification must be shorter than this many bytes.
+const MAX_PAYLOAD_LEN: usize = 8000;
+
+impl PgConnection {
+    /// Send a notification on `channel`, with `payload` serialized as JSON.
+    ///
+    /// Notifications sent in a transaction are delivered when it commits.
+    /// They can be received with [`PgTypedListener`].
+    ///
+    /// Returns [`Error::Encode`] if the serialized payload is 8000 bytes or longer, which is the
+    /// limit of Postgres. Larger payloads can be stored in a table instead, with the
+    /// notification sending their key.
+    pub async fn notify_as<T>(&mut self, channel: &str, payload: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let payload = serde_json::to_string(payload).map_err(|e| Error::Encode(e.into()))?;
+
+        if payload.len() >= MAX_PAYLOAD_LEN {
+            return Err(Error::Encode(
+                format!(
+                    "the payload of a notification on channel {channel:?} is {} bytes, \
+                     but must be shorter than {MAX_PAYLOAD_LEN} bytes",
+                    payload.len()
+                )
+                .into(),
+            ));
+        }
+
+        crate::query::query("SELECT pg_notify($1, $2)")
+            .bind(channel)
+            .bind(payload)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+}
+
 impl PgNotification {
     /// The process ID of the notifying backend process.
     #[inline]
@@ -424,7 +700,7 @@ impl Debug for PgNotification {
     }
 }
 
-fn ident(mut name: &str) -> String {
+pub(crate) fn ident(mut name: &str) -> String {
     // If the input string contains a NUL byte, we should truncate the
     // identifier.
     if let Some(index) = name.find('\0') {
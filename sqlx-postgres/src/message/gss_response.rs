@@ -0,0 +1,28 @@
+use crate::io::Encode;
+use crate::io::PgBufMutExt;
+
+/// A GSSAPI or SSPI token, sent in response to [`Authentication::Gss`] or
+/// [`Authentication::GssContinue`].
+///
+/// [`Authentication::Gss`]: super::Authentication::Gss
+/// [`Authentication::GssContinue`]: super::Authentication::GssContinue
+pub struct GssResponse<'a>(pub &'a [u8]);
+
+impl Encode<'_> for GssResponse<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.push(b'p');
+        buf.put_length_prefixed(|buf| {
+            buf.extend(self.0);
+        });
+    }
+}
+
+#[test]
+fn test_encode_gss_response() {
+    const EXPECTED: &[u8] = b"p\0\0\0\x08\x60\x82\x01\x02";
+
+    let mut buf = Vec::new();
+    GssResponse(b"\x60\x82\x01\x02").encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
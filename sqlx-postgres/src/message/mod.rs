@@ -6,6 +6,7 @@ use crate::io::Decode;
 mod authentication;
 mod backend_key_data;
 mod bind;
+mod cancel_request;
 mod close;
 mod command_complete;
 mod copy;
@@ -13,6 +14,8 @@ mod data_row;
 mod describe;
 mod execute;
 mod flush;
+#[cfg(feature = "gssapi")]
+mod gss_response;
 mod notification;
 mod parameter_description;
 mod parameter_status;
@@ -31,6 +34,7 @@ mod terminate;
 pub use authentication::{Authentication, AuthenticationSasl};
 pub use backend_key_data::BackendKeyData;
 pub use bind::Bind;
+pub use cancel_request::CancelRequest;
 pub use close::Close;
 pub use command_complete::CommandComplete;
 pub use copy::{CopyData, CopyDone, CopyFail, CopyResponse};
@@ -38,6 +42,8 @@ pub use data_row::DataRow;
 pub use describe::Describe;
 pub use execute::Execute;
 pub use flush::Flush;
+#[cfg(feature = "gssapi")]
+pub use gss_response::GssResponse;
 pub use notification::Notification;
 pub use parameter_description::ParameterDescription;
 pub use parameter_status::ParameterStatus;
@@ -61,6 +67,7 @@ pub enum MessageFormat {
     BindComplete,
     CloseComplete,
     CommandComplete,
+    CopyBothResponse,
     CopyData,
     CopyDone,
     CopyInResponse,
@@ -104,6 +111,7 @@ impl MessageFormat {
             b'2' => MessageFormat::BindComplete,
             b'3' => MessageFormat::CloseComplete,
             b'C' => MessageFormat::CommandComplete,
+            b'W' => MessageFormat::CopyBothResponse,
             b'd' => MessageFormat::CopyData,
             b'c' => MessageFormat::CopyDone,
             b'G' => MessageFormat::CopyInResponse,
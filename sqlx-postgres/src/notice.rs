@@ -0,0 +1,72 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::message::{Notice, PgSeverity};
+
+/// A notice or warning sent by the server, e.g. with `RAISE NOTICE` in PL/pgSQL.
+///
+/// Notices are logged by default, or passed to the handler set with
+/// [`PgConnectOptions::notice_handler`][crate::PgConnectOptions::notice_handler].
+#[derive(Debug)]
+pub struct PgNotice(Notice);
+
+// The fields are those of errors:
+// https://www.postgresql.org/docs/current/protocol-error-fields.html
+
+impl PgNotice {
+    #[inline]
+    pub fn severity(&self) -> PgSeverity {
+        self.0.severity()
+    }
+
+    /// The [SQLSTATE](https://www.postgresql.org/docs/current/errcodes-appendix.html) code for
+    /// this notice, e.g. `00000` for `RAISE NOTICE`.
+    #[inline]
+    pub fn code(&self) -> &str {
+        self.0.code()
+    }
+
+    /// The primary human-readable message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        self.0.message()
+    }
+
+    /// An optional secondary message carrying more detail.
+    #[inline]
+    pub fn detail(&self) -> Option<&str> {
+        self.0.get(b'D')
+    }
+
+    /// An optional suggestion of what to do.
+    #[inline]
+    pub fn hint(&self) -> Option<&str> {
+        self.0.get(b'H')
+    }
+
+    /// An indication of the context in which the notice was raised, like a call stack traceback
+    /// of active procedural language functions, one entry per line, most recent first.
+    pub fn r#where(&self) -> Option<&str> {
+        self.0.get(b'W')
+    }
+}
+
+/// The handler set with [`PgConnectOptions::notice_handler`][crate::PgConnectOptions::notice_handler].
+#[derive(Clone)]
+pub(crate) struct NoticeHandler(Arc<dyn Fn(PgNotice) + Send + Sync + 'static>);
+
+impl NoticeHandler {
+    pub(crate) fn new(handler: impl Fn(PgNotice) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+
+    pub(crate) fn handle(&self, notice: Notice) {
+        (self.0)(PgNotice(notice));
+    }
+}
+
+impl Debug for NoticeHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("NoticeHandler")
+    }
+}
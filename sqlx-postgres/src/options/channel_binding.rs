@@ -0,0 +1,42 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for controlling the use of channel binding when authenticating with SCRAM.
+///
+/// Channel binding ties the authentication to the TLS connection, which protects against a
+/// server that is impersonated with a stolen or mis-issued certificate. It is only possible
+/// over an SSL connection, with the `SCRAM-SHA-256` authentication method.
+///
+/// It is used by the [`channel_binding`](super::PgConnectOptions::channel_binding) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgChannelBinding {
+    /// Never use channel binding.
+    Disable,
+
+    /// Use channel binding if the server supports it.
+    #[default]
+    Prefer,
+
+    /// Only connect if the server authenticates with channel binding.
+    ///
+    /// This rejects every other authentication method, including `trust` and passwords.
+    Require,
+}
+
+impl FromStr for PgChannelBinding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "disable" => PgChannelBinding::Disable,
+            "prefer" => PgChannelBinding::Prefer,
+            "require" => PgChannelBinding::Require,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {s:?} for `channel_binding`").into(),
+                ));
+            }
+        })
+    }
+}
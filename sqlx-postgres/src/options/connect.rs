@@ -17,7 +17,7 @@ impl ConnectOptions for PgConnectOptions {
     where
         Self::Connection: Sized,
     {
-        Box::pin(PgConnection::establish(self))
+        Box::pin(PgConnection::connect_to_target(self))
     }
 
     fn log_statements(mut self, level: LevelFilter) -> Self {
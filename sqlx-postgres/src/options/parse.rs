@@ -2,12 +2,21 @@ use crate::error::Error;
 use crate::PgConnectOptions;
 use sqlx_core::percent_encoding::percent_decode_str;
 use sqlx_core::Url;
+use std::borrow::Cow;
 use std::net::IpAddr;
 use std::str::FromStr;
 
 impl PgConnectOptions {
     pub(crate) fn parse_from_url(url: &Url) -> Result<Self, Error> {
+        Self::parse_from_url_with_fallback_hosts(url, Vec::new())
+    }
+
+    fn parse_from_url_with_fallback_hosts(
+        url: &Url,
+        fallback_hosts: Vec<(String, Option<u16>)>,
+    ) -> Result<Self, Error> {
         let mut options = Self::new_without_pgpass();
+        options.fallback_hosts = fallback_hosts;
 
         if let Some(host) = url.host_str() {
             let host_decoded = percent_decode_str(host);
@@ -49,6 +58,22 @@ impl PgConnectOptions {
                     options = options.ssl_mode(value.parse().map_err(Error::config)?);
                 }
 
+                "channel_binding" | "channel-binding" => {
+                    options = options.channel_binding(value.parse().map_err(Error::config)?);
+                }
+
+                "krbsrvname" => options = options.kerberos_service_name(&value),
+
+                "gssdelegation" => match &*value {
+                    "0" => options = options.gss_delegation(false),
+                    "1" => options = options.gss_delegation(true),
+                    _ => {
+                        return Err(Error::Configuration(
+                            format!("unknown value {value:?} for `gssdelegation`").into(),
+                        ))
+                    }
+                },
+
                 "sslrootcert" | "ssl-root-cert" | "ssl-ca" => {
                     options = options.ssl_root_cert(&*value);
                 }
@@ -62,12 +87,22 @@ impl PgConnectOptions {
                         options.statement_cache_capacity(value.parse().map_err(Error::config)?);
                 }
 
+                "strict-domains" => {
+                    options = options.strict_domains(value.parse().map_err(Error::config)?);
+                }
+
                 "host" => {
-                    if value.starts_with("/") {
-                        options = options.socket(&*value);
+                    let mut hosts = value.split(',');
+                    let host = hosts.next().unwrap_or_default();
+
+                    if host.starts_with('/') {
+                        options = options.socket(host);
                     } else {
-                        options = options.host(&*value);
+                        options = options.host(host);
                     }
+
+                    // like libpq, a list of hosts replaces the hosts of the URL
+                    options.fallback_hosts = hosts.map(|host| (host.to_owned(), None)).collect();
                 }
 
                 "hostaddr" => {
@@ -75,7 +110,29 @@ impl PgConnectOptions {
                     options = options.host(&*value)
                 }
 
-                "port" => options = options.port(value.parse().map_err(Error::config)?),
+                "port" => {
+                    let mut ports = value.split(',');
+                    let port = ports.next().unwrap_or_default();
+                    options = options.port(port.parse().map_err(Error::config)?);
+
+                    for (host, port) in options.fallback_hosts.iter_mut().zip(ports) {
+                        host.1 = Some(port.parse().map_err(Error::config)?);
+                    }
+                }
+
+                "target_session_attrs" => {
+                    options = options.target_session_attrs(value.parse().map_err(Error::config)?);
+                }
+
+                "load_balance_hosts" => match &*value {
+                    "disable" => options = options.load_balance_hosts(false),
+                    "random" => options = options.load_balance_hosts(true),
+                    _ => {
+                        return Err(Error::Configuration(
+                            format!("unknown value {value:?} for `load_balance_hosts`").into(),
+                        ))
+                    }
+                },
 
                 "dbname" => options = options.database(&*value),
 
@@ -114,12 +171,74 @@ impl FromStr for PgConnectOptions {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Error> {
+        // the `url` crate doesn't accept a list of hosts, so only the first one is left in it
+        let (s, fallback_hosts) = split_fallback_hosts(s)?;
         let url: Url = s.parse().map_err(Error::config)?;
 
-        Self::parse_from_url(&url)
+        Self::parse_from_url_with_fallback_hosts(&url, fallback_hosts)
     }
 }
 
+/// A host and its port, from a URL with multiple hosts.
+type FallbackHost = (String, Option<u16>);
+
+/// Split the comma-separated hosts of a URL like `postgres://host1:5432,host2:5433/db` into a URL
+/// with only the first host, and the other hosts with their port.
+fn split_fallback_hosts(url: &str) -> Result<(Cow<'_, str>, Vec<FallbackHost>), Error> {
+    let Some(scheme_end) = url.find("://") else {
+        return Ok((url.into(), Vec::new()));
+    };
+
+    let authority_start = scheme_end + 3;
+    let authority_end = url[authority_start..]
+        .find(|c| matches!(c, '/' | '?' | '#'))
+        .map_or(url.len(), |end| authority_start + end);
+
+    // the hosts come after the user info
+    let hosts_start = url[authority_start..authority_end]
+        .rfind('@')
+        .map_or(authority_start, |at| authority_start + at + 1);
+
+    let Some((first, rest)) = url[hosts_start..authority_end].split_once(',') else {
+        return Ok((url.into(), Vec::new()));
+    };
+
+    let fallback_hosts = rest
+        .split(',')
+        .map(parse_host_and_port)
+        .collect::<Result<_, _>>()?;
+
+    let url = format!("{}{}{}", &url[..hosts_start], first, &url[authority_end..]);
+
+    Ok((url.into(), fallback_hosts))
+}
+
+/// Parse `host`, `host:port` or `[ipv6]:port`, keeping the brackets like the `url` crate does.
+fn parse_host_and_port(s: &str) -> Result<FallbackHost, Error> {
+    let (host, port) = if s.starts_with('[') {
+        match s.split_once("]:") {
+            Some((host, port)) => (&s[..=host.len()], Some(port)),
+            None => (s, None),
+        }
+    } else {
+        match s.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (s, None),
+        }
+    };
+
+    let host = percent_decode_str(host)
+        .decode_utf8()
+        .map_err(Error::config)?
+        .into_owned();
+
+    let port = port
+        .map(|port| port.parse().map_err(Error::config))
+        .transpose()?;
+
+    Ok((host, port))
+}
+
 #[test]
 fn it_parses_socket_correctly_from_parameter() {
     let url = "postgres:///?host=/var/run/postgres/";
@@ -155,6 +274,17 @@ fn it_parses_port_correctly_from_parameter() {
     assert_eq!(1234, opts.port);
 }
 
+#[test]
+fn it_parses_channel_binding_correctly_from_parameter() {
+    let url = "postgres:///?channel_binding=require";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!(crate::PgChannelBinding::Require, opts.channel_binding);
+
+    let url = "postgres:///?channel_binding=always";
+    assert!(PgConnectOptions::from_str(url).is_err());
+}
+
 #[test]
 fn it_parses_dbname_correctly_from_parameter() {
     let url = "postgres:///?dbname=some_db";
@@ -242,3 +372,57 @@ fn it_parses_sqlx_options_correctly() {
         opts.options
     );
 }
+
+#[test]
+fn it_parses_gssapi_parameters_correctly() {
+    let url = "postgres:///?krbsrvname=postgresql&gssdelegation=1";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!("postgresql", opts.krb_srv_name);
+    assert!(opts.gss_delegation);
+
+    let url = "postgres:///?gssdelegation=yes";
+    assert!(PgConnectOptions::from_str(url).is_err());
+}
+
+#[test]
+fn it_parses_strict_domains_correctly() {
+    let url = "postgres:///?strict-domains=true";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert!(opts.strict_domains);
+
+    let url = "postgres:///?strict-domains=1";
+    assert!(PgConnectOptions::from_str(url).is_err());
+}
+
+#[test]
+fn it_parses_multiple_hosts_correctly() {
+    let url = "postgres://user@host1:5433,host2,[::1]:5434/db?target_session_attrs=read-write";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!("host1", &opts.host);
+    assert_eq!(5433, opts.port);
+    assert_eq!(Some("db"), opts.database.as_deref());
+    assert_eq!("user", &opts.username);
+    assert_eq!(
+        opts.fallback_hosts,
+        [("host2".to_owned(), None), ("[::1]".to_owned(), Some(5434))]
+    );
+    assert_eq!(
+        opts.target_session_attrs,
+        crate::PgTargetSessionAttrs::ReadWrite
+    );
+    assert!(!opts.load_balance_hosts);
+
+    let url = "postgres:///db?host=host1,/tmp&port=5433,5434&load_balance_hosts=random";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!("host1", &opts.host);
+    assert_eq!(5433, opts.port);
+    assert_eq!(opts.fallback_hosts, [("/tmp".to_owned(), Some(5434))]);
+    assert!(opts.load_balance_hosts);
+
+    assert!(PgConnectOptions::from_str("postgres://host1,host2:x/db").is_err());
+    assert!(PgConnectOptions::from_str("postgres:///?target_session_attrs=writable").is_err());
+}
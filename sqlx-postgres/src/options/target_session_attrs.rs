@@ -0,0 +1,66 @@
+use crate::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The properties that a server must have to be connected to, when several hosts are given.
+///
+/// The hosts are tried in order until one of them has these properties, as with the
+/// `target_session_attrs` parameter of libpq.
+///
+/// It is used by the [`target_session_attrs`](super::PgConnectOptions::target_session_attrs)
+/// method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgTargetSessionAttrs {
+    /// Any server is accepted.
+    #[default]
+    Any,
+
+    /// The server must accept read-write transactions by default.
+    ReadWrite,
+
+    /// The server must not accept read-write transactions by default.
+    ReadOnly,
+
+    /// The server must not be in hot standby mode.
+    Primary,
+
+    /// The server must be in hot standby mode.
+    Standby,
+
+    /// A server in hot standby mode is preferred, but any server is accepted if there is none.
+    PreferStandby,
+}
+
+impl Display for PgTargetSessionAttrs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PgTargetSessionAttrs::Any => "any",
+            PgTargetSessionAttrs::ReadWrite => "read-write",
+            PgTargetSessionAttrs::ReadOnly => "read-only",
+            PgTargetSessionAttrs::Primary => "primary",
+            PgTargetSessionAttrs::Standby => "standby",
+            PgTargetSessionAttrs::PreferStandby => "prefer-standby",
+        })
+    }
+}
+
+impl FromStr for PgTargetSessionAttrs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "any" => PgTargetSessionAttrs::Any,
+            "read-write" => PgTargetSessionAttrs::ReadWrite,
+            "read-only" => PgTargetSessionAttrs::ReadOnly,
+            "primary" => PgTargetSessionAttrs::Primary,
+            "standby" => PgTargetSessionAttrs::Standby,
+            "prefer-standby" => PgTargetSessionAttrs::PreferStandby,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {s:?} for `target_session_attrs`").into(),
+                ));
+            }
+        })
+    }
+}
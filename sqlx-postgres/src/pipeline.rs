@@ -0,0 +1,282 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::executor::Execute;
+use crate::logger::QueryLogger;
+use crate::message::{self, Bind, CommandComplete, DataRow, MessageFormat};
+use crate::statement::PgStatementMetadata;
+use crate::{PgArguments, PgConnection, PgQueryResult, PgRow, PgValueFormat, Postgres};
+
+impl PgConnection {
+    /// Start a pipeline, which sends several queries to the server at once instead of waiting
+    /// for the result of each query before sending the next.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+    /// use sqlx::Row;
+    ///
+    /// let results = conn
+    ///     .pipeline()
+    ///     .push(sqlx::query("INSERT INTO users (name) VALUES ($1)").bind("Alice"))
+    ///     .push(sqlx::query("SELECT count(*) FROM users"))
+    ///     .run()
+    ///     .await?;
+    ///
+    /// assert_eq!(results[0].rows_affected(), 1);
+    ///
+    /// let count: i64 = results[1].rows()[0].try_get(0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pipeline<'q>(&mut self) -> PgPipeline<'_, 'q> {
+        PgPipeline {
+            conn: self,
+            queries: Vec::new(),
+        }
+    }
+}
+
+/// A batch of queries that are sent to the server at once, created by
+/// [`PgConnection::pipeline`].
+///
+/// This saves a round trip per query, which makes a large difference on links with a high
+/// latency. The queries are still executed one after another by the server.
+///
+/// Every query is executed as a prepared statement, so a query cannot contain several
+/// statements. Statements need to be prepared before the pipeline is sent, which takes a
+/// round trip for each one that is not cached yet.
+///
+/// ### Transactions
+/// The queries of a pipeline are executed in a single implicit transaction, unless a
+/// transaction is already open. If a query fails, the queries after it are skipped and the
+/// changes of the queries before it are rolled back; inside an open transaction, the
+/// transaction is aborted instead.
+#[must_use = "a pipeline does nothing unless `.run()` is called"]
+pub struct PgPipeline<'c, 'q> {
+    conn: &'c mut PgConnection,
+    queries: Vec<PipelinedQuery<'q>>,
+}
+
+struct PipelinedQuery<'q> {
+    sql: &'q str,
+    arguments: PgArguments,
+    persistent: bool,
+    metadata: Option<Arc<PgStatementMetadata>>,
+}
+
+impl<'c, 'q> PgPipeline<'c, 'q> {
+    /// Add a query to the end of the pipeline.
+    pub fn push<E: Execute<'q, Postgres>>(mut self, mut query: E) -> Self {
+        self.queries.push(PipelinedQuery {
+            sql: query.sql(),
+            arguments: query.take_arguments().unwrap_or_default(),
+            persistent: query.persistent(),
+            metadata: query.statement().map(|s| Arc::clone(&s.metadata)),
+        });
+
+        self
+    }
+
+    /// Returns the number of queries in the pipeline.
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Returns `true` if no queries were added to the pipeline.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Send the queries to the server and collect their results, in the order they were
+    /// added.
+    ///
+    /// Returns the first error of a query; the results of the other queries are discarded.
+    pub async fn run(self) -> Result<Vec<PgPipelineResult>> {
+        let conn = self.conn;
+
+        if self.queries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // the settings of one query would last until the end of the pipeline
+        if self
+            .queries
+            .iter()
+            .any(|q| !q.arguments.settings.is_empty())
+        {
+            return Err(Error::Configuration(
+                "query settings are not supported in a pipeline".into(),
+            ));
+        }
+
+        conn.wait_until_ready().await?;
+
+        // statements cannot be prepared in the middle of the pipeline, as preparing a
+        // statement can take several round trips
+        let mut statements = Vec::with_capacity(self.queries.len());
+
+        for mut query in self.queries {
+            let (statement, metadata) = conn
+                .get_or_prepare(
+                    query.sql,
+                    &query.arguments.types,
+                    query.persistent,
+                    query.metadata,
+                )
+                .await?;
+
+            query
+                .arguments
+                .apply_patches(conn, &metadata.parameters)
+                .await?;
+
+            statements.push((query.sql, query.arguments, statement, metadata));
+        }
+
+        conn.wait_until_ready().await?;
+
+        for (sql, arguments, statement, metadata) in &statements {
+            conn.write_unnamed_parse(sql, *statement, metadata);
+
+            conn.stream.write(Bind {
+                portal: None,
+                statement: *statement,
+                formats: &[PgValueFormat::Binary],
+                num_params: arguments.types.len() as i16,
+                params: &arguments.buffer,
+                result_formats: &[PgValueFormat::Binary],
+            });
+
+            conn.stream.write(message::Execute {
+                portal: None,
+                limit: 0,
+            });
+
+            conn.stream.write(message::Close::Portal(None));
+        }
+
+        // a single [Sync] for every query
+        conn.write_sync();
+        conn.stream.flush().await?;
+
+        let mut results = Vec::with_capacity(statements.len());
+
+        let mut expected = statements
+            .iter()
+            .map(|(sql, _, _, metadata)| {
+                (
+                    QueryLogger::new(sql, conn.log_settings.clone()),
+                    Arc::clone(metadata),
+                )
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let mut current = expected.next();
+        let mut rows = Vec::new();
+
+        loop {
+            let message = conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::ParseComplete
+                | MessageFormat::BindComplete
+                // unnamed portal has been closed
+                | MessageFormat::CloseComplete => {}
+
+                MessageFormat::DataRow => {
+                    let (logger, metadata) = current
+                        .as_mut()
+                        .ok_or_else(|| err_protocol!("pipeline: unexpected DataRow"))?;
+
+                    logger.increment_rows_returned();
+
+                    let data: DataRow = message.decode()?;
+
+                    rows.push(PgRow {
+                        data,
+                        format: PgValueFormat::Binary,
+                        metadata: Arc::clone(metadata),
+                    });
+                }
+
+                MessageFormat::CommandComplete | MessageFormat::EmptyQueryResponse => {
+                    let (mut logger, _) = current
+                        .take()
+                        .ok_or_else(|| err_protocol!("pipeline: unexpected {:?}", message.format))?;
+
+                    let rows_affected = if message.format == MessageFormat::CommandComplete {
+                        message.decode::<CommandComplete>()?.rows_affected()
+                    } else {
+                        0
+                    };
+
+                    logger.increase_rows_affected(rows_affected);
+
+                    results.push(PgPipelineResult {
+                        result: PgQueryResult { rows_affected },
+                        rows: std::mem::take(&mut rows),
+                    });
+
+                    current = expected.next();
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    conn.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                _ => {
+                    return Err(err_protocol!(
+                        "pipeline: unexpected message: {:?}",
+                        message.format
+                    ));
+                }
+            }
+        }
+
+        if results.len() != statements.len() {
+            return Err(err_protocol!(
+                "pipeline: expected {} results but received {}",
+                statements.len(),
+                results.len()
+            ));
+        }
+
+        Ok(results)
+    }
+}
+
+/// The result of a query of a [`PgPipeline`].
+pub struct PgPipelineResult {
+    result: PgQueryResult,
+    rows: Vec<PgRow>,
+}
+
+impl PgPipelineResult {
+    /// The number of rows affected by the query.
+    pub fn rows_affected(&self) -> u64 {
+        self.result.rows_affected()
+    }
+
+    /// The rows returned by the query.
+    pub fn rows(&self) -> &[PgRow] {
+        &self.rows
+    }
+
+    /// Returns the rows returned by the query.
+    pub fn into_rows(self) -> Vec<PgRow> {
+        self.rows
+    }
+}
+
+impl Debug for PgPipelineResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // `PgRow` does not implement `Debug`
+        f.debug_struct("PgPipelineResult")
+            .field("rows_affected", &self.rows_affected())
+            .field("rows", &self.rows.len())
+            .finish()
+    }
+}
@@ -0,0 +1,72 @@
+use sqlx_core::query::{Map, Query};
+use sqlx_core::query_as::QueryAs;
+use sqlx_core::query_scalar::QueryScalar;
+
+use crate::{PgArguments, Postgres};
+
+/// Extension trait for changing server settings while a single query runs.
+///
+/// The settings are applied with `set_config(name, value, true)` right before the query, in
+/// the same round trip. Outside of a transaction, they are reset after the query; inside of
+/// one, they last until the end of the transaction, like `SET LOCAL`.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx_postgres::PgConnection) -> sqlx_core::error::Result<()> {
+/// use sqlx_core::executor::Executor;
+/// use sqlx_postgres::PgQueryExt;
+///
+/// let row = sqlx_core::query::query("SELECT * FROM large_table ORDER BY updated_at")
+///     .with_setting("statement_timeout", "5s")
+///     .with_setting("lock_timeout", "1s")
+///     .fetch_optional(conn)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Settings are not supported by [`PgPipeline`](crate::PgPipeline) and
+/// [`PgConnection::fetch_chunked`](crate::PgConnection::fetch_chunked), which return an error.
+pub trait PgQueryExt: Sized {
+    /// Set the server setting `name` to `value` while this query runs.
+    ///
+    /// An unknown setting or an invalid value is reported as an error of the query.
+    fn with_setting(self, name: impl Into<String>, value: impl Into<String>) -> Self;
+}
+
+fn push_setting(
+    arguments: Option<&mut PgArguments>,
+    name: impl Into<String>,
+    value: impl Into<String>,
+) {
+    if let Some(arguments) = arguments {
+        arguments.settings.push((name.into(), value.into()));
+    }
+}
+
+impl<'q> PgQueryExt for Query<'q, Postgres, PgArguments> {
+    fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        push_setting(self.arguments_mut(), name, value);
+        self
+    }
+}
+
+impl<'q, O> PgQueryExt for QueryAs<'q, Postgres, O, PgArguments> {
+    fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        push_setting(self.arguments_mut(), name, value);
+        self
+    }
+}
+
+impl<'q, O> PgQueryExt for QueryScalar<'q, Postgres, O, PgArguments> {
+    fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        push_setting(self.arguments_mut(), name, value);
+        self
+    }
+}
+
+impl<'q, F> PgQueryExt for Map<'q, Postgres, F, PgArguments> {
+    fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        push_setting(self.arguments_mut(), name, value);
+        self
+    }
+}
@@ -0,0 +1,614 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sqlx_core::bytes::{Buf, BufMut, Bytes};
+
+use crate::connection::Connection;
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::listener::ident;
+use crate::message::{CopyData, CopyDone, CopyResponse, MessageFormat, Query};
+use crate::row::Row;
+use crate::types::PgLsn;
+use crate::{PgConnectOptions, PgConnection, PgCopyBoth, PgRow};
+
+pub use pgoutput::{
+    PgOutputBegin, PgOutputColumn, PgOutputCommit, PgOutputDelete, PgOutputInsert, PgOutputMessage,
+    PgOutputOrigin, PgOutputRelation, PgOutputTruncate, PgOutputTuple, PgOutputType,
+    PgOutputUpdate, PgReplicaIdentity,
+};
+
+mod pgoutput;
+
+// https://www.postgresql.org/docs/current/protocol-replication.html
+
+/// The time between the Unix epoch and the Postgres epoch (`2000-01-01 00:00:00 UTC`).
+const POSTGRES_EPOCH: Duration = Duration::from_secs(946_684_800);
+
+/// A connection to Postgres in logical replication mode, which streams the changes made to
+/// a database through a replication slot.
+///
+/// A replication connection only accepts replication commands and simple queries, so it is
+/// separate from [`PgConnection`]. The role it connects as must have the `REPLICATION`
+/// attribute, and the server must be configured with `wal_level = logical`.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use sqlx::postgres::{PgReplicationConnection, PgReplicationMessage};
+///
+/// let mut conn = PgReplicationConnection::connect("postgres://localhost/app").await?;
+///
+/// let slot = conn
+///     .create_replication_slot("app_slot", "test_decoding", false)
+///     .await?;
+///
+/// let mut stream = conn
+///     .start_replication("app_slot", slot.consistent_point(), &[])
+///     .await?;
+///
+/// while let Some(message) = stream.recv().await? {
+///     match message {
+///         PgReplicationMessage::XLogData(data) => {
+///             println!("{}", String::from_utf8_lossy(data.data()));
+///
+///             let lsn = data.wal_end();
+///             stream.send_status_update(lsn, lsn, lsn, false).await?;
+///         }
+///         PgReplicationMessage::PrimaryKeepalive(keepalive) => {
+///             if keepalive.reply_requested() {
+///                 let lsn = keepalive.wal_end();
+///                 stream.send_status_update(lsn, lsn, lsn, false).await?;
+///             }
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PgReplicationConnection {
+    conn: PgConnection,
+
+    // set while a stream was started and not finished yet
+    streaming: bool,
+}
+
+impl PgReplicationConnection {
+    /// Open a replication connection to the database at `url`.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options: PgConnectOptions = url.parse()?;
+
+        Self::connect_with(&options).await
+    }
+
+    /// Open a replication connection to the database described by `options`.
+    pub async fn connect_with(options: &PgConnectOptions) -> Result<Self> {
+        let mut options = options.clone();
+        options.replication = true;
+
+        Ok(Self {
+            conn: PgConnection::establish(&options).await?,
+            streaming: false,
+        })
+    }
+
+    /// Request the identity of the server with `IDENTIFY_SYSTEM`.
+    pub async fn identify_system(&mut self) -> Result<PgReplicationSystem> {
+        let row = self.fetch_one("IDENTIFY_SYSTEM").await?;
+
+        Ok(PgReplicationSystem {
+            system_id: row.try_get("systemid")?,
+            timeline: row.try_get("timeline")?,
+            xlog_pos: parse_lsn(row.try_get("xlogpos")?)?,
+            dbname: row.try_get("dbname")?,
+        })
+    }
+
+    /// Create a logical replication slot named `slot_name`, which decodes changes with
+    /// `output_plugin` (e.g. `pgoutput` or `test_decoding`).
+    ///
+    /// A `temporary` slot is dropped when the connection is closed, or on an error.
+    pub async fn create_replication_slot(
+        &mut self,
+        slot_name: &str,
+        output_plugin: &str,
+        temporary: bool,
+    ) -> Result<PgReplicationSlot> {
+        let command = format!(
+            r#"CREATE_REPLICATION_SLOT "{}" {}LOGICAL "{}""#,
+            ident(slot_name),
+            if temporary { "TEMPORARY " } else { "" },
+            ident(output_plugin)
+        );
+
+        let row = self.fetch_one(&command).await?;
+
+        Ok(PgReplicationSlot {
+            slot_name: row.try_get("slot_name")?,
+            consistent_point: parse_lsn(row.try_get("consistent_point")?)?,
+            snapshot_name: row.try_get("snapshot_name")?,
+            output_plugin: row.try_get("output_plugin")?,
+        })
+    }
+
+    /// Drop the replication slot named `slot_name`.
+    pub async fn drop_replication_slot(&mut self, slot_name: &str) -> Result<()> {
+        self.wait_until_ready().await?;
+
+        self.conn
+            .execute(&*format!(r#"DROP_REPLICATION_SLOT "{}""#, ident(slot_name)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start streaming changes from the logical replication slot named `slot_name`, beginning
+    /// at `start_lsn`.
+    ///
+    /// `options` are passed to the output plugin of the slot; e.g. `pgoutput` requires
+    /// `proto_version` and `publication_names`.
+    ///
+    /// If the returned [`PgReplicationStream`] is dropped without calling
+    /// [`finish()`][PgReplicationStream::finish], the stream is ended the next time the
+    /// connection is used.
+    pub async fn start_replication(
+        &mut self,
+        slot_name: &str,
+        start_lsn: PgLsn,
+        options: &[(&str, &str)],
+    ) -> Result<PgReplicationStream<'_>> {
+        self.wait_until_ready().await?;
+
+        let command = build_start_replication_command(slot_name, start_lsn, options);
+
+        self.conn.wait_until_ready().await?;
+        self.conn.stream.send(Query(&command)).await?;
+
+        if let Err(e) = self
+            .conn
+            .stream
+            .recv_expect::<CopyResponse>(MessageFormat::CopyBothResponse)
+            .await
+        {
+            self.conn.stream.recv().await?;
+            return Err(e);
+        }
+
+        self.streaming = true;
+
+        Ok(PgReplicationStream {
+            conn: self,
+            done_sent: false,
+            done_received: false,
+        })
+    }
+
+    /// Issue a replication command that starts the `COPY BOTH` sub-protocol, and exchange
+    /// its raw `CopyData` messages.
+    ///
+    /// This gives access to the streams that [`start_replication`][Self::start_replication]
+    /// does not cover, e.g. physical replication with
+    /// `START_REPLICATION PHYSICAL 0/1000000`.
+    pub async fn copy_both(&mut self, command: &str) -> Result<PgCopyBoth<&mut PgConnection>> {
+        self.wait_until_ready().await?;
+
+        self.conn.copy_both(command).await
+    }
+
+    /// Explicitly close this replication connection.
+    pub async fn close(self) -> Result<()> {
+        self.conn.close().await
+    }
+
+    async fn fetch_one(&mut self, command: &str) -> Result<PgRow> {
+        self.wait_until_ready().await?;
+
+        self.conn.fetch_one(command).await
+    }
+
+    // end a stream that was dropped without calling `finish()`
+    async fn wait_until_ready(&mut self) -> Result<()> {
+        if self.streaming {
+            // the stream buffered its `CopyDone` when it was dropped
+            self.conn.stream.flush().await?;
+
+            end_streaming(&mut self.conn).await?;
+            self.streaming = false;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read and discard messages until the server is ready for the next command, after the
+/// client sent `CopyDone` to end the stream.
+async fn end_streaming(conn: &mut PgConnection) -> Result<()> {
+    loop {
+        let message = conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::CopyData | MessageFormat::CopyDone | MessageFormat::CommandComplete => {}
+            MessageFormat::ReadyForQuery => return Ok(()),
+            _ => {
+                return Err(err_protocol!(
+                    "unexpected message format while ending replication: {:?}",
+                    message.format
+                ))
+            }
+        }
+    }
+}
+
+/// A stream of changes from a logical replication slot.
+///
+/// Created by [`PgReplicationConnection::start_replication`].
+pub struct PgReplicationStream<'c> {
+    conn: &'c mut PgReplicationConnection,
+    done_sent: bool,
+    done_received: bool,
+}
+
+impl PgReplicationStream<'_> {
+    /// Receive the next message from the server.
+    ///
+    /// Returns `None` if the server ended the stream.
+    ///
+    /// The server expects regular [status updates][Self::send_status_update], and asks for
+    /// one with [`PgPrimaryKeepalive::reply_requested`]. It disconnects clients that remain
+    /// silent for longer than its `wal_sender_timeout`.
+    pub async fn recv(&mut self) -> Result<Option<PgReplicationMessage>> {
+        if self.done_received {
+            return Ok(None);
+        }
+
+        let message = self.conn.conn.stream.recv().await?;
+
+        match message.format {
+            MessageFormat::CopyData => {
+                let data = message.decode::<CopyData<Bytes>>()?.0;
+
+                PgReplicationMessage::decode(data).map(Some)
+            }
+            MessageFormat::CopyDone => {
+                self.done_received = true;
+                Ok(None)
+            }
+            _ => Err(err_protocol!(
+                "unexpected message format during replication: {:?}",
+                message.format
+            )),
+        }
+    }
+
+    /// Report the progress of the client to the server with a standby status update.
+    ///
+    /// * `written`: everything up to this LSN was received.
+    /// * `flushed`: everything up to this LSN was stored durably. The server may discard
+    ///   the WAL before it, so the changes cannot be streamed again from the slot.
+    /// * `applied`: everything up to this LSN was applied.
+    ///
+    /// If `reply_requested` is set, the server responds with a keepalive right away.
+    pub async fn send_status_update(
+        &mut self,
+        written: PgLsn,
+        flushed: PgLsn,
+        applied: PgLsn,
+        reply_requested: bool,
+    ) -> Result<()> {
+        let mut data = Vec::with_capacity(34);
+
+        data.push(b'r');
+        data.put_u64(written.to_u64());
+        data.put_u64(flushed.to_u64());
+        data.put_u64(applied.to_u64());
+        data.put_i64(to_postgres_timestamp(SystemTime::now()));
+        data.push(reply_requested as u8);
+
+        self.conn.conn.stream.send(CopyData(data)).await
+    }
+
+    /// End the stream, discarding any messages the server sent in the meantime.
+    ///
+    /// The connection is ready for the next command afterwards.
+    pub async fn finish(mut self) -> Result<()> {
+        self.done_sent = true;
+
+        self.conn.conn.stream.send(CopyDone).await?;
+
+        end_streaming(&mut self.conn.conn).await?;
+        self.conn.streaming = false;
+
+        Ok(())
+    }
+}
+
+impl Drop for PgReplicationStream<'_> {
+    fn drop(&mut self) {
+        if !self.done_sent {
+            // the rest of the stream is discarded the next time the connection is used
+            self.conn.conn.stream.write(CopyDone);
+        }
+    }
+}
+
+/// A message streamed from a logical replication slot.
+#[derive(Debug, Clone)]
+pub enum PgReplicationMessage {
+    /// A chunk of WAL data, which holds the output of the output plugin.
+    XLogData(PgXLogData),
+
+    /// A keepalive, which reports the position of the server.
+    PrimaryKeepalive(PgPrimaryKeepalive),
+}
+
+impl PgReplicationMessage {
+    fn decode(mut buf: Bytes) -> Result<Self> {
+        if buf.is_empty() {
+            return Err(err_protocol!("empty replication message"));
+        }
+
+        match buf.get_u8() {
+            b'w' => {
+                if buf.len() < 24 {
+                    return Err(err_protocol!("XLogData message too short"));
+                }
+
+                Ok(PgReplicationMessage::XLogData(PgXLogData {
+                    wal_start: PgLsn::from_u64(buf.get_u64()),
+                    wal_end: PgLsn::from_u64(buf.get_u64()),
+                    server_time: from_postgres_timestamp(buf.get_i64()),
+                    data: buf,
+                }))
+            }
+
+            b'k' => {
+                if buf.len() < 17 {
+                    return Err(err_protocol!("primary keepalive message too short"));
+                }
+
+                Ok(PgReplicationMessage::PrimaryKeepalive(PgPrimaryKeepalive {
+                    wal_end: PgLsn::from_u64(buf.get_u64()),
+                    server_time: from_postgres_timestamp(buf.get_i64()),
+                    reply_requested: buf.get_u8() != 0,
+                }))
+            }
+
+            ty => Err(err_protocol!(
+                "unknown replication message type: {:?}",
+                ty as char
+            )),
+        }
+    }
+}
+
+/// A chunk of WAL data, received as [`PgReplicationMessage::XLogData`].
+#[derive(Debug, Clone)]
+pub struct PgXLogData {
+    wal_start: PgLsn,
+    wal_end: PgLsn,
+    server_time: SystemTime,
+    data: Bytes,
+}
+
+impl PgXLogData {
+    /// The LSN the data starts at.
+    pub fn wal_start(&self) -> PgLsn {
+        self.wal_start
+    }
+
+    /// The current end of the WAL on the server.
+    pub fn wal_end(&self) -> PgLsn {
+        self.wal_end
+    }
+
+    /// The time at which the server sent the message.
+    pub fn server_time(&self) -> SystemTime {
+        self.server_time
+    }
+
+    /// The WAL data, in the format of the output plugin of the slot.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+}
+
+/// A keepalive, received as [`PgReplicationMessage::PrimaryKeepalive`].
+#[derive(Debug, Clone)]
+pub struct PgPrimaryKeepalive {
+    wal_end: PgLsn,
+    server_time: SystemTime,
+    reply_requested: bool,
+}
+
+impl PgPrimaryKeepalive {
+    /// The current end of the WAL on the server.
+    pub fn wal_end(&self) -> PgLsn {
+        self.wal_end
+    }
+
+    /// The time at which the server sent the message.
+    pub fn server_time(&self) -> SystemTime {
+        self.server_time
+    }
+
+    /// Returns `true` if the server asks for a status update right away, to avoid
+    /// disconnecting the client.
+    pub fn reply_requested(&self) -> bool {
+        self.reply_requested
+    }
+}
+
+/// A replication slot, created by [`PgReplicationConnection::create_replication_slot`].
+#[derive(Debug, Clone)]
+pub struct PgReplicationSlot {
+    slot_name: String,
+    consistent_point: PgLsn,
+    snapshot_name: Option<String>,
+    output_plugin: Option<String>,
+}
+
+impl PgReplicationSlot {
+    /// The name of the slot.
+    pub fn slot_name(&self) -> &str {
+        &self.slot_name
+    }
+
+    /// The LSN at which the slot became consistent; streaming from here includes every
+    /// change made after the slot was created.
+    pub fn consistent_point(&self) -> PgLsn {
+        self.consistent_point
+    }
+
+    /// The snapshot exported by the slot, which can be used to read the state of the
+    /// database at the consistent point until the next command on the connection.
+    pub fn snapshot_name(&self) -> Option<&str> {
+        self.snapshot_name.as_deref()
+    }
+
+    /// The output plugin of the slot.
+    pub fn output_plugin(&self) -> Option<&str> {
+        self.output_plugin.as_deref()
+    }
+}
+
+/// The identity of the server, returned by [`PgReplicationConnection::identify_system`].
+#[derive(Debug, Clone)]
+pub struct PgReplicationSystem {
+    system_id: String,
+    timeline: i32,
+    xlog_pos: PgLsn,
+    dbname: Option<String>,
+}
+
+impl PgReplicationSystem {
+    /// The unique identifier of the database cluster.
+    pub fn system_id(&self) -> &str {
+        &self.system_id
+    }
+
+    /// The current timeline.
+    pub fn timeline(&self) -> i32 {
+        self.timeline
+    }
+
+    /// The current position of the WAL.
+    pub fn xlog_pos(&self) -> PgLsn {
+        self.xlog_pos
+    }
+
+    /// The database connected to.
+    pub fn dbname(&self) -> Option<&str> {
+        self.dbname.as_deref()
+    }
+}
+
+fn parse_lsn(text: String) -> Result<PgLsn> {
+    text.parse().map_err(Error::Decode)
+}
+
+fn from_postgres_timestamp(micros: i64) -> SystemTime {
+    let epoch = UNIX_EPOCH + POSTGRES_EPOCH;
+    let offset = Duration::from_micros(micros.unsigned_abs());
+
+    if micros >= 0 {
+        epoch + offset
+    } else {
+        epoch - offset
+    }
+}
+
+fn to_postgres_timestamp(time: SystemTime) -> i64 {
+    let epoch = UNIX_EPOCH + POSTGRES_EPOCH;
+
+    match time.duration_since(epoch) {
+        Ok(since) => since.as_micros() as i64,
+        Err(e) => -(e.duration().as_micros() as i64),
+    }
+}
+
+fn build_start_replication_command(
+    slot_name: &str,
+    start_lsn: PgLsn,
+    options: &[(&str, &str)],
+) -> String {
+    let mut command = format!(
+        r#"START_REPLICATION SLOT "{}" LOGICAL {}"#,
+        ident(slot_name),
+        start_lsn
+    );
+
+    for (i, (name, value)) in options.iter().enumerate() {
+        command.push_str(if i == 0 { " (" } else { ", " });
+        command.push_str(&format!(
+            r#""{}" '{}'"#,
+            ident(name),
+            value.replace('\0', "").replace('\'', "''")
+        ));
+    }
+
+    if !options.is_empty() {
+        command.push(')');
+    }
+
+    command
+}
+
+#[test]
+fn test_build_start_replication_command() {
+    let lsn: PgLsn = "16/B374D848".parse().unwrap();
+
+    assert_eq!(
+        build_start_replication_command("slot", lsn, &[]),
+        r#"START_REPLICATION SLOT "slot" LOGICAL 16/B374D848"#
+    );
+
+    assert_eq!(
+        build_start_replication_command(
+            "my\"slot",
+            lsn,
+            &[("proto_version", "1"), ("publication_names", "it's")]
+        ),
+        r#"START_REPLICATION SLOT "my""slot" LOGICAL 16/B374D848 ("proto_version" '1', "publication_names" 'it''s')"#
+    );
+}
+
+#[test]
+fn test_decode_replication_messages() {
+    let mut xlog = vec![b'w'];
+    xlog.extend_from_slice(&1_u64.to_be_bytes());
+    xlog.extend_from_slice(&2_u64.to_be_bytes());
+    xlog.extend_from_slice(&1_000_000_i64.to_be_bytes());
+    xlog.extend_from_slice(b"BEGIN 42");
+
+    let PgReplicationMessage::XLogData(data) = PgReplicationMessage::decode(xlog.into()).unwrap()
+    else {
+        panic!("expected XLogData");
+    };
+
+    assert_eq!(data.wal_start(), PgLsn::from_u64(1));
+    assert_eq!(data.wal_end(), PgLsn::from_u64(2));
+    assert_eq!(
+        data.server_time(),
+        UNIX_EPOCH + POSTGRES_EPOCH + Duration::from_secs(1)
+    );
+    assert_eq!(&data.data()[..], b"BEGIN 42");
+
+    let mut keepalive = vec![b'k'];
+    keepalive.extend_from_slice(&3_u64.to_be_bytes());
+    keepalive.extend_from_slice(&(-1_i64).to_be_bytes());
+    keepalive.push(1);
+
+    let PgReplicationMessage::PrimaryKeepalive(keepalive) =
+        PgReplicationMessage::decode(keepalive.into()).unwrap()
+    else {
+        panic!("expected PrimaryKeepalive");
+    };
+
+    assert_eq!(keepalive.wal_end(), PgLsn::from_u64(3));
+    assert!(keepalive.reply_requested());
+    assert_eq!(
+        to_postgres_timestamp(keepalive.server_time()),
+        -1,
+        "timestamps before the Postgres epoch round-trip"
+    );
+
+    assert!(PgReplicationMessage::decode(Bytes::from_static(b"k\0")).is_err());
+}
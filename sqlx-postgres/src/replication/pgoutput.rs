@@ -0,0 +1,825 @@
+use std::cmp;
+use std::time::SystemTime;
+
+use sqlx_core::bytes::{Buf, Bytes};
+
+use crate::decode::Decode;
+use crate::error::{mismatched_types, Error, Result};
+use crate::io::BufExt;
+use crate::type_info::PgTypeInfo;
+use crate::types::{Oid, PgLsn, Type};
+use crate::value::{PgValueFormat, PgValueRef};
+use crate::Postgres;
+
+use super::{from_postgres_timestamp, PgXLogData};
+
+// https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html
+
+impl PgXLogData {
+    /// Decode the WAL data as a message of the `pgoutput` output plugin.
+    ///
+    /// Protocol version 1 is supported, as well as the binary values of version 2; streamed
+    /// transactions (the `streaming` option) are not.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     stream: &mut sqlx::postgres::PgReplicationStream<'_>,
+    /// # ) -> sqlx::Result<()> {
+    /// use std::collections::HashMap;
+    /// use sqlx::postgres::{PgOutputMessage, PgReplicationMessage};
+    ///
+    /// let mut relations = HashMap::new();
+    ///
+    /// while let Some(PgReplicationMessage::XLogData(data)) = stream.recv().await? {
+    ///     match data.decode_pgoutput()? {
+    ///         PgOutputMessage::Relation(relation) => {
+    ///             relations.insert(relation.id(), relation);
+    ///         }
+    ///         PgOutputMessage::Insert(insert) => {
+    ///             let relation = &relations[&insert.relation_id()];
+    ///             let id: i64 = insert.new_tuple().try_get(relation, 0)?;
+    ///
+    ///             println!("{} inserted into {}", id, relation.name());
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode_pgoutput(&self) -> Result<PgOutputMessage> {
+        PgOutputMessage::decode(self.data.clone())
+    }
+}
+
+/// A message of the `pgoutput` output plugin, decoded by [`PgXLogData::decode_pgoutput`].
+///
+/// The changes of a transaction are sent between its [`Begin`][Self::Begin] and
+/// [`Commit`][Self::Commit]. A [`Relation`][Self::Relation] describing a table is sent before
+/// the first change to it, and again after its definition changed.
+#[derive(Debug, Clone)]
+pub enum PgOutputMessage {
+    /// The start of a transaction.
+    Begin(PgOutputBegin),
+
+    /// The end of a transaction.
+    Commit(PgOutputCommit),
+
+    /// The replication origin of the transaction.
+    Origin(PgOutputOrigin),
+
+    /// The definition of a table.
+    Relation(PgOutputRelation),
+
+    /// The name of a custom type used by a following relation.
+    Type(PgOutputType),
+
+    /// A row was inserted.
+    Insert(PgOutputInsert),
+
+    /// A row was updated.
+    Update(PgOutputUpdate),
+
+    /// A row was deleted.
+    Delete(PgOutputDelete),
+
+    /// Tables were truncated.
+    Truncate(PgOutputTruncate),
+}
+
+impl PgOutputMessage {
+    fn decode(mut buf: Bytes) -> Result<Self> {
+        let message = match get_u8(&mut buf)? {
+            b'B' => PgOutputMessage::Begin(PgOutputBegin {
+                final_lsn: get_lsn(&mut buf)?,
+                commit_time: get_timestamp(&mut buf)?,
+                xid: get_u32(&mut buf)?,
+            }),
+
+            b'C' => {
+                // flags, currently unused
+                get_u8(&mut buf)?;
+
+                PgOutputMessage::Commit(PgOutputCommit {
+                    commit_lsn: get_lsn(&mut buf)?,
+                    end_lsn: get_lsn(&mut buf)?,
+                    commit_time: get_timestamp(&mut buf)?,
+                })
+            }
+
+            b'O' => PgOutputMessage::Origin(PgOutputOrigin {
+                commit_lsn: get_lsn(&mut buf)?,
+                name: buf.get_str_nul()?,
+            }),
+
+            b'R' => {
+                let id = get_oid(&mut buf)?;
+                let namespace = buf.get_str_nul()?;
+                let name = buf.get_str_nul()?;
+                let replica_identity = PgReplicaIdentity::decode(get_u8(&mut buf)?)?;
+                let num_columns = get_i16(&mut buf)?;
+
+                let mut columns = Vec::with_capacity(cmp::max(num_columns, 0) as usize);
+
+                for _ in 0..num_columns {
+                    let flags = get_u8(&mut buf)?;
+                    let name = buf.get_str_nul()?;
+                    let type_id = get_oid(&mut buf)?;
+
+                    columns.push(PgOutputColumn {
+                        name,
+                        is_key: flags & 1 != 0,
+                        type_info: PgTypeInfo::try_from_oid(type_id)
+                            .unwrap_or(PgTypeInfo::with_oid(type_id)),
+                        type_modifier: get_i32(&mut buf)?,
+                    });
+                }
+
+                PgOutputMessage::Relation(PgOutputRelation {
+                    id,
+                    namespace,
+                    name,
+                    replica_identity,
+                    columns,
+                })
+            }
+
+            b'Y' => PgOutputMessage::Type(PgOutputType {
+                id: get_oid(&mut buf)?,
+                namespace: buf.get_str_nul()?,
+                name: buf.get_str_nul()?,
+            }),
+
+            b'I' => {
+                let relation_id = get_oid(&mut buf)?;
+
+                match get_u8(&mut buf)? {
+                    b'N' => {}
+                    ty => return Err(unexpected_tuple_type(ty)),
+                }
+
+                PgOutputMessage::Insert(PgOutputInsert {
+                    relation_id,
+                    new: PgOutputTuple::decode(&mut buf)?,
+                })
+            }
+
+            b'U' => {
+                let relation_id = get_oid(&mut buf)?;
+
+                let mut key = None;
+                let mut old = None;
+
+                let new = loop {
+                    match get_u8(&mut buf)? {
+                        b'K' if key.is_none() && old.is_none() => {
+                            key = Some(PgOutputTuple::decode(&mut buf)?)
+                        }
+                        b'O' if key.is_none() && old.is_none() => {
+                            old = Some(PgOutputTuple::decode(&mut buf)?)
+                        }
+                        b'N' => break PgOutputTuple::decode(&mut buf)?,
+                        ty => return Err(unexpected_tuple_type(ty)),
+                    }
+                };
+
+                PgOutputMessage::Update(PgOutputUpdate {
+                    relation_id,
+                    key,
+                    old,
+                    new,
+                })
+            }
+
+            b'D' => {
+                let relation_id = get_oid(&mut buf)?;
+
+                let (key, old) = match get_u8(&mut buf)? {
+                    b'K' => (Some(PgOutputTuple::decode(&mut buf)?), None),
+                    b'O' => (None, Some(PgOutputTuple::decode(&mut buf)?)),
+                    ty => return Err(unexpected_tuple_type(ty)),
+                };
+
+                PgOutputMessage::Delete(PgOutputDelete {
+                    relation_id,
+                    key,
+                    old,
+                })
+            }
+
+            b'T' => {
+                let num_relations = get_u32(&mut buf)?;
+                let options = get_u8(&mut buf)?;
+
+                let relation_ids = (0..num_relations)
+                    .map(|_| get_oid(&mut buf))
+                    .collect::<Result<_>>()?;
+
+                PgOutputMessage::Truncate(PgOutputTruncate {
+                    relation_ids,
+                    cascade: options & 1 != 0,
+                    restart_identity: options & 2 != 0,
+                })
+            }
+
+            ty => {
+                return Err(err_protocol!(
+                    "unsupported pgoutput message type: {:?}",
+                    ty as char
+                ))
+            }
+        };
+
+        if !buf.is_empty() {
+            return Err(err_protocol!(
+                "{} trailing bytes after pgoutput message",
+                buf.len()
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+/// The start of a transaction, received as [`PgOutputMessage::Begin`].
+#[derive(Debug, Clone)]
+pub struct PgOutputBegin {
+    final_lsn: PgLsn,
+    commit_time: SystemTime,
+    xid: u32,
+}
+
+impl PgOutputBegin {
+    /// The LSN of the commit of the transaction.
+    pub fn final_lsn(&self) -> PgLsn {
+        self.final_lsn
+    }
+
+    /// The time at which the transaction was committed.
+    pub fn commit_time(&self) -> SystemTime {
+        self.commit_time
+    }
+
+    /// The ID of the transaction.
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+}
+
+/// The end of a transaction, received as [`PgOutputMessage::Commit`].
+#[derive(Debug, Clone)]
+pub struct PgOutputCommit {
+    commit_lsn: PgLsn,
+    end_lsn: PgLsn,
+    commit_time: SystemTime,
+}
+
+impl PgOutputCommit {
+    /// The LSN of the commit.
+    pub fn commit_lsn(&self) -> PgLsn {
+        self.commit_lsn
+    }
+
+    /// The LSN just after the commit; the transaction was processed when everything up to
+    /// this LSN is [flushed][super::PgReplicationStream::send_status_update].
+    pub fn end_lsn(&self) -> PgLsn {
+        self.end_lsn
+    }
+
+    /// The time at which the transaction was committed.
+    pub fn commit_time(&self) -> SystemTime {
+        self.commit_time
+    }
+}
+
+/// The replication origin of a transaction, received as [`PgOutputMessage::Origin`].
+#[derive(Debug, Clone)]
+pub struct PgOutputOrigin {
+    commit_lsn: PgLsn,
+    name: String,
+}
+
+impl PgOutputOrigin {
+    /// The LSN of the commit on the origin server.
+    pub fn commit_lsn(&self) -> PgLsn {
+        self.commit_lsn
+    }
+
+    /// The name of the origin.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The definition of a table, received as [`PgOutputMessage::Relation`].
+///
+/// The values of the rows of the table are decoded with the types of its columns, in
+/// [`PgOutputTuple::try_get`].
+#[derive(Debug, Clone)]
+pub struct PgOutputRelation {
+    id: Oid,
+    namespace: String,
+    name: String,
+    replica_identity: PgReplicaIdentity,
+    columns: Vec<PgOutputColumn>,
+}
+
+impl PgOutputRelation {
+    /// The OID of the table, which identifies it in the other messages.
+    pub fn id(&self) -> Oid {
+        self.id
+    }
+
+    /// The schema of the table; empty for `pg_catalog`.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The name of the table.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Which values of the old row are sent for updates and deletes.
+    pub fn replica_identity(&self) -> PgReplicaIdentity {
+        self.replica_identity
+    }
+
+    /// The columns of the table, in the order of the values of its rows.
+    pub fn columns(&self) -> &[PgOutputColumn] {
+        &self.columns
+    }
+}
+
+/// The replica identity of a table, which determines the values of the old row that are
+/// sent for updates and deletes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgReplicaIdentity {
+    /// The values of the primary key are sent.
+    Default,
+
+    /// No values are sent.
+    Nothing,
+
+    /// The values of every column are sent.
+    Full,
+
+    /// The values of the columns of a chosen index are sent.
+    Index,
+}
+
+impl PgReplicaIdentity {
+    fn decode(identity: u8) -> Result<Self> {
+        Ok(match identity {
+            b'd' => PgReplicaIdentity::Default,
+            b'n' => PgReplicaIdentity::Nothing,
+            b'f' => PgReplicaIdentity::Full,
+            b'i' => PgReplicaIdentity::Index,
+            _ => {
+                return Err(err_protocol!(
+                    "unknown replica identity: {:?}",
+                    identity as char
+                ))
+            }
+        })
+    }
+}
+
+/// A column of a [`PgOutputRelation`].
+#[derive(Debug, Clone)]
+pub struct PgOutputColumn {
+    name: String,
+    is_key: bool,
+    type_info: PgTypeInfo,
+    type_modifier: i32,
+}
+
+impl PgOutputColumn {
+    /// The name of the column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the column is part of the replica identity of the table.
+    pub fn is_key(&self) -> bool {
+        self.is_key
+    }
+
+    /// The type of the column.
+    pub fn type_info(&self) -> &PgTypeInfo {
+        &self.type_info
+    }
+
+    /// The type modifier of the column (`atttypmod`), e.g. the length of a `VARCHAR(n)`.
+    pub fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+}
+
+/// The name of a custom type, received as [`PgOutputMessage::Type`].
+#[derive(Debug, Clone)]
+pub struct PgOutputType {
+    id: Oid,
+    namespace: String,
+    name: String,
+}
+
+impl PgOutputType {
+    /// The OID of the type.
+    pub fn id(&self) -> Oid {
+        self.id
+    }
+
+    /// The schema of the type; empty for `pg_catalog`.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The name of the type.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// An inserted row, received as [`PgOutputMessage::Insert`].
+#[derive(Debug, Clone)]
+pub struct PgOutputInsert {
+    relation_id: Oid,
+    new: PgOutputTuple,
+}
+
+impl PgOutputInsert {
+    /// The OID of the [relation][PgOutputRelation] of the row.
+    pub fn relation_id(&self) -> Oid {
+        self.relation_id
+    }
+
+    /// The inserted row.
+    pub fn new_tuple(&self) -> &PgOutputTuple {
+        &self.new
+    }
+}
+
+/// An updated row, received as [`PgOutputMessage::Update`].
+#[derive(Debug, Clone)]
+pub struct PgOutputUpdate {
+    relation_id: Oid,
+    key: Option<PgOutputTuple>,
+    old: Option<PgOutputTuple>,
+    new: PgOutputTuple,
+}
+
+impl PgOutputUpdate {
+    /// The OID of the [relation][PgOutputRelation] of the row.
+    pub fn relation_id(&self) -> Oid {
+        self.relation_id
+    }
+
+    /// The key columns of the row before the update, if they were changed. The values of
+    /// the other columns are null.
+    pub fn key_tuple(&self) -> Option<&PgOutputTuple> {
+        self.key.as_ref()
+    }
+
+    /// The row before the update, if the replica identity of the table is
+    /// [`Full`][PgReplicaIdentity::Full].
+    pub fn old_tuple(&self) -> Option<&PgOutputTuple> {
+        self.old.as_ref()
+    }
+
+    /// The row after the update.
+    pub fn new_tuple(&self) -> &PgOutputTuple {
+        &self.new
+    }
+}
+
+/// A deleted row, received as [`PgOutputMessage::Delete`].
+#[derive(Debug, Clone)]
+pub struct PgOutputDelete {
+    relation_id: Oid,
+    key: Option<PgOutputTuple>,
+    old: Option<PgOutputTuple>,
+}
+
+impl PgOutputDelete {
+    /// The OID of the [relation][PgOutputRelation] of the row.
+    pub fn relation_id(&self) -> Oid {
+        self.relation_id
+    }
+
+    /// The key columns of the deleted row, unless the replica identity of the table is
+    /// [`Full`][PgReplicaIdentity::Full]. The values of the other columns are null.
+    pub fn key_tuple(&self) -> Option<&PgOutputTuple> {
+        self.key.as_ref()
+    }
+
+    /// The deleted row, if the replica identity of the table is
+    /// [`Full`][PgReplicaIdentity::Full].
+    pub fn old_tuple(&self) -> Option<&PgOutputTuple> {
+        self.old.as_ref()
+    }
+}
+
+/// Truncated tables, received as [`PgOutputMessage::Truncate`].
+#[derive(Debug, Clone)]
+pub struct PgOutputTruncate {
+    relation_ids: Vec<Oid>,
+    cascade: bool,
+    restart_identity: bool,
+}
+
+impl PgOutputTruncate {
+    /// The OIDs of the truncated [relations][PgOutputRelation].
+    pub fn relation_ids(&self) -> &[Oid] {
+        &self.relation_ids
+    }
+
+    /// Returns `true` if the tables were truncated with `CASCADE`.
+    pub fn cascade(&self) -> bool {
+        self.cascade
+    }
+
+    /// Returns `true` if the tables were truncated with `RESTART IDENTITY`.
+    pub fn restart_identity(&self) -> bool {
+        self.restart_identity
+    }
+}
+
+/// The values of a row, in the order of the columns of its [relation][PgOutputRelation].
+#[derive(Debug, Clone)]
+pub struct PgOutputTuple {
+    values: Vec<TupleValue>,
+}
+
+#[derive(Debug, Clone)]
+enum TupleValue {
+    Null,
+    Unchanged,
+    Text(Bytes),
+    Binary(Bytes),
+}
+
+impl PgOutputTuple {
+    fn decode(buf: &mut Bytes) -> Result<Self> {
+        let num_values = get_i16(buf)?;
+        let mut values = Vec::with_capacity(cmp::max(num_values, 0) as usize);
+
+        for _ in 0..num_values {
+            values.push(match get_u8(buf)? {
+                b'n' => TupleValue::Null,
+                b'u' => TupleValue::Unchanged,
+                b't' => TupleValue::Text(get_value(buf)?),
+                b'b' => TupleValue::Binary(get_value(buf)?),
+                ty => {
+                    return Err(err_protocol!(
+                        "unknown pgoutput value type: {:?}",
+                        ty as char
+                    ))
+                }
+            });
+        }
+
+        Ok(Self { values })
+    }
+
+    /// The number of values in the row.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the row has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` if the value at `index` is null.
+    pub fn is_null(&self, index: usize) -> bool {
+        matches!(self.values.get(index), Some(TupleValue::Null))
+    }
+
+    /// Returns `true` if the value at `index` was not sent, because it is a stored
+    /// out-of-line (TOAST) value that was not changed by the update.
+    pub fn is_unchanged(&self, index: usize) -> bool {
+        matches!(self.values.get(index), Some(TupleValue::Unchanged))
+    }
+
+    /// Decode the value at `index` with the type of the column of `relation`, which
+    /// must be the relation of the row.
+    ///
+    /// Decode into an `Option` to accept null values. [Unchanged][Self::is_unchanged] values
+    /// cannot be decoded.
+    pub fn try_get<'r, T>(&'r self, relation: &PgOutputRelation, index: usize) -> Result<T>
+    where
+        T: Decode<'r, Postgres> + Type<Postgres>,
+    {
+        let (value, column) = match (self.values.get(index), relation.columns.get(index)) {
+            (Some(value), Some(column)) => (value, column),
+            _ => {
+                return Err(Error::ColumnIndexOutOfBounds {
+                    index,
+                    len: cmp::min(self.values.len(), relation.columns.len()),
+                })
+            }
+        };
+
+        let (value, format) = match value {
+            TupleValue::Null => (None, PgValueFormat::Text),
+            TupleValue::Text(value) => (Some(&value[..]), PgValueFormat::Text),
+            TupleValue::Binary(value) => (Some(&value[..]), PgValueFormat::Binary),
+            TupleValue::Unchanged => {
+                return Err(Error::ColumnDecode {
+                    index: format!("{index:?}"),
+                    source: "unchanged TOAST value was not sent".into(),
+                })
+            }
+        };
+
+        if value.is_some() && !T::compatible(&column.type_info) {
+            return Err(Error::ColumnDecode {
+                index: format!("{index:?}"),
+                source: mismatched_types::<Postgres, T>(&column.type_info),
+            });
+        }
+
+        let value = PgValueRef {
+            value,
+            row: None,
+            type_info: column.type_info.clone(),
+            format,
+        };
+
+        T::decode(value).map_err(|source| Error::ColumnDecode {
+            index: format!("{index:?}"),
+            source,
+        })
+    }
+}
+
+fn unexpected_tuple_type(ty: u8) -> Error {
+    err_protocol!("unexpected pgoutput tuple type: {:?}", ty as char)
+}
+
+fn ensure_remaining(buf: &Bytes, len: usize) -> Result<()> {
+    if buf.remaining() < len {
+        return Err(err_protocol!("pgoutput message too short"));
+    }
+
+    Ok(())
+}
+
+fn get_u8(buf: &mut Bytes) -> Result<u8> {
+    ensure_remaining(buf, 1)?;
+    Ok(buf.get_u8())
+}
+
+fn get_i16(buf: &mut Bytes) -> Result<i16> {
+    ensure_remaining(buf, 2)?;
+    Ok(buf.get_i16())
+}
+
+fn get_i32(buf: &mut Bytes) -> Result<i32> {
+    ensure_remaining(buf, 4)?;
+    Ok(buf.get_i32())
+}
+
+fn get_u32(buf: &mut Bytes) -> Result<u32> {
+    ensure_remaining(buf, 4)?;
+    Ok(buf.get_u32())
+}
+
+fn get_oid(buf: &mut Bytes) -> Result<Oid> {
+    get_u32(buf).map(Oid)
+}
+
+fn get_lsn(buf: &mut Bytes) -> Result<PgLsn> {
+    ensure_remaining(buf, 8)?;
+    Ok(PgLsn::from_u64(buf.get_u64()))
+}
+
+fn get_timestamp(buf: &mut Bytes) -> Result<SystemTime> {
+    ensure_remaining(buf, 8)?;
+    Ok(from_postgres_timestamp(buf.get_i64()))
+}
+
+fn get_value(buf: &mut Bytes) -> Result<Bytes> {
+    let len = get_u32(buf)? as usize;
+
+    ensure_remaining(buf, len)?;
+    Ok(buf.split_to(len))
+}
+
+#[cfg(test)]
+fn encode_tuple(buf: &mut Vec<u8>, values: &[Option<&str>]) {
+    buf.extend_from_slice(&(values.len() as i16).to_be_bytes());
+
+    for value in values {
+        match value {
+            Some(value) => {
+                buf.push(b't');
+                buf.extend_from_slice(&(value.len() as u32).to_be_bytes());
+                buf.extend_from_slice(value.as_bytes());
+            }
+            None => buf.push(b'n'),
+        }
+    }
+}
+
+#[test]
+fn test_decode_pgoutput_relation_and_insert() {
+    let mut relation = vec![b'R'];
+    relation.extend_from_slice(&16384_u32.to_be_bytes());
+    relation.extend_from_slice(b"public\0users\0d");
+    relation.extend_from_slice(&2_i16.to_be_bytes());
+    relation.extend_from_slice(b"\x01id\0");
+    relation.extend_from_slice(&23_u32.to_be_bytes());
+    relation.extend_from_slice(&(-1_i32).to_be_bytes());
+    relation.extend_from_slice(b"\x00name\0");
+    relation.extend_from_slice(&1043_u32.to_be_bytes());
+    relation.extend_from_slice(&68_i32.to_be_bytes());
+
+    let PgOutputMessage::Relation(relation) = PgOutputMessage::decode(relation.into()).unwrap()
+    else {
+        panic!("expected Relation");
+    };
+
+    assert_eq!(relation.id(), Oid(16384));
+    assert_eq!(relation.namespace(), "public");
+    assert_eq!(relation.name(), "users");
+    assert_eq!(relation.replica_identity(), PgReplicaIdentity::Default);
+    assert_eq!(relation.columns().len(), 2);
+    assert_eq!(relation.columns()[0].name(), "id");
+    assert!(relation.columns()[0].is_key());
+    assert_eq!(*relation.columns()[0].type_info(), PgTypeInfo::INT4);
+    assert!(!relation.columns()[1].is_key());
+    assert_eq!(relation.columns()[1].type_modifier(), 68);
+
+    let mut insert = vec![b'I'];
+    insert.extend_from_slice(&16384_u32.to_be_bytes());
+    insert.push(b'N');
+    encode_tuple(&mut insert, &[Some("42"), None]);
+
+    let PgOutputMessage::Insert(insert) = PgOutputMessage::decode(insert.into()).unwrap() else {
+        panic!("expected Insert");
+    };
+
+    assert_eq!(insert.relation_id(), Oid(16384));
+    assert_eq!(insert.new_tuple().len(), 2);
+    assert_eq!(insert.new_tuple().try_get::<i32>(&relation, 0).unwrap(), 42);
+    assert!(insert.new_tuple().is_null(1));
+    assert_eq!(
+        insert
+            .new_tuple()
+            .try_get::<Option<String>>(&relation, 1)
+            .unwrap(),
+        None
+    );
+
+    assert!(matches!(
+        insert.new_tuple().try_get::<String>(&relation, 0),
+        Err(Error::ColumnDecode { .. })
+    ));
+    assert!(matches!(
+        insert.new_tuple().try_get::<i32>(&relation, 2),
+        Err(Error::ColumnIndexOutOfBounds { index: 2, len: 2 })
+    ));
+}
+
+#[test]
+fn test_decode_pgoutput_update_and_truncate() {
+    let mut update = vec![b'U'];
+    update.extend_from_slice(&16384_u32.to_be_bytes());
+    update.push(b'K');
+    encode_tuple(&mut update, &[Some("1"), None]);
+    update.push(b'N');
+    update.extend_from_slice(&2_i16.to_be_bytes());
+    update.extend_from_slice(b"n");
+    update.extend_from_slice(b"u");
+
+    // a row may contain unchanged TOAST values
+    let PgOutputMessage::Update(update) = PgOutputMessage::decode(update.into()).unwrap() else {
+        panic!("expected Update");
+    };
+
+    assert!(update.key_tuple().is_some());
+    assert!(update.old_tuple().is_none());
+    assert_eq!(update.new_tuple().len(), 2);
+    assert!(update.new_tuple().is_null(0));
+    assert!(update.new_tuple().is_unchanged(1));
+
+    let mut truncate = vec![b'T'];
+    truncate.extend_from_slice(&2_u32.to_be_bytes());
+    truncate.push(3);
+    truncate.extend_from_slice(&1_u32.to_be_bytes());
+    truncate.extend_from_slice(&2_u32.to_be_bytes());
+
+    let PgOutputMessage::Truncate(truncate) = PgOutputMessage::decode(truncate.into()).unwrap()
+    else {
+        panic!("expected Truncate");
+    };
+
+    assert_eq!(truncate.relation_ids(), [Oid(1), Oid(2)]);
+    assert!(truncate.cascade());
+    assert!(truncate.restart_identity());
+
+    assert!(PgOutputMessage::decode(Bytes::from_static(b"B\0\0")).is_err());
+}
@@ -2,6 +2,7 @@ use futures_core::future::BoxFuture;
 
 use crate::error::Error;
 use crate::executor::Executor;
+use crate::query_as::query_as;
 
 use crate::{PgConnection, Postgres};
 
@@ -57,4 +58,92 @@ impl TransactionManager for PgTransactionManager {
             conn.transaction_depth -= 1;
         }
     }
+
+    fn prepare<'c>(conn: &'c mut PgConnection, gid: &'c str) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            // `PREPARE TRANSACTION` inside a savepoint would prepare the whole transaction
+            if conn.transaction_depth != 1 {
+                return Err(err_protocol!(
+                    "only a transaction can be prepared for a two-phase commit, not a savepoint"
+                ));
+            }
+
+            conn.execute(&*format!("PREPARE TRANSACTION {}", quote_gid(gid)))
+                .await?;
+
+            conn.transaction_depth -= 1;
+
+            Ok(())
+        })
+    }
+}
+
+/// A transaction that was prepared for a two-phase commit, and is waiting to be committed or
+/// rolled back.
+///
+/// Returned by [`PgConnection::prepared_transactions()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgPreparedTransaction {
+    /// The global identifier given to [`Transaction::prepare_2pc()`].
+    pub gid: String,
+
+    /// The role that prepared the transaction. Only this role or a superuser can finish it.
+    pub owner: String,
+
+    /// The numeric identifier of the transaction.
+    pub transaction_id: u32,
+}
+
+impl PgConnection {
+    /// List the transactions of the current database that were prepared for a two-phase commit
+    /// and have not been committed or rolled back yet.
+    ///
+    /// After a crash of the application, these are the in-doubt transactions to be finished with
+    /// [`Self::commit_prepared()`] or [`Self::rollback_prepared()`].
+    pub async fn prepared_transactions(&mut self) -> Result<Vec<PgPreparedTransaction>, Error> {
+        let rows: Vec<(String, String, i64)> = query_as(
+            "SELECT gid, owner::text, transaction::text::int8 \
+            FROM pg_catalog.pg_prepared_xacts \
+            WHERE database = current_database() \
+            ORDER BY prepared",
+        )
+        .fetch_all(&mut *self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(gid, owner, transaction_id)| PgPreparedTransaction {
+                gid,
+                owner,
+                transaction_id: transaction_id as u32,
+            })
+            .collect())
+    }
+
+    /// Commit the transaction that was prepared under the global identifier `gid` with
+    /// [`Transaction::prepare_2pc()`].
+    ///
+    /// This cannot be executed inside a transaction.
+    pub async fn commit_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        self.execute(&*format!("COMMIT PREPARED {}", quote_gid(gid)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Roll back the transaction that was prepared under the global identifier `gid` with
+    /// [`Transaction::prepare_2pc()`].
+    ///
+    /// This cannot be executed inside a transaction.
+    pub async fn rollback_prepared(&mut self, gid: &str) -> Result<(), Error> {
+        self.execute(&*format!("ROLLBACK PREPARED {}", quote_gid(gid)))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Quote a global transaction identifier as a string literal, as it can't be a bind parameter.
+fn quote_gid(gid: &str) -> String {
+    format!("'{}'", gid.replace('\0', "").replace('\'', "''"))
 }
@@ -114,10 +114,26 @@ pub enum PgType {
     DateRangeArray,
     Int8Range,
     Int8RangeArray,
+    Int4Multirange,
+    Int4MultirangeArray,
+    NumMultirange,
+    NumMultirangeArray,
+    TsMultirange,
+    TsMultirangeArray,
+    TstzMultirange,
+    TstzMultirangeArray,
+    DateMultirange,
+    DateMultirangeArray,
+    Int8Multirange,
+    Int8MultirangeArray,
     Jsonpath,
     JsonpathArray,
     Money,
     MoneyArray,
+    Tid,
+    TidArray,
+    PgLsn,
+    PgLsnArray,
 
     // https://www.postgresql.org/docs/9.3/datatype-pseudo.html
     Void,
@@ -161,6 +177,20 @@ impl PgTypeInfo {
         PgType::try_from_oid(oid).map(Self)
     }
 
+    /// If `self` is a domain, return its base type, looking through domains over domains.
+    pub(crate) fn domain_base_type(&self) -> Option<&PgTypeInfo> {
+        let mut base = None;
+
+        while let PgType::Custom(ty) = &base.unwrap_or(self).0 {
+            match &ty.kind {
+                PgTypeKind::Domain(ty) => base = Some(ty),
+                _ => break,
+            }
+        }
+
+        base
+    }
+
     /// Returns the _kind_ (simple, array, enum, etc.) for this type.
     pub fn kind(&self) -> &PgTypeKind {
         self.0.kind()
@@ -286,6 +316,10 @@ impl PgType {
             775 => PgType::Macaddr8Array,
             790 => PgType::Money,
             791 => PgType::MoneyArray,
+            27 => PgType::Tid,
+            1010 => PgType::TidArray,
+            3220 => PgType::PgLsn,
+            3221 => PgType::PgLsnArray,
             829 => PgType::Macaddr,
             869 => PgType::Inet,
             1000 => PgType::BoolArray,
@@ -347,6 +381,18 @@ impl PgType {
             3913 => PgType::DateRangeArray,
             3926 => PgType::Int8Range,
             3927 => PgType::Int8RangeArray,
+            4451 => PgType::Int4Multirange,
+            6150 => PgType::Int4MultirangeArray,
+            4532 => PgType::NumMultirange,
+            6151 => PgType::NumMultirangeArray,
+            4533 => PgType::TsMultirange,
+            6152 => PgType::TsMultirangeArray,
+            4534 => PgType::TstzMultirange,
+            6153 => PgType::TstzMultirangeArray,
+            4535 => PgType::DateMultirange,
+            6155 => PgType::DateMultirangeArray,
+            4536 => PgType::Int8Multirange,
+            6157 => PgType::Int8MultirangeArray,
             4072 => PgType::Jsonpath,
             4073 => PgType::JsonpathArray,
 
@@ -394,6 +440,10 @@ impl PgType {
             PgType::Macaddr8Array => Oid(775),
             PgType::Money => Oid(790),
             PgType::MoneyArray => Oid(791),
+            PgType::Tid => Oid(27),
+            PgType::TidArray => Oid(1010),
+            PgType::PgLsn => Oid(3220),
+            PgType::PgLsnArray => Oid(3221),
             PgType::Macaddr => Oid(829),
             PgType::Inet => Oid(869),
             PgType::BoolArray => Oid(1000),
@@ -455,6 +505,18 @@ impl PgType {
             PgType::DateRangeArray => Oid(3913),
             PgType::Int8Range => Oid(3926),
             PgType::Int8RangeArray => Oid(3927),
+            PgType::Int4Multirange => Oid(4451),
+            PgType::Int4MultirangeArray => Oid(6150),
+            PgType::NumMultirange => Oid(4532),
+            PgType::NumMultirangeArray => Oid(6151),
+            PgType::TsMultirange => Oid(4533),
+            PgType::TsMultirangeArray => Oid(6152),
+            PgType::TstzMultirange => Oid(4534),
+            PgType::TstzMultirangeArray => Oid(6153),
+            PgType::DateMultirange => Oid(4535),
+            PgType::DateMultirangeArray => Oid(6155),
+            PgType::Int8Multirange => Oid(4536),
+            PgType::Int8MultirangeArray => Oid(6157),
             PgType::Jsonpath => Oid(4072),
             PgType::JsonpathArray => Oid(4073),
             PgType::Custom(ty) => ty.oid,
@@ -555,10 +617,26 @@ impl PgType {
             PgType::DateRangeArray => "DATERANGE[]",
             PgType::Int8Range => "INT8RANGE",
             PgType::Int8RangeArray => "INT8RANGE[]",
+            PgType::Int4Multirange => "INT4MULTIRANGE",
+            PgType::Int4MultirangeArray => "INT4MULTIRANGE[]",
+            PgType::NumMultirange => "NUMMULTIRANGE",
+            PgType::NumMultirangeArray => "NUMMULTIRANGE[]",
+            PgType::TsMultirange => "TSMULTIRANGE",
+            PgType::TsMultirangeArray => "TSMULTIRANGE[]",
+            PgType::TstzMultirange => "TSTZMULTIRANGE",
+            PgType::TstzMultirangeArray => "TSTZMULTIRANGE[]",
+            PgType::DateMultirange => "DATEMULTIRANGE",
+            PgType::DateMultirangeArray => "DATEMULTIRANGE[]",
+            PgType::Int8Multirange => "INT8MULTIRANGE",
+            PgType::Int8MultirangeArray => "INT8MULTIRANGE[]",
             PgType::Jsonpath => "JSONPATH",
             PgType::JsonpathArray => "JSONPATH[]",
             PgType::Money => "MONEY",
             PgType::MoneyArray => "MONEY[]",
+            PgType::Tid => "TID",
+            PgType::TidArray => "TID[]",
+            PgType::PgLsn => "PG_LSN",
+            PgType::PgLsnArray => "PG_LSN[]",
             PgType::Void => "VOID",
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
@@ -655,10 +733,26 @@ impl PgType {
             PgType::DateRangeArray => "_daterange",
             PgType::Int8Range => "int8range",
             PgType::Int8RangeArray => "_int8range",
+            PgType::Int4Multirange => "int4multirange",
+            PgType::Int4MultirangeArray => "_int4multirange",
+            PgType::NumMultirange => "nummultirange",
+            PgType::NumMultirangeArray => "_nummultirange",
+            PgType::TsMultirange => "tsmultirange",
+            PgType::TsMultirangeArray => "_tsmultirange",
+            PgType::TstzMultirange => "tstzmultirange",
+            PgType::TstzMultirangeArray => "_tstzmultirange",
+            PgType::DateMultirange => "datemultirange",
+            PgType::DateMultirangeArray => "_datemultirange",
+            PgType::Int8Multirange => "int8multirange",
+            PgType::Int8MultirangeArray => "_int8multirange",
             PgType::Jsonpath => "jsonpath",
             PgType::JsonpathArray => "_jsonpath",
             PgType::Money => "money",
             PgType::MoneyArray => "_money",
+            PgType::Tid => "tid",
+            PgType::TidArray => "_tid",
+            PgType::PgLsn => "pg_lsn",
+            PgType::PgLsnArray => "_pg_lsn",
             PgType::Void => "void",
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
@@ -755,10 +849,26 @@ impl PgType {
             PgType::DateRangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::DateRange)),
             PgType::Int8Range => &PgTypeKind::Range(PgTypeInfo::INT8),
             PgType::Int8RangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::Int8Range)),
+            PgType::Int4Multirange => &PgTypeKind::Simple,
+            PgType::Int4MultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::Int4Multirange)),
+            PgType::NumMultirange => &PgTypeKind::Simple,
+            PgType::NumMultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::NumMultirange)),
+            PgType::TsMultirange => &PgTypeKind::Simple,
+            PgType::TsMultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::TsMultirange)),
+            PgType::TstzMultirange => &PgTypeKind::Simple,
+            PgType::TstzMultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::TstzMultirange)),
+            PgType::DateMultirange => &PgTypeKind::Simple,
+            PgType::DateMultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::DateMultirange)),
+            PgType::Int8Multirange => &PgTypeKind::Simple,
+            PgType::Int8MultirangeArray => &PgTypeKind::Array(PgTypeInfo(PgType::Int8Multirange)),
             PgType::Jsonpath => &PgTypeKind::Simple,
             PgType::JsonpathArray => &PgTypeKind::Array(PgTypeInfo(PgType::Jsonpath)),
             PgType::Money => &PgTypeKind::Simple,
             PgType::MoneyArray => &PgTypeKind::Array(PgTypeInfo(PgType::Money)),
+            PgType::Tid => &PgTypeKind::Simple,
+            PgType::TidArray => &PgTypeKind::Array(PgTypeInfo(PgType::Tid)),
+            PgType::PgLsn => &PgTypeKind::Simple,
+            PgType::PgLsnArray => &PgTypeKind::Array(PgTypeInfo(PgType::PgLsn)),
 
             PgType::Void => &PgTypeKind::Pseudo,
 
@@ -824,6 +934,10 @@ impl PgType {
             PgType::Macaddr8Array => Some(Cow::Owned(PgTypeInfo(PgType::Macaddr8))),
             PgType::Money => None,
             PgType::MoneyArray => Some(Cow::Owned(PgTypeInfo(PgType::Money))),
+            PgType::Tid => None,
+            PgType::TidArray => Some(Cow::Owned(PgTypeInfo(PgType::Tid))),
+            PgType::PgLsn => None,
+            PgType::PgLsnArray => Some(Cow::Owned(PgTypeInfo(PgType::PgLsn))),
             PgType::Macaddr => None,
             PgType::MacaddrArray => Some(Cow::Owned(PgTypeInfo(PgType::Macaddr))),
             PgType::Inet => None,
@@ -868,6 +982,18 @@ impl PgType {
             PgType::DateRangeArray => Some(Cow::Owned(PgTypeInfo(PgType::DateRange))),
             PgType::Int8Range => None,
             PgType::Int8RangeArray => Some(Cow::Owned(PgTypeInfo(PgType::Int8Range))),
+            PgType::Int4Multirange => None,
+            PgType::Int4MultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::Int4Multirange))),
+            PgType::NumMultirange => None,
+            PgType::NumMultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::NumMultirange))),
+            PgType::TsMultirange => None,
+            PgType::TsMultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::TsMultirange))),
+            PgType::TstzMultirange => None,
+            PgType::TstzMultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::TstzMultirange))),
+            PgType::DateMultirange => None,
+            PgType::DateMultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::DateMultirange))),
+            PgType::Int8Multirange => None,
+            PgType::Int8MultirangeArray => Some(Cow::Owned(PgTypeInfo(PgType::Int8Multirange))),
             PgType::Jsonpath => None,
             PgType::JsonpathArray => Some(Cow::Owned(PgTypeInfo(PgType::Jsonpath))),
             // There is no `UnknownArray`
@@ -1026,6 +1152,18 @@ impl PgTypeInfo {
     pub(crate) const MONEY: Self = Self(PgType::Money);
     pub(crate) const MONEY_ARRAY: Self = Self(PgType::MoneyArray);
 
+    //
+    // system types
+    // https://www.postgresql.org/docs/current/datatype-oid.html
+    // https://www.postgresql.org/docs/current/datatype-pg-lsn.html
+    //
+
+    pub(crate) const TID: Self = Self(PgType::Tid);
+    pub(crate) const TID_ARRAY: Self = Self(PgType::TidArray);
+
+    pub(crate) const PG_LSN: Self = Self(PgType::PgLsn);
+    pub(crate) const PG_LSN_ARRAY: Self = Self(PgType::PgLsnArray);
+
     //
     // date/time types
     // https://www.postgresql.org/docs/current/datatype-datetime.html
@@ -1122,6 +1260,29 @@ impl PgTypeInfo {
     pub(crate) const INT8_RANGE: Self = Self(PgType::Int8Range);
     pub(crate) const INT8_RANGE_ARRAY: Self = Self(PgType::Int8RangeArray);
 
+    //
+    // multirange types
+    // https://www.postgresql.org/docs/current/rangetypes.html
+    //
+
+    pub(crate) const INT4_MULTIRANGE: Self = Self(PgType::Int4Multirange);
+    pub(crate) const INT4_MULTIRANGE_ARRAY: Self = Self(PgType::Int4MultirangeArray);
+
+    pub(crate) const NUM_MULTIRANGE: Self = Self(PgType::NumMultirange);
+    pub(crate) const NUM_MULTIRANGE_ARRAY: Self = Self(PgType::NumMultirangeArray);
+
+    pub(crate) const TS_MULTIRANGE: Self = Self(PgType::TsMultirange);
+    pub(crate) const TS_MULTIRANGE_ARRAY: Self = Self(PgType::TsMultirangeArray);
+
+    pub(crate) const TSTZ_MULTIRANGE: Self = Self(PgType::TstzMultirange);
+    pub(crate) const TSTZ_MULTIRANGE_ARRAY: Self = Self(PgType::TstzMultirangeArray);
+
+    pub(crate) const DATE_MULTIRANGE: Self = Self(PgType::DateMultirange);
+    pub(crate) const DATE_MULTIRANGE_ARRAY: Self = Self(PgType::DateMultirangeArray);
+
+    pub(crate) const INT8_MULTIRANGE: Self = Self(PgType::Int8Multirange);
+    pub(crate) const INT8_MULTIRANGE_ARRAY: Self = Self(PgType::Int8MultirangeArray);
+
     //
     // pseudo types
     // https://www.postgresql.org/docs/9.3/datatype-pseudo.html
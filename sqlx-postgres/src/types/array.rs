@@ -4,7 +4,7 @@ use std::borrow::Cow;
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
-use crate::type_info::PgType;
+use crate::type_info::{PgType, PgTypeKind};
 use crate::types::Oid;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
@@ -50,7 +50,24 @@ use crate::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 pub trait PgHasArrayType {
     fn array_type_info() -> PgTypeInfo;
     fn array_compatible(ty: &PgTypeInfo) -> bool {
-        *ty == Self::array_type_info()
+        *ty == Self::array_type_info() || domain_array_compatible(ty, &Self::array_type_info())
+    }
+}
+
+// whether `ty` is an array of a domain over the element type of `array`
+fn domain_array_compatible(ty: &PgTypeInfo, array: &PgTypeInfo) -> bool {
+    let base = match &ty.0 {
+        PgType::Custom(ty) => match &ty.kind {
+            PgTypeKind::Array(element) => element.domain_base_type(),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    // the element type of a declared array type isn't known
+    match (base, &array.0) {
+        (None, _) | (_, PgType::DeclareWithName(_) | PgType::DeclareWithOid(_)) => false,
+        (Some(base), array) => array.try_array_element().map_or(false, |e| *base == *e),
     }
 }
 
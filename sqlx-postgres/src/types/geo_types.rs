@@ -0,0 +1,124 @@
+use geo_types::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+use sqlx_core::io::wkb::{self, WriteWkb};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+// PostGIS sends geometries in EWKB, an extension of WKB (well-known binary) with an optional
+// SRID, which is written as hex in the text format. WKB is accepted as input, with the default
+// SRID of the column type.
+// https://postgis.net/docs/using_postgis_dbmanagement.html#EWKB_EWKT
+
+impl Type<Postgres> for Geometry<f64> {
+    fn type_info() -> PgTypeInfo {
+        // Since `geometry` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("geometry")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::with_name("geometry") || *ty == PgTypeInfo::with_name("geography")
+    }
+}
+
+impl Encode<'_, Postgres> for Geometry<f64> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        self.write_wkb(buf);
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Postgres> for Geometry<f64> {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => wkb::decode(value.as_bytes()?),
+            PgValueFormat::Text => wkb::decode(&hex::decode(value.as_str()?)?),
+        }
+    }
+}
+
+macro_rules! impl_geometry_type {
+    ($($ty:ident),*) => {
+        $(
+            impl Type<Postgres> for $ty<f64> {
+                fn type_info() -> PgTypeInfo {
+                    <Geometry<f64> as Type<Postgres>>::type_info()
+                }
+
+                fn compatible(ty: &PgTypeInfo) -> bool {
+                    <Geometry<f64> as Type<Postgres>>::compatible(ty)
+                }
+            }
+
+            impl Encode<'_, Postgres> for $ty<f64> {
+                fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+                    self.write_wkb(buf);
+
+                    IsNull::No
+                }
+            }
+
+            impl Decode<'_, Postgres> for $ty<f64> {
+                fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+                    Ok($ty::try_from(<Geometry<f64> as Decode<Postgres>>::decode(value)?)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_geometry_type!(
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection
+);
+
+#[test]
+fn test_encode_decode_geometry() {
+    let point = Point::new(1.0, 2.0);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(point, &mut buf);
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: Point::<f64>::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(
+        <Point<f64> as Decode<Postgres>>::decode(value).unwrap(),
+        point
+    );
+
+    // SRID=4326;POINT(1 2), as PostGIS writes it in the text format
+    let value = PgValueRef {
+        value: Some(b"0101000020E6100000000000000000F03F0000000000000040"),
+        row: None,
+        type_info: PgTypeInfo::with_name("geography"),
+        format: PgValueFormat::Text,
+    };
+
+    assert_eq!(
+        <Geometry<f64> as Decode<Postgres>>::decode(value).unwrap(),
+        Geometry::Point(point)
+    );
+
+    assert!(<Geometry<f64> as Type<Postgres>>::compatible(
+        &PgTypeInfo::with_name("geography")
+    ));
+    assert!(!<Geometry<f64> as Type<Postgres>>::compatible(
+        &PgTypeInfo::with_name("box2d")
+    ));
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::ops::{Deref, DerefMut};
+use std::str;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A set of key/value pairs, from the [`hstore`] extension.
+///
+/// The values can be `NULL`, and the pairs are ordered by key.
+///
+/// `HashMap<String, Option<String>>` can also be used for `hstore` values.
+///
+/// ```rust
+/// # use sqlx_core::postgres::types::PgHstore;
+/// let mut hstore = PgHstore::default();
+///
+/// hstore.insert("color".into(), Some("red".into()));
+/// hstore.insert("size".into(), None);
+///
+/// assert_eq!(hstore.get("color"), Some(&Some("red".into())));
+/// ```
+///
+/// [`hstore`]: https://www.postgresql.org/docs/current/hstore.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PgHstore(pub BTreeMap<String, Option<String>>);
+
+impl Deref for PgHstore {
+    type Target = BTreeMap<String, Option<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for PgHstore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<BTreeMap<String, Option<String>>> for PgHstore {
+    fn from(map: BTreeMap<String, Option<String>>) -> Self {
+        PgHstore(map)
+    }
+}
+
+impl<K, V> FromIterator<(K, Option<V>)> for PgHstore
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, Option<V>)>>(iter: I) -> Self {
+        PgHstore(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.map(Into::into)))
+                .collect(),
+        )
+    }
+}
+
+impl IntoIterator for PgHstore {
+    type Item = (String, Option<String>);
+    type IntoIter = std::collections::btree_map::IntoIter<String, Option<String>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Type<Postgres> for PgHstore {
+    fn type_info() -> PgTypeInfo {
+        // Since `hstore` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("hstore")
+    }
+}
+
+impl PgHasArrayType for PgHstore {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_hstore")
+    }
+}
+
+impl Encode<'_, Postgres> for PgHstore {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        encode_pairs(buf, self.0.len(), self.0.iter());
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgHstore {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(PgHstore(decode_pairs(value)?.collect::<Result<_, _>>()?))
+    }
+}
+
+impl<S> Type<Postgres> for HashMap<String, Option<String>, S> {
+    fn type_info() -> PgTypeInfo {
+        PgHstore::type_info()
+    }
+}
+
+impl<S> Encode<'_, Postgres> for HashMap<String, Option<String>, S> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        encode_pairs(buf, self.len(), self.iter());
+
+        IsNull::No
+    }
+}
+
+impl<'r, S> Decode<'r, Postgres> for HashMap<String, Option<String>, S>
+where
+    S: BuildHasher + Default,
+{
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_pairs(value)?.collect()
+    }
+}
+
+fn encode_pairs<'a>(
+    buf: &mut PgArgumentBuffer,
+    len: usize,
+    pairs: impl Iterator<Item = (&'a String, &'a Option<String>)>,
+) {
+    buf.extend(&(len as i32).to_be_bytes());
+
+    for (key, value) in pairs {
+        buf.extend(&(key.len() as i32).to_be_bytes());
+        buf.extend(key.as_bytes());
+
+        match value {
+            Some(value) => {
+                buf.extend(&(value.len() as i32).to_be_bytes());
+                buf.extend(value.as_bytes());
+            }
+
+            // a length of -1 is `NULL`
+            None => buf.extend(&(-1_i32).to_be_bytes()),
+        }
+    }
+}
+
+type Pair = (String, Option<String>);
+
+fn decode_pairs(
+    value: PgValueRef<'_>,
+) -> Result<Box<dyn Iterator<Item = Result<Pair, BoxDynError>> + '_>, BoxDynError> {
+    Ok(match value.format() {
+        PgValueFormat::Binary => {
+            let mut buf = value.as_bytes()?;
+
+            let len = read_len(&mut buf)?.ok_or("hstore: negative number of pairs")?;
+
+            Box::new((0..len).map(move |_| {
+                let key = read_str(&mut buf)?.ok_or("hstore: key is NULL")?;
+                let value = read_str(&mut buf)?;
+
+                Ok((key.to_owned(), value.map(str::to_owned)))
+            }))
+        }
+
+        PgValueFormat::Text => Box::new(parse(value.as_str()?)?.into_iter().map(Ok)),
+    })
+}
+
+/// Read a length, which is `None` if it's negative.
+fn read_len(buf: &mut &[u8]) -> Result<Option<usize>, BoxDynError> {
+    if buf.len() < 4 {
+        return Err("hstore: unexpected end of data".into());
+    }
+
+    let len = BigEndian::read_i32(buf);
+    *buf = &buf[4..];
+
+    Ok(usize::try_from(len).ok())
+}
+
+/// Read a string prefixed with its length, which is `None` if it's `NULL`.
+fn read_str<'a>(buf: &mut &'a [u8]) -> Result<Option<&'a str>, BoxDynError> {
+    let len = match read_len(buf)? {
+        Some(len) => len,
+        None => return Ok(None),
+    };
+
+    if buf.len() < len {
+        return Err("hstore: unexpected end of data".into());
+    }
+
+    let (s, rest) = buf.split_at(len);
+    *buf = rest;
+
+    Ok(Some(str::from_utf8(s)?))
+}
+
+/// Parse the textual format of `hstore`, e.g. `"a"=>"1", "b"=>NULL`.
+fn parse(s: &str) -> Result<Vec<Pair>, BoxDynError> {
+    let mut pairs = Vec::new();
+    let mut rest = s.trim_start();
+
+    while !rest.is_empty() {
+        let key;
+        (key, rest) = parse_quoted(rest)?;
+
+        rest = rest
+            .trim_start()
+            .strip_prefix("=>")
+            .ok_or_else(|| format!("hstore: expected `=>` in {s:?}"))?
+            .trim_start();
+
+        let value = if let Some(after) = rest.strip_prefix("NULL") {
+            rest = after;
+            None
+        } else {
+            let value;
+            (value, rest) = parse_quoted(rest)?;
+            Some(value)
+        };
+
+        pairs.push((key, value));
+
+        rest = rest.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+
+    Ok(pairs)
+}
+
+/// Parse a string between double quotes, with `\` escaping the next character.
+fn parse_quoted(s: &str) -> Result<(String, &str), BoxDynError> {
+    let mut chars = s
+        .strip_prefix('"')
+        .ok_or_else(|| format!("hstore: expected `\"` at {s:?}"))?
+        .char_indices();
+
+    let mut value = String::new();
+
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, chars.as_str())),
+            '\\' => value.extend(chars.next().map(|(_, c)| c)),
+            c => value.push(c),
+        }
+    }
+
+    Err(format!("hstore: unterminated string in {s:?}").into())
+}
+
+#[test]
+fn test_encode_decode_hstore() {
+    let hstore = PgHstore::from_iter([("a", Some("1")), ("b", None)]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(&hstore, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\0\0\x02\0\0\0\x01a\0\0\0\x011\0\0\0\x01b\xff\xff\xff\xff".as_slice()
+    );
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgHstore::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgHstore::decode(value).unwrap(), hstore);
+}
+
+#[test]
+fn test_parse_hstore() {
+    assert_eq!(
+        parse(r#""a"=>"1", "b"=>NULL, "c \"d\""=>"e\\f""#).unwrap(),
+        [
+            ("a".to_owned(), Some("1".to_owned())),
+            ("b".to_owned(), None),
+            (r#"c "d""#.to_owned(), Some(r"e\f".to_owned())),
+        ]
+    );
+
+    assert_eq!(parse("").unwrap(), []);
+    assert!(parse(r#""a"=>"1"#).is_err());
+    assert!(parse(r#""a""1""#).is_err());
+}
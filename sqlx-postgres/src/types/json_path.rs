@@ -0,0 +1,139 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// An SQL/JSON path expression, the [`jsonpath`] type of Postgres 12 and later.
+///
+/// The expression is kept as text, and is only checked by Postgres. Postgres also normalizes
+/// the expressions it returns, so a decoded expression may not have the text that was encoded.
+///
+/// ```rust,ignore
+/// let values: Vec<JsonValue> = sqlx::query_scalar("SELECT jsonb_path_query($1, $2)")
+///     .bind(json!({ "a": [1, 2, 3] }))
+///     .bind(PgJsonPath::new("$.a[*] ? (@ > 1)"))
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// [`jsonpath`]: https://www.postgresql.org/docs/current/datatype-json.html#DATATYPE-JSONPATH
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgJsonPath(String);
+
+impl PgJsonPath {
+    /// Create a path from the text of an expression, like `$.a[*] ? (@ > 1)`.
+    pub fn new(path: impl Into<String>) -> Self {
+        PgJsonPath(path.into())
+    }
+
+    /// Returns the text of this path.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the text of this path.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for PgJsonPath {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for PgJsonPath {
+    fn from(path: String) -> Self {
+        PgJsonPath(path)
+    }
+}
+
+impl From<&str> for PgJsonPath {
+    fn from(path: &str) -> Self {
+        PgJsonPath(path.to_owned())
+    }
+}
+
+impl Display for PgJsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type<Postgres> for PgJsonPath {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::JSONPATH
+    }
+}
+
+impl PgHasArrayType for PgJsonPath {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::JSONPATH_ARRAY
+    }
+}
+
+impl Encode<'_, Postgres> for PgJsonPath {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // the binary format is the version of the format, followed by the text
+        buf.push(1);
+        buf.extend(self.0.as_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Postgres> for PgJsonPath {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let (version, path) = value
+                    .as_bytes()?
+                    .split_first()
+                    .ok_or("jsonpath: expected a version byte")?;
+
+                if *version != 1 {
+                    return Err(format!("jsonpath: unsupported version {version}").into());
+                }
+
+                Ok(PgJsonPath(std::str::from_utf8(path)?.to_owned()))
+            }
+
+            PgValueFormat::Text => Ok(PgJsonPath(value.as_str()?.to_owned())),
+        }
+    }
+}
+
+#[test]
+fn test_encode_decode_json_path() {
+    let path = PgJsonPath::new("$.a[*]");
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode_by_ref(&path, &mut buf);
+
+    assert_eq!(&**buf, b"\x01$.a[*]");
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgJsonPath::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgJsonPath::decode(value).unwrap(), path);
+
+    let value = PgValueRef {
+        value: Some(b"\x02$"),
+        row: None,
+        type_info: PgJsonPath::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert!(PgJsonPath::decode(value).is_err());
+}
@@ -2,7 +2,7 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::types::Type;
-use crate::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use bitflags::bitflags;
 use std::fmt::{self, Display, Formatter};
 use std::io::Write;
@@ -138,6 +138,12 @@ impl Type<Postgres> for PgLQuery {
     }
 }
 
+impl PgHasArrayType for PgLQuery {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_lquery")
+    }
+}
+
 impl Encode<'_, Postgres> for PgLQuery {
     fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
         buf.extend(1i8.to_le_bytes());
@@ -152,12 +158,14 @@ impl<'r> Decode<'r, Postgres> for PgLQuery {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
         match value.format() {
             PgValueFormat::Binary => {
-                let bytes = value.as_bytes()?;
-                let version = i8::from_le_bytes([bytes[0]; 1]);
-                if version != 1 {
+                let (version, bytes) = value
+                    .as_bytes()?
+                    .split_first()
+                    .ok_or("lquery: expected a version byte")?;
+                if *version != 1 {
                     return Err(Box::new(PgLQueryParseError::InvalidLqueryVersion));
                 }
-                Ok(Self::from_str(std::str::from_utf8(&bytes[1..])?)?)
+                Ok(Self::from_str(std::str::from_utf8(bytes)?)?)
             }
             PgValueFormat::Text => Ok(Self::from_str(value.as_str()?)?),
         }
@@ -226,17 +234,28 @@ impl FromStr for PgLQueryLevel {
             match bytes[0] {
                 b'*' => {
                     if bytes.len() > 1 {
-                        let parts = s[2..s.len() - 1].split(',').collect::<Vec<_>>();
-                        match parts.len() {
-                            1 => {
-                                let number = parts[0].parse()?;
+                        let bounds = s[1..]
+                            .strip_prefix('{')
+                            .and_then(|s| s.strip_suffix('}'))
+                            .ok_or(PgLQueryParseError::UnexpectedCharacter)?;
+
+                        // either bound can be left out, e.g. `*{2,}`
+                        let parse_bound = |bound: &str| {
+                            Ok::<_, PgLQueryParseError>(match bound.trim() {
+                                "" => None,
+                                bound => Some(bound.parse()?),
+                            })
+                        };
+
+                        match bounds.split_once(',') {
+                            None => {
+                                let number = bounds.trim().parse()?;
                                 Ok(PgLQueryLevel::Star(Some(number), Some(number)))
                             }
-                            2 => Ok(PgLQueryLevel::Star(
-                                Some(parts[0].parse()?),
-                                Some(parts[1].parse()?),
+                            Some((at_least, at_most)) => Ok(PgLQueryLevel::Star(
+                                parse_bound(at_least)?,
+                                parse_bound(at_most)?,
                             )),
-                            _ => Err(PgLQueryParseError::UnexpectedCharacter),
                         }
                     } else {
                         Ok(PgLQueryLevel::Star(None, None))
@@ -288,7 +307,7 @@ fn write_variants(f: &mut Formatter<'_>, variants: &[PgLQueryVariant], not: bool
     if let Some(variant) = iter.next() {
         write!(f, "{}{}", if not { "!" } else { "" }, variant)?;
         for variant in iter {
-            write!(f, ".{variant}")?;
+            write!(f, "|{variant}")?;
         }
     }
     Ok(())
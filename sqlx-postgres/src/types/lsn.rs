@@ -0,0 +1,143 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A position in the write-ahead log, the [`pg_lsn`] type.
+///
+/// Displayed and parsed in the textual format of Postgres: two hexadecimal numbers of up to
+/// 8 digits each, separated by a slash (e.g. `16/B374D848`).
+///
+/// LSNs are ordered by their position in the log.
+///
+/// [`pg_lsn`]: https://www.postgresql.org/docs/current/datatype-pg-lsn.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(u64);
+
+impl PgLsn {
+    /// Create an LSN from its numeric representation.
+    pub const fn from_u64(lsn: u64) -> Self {
+        PgLsn(lsn)
+    }
+
+    /// Returns the numeric representation of this LSN.
+    pub const fn to_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for PgLsn {
+    fn from(lsn: u64) -> Self {
+        PgLsn(lsn)
+    }
+}
+
+impl From<PgLsn> for u64 {
+    fn from(lsn: PgLsn) -> Self {
+        lsn.0
+    }
+}
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hi, lo) = s
+            .split_once('/')
+            .ok_or_else(|| format!("invalid LSN {s:?}: expected `/`"))?;
+
+        let hi = u32::from_str_radix(hi, 16)?;
+        let lo = u32::from_str_radix(lo, 16)?;
+
+        Ok(PgLsn((u64::from(hi) << 32) | u64::from(lo)))
+    }
+}
+
+impl Type<Postgres> for PgLsn {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::PG_LSN
+    }
+}
+
+impl PgHasArrayType for PgLsn {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::PG_LSN_ARRAY
+    }
+}
+
+impl Encode<'_, Postgres> for PgLsn {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        buf.extend(&self.0.to_be_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Postgres> for PgLsn {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let bytes = value.as_bytes()?;
+                let bytes = bytes
+                    .try_into()
+                    .map_err(|_| format!("invalid LSN: expected 8 bytes, got {}", bytes.len()))?;
+
+                Ok(PgLsn(u64::from_be_bytes(bytes)))
+            }
+
+            PgValueFormat::Text => value.as_str()?.parse(),
+        }
+    }
+}
+
+#[test]
+fn test_lsn_display_and_parse() {
+    let lsn: PgLsn = "16/B374D848".parse().unwrap();
+
+    assert_eq!(lsn.to_u64(), 0x16_B374_D848);
+    assert_eq!(lsn.to_string(), "16/B374D848");
+    assert_eq!(PgLsn::default().to_string(), "0/0");
+
+    assert!("16B374D848".parse::<PgLsn>().is_err());
+    assert!("16/G".parse::<PgLsn>().is_err());
+
+    assert!(PgLsn::from(1) < lsn);
+}
+
+#[test]
+fn test_encode_decode_lsn() {
+    let lsn = PgLsn::from_u64(0x16_B374_D848);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(lsn, &mut buf);
+
+    assert_eq!(&**buf, &0x16_B374_D848_u64.to_be_bytes());
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgLsn::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgLsn::decode(value).unwrap(), lsn);
+
+    let value = PgValueRef {
+        value: Some(b"0/1"),
+        row: None,
+        type_info: PgLsn::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert!(PgLsn::decode(value).is_err());
+}
@@ -12,7 +12,7 @@ use std::str::FromStr;
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum PgLTreeParseError {
-    /// LTree labels can only contain [A-Za-z0-9_]
+    /// LTree labels can only contain [A-Za-z0-9_-], and up to 1000 characters
     #[error("ltree label contains invalid characters")]
     InvalidLtreeLabel,
 
@@ -30,10 +30,11 @@ impl PgLTreeLabel {
         String: From<S>,
     {
         let label = String::from(label);
-        if label.len() <= 256
+        // hyphens are allowed since Postgres 16
+        if label.len() <= 1000
             && label
                 .bytes()
-                .all(|c| c.is_ascii_alphabetic() || c.is_ascii_digit() || c == b'_')
+                .all(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-')
         {
             Ok(Self(label))
         } else {
@@ -137,6 +138,11 @@ impl FromStr for PgLTree {
     type Err = PgLTreeParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // an empty path has no labels, rather than one empty label
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
         Ok(Self {
             labels: s
                 .split('.')
@@ -194,12 +200,14 @@ impl<'r> Decode<'r, Postgres> for PgLTree {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
         match value.format() {
             PgValueFormat::Binary => {
-                let bytes = value.as_bytes()?;
-                let version = i8::from_le_bytes([bytes[0]; 1]);
-                if version != 1 {
+                let (version, bytes) = value
+                    .as_bytes()?
+                    .split_first()
+                    .ok_or("ltree: expected a version byte")?;
+                if *version != 1 {
                     return Err(Box::new(PgLTreeParseError::InvalidLtreeVersion));
                 }
-                Ok(Self::from_str(std::str::from_utf8(&bytes[1..])?)?)
+                Ok(Self::from_str(std::str::from_utf8(bytes)?)?)
             }
             PgValueFormat::Text => Ok(Self::from_str(value.as_str()?)?),
         }
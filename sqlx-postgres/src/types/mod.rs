@@ -16,9 +16,14 @@
 //! | `()`                                  | VOID                                                 |
 //! | [`PgInterval`]                        | INTERVAL                                             |
 //! | [`PgRange<T>`](PgRange)               | INT8RANGE, INT4RANGE, TSRANGE, TSTZRANGE, DATERANGE, NUMRANGE |
+//! | [`PgMultiRange<T>`](PgMultiRange)     | INT8MULTIRANGE, INT4MULTIRANGE, TSMULTIRANGE, TSTZMULTIRANGE, DATEMULTIRANGE, NUMMULTIRANGE |
 //! | [`PgMoney`]                           | MONEY                                                |
 //! | [`PgLTree`]                           | LTREE                                                |
 //! | [`PgLQuery`]                          | LQUERY                                               |
+//! | [`PgHstore`], `HashMap<String, Option<String>>` | HSTORE                                     |
+//! | [`PgJsonPath`]                        | JSONPATH                                             |
+//! | [`PgLsn`]                             | PG_LSN                                               |
+//! | [`PgTid`]                             | TID                                                  |
 //!
 //! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
 //! Requires the `bigdecimal` Cargo feature flag.
@@ -98,6 +103,36 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `bit_vec::BitVec`                     | BIT, VARBIT                                          |
 //!
+//! ### [`pgvector`](https://github.com/pgvector/pgvector)
+//!
+//! Requires the `pgvector` Cargo feature flag, and the `vector` extension in the database.
+//!
+//! | Rust type                             | Postgres type(s)                                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | [`PgVector`]                          | VECTOR                                               |
+//! | [`PgHalfVector`]                      | HALFVEC                                              |
+//! | [`PgSparseVector`]                    | SPARSEVEC                                            |
+//!
+//! ### [`geo-types`](https://crates.io/crates/geo-types)
+//!
+//! Requires the `geo-types` Cargo feature flag, and the `postgis` extension in the database.
+//!
+//! | Rust type                             | Postgres type(s)                                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `geo_types::Geometry<f64>`            | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::Point<f64>`               | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::LineString<f64>`          | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::Polygon<f64>`             | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::MultiPoint<f64>`          | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::MultiLineString<f64>`     | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::MultiPolygon<f64>`        | GEOMETRY, GEOGRAPHY                                  |
+//! | `geo_types::GeometryCollection<f64>`  | GEOMETRY, GEOGRAPHY                                  |
+//!
+//! The SRID of a value is not part of `geo-types`: it is dropped when decoding, and values are
+//! sent without one, so they get the default of the column type (0 for GEOMETRY, 4326 for
+//! GEOGRAPHY). Use `ST_SetSRID($1, 4326)` in the query to assign another. Geometries with Z or M
+//! coordinates fail to decode.
+//!
 //! ### [`json`](https://crates.io/crates/serde_json)
 //!
 //! Requires the `json` Cargo feature flag.
@@ -135,8 +170,9 @@
 //! }
 //! ```
 //!
-//! Anonymous composite types are represented as tuples. Note that anonymous composites may only
-//! be returned and not sent to Postgres (this is a limitation of postgres).
+//! Anonymous composite types are represented as tuples, or as [`PgRecord`] if the types of the
+//! fields are only known at runtime. Note that anonymous composites may only be returned and not
+//! sent to Postgres (this is a limitation of postgres).
 //!
 //! # Arrays
 //!
@@ -176,17 +212,22 @@ mod array;
 mod bool;
 mod bytes;
 mod float;
+mod hstore;
 mod int;
 mod interval;
 mod lquery;
+mod lsn;
 mod ltree;
 // Not behind a Cargo feature because we require JSON in the driver implementation.
 mod json;
+mod json_path;
 mod money;
+mod multirange;
 mod oid;
 mod range;
 mod record;
 mod str;
+mod tid;
 mod tuple;
 mod void;
 
@@ -223,22 +264,37 @@ mod mac_address;
 #[cfg(feature = "bit-vec")]
 mod bit_vec;
 
+#[cfg(feature = "pgvector")]
+mod vector;
+
+#[cfg(feature = "geo-types")]
+mod geo_types;
+
 pub use array::PgHasArrayType;
+pub use hstore::PgHstore;
 pub use interval::PgInterval;
+pub use json_path::PgJsonPath;
 pub use lquery::PgLQuery;
 pub use lquery::PgLQueryLevel;
 pub use lquery::PgLQueryVariant;
 pub use lquery::PgLQueryVariantFlag;
+pub use lsn::PgLsn;
 pub use ltree::PgLTree;
 pub use ltree::PgLTreeLabel;
 pub use ltree::PgLTreeParseError;
 pub use money::PgMoney;
+pub use multirange::PgMultiRange;
 pub use oid::Oid;
 pub use range::PgRange;
+pub use record::PgRecord;
+pub use tid::PgTid;
 
 #[cfg(any(feature = "chrono", feature = "time"))]
 pub use time_tz::PgTimeTz;
 
+#[cfg(feature = "pgvector")]
+pub use vector::{PgHalfVector, PgSparseVector, PgVector};
+
 // used in derive(Type) for `struct`
 // but the interface is not considered part of the public API
 #[doc(hidden)]
@@ -249,8 +305,13 @@ fn array_compatible<E: Type<Postgres> + ?Sized>(ty: &PgTypeInfo) -> bool {
     // we require the declared type to be an _array_ with an
     // element type that is acceptable
     if let PgTypeKind::Array(element) = &ty.kind() {
-        return E::compatible(&element);
+        return domain_compatible::<E>(element);
     }
 
     false
 }
+
+// Type::compatible impl that also accepts a domain over an acceptable type
+fn domain_compatible<E: Type<Postgres> + ?Sized>(ty: &PgTypeInfo) -> bool {
+    E::compatible(ty) || ty.domain_base_type().map_or(false, E::compatible)
+}
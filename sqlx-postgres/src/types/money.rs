@@ -6,10 +6,7 @@ use crate::{
     {PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres},
 };
 use byteorder::{BigEndian, ByteOrder};
-use std::{
-    io,
-    ops::{Add, AddAssign, Sub, SubAssign},
-};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 /// The PostgreSQL [`MONEY`] type stores a currency amount with a fixed fractional
 /// precision. The fractional precision is determined by the database's
@@ -18,7 +15,9 @@ use std::{
 /// Data is read and written as 64-bit signed integers, and conversion into a
 /// decimal should be done using the right precision.
 ///
-/// Reading `MONEY` value in text format is not supported and will cause an error.
+/// In the text format, e.g. of queries without parameters, Postgres formats the value for the
+/// locale, like `-$1,234.56`. Since it always writes `frac_digits` fractional digits, the value is
+/// read from the digits, ignoring the currency symbol and separators.
 ///
 /// ### `locale_frac_digits`
 /// This parameter corresponds to the number of digits after the decimal separator.
@@ -133,8 +132,8 @@ impl PgMoney {
         let cents = decimal * multiplier;
 
         let money = cents.to_i64().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
                 "Provided BigDecimal could not convert to i64: overflow.",
             )
         })?;
@@ -176,22 +175,48 @@ impl Decode<'_, Postgres> for PgMoney {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         match value.format() {
             PgValueFormat::Binary => {
-                let cents = BigEndian::read_i64(value.as_bytes()?);
+                let bytes = value.as_bytes()?;
 
-                Ok(PgMoney(cents))
-            }
-            PgValueFormat::Text => {
-                let error = io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Reading a `MONEY` value in text format is not supported.",
-                );
+                if bytes.len() != 8 {
+                    return Err(format!("money: expected 8 bytes, got {}", bytes.len()).into());
+                }
 
-                Err(Box::new(error))
+                Ok(PgMoney(BigEndian::read_i64(bytes)))
             }
+            PgValueFormat::Text => parse_money(value.as_str()?),
         }
     }
 }
 
+/// Parse a `MONEY` value formatted for the locale of the server, like `-$1,234.56` or `($1.00)`.
+fn parse_money(s: &str) -> Result<PgMoney, BoxDynError> {
+    let mut value: i64 = 0;
+    let mut has_digits = false;
+
+    for digit in s.chars().filter_map(|c| c.to_digit(10)) {
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_sub(i64::from(digit)))
+            .ok_or_else(|| format!("money: {s:?} is out of range"))?;
+
+        has_digits = true;
+    }
+
+    if !has_digits {
+        return Err(format!("money: expected digits in {s:?}").into());
+    }
+
+    // digits are subtracted, so the minimum value can be parsed too
+    if s.contains(|c| c == '-' || c == '(') {
+        Ok(PgMoney(value))
+    } else {
+        value
+            .checked_neg()
+            .map(PgMoney)
+            .ok_or_else(|| format!("money: {s:?} is out of range").into())
+    }
+}
+
 impl Add<PgMoney> for PgMoney {
     type Output = PgMoney;
 
@@ -250,7 +275,7 @@ impl SubAssign<PgMoney> for PgMoney {
 
 #[cfg(test)]
 mod tests {
-    use super::PgMoney;
+    use super::{parse_money, PgMoney};
 
     #[test]
     fn adding_works() {
@@ -304,6 +329,25 @@ mod tests {
         money -= PgMoney(1);
     }
 
+    #[test]
+    fn parsing_text_works() {
+        assert_eq!(PgMoney(12345), parse_money("$123.45").unwrap());
+        assert_eq!(PgMoney(-123456), parse_money("-$1,234.56").unwrap());
+        assert_eq!(PgMoney(-100), parse_money("($1.00)").unwrap());
+        assert_eq!(PgMoney(123456), parse_money("1.234,56 €").unwrap());
+        assert_eq!(
+            PgMoney(i64::MIN),
+            parse_money("-$92,233,720,368,547,758.08").unwrap()
+        );
+        assert_eq!(
+            PgMoney(i64::MAX),
+            parse_money("$92,233,720,368,547,758.07").unwrap()
+        );
+
+        assert!(parse_money("$92,233,720,368,547,758.08").is_err());
+        assert!(parse_money("$").is_err());
+    }
+
     #[test]
     #[cfg(feature = "bigdecimal")]
     fn conversion_to_bigdecimal_works() {
@@ -0,0 +1,389 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+use sqlx_core::bytes::Buf;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::{PgRange, Type};
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A set of non-overlapping ranges, for the multirange types of PostgreSQL 14 and later.
+///
+/// The ranges that are read from the database are ordered and don't overlap or touch, as
+/// PostgreSQL normalizes multiranges. The ranges that are written to the database don't need
+/// to be normalized.
+///
+/// ```rust
+/// # use sqlx_core::postgres::types::{PgMultiRange, PgRange};
+/// let multirange = PgMultiRange::from(vec![PgRange::from(1..3), PgRange::from(5..7)]);
+///
+/// assert_eq!(multirange.to_string(), "{[1,3),[5,7)}");
+/// ```
+///
+/// See <https://www.postgresql.org/docs/current/rangetypes.html>
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PgMultiRange<T>(pub Vec<PgRange<T>>);
+
+impl<T> Default for PgMultiRange<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Deref for PgMultiRange<T> {
+    type Target = Vec<PgRange<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for PgMultiRange<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<PgRange<T>>> for PgMultiRange<T> {
+    fn from(ranges: Vec<PgRange<T>>) -> Self {
+        Self(ranges)
+    }
+}
+
+impl<T, R> FromIterator<R> for PgMultiRange<T>
+where
+    R: Into<PgRange<T>>,
+{
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T> IntoIterator for PgMultiRange<T> {
+    type Item = PgRange<T>;
+    type IntoIter = std::vec::IntoIter<PgRange<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Type<Postgres> for PgMultiRange<i32> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT4_MULTIRANGE
+    }
+}
+
+impl Type<Postgres> for PgMultiRange<i64> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::INT8_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl Type<Postgres> for PgMultiRange<bigdecimal::BigDecimal> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl Type<Postgres> for PgMultiRange<rust_decimal::Decimal> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for PgMultiRange<chrono::NaiveDate> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::DATE_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for PgMultiRange<chrono::NaiveDateTime> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TS_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> Type<Postgres> for PgMultiRange<chrono::DateTime<Tz>> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TSTZ_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "time")]
+impl Type<Postgres> for PgMultiRange<time::Date> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::DATE_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "time")]
+impl Type<Postgres> for PgMultiRange<time::PrimitiveDateTime> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TS_MULTIRANGE
+    }
+}
+
+#[cfg(feature = "time")]
+impl Type<Postgres> for PgMultiRange<time::OffsetDateTime> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TSTZ_MULTIRANGE
+    }
+}
+
+impl PgHasArrayType for PgMultiRange<i32> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::INT4_MULTIRANGE_ARRAY
+    }
+}
+
+impl PgHasArrayType for PgMultiRange<i64> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::INT8_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl PgHasArrayType for PgMultiRange<bigdecimal::BigDecimal> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl PgHasArrayType for PgMultiRange<rust_decimal::Decimal> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::NUM_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl PgHasArrayType for PgMultiRange<chrono::NaiveDate> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::DATE_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl PgHasArrayType for PgMultiRange<chrono::NaiveDateTime> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TS_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> PgHasArrayType for PgMultiRange<chrono::DateTime<Tz>> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TSTZ_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "time")]
+impl PgHasArrayType for PgMultiRange<time::Date> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::DATE_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "time")]
+impl PgHasArrayType for PgMultiRange<time::PrimitiveDateTime> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TS_MULTIRANGE_ARRAY
+    }
+}
+
+#[cfg(feature = "time")]
+impl PgHasArrayType for PgMultiRange<time::OffsetDateTime> {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TSTZ_MULTIRANGE_ARRAY
+    }
+}
+
+impl<'q, T> Encode<'q, Postgres> for PgMultiRange<T>
+where
+    T: Encode<'q, Postgres>,
+{
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // https://github.com/postgres/postgres/blob/REL_14_0/src/backend/utils/adt/multirangetypes.c#L376
+
+        buf.extend(&(self.0.len() as i32).to_be_bytes());
+
+        // every range is prefixed with its length
+        for range in &self.0 {
+            buf.encode(range);
+        }
+
+        IsNull::No
+    }
+}
+
+impl<'r, T> Decode<'r, Postgres> for PgMultiRange<T>
+where
+    T: Type<Postgres> + for<'a> Decode<'a, Postgres>,
+    PgRange<T>: Type<Postgres>,
+{
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        // the ranges are decoded as the range type of the multirange, which has the bounds'
+        // type as its element type
+        let range_ty = PgRange::<T>::type_info();
+
+        match value.format {
+            PgValueFormat::Binary => {
+                let mut buf = value.as_bytes()?;
+
+                if buf.len() < 4 {
+                    return Err("multirange: unexpected end of data".into());
+                }
+
+                let count = buf.get_i32();
+                let mut ranges = Vec::with_capacity(usize::try_from(count).unwrap_or(0));
+
+                for _ in 0..count {
+                    if buf.len() < 4 || buf.len() - 4 < (&buf[..4]).get_i32() as usize {
+                        return Err("multirange: unexpected end of data".into());
+                    }
+
+                    let range = PgValueRef::get(&mut buf, PgValueFormat::Binary, range_ty.clone());
+
+                    ranges.push(PgRange::decode(range)?);
+                }
+
+                Ok(PgMultiRange(ranges))
+            }
+
+            PgValueFormat::Text => {
+                let ranges = split_ranges(value.as_str()?)?
+                    .into_iter()
+                    .map(|range| {
+                        PgRange::decode(PgValueRef {
+                            type_info: range_ty.clone(),
+                            format: PgValueFormat::Text,
+                            value: Some(range.as_bytes()),
+                            row: None,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(PgMultiRange(ranges))
+            }
+        }
+    }
+}
+
+/// Split the textual format of a multirange, e.g. `{[1,3),[5,7)}`, into its ranges.
+fn split_ranges(s: &str) -> Result<Vec<&str>, BoxDynError> {
+    // https://github.com/postgres/postgres/blob/REL_14_0/src/backend/utils/adt/multirangetypes.c#L118
+
+    let mut rest = s
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or_else(|| format!("multirange: expected `{{` and `}}` around {s:?}"))?;
+
+    let mut ranges = Vec::new();
+
+    loop {
+        rest = rest.trim_start();
+
+        if rest.is_empty() {
+            break;
+        }
+
+        if !rest.starts_with(['[', '(']) {
+            return Err(format!("multirange: expected `[` or `(` at {rest:?}").into());
+        }
+
+        let mut in_quotes = false;
+        let mut in_escape = false;
+        let mut end = None;
+
+        for (i, ch) in rest.char_indices() {
+            match ch {
+                _ if in_escape => in_escape = false,
+                '\\' => in_escape = true,
+                '"' => in_quotes = !in_quotes,
+                ')' | ']' if !in_quotes => {
+                    end = Some(i + 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let end = end.ok_or_else(|| format!("multirange: unterminated range in {s:?}"))?;
+
+        ranges.push(&rest[..end]);
+
+        rest = rest[end..].trim_start();
+
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after;
+        } else if !rest.is_empty() {
+            return Err(format!("multirange: expected `,` at {rest:?}").into());
+        }
+    }
+
+    Ok(ranges)
+}
+
+impl<T> Display for PgMultiRange<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+
+        for (i, range) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{range}")?;
+        }
+
+        f.write_str("}")
+    }
+}
+
+#[test]
+fn test_encode_decode_multirange() {
+    let multirange = PgMultiRange::from(vec![PgRange::from(1..3), PgRange::from(5..)]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(&multirange, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\0\0\x02\0\0\0\x11\x02\0\0\0\x04\0\0\0\x01\0\0\0\x04\0\0\0\x03\0\0\0\x09\x12\0\0\0\x04\0\0\0\x05"
+            .as_slice()
+    );
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgMultiRange::<i32>::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgMultiRange::<i32>::decode(value).unwrap(), multirange);
+}
+
+#[test]
+fn test_split_multirange() {
+    assert_eq!(split_ranges("{[1,3), [5,7)}").unwrap(), ["[1,3)", "[5,7)"]);
+    assert_eq!(
+        split_ranges(r#"{["2020-01-01 00:00:00","a\")"]}"#).unwrap(),
+        [r#"["2020-01-01 00:00:00","a\")"]"#]
+    );
+    assert_eq!(split_ranges("{}").unwrap(), Vec::<&str>::new());
+    assert!(split_ranges("[1,3)").is_err());
+    assert!(split_ranges("{[1,3)").is_err());
+    assert!(split_ranges("{[1,3) [5,7)}").is_err());
+}
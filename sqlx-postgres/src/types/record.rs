@@ -2,12 +2,12 @@ use sqlx_core::bytes::Buf;
 
 use crate::decode::Decode;
 use crate::encode::Encode;
-use crate::error::{mismatched_types, BoxDynError};
+use crate::error::{mismatched_types, BoxDynError, Error};
 use crate::type_info::TypeInfo;
 use crate::type_info::{PgType, PgTypeKind};
 use crate::types::Oid;
-use crate::types::Type;
-use crate::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::types::{array_compatible, domain_compatible, Type};
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 
 #[doc(hidden)]
 pub struct PgRecordEncoder<'a> {
@@ -63,6 +63,8 @@ pub struct PgRecordDecoder<'r> {
     typ: PgTypeInfo,
     fmt: PgValueFormat,
     ind: usize,
+    // whether the last field of the text format was read
+    end: bool,
 }
 
 impl<'r> PgRecordDecoder<'r> {
@@ -78,8 +80,7 @@ impl<'r> PgRecordDecoder<'r> {
             }
 
             PgValueFormat::Text => {
-                // remove the enclosing `(` .. `)`
-                buf = &buf[1..(buf.len() - 1)];
+                buf = strip_parens(buf)?;
             }
         }
 
@@ -88,6 +89,7 @@ impl<'r> PgRecordDecoder<'r> {
             fmt,
             typ,
             ind: 0,
+            end: false,
         })
     }
 
@@ -96,7 +98,13 @@ impl<'r> PgRecordDecoder<'r> {
     where
         T: for<'a> Decode<'a, Postgres> + Type<Postgres>,
     {
-        if self.buf.is_empty() {
+        let exhausted = match self.fmt {
+            PgValueFormat::Binary => self.buf.is_empty(),
+            // the last field can be empty, for `NULL`
+            PgValueFormat::Text => self.end,
+        };
+
+        if exhausted {
             return Err(format!("no field `{0}` found on record", self.ind).into());
         }
 
@@ -126,7 +134,7 @@ impl<'r> PgRecordDecoder<'r> {
                 };
 
                 if let Some(ty) = &element_type_opt {
-                    if !ty.is_null() && !T::compatible(ty) {
+                    if !ty.is_null() && !domain_compatible::<T>(ty) {
                         return Err(mismatched_types::<Postgres, T>(ty));
                     }
                 }
@@ -141,52 +149,9 @@ impl<'r> PgRecordDecoder<'r> {
             }
 
             PgValueFormat::Text => {
-                let mut element = String::new();
-                let mut quoted = false;
-                let mut in_quotes = false;
-                let mut in_escape = false;
-                let mut prev_ch = '\0';
-
-                while !self.buf.is_empty() {
-                    let ch = self.buf.get_u8() as char;
-                    match ch {
-                        _ if in_escape => {
-                            element.push(ch);
-                            in_escape = false;
-                        }
-
-                        '"' if in_quotes => {
-                            in_quotes = false;
-                        }
-
-                        '"' => {
-                            in_quotes = true;
-                            quoted = true;
-
-                            if prev_ch == '"' {
-                                element.push('"')
-                            }
-                        }
-
-                        '\\' if !in_escape => {
-                            in_escape = true;
-                        }
-
-                        ',' if !in_quotes => break,
-
-                        _ => {
-                            element.push(ch);
-                        }
-                    }
-                    prev_ch = ch;
-                }
+                let element = read_text_field(&mut self.buf, &mut self.end);
 
-                let buf = if element.is_empty() && !quoted {
-                    // completely empty input means NULL
-                    None
-                } else {
-                    Some(element.as_bytes())
-                };
+                self.ind += 1;
 
                 // NOTE: we do not call [`accepts`] or give a chance to from a user as
                 //       TEXT sequences are not strongly typed
@@ -196,10 +161,279 @@ impl<'r> PgRecordDecoder<'r> {
                     //       we could use.
                     type_info: PgTypeInfo::with_oid(Oid(0)),
                     format: self.fmt,
-                    value: buf,
+                    value: element.as_deref(),
                     row: None,
                 })
             }
         }
     }
 }
+
+/// A record whose fields are only known at runtime, e.g. from `SELECT (a, b)` or `ROW(a, b)`.
+///
+/// The fields are accessed by position, like the columns of a row. A tuple can be decoded
+/// instead if the types of the fields are known up front.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::postgres::PgConnection) -> sqlx::Result<()> {
+/// use sqlx::postgres::types::PgRecord;
+///
+/// let record: PgRecord = sqlx::query_scalar("SELECT ROW(1, 'foo')")
+///     .fetch_one(conn)
+///     .await?;
+///
+/// let id: i32 = record.try_get(0)?;
+/// let name: &str = record.try_get(1)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// In the text format, which is used by queries without arguments that aren't prepared,
+/// the types of the fields aren't known, so they aren't checked either. `ROW()` and
+/// `ROW(NULL)` are also both read as a record with a single `NULL` field.
+#[derive(Debug, Clone)]
+pub struct PgRecord {
+    format: PgValueFormat,
+    fields: Vec<PgRecordField>,
+}
+
+#[derive(Debug, Clone)]
+struct PgRecordField {
+    type_info: PgTypeInfo,
+    value: Option<Vec<u8>>,
+}
+
+impl PgRecord {
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if the record has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Decode the field at `index`, after checking that its type is compatible with `T`.
+    pub fn try_get<'r, T>(&'r self, index: usize) -> Result<T, Error>
+    where
+        T: Decode<'r, Postgres> + Type<Postgres>,
+    {
+        let field = self.field(index)?;
+        let ty = &field.type_info;
+
+        // the types of fields in the text format, and of custom types, are unknown
+        let known = !matches!(ty.0, PgType::DeclareWithOid(_));
+
+        if known && !ty.is_null() && !domain_compatible::<T>(ty) {
+            return Err(Error::Decode(mismatched_types::<Postgres, T>(ty)));
+        }
+
+        self.decode(field)
+    }
+
+    /// Decode the field at `index`, without checking its type.
+    pub fn try_get_unchecked<'r, T>(&'r self, index: usize) -> Result<T, Error>
+    where
+        T: Decode<'r, Postgres>,
+    {
+        self.decode(self.field(index)?)
+    }
+
+    fn field(&self, index: usize) -> Result<&PgRecordField, Error> {
+        self.fields
+            .get(index)
+            .ok_or_else(|| Error::Decode(format!("no field `{index}` found on record").into()))
+    }
+
+    fn decode<'r, T>(&self, field: &'r PgRecordField) -> Result<T, Error>
+    where
+        T: Decode<'r, Postgres>,
+    {
+        T::decode(PgValueRef {
+            value: field.value.as_deref(),
+            row: None,
+            type_info: field.type_info.clone(),
+            format: self.format,
+        })
+        .map_err(Error::Decode)
+    }
+}
+
+impl Type<Postgres> for PgRecord {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::RECORD
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        match &ty.0 {
+            PgType::Record => true,
+            PgType::Custom(ty) => matches!(ty.kind, PgTypeKind::Composite(_)),
+            _ => false,
+        }
+    }
+}
+
+impl PgHasArrayType for PgRecord {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::RECORD_ARRAY
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        // also accept arrays of named composite types, e.g. from `array_agg(some_table)`
+        array_compatible::<Self>(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgRecord {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let format = value.format();
+        let mut buf = value.as_bytes()?;
+        let mut fields = Vec::new();
+
+        match format {
+            PgValueFormat::Binary => {
+                if buf.len() < 4 {
+                    return Err("record: unexpected end of data".into());
+                }
+
+                let count = buf.get_u32();
+
+                // the fields of a named composite type have been fetched already
+                let composite = match &value.type_info.0 {
+                    PgType::Custom(ty) => match &ty.kind {
+                        PgTypeKind::Composite(composite) => Some(composite),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                for index in 0..count {
+                    if buf.len() < 8 {
+                        return Err("record: unexpected end of data".into());
+                    }
+
+                    let oid = Oid(buf.get_u32());
+                    let len = (&buf[..4]).get_i32();
+
+                    if len > 0 && buf.len() - 4 < len as usize {
+                        return Err("record: unexpected end of data".into());
+                    }
+
+                    let type_info = composite
+                        .and_then(|composite| composite.get(index as usize))
+                        .map(|(_, ty)| ty.clone())
+                        .or_else(|| PgTypeInfo::try_from_oid(oid))
+                        .unwrap_or_else(|| PgTypeInfo::with_oid(oid));
+
+                    let field = PgValueRef::get(&mut buf, format, type_info);
+
+                    fields.push(PgRecordField {
+                        value: field.value.map(<[u8]>::to_vec),
+                        type_info: field.type_info,
+                    });
+                }
+            }
+
+            PgValueFormat::Text => {
+                buf = strip_parens(buf)?;
+
+                let mut end = false;
+
+                while !end {
+                    fields.push(PgRecordField {
+                        value: read_text_field(&mut buf, &mut end),
+                        type_info: PgTypeInfo::with_oid(Oid(0)),
+                    });
+                }
+            }
+        }
+
+        Ok(PgRecord { format, fields })
+    }
+}
+
+/// Remove the enclosing `(` .. `)` of the text format of a record.
+fn strip_parens(buf: &[u8]) -> Result<&[u8], BoxDynError> {
+    buf.strip_prefix(b"(")
+        .and_then(|buf| buf.strip_suffix(b")"))
+        .ok_or_else(|| "expected `(` and `)` around record".into())
+}
+
+/// Read the next field of the text format of a record, which is `None` for `NULL`.
+///
+/// `end` is set after the last field.
+fn read_text_field(buf: &mut &[u8], end: &mut bool) -> Option<Vec<u8>> {
+    let mut element = Vec::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut in_escape = false;
+    let mut prev_ch = b'\0';
+
+    *end = true;
+
+    while !buf.is_empty() {
+        let ch = buf.get_u8();
+        match ch {
+            _ if in_escape => {
+                element.push(ch);
+                in_escape = false;
+            }
+
+            b'"' if in_quotes => {
+                in_quotes = false;
+            }
+
+            b'"' => {
+                in_quotes = true;
+                quoted = true;
+
+                if prev_ch == b'"' {
+                    element.push(b'"')
+                }
+            }
+
+            b'\\' if !in_escape => {
+                in_escape = true;
+            }
+
+            b',' if !in_quotes => {
+                *end = false;
+                break;
+            }
+
+            _ => {
+                element.push(ch);
+            }
+        }
+        prev_ch = ch;
+    }
+
+    if element.is_empty() && !quoted {
+        // completely empty input means NULL
+        None
+    } else {
+        Some(element)
+    }
+}
+
+#[test]
+fn test_read_text_fields() {
+    let mut buf = strip_parens(br#"(1,,"a ""b"", c",)"#).unwrap();
+    let mut end = false;
+    let mut fields = Vec::new();
+
+    while !end {
+        fields.push(read_text_field(&mut buf, &mut end));
+    }
+
+    assert_eq!(
+        fields,
+        [
+            Some(b"1".to_vec()),
+            None,
+            Some(br#"a "b", c"#.to_vec()),
+            None
+        ]
+    );
+}
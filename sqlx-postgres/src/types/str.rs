@@ -18,6 +18,8 @@ impl Type<Postgres> for str {
             PgTypeInfo::BPCHAR,
             PgTypeInfo::VARCHAR,
             PgTypeInfo::UNKNOWN,
+            // Since `citext` is enabled by an extension, it does not have a stable OID.
+            PgTypeInfo::with_name("citext"),
         ]
         .contains(ty)
     }
@@ -49,7 +51,8 @@ impl PgHasArrayType for &'_ str {
     }
 
     fn array_compatible(ty: &PgTypeInfo) -> bool {
-        array_compatible::<&str>(ty)
+        // checked first, as the element type of an unresolved type is unknown
+        *ty == PgTypeInfo::with_name("_citext") || array_compatible::<&str>(ty)
     }
 }
 
@@ -0,0 +1,152 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// The physical location of a row version in its table, the [`tid`] type of the `ctid` column.
+///
+/// Displayed and parsed in the textual format of Postgres, `(block,offset)` (e.g. `(0,1)`).
+///
+/// Tuple identifiers are ordered by block, and then by offset within the block.
+///
+/// [`tid`]: https://www.postgresql.org/docs/current/datatype-oid.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgTid {
+    /// The number of the block (page) of the table.
+    pub block: u32,
+
+    /// The position of the row version within its block, starting at 1.
+    pub offset: u16,
+}
+
+impl Display for PgTid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.block, self.offset)
+    }
+}
+
+impl FromStr for PgTid {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (block, offset) = s
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(','))
+            .ok_or_else(|| format!("invalid tid {s:?}: expected `(block,offset)`"))?;
+
+        Ok(PgTid {
+            block: block.trim().parse()?,
+            offset: offset.trim().parse()?,
+        })
+    }
+}
+
+impl Type<Postgres> for PgTid {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::TID
+    }
+}
+
+impl PgHasArrayType for PgTid {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::TID_ARRAY
+    }
+}
+
+impl Encode<'_, Postgres> for PgTid {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        buf.extend(&self.block.to_be_bytes());
+        buf.extend(&self.offset.to_be_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Postgres> for PgTid {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let bytes = value.as_bytes()?;
+
+                if bytes.len() != 6 {
+                    return Err(
+                        format!("invalid tid: expected 6 bytes, got {}", bytes.len()).into(),
+                    );
+                }
+
+                let (block, offset) = bytes.split_at(4);
+
+                Ok(PgTid {
+                    block: u32::from_be_bytes(block.try_into()?),
+                    offset: u16::from_be_bytes(offset.try_into()?),
+                })
+            }
+
+            PgValueFormat::Text => value.as_str()?.parse(),
+        }
+    }
+}
+
+#[test]
+fn test_tid_display_and_parse() {
+    let tid: PgTid = "(12,3)".parse().unwrap();
+
+    assert_eq!(
+        tid,
+        PgTid {
+            block: 12,
+            offset: 3
+        }
+    );
+    assert_eq!(tid.to_string(), "(12,3)");
+
+    assert!("12,3".parse::<PgTid>().is_err());
+    assert!("(12)".parse::<PgTid>().is_err());
+    assert!("(12,70000)".parse::<PgTid>().is_err());
+
+    assert!(
+        PgTid {
+            block: 1,
+            offset: 9
+        } < PgTid {
+            block: 2,
+            offset: 1
+        }
+    );
+    assert!(
+        PgTid {
+            block: 2,
+            offset: 1
+        } < PgTid {
+            block: 2,
+            offset: 2
+        }
+    );
+}
+
+#[test]
+fn test_encode_decode_tid() {
+    let tid = PgTid {
+        block: 0x0102_0304,
+        offset: 0x0506,
+    };
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(tid, &mut buf);
+
+    assert_eq!(&**buf, b"\x01\x02\x03\x04\x05\x06");
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgTid::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgTid::decode(value).unwrap(), tid);
+}
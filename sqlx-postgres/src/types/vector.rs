@@ -0,0 +1,512 @@
+use std::ops::Deref;
+
+use byteorder::{BigEndian, ByteOrder};
+use half::f16;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A vector of single-precision floats, from the [`pgvector`] extension (`vector`).
+///
+/// The textual format is a list of the elements between brackets, e.g. `[1,2,3]`.
+///
+/// [`pgvector`]: https://github.com/pgvector/pgvector
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgVector(Vec<f32>);
+
+impl PgVector {
+    /// Returns the elements of this vector.
+    pub fn as_slice(&self) -> &[f32] {
+        &self.0
+    }
+
+    /// Returns the elements of this vector.
+    pub fn into_vec(self) -> Vec<f32> {
+        self.0
+    }
+}
+
+impl From<Vec<f32>> for PgVector {
+    fn from(elements: Vec<f32>) -> Self {
+        PgVector(elements)
+    }
+}
+
+impl From<&[f32]> for PgVector {
+    fn from(elements: &[f32]) -> Self {
+        PgVector(elements.to_vec())
+    }
+}
+
+impl From<PgVector> for Vec<f32> {
+    fn from(vector: PgVector) -> Self {
+        vector.0
+    }
+}
+
+impl Deref for PgVector {
+    type Target = [f32];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Type<Postgres> for PgVector {
+    fn type_info() -> PgTypeInfo {
+        // Since `vector` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("vector")
+    }
+}
+
+impl PgHasArrayType for PgVector {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_vector")
+    }
+}
+
+impl Encode<'_, Postgres> for PgVector {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // the number of dimensions and a reserved field
+        buf.extend(&(self.0.len() as i16).to_be_bytes());
+        buf.extend(&0_i16.to_be_bytes());
+
+        for element in &self.0 {
+            buf.extend(&element.to_be_bytes());
+        }
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        4 + self.0.len() * 4
+    }
+}
+
+impl Decode<'_, Postgres> for PgVector {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let elements = decode_header(value.as_bytes()?, 4)?;
+
+                Ok(PgVector(
+                    elements.chunks_exact(4).map(BigEndian::read_f32).collect(),
+                ))
+            }
+
+            PgValueFormat::Text => Ok(PgVector(parse_elements(value.as_str()?)?)),
+        }
+    }
+}
+
+/// A vector of half-precision floats, from the [`pgvector`] extension (`halfvec`).
+///
+/// This takes half the space of a [`PgVector`], at the cost of precision. The elements are
+/// converted from and to `f32`, which rounds them to the nearest half-precision float.
+///
+/// [`pgvector`]: https://github.com/pgvector/pgvector
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgHalfVector(Vec<f16>);
+
+impl PgHalfVector {
+    /// Create a vector from single-precision floats, which are rounded to half-precision.
+    pub fn from_f32_slice(elements: &[f32]) -> Self {
+        PgHalfVector(elements.iter().copied().map(f16::from_f32).collect())
+    }
+
+    /// Returns the number of elements of this vector.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the elements of this vector, as single-precision floats.
+    pub fn to_f32_vec(&self) -> Vec<f32> {
+        self.0.iter().map(|element| element.to_f32()).collect()
+    }
+}
+
+impl From<Vec<f32>> for PgHalfVector {
+    fn from(elements: Vec<f32>) -> Self {
+        PgHalfVector::from_f32_slice(&elements)
+    }
+}
+
+impl From<&[f32]> for PgHalfVector {
+    fn from(elements: &[f32]) -> Self {
+        PgHalfVector::from_f32_slice(elements)
+    }
+}
+
+impl Type<Postgres> for PgHalfVector {
+    fn type_info() -> PgTypeInfo {
+        // Since `halfvec` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("halfvec")
+    }
+}
+
+impl PgHasArrayType for PgHalfVector {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_halfvec")
+    }
+}
+
+impl Encode<'_, Postgres> for PgHalfVector {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // the number of dimensions and a reserved field
+        buf.extend(&(self.0.len() as i16).to_be_bytes());
+        buf.extend(&0_i16.to_be_bytes());
+
+        for element in &self.0 {
+            buf.extend(&element.to_bits().to_be_bytes());
+        }
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        4 + self.0.len() * 2
+    }
+}
+
+impl Decode<'_, Postgres> for PgHalfVector {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let elements = decode_header(value.as_bytes()?, 2)?;
+
+                Ok(PgHalfVector(
+                    elements
+                        .chunks_exact(2)
+                        .map(|bits| f16::from_bits(BigEndian::read_u16(bits)))
+                        .collect(),
+                ))
+            }
+
+            PgValueFormat::Text => Ok(PgHalfVector::from_f32_slice(&parse_elements(
+                value.as_str()?,
+            )?)),
+        }
+    }
+}
+
+/// A sparse vector of single-precision floats, from the [`pgvector`] extension (`sparsevec`).
+///
+/// Only the elements that are not zero are stored, with their (zero-based) index.
+///
+/// The textual format lists the elements with their one-based index between braces, followed
+/// by the number of dimensions, e.g. `{1:1.5,3:2}/5` for `[1.5,0,2,0,0]`.
+///
+/// [`pgvector`]: https://github.com/pgvector/pgvector
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgSparseVector {
+    dimensions: u32,
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl PgSparseVector {
+    /// Create a sparse vector from the elements of a dense vector.
+    pub fn from_dense(elements: &[f32]) -> Self {
+        let (indices, values) = elements
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0.0)
+            .map(|(index, value)| (index as u32, *value))
+            .unzip();
+
+        PgSparseVector {
+            dimensions: elements.len() as u32,
+            indices,
+            values,
+        }
+    }
+
+    /// Create a sparse vector with `dimensions` elements, of which the ones at `indices` are
+    /// `values` and the others are zero.
+    ///
+    /// Returns `None` if `indices` and `values` have different lengths, or if the indices are
+    /// not strictly increasing and smaller than `dimensions`.
+    pub fn from_parts(dimensions: u32, indices: Vec<u32>, values: Vec<f32>) -> Option<Self> {
+        let increasing = indices.windows(2).all(|pair| pair[0] < pair[1]);
+        let in_bounds = indices.last().map_or(true, |index| *index < dimensions);
+
+        if indices.len() != values.len() || !increasing || !in_bounds {
+            return None;
+        }
+
+        Some(PgSparseVector {
+            dimensions,
+            indices,
+            values,
+        })
+    }
+
+    /// Returns the number of dimensions of this vector.
+    pub fn dimensions(&self) -> u32 {
+        self.dimensions
+    }
+
+    /// Returns the zero-based indices of the elements that are not zero, in increasing order.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Returns the elements that are not zero, in the same order as [`indices`][Self::indices].
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Returns every element of this vector, including the zeros.
+    pub fn to_dense(&self) -> Vec<f32> {
+        let mut elements = vec![0.0; self.dimensions as usize];
+
+        for (index, value) in self.indices.iter().zip(&self.values) {
+            elements[*index as usize] = *value;
+        }
+
+        elements
+    }
+}
+
+impl Type<Postgres> for PgSparseVector {
+    fn type_info() -> PgTypeInfo {
+        // Since `sparsevec` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("sparsevec")
+    }
+}
+
+impl PgHasArrayType for PgSparseVector {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_sparsevec")
+    }
+}
+
+impl Encode<'_, Postgres> for PgSparseVector {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // the number of dimensions, the number of elements that are not zero, and a
+        // reserved field
+        buf.extend(&(self.dimensions as i32).to_be_bytes());
+        buf.extend(&(self.indices.len() as i32).to_be_bytes());
+        buf.extend(&0_i32.to_be_bytes());
+
+        for index in &self.indices {
+            buf.extend(&(*index as i32).to_be_bytes());
+        }
+
+        for value in &self.values {
+            buf.extend(&value.to_be_bytes());
+        }
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        12 + self.indices.len() * 8
+    }
+}
+
+impl Decode<'_, Postgres> for PgSparseVector {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        let vector = match value.format() {
+            PgValueFormat::Binary => {
+                let buf = value.as_bytes()?;
+
+                if buf.len() < 12 {
+                    return Err(format!(
+                        "sparsevec: expected at least 12 bytes, got {}",
+                        buf.len()
+                    )
+                    .into());
+                }
+
+                let dimensions = u32::try_from(BigEndian::read_i32(buf))?;
+                let len = usize::try_from(BigEndian::read_i32(&buf[4..]))?;
+                let buf = &buf[12..];
+
+                if buf.len() != len * 8 {
+                    return Err(format!(
+                        "sparsevec: expected {} bytes for {len} elements, got {}",
+                        len * 8,
+                        buf.len()
+                    )
+                    .into());
+                }
+
+                let (indices, values) = buf.split_at(len * 4);
+
+                PgSparseVector::from_parts(
+                    dimensions,
+                    indices
+                        .chunks_exact(4)
+                        .map(|index| u32::try_from(BigEndian::read_i32(index)))
+                        .collect::<Result<_, _>>()?,
+                    values.chunks_exact(4).map(BigEndian::read_f32).collect(),
+                )
+            }
+
+            PgValueFormat::Text => parse_sparse(value.as_str()?)?,
+        };
+
+        vector.ok_or_else(|| "sparsevec: invalid indices".into())
+    }
+}
+
+/// Check the header of a binary `vector` or `halfvec`, and return the elements.
+fn decode_header(buf: &[u8], element_size: usize) -> Result<&[u8], BoxDynError> {
+    if buf.len() < 4 {
+        return Err(format!("vector: expected at least 4 bytes, got {}", buf.len()).into());
+    }
+
+    let dimensions = usize::try_from(BigEndian::read_i16(buf))?;
+    let elements = &buf[4..];
+
+    if elements.len() != dimensions * element_size {
+        return Err(format!(
+            "vector: expected {} bytes for {dimensions} dimensions, got {}",
+            dimensions * element_size,
+            elements.len()
+        )
+        .into());
+    }
+
+    Ok(elements)
+}
+
+/// Parse the textual format of a `vector` or `halfvec`, e.g. `[1,2,3]`.
+fn parse_elements(s: &str) -> Result<Vec<f32>, BoxDynError> {
+    let elements = s
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| format!("vector: expected brackets around {s:?}"))?;
+
+    if elements.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(elements
+        .split(',')
+        .map(|element| element.trim().parse())
+        .collect::<Result<_, _>>()?)
+}
+
+/// Parse the textual format of a `sparsevec`, e.g. `{1:1.5,3:2}/5`.
+fn parse_sparse(s: &str) -> Result<Option<PgSparseVector>, BoxDynError> {
+    let (elements, dimensions) = s
+        .strip_prefix('{')
+        .and_then(|s| s.split_once("}/"))
+        .ok_or_else(|| format!("sparsevec: expected `{{...}}/dimensions`, got {s:?}"))?;
+
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+
+    for element in elements
+        .split(',')
+        .filter(|element| !element.trim().is_empty())
+    {
+        let (index, value) = element
+            .split_once(':')
+            .ok_or_else(|| format!("sparsevec: expected `index:value`, got {element:?}"))?;
+
+        // the indices are one-based in the textual format
+        let index: u32 = index.trim().parse()?;
+
+        indices.push(
+            index
+                .checked_sub(1)
+                .ok_or("sparsevec: indices start at 1")?,
+        );
+        values.push(value.trim().parse()?);
+    }
+
+    Ok(PgSparseVector::from_parts(
+        dimensions.trim().parse()?,
+        indices,
+        values,
+    ))
+}
+
+#[test]
+fn test_encode_decode_vector() {
+    let vector = PgVector::from(vec![1.0, -2.5, 3.0]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(&vector, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\x03\0\0\x3f\x80\0\0\xc0\x20\0\0\x40\x40\0\0".as_slice()
+    );
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgVector::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgVector::decode(value).unwrap(), vector);
+    assert_eq!(parse_elements("[1,-2.5, 3]").unwrap(), vector.as_slice());
+    assert_eq!(parse_elements("[]").unwrap(), Vec::<f32>::new());
+    assert!(parse_elements("1,2").is_err());
+}
+
+#[test]
+fn test_encode_decode_half_vector() {
+    let vector = PgHalfVector::from(vec![1.0, -2.5, 0.1]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(&vector, &mut buf);
+
+    assert_eq!(&**buf, b"\0\x03\0\0\x3c\0\xc1\0\x2e\x66".as_slice());
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgHalfVector::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    let decoded = PgHalfVector::decode(value).unwrap();
+
+    assert_eq!(decoded, vector);
+    assert_eq!(decoded.to_f32_vec()[..2], [1.0, -2.5]);
+}
+
+#[test]
+fn test_encode_decode_sparse_vector() {
+    let vector = PgSparseVector::from_dense(&[1.5, 0.0, 2.0, 0.0, 0.0]);
+
+    assert_eq!(vector.dimensions(), 5);
+    assert_eq!(vector.indices(), [0, 2]);
+    assert_eq!(vector.values(), [1.5, 2.0]);
+    assert_eq!(vector.to_dense(), [1.5, 0.0, 2.0, 0.0, 0.0]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = Encode::<Postgres>::encode(&vector, &mut buf);
+
+    let value = PgValueRef {
+        value: Some(&buf),
+        row: None,
+        type_info: PgSparseVector::type_info(),
+        format: PgValueFormat::Binary,
+    };
+
+    assert_eq!(PgSparseVector::decode(value).unwrap(), vector);
+    assert_eq!(parse_sparse("{1:1.5,3:2}/5").unwrap(), Some(vector));
+    assert_eq!(
+        parse_sparse("{}/3").unwrap(),
+        Some(PgSparseVector::from_dense(&[0.0; 3]))
+    );
+
+    assert!(PgSparseVector::from_parts(5, vec![2, 1], vec![1.0, 1.0]).is_none());
+    assert!(PgSparseVector::from_parts(5, vec![5], vec![1.0]).is_none());
+    assert!(parse_sparse("{0:1}/5").is_err());
+}
@@ -0,0 +1,74 @@
+//! Attaching other database files to a connection, so their tables can be used as
+//! `schema.table`.
+//!
+//! <https://www.sqlite.org/lang_attach.html>
+
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::query::query;
+use crate::query_as::query_as;
+use crate::SqliteConnection;
+
+impl SqliteConnection {
+    /// Attach the database file at `path` to this connection as `schema`, with
+    /// [`ATTACH DATABASE`](https://www.sqlite.org/lang_attach.html).
+    ///
+    /// The file is opened like the main database of the connection: it is only created if it
+    /// does not exist with [`create_if_missing()`][crate::SqliteConnectOptions::create_if_missing],
+    /// and it is read-only if the connection is. An empty path or `:memory:` attaches a new
+    /// in-memory database.
+    ///
+    /// A database is only attached to this connection. To attach it to every connection of a
+    /// pool, use [`SqliteConnectOptions::attach()`][crate::SqliteConnectOptions::attach]
+    /// instead.
+    ///
+    /// Fails if the connection is in a transaction, or if `schema` is already in use.
+    pub async fn attach(&mut self, path: impl AsRef<Path>, schema: &str) -> Result<(), Error> {
+        let path = path.as_ref();
+        let path = path.to_str().ok_or_else(|| {
+            Error::Configuration(format!("database path {path:?} is not valid UTF-8").into())
+        })?;
+
+        query(&format!("ATTACH DATABASE ?1 AS {}", quote(schema)))
+            .bind(path)
+            .persistent(false)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Detach the database `schema` that was attached to this connection.
+    ///
+    /// Fails if the connection is in a transaction, or if no database is attached as `schema`.
+    pub async fn detach(&mut self, schema: &str) -> Result<(), Error> {
+        query(&format!("DETACH DATABASE {}", quote(schema)))
+            .persistent(false)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the schema names and paths of the databases that are attached to this
+    /// connection, in the order they were attached, including the ones attached with a raw
+    /// `ATTACH` statement. The path of an in-memory database is empty.
+    pub async fn attached_databases(&mut self) -> Result<Vec<(String, PathBuf)>, Error> {
+        let databases: Vec<(String, String)> =
+            query_as("SELECT name, file FROM pragma_database_list ORDER BY seq")
+                .fetch_all(self)
+                .await?;
+
+        Ok(databases
+            .into_iter()
+            .filter(|(name, _)| name != "main" && name != "temp")
+            .map(|(name, file)| (name, PathBuf::from(file)))
+            .collect())
+    }
+}
+
+/// Quotes a schema name as an identifier.
+fn quote(schema: &str) -> String {
+    format!("\"{}\"", schema.replace('"', "\"\""))
+}
@@ -0,0 +1,243 @@
+//! The online backup API of SQLite, which copies a database page by page while it is in use.
+//!
+//! <https://www.sqlite.org/backup.html>
+
+use std::cmp;
+use std::os::raw::{c_char, c_int};
+use std::ptr::NonNull;
+use std::time::Duration;
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_backup, sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_pagecount,
+    sqlite3_backup_remaining, sqlite3_backup_step, SQLITE_BUSY, SQLITE_DONE, SQLITE_LOCKED,
+    SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::{SqliteConnection, SqliteError};
+
+// the schema of the database that was opened, as opposed to an attached database
+const MAIN: &[u8] = b"main\0";
+
+// the wait before another step, while another connection holds a lock on a database
+const BUSY_PAUSE: Duration = Duration::from_millis(10);
+
+/// A backup of the database of a connection into the database of another, returned by
+/// [`SqliteConnection::backup_to()`].
+///
+/// The pages of the source database are copied a few at a time, so other connections can use
+/// it between the steps. If another connection writes to it, the backup starts over; a write
+/// through the source connection itself is copied into the backup instead.
+///
+/// The backup replaces the whole destination database. Dropping the future of
+/// [`run()`][Self::run] stops the backup, and leaves the destination database as it was.
+#[must_use = "a backup does nothing until it is run"]
+pub struct SqliteBackup<'c> {
+    source: &'c mut SqliteConnection,
+    dest: &'c mut SqliteConnection,
+    pages_per_step: c_int,
+    pause: Duration,
+}
+
+/// How far a [`SqliteBackup`] is, after a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteBackupProgress {
+    remaining: u32,
+    page_count: u32,
+}
+
+impl SqliteBackupProgress {
+    /// Returns the number of pages that still have to be copied.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Returns the number of pages of the source database.
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+}
+
+impl SqliteConnection {
+    /// Back up the database of this connection into the database of `dest`, replacing its
+    /// contents, with the [online backup API](https://www.sqlite.org/backup.html) of SQLite.
+    ///
+    /// `dest` may be a connection to a file, e.g. to snapshot a database that is in use, or to
+    /// an in-memory database, e.g. to load a database from a file into memory.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use std::str::FromStr;
+    /// use std::time::Duration;
+    ///
+    /// use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+    /// use sqlx::{ConnectOptions, Connection};
+    ///
+    /// let mut conn = SqliteConnection::connect("sqlite:app.db").await?;
+    ///
+    /// let mut snapshot = SqliteConnectOptions::from_str("sqlite:snapshot.db")?
+    ///     .create_if_missing(true)
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// conn.backup_to(&mut snapshot)
+    ///     .pages_per_step(100)
+    ///     .pause(Duration::from_millis(10))
+    ///     .run_with_progress(|progress| {
+    ///         println!("{} of {} pages left", progress.remaining(), progress.page_count());
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn backup_to<'c>(&'c mut self, dest: &'c mut SqliteConnection) -> SqliteBackup<'c> {
+        SqliteBackup {
+            source: self,
+            dest,
+            pages_per_step: -1,
+            pause: Duration::ZERO,
+        }
+    }
+}
+
+impl SqliteBackup<'_> {
+    /// Sets the number of pages copied in each step.
+    ///
+    /// The default value of zero or less copies the whole database in a single step, which
+    /// keeps the source database locked until the backup is done.
+    pub fn pages_per_step(mut self, pages: i32) -> Self {
+        // no page would be copied with zero
+        self.pages_per_step = if pages > 0 { pages } else { -1 };
+        self
+    }
+
+    /// Sets how long to wait between two steps, so other connections can write to the source
+    /// database in the meantime.
+    ///
+    /// The default value is set to zero.
+    pub fn pause(mut self, duration: Duration) -> Self {
+        self.pause = duration;
+        self
+    }
+
+    /// Copy the database.
+    pub async fn run(self) -> Result<(), Error> {
+        self.run_with_progress(|_| {}).await
+    }
+
+    /// Copy the database, calling `progress` after each step.
+    pub async fn run_with_progress<F>(self, mut progress: F) -> Result<(), Error>
+    where
+        F: FnMut(SqliteBackupProgress),
+    {
+        // the worker threads make no calls on the handles until the backup is finished
+        let mut source = self.source.lock_handle().await?;
+        let mut dest = self.dest.lock_handle().await?;
+
+        let backup = Backup::init(dest.as_raw_handle(), source.as_raw_handle())?;
+
+        loop {
+            // SAFETY: the handles of both connections are locked
+            let (status, remaining, page_count) = unsafe {
+                let status = sqlite3_backup_step(backup.ptr.as_ptr(), self.pages_per_step);
+
+                (
+                    status,
+                    sqlite3_backup_remaining(backup.ptr.as_ptr()),
+                    sqlite3_backup_pagecount(backup.ptr.as_ptr()),
+                )
+            };
+
+            let pause = match status {
+                SQLITE_DONE => break,
+                SQLITE_OK => self.pause,
+
+                // another connection holds a lock on one of the databases
+                SQLITE_BUSY | SQLITE_LOCKED => cmp::max(self.pause, BUSY_PAUSE),
+
+                // the error is reported by `sqlite3_backup_finish()`
+                _ => {
+                    return Err(backup.finish().err().unwrap_or_else(|| {
+                        Error::Protocol(format!("backup step failed with code {status}"))
+                    }))
+                }
+            };
+
+            progress(SqliteBackupProgress {
+                remaining: remaining as u32,
+                page_count: page_count as u32,
+            });
+
+            if pause.is_zero() {
+                sqlx_core::rt::yield_now().await;
+            } else {
+                sqlx_core::rt::sleep(pause).await;
+            }
+        }
+
+        let page_count = unsafe { sqlite3_backup_pagecount(backup.ptr.as_ptr()) };
+
+        backup.finish()?;
+
+        progress(SqliteBackupProgress {
+            remaining: 0,
+            page_count: page_count as u32,
+        });
+
+        Ok(())
+    }
+}
+
+/// A `sqlite3_backup`, which is finished when dropped.
+struct Backup {
+    ptr: NonNull<sqlite3_backup>,
+    dest: NonNull<sqlite3>,
+}
+
+// SAFETY: the backup is only used while the handles of both connections are locked
+unsafe impl Send for Backup {}
+
+impl Backup {
+    fn init(dest: NonNull<sqlite3>, source: NonNull<sqlite3>) -> Result<Self, Error> {
+        // https://www.sqlite.org/c3ref/backup_finish.html#sqlite3backupinit
+        // SAFETY: the handles of both connections are locked
+        let ptr = unsafe {
+            sqlite3_backup_init(
+                dest.as_ptr(),
+                MAIN.as_ptr() as *const c_char,
+                source.as_ptr(),
+                MAIN.as_ptr() as *const c_char,
+            )
+        };
+
+        match NonNull::new(ptr) {
+            Some(ptr) => Ok(Backup { ptr, dest }),
+
+            // the error is stored in the destination connection
+            None => Err(Error::Database(Box::new(SqliteError::new(dest.as_ptr())))),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        let dest = self.dest;
+
+        // SAFETY: the backup is not used after it is finished
+        let status = unsafe { sqlite3_backup_finish(self.ptr.as_ptr()) };
+        std::mem::forget(self);
+
+        if status == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(Error::Database(Box::new(SqliteError::new(dest.as_ptr()))))
+        }
+    }
+}
+
+impl Drop for Backup {
+    fn drop(&mut self) {
+        // SAFETY: the backup is not used after it is finished
+        unsafe {
+            sqlite3_backup_finish(self.ptr.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Incremental I/O of a single BLOB value, without reading or writing all of it in a query.
+//!
+//! <https://www.sqlite.org/c3ref/blob_open.html>
+
+use std::cmp;
+use std::ffi::CString;
+use std::io::{self, SeekFrom};
+use std::os::raw::{c_int, c_void};
+use std::pin::Pin;
+use std::ptr::{self, NonNull};
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncRead, AsyncSeek, AsyncWrite};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_blob, sqlite3_blob_bytes, sqlite3_blob_close, sqlite3_blob_open,
+    sqlite3_blob_read, sqlite3_blob_reopen, sqlite3_blob_write, SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::connection::LockedSqliteHandle;
+use crate::{SqliteConnection, SqliteError};
+
+/// A BLOB value in a row of a table, opened with [`SqliteConnection::open_blob()`].
+///
+/// The value is read and written in place, to transfer a large value in chunks, e.g. with
+/// [`futures::io::copy()`][copy]; the size of the value cannot be changed, so it has to be
+/// created with the final size first, e.g. with `zeroblob(size)`.
+///
+/// The connection is locked while the blob is open, and the reads and writes are made on the
+/// current task. If the row is changed or deleted through another handle in the meantime, the
+/// blob expires, and all reads and writes fail.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::sqlite::SqliteConnection, data: &[u8]) -> sqlx::Result<()> {
+/// use futures::io::AsyncWriteExt;
+///
+/// let id = sqlx::query("INSERT INTO file (data) VALUES (zeroblob(?))")
+///     .bind(data.len() as i64)
+///     .execute(&mut *conn)
+///     .await?
+///     .last_insert_rowid();
+///
+/// let mut blob = conn.open_blob(None, "file", "data", id, false).await?;
+///
+/// for chunk in data.chunks(64 * 1024) {
+///     blob.write_all(chunk).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [copy]: https://docs.rs/futures/latest/futures/io/fn.copy.html
+pub struct SqliteBlob<'c> {
+    blob: NonNull<sqlite3_blob>,
+    db: NonNull<sqlite3>,
+    len: u64,
+    pos: u64,
+    // the blob is closed before the connection is unlocked
+    _locked: LockedSqliteHandle<'c>,
+}
+
+// SAFETY: the blob is only used while the handle of its connection is locked
+unsafe impl Send for SqliteBlob<'_> {}
+
+impl SqliteConnection {
+    /// Open the BLOB value of `column` in the row `rowid` of `table`, in the database `schema`;
+    /// `None` is the `main` database.
+    ///
+    /// The value must be a BLOB or TEXT value, and the column must not be part of an index, or
+    /// of the primary key of a `WITHOUT ROWID` table. If `read_only` is set, the value cannot be
+    /// written.
+    pub async fn open_blob(
+        &mut self,
+        schema: Option<&str>,
+        table: &str,
+        column: &str,
+        rowid: i64,
+        read_only: bool,
+    ) -> Result<SqliteBlob<'_>, Error> {
+        let schema = name(schema.unwrap_or("main"))?;
+        let table = name(table)?;
+        let column = name(column)?;
+
+        let mut locked = self.lock_handle().await?;
+        let db = locked.as_raw_handle();
+
+        let mut blob = ptr::null_mut();
+
+        // SAFETY: the handle is locked
+        let status = unsafe {
+            sqlite3_blob_open(
+                db.as_ptr(),
+                schema.as_ptr(),
+                table.as_ptr(),
+                column.as_ptr(),
+                rowid,
+                c_int::from(!read_only),
+                &mut blob,
+            )
+        };
+
+        let blob = match NonNull::new(blob) {
+            Some(blob) if status == SQLITE_OK => blob,
+            _ => return Err(Error::Database(Box::new(SqliteError::new(db.as_ptr())))),
+        };
+
+        let len = unsafe { sqlite3_blob_bytes(blob.as_ptr()) } as u64;
+
+        Ok(SqliteBlob {
+            blob,
+            db,
+            len,
+            pos: 0,
+            _locked: locked,
+        })
+    }
+}
+
+impl SqliteBlob<'_> {
+    /// Returns the size of the value in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the value is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Move to the value of the same column in the row `rowid` of the same table, and seek to
+    /// its start. This is faster than opening another blob.
+    pub fn reopen(&mut self, rowid: i64) -> Result<(), Error> {
+        // SAFETY: the handle of the connection is locked
+        let status = unsafe { sqlite3_blob_reopen(self.blob.as_ptr(), rowid) };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(self.error())));
+        }
+
+        self.len = unsafe { sqlite3_blob_bytes(self.blob.as_ptr()) } as u64;
+        self.pos = 0;
+
+        Ok(())
+    }
+
+    fn error(&self) -> SqliteError {
+        SqliteError::new(self.db.as_ptr())
+    }
+
+    /// The number of bytes from the position to the end of the value, at most `max`.
+    fn available(&self, max: usize) -> c_int {
+        let left = self.len.saturating_sub(self.pos);
+
+        // the size of a value is an `int`
+        cmp::min(left, max as u64) as c_int
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.available(buf.len());
+
+        if n == 0 {
+            return Ok(0);
+        }
+
+        // SAFETY: the handle of the connection is locked, and `buf` has room for `n` bytes
+        let status = unsafe {
+            sqlite3_blob_read(
+                self.blob.as_ptr(),
+                buf.as_mut_ptr() as *mut c_void,
+                n,
+                self.pos as c_int,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(io::Error::new(io::ErrorKind::Other, self.error()));
+        }
+
+        self.pos += n as u64;
+
+        Ok(n as usize)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // the value cannot grow, so a write at its end returns zero
+        let n = self.available(buf.len());
+
+        if n == 0 {
+            return Ok(0);
+        }
+
+        // SAFETY: the handle of the connection is locked, and `buf` has `n` bytes
+        let status = unsafe {
+            sqlite3_blob_write(
+                self.blob.as_ptr(),
+                buf.as_ptr() as *const c_void,
+                n,
+                self.pos as c_int,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(io::Error::new(io::ErrorKind::Other, self.error()));
+        }
+
+        self.pos += n as u64;
+
+        Ok(n as usize)
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.pos = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.len, offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+
+        match base.checked_add_signed(offset) {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl AsyncRead for SqliteBlob<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().read(buf))
+    }
+}
+
+impl AsyncWrite for SqliteBlob<'_> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // writes go to the page cache of the connection directly
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for SqliteBlob<'_> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        Poll::Ready(self.get_mut().seek(pos))
+    }
+}
+
+impl Drop for SqliteBlob<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the handle of the connection is still locked
+        unsafe {
+            sqlite3_blob_close(self.blob.as_ptr());
+        }
+    }
+}
+
+fn name(name: &str) -> Result<CString, Error> {
+    CString::new(name).map_err(|_| err_protocol!("invalid name: {:?}", name))
+}
@@ -0,0 +1,60 @@
+use std::fmt::{self, Debug, Formatter};
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use libsqlite3_sys::{sqlite3_busy_handler, SQLITE_OK};
+
+use crate::connection::handle::ConnectionHandle;
+use crate::error::Error;
+use crate::SqliteError;
+
+/// A busy handler that is set on each connection opened with the options.
+#[derive(Clone)]
+pub(crate) struct BusyHandler {
+    handler: Arc<dyn Fn(u32) -> bool + Send + Sync + 'static>,
+    // SAFETY: this must match the concrete type of `handler`
+    call: unsafe extern "C" fn(*mut c_void, c_int) -> c_int,
+}
+
+impl BusyHandler {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(u32) -> bool + Send + Sync + 'static,
+    {
+        BusyHandler {
+            handler: Arc::new(handler),
+            call: call_busy_handler::<F>,
+        }
+    }
+
+    /// Sets the handler on the connection, which must keep `self` until it is closed.
+    pub(crate) fn set(&self, handle: &mut ConnectionHandle) -> Result<(), Error> {
+        // the handler is not moved, as it is behind the `Arc`
+        let data = Arc::as_ptr(&self.handler) as *const c_void as *mut c_void;
+
+        let status = unsafe { sqlite3_busy_handler(handle.as_ptr(), Some(self.call), data) };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for BusyHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BusyHandler").finish_non_exhaustive()
+    }
+}
+
+/// Returns non-zero to retry, if the handler returns `true`; a panic gives up.
+unsafe extern "C" fn call_busy_handler<F>(data: *mut c_void, attempts: c_int) -> c_int
+where
+    F: Fn(u32) -> bool,
+{
+    let handler = &*(data as *const F);
+    let r = catch_unwind(AssertUnwindSafe(|| handler(attempts as u32)));
+    c_int::from(r.unwrap_or_default())
+}
@@ -0,0 +1,63 @@
+//! Encrypted databases, with [SQLCipher](https://www.zetetic.net/sqlcipher/).
+//!
+//! <https://www.zetetic.net/sqlcipher/sqlcipher-api/>
+
+use crate::error::Error;
+use crate::query::query;
+use crate::query_scalar::query_scalar;
+use crate::SqliteConnection;
+
+impl SqliteConnection {
+    /// Change the key of the encrypted `main` database, with
+    /// [`PRAGMA rekey`](https://www.zetetic.net/sqlcipher/sqlcipher-api/#rekey), and re-encrypt
+    /// all of its pages with the new key.
+    ///
+    /// The database must have been opened with its current key, e.g. with
+    /// [`SqliteConnectOptions::key()`][crate::SqliteConnectOptions::key]. The other connections
+    /// to the database keep working, but new connections need the new key, so the options of a
+    /// pool should be changed as well:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: sqlx::SqlitePool) -> sqlx::Result<()> {
+    /// pool.acquire().await?.rekey("new key").await?;
+    ///
+    /// let options = (*pool.connect_options()).clone().key("new key");
+    /// pool.set_connect_options(options);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Fails if SQLite was not built with SQLCipher.
+    pub async fn rekey(&mut self, key: &str) -> Result<(), Error> {
+        ensure_cipher(self).await?;
+
+        query(&format!("PRAGMA rekey = {}", quote_key(key)))
+            .persistent(false)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Fails if the connection does not support encryption, as SQLite ignores the pragmas of
+/// SQLCipher, and would store the data unencrypted.
+pub(crate) async fn ensure_cipher(conn: &mut SqliteConnection) -> Result<(), Error> {
+    let version: Option<String> = query_scalar("PRAGMA cipher_version")
+        .persistent(false)
+        .fetch_optional(conn)
+        .await?;
+
+    if version.is_none() {
+        return Err(Error::Configuration(
+            "an encryption key is set, but SQLite was not built with SQLCipher".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Quotes a key as a string literal, which SQLCipher derives the encryption key from.
+pub(crate) fn quote_key(key: &str) -> String {
+    format!("'{}'", key.replace('\'', "''"))
+}
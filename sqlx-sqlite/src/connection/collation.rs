@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 use std::ffi::CString;
 use std::fmt::{self, Debug, Formatter};
 use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::slice;
 use std::str::from_utf8_unchecked;
 use std::sync::Arc;
@@ -135,7 +136,8 @@ where
         let c_slice = slice::from_raw_parts(right_ptr as *const u8, right_len as usize);
         from_utf8_unchecked(c_slice)
     };
-    let t = (*boxed_f)(s1, s2);
+    // a panic must not unwind into SQLite
+    let t = catch_unwind(AssertUnwindSafe(|| (*boxed_f)(s1, s2))).unwrap_or(Ordering::Equal);
 
     match t {
         Ordering::Less => -1,
@@ -1,3 +1,4 @@
+use crate::connection::busy::BusyHandler;
 use crate::connection::handle::ConnectionHandle;
 use crate::connection::LogSettings;
 use crate::connection::{ConnectionState, Statements};
@@ -11,10 +12,12 @@ use libsqlite3_sys::{
 };
 use sqlx_core::IndexMap;
 use std::ffi::{c_void, CStr, CString};
+use std::fmt::{self, Debug, Formatter};
 use std::io;
 use std::os::raw::c_int;
 use std::ptr::{addr_of_mut, null, null_mut};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 static THREAD_ID: AtomicU64 = AtomicU64::new(0);
@@ -35,10 +38,52 @@ impl SqliteLoadExtensionMode {
     }
 }
 
+/// Keeps a shared in-memory database alive while the options of its connections exist, with a
+/// connection of its own, as SQLite deletes the database when its last connection is closed.
+#[derive(Default)]
+pub(crate) struct MemoryAnchor(Mutex<Option<ConnectionHandle>>);
+
+impl MemoryAnchor {
+    /// Opens the connection of the anchor, if it is not open yet.
+    fn keep(&self, filename: &CStr, open_flags: i32) -> Result<(), Error> {
+        let mut anchor = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        if anchor.is_some() {
+            return Ok(());
+        }
+
+        let mut handle = null_mut();
+        let status = unsafe { sqlite3_open_v2(filename.as_ptr(), &mut handle, open_flags, null()) };
+
+        if handle.is_null() {
+            return Err(Error::Io(io::ErrorKind::OutOfMemory.into()));
+        }
+
+        // SAFE: tested for NULL just above
+        let handle = unsafe { ConnectionHandle::new(handle) };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+        }
+
+        *anchor = Some(handle);
+
+        Ok(())
+    }
+}
+
+impl Debug for MemoryAnchor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryAnchor").finish_non_exhaustive()
+    }
+}
+
 pub struct EstablishParams {
     filename: CString,
     open_flags: i32,
+    memory_anchor: Option<Arc<MemoryAnchor>>,
     busy_timeout: Duration,
+    busy_handler: Option<BusyHandler>,
     statement_cache_capacity: usize,
     log_settings: LogSettings,
     extensions: IndexMap<CString, Option<CString>>,
@@ -111,6 +156,18 @@ impl EstablishParams {
             )
         })?;
 
+        if let Some(allowed) = &options.allowed_extensions {
+            if let Some(name) = options
+                .extensions
+                .keys()
+                .find(|name| !allowed.contains(name))
+            {
+                return Err(Error::Configuration(
+                    format!("extension {name:?} is not in the allowed extensions").into(),
+                ));
+            }
+        }
+
         let extensions = options
             .extensions
             .iter()
@@ -141,7 +198,10 @@ impl EstablishParams {
         Ok(Self {
             filename,
             open_flags: flags,
+            memory_anchor: (options.in_memory && options.shared_cache)
+                .then(|| options.memory_anchor.clone()),
             busy_timeout: options.busy_timeout,
+            busy_handler: options.busy_handler.clone(),
             statement_cache_capacity: options.statement_cache_capacity,
             log_settings: options.log_settings.clone(),
             extensions,
@@ -174,6 +234,43 @@ impl EstablishParams {
         Ok(())
     }
 
+    fn load_extensions(&self, handle: &ConnectionHandle) -> Result<(), Error> {
+        for ext in self.extensions.iter() {
+            // `sqlite3_load_extension` is unusual as it returns its errors via an out-pointer
+            // rather than by calling `sqlite3_errmsg`
+            let mut error = null_mut();
+            let status = unsafe {
+                sqlite3_load_extension(
+                    handle.as_ptr(),
+                    ext.0.as_ptr(),
+                    ext.1.as_ref().map_or(null(), |e| e.as_ptr()),
+                    addr_of_mut!(error),
+                )
+            };
+
+            if status != SQLITE_OK {
+                // SAFETY: We become responsible for any memory allocation at `&error`, so test
+                // for null and take an RAII version for returns
+                let err_msg = if !error.is_null() {
+                    unsafe {
+                        let e = CStr::from_ptr(error).into();
+                        sqlite3_free(error as *mut c_void);
+                        e
+                    }
+                } else {
+                    CString::new("Unknown error when loading extension")
+                        .expect("text should be representable as a CString")
+                };
+                return Err(Error::Database(Box::new(SqliteError::extension(
+                    handle.as_ptr(),
+                    &err_msg,
+                ))));
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn establish(&self) -> Result<ConnectionState, Error> {
         let mut handle = null_mut();
 
@@ -192,7 +289,7 @@ impl EstablishParams {
 
         // SAFE: tested for NULL just above
         // This allows any returns below to close this handle with RAII
-        let handle = unsafe { ConnectionHandle::new(handle) };
+        let mut handle = unsafe { ConnectionHandle::new(handle) };
 
         if status != SQLITE_OK {
             return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
@@ -211,47 +308,22 @@ impl EstablishParams {
                 Self::sqlite3_set_load_extension(handle.as_ptr(), SqliteLoadExtensionMode::Enable)?;
             }
 
-            for ext in self.extensions.iter() {
-                // `sqlite3_load_extension` is unusual as it returns its errors via an out-pointer
-                // rather than by calling `sqlite3_errmsg`
-                let mut error = null_mut();
-                status = unsafe {
-                    sqlite3_load_extension(
-                        handle.as_ptr(),
-                        ext.0.as_ptr(),
-                        ext.1.as_ref().map_or(null(), |e| e.as_ptr()),
-                        addr_of_mut!(error),
-                    )
-                };
+            let loaded = self.load_extensions(&handle);
 
-                if status != SQLITE_OK {
-                    // SAFETY: We become responsible for any memory allocation at `&error`, so test
-                    // for null and take an RAII version for returns
-                    let err_msg = if !error.is_null() {
-                        unsafe {
-                            let e = CStr::from_ptr(error).into();
-                            sqlite3_free(error as *mut c_void);
-                            e
-                        }
-                    } else {
-                        CString::new("Unknown error when loading extension")
-                            .expect("text should be representable as a CString")
-                    };
-                    return Err(Error::Database(Box::new(SqliteError::extension(
-                        handle.as_ptr(),
-                        &err_msg,
-                    ))));
-                }
-            } // Preempt any hypothetical security issues arising from leaving ENABLE_LOAD_EXTENSION
-              // on by disabling the flag again once we've loaded all the requested modules.
-              // Fail-fast (via `?`) if disabling the extension loader didn't work for some reason,
-              // avoids an unexpected state going undetected.
-            unsafe {
+            // Preempt any hypothetical security issues arising from leaving ENABLE_LOAD_EXTENSION
+            // on by disabling the flag again once we've loaded all the requested modules, or
+            // failed to load one of them.
+            // Fail-fast (via `?`) if disabling the extension loader didn't work for some reason,
+            // avoids an unexpected state going undetected.
+            let disabled = unsafe {
                 Self::sqlite3_set_load_extension(
                     handle.as_ptr(),
                     SqliteLoadExtensionMode::DisableAll,
-                )?;
-            }
+                )
+            };
+
+            loaded?;
+            disabled?;
         }
 
         #[cfg(feature = "regexp")]
@@ -263,18 +335,27 @@ impl EstablishParams {
             }
         }
 
-        // Configure a busy timeout
-        // This causes SQLite to automatically sleep in increasing intervals until the time
-        // when there is something locked during [sqlite3_step].
-        //
-        // We also need to convert the u128 value to i32, checking we're not overflowing.
-        let ms = i32::try_from(self.busy_timeout.as_millis())
-            .expect("Given busy timeout value is too big.");
+        if let Some(busy_handler) = &self.busy_handler {
+            // The handler replaces the busy timeout, and is kept in the connection state
+            busy_handler.set(&mut handle)?;
+        } else {
+            // Configure a busy timeout
+            // This causes SQLite to automatically sleep in increasing intervals until the time
+            // when there is something locked during [sqlite3_step].
+            //
+            // We also need to convert the u128 value to i32, checking we're not overflowing.
+            let ms = i32::try_from(self.busy_timeout.as_millis())
+                .expect("Given busy timeout value is too big.");
 
-        status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
+            status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
 
-        if status != SQLITE_OK {
-            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+            if status != SQLITE_OK {
+                return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+            }
+        }
+
+        if let Some(anchor) = &self.memory_anchor {
+            anchor.keep(&self.filename, self.open_flags)?;
         }
 
         Ok(ConnectionState {
@@ -283,6 +364,15 @@ impl EstablishParams {
             transaction_depth: 0,
             log_settings: self.log_settings.clone(),
             progress_handler_callback: None,
+            _busy_handler: self.busy_handler.clone(),
+            wal_hook_callback: None,
+            commit_hook_callback: None,
+            rollback_hook_callback: None,
+            update_hook_callback: None,
+            #[cfg(feature = "preupdate-hook")]
+            preupdate_hook_callback: None,
+            #[cfg(feature = "session")]
+            sessions: Vec::new(),
         })
     }
 }
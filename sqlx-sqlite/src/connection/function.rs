@@ -0,0 +1,437 @@
+//! User-defined scalar and aggregate SQL functions.
+//!
+//! <https://www.sqlite.org/c3ref/create_function.html>
+
+use std::ffi::CString;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3_aggregate_context, sqlite3_context, sqlite3_create_function_v2, sqlite3_result_blob64,
+    sqlite3_result_double, sqlite3_result_error, sqlite3_result_int, sqlite3_result_int64,
+    sqlite3_result_null, sqlite3_result_text64, sqlite3_user_data, sqlite3_value,
+    SQLITE_DETERMINISTIC, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+use sqlx_core::error::{mismatched_types, BoxDynError, Error};
+use sqlx_core::type_info::TypeInfo;
+
+use crate::connection::handle::ConnectionHandle;
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::type_info::DataType;
+use crate::types::Type;
+use crate::value::ValueRef;
+use crate::{
+    Sqlite, SqliteArgumentValue, SqliteError, SqliteTypeInfo, SqliteValue, SqliteValueRef,
+};
+
+/// The arguments of a call to a user-defined function.
+pub struct SqliteFunctionArgs<'a> {
+    values: &'a [SqliteValue],
+}
+
+impl<'a> SqliteFunctionArgs<'a> {
+    pub(crate) fn new(values: &'a [SqliteValue]) -> Self {
+        SqliteFunctionArgs { values }
+    }
+
+    /// Returns the number of arguments.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the function was called without arguments.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Decode the argument at `index`.
+    ///
+    /// Returns an error if there is no such argument, or if its type is not compatible with `T`,
+    /// the same way as [`Row::try_get()`][sqlx_core::row::Row::try_get].
+    pub fn get<T>(&self, index: usize) -> Result<T, BoxDynError>
+    where
+        T: Decode<'a, Sqlite> + Type<Sqlite>,
+    {
+        let value = self.value(index)?;
+
+        if !value.is_null() {
+            let ty = value.type_info();
+
+            if !ty.is_null() && !T::compatible(&ty) {
+                return Err(mismatched_types::<Sqlite, T>(&ty));
+            }
+        }
+
+        T::decode(value)
+    }
+
+    /// Returns the argument at `index`, to decode it without checking its type.
+    pub fn value(&self, index: usize) -> Result<SqliteValueRef<'a>, BoxDynError> {
+        let values = self.values;
+
+        values
+            .get(index)
+            .map(SqliteValueRef::value)
+            .ok_or_else(|| format!("function argument index out of bounds: {index}").into())
+    }
+}
+
+/// The state of a user-defined aggregate function, for one group of rows.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use std::str::FromStr;
+///
+/// use sqlx::error::BoxDynError;
+/// use sqlx::sqlite::{SqliteAggregate, SqliteConnectOptions, SqliteFunctionArgs};
+/// use sqlx::ConnectOptions;
+///
+/// // the product of the values of a column
+/// #[derive(Default)]
+/// struct Product(f64);
+///
+/// impl SqliteAggregate for Product {
+///     type Output = f64;
+///
+///     fn step(&mut self, args: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError> {
+///         self.0 *= args.get::<f64>(0)?;
+///         Ok(())
+///     }
+///
+///     fn finish(self) -> Result<f64, BoxDynError> {
+///         Ok(self.0)
+///     }
+/// }
+///
+/// let conn = SqliteConnectOptions::from_str("sqlite::memory:")?
+///     .create_aggregate_function("product", 1, true, || Product(1.0))
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait SqliteAggregate: 'static {
+    /// The result of the function.
+    type Output: Encode<'static, Sqlite>;
+
+    /// Add the arguments of a row of the group.
+    fn step(&mut self, args: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError>;
+
+    /// Returns the result for the group, after all of its rows were added.
+    fn finish(self) -> Result<Self::Output, BoxDynError>;
+}
+
+/// Registers a [`Function`] on a connection under the given name.
+type CreateFunction =
+    dyn Fn(&mut ConnectionHandle, &str) -> Result<(), Error> + Send + Sync + 'static;
+
+/// A user-defined function of [`SqliteConnectOptions`][crate::SqliteConnectOptions], created
+/// on each new connection.
+#[derive(Clone)]
+pub(crate) struct Function {
+    name: Arc<str>,
+    create: Arc<CreateFunction>,
+}
+
+impl Function {
+    pub(crate) fn scalar<N, F, R>(name: N, num_args: i32, deterministic: bool, f: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn(&SqliteFunctionArgs<'_>) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        let f = Arc::new(f);
+
+        Function {
+            name: name.into(),
+            create: Arc::new(move |handle, name| {
+                create_scalar_function(handle, name, num_args, deterministic, Arc::clone(&f))
+            }),
+        }
+    }
+
+    pub(crate) fn aggregate<N, F, A>(name: N, num_args: i32, deterministic: bool, init: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        let init = Arc::new(init);
+
+        Function {
+            name: name.into(),
+            create: Arc::new(move |handle, name| {
+                create_aggregate_function(handle, name, num_args, deterministic, Arc::clone(&init))
+            }),
+        }
+    }
+
+    pub(crate) fn create(&self, handle: &mut ConnectionHandle) -> Result<(), Error> {
+        (self.create)(handle, &self.name)
+    }
+}
+
+impl Debug for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn create_scalar_function<F, R>(
+    handle: &mut ConnectionHandle,
+    name: &str,
+    num_args: i32,
+    deterministic: bool,
+    f: Arc<F>,
+) -> Result<(), Error>
+where
+    F: Fn(&SqliteFunctionArgs<'_>) -> Result<R, BoxDynError> + Send + Sync + 'static,
+    R: Encode<'static, Sqlite>,
+{
+    unsafe extern "C" fn call_scalar<F, R>(
+        ctx: *mut sqlite3_context,
+        argc: c_int,
+        argv: *mut *mut sqlite3_value,
+    ) where
+        F: Fn(&SqliteFunctionArgs<'_>) -> Result<R, BoxDynError>,
+        R: Encode<'static, Sqlite>,
+    {
+        let f = &*(sqlite3_user_data(ctx) as *const F);
+        let values = function_values(argc, argv);
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            f(&SqliteFunctionArgs { values: &values })
+        }));
+
+        set_result(ctx, r);
+    }
+
+    create_function(
+        handle,
+        name,
+        num_args,
+        deterministic,
+        Arc::into_raw(f) as *mut c_void,
+        Some(call_scalar::<F, R>),
+        None,
+        None,
+        drop_arc_value::<F>,
+    )
+}
+
+pub(crate) fn create_aggregate_function<F, A>(
+    handle: &mut ConnectionHandle,
+    name: &str,
+    num_args: i32,
+    deterministic: bool,
+    init: Arc<F>,
+) -> Result<(), Error>
+where
+    F: Fn() -> A + Send + Sync + 'static,
+    A: SqliteAggregate,
+{
+    // the aggregate context of SQLite, zeroed for the first row of a group, holds a pointer to
+    // the boxed state
+    unsafe extern "C" fn step<F, A>(
+        ctx: *mut sqlite3_context,
+        argc: c_int,
+        argv: *mut *mut sqlite3_value,
+    ) where
+        F: Fn() -> A,
+        A: SqliteAggregate,
+    {
+        let state =
+            sqlite3_aggregate_context(ctx, mem::size_of::<*mut A>() as c_int) as *mut *mut A;
+
+        if state.is_null() {
+            set_error(ctx, "out of memory");
+            return;
+        }
+
+        let init = &*(sqlite3_user_data(ctx) as *const F);
+        let values = function_values(argc, argv);
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            if (*state).is_null() {
+                *state = Box::into_raw(Box::new(init()));
+            }
+
+            (**state).step(&SqliteFunctionArgs { values: &values })
+        }));
+
+        // only errors are reported, the result is set by `finish()`
+        match r {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => set_error(ctx, &e.to_string()),
+            Err(_) => set_error(ctx, PANICKED),
+        }
+    }
+
+    unsafe extern "C" fn finish<F, A>(ctx: *mut sqlite3_context)
+    where
+        F: Fn() -> A,
+        A: SqliteAggregate,
+    {
+        // a null pointer if `step()` was never called, for a group without rows
+        let state = sqlite3_aggregate_context(ctx, 0) as *mut *mut A;
+        let init = &*(sqlite3_user_data(ctx) as *const F);
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            let state = if state.is_null() || (*state).is_null() {
+                init()
+            } else {
+                *Box::from_raw(mem::replace(&mut *state, ptr::null_mut()))
+            };
+
+            state.finish()
+        }));
+
+        set_result(ctx, r);
+    }
+
+    create_function(
+        handle,
+        name,
+        num_args,
+        deterministic,
+        Arc::into_raw(init) as *mut c_void,
+        None,
+        Some(step::<F, A>),
+        Some(finish::<F, A>),
+        drop_arc_value::<F>,
+    )
+}
+
+const PANICKED: &str = "user-defined function panicked";
+
+type FunctionCallback = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+#[allow(clippy::too_many_arguments)]
+fn create_function(
+    handle: &mut ConnectionHandle,
+    name: &str,
+    num_args: i32,
+    deterministic: bool,
+    user_data: *mut c_void,
+    call: Option<FunctionCallback>,
+    step: Option<FunctionCallback>,
+    finish: Option<unsafe extern "C" fn(*mut sqlite3_context)>,
+    free: unsafe extern "C" fn(*mut c_void),
+) -> Result<(), Error> {
+    let c_name = match CString::new(name) {
+        Ok(c_name) => c_name,
+        Err(_) => {
+            // SAFETY: the user data was not passed to SQLite
+            unsafe { free(user_data) };
+            return Err(err_protocol!("invalid function name: {}", name));
+        }
+    };
+
+    let mut flags = SQLITE_UTF8;
+
+    if deterministic {
+        flags |= SQLITE_DETERMINISTIC;
+    }
+
+    // the `xDestroy` callback is called by SQLite if the function cannot be created
+    let r = unsafe {
+        sqlite3_create_function_v2(
+            handle.as_ptr(),
+            c_name.as_ptr(),
+            num_args,
+            flags,
+            user_data,
+            call,
+            step,
+            finish,
+            Some(free),
+        )
+    };
+
+    if r == SQLITE_OK {
+        Ok(())
+    } else {
+        Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))))
+    }
+}
+
+unsafe extern "C" fn drop_arc_value<T>(p: *mut c_void) {
+    drop(Arc::from_raw(p as *const T));
+}
+
+/// Copies of the arguments of a call, which are only valid during the call.
+pub(crate) unsafe fn function_values(
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) -> Vec<SqliteValue> {
+    if argc <= 0 {
+        return Vec::new();
+    }
+
+    slice::from_raw_parts(argv, argc as usize)
+        .iter()
+        .map(|&value| SqliteValue::new(value, SqliteTypeInfo(DataType::Null)))
+        .collect()
+}
+
+unsafe fn set_result<R>(ctx: *mut sqlite3_context, r: std::thread::Result<Result<R, BoxDynError>>)
+where
+    R: Encode<'static, Sqlite>,
+{
+    let value = match r {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => return set_error(ctx, &e.to_string()),
+        Err(_) => return set_error(ctx, PANICKED),
+    };
+
+    set_value(ctx, value);
+}
+
+/// Set the result of `ctx` to `value`, encoded like a bind argument.
+pub(crate) unsafe fn set_value<'q, T>(ctx: *mut sqlite3_context, value: T)
+where
+    T: Encode<'q, Sqlite>,
+{
+    let mut buf = Vec::with_capacity(1);
+
+    if let IsNull::Yes = value.encode(&mut buf) {
+        buf.clear();
+    }
+
+    match buf.pop().unwrap_or(SqliteArgumentValue::Null) {
+        SqliteArgumentValue::Null => sqlite3_result_null(ctx),
+        SqliteArgumentValue::Text(v) => sqlite3_result_text64(
+            ctx,
+            v.as_ptr() as *const c_char,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+            SQLITE_UTF8 as u8,
+        ),
+        SqliteArgumentValue::Blob(v) => sqlite3_result_blob64(
+            ctx,
+            v.as_ptr() as *const c_void,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+        ),
+        SqliteArgumentValue::Double(v) => sqlite3_result_double(ctx, v),
+        SqliteArgumentValue::Int(v) => sqlite3_result_int(ctx, v),
+        SqliteArgumentValue::Int64(v) => sqlite3_result_int64(ctx, v),
+    }
+}
+
+unsafe fn set_error(ctx: *mut sqlite3_context, message: &str) {
+    // SQLite copies the message
+    sqlite3_result_error(
+        ctx,
+        message.as_ptr() as *const c_char,
+        message.len() as c_int,
+    );
+}
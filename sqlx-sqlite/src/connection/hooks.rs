@@ -0,0 +1,478 @@
+//! The commit, rollback, update and preupdate hooks, which are called on the worker thread as
+//! the database of a connection changes.
+//!
+//! <https://www.sqlite.org/c3ref/commit_hook.html>
+//! <https://www.sqlite.org/c3ref/update_hook.html>
+//! <https://www.sqlite.org/c3ref/preupdate_blobwrite.html>
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::{self, NonNull};
+
+use libsqlite3_sys::{
+    sqlite3_commit_hook, sqlite3_int64, sqlite3_rollback_hook, sqlite3_update_hook, SQLITE_DELETE,
+    SQLITE_INSERT, SQLITE_UPDATE,
+};
+
+use crate::connection::{ConnectionState, Handler, LockedSqliteHandle};
+
+#[cfg(feature = "preupdate-hook")]
+pub(crate) use preupdate::PreupdateHook;
+#[cfg(feature = "preupdate-hook")]
+pub use preupdate::SqlitePreupdateHookResult;
+
+/// The kind of change to a row, which is reported to the update and preupdate hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+    /// An operation code that is not known to SQLx.
+    Other(i32),
+}
+
+impl SqliteOperation {
+    pub(crate) fn from_int(op: c_int) -> Self {
+        match op {
+            SQLITE_INSERT => SqliteOperation::Insert,
+            SQLITE_UPDATE => SqliteOperation::Update,
+            SQLITE_DELETE => SqliteOperation::Delete,
+            other => SqliteOperation::Other(other),
+        }
+    }
+}
+
+/// A row that was changed, which is passed to the callback of
+/// [`LockedSqliteHandle::set_update_hook()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteUpdateHookResult<'a> {
+    operation: SqliteOperation,
+    database: &'a str,
+    table: &'a str,
+    rowid: i64,
+}
+
+impl<'a> SqliteUpdateHookResult<'a> {
+    /// Returns whether the row was inserted, updated or deleted.
+    pub fn operation(&self) -> SqliteOperation {
+        self.operation
+    }
+
+    /// Returns the name of the database of the table, e.g. `main`.
+    pub fn database(&self) -> &'a str {
+        self.database
+    }
+
+    /// Returns the name of the table of the row.
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// Returns the rowid of the row, after the change.
+    pub fn rowid(&self) -> i64 {
+        self.rowid
+    }
+}
+
+/// An update hook that is set on the connection, and dropped when it is removed.
+pub(crate) struct UpdateHook(NonNull<dyn FnMut(SqliteUpdateHookResult<'_>) + Send + 'static>);
+
+unsafe impl Send for UpdateHook {}
+
+/// A rollback hook that is set on the connection, and dropped when it is removed.
+pub(crate) struct RollbackHook(NonNull<dyn FnMut() + Send + 'static>);
+
+unsafe impl Send for RollbackHook {}
+
+impl ConnectionState {
+    /// Drops the `commit_hook_callback` if it exists.
+    pub(crate) fn remove_commit_hook(&mut self) {
+        if let Some(hook) = self.commit_hook_callback.take() {
+            unsafe {
+                sqlite3_commit_hook(self.handle.as_ptr(), None, ptr::null_mut());
+                drop(Box::from_raw(hook.0.as_ptr()));
+            }
+        }
+    }
+
+    /// Drops the `rollback_hook_callback` if it exists.
+    pub(crate) fn remove_rollback_hook(&mut self) {
+        if let Some(hook) = self.rollback_hook_callback.take() {
+            unsafe {
+                sqlite3_rollback_hook(self.handle.as_ptr(), None, ptr::null_mut());
+                drop(Box::from_raw(hook.0.as_ptr()));
+            }
+        }
+    }
+
+    /// Drops the `update_hook_callback` if it exists.
+    pub(crate) fn remove_update_hook(&mut self) {
+        if let Some(hook) = self.update_hook_callback.take() {
+            unsafe {
+                sqlite3_update_hook(self.handle.as_ptr(), None, ptr::null_mut());
+                drop(Box::from_raw(hook.0.as_ptr()));
+            }
+        }
+    }
+}
+
+impl LockedSqliteHandle<'_> {
+    /// Sets a callback that is invoked before each commit. If the callback returns `false`, the
+    /// commit is turned into a rollback, and the statement that commits fails.
+    ///
+    /// Hooks run on the worker thread of the connection, while the statement that triggered them
+    /// is running, so they must not block or use the connection. To handle the changes from a
+    /// task, send them through a channel, e.g. with `flume::Sender::send()`.
+    ///
+    /// Only a single commit hook may be set per connection; setting a new hook replaces the old
+    /// one.
+    pub fn set_commit_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        unsafe {
+            // SAFETY: `Box::into_raw()` always returns a non-null pointer.
+            let callback = NonNull::new_unchecked(Box::into_raw(Box::new(callback)));
+            let arg = callback.as_ptr() as *mut c_void;
+
+            self.guard.remove_commit_hook();
+            self.guard.commit_hook_callback = Some(Handler(callback));
+
+            sqlite3_commit_hook(
+                self.as_raw_handle().as_ptr(),
+                Some(commit_hook_callback::<F>),
+                arg,
+            );
+        }
+    }
+
+    /// Sets a callback that is invoked after each rollback, including the automatic rollback of
+    /// a failed statement, but not when the connection is closed.
+    ///
+    /// See [`set_commit_hook()`][Self::set_commit_hook] for how hooks are run.
+    pub fn set_rollback_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        unsafe {
+            // SAFETY: `Box::into_raw()` always returns a non-null pointer.
+            let callback = NonNull::new_unchecked(Box::into_raw(Box::new(callback)));
+            let arg = callback.as_ptr() as *mut c_void;
+
+            self.guard.remove_rollback_hook();
+            self.guard.rollback_hook_callback = Some(RollbackHook(callback));
+
+            sqlite3_rollback_hook(
+                self.as_raw_handle().as_ptr(),
+                Some(rollback_hook_callback::<F>),
+                arg,
+            );
+        }
+    }
+
+    /// Sets a callback that is invoked after a row of a rowid table is inserted, updated or
+    /// deleted, e.g. to invalidate a cache. Changes to `WITHOUT ROWID` tables and to internal
+    /// tables are not reported, nor are rows deleted by a truncation with `DELETE FROM table`.
+    ///
+    /// The change may still be rolled back, which is reported to the
+    /// [rollback hook][Self::set_rollback_hook].
+    ///
+    /// See [`set_commit_hook()`][Self::set_commit_hook] for how hooks are run.
+    pub fn set_update_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut(SqliteUpdateHookResult<'_>) + Send + 'static,
+    {
+        unsafe {
+            // SAFETY: `Box::into_raw()` always returns a non-null pointer.
+            let callback = NonNull::new_unchecked(Box::into_raw(Box::new(callback)));
+            let arg = callback.as_ptr() as *mut c_void;
+
+            self.guard.remove_update_hook();
+            self.guard.update_hook_callback = Some(UpdateHook(callback));
+
+            sqlite3_update_hook(
+                self.as_raw_handle().as_ptr(),
+                Some(update_hook_callback::<F>),
+                arg,
+            );
+        }
+    }
+
+    /// Removes the commit hook. The method does nothing if no hook was set.
+    pub fn remove_commit_hook(&mut self) {
+        self.guard.remove_commit_hook();
+    }
+
+    /// Removes the rollback hook. The method does nothing if no hook was set.
+    pub fn remove_rollback_hook(&mut self) {
+        self.guard.remove_rollback_hook();
+    }
+
+    /// Removes the update hook. The method does nothing if no hook was set.
+    pub fn remove_update_hook(&mut self) {
+        self.guard.remove_update_hook();
+    }
+}
+
+/// Returns non-zero to turn the commit into a rollback, if the callback returns `false` or
+/// panics.
+extern "C" fn commit_hook_callback<F>(callback: *mut c_void) -> c_int
+where
+    F: FnMut() -> bool,
+{
+    unsafe {
+        let callback = &mut *callback.cast::<F>();
+        let r = catch_unwind(AssertUnwindSafe(callback));
+        c_int::from(!r.unwrap_or_default())
+    }
+}
+
+extern "C" fn rollback_hook_callback<F>(callback: *mut c_void)
+where
+    F: FnMut(),
+{
+    unsafe {
+        let callback = &mut *callback.cast::<F>();
+        // a panic must not unwind into SQLite
+        let _ = catch_unwind(AssertUnwindSafe(callback));
+    }
+}
+
+extern "C" fn update_hook_callback<F>(
+    callback: *mut c_void,
+    op: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: sqlite3_int64,
+) where
+    F: FnMut(SqliteUpdateHookResult<'_>),
+{
+    unsafe {
+        let callback = &mut *callback.cast::<F>();
+
+        let result = SqliteUpdateHookResult {
+            operation: SqliteOperation::from_int(op),
+            database: str_from_ptr(database),
+            table: str_from_ptr(table),
+            rowid,
+        };
+
+        // a panic must not unwind into SQLite
+        let _ = catch_unwind(AssertUnwindSafe(|| callback(result)));
+    }
+}
+
+/// Names of schema objects are valid UTF-8, as SQL text is.
+unsafe fn str_from_ptr<'a>(s: *const c_char) -> &'a str {
+    CStr::from_ptr(s).to_str().unwrap_or_default()
+}
+
+#[cfg(feature = "preupdate-hook")]
+mod preupdate {
+    use std::marker::PhantomData;
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::ptr::{self, NonNull};
+
+    use libsqlite3_sys::{
+        sqlite3, sqlite3_int64, sqlite3_preupdate_count, sqlite3_preupdate_depth,
+        sqlite3_preupdate_hook, sqlite3_preupdate_new, sqlite3_preupdate_old, sqlite3_value,
+        SQLITE_OK,
+    };
+    use sqlx_core::error::Error;
+
+    use super::{str_from_ptr, SqliteOperation};
+    use crate::connection::{ConnectionState, LockedSqliteHandle};
+    use crate::type_info::DataType;
+    use crate::{SqliteError, SqliteTypeInfo, SqliteValue};
+
+    /// A row that is about to change, which is passed to the callback of
+    /// [`LockedSqliteHandle::set_preupdate_hook()`].
+    ///
+    /// The values of the row can only be read during the callback.
+    pub struct SqlitePreupdateHookResult<'a> {
+        operation: SqliteOperation,
+        database: &'a str,
+        table: &'a str,
+        old_rowid: i64,
+        new_rowid: i64,
+        db: NonNull<sqlite3>,
+        // the values belong to the statement that is running
+        _marker: PhantomData<&'a mut sqlite3>,
+    }
+
+    impl<'a> SqlitePreupdateHookResult<'a> {
+        /// Returns whether the row is inserted, updated or deleted.
+        pub fn operation(&self) -> SqliteOperation {
+            self.operation
+        }
+
+        /// Returns the name of the database of the table, e.g. `main`.
+        pub fn database(&self) -> &'a str {
+            self.database
+        }
+
+        /// Returns the name of the table of the row.
+        pub fn table(&self) -> &'a str {
+            self.table
+        }
+
+        /// Returns the rowid of the row before the change, or `None` for an insert.
+        ///
+        /// The rowid of a `WITHOUT ROWID` table is undefined.
+        pub fn old_rowid(&self) -> Option<i64> {
+            (self.operation != SqliteOperation::Insert).then_some(self.old_rowid)
+        }
+
+        /// Returns the rowid of the row after the change, or `None` for a delete.
+        ///
+        /// The rowid of a `WITHOUT ROWID` table is undefined.
+        pub fn new_rowid(&self) -> Option<i64> {
+            (self.operation != SqliteOperation::Delete).then_some(self.new_rowid)
+        }
+
+        /// Returns the number of columns of the row.
+        pub fn column_count(&self) -> usize {
+            unsafe { sqlite3_preupdate_count(self.db.as_ptr()) as usize }
+        }
+
+        /// Returns `0` for a change by a top-level statement, `1` for a change by a trigger of
+        /// that statement, and so on.
+        pub fn depth(&self) -> i32 {
+            unsafe { sqlite3_preupdate_depth(self.db.as_ptr()) }
+        }
+
+        /// Returns the value of column `index` before the change. Fails for an insert, which has
+        /// no old row.
+        pub fn old_value(&self, index: usize) -> Result<SqliteValue, Error> {
+            if self.operation == SqliteOperation::Insert {
+                return Err(err_protocol!("an inserted row has no old values"));
+            }
+
+            self.value(index, sqlite3_preupdate_old)
+        }
+
+        /// Returns the value of column `index` after the change. Fails for a delete, which has
+        /// no new row.
+        pub fn new_value(&self, index: usize) -> Result<SqliteValue, Error> {
+            if self.operation == SqliteOperation::Delete {
+                return Err(err_protocol!("a deleted row has no new values"));
+            }
+
+            self.value(index, sqlite3_preupdate_new)
+        }
+
+        fn value(
+            &self,
+            index: usize,
+            get: unsafe extern "C" fn(*mut sqlite3, c_int, *mut *mut sqlite3_value) -> c_int,
+        ) -> Result<SqliteValue, Error> {
+            let len = self.column_count();
+
+            if index >= len {
+                return Err(Error::ColumnIndexOutOfBounds { index, len });
+            }
+
+            let mut value = ptr::null_mut();
+
+            // SAFETY: the value is copied, as it is only valid during the callback
+            unsafe {
+                let status = get(self.db.as_ptr(), index as c_int, &mut value);
+
+                if status != SQLITE_OK || value.is_null() {
+                    return Err(Error::Database(Box::new(SqliteError::new(
+                        self.db.as_ptr(),
+                    ))));
+                }
+
+                Ok(SqliteValue::new(value, SqliteTypeInfo(DataType::Null)))
+            }
+        }
+    }
+
+    /// A preupdate hook that is set on the connection, and dropped when it is removed.
+    pub(crate) struct PreupdateHook(
+        NonNull<dyn FnMut(SqlitePreupdateHookResult<'_>) + Send + 'static>,
+    );
+
+    unsafe impl Send for PreupdateHook {}
+
+    impl ConnectionState {
+        /// Drops the `preupdate_hook_callback` if it exists.
+        pub(crate) fn remove_preupdate_hook(&mut self) {
+            if let Some(hook) = self.preupdate_hook_callback.take() {
+                unsafe {
+                    sqlite3_preupdate_hook(self.handle.as_ptr(), None, ptr::null_mut());
+                    drop(Box::from_raw(hook.0.as_ptr()));
+                }
+            }
+        }
+    }
+
+    impl LockedSqliteHandle<'_> {
+        /// Sets a callback that is invoked before a row is inserted, updated or deleted, with
+        /// the values of the row before and after the change, e.g. to track changes.
+        ///
+        /// Unlike the [update hook][Self::set_update_hook], the changes to `WITHOUT ROWID`
+        /// tables are reported as well.
+        ///
+        /// SQLite must be compiled with `SQLITE_ENABLE_PREUPDATE_HOOK`, e.g. by setting the
+        /// environment variable `LIBSQLITE3_FLAGS=SQLITE_ENABLE_PREUPDATE_HOOK` for the build of
+        /// the bundled SQLite; otherwise, linking fails.
+        ///
+        /// See [`set_commit_hook()`][Self::set_commit_hook] for how hooks are run.
+        pub fn set_preupdate_hook<F>(&mut self, callback: F)
+        where
+            F: FnMut(SqlitePreupdateHookResult<'_>) + Send + 'static,
+        {
+            unsafe {
+                // SAFETY: `Box::into_raw()` always returns a non-null pointer.
+                let callback = NonNull::new_unchecked(Box::into_raw(Box::new(callback)));
+                let arg = callback.as_ptr() as *mut c_void;
+
+                self.guard.remove_preupdate_hook();
+                self.guard.preupdate_hook_callback = Some(PreupdateHook(callback));
+
+                sqlite3_preupdate_hook(
+                    self.as_raw_handle().as_ptr(),
+                    Some(preupdate_hook_callback::<F>),
+                    arg,
+                );
+            }
+        }
+
+        /// Removes the preupdate hook. The method does nothing if no hook was set.
+        pub fn remove_preupdate_hook(&mut self) {
+            self.guard.remove_preupdate_hook();
+        }
+    }
+
+    unsafe extern "C" fn preupdate_hook_callback<F>(
+        callback: *mut c_void,
+        db: *mut sqlite3,
+        op: c_int,
+        database: *const c_char,
+        table: *const c_char,
+        old_rowid: sqlite3_int64,
+        new_rowid: sqlite3_int64,
+    ) where
+        F: FnMut(SqlitePreupdateHookResult<'_>),
+    {
+        let callback = &mut *callback.cast::<F>();
+
+        let result = SqlitePreupdateHookResult {
+            operation: SqliteOperation::from_int(op),
+            database: str_from_ptr(database),
+            table: str_from_ptr(table),
+            old_rowid,
+            new_rowid,
+            db: NonNull::new_unchecked(db),
+            _marker: PhantomData,
+        };
+
+        // a panic must not unwind into SQLite
+        let _ = catch_unwind(AssertUnwindSafe(|| callback(result)));
+    }
+}
@@ -3,13 +3,15 @@ use futures_intrusive::sync::MutexGuard;
 use futures_util::future;
 use libsqlite3_sys::{sqlite3, sqlite3_progress_handler};
 use sqlx_core::common::StatementCache;
-use sqlx_core::error::Error;
+use sqlx_core::encode::Encode;
+use sqlx_core::error::{BoxDynError, Error};
 use sqlx_core::transaction::Transaction;
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Formatter};
 use std::os::raw::{c_int, c_void};
 use std::panic::catch_unwind;
 use std::ptr::NonNull;
+use std::sync::Arc;
 
 use crate::connection::establish::EstablishParams;
 use crate::connection::worker::ConnectionWorker;
@@ -21,16 +23,48 @@ use std::fmt::Write;
 
 pub(crate) use sqlx_core::connection::*;
 
+pub use backup::{SqliteBackup, SqliteBackupProgress};
+pub use blob::SqliteBlob;
+pub use function::{SqliteAggregate, SqliteFunctionArgs};
 pub(crate) use handle::{ConnectionHandle, ConnectionHandleRaw};
-
+#[cfg(feature = "preupdate-hook")]
+pub use hooks::SqlitePreupdateHookResult;
+pub use hooks::{SqliteOperation, SqliteUpdateHookResult};
+#[cfg(feature = "session")]
+pub use session::{SqliteConflict, SqliteConflictAction, SqliteConflictKind, SqliteSession};
+#[cfg(feature = "snapshot")]
+pub use snapshot::SqliteSnapshot;
+pub use status::SqliteStatus;
+pub use vtab::{
+    SqliteIndexConstraint, SqliteIndexConstraintOp, SqliteIndexInfo, SqliteIndexOrderBy,
+    SqliteVirtualTable, SqliteVirtualTableColumn, SqliteVirtualTableCursor, SqliteVirtualTableKind,
+};
+pub use wal::{SqliteCheckpoint, SqliteCheckpointMode};
+
+mod attach;
+mod backup;
+mod blob;
+pub(crate) mod busy;
+pub(crate) mod cipher;
 pub(crate) mod collation;
 pub(crate) mod describe;
 pub(crate) mod establish;
 pub(crate) mod execute;
 mod executor;
 mod explain;
+pub(crate) mod function;
 mod handle;
+mod hooks;
 mod intmap;
+mod serialize;
+#[cfg(feature = "session")]
+mod session;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+mod status;
+mod vacuum;
+pub(crate) mod vtab;
+mod wal;
 
 mod worker;
 
@@ -71,6 +105,32 @@ pub(crate) struct ConnectionState {
     /// Stores the progress handler set on the current connection. If the handler returns `false`,
     /// the query is interrupted.
     progress_handler_callback: Option<Handler>,
+
+    /// Keeps the busy handler set on the current connection alive, if it replaces the busy
+    /// timeout.
+    _busy_handler: Option<busy::BusyHandler>,
+
+    /// Stores the WAL hook set on the current connection.
+    wal_hook_callback: Option<wal::WalHook>,
+
+    /// Stores the commit hook set on the current connection. If the hook returns `false`, the
+    /// commit is turned into a rollback.
+    commit_hook_callback: Option<Handler>,
+
+    /// Stores the rollback hook set on the current connection.
+    rollback_hook_callback: Option<hooks::RollbackHook>,
+
+    /// Stores the update hook set on the current connection.
+    update_hook_callback: Option<hooks::UpdateHook>,
+
+    /// Stores the preupdate hook set on the current connection.
+    #[cfg(feature = "preupdate-hook")]
+    preupdate_hook_callback: Option<hooks::PreupdateHook>,
+
+    /// Stores the sessions created on the current connection, which are deleted before the
+    /// connection is closed.
+    #[cfg(feature = "session")]
+    sessions: Vec<Arc<session::SessionHandle>>,
 }
 
 impl ConnectionState {
@@ -245,6 +305,67 @@ impl LockedSqliteHandle<'_> {
         collation::create_collation(&mut self.guard.handle, name, compare)
     }
 
+    /// Apply a user-defined scalar function to the open database.
+    ///
+    /// See [`SqliteConnectOptions::create_scalar_function()`] for details.
+    pub fn create_scalar_function<F, R>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        deterministic: bool,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&SqliteFunctionArgs<'_>) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        function::create_scalar_function(
+            &mut self.guard.handle,
+            name,
+            num_args,
+            deterministic,
+            Arc::new(f),
+        )
+    }
+
+    /// Apply a user-defined aggregate function to the open database.
+    ///
+    /// See [`SqliteConnectOptions::create_aggregate_function()`] for details.
+    pub fn create_aggregate_function<F, A>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        deterministic: bool,
+        init: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        function::create_aggregate_function(
+            &mut self.guard.handle,
+            name,
+            num_args,
+            deterministic,
+            Arc::new(init),
+        )
+    }
+
+    /// Apply a virtual table module to the open database.
+    ///
+    /// See [`SqliteConnectOptions::create_module()`] for details.
+    pub fn create_module<T>(
+        &mut self,
+        name: &str,
+        kind: SqliteVirtualTableKind,
+        aux: T::Aux,
+    ) -> Result<(), Error>
+    where
+        T: SqliteVirtualTable,
+    {
+        vtab::create_module::<T>(&mut self.guard.handle, name, kind, Arc::new(aux))
+    }
+
     /// Sets a progress handler that is invoked periodically during long running calls. If the progress callback
     /// returns `false`, then the operation is interrupted.
     ///
@@ -290,6 +411,14 @@ impl Drop for ConnectionState {
         // explicitly drop statements before the connection handle is dropped
         self.statements.clear();
         self.remove_progress_handler();
+        self.remove_wal_hook();
+        self.remove_commit_hook();
+        self.remove_rollback_hook();
+        self.remove_update_hook();
+        #[cfg(feature = "preupdate-hook")]
+        self.remove_preupdate_hook();
+        #[cfg(feature = "session")]
+        self.remove_sessions();
     }
 }
 
@@ -0,0 +1,157 @@
+//! Copying a database to and from bytes in memory, with `sqlite3_serialize()` and
+//! `sqlite3_deserialize()`.
+//!
+//! <https://www.sqlite.org/c3ref/serialize.html>
+
+use std::cmp;
+use std::ffi::CString;
+use std::os::raw::c_void;
+use std::ptr;
+use std::slice;
+
+use libsqlite3_sys::{
+    sqlite3_deserialize, sqlite3_free, sqlite3_int64, sqlite3_malloc64, sqlite3_serialize,
+    SQLITE_DESERIALIZE_FREEONCLOSE, SQLITE_DESERIALIZE_READONLY, SQLITE_DESERIALIZE_RESIZEABLE,
+    SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::{SqliteConnection, SqliteError};
+
+// the schema of the database that was opened, as opposed to an attached database
+const MAIN: &str = "main";
+
+// the file format version numbers for reading and writing, in the database header
+const VERSION_OFFSETS: [usize; 2] = [18, 19];
+const VERSION_LEGACY: u8 = 1;
+const VERSION_WAL: u8 = 2;
+
+impl SqliteConnection {
+    /// Copy the database `schema` of this connection into bytes, in the format of a database
+    /// file; `None` is the `main` database, as opposed to an attached database.
+    ///
+    /// The bytes can be restored into another connection with
+    /// [`deserialize()`][Self::deserialize], or written to a file. This works for any
+    /// database, but is most useful for an in-memory database:
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use sqlx::sqlite::SqliteConnection;
+    /// use sqlx::{Connection, Executor};
+    ///
+    /// let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    /// conn.execute("CREATE TABLE item (name TEXT); INSERT INTO item VALUES ('a')").await?;
+    ///
+    /// let snapshot = conn.serialize(None).await?;
+    ///
+    /// // later, or in another process
+    /// let mut restored = SqliteConnection::connect("sqlite::memory:").await?;
+    /// restored.deserialize(None, &snapshot, false).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn serialize(&mut self, schema: Option<&str>) -> Result<Vec<u8>, Error> {
+        let schema = schema.unwrap_or(MAIN);
+        let c_schema = schema_name(schema)?;
+
+        let mut locked = self.lock_handle().await?;
+        let handle = locked.as_raw_handle();
+
+        let mut size: sqlite3_int64 = 0;
+
+        // SAFETY: the handle is locked
+        let data = unsafe { sqlite3_serialize(handle.as_ptr(), c_schema.as_ptr(), &mut size, 0) };
+
+        if data.is_null() {
+            // an in-memory database without pages, as the copy of zero bytes is not allocated
+            if size == 0 {
+                return Ok(Vec::new());
+            }
+
+            return Err(err_protocol!("failed to serialize database {:?}", schema));
+        }
+
+        // SAFETY: SQLite allocated `size` bytes, which are freed by us
+        let bytes = unsafe {
+            let bytes = slice::from_raw_parts(data, size as usize).to_vec();
+            sqlite3_free(data as *mut c_void);
+            bytes
+        };
+
+        Ok(bytes)
+    }
+
+    /// Replace the database `schema` of this connection with the contents of `data`, which were
+    /// returned by [`serialize()`][Self::serialize] or read from a database file; `None` is the
+    /// `main` database.
+    ///
+    /// The database is kept in memory from then on, even if it was opened from a file, and
+    /// changes to it are not written anywhere. If `read_only` is set, the database cannot be
+    /// changed at all. A database that was in the WAL journal mode is restored in the rollback
+    /// journal mode, as an in-memory database cannot use a WAL.
+    ///
+    /// Fails if the connection is in a transaction.
+    pub async fn deserialize(
+        &mut self,
+        schema: Option<&str>,
+        data: &[u8],
+        read_only: bool,
+    ) -> Result<(), Error> {
+        let c_schema = schema_name(schema.unwrap_or(MAIN))?;
+
+        let mut locked = self.lock_handle().await?;
+        let handle = locked.as_raw_handle();
+
+        // SQLite takes ownership of a buffer it allocated, which it frees once the database is
+        // closed, or if it cannot be deserialized; at least one byte is allocated, as no
+        // memory is returned for zero
+        let buf = unsafe { sqlite3_malloc64(cmp::max(data.len(), 1) as u64) } as *mut u8;
+
+        if buf.is_null() {
+            return Err(Error::Io(std::io::ErrorKind::OutOfMemory.into()));
+        }
+
+        // SAFETY: the buffer has room for `data`
+        let copy = unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), buf, data.len());
+            slice::from_raw_parts_mut(buf, data.len())
+        };
+
+        if copy.len() > VERSION_OFFSETS[1]
+            && VERSION_OFFSETS.iter().all(|&i| copy[i] == VERSION_WAL)
+        {
+            for i in VERSION_OFFSETS {
+                copy[i] = VERSION_LEGACY;
+            }
+        }
+
+        let flags = SQLITE_DESERIALIZE_FREEONCLOSE
+            | if read_only {
+                SQLITE_DESERIALIZE_READONLY
+            } else {
+                SQLITE_DESERIALIZE_RESIZEABLE
+            };
+
+        // SAFETY: the handle is locked
+        let status = unsafe {
+            sqlite3_deserialize(
+                handle.as_ptr(),
+                c_schema.as_ptr(),
+                buf,
+                data.len() as sqlite3_int64,
+                data.len() as sqlite3_int64,
+                flags as u32,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+        }
+
+        Ok(())
+    }
+}
+
+fn schema_name(schema: &str) -> Result<CString, Error> {
+    CString::new(schema).map_err(|_| err_protocol!("invalid schema name: {:?}", schema))
+}
@@ -0,0 +1,508 @@
+//! The session extension of SQLite, which records the changes to a database as changesets that
+//! can be applied to another database, or inverted to undo them.
+//!
+//! <https://www.sqlite.org/sessionintro.html>
+
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3_changeset_iter, sqlite3_free, sqlite3_session, sqlite3_value, sqlite3changeset_apply,
+    sqlite3changeset_conflict, sqlite3changeset_invert, sqlite3changeset_new, sqlite3changeset_old,
+    sqlite3changeset_op, sqlite3session_attach, sqlite3session_changeset, sqlite3session_create,
+    sqlite3session_delete, sqlite3session_patchset, SQLITE_CHANGESET_ABORT,
+    SQLITE_CHANGESET_CONFLICT, SQLITE_CHANGESET_CONSTRAINT, SQLITE_CHANGESET_DATA,
+    SQLITE_CHANGESET_FOREIGN_KEY, SQLITE_CHANGESET_NOTFOUND, SQLITE_CHANGESET_OMIT,
+    SQLITE_CHANGESET_REPLACE, SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::connection::{ConnectionState, SqliteOperation};
+use crate::type_info::DataType;
+use crate::{SqliteConnection, SqliteError, SqliteTypeInfo, SqliteValue};
+
+/// A session that records the changes to the tables of a database that are attached to it,
+/// created with [`SqliteConnection::create_session()`].
+///
+/// The session belongs to the connection that created it, and must be used with that
+/// connection. It keeps recording until it is [deleted][Self::delete]; a session that is
+/// dropped instead is deleted the next time a session is created on the connection, or when
+/// the connection is closed.
+///
+/// The SQLite library must be compiled with `SQLITE_ENABLE_SESSION` and
+/// `SQLITE_ENABLE_PREUPDATE_HOOK`, e.g. by setting the environment variable
+/// `LIBSQLITE3_FLAGS="SQLITE_ENABLE_SESSION SQLITE_ENABLE_PREUPDATE_HOOK"` for the build of the
+/// bundled SQLite; otherwise, linking fails.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use sqlx::sqlite::{SqliteConflictAction, SqliteConnection};
+/// use sqlx::{Connection, Executor};
+///
+/// let mut conn = SqliteConnection::connect("sqlite:app.db").await?;
+/// let mut replica = SqliteConnection::connect("sqlite:replica.db").await?;
+///
+/// let session = conn.create_session(None).await?;
+/// session.attach(&mut conn, Some("item")).await?;
+///
+/// conn.execute("INSERT INTO item (name) VALUES ('a')").await?;
+///
+/// let changeset = session.changeset(&mut conn).await?;
+///
+/// replica
+///     .apply_changeset(&changeset, |_conflict| SqliteConflictAction::Replace)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteSession {
+    handle: Arc<SessionHandle>,
+}
+
+/// A `sqlite3_session`, which is deleted by the connection that created it.
+pub(crate) struct SessionHandle(AtomicPtr<sqlite3_session>);
+
+impl SessionHandle {
+    /// Deletes the session; it must not be in use.
+    pub(crate) unsafe fn delete(&self) {
+        let session = self.0.swap(ptr::null_mut(), Ordering::AcqRel);
+
+        if !session.is_null() {
+            sqlite3session_delete(session);
+        }
+    }
+}
+
+impl ConnectionState {
+    /// Deletes the sessions that are no longer referenced by a `SqliteSession`.
+    fn remove_dropped_sessions(&mut self) {
+        self.sessions.retain(|session| {
+            let in_use = Arc::strong_count(session) > 1;
+
+            if !in_use {
+                unsafe { session.delete() };
+            }
+
+            in_use
+        });
+    }
+
+    /// Deletes all sessions, which must happen before the connection is closed.
+    pub(crate) fn remove_sessions(&mut self) {
+        for session in self.sessions.drain(..) {
+            unsafe { session.delete() };
+        }
+    }
+
+    fn session(&self, session: &SqliteSession) -> Result<*mut sqlite3_session, Error> {
+        if !self
+            .sessions
+            .iter()
+            .any(|handle| Arc::ptr_eq(handle, &session.handle))
+        {
+            return Err(err_protocol!(
+                "the session belongs to another connection, or was deleted"
+            ));
+        }
+
+        Ok(session.handle.0.load(Ordering::Acquire))
+    }
+}
+
+impl SqliteConnection {
+    /// Start a [session][SqliteSession] on the database `schema` of this connection; `None` is
+    /// the `main` database, as opposed to an attached database.
+    ///
+    /// The session records nothing until tables are [attached][SqliteSession::attach] to it.
+    pub async fn create_session(&mut self, schema: Option<&str>) -> Result<SqliteSession, Error> {
+        let schema = CString::new(schema.unwrap_or("main"))
+            .map_err(|_| err_protocol!("invalid schema name: {:?}", schema))?;
+
+        let mut locked = self.lock_handle().await?;
+        locked.guard.remove_dropped_sessions();
+
+        let mut session = ptr::null_mut();
+
+        // SAFETY: the handle is locked
+        let status = unsafe {
+            sqlite3session_create(
+                locked.as_raw_handle().as_ptr(),
+                schema.as_ptr(),
+                &mut session,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        let handle = Arc::new(SessionHandle(AtomicPtr::new(session)));
+        locked.guard.sessions.push(Arc::clone(&handle));
+
+        Ok(SqliteSession { handle })
+    }
+
+    /// Apply a changeset or patchset, from [`SqliteSession::changeset()`] or
+    /// [`SqliteSession::patchset()`], to the database of this connection.
+    ///
+    /// `conflict` is called for each change that cannot be applied as is, e.g. because the row
+    /// to update does not exist, and decides what happens to it. The changes are applied in
+    /// a savepoint, so if `conflict` returns [`Abort`][SqliteConflictAction::Abort], none of
+    /// them are applied, and an error is returned.
+    pub async fn apply_changeset<F>(&mut self, changeset: &[u8], conflict: F) -> Result<(), Error>
+    where
+        F: FnMut(&SqliteConflict<'_>) -> SqliteConflictAction,
+    {
+        let len = changeset_len(changeset)?;
+
+        let mut locked = self.lock_handle().await?;
+        let mut conflict = conflict;
+
+        // SAFETY: the handle is locked; SQLite does not write to the changeset
+        let status = unsafe {
+            sqlite3changeset_apply(
+                locked.as_raw_handle().as_ptr(),
+                len,
+                changeset.as_ptr() as *mut c_void,
+                None,
+                Some(conflict_callback::<F>),
+                &mut conflict as *mut F as *mut c_void,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        Ok(())
+    }
+}
+
+impl SqliteSession {
+    /// Record the changes to `table` of the database of the session, or to all of its tables if
+    /// `table` is `None`, including the tables that are created later.
+    ///
+    /// Only changes to tables with a `PRIMARY KEY` are recorded.
+    pub async fn attach(
+        &self,
+        conn: &mut SqliteConnection,
+        table: Option<&str>,
+    ) -> Result<(), Error> {
+        let table = table
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| err_protocol!("invalid table name: {:?}", table))?;
+
+        let locked = conn.lock_handle().await?;
+        let session = locked.guard.session(self)?;
+
+        // SAFETY: the handle of the connection of the session is locked
+        let status = unsafe {
+            sqlite3session_attach(session, table.as_ref().map_or(ptr::null(), |t| t.as_ptr()))
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the changes that were recorded so far, as a changeset. A changeset contains the
+    /// old values of the changed rows as well, so it can be [inverted][Self::invert_changeset],
+    /// and conflicts can be found when it is applied.
+    pub async fn changeset(&self, conn: &mut SqliteConnection) -> Result<Vec<u8>, Error> {
+        self.collect(conn, sqlite3session_changeset).await
+    }
+
+    /// Returns the changes that were recorded so far, as a patchset, which is smaller than a
+    /// changeset, as it only contains the primary keys of updated and deleted rows.
+    pub async fn patchset(&self, conn: &mut SqliteConnection) -> Result<Vec<u8>, Error> {
+        self.collect(conn, sqlite3session_patchset).await
+    }
+
+    /// Stop recording the changes, and free the session.
+    pub async fn delete(self, conn: &mut SqliteConnection) -> Result<(), Error> {
+        let mut locked = conn.lock_handle().await?;
+        locked.guard.session(&self)?;
+
+        locked
+            .guard
+            .sessions
+            .retain(|handle| !Arc::ptr_eq(handle, &self.handle));
+
+        // SAFETY: the handle of the connection of the session is locked
+        unsafe { self.handle.delete() };
+
+        Ok(())
+    }
+
+    /// Returns a changeset that undoes the changes of `changeset`, i.e. inserts the deleted
+    /// rows, deletes the inserted rows, and restores the old values of the updated rows.
+    ///
+    /// A patchset cannot be inverted.
+    pub fn invert_changeset(changeset: &[u8]) -> Result<Vec<u8>, Error> {
+        let len = changeset_len(changeset)?;
+
+        let mut out_len = 0;
+        let mut out = ptr::null_mut();
+
+        // SAFETY: the changeset is only read
+        let status = unsafe {
+            sqlite3changeset_invert(
+                len,
+                changeset.as_ptr() as *const c_void,
+                &mut out_len,
+                &mut out,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        Ok(unsafe { take_buffer(out, out_len) })
+    }
+
+    async fn collect(
+        &self,
+        conn: &mut SqliteConnection,
+        collect: unsafe extern "C" fn(*mut sqlite3_session, *mut c_int, *mut *mut c_void) -> c_int,
+    ) -> Result<Vec<u8>, Error> {
+        let locked = conn.lock_handle().await?;
+        let session = locked.guard.session(self)?;
+
+        let mut len = 0;
+        let mut buf = ptr::null_mut();
+
+        // SAFETY: the handle of the connection of the session is locked
+        let status = unsafe { collect(session, &mut len, &mut buf) };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        Ok(unsafe { take_buffer(buf, len) })
+    }
+}
+
+/// Why a change of a changeset cannot be applied as is; refer to
+/// [SQLite documentation](https://www.sqlite.org/session/c_changeset_conflict.html) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqliteConflictKind {
+    /// The row to update or delete exists, but its values are not the old values of the
+    /// change.
+    Data,
+    /// The row to update or delete does not exist.
+    NotFound,
+    /// The row to insert already exists.
+    Conflict,
+    /// The change violates a `NOT NULL`, `UNIQUE` or `CHECK` constraint.
+    Constraint,
+    /// Applying all changes would violate a foreign key constraint. This is reported only once,
+    /// after all changes are applied, for no particular row.
+    ForeignKey,
+    /// A kind of conflict that is not known to SQLx.
+    Other(i32),
+}
+
+/// What happens to a change that cannot be applied as is, which is returned by the conflict
+/// handler of [`SqliteConnection::apply_changeset()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteConflictAction {
+    /// Skip the change.
+    Omit,
+    /// Overwrite the conflicting row with the change. Only valid for the conflicts of the
+    /// kinds [`Data`][SqliteConflictKind::Data] and [`Conflict`][SqliteConflictKind::Conflict].
+    Replace,
+    /// Stop, and undo all changes of the changeset.
+    Abort,
+}
+
+/// A change that cannot be applied as is, which is passed to the conflict handler of
+/// [`SqliteConnection::apply_changeset()`].
+pub struct SqliteConflict<'a> {
+    kind: SqliteConflictKind,
+    table: &'a str,
+    operation: SqliteOperation,
+    column_count: usize,
+    iter: NonNull<sqlite3_changeset_iter>,
+    // the values belong to the changeset that is applied
+    _marker: PhantomData<&'a mut sqlite3_changeset_iter>,
+}
+
+impl<'a> SqliteConflict<'a> {
+    /// Returns why the change cannot be applied as is.
+    pub fn kind(&self) -> SqliteConflictKind {
+        self.kind
+    }
+
+    /// Returns the name of the table of the change; an empty string for a conflict of the kind
+    /// [`ForeignKey`][SqliteConflictKind::ForeignKey].
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// Returns whether the change inserts, updates or deletes a row.
+    pub fn operation(&self) -> SqliteOperation {
+        self.operation
+    }
+
+    /// Returns the number of columns of the table.
+    pub fn column_count(&self) -> usize {
+        self.column_count
+    }
+
+    /// Returns the value of column `index` before the change, for an update or a delete. For
+    /// an update, the value is `None` if the column is neither changed nor part of the primary
+    /// key.
+    pub fn old_value(&self, index: usize) -> Result<Option<SqliteValue>, Error> {
+        if self.operation == SqliteOperation::Insert {
+            return Err(err_protocol!("an inserted row has no old values"));
+        }
+
+        self.value(index, sqlite3changeset_old)
+    }
+
+    /// Returns the value of column `index` after the change, for an insert or an update. For
+    /// an update, the value is `None` if the column is not changed.
+    pub fn new_value(&self, index: usize) -> Result<Option<SqliteValue>, Error> {
+        if self.operation == SqliteOperation::Delete {
+            return Err(err_protocol!("a deleted row has no new values"));
+        }
+
+        self.value(index, sqlite3changeset_new)
+    }
+
+    /// Returns the value of column `index` of the row in the database that conflicts with the
+    /// change, for the conflicts of the kinds [`Data`][SqliteConflictKind::Data] and
+    /// [`Conflict`][SqliteConflictKind::Conflict].
+    pub fn conflicting_value(&self, index: usize) -> Result<Option<SqliteValue>, Error> {
+        if !matches!(
+            self.kind,
+            SqliteConflictKind::Data | SqliteConflictKind::Conflict
+        ) {
+            return Err(err_protocol!(
+                "a conflict of the kind {:?} has no conflicting row",
+                self.kind
+            ));
+        }
+
+        self.value(index, sqlite3changeset_conflict)
+    }
+
+    fn value(
+        &self,
+        index: usize,
+        get: unsafe extern "C" fn(
+            *mut sqlite3_changeset_iter,
+            c_int,
+            *mut *mut sqlite3_value,
+        ) -> c_int,
+    ) -> Result<Option<SqliteValue>, Error> {
+        let len = self.column_count;
+
+        if index >= len {
+            return Err(Error::ColumnIndexOutOfBounds { index, len });
+        }
+
+        let mut value = ptr::null_mut();
+
+        // SAFETY: the value is copied, as it is only valid during the conflict handler
+        unsafe {
+            let status = get(self.iter.as_ptr(), index as c_int, &mut value);
+
+            if status != SQLITE_OK {
+                return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+            }
+
+            Ok(NonNull::new(value)
+                .map(|value| SqliteValue::new(value.as_ptr(), SqliteTypeInfo(DataType::Null))))
+        }
+    }
+}
+
+impl SqliteConflictKind {
+    fn from_int(kind: c_int) -> Self {
+        match kind {
+            SQLITE_CHANGESET_DATA => SqliteConflictKind::Data,
+            SQLITE_CHANGESET_NOTFOUND => SqliteConflictKind::NotFound,
+            SQLITE_CHANGESET_CONFLICT => SqliteConflictKind::Conflict,
+            SQLITE_CHANGESET_CONSTRAINT => SqliteConflictKind::Constraint,
+            SQLITE_CHANGESET_FOREIGN_KEY => SqliteConflictKind::ForeignKey,
+            other => SqliteConflictKind::Other(other),
+        }
+    }
+}
+
+impl SqliteConflictAction {
+    fn as_int(self) -> c_int {
+        match self {
+            SqliteConflictAction::Omit => SQLITE_CHANGESET_OMIT,
+            SqliteConflictAction::Replace => SQLITE_CHANGESET_REPLACE,
+            SqliteConflictAction::Abort => SQLITE_CHANGESET_ABORT,
+        }
+    }
+}
+
+/// Aborts the changeset if the handler panics.
+unsafe extern "C" fn conflict_callback<F>(
+    conflict: *mut c_void,
+    kind: c_int,
+    iter: *mut sqlite3_changeset_iter,
+) -> c_int
+where
+    F: FnMut(&SqliteConflict<'_>) -> SqliteConflictAction,
+{
+    let handler = &mut *conflict.cast::<F>();
+    let kind = SqliteConflictKind::from_int(kind);
+
+    let mut table: *const c_char = ptr::null();
+    let mut column_count = 0;
+    let mut op = 0;
+    let mut indirect = 0;
+
+    // the iterator does not point to a change for a foreign key conflict
+    if kind != SqliteConflictKind::ForeignKey {
+        sqlite3changeset_op(iter, &mut table, &mut column_count, &mut op, &mut indirect);
+    }
+
+    let conflict = SqliteConflict {
+        kind,
+        table: if table.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(table).to_str().unwrap_or_default()
+        },
+        operation: SqliteOperation::from_int(op),
+        column_count: column_count as usize,
+        iter: NonNull::new_unchecked(iter),
+        _marker: PhantomData,
+    };
+
+    catch_unwind(AssertUnwindSafe(|| handler(&conflict)))
+        .unwrap_or(SqliteConflictAction::Abort)
+        .as_int()
+}
+
+fn changeset_len(changeset: &[u8]) -> Result<c_int, Error> {
+    c_int::try_from(changeset.len()).map_err(|_| err_protocol!("changeset is too large"))
+}
+
+/// Copies and frees a buffer that SQLite allocated.
+unsafe fn take_buffer(buf: *mut c_void, len: c_int) -> Vec<u8> {
+    if buf.is_null() {
+        return Vec::new();
+    }
+
+    let bytes = slice::from_raw_parts(buf as *const u8, len as usize).to_vec();
+    sqlite3_free(buf);
+    bytes
+}
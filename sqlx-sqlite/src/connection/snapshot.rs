@@ -0,0 +1,126 @@
+//! Reading a database as it was at an earlier transaction, in the WAL journal mode.
+//!
+//! <https://www.sqlite.org/c3ref/snapshot.html>
+
+use std::ffi::CString;
+use std::fmt::{self, Debug, Formatter};
+use std::ptr::{self, NonNull};
+
+use libsqlite3_sys::{
+    sqlite3_snapshot, sqlite3_snapshot_free, sqlite3_snapshot_get, sqlite3_snapshot_open, SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::{SqliteConnection, SqliteError};
+
+// the schema of the database that was opened, as opposed to an attached database
+const MAIN: &str = "main";
+
+/// The state of a database in the WAL journal mode at a read transaction, returned by
+/// [`SqliteConnection::snapshot()`].
+///
+/// A snapshot can be opened by any connection to the same database, e.g. by several
+/// connections of a pool, to read the same data in all of them. It can only be opened while
+/// the frames of the WAL that it needs are there, so a checkpoint that restarts or truncates
+/// the WAL invalidates it.
+pub struct SqliteSnapshot {
+    snapshot: NonNull<sqlite3_snapshot>,
+}
+
+// SAFETY: a snapshot is an immutable value, which is not tied to its connection
+unsafe impl Send for SqliteSnapshot {}
+unsafe impl Sync for SqliteSnapshot {}
+
+impl Debug for SqliteSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteSnapshot").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SqliteSnapshot {
+    fn drop(&mut self) {
+        // SAFETY: the snapshot was allocated by SQLite, and is only freed here
+        unsafe { sqlite3_snapshot_free(self.snapshot.as_ptr()) }
+    }
+}
+
+impl SqliteConnection {
+    /// Capture the state of the database `schema` that the current read transaction sees, with
+    /// [`sqlite3_snapshot_get()`](https://www.sqlite.org/c3ref/snapshot_get.html); `None` is the
+    /// `main` database.
+    ///
+    /// The database must be in the WAL journal mode, and the connection must be in a
+    /// transaction that has read from the database, but not written to it.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: sqlx::SqlitePool) -> sqlx::Result<()> {
+    /// let mut tx = pool.begin().await?;
+    /// let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+    ///     .fetch_one(&mut *tx)
+    ///     .await?;
+    /// let snapshot = tx.snapshot(None).await?;
+    /// tx.commit().await?;
+    ///
+    /// // another connection, which sees the same rows even if some were inserted meanwhile
+    /// let mut tx = pool.begin().await?;
+    /// tx.open_snapshot(None, &snapshot).await?;
+    /// let items: Vec<String> = sqlx::query_scalar("SELECT name FROM item")
+    ///     .fetch_all(&mut *tx)
+    ///     .await?;
+    /// assert_eq!(items.len() as i64, count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn snapshot(&mut self, schema: Option<&str>) -> Result<SqliteSnapshot, Error> {
+        let schema = schema_name(schema)?;
+
+        let mut locked = self.lock_handle().await?;
+        let handle = locked.as_raw_handle();
+
+        let mut snapshot = ptr::null_mut();
+
+        // SAFETY: the handle is locked
+        let status =
+            unsafe { sqlite3_snapshot_get(handle.as_ptr(), schema.as_ptr(), &mut snapshot) };
+
+        match NonNull::new(snapshot) {
+            Some(snapshot) if status == SQLITE_OK => Ok(SqliteSnapshot { snapshot }),
+            _ => Err(Error::Database(Box::new(SqliteError::from_code(status)))),
+        }
+    }
+
+    /// Make the current transaction read the database `schema` as it was at `snapshot`, with
+    /// [`sqlite3_snapshot_open()`](https://www.sqlite.org/c3ref/snapshot_open.html); `None` is
+    /// the `main` database.
+    ///
+    /// The connection must be in a transaction that has not written to the database, e.g.
+    /// right after [`begin()`][sqlx_core::connection::Connection::begin]; if the transaction has
+    /// read from the database already, it switches to the snapshot. The transaction cannot
+    /// write to the database afterwards.
+    pub async fn open_snapshot(
+        &mut self,
+        schema: Option<&str>,
+        snapshot: &SqliteSnapshot,
+    ) -> Result<(), Error> {
+        let schema = schema_name(schema)?;
+
+        let mut locked = self.lock_handle().await?;
+        let handle = locked.as_raw_handle();
+
+        // SAFETY: the handle is locked, and SQLite does not change the snapshot
+        let status = unsafe {
+            sqlite3_snapshot_open(handle.as_ptr(), schema.as_ptr(), snapshot.snapshot.as_ptr())
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::from_code(status))));
+        }
+
+        Ok(())
+    }
+}
+
+fn schema_name(schema: Option<&str>) -> Result<CString, Error> {
+    let schema = schema.unwrap_or(MAIN);
+    CString::new(schema).map_err(|_| err_protocol!("invalid schema name: {:?}", schema))
+}
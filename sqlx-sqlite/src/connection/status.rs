@@ -0,0 +1,181 @@
+//! Memory and cache usage of SQLite, for monitoring.
+//!
+//! <https://www.sqlite.org/c3ref/db_status.html>
+
+use std::os::raw::c_int;
+use std::ptr::{self, NonNull};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_db_status, sqlite3_int64, sqlite3_next_stmt, sqlite3_status64,
+    SQLITE_DBSTATUS_CACHE_HIT, SQLITE_DBSTATUS_CACHE_MISS, SQLITE_DBSTATUS_CACHE_SPILL,
+    SQLITE_DBSTATUS_CACHE_USED, SQLITE_DBSTATUS_CACHE_WRITE, SQLITE_DBSTATUS_LOOKASIDE_USED,
+    SQLITE_DBSTATUS_SCHEMA_USED, SQLITE_DBSTATUS_STMT_USED, SQLITE_OK, SQLITE_STATUS_MEMORY_USED,
+};
+use sqlx_core::error::Error;
+
+use crate::{SqliteConnection, SqliteError};
+
+/// The resource usage of a connection, returned by [`SqliteConnection::status()`].
+///
+/// The sizes are in bytes. The cache counters are totals since the connection was opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteStatus {
+    memory_used: u64,
+    memory_highwater: u64,
+    cache_used: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+    cache_writes: u64,
+    cache_spills: u64,
+    schema_used: u64,
+    statements_used: u64,
+    statements: usize,
+    lookaside_used: u64,
+}
+
+impl SqliteStatus {
+    /// Returns the memory allocated by SQLite, for all connections of the process.
+    ///
+    /// This is zero if SQLite was built without memory statistics.
+    pub fn memory_used(&self) -> u64 {
+        self.memory_used
+    }
+
+    /// Returns the most memory that was allocated by SQLite at once, for all connections of the
+    /// process.
+    pub fn memory_highwater(&self) -> u64 {
+        self.memory_highwater
+    }
+
+    /// Returns the memory used by the page cache of this connection.
+    pub fn cache_used(&self) -> u64 {
+        self.cache_used
+    }
+
+    /// Returns the number of pages that were found in the page cache.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits
+    }
+
+    /// Returns the number of pages that were not found in the page cache, and were read from
+    /// the database file.
+    pub fn cache_misses(&self) -> u64 {
+        self.cache_misses
+    }
+
+    /// Returns the number of pages that were written to the database file when committing.
+    pub fn cache_writes(&self) -> u64 {
+        self.cache_writes
+    }
+
+    /// Returns the number of pages that were written to the database file in the middle of a
+    /// transaction, because the page cache was full.
+    pub fn cache_spills(&self) -> u64 {
+        self.cache_spills
+    }
+
+    /// Returns the memory used by the schemas of the databases of this connection.
+    pub fn schema_used(&self) -> u64 {
+        self.schema_used
+    }
+
+    /// Returns the memory used by the prepared statements of this connection.
+    pub fn statements_used(&self) -> u64 {
+        self.statements_used
+    }
+
+    /// Returns the number of prepared statements of this connection, including the
+    /// statements that are cached.
+    pub fn statements(&self) -> usize {
+        self.statements
+    }
+
+    /// Returns the number of lookaside memory slots in use by this connection.
+    pub fn lookaside_used(&self) -> u64 {
+        self.lookaside_used
+    }
+}
+
+impl SqliteConnection {
+    /// Returns the memory and page cache usage of this connection, with
+    /// [`sqlite3_db_status()`](https://www.sqlite.org/c3ref/db_status.html), and of SQLite as a
+    /// whole, with [`sqlite3_status64()`](https://www.sqlite.org/c3ref/status.html).
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::sqlite::SqliteConnection) -> sqlx::Result<()> {
+    /// let status = conn.status().await?;
+    ///
+    /// println!(
+    ///     "{} bytes in the page cache, {} hits, {} misses",
+    ///     status.cache_used(),
+    ///     status.cache_hits(),
+    ///     status.cache_misses(),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&mut self) -> Result<SqliteStatus, Error> {
+        let mut locked = self.lock_handle().await?;
+        let db = locked.as_raw_handle();
+
+        let mut memory_used: sqlite3_int64 = 0;
+        let mut memory_highwater: sqlite3_int64 = 0;
+
+        let status = unsafe {
+            sqlite3_status64(
+                SQLITE_STATUS_MEMORY_USED,
+                &mut memory_used,
+                &mut memory_highwater,
+                0,
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(err_protocol!(
+                "failed to read the memory status: {}",
+                status
+            ));
+        }
+
+        // SAFETY: the handle is locked
+        let statements = unsafe {
+            let mut count = 0;
+            let mut stmt = sqlite3_next_stmt(db.as_ptr(), ptr::null_mut());
+
+            while !stmt.is_null() {
+                count += 1;
+                stmt = sqlite3_next_stmt(db.as_ptr(), stmt);
+            }
+
+            count
+        };
+
+        Ok(SqliteStatus {
+            memory_used: memory_used as u64,
+            memory_highwater: memory_highwater as u64,
+            cache_used: db_status(db, SQLITE_DBSTATUS_CACHE_USED)?,
+            cache_hits: db_status(db, SQLITE_DBSTATUS_CACHE_HIT)?,
+            cache_misses: db_status(db, SQLITE_DBSTATUS_CACHE_MISS)?,
+            cache_writes: db_status(db, SQLITE_DBSTATUS_CACHE_WRITE)?,
+            cache_spills: db_status(db, SQLITE_DBSTATUS_CACHE_SPILL)?,
+            schema_used: db_status(db, SQLITE_DBSTATUS_SCHEMA_USED)?,
+            statements_used: db_status(db, SQLITE_DBSTATUS_STMT_USED)?,
+            statements,
+            lookaside_used: db_status(db, SQLITE_DBSTATUS_LOOKASIDE_USED)?,
+        })
+    }
+}
+
+/// Returns the current value of a status counter of the connection, which must be locked.
+fn db_status(db: NonNull<sqlite3>, op: c_int) -> Result<u64, Error> {
+    let mut current = 0;
+    let mut highwater = 0;
+
+    let status = unsafe { sqlite3_db_status(db.as_ptr(), op, &mut current, &mut highwater, 0) };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(db.as_ptr()))));
+    }
+
+    Ok(current as u64)
+}
@@ -0,0 +1,88 @@
+//! Compacted copies of a database, with `VACUUM INTO`.
+//!
+//! <https://www.sqlite.org/lang_vacuum.html#vacuum_with_an_into_clause>
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::query::query;
+use crate::SqliteConnection;
+
+impl SqliteConnection {
+    /// Write a compacted copy of the `main` database to a new file at `path`, with
+    /// [`VACUUM INTO`](https://www.sqlite.org/lang_vacuum.html#vacuum_with_an_into_clause).
+    ///
+    /// The copy is made in a read transaction, so in the WAL journal mode the other connections
+    /// can keep reading and writing meanwhile, and the copy is consistent. Unlike
+    /// [`backup_to()`][Self::backup_to], the copy leaves out the free pages, and is not
+    /// restarted if the database is changed while it runs.
+    ///
+    /// Fails if a file exists at `path` already, or if the connection is in a transaction.
+    pub async fn vacuum_into(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let existed = path.exists();
+
+        let res = vacuum_into(self, path).await;
+
+        // the copy is incomplete, e.g. if it was interrupted
+        if res.is_err() && !existed {
+            let _ = fs::remove_file(path);
+        }
+
+        res
+    }
+
+    /// Like [`vacuum_into()`][Self::vacuum_into], and call `progress` about every `num_ops`
+    /// [virtual machine instructions](https://www.sqlite.org/opcode.html) while the copy is
+    /// made; if it returns `false`, the copy is interrupted, and the file is removed.
+    ///
+    /// This replaces the [progress handler][crate::LockedSqliteHandle::set_progress_handler] of
+    /// the connection, which is removed afterwards.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::sqlite::SqliteConnection) -> sqlx::Result<()> {
+    /// use std::time::{Duration, Instant};
+    ///
+    /// // give up on the copy after a minute
+    /// let deadline = Instant::now() + Duration::from_secs(60);
+    ///
+    /// conn.vacuum_into_with_progress("backup.db", 10_000, move || Instant::now() < deadline)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn vacuum_into_with_progress<F>(
+        &mut self,
+        path: impl AsRef<Path>,
+        num_ops: i32,
+        progress: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        self.lock_handle()
+            .await?
+            .set_progress_handler(num_ops, progress);
+
+        let res = self.vacuum_into(path).await;
+
+        self.lock_handle().await?.remove_progress_handler();
+
+        res
+    }
+}
+
+async fn vacuum_into(conn: &mut SqliteConnection, path: &Path) -> Result<(), Error> {
+    let path = path.to_str().ok_or_else(|| {
+        Error::Configuration(format!("database path {path:?} is not valid UTF-8").into())
+    })?;
+
+    query("VACUUM INTO ?1")
+        .bind(path)
+        .persistent(false)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
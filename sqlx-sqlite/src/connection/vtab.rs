@@ -0,0 +1,750 @@
+//! Virtual tables, which are implemented in Rust and queried like any other table.
+//!
+//! <https://www.sqlite.org/vtab.html>
+
+use std::ffi::{CStr, CString};
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_context, sqlite3_create_module_v2, sqlite3_declare_vtab, sqlite3_free,
+    sqlite3_index_constraint, sqlite3_index_info, sqlite3_index_orderby, sqlite3_int64,
+    sqlite3_module, sqlite3_mprintf, sqlite3_value, sqlite3_vtab, sqlite3_vtab_cursor,
+    SQLITE_ERROR, SQLITE_INDEX_CONSTRAINT_EQ, SQLITE_INDEX_CONSTRAINT_GE,
+    SQLITE_INDEX_CONSTRAINT_GLOB, SQLITE_INDEX_CONSTRAINT_GT, SQLITE_INDEX_CONSTRAINT_IS,
+    SQLITE_INDEX_CONSTRAINT_ISNOT, SQLITE_INDEX_CONSTRAINT_ISNOTNULL,
+    SQLITE_INDEX_CONSTRAINT_ISNULL, SQLITE_INDEX_CONSTRAINT_LE, SQLITE_INDEX_CONSTRAINT_LIKE,
+    SQLITE_INDEX_CONSTRAINT_LIMIT, SQLITE_INDEX_CONSTRAINT_LT, SQLITE_INDEX_CONSTRAINT_MATCH,
+    SQLITE_INDEX_CONSTRAINT_NE, SQLITE_INDEX_CONSTRAINT_OFFSET, SQLITE_INDEX_CONSTRAINT_REGEXP,
+    SQLITE_OK,
+};
+use sqlx_core::error::{BoxDynError, Error};
+
+use crate::connection::function::{function_values, set_value};
+use crate::connection::handle::ConnectionHandle;
+use crate::encode::Encode;
+use crate::{Sqlite, SqliteError, SqliteFunctionArgs};
+
+const PANICKED: &str = "virtual table panicked";
+
+/// A table of a virtual table module, whose rows are computed by Rust code.
+///
+/// A module is registered with
+/// [`SqliteConnectOptions::create_module()`][crate::SqliteConnectOptions::create_module], and
+/// its tables are read with a [`SqliteVirtualTableCursor`]. Virtual tables are read-only.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use std::str::FromStr;
+/// use std::sync::Arc;
+///
+/// use sqlx::error::BoxDynError;
+/// use sqlx::sqlite::{
+///     SqliteConnectOptions, SqliteFunctionArgs, SqliteVirtualTable, SqliteVirtualTableColumn,
+///     SqliteVirtualTableCursor, SqliteVirtualTableKind,
+/// };
+/// use sqlx::ConnectOptions;
+///
+/// // the names and sizes of some files, as the table `files`
+/// struct Files(Arc<Vec<(String, i64)>>);
+///
+/// struct FilesCursor {
+///     files: Arc<Vec<(String, i64)>>,
+///     row: usize,
+/// }
+///
+/// impl SqliteVirtualTable for Files {
+///     type Aux = Arc<Vec<(String, i64)>>;
+///     type Cursor = FilesCursor;
+///
+///     fn connect(files: &Self::Aux, _args: &[&str]) -> Result<(String, Self), BoxDynError> {
+///         Ok(("CREATE TABLE x(name TEXT, size INTEGER)".into(), Files(files.clone())))
+///     }
+///
+///     fn open(&self) -> Result<FilesCursor, BoxDynError> {
+///         Ok(FilesCursor { files: self.0.clone(), row: 0 })
+///     }
+/// }
+///
+/// impl SqliteVirtualTableCursor for FilesCursor {
+///     fn filter(&mut self, _: i32, _: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError> {
+///         self.row = 0;
+///         Ok(())
+///     }
+///
+///     fn next(&mut self) -> Result<(), BoxDynError> {
+///         self.row += 1;
+///         Ok(())
+///     }
+///
+///     fn eof(&self) -> bool {
+///         self.row >= self.files.len()
+///     }
+///
+///     fn column(
+///         &self,
+///         column: usize,
+///         value: &mut SqliteVirtualTableColumn<'_>,
+///     ) -> Result<(), BoxDynError> {
+///         let (name, size) = &self.files[self.row];
+///
+///         match column {
+///             0 => value.set(name.as_str()),
+///             _ => value.set(*size),
+///         }
+///
+///         Ok(())
+///     }
+///
+///     fn rowid(&self) -> Result<i64, BoxDynError> {
+///         Ok(self.row as i64)
+///     }
+/// }
+///
+/// let files = Arc::new(vec![("a.txt".to_owned(), 12), ("b.txt".to_owned(), 34)]);
+///
+/// let mut conn = SqliteConnectOptions::from_str("sqlite::memory:")?
+///     .create_module::<Files>("files", SqliteVirtualTableKind::EponymousOnly, files)
+///     .connect()
+///     .await?;
+///
+/// let total: i64 = sqlx::query_scalar("SELECT SUM(size) FROM files")
+///     .fetch_one(&mut conn)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait SqliteVirtualTable: Sized + 'static {
+    /// The data of the module, shared by all of its tables, e.g. the data structure that the
+    /// tables expose or a client for a remote API.
+    type Aux: Send + Sync + 'static;
+
+    /// The cursor which reads the rows of the table.
+    type Cursor: SqliteVirtualTableCursor;
+
+    /// Connect to a table of the module, which exists already.
+    ///
+    /// `args` are the arguments of the module in the `CREATE VIRTUAL TABLE` statement of the
+    /// table, and empty for an eponymous table.
+    ///
+    /// Returns the declaration of the columns of the table, as a `CREATE TABLE` statement whose
+    /// table name is ignored, e.g. `CREATE TABLE x(name TEXT, size INTEGER)`. Columns declared
+    /// as `HIDDEN` are left out of `SELECT *`; the hidden columns of an eponymous table are
+    /// also the arguments of the table as a table-valued function, e.g.
+    /// `SELECT * FROM series(1, 10)` for `CREATE TABLE x(value, start HIDDEN, stop HIDDEN)`.
+    fn connect(aux: &Self::Aux, args: &[&str]) -> Result<(String, Self), BoxDynError>;
+
+    /// Create a new table of a [regular][SqliteVirtualTableKind::Regular] module, with
+    /// `CREATE VIRTUAL TABLE`, e.g. to set up storage for it.
+    ///
+    /// The default implementation is [`connect()`][Self::connect].
+    fn create(aux: &Self::Aux, args: &[&str]) -> Result<(String, Self), BoxDynError> {
+        Self::connect(aux, args)
+    }
+
+    /// Choose how the rows for a query are read, from the constraints in its `WHERE` clause
+    /// and its `ORDER BY` clause.
+    ///
+    /// The constraints that are used are passed to [`SqliteVirtualTableCursor::filter()`]. A
+    /// table-valued function has to use the equality constraints on its hidden columns, which
+    /// are its arguments.
+    ///
+    /// The default implementation reads all rows, in any order.
+    fn best_index(&self, info: &mut SqliteIndexInfo<'_>) -> Result<(), BoxDynError> {
+        let _ = info;
+        Ok(())
+    }
+
+    /// Open a new cursor, to read the rows of the table.
+    fn open(&self) -> Result<Self::Cursor, BoxDynError>;
+}
+
+/// A cursor of a [`SqliteVirtualTable`], opened by [`SqliteVirtualTable::open()`].
+pub trait SqliteVirtualTableCursor: 'static {
+    /// Start reading the rows, with the plan chosen by [`SqliteVirtualTable::best_index()`].
+    ///
+    /// `index_num` is the number set by [`SqliteIndexInfo::set_index_num()`], and `args` are
+    /// the values of the constraints selected with [`SqliteIndexInfo::use_constraint()`].
+    fn filter(&mut self, index_num: i32, args: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError>;
+
+    /// Advance to the next row.
+    fn next(&mut self) -> Result<(), BoxDynError>;
+
+    /// Whether the cursor is past the last row.
+    fn eof(&self) -> bool;
+
+    /// Set `value` to the column of the current row at `column`, counted from zero in the
+    /// order of the declaration of the table. A column that is not set is `NULL`.
+    fn column(
+        &self,
+        column: usize,
+        value: &mut SqliteVirtualTableColumn<'_>,
+    ) -> Result<(), BoxDynError>;
+
+    /// Returns the rowid of the current row.
+    fn rowid(&self) -> Result<i64, BoxDynError>;
+}
+
+/// How the tables of a virtual table module are created.
+///
+/// See [Eponymous Virtual Tables](https://www.sqlite.org/vtab.html#eponymous_virtual_tables).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteVirtualTableKind {
+    /// Tables are created with `CREATE VIRTUAL TABLE name USING module(args...)`, and stored
+    /// in the schema of the database, so the module has to be registered on every connection
+    /// that reads them.
+    Regular,
+
+    /// A table with the name of the module exists in every connection, in addition to the
+    /// tables created with `CREATE VIRTUAL TABLE`.
+    Eponymous,
+
+    /// Only the table with the name of the module exists, e.g. for a table-valued function.
+    EponymousOnly,
+}
+
+/// The constraints and ordering of a query on a virtual table, to choose how to read its rows
+/// in [`SqliteVirtualTable::best_index()`].
+pub struct SqliteIndexInfo<'a> {
+    info: &'a mut sqlite3_index_info,
+}
+
+/// A constraint on a column of a virtual table, e.g. `size > ?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteIndexConstraint {
+    column: i32,
+    op: SqliteIndexConstraintOp,
+    usable: bool,
+}
+
+/// The operator of a [`SqliteIndexConstraint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqliteIndexConstraintOp {
+    Eq,
+    Gt,
+    Le,
+    Lt,
+    Ge,
+    Match,
+    Like,
+    Glob,
+    Regexp,
+    Ne,
+    IsNot,
+    IsNotNull,
+    IsNull,
+    Is,
+    Limit,
+    Offset,
+    /// Another operator, e.g. a function overloaded by the table.
+    Other(u8),
+}
+
+/// A term of the `ORDER BY` clause of a query on a virtual table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteIndexOrderBy {
+    column: i32,
+    desc: bool,
+}
+
+/// The value of a column of a virtual table, set by [`SqliteVirtualTableCursor::column()`].
+pub struct SqliteVirtualTableColumn<'a> {
+    ctx: *mut sqlite3_context,
+    marker: PhantomData<&'a mut sqlite3_context>,
+}
+
+impl SqliteIndexInfo<'_> {
+    /// Returns the constraints of the `WHERE` clause on the table.
+    pub fn constraints(&self) -> impl Iterator<Item = SqliteIndexConstraint> + '_ {
+        // SAFETY: SQLite passes `nConstraint` constraints
+        unsafe { raw_slice(self.info.aConstraint, self.info.nConstraint) }
+            .iter()
+            .map(
+                |constraint: &sqlite3_index_constraint| SqliteIndexConstraint {
+                    column: constraint.iColumn,
+                    op: SqliteIndexConstraintOp::from_code(constraint.op),
+                    usable: constraint.usable != 0,
+                },
+            )
+    }
+
+    /// Returns the terms of the `ORDER BY` clause, if all of them are on columns of the table.
+    pub fn order_by(&self) -> impl Iterator<Item = SqliteIndexOrderBy> + '_ {
+        // SAFETY: SQLite passes `nOrderBy` terms
+        unsafe { raw_slice(self.info.aOrderBy, self.info.nOrderBy) }
+            .iter()
+            .map(|order_by: &sqlite3_index_orderby| SqliteIndexOrderBy {
+                column: order_by.iColumn,
+                desc: order_by.desc != 0,
+            })
+    }
+
+    /// Pass the value of the constraint at `constraint`, in the order of
+    /// [`constraints()`][Self::constraints], as the argument at `arg` to
+    /// [`SqliteVirtualTableCursor::filter()`].
+    ///
+    /// The arguments must be numbered from zero without gaps. Set `omit` if the cursor only
+    /// returns rows that satisfy the constraint, so SQLite does not check it again.
+    ///
+    /// # Panics
+    /// If there is no constraint at `constraint`.
+    pub fn use_constraint(&mut self, constraint: usize, arg: usize, omit: bool) {
+        assert!(
+            constraint < self.info.nConstraint as usize,
+            "constraint index out of bounds: {constraint}"
+        );
+
+        // SAFETY: there is a usage for every constraint
+        let usage = unsafe { &mut *self.info.aConstraintUsage.add(constraint) };
+
+        usage.argvIndex = arg as c_int + 1;
+        usage.omit = omit.into();
+    }
+
+    /// Sets the number of the chosen plan, passed to [`SqliteVirtualTableCursor::filter()`].
+    pub fn set_index_num(&mut self, num: i32) {
+        self.info.idxNum = num;
+    }
+
+    /// Set to `true` if the cursor returns the rows in the order of
+    /// [`order_by()`][Self::order_by], so SQLite does not sort them.
+    pub fn set_order_by_consumed(&mut self, consumed: bool) {
+        self.info.orderByConsumed = consumed.into();
+    }
+
+    /// Sets the estimated cost of the plan, e.g. the number of disk accesses.
+    pub fn set_estimated_cost(&mut self, cost: f64) {
+        self.info.estimatedCost = cost;
+    }
+
+    /// Sets the estimated number of rows returned by the plan.
+    pub fn set_estimated_rows(&mut self, rows: i64) {
+        self.info.estimatedRows = rows;
+    }
+}
+
+impl SqliteIndexConstraint {
+    /// Returns the column of the constraint, counted from zero, or `-1` for the rowid.
+    pub fn column(&self) -> i32 {
+        self.column
+    }
+
+    /// Returns the operator of the constraint.
+    pub fn op(&self) -> SqliteIndexConstraintOp {
+        self.op
+    }
+
+    /// Whether the constraint can be used by this plan; only usable constraints may be passed
+    /// with [`SqliteIndexInfo::use_constraint()`].
+    pub fn is_usable(&self) -> bool {
+        self.usable
+    }
+}
+
+impl SqliteIndexConstraintOp {
+    fn from_code(code: u8) -> Self {
+        use SqliteIndexConstraintOp::*;
+
+        match c_int::from(code) {
+            SQLITE_INDEX_CONSTRAINT_EQ => Eq,
+            SQLITE_INDEX_CONSTRAINT_GT => Gt,
+            SQLITE_INDEX_CONSTRAINT_LE => Le,
+            SQLITE_INDEX_CONSTRAINT_LT => Lt,
+            SQLITE_INDEX_CONSTRAINT_GE => Ge,
+            SQLITE_INDEX_CONSTRAINT_MATCH => Match,
+            SQLITE_INDEX_CONSTRAINT_LIKE => Like,
+            SQLITE_INDEX_CONSTRAINT_GLOB => Glob,
+            SQLITE_INDEX_CONSTRAINT_REGEXP => Regexp,
+            SQLITE_INDEX_CONSTRAINT_NE => Ne,
+            SQLITE_INDEX_CONSTRAINT_ISNOT => IsNot,
+            SQLITE_INDEX_CONSTRAINT_ISNOTNULL => IsNotNull,
+            SQLITE_INDEX_CONSTRAINT_ISNULL => IsNull,
+            SQLITE_INDEX_CONSTRAINT_IS => Is,
+            SQLITE_INDEX_CONSTRAINT_LIMIT => Limit,
+            SQLITE_INDEX_CONSTRAINT_OFFSET => Offset,
+            _ => Other(code),
+        }
+    }
+}
+
+impl SqliteIndexOrderBy {
+    /// Returns the column of the term, counted from zero.
+    pub fn column(&self) -> i32 {
+        self.column
+    }
+
+    /// Whether the term is descending.
+    pub fn is_desc(&self) -> bool {
+        self.desc
+    }
+}
+
+impl SqliteVirtualTableColumn<'_> {
+    /// Set the value of the column, encoded like a bind argument.
+    pub fn set<'q, T>(&mut self, value: T)
+    where
+        T: Encode<'q, Sqlite>,
+    {
+        // SAFETY: the context is valid during the call of `column()`
+        unsafe { set_value(self.ctx, value) }
+    }
+}
+
+/// A virtual table module of [`SqliteConnectOptions`][crate::SqliteConnectOptions], created on
+/// each new connection.
+#[derive(Clone)]
+pub(crate) struct Module {
+    name: Arc<str>,
+    create: Arc<CreateModule>,
+}
+
+/// Registers a [`Module`] on a connection under the given name.
+type CreateModule =
+    dyn Fn(&mut ConnectionHandle, &str) -> Result<(), Error> + Send + Sync + 'static;
+
+impl Module {
+    pub(crate) fn new<T, N>(name: N, kind: SqliteVirtualTableKind, aux: T::Aux) -> Self
+    where
+        T: SqliteVirtualTable,
+        N: Into<Arc<str>>,
+    {
+        let aux = Arc::new(aux);
+
+        Module {
+            name: name.into(),
+            create: Arc::new(move |handle, name| {
+                create_module::<T>(handle, name, kind, Arc::clone(&aux))
+            }),
+        }
+    }
+
+    pub(crate) fn create(&self, handle: &mut ConnectionHandle) -> Result<(), Error> {
+        (self.create)(handle, &self.name)
+    }
+}
+
+impl Debug for Module {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Module")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+// the client data of a module, which outlives its tables
+struct RawModule<T: SqliteVirtualTable> {
+    module: sqlite3_module,
+    aux: Arc<T::Aux>,
+}
+
+// SQLite reads the fields of `sqlite3_vtab` and `sqlite3_vtab_cursor` at the start of these
+#[repr(C)]
+struct RawTable<T> {
+    base: sqlite3_vtab,
+    table: T,
+}
+
+#[repr(C)]
+struct RawCursor<C> {
+    base: sqlite3_vtab_cursor,
+    cursor: C,
+}
+
+pub(crate) fn create_module<T>(
+    handle: &mut ConnectionHandle,
+    name: &str,
+    kind: SqliteVirtualTableKind,
+    aux: Arc<T::Aux>,
+) -> Result<(), Error>
+where
+    T: SqliteVirtualTable,
+{
+    let c_name = CString::new(name).map_err(|_| err_protocol!("invalid module name: {}", name))?;
+
+    let module = Box::into_raw(Box::new(RawModule::<T> {
+        module: sqlite3_module {
+            iVersion: 1,
+            // a module with `xCreate` equal to `xConnect` is eponymous, and one without
+            // `xCreate` is only eponymous
+            xCreate: match kind {
+                SqliteVirtualTableKind::Regular => Some(create_table::<T>),
+                SqliteVirtualTableKind::Eponymous => Some(connect_table::<T>),
+                SqliteVirtualTableKind::EponymousOnly => None,
+            },
+            xConnect: Some(connect_table::<T>),
+            xBestIndex: Some(best_index::<T>),
+            xDisconnect: Some(disconnect_table::<T>),
+            xDestroy: Some(disconnect_table::<T>),
+            xOpen: Some(open_cursor::<T>),
+            xClose: Some(close_cursor::<T::Cursor>),
+            xFilter: Some(filter::<T::Cursor>),
+            xNext: Some(next::<T::Cursor>),
+            xEof: Some(eof::<T::Cursor>),
+            xColumn: Some(column::<T::Cursor>),
+            xRowid: Some(rowid::<T::Cursor>),
+            xUpdate: None,
+            xBegin: None,
+            xSync: None,
+            xCommit: None,
+            xRollback: None,
+            xFindFunction: None,
+            xRename: None,
+            xSavepoint: None,
+            xRelease: None,
+            xRollbackTo: None,
+            xShadowName: None,
+        },
+        aux,
+    }));
+
+    // the client data is dropped by SQLite if the module cannot be created
+    let r = unsafe {
+        sqlite3_create_module_v2(
+            handle.as_ptr(),
+            c_name.as_ptr(),
+            ptr::addr_of!((*module).module),
+            module as *mut c_void,
+            Some(drop_module::<T>),
+        )
+    };
+
+    if r == SQLITE_OK {
+        Ok(())
+    } else {
+        Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))))
+    }
+}
+
+unsafe extern "C" fn drop_module<T: SqliteVirtualTable>(p: *mut c_void) {
+    drop_guarded(Box::from_raw(p as *mut RawModule<T>));
+}
+
+unsafe extern "C" fn create_table<T: SqliteVirtualTable>(
+    db: *mut sqlite3,
+    aux: *mut c_void,
+    argc: c_int,
+    argv: *const *const c_char,
+    pp_vtab: *mut *mut sqlite3_vtab,
+    pz_err: *mut *mut c_char,
+) -> c_int {
+    init_table::<T>(true, db, aux, argc, argv, pp_vtab, pz_err)
+}
+
+unsafe extern "C" fn connect_table<T: SqliteVirtualTable>(
+    db: *mut sqlite3,
+    aux: *mut c_void,
+    argc: c_int,
+    argv: *const *const c_char,
+    pp_vtab: *mut *mut sqlite3_vtab,
+    pz_err: *mut *mut c_char,
+) -> c_int {
+    init_table::<T>(false, db, aux, argc, argv, pp_vtab, pz_err)
+}
+
+// not inlined, so `create_table()` and `connect_table()` are never merged into one function,
+// which would make a regular module eponymous
+#[inline(never)]
+unsafe fn init_table<T: SqliteVirtualTable>(
+    create: bool,
+    db: *mut sqlite3,
+    aux: *mut c_void,
+    argc: c_int,
+    argv: *const *const c_char,
+    pp_vtab: *mut *mut sqlite3_vtab,
+    pz_err: *mut *mut c_char,
+) -> c_int {
+    let module = &*(aux as *const RawModule<T>);
+
+    let r = guard(|| {
+        // the name of the module, the database and the table come before the arguments
+        let args = raw_slice(argv, argc)
+            .iter()
+            .skip(3)
+            .map(|&arg| CStr::from_ptr(arg).to_str())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (declaration, table) = if create {
+            T::create(&module.aux, &args)?
+        } else {
+            T::connect(&module.aux, &args)?
+        };
+
+        let declaration = CString::new(declaration)?;
+
+        if sqlite3_declare_vtab(db, declaration.as_ptr()) != SQLITE_OK {
+            return Err(Box::new(SqliteError::new(db)) as BoxDynError);
+        }
+
+        Ok(table)
+    });
+
+    match r {
+        Ok(table) => {
+            *pp_vtab = Box::into_raw(Box::new(RawTable {
+                base: sqlite3_vtab {
+                    pModule: ptr::null(),
+                    nRef: 0,
+                    zErrMsg: ptr::null_mut(),
+                },
+                table,
+            })) as *mut sqlite3_vtab;
+
+            SQLITE_OK
+        }
+
+        Err(e) => {
+            *pz_err = sqlite_string(&e.to_string());
+            SQLITE_ERROR
+        }
+    }
+}
+
+unsafe extern "C" fn best_index<T: SqliteVirtualTable>(
+    vtab: *mut sqlite3_vtab,
+    info: *mut sqlite3_index_info,
+) -> c_int {
+    let table = &(*(vtab as *mut RawTable<T>)).table;
+
+    let r = guard(|| table.best_index(&mut SqliteIndexInfo { info: &mut *info }));
+
+    result_code(vtab, r)
+}
+
+unsafe extern "C" fn disconnect_table<T: SqliteVirtualTable>(vtab: *mut sqlite3_vtab) -> c_int {
+    drop_guarded(Box::from_raw(vtab as *mut RawTable<T>));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn open_cursor<T: SqliteVirtualTable>(
+    vtab: *mut sqlite3_vtab,
+    pp_cursor: *mut *mut sqlite3_vtab_cursor,
+) -> c_int {
+    let table = &(*(vtab as *mut RawTable<T>)).table;
+
+    let r = guard(|| table.open()).map(|cursor| {
+        *pp_cursor = Box::into_raw(Box::new(RawCursor {
+            base: sqlite3_vtab_cursor {
+                pVtab: ptr::null_mut(),
+            },
+            cursor,
+        })) as *mut sqlite3_vtab_cursor;
+    });
+
+    result_code(vtab, r)
+}
+
+unsafe extern "C" fn close_cursor<C: SqliteVirtualTableCursor>(
+    cursor: *mut sqlite3_vtab_cursor,
+) -> c_int {
+    drop_guarded(Box::from_raw(cursor as *mut RawCursor<C>));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn filter<C: SqliteVirtualTableCursor>(
+    cursor: *mut sqlite3_vtab_cursor,
+    index_num: c_int,
+    _index_str: *const c_char,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) -> c_int {
+    let values = function_values(argc, argv);
+    let raw = &mut *(cursor as *mut RawCursor<C>);
+
+    let r = guard(|| {
+        raw.cursor
+            .filter(index_num, &SqliteFunctionArgs::new(&values))
+    });
+
+    result_code(raw.base.pVtab, r)
+}
+
+unsafe extern "C" fn next<C: SqliteVirtualTableCursor>(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    let raw = &mut *(cursor as *mut RawCursor<C>);
+
+    let r = guard(|| raw.cursor.next());
+
+    result_code(raw.base.pVtab, r)
+}
+
+unsafe extern "C" fn eof<C: SqliteVirtualTableCursor>(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    let raw = &*(cursor as *mut RawCursor<C>);
+
+    // a cursor that panicked has no more rows
+    catch_unwind(AssertUnwindSafe(|| raw.cursor.eof()))
+        .unwrap_or(true)
+        .into()
+}
+
+unsafe extern "C" fn column<C: SqliteVirtualTableCursor>(
+    cursor: *mut sqlite3_vtab_cursor,
+    ctx: *mut sqlite3_context,
+    i: c_int,
+) -> c_int {
+    let raw = &*(cursor as *mut RawCursor<C>);
+
+    let r = guard(|| {
+        raw.cursor.column(
+            i as usize,
+            &mut SqliteVirtualTableColumn {
+                ctx,
+                marker: PhantomData,
+            },
+        )
+    });
+
+    result_code(raw.base.pVtab, r)
+}
+
+unsafe extern "C" fn rowid<C: SqliteVirtualTableCursor>(
+    cursor: *mut sqlite3_vtab_cursor,
+    p_rowid: *mut sqlite3_int64,
+) -> c_int {
+    let raw = &*(cursor as *mut RawCursor<C>);
+
+    let r = guard(|| raw.cursor.rowid()).map(|rowid| *p_rowid = rowid);
+
+    result_code(raw.base.pVtab, r)
+}
+
+fn guard<R>(f: impl FnOnce() -> Result<R, BoxDynError>) -> Result<R, BoxDynError> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| Err(PANICKED.into()))
+}
+
+// drop a module, table or cursor of the user, which must not unwind into SQLite either; SQLite
+// has no use for an error here, as it releases the object regardless
+fn drop_guarded<T>(value: T) {
+    let _ = catch_unwind(AssertUnwindSafe(|| drop(value)));
+}
+
+// the error of a table is reported by SQLite as the error of the statement
+unsafe fn result_code(vtab: *mut sqlite3_vtab, r: Result<(), BoxDynError>) -> c_int {
+    let Err(e) = r else {
+        return SQLITE_OK;
+    };
+
+    sqlite3_free((*vtab).zErrMsg as *mut c_void);
+    (*vtab).zErrMsg = sqlite_string(&e.to_string());
+
+    SQLITE_ERROR
+}
+
+// a copy of `s` allocated by SQLite, which frees it
+unsafe fn sqlite_string(s: &str) -> *mut c_char {
+    let s = CString::new(s.replace('\0', "")).unwrap_or_default();
+
+    sqlite3_mprintf(b"%s\0".as_ptr() as *const c_char, s.as_ptr())
+}
+
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: c_int) -> &'a [T] {
+    if ptr.is_null() || len <= 0 {
+        return &[];
+    }
+
+    slice::from_raw_parts(ptr, len as usize)
+}
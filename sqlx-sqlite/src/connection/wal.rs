@@ -0,0 +1,220 @@
+//! Checkpoints and the commit hook of the write-ahead log.
+//!
+//! <https://www.sqlite.org/wal.html#checkpointing>
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::NonNull;
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_wal_autocheckpoint, sqlite3_wal_checkpoint_v2, sqlite3_wal_hook, SQLITE_BUSY,
+    SQLITE_CHECKPOINT_FULL, SQLITE_CHECKPOINT_PASSIVE, SQLITE_CHECKPOINT_RESTART,
+    SQLITE_CHECKPOINT_TRUNCATE, SQLITE_OK,
+};
+use sqlx_core::error::Error;
+
+use crate::connection::{ConnectionState, LockedSqliteHandle};
+use crate::{SqliteConnection, SqliteError};
+
+// the schema of the database that was opened, as opposed to an attached database
+const MAIN: &[u8] = b"main\0";
+
+// the number of pages in the WAL after which SQLite checkpoints by default
+const DEFAULT_AUTOCHECKPOINT: c_int = 1000;
+
+/// How much work a checkpoint does to copy the WAL back into the database file.
+///
+/// Refer to [SQLite documentation] for the meaning of the modes.
+///
+/// [SQLite documentation]: https://www.sqlite.org/c3ref/wal_checkpoint_v2.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteCheckpointMode {
+    /// Copy as many frames as possible without waiting for readers or writers.
+    Passive,
+    /// Wait for writers, and for readers of older snapshots, then copy all frames.
+    Full,
+    /// Like [`Full`][Self::Full], then wait for all readers, so the next writer starts the WAL
+    /// from the beginning.
+    Restart,
+    /// Like [`Restart`][Self::Restart], then truncate the WAL file to zero bytes.
+    Truncate,
+}
+
+impl SqliteCheckpointMode {
+    fn as_int(self) -> c_int {
+        match self {
+            SqliteCheckpointMode::Passive => SQLITE_CHECKPOINT_PASSIVE,
+            SqliteCheckpointMode::Full => SQLITE_CHECKPOINT_FULL,
+            SqliteCheckpointMode::Restart => SQLITE_CHECKPOINT_RESTART,
+            SqliteCheckpointMode::Truncate => SQLITE_CHECKPOINT_TRUNCATE,
+        }
+    }
+}
+
+/// The result of [`SqliteConnection::checkpoint()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteCheckpoint {
+    busy: bool,
+    log_frames: u32,
+    checkpointed_frames: u32,
+}
+
+impl SqliteCheckpoint {
+    /// Whether the checkpoint could not be finished, because another connection was reading or
+    /// writing the database for longer than the busy timeout.
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// Returns the number of frames in the WAL, or zero if the database is not in the WAL
+    /// journal mode.
+    pub fn log_frames(&self) -> u32 {
+        self.log_frames
+    }
+
+    /// Returns the number of frames of the WAL that are copied into the database file.
+    pub fn checkpointed_frames(&self) -> u32 {
+        self.checkpointed_frames
+    }
+}
+
+impl SqliteConnection {
+    /// Copy the frames of the write-ahead log of the `main` database into the database file,
+    /// with [`sqlite3_wal_checkpoint_v2()`](https://www.sqlite.org/c3ref/wal_checkpoint_v2.html).
+    ///
+    /// A mode other than [`Passive`][SqliteCheckpointMode::Passive] waits up to the busy timeout
+    /// for other connections; if they are still busy, the checkpoint stops, and the result
+    /// [`is_busy()`][SqliteCheckpoint::is_busy].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::sqlite::SqliteConnection) -> sqlx::Result<()> {
+    /// use sqlx::sqlite::SqliteCheckpointMode;
+    ///
+    /// let checkpoint = conn.checkpoint(SqliteCheckpointMode::Truncate).await?;
+    ///
+    /// if checkpoint.is_busy() {
+    ///     println!("{} frames are left in the WAL", checkpoint.log_frames());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn checkpoint(
+        &mut self,
+        mode: SqliteCheckpointMode,
+    ) -> Result<SqliteCheckpoint, Error> {
+        self.worker.checkpoint(mode).await
+    }
+}
+
+/// Run a checkpoint on the worker thread, as it may wait for other connections.
+pub(crate) fn checkpoint(
+    conn: &mut ConnectionState,
+    mode: SqliteCheckpointMode,
+) -> Result<SqliteCheckpoint, Error> {
+    let mut log_frames = 0;
+    let mut checkpointed_frames = 0;
+
+    let status = unsafe {
+        sqlite3_wal_checkpoint_v2(
+            conn.handle.as_ptr(),
+            MAIN.as_ptr() as *const c_char,
+            mode.as_int(),
+            &mut log_frames,
+            &mut checkpointed_frames,
+        )
+    };
+
+    if status != SQLITE_OK && status != SQLITE_BUSY {
+        return Err(Error::Database(Box::new(SqliteError::new(
+            conn.handle.as_ptr(),
+        ))));
+    }
+
+    // both are -1 if the database is not in the WAL journal mode
+    Ok(SqliteCheckpoint {
+        busy: status == SQLITE_BUSY,
+        log_frames: log_frames.try_into().unwrap_or(0),
+        checkpointed_frames: checkpointed_frames.try_into().unwrap_or(0),
+    })
+}
+
+/// The callback of a [`WalHook`], called with the name of the database and the number of
+/// frames in the WAL.
+type WalHookFn = dyn FnMut(&str, u32) + Send + 'static;
+
+/// A WAL hook that is set on the connection, and dropped when it is removed.
+pub(crate) struct WalHook(NonNull<WalHookFn>);
+
+unsafe impl Send for WalHook {}
+
+impl ConnectionState {
+    /// Drops the `wal_hook_callback` if it exists, and turns the automatic checkpoints back on.
+    pub(crate) fn remove_wal_hook(&mut self) {
+        if let Some(hook) = self.wal_hook_callback.take() {
+            unsafe {
+                // replaces the hook
+                sqlite3_wal_autocheckpoint(self.handle.as_ptr(), DEFAULT_AUTOCHECKPOINT);
+                drop(Box::from_raw(hook.0.as_ptr()));
+            }
+        }
+    }
+}
+
+impl LockedSqliteHandle<'_> {
+    /// Sets a callback that is invoked after each commit that writes to the write-ahead log,
+    /// with the name of the database and the number of frames in the WAL.
+    ///
+    /// The hook replaces the automatic checkpoints of SQLite, e.g. to checkpoint from another
+    /// task with [`SqliteConnection::checkpoint()`] once the WAL grows too large. The hook runs
+    /// on the worker thread of the connection, during the commit, so it must not block or use
+    /// the connection.
+    ///
+    /// Only a single WAL hook may be set per connection; setting a new hook replaces the old
+    /// one.
+    pub fn set_wal_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut(&str, u32) + Send + 'static,
+    {
+        unsafe {
+            // SAFETY: `Box::into_raw()` always returns a non-null pointer.
+            let callback = NonNull::new_unchecked(Box::into_raw(Box::new(callback)));
+            let arg = callback.as_ptr() as *mut c_void;
+
+            self.guard.remove_wal_hook();
+            self.guard.wal_hook_callback = Some(WalHook(callback));
+
+            sqlite3_wal_hook(
+                self.as_raw_handle().as_ptr(),
+                Some(wal_hook_callback::<F>),
+                arg,
+            );
+        }
+    }
+
+    /// Removes the WAL hook, and turns the automatic checkpoints back on with the default of
+    /// 1000 pages. The method does nothing if no hook was set.
+    pub fn remove_wal_hook(&mut self) {
+        self.guard.remove_wal_hook();
+    }
+}
+
+extern "C" fn wal_hook_callback<F>(
+    callback: *mut c_void,
+    _db: *mut sqlite3,
+    db_name: *const c_char,
+    frames: c_int,
+) -> c_int
+where
+    F: FnMut(&str, u32),
+{
+    unsafe {
+        let callback = &mut *callback.cast::<F>();
+        let db_name = CStr::from_ptr(db_name).to_str().unwrap_or_default();
+
+        // a panic must not unwind into SQLite, and the commit succeeded either way
+        let _ = catch_unwind(AssertUnwindSafe(|| callback(db_name, frames as u32)));
+    }
+
+    SQLITE_OK
+}
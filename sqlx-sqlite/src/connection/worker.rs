@@ -17,8 +17,11 @@ use sqlx_core::Either;
 use crate::connection::describe::describe;
 use crate::connection::establish::EstablishParams;
 use crate::connection::ConnectionState;
-use crate::connection::{execute, ConnectionHandleRaw};
-use crate::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow, SqliteStatement};
+use crate::connection::{execute, wal, ConnectionHandleRaw};
+use crate::{
+    Sqlite, SqliteArguments, SqliteCheckpoint, SqliteCheckpointMode, SqliteQueryResult, SqliteRow,
+    SqliteStatement,
+};
 
 // Each SQLite connection has a dedicated thread.
 
@@ -67,6 +70,10 @@ enum Command {
     ClearCache {
         tx: oneshot::Sender<()>,
     },
+    Checkpoint {
+        mode: SqliteCheckpointMode,
+        tx: oneshot::Sender<Result<SqliteCheckpoint, Error>>,
+    },
     Ping {
         tx: oneshot::Sender<()>,
     },
@@ -148,7 +155,10 @@ impl ConnectionWorker {
                             };
 
                             for res in iter {
-                                if tx.send(res).is_err() {
+                                // stepping a statement again after an error would run it again
+                                let has_error = res.is_err();
+
+                                if tx.send(res).is_err() || has_error {
                                     break;
                                 }
                             }
@@ -245,6 +255,9 @@ impl ConnectionWorker {
                             drop(conn);
                             conn = futures_executor::block_on(shared.conn.lock());
                         }
+                        Command::Checkpoint { mode, tx } => {
+                            tx.send(wal::checkpoint(&mut conn, mode)).ok();
+                        }
                         Command::Ping { tx } => {
                             tx.send(()).ok();
                         }
@@ -322,6 +335,14 @@ impl ConnectionWorker {
             .map_err(|_| Error::WorkerCrashed)
     }
 
+    pub(crate) async fn checkpoint(
+        &mut self,
+        mode: SqliteCheckpointMode,
+    ) -> Result<SqliteCheckpoint, Error> {
+        self.oneshot_cmd(|tx| Command::Checkpoint { mode, tx })
+            .await?
+    }
+
     pub(crate) async fn ping(&mut self) -> Result<(), Error> {
         self.oneshot_cmd(|tx| Command::Ping { tx }).await
     }
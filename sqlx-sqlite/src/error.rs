@@ -40,6 +40,19 @@ impl SqliteError {
         }
     }
 
+    /// For errors of calls that do not store them in the connection, e.g. of the session
+    /// extension
+    #[cfg(any(feature = "session", feature = "snapshot"))]
+    pub(crate) fn from_code(code: c_int) -> Self {
+        // a static string, for any code
+        let message = unsafe { CStr::from_ptr(libsqlite3_sys::sqlite3_errstr(code)) };
+
+        Self {
+            code,
+            message: message.to_string_lossy().into_owned(),
+        }
+    }
+
     /// For errors during extension load, the error message is supplied via a separate pointer
     pub(crate) fn extension(handle: *mut sqlite3, error_msg: &CStr) -> Self {
         let mut err = Self::new(handle);
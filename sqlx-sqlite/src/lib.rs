@@ -33,7 +33,19 @@ use std::sync::atomic::AtomicBool;
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
 pub use column::SqliteColumn;
-pub use connection::{LockedSqliteHandle, SqliteConnection};
+#[cfg(feature = "preupdate-hook")]
+pub use connection::SqlitePreupdateHookResult;
+#[cfg(feature = "snapshot")]
+pub use connection::SqliteSnapshot;
+pub use connection::{
+    LockedSqliteHandle, SqliteAggregate, SqliteBackup, SqliteBackupProgress, SqliteBlob,
+    SqliteCheckpoint, SqliteCheckpointMode, SqliteConnection, SqliteFunctionArgs,
+    SqliteIndexConstraint, SqliteIndexConstraintOp, SqliteIndexInfo, SqliteIndexOrderBy,
+    SqliteOperation, SqliteStatus, SqliteUpdateHookResult, SqliteVirtualTable,
+    SqliteVirtualTableColumn, SqliteVirtualTableCursor, SqliteVirtualTableKind,
+};
+#[cfg(feature = "session")]
+pub use connection::{SqliteConflict, SqliteConflictAction, SqliteConflictKind, SqliteSession};
 pub use database::Sqlite;
 pub use error::SqliteError;
 pub use options::{
@@ -41,6 +53,7 @@ pub use options::{
 };
 pub use query_result::SqliteQueryResult;
 pub use row::SqliteRow;
+pub use rw_pool::{SqliteRwPool, SqliteRwPoolOptions};
 pub use statement::SqliteStatement;
 pub use transaction::SqliteTransactionManager;
 pub use type_info::SqliteTypeInfo;
@@ -63,6 +76,7 @@ mod logger;
 mod options;
 mod query_result;
 mod row;
+mod rw_pool;
 mod statement;
 mod transaction;
 mod type_info;
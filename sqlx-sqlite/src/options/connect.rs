@@ -1,3 +1,4 @@
+use crate::connection::cipher::ensure_cipher;
 use crate::{SqliteConnectOptions, SqliteConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
@@ -34,12 +35,29 @@ impl ConnectOptions for SqliteConnectOptions {
             // Execute PRAGMAs
             conn.execute(&*self.pragma_string()).await?;
 
-            if !self.collations.is_empty() {
+            if matches!(self.pragmas.get("key"), Some(Some(_))) {
+                ensure_cipher(&mut conn).await?;
+            }
+
+            for (path, schema) in &self.attached {
+                conn.attach(path, schema).await?;
+            }
+
+            if !self.collations.is_empty() || !self.functions.is_empty() || !self.modules.is_empty()
+            {
                 let mut locked = conn.lock_handle().await?;
 
                 for collation in &self.collations {
                     collation.create(&mut locked.guard.handle)?;
                 }
+
+                for function in &self.functions {
+                    function.create(&mut locked.guard.handle)?;
+                }
+
+                for module in &self.modules {
+                    module.create(&mut locked.guard.handle)?;
+                }
             }
 
             Ok(conn)
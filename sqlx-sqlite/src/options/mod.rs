@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 mod auto_vacuum;
 mod connect;
@@ -17,7 +17,17 @@ use std::{borrow::Cow, time::Duration};
 pub use synchronous::SqliteSynchronous;
 
 use crate::common::DebugFn;
+use crate::connection::busy::BusyHandler;
+use crate::connection::cipher::quote_key;
 use crate::connection::collation::Collation;
+use crate::connection::establish::MemoryAnchor;
+use crate::connection::function::Function;
+use crate::connection::vtab::Module;
+use crate::encode::Encode;
+use crate::error::BoxDynError;
+use crate::{
+    Sqlite, SqliteAggregate, SqliteFunctionArgs, SqliteVirtualTable, SqliteVirtualTableKind,
+};
 use sqlx_core::IndexMap;
 
 /// Options and flags which can be used to configure a SQLite connection.
@@ -56,11 +66,13 @@ use sqlx_core::IndexMap;
 pub struct SqliteConnectOptions {
     pub(crate) filename: Cow<'static, Path>,
     pub(crate) in_memory: bool,
+    pub(crate) memory_anchor: Arc<MemoryAnchor>,
     pub(crate) read_only: bool,
     pub(crate) create_if_missing: bool,
     pub(crate) shared_cache: bool,
     pub(crate) statement_cache_capacity: usize,
     pub(crate) busy_timeout: Duration,
+    pub(crate) busy_handler: Option<BusyHandler>,
     pub(crate) log_settings: LogSettings,
     pub(crate) immutable: bool,
     pub(crate) vfs: Option<Cow<'static, str>>,
@@ -71,11 +83,15 @@ pub struct SqliteConnectOptions {
     /// be added to the map with a `None` value.
     /// <https://www.sqlite.org/loadext.html#loading_an_extension>
     pub(crate) extensions: IndexMap<Cow<'static, str>, Option<Cow<'static, str>>>,
+    pub(crate) allowed_extensions: Option<Vec<Cow<'static, str>>>,
+    pub(crate) attached: Vec<(Cow<'static, Path>, Cow<'static, str>)>,
 
     pub(crate) command_channel_size: usize,
     pub(crate) row_channel_size: usize,
 
     pub(crate) collations: Vec<Collation>,
+    pub(crate) functions: Vec<Function>,
+    pub(crate) modules: Vec<Module>,
 
     pub(crate) serialized: bool,
     pub(crate) thread_name: Arc<DebugFn<dyn Fn(u64) -> String + Send + Sync + 'static>>,
@@ -184,17 +200,23 @@ impl SqliteConnectOptions {
         Self {
             filename: Cow::Borrowed(Path::new(":memory:")),
             in_memory: false,
+            memory_anchor: Default::default(),
             read_only: false,
             create_if_missing: false,
             shared_cache: false,
             statement_cache_capacity: 100,
             busy_timeout: Duration::from_secs(5),
+            busy_handler: None,
             log_settings: Default::default(),
             immutable: false,
             vfs: None,
             pragmas,
             extensions: Default::default(),
+            allowed_extensions: None,
+            attached: Vec::new(),
             collations: Default::default(),
+            functions: Default::default(),
+            modules: Default::default(),
             serialized: false,
             thread_name: Arc::new(DebugFn(|id| format!("sqlx-sqlite-worker-{id}"))),
             command_channel_size: 50,
@@ -208,6 +230,38 @@ impl SqliteConnectOptions {
     /// Sets the name of the database file.
     pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
         self.filename = Cow::Owned(filename.as_ref().to_owned());
+        self.memory_anchor = Default::default();
+        self
+    }
+
+    /// Use the in-memory database `name`, which is shared by all connections opened with these
+    /// options, e.g. by all connections of a pool.
+    ///
+    /// SQLite deletes an in-memory database when its last connection is closed, so the options
+    /// keep a connection to it once one is opened, until the options and all their clones are
+    /// dropped, e.g. with the pool. Options with the same `name` use the same database, if it
+    /// still exists.
+    ///
+    /// This is what the URL `sqlite::memory:` does, with a unique name; a
+    /// [`filename()`][Self::filename] of `:memory:` is a new database for each connection
+    /// instead.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    ///
+    /// let pool = SqlitePoolOptions::new()
+    ///     .max_connections(4)
+    ///     .connect_with(SqliteConnectOptions::new().shared_in_memory("cache"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shared_in_memory(mut self, name: &str) -> Self {
+        self.filename = Cow::Owned(PathBuf::from(format!("file:{name}")));
+        self.in_memory = true;
+        self.shared_cache = true;
+        self.memory_anchor = Default::default();
         self
     }
 
@@ -290,11 +344,52 @@ impl SqliteConnectOptions {
     /// returning a busy timeout error.
     ///
     /// The default busy timeout is 5 seconds.
+    ///
+    /// The timeout is not used if a [`busy_handler()`][Self::busy_handler] is set.
     pub fn busy_timeout(mut self, timeout: Duration) -> Self {
         self.busy_timeout = timeout;
         self
     }
 
+    /// Sets a handler that decides whether to retry when the database is locked by another
+    /// connection, instead of the [`busy_timeout()`][Self::busy_timeout].
+    ///
+    /// The handler is called with the number of times it was called before for the same lock,
+    /// and returns `true` to try again, or `false` to give up and return a busy error. It runs
+    /// on the worker thread of the connection, so it may sleep before it returns, e.g. to back
+    /// off with jitter:
+    ///
+    /// ```rust,no_run
+    /// # fn example() -> sqlx::Result<()> {
+    /// use std::str::FromStr;
+    /// use std::time::Duration;
+    ///
+    /// use sqlx::sqlite::SqliteConnectOptions;
+    ///
+    /// let options = SqliteConnectOptions::from_str("sqlite:app.db")?.busy_handler(|attempts| {
+    ///     if attempts >= 10 {
+    ///         return false;
+    ///     }
+    ///
+    ///     std::thread::sleep(Duration::from_millis(1 << attempts));
+    ///     true
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A panic in the handler gives up. The handler must not use the connection.
+    ///
+    /// See [`sqlite3_busy_handler()`](https://www.sqlite.org/c3ref/busy_handler.html) for
+    /// details.
+    pub fn busy_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(u32) -> bool + Send + Sync + 'static,
+    {
+        self.busy_handler = Some(BusyHandler::new(handler));
+        self
+    }
+
     /// Sets the [synchronous](https://www.sqlite.org/pragma.html#pragma_synchronous) setting for the database connection.
     ///
     /// The default synchronous settings is FULL. However, if durability is not a concern,
@@ -324,6 +419,20 @@ impl SqliteConnectOptions {
         self.pragma("page_size", page_size.to_string())
     }
 
+    /// Sets the key of a database that is encrypted with [SQLCipher](https://www.zetetic.net/sqlcipher/),
+    /// with [`PRAGMA key`](https://www.zetetic.net/sqlcipher/sqlcipher-api/#key).
+    ///
+    /// The key is applied before any other statement on each connection, so the options can be
+    /// used for a pool. A new database is encrypted with the key. The key can be changed with
+    /// [`SqliteConnection::rekey()`][crate::SqliteConnection::rekey].
+    ///
+    /// SQLite has to be built with SQLCipher, e.g. with the `sqlite-sqlcipher` feature; if it is
+    /// not, connecting fails, instead of storing the data unencrypted. A wrong key is only
+    /// detected by the first query that reads the database.
+    pub fn key(self, key: &str) -> Self {
+        self.pragma("key", quote_key(key))
+    }
+
     /// Sets custom initial pragma for the database connection.
     pub fn pragma<K, V>(mut self, key: K, value: V) -> Self
     where
@@ -338,6 +447,30 @@ impl SqliteConnectOptions {
     ///
     /// If a collation with the same name already exists, it will be replaced.
     ///
+    /// The collation is registered on every connection opened with these options, e.g. each
+    /// connection of a pool, so it can be used by the `COLLATE` clauses of queries and of the
+    /// schema:
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use std::str::FromStr;
+    ///
+    /// use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+    ///
+    /// let options = SqliteConnectOptions::from_str("sqlite:app.db")?
+    ///     .collation("nocase_unicode", |a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+    ///
+    /// let pool = SqlitePool::connect_with(options).await?;
+    ///
+    /// sqlx::query("SELECT name FROM item ORDER BY name COLLATE nocase_unicode")
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A panic in `collate` is caught, and the strings are considered equal.
+    ///
     /// See [`sqlite3_create_collation()`](https://www.sqlite.org/c3ref/create_collation.html) for details.
     ///
     /// Note this excerpt:
@@ -359,6 +492,105 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Add a user-defined scalar function, which computes a value from the arguments of a call.
+    ///
+    /// The arguments are decoded with [`SqliteFunctionArgs::get()`], and the result is encoded
+    /// like a bind argument; an error is returned to SQLite as the error of the statement.
+    /// `num_args` is the number of arguments of the function, or `-1` for any number of them.
+    /// A function with the same name and number of arguments, including a built-in one, is
+    /// replaced.
+    ///
+    /// Set `deterministic` if the function always returns the same result for the same
+    /// arguments. Deterministic functions can be used in indexes on expressions, `CHECK`
+    /// constraints and generated columns, and SQLite may call them less often.
+    ///
+    /// See [`sqlite3_create_function()`](https://www.sqlite.org/c3ref/create_function.html) for details.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use std::str::FromStr;
+    ///
+    /// use sqlx::sqlite::SqliteConnectOptions;
+    /// use sqlx::ConnectOptions;
+    ///
+    /// let mut conn = SqliteConnectOptions::from_str("sqlite:app.db")?
+    ///     .create_scalar_function("slug", 1, true, |args| {
+    ///         let title: String = args.get(0)?;
+    ///         Ok(title.to_lowercase().replace(' ', "-"))
+    ///     })
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// sqlx::query("CREATE INDEX IF NOT EXISTS posts_slug ON posts (slug(title))")
+    ///     .execute(&mut conn)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_scalar_function<N, F, R>(
+        mut self,
+        name: N,
+        num_args: i32,
+        deterministic: bool,
+        f: F,
+    ) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn(&SqliteFunctionArgs<'_>) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        self.functions
+            .push(Function::scalar(name, num_args, deterministic, f));
+        self
+    }
+
+    /// Add a user-defined aggregate function, which computes a value from the arguments of the
+    /// rows of a group, like `sum()` or `group_concat()`.
+    ///
+    /// `init` returns the [`SqliteAggregate`] state of a new group. The rows of the group are
+    /// added with [`SqliteAggregate::step()`], and the result is returned by
+    /// [`SqliteAggregate::finish()`]. `num_args` and `deterministic` are the same as for
+    /// [`create_scalar_function()`][Self::create_scalar_function].
+    ///
+    /// See [`SqliteAggregate`] for an example.
+    pub fn create_aggregate_function<N, F, A>(
+        mut self,
+        name: N,
+        num_args: i32,
+        deterministic: bool,
+        init: F,
+    ) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        self.functions
+            .push(Function::aggregate(name, num_args, deterministic, init));
+        self
+    }
+
+    /// Add a virtual table module, whose tables are implemented by `T`.
+    ///
+    /// `aux` is shared by all tables of the module, e.g. the data that they expose. `kind`
+    /// decides whether the tables are created with `CREATE VIRTUAL TABLE`, or a single table
+    /// with the name of the module exists in every connection. A module with the same name is
+    /// replaced.
+    ///
+    /// See [`SqliteVirtualTable`] for an example, and [The Virtual Table Mechanism Of SQLite](https://www.sqlite.org/vtab.html) for details.
+    pub fn create_module<T>(
+        mut self,
+        name: impl Into<Arc<str>>,
+        kind: SqliteVirtualTableKind,
+        aux: T::Aux,
+    ) -> Self
+    where
+        T: SqliteVirtualTable,
+    {
+        self.modules.push(Module::new::<T, _>(name, kind, aux));
+        self
+    }
+
     /// Set to `true` to signal to SQLite that the database file is on read-only media.
     ///
     /// If enabled, SQLite assumes the database file _cannot_ be modified, even by higher
@@ -476,6 +708,66 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Sets the extensions that may be loaded with [`extension`][Self::extension] and
+    /// [`extension_with_entrypoint`][Self::extension_with_entrypoint].
+    ///
+    /// Connecting fails if another extension would be loaded, e.g. because the options are
+    /// built from configuration that is not trusted as much as the code which sets the
+    /// allow-list. The names are compared with the names of the extensions exactly as they
+    /// are given, so an extension that is added as a path has to be allowed as that path.
+    ///
+    /// By default, any extension may be loaded. Extensions are only ever loaded through the
+    /// C API while the connection is being established; the `load_extension()` SQL function
+    /// stays disabled.
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use std::str::FromStr;
+    /// # use sqlx_sqlite::SqliteConnectOptions;
+    /// # fn options() -> Result<SqliteConnectOptions, Error> {
+    /// let options = SqliteConnectOptions::from_str("sqlite://data.db")?
+    ///     .allowed_extensions(["mod_spatialite", "vss0"])
+    ///     .extension("mod_spatialite");
+    /// # Ok(options)
+    /// # }
+    /// ```
+    pub fn allowed_extensions<I, E>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Cow<'static, str>>,
+    {
+        self.allowed_extensions = Some(extensions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Attach the database file at `path` as `schema` to every connection opened with these
+    /// options, e.g. each connection of a pool, after the `PRAGMA`s are executed.
+    ///
+    /// See [`SqliteConnection::attach()`][crate::SqliteConnection::attach] for details.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use std::str::FromStr;
+    ///
+    /// use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+    ///
+    /// let options = SqliteConnectOptions::from_str("sqlite:app.db")?
+    ///     .create_if_missing(true)
+    ///     .attach("archive.db", "archive");
+    ///
+    /// let pool = SqlitePool::connect_with(options).await?;
+    ///
+    /// sqlx::query("INSERT INTO archive.item SELECT * FROM main.item WHERE archived")
+    ///     .execute(&pool)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attach(mut self, path: impl AsRef<Path>, schema: impl Into<Cow<'static, str>>) -> Self {
+        self.attached
+            .push((Cow::Owned(path.as_ref().to_owned()), schema.into()));
+        self
+    }
+
     /// Execute `PRAGMA optimize;` on the SQLite connection before closing.
     ///
     /// The SQLite manual recommends using this for long-lived databases.
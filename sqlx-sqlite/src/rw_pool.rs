@@ -0,0 +1,269 @@
+//! A pool with one connection for writing and many for reading, the usual way to use a database
+//! from multiple tasks without `SQLITE_BUSY` errors.
+//!
+//! <https://www.sqlite.org/wal.html#concurrency>
+
+use std::cmp;
+use std::future::Future;
+
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use sqlx_core::describe::Describe;
+use sqlx_core::error::Error;
+use sqlx_core::executor::{Execute, Executor};
+use sqlx_core::pool::PoolConnection;
+use sqlx_core::transaction::Transaction;
+use sqlx_core::Either;
+
+use crate::{
+    Sqlite, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions,
+    SqliteQueryResult, SqliteRow, SqliteStatement, SqliteTypeInfo,
+};
+
+/// Options to create a [`SqliteRwPool`].
+#[derive(Clone, Debug)]
+pub struct SqliteRwPoolOptions {
+    readers: SqlitePoolOptions,
+    writer: SqlitePoolOptions,
+}
+
+impl Default for SqliteRwPoolOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SqliteRwPoolOptions {
+    /// Returns the default options, with up to 10 connections for reading, like a
+    /// [`SqlitePool`].
+    pub fn new() -> Self {
+        SqliteRwPoolOptions {
+            readers: SqlitePoolOptions::new(),
+            writer: SqlitePoolOptions::new(),
+        }
+    }
+
+    /// Set the maximum number of connections for reading.
+    pub fn max_readers(mut self, max: u32) -> Self {
+        self.readers = self.readers.max_connections(max);
+        self
+    }
+
+    /// Set the options of the pool of connections for reading, e.g. the timeouts or callbacks.
+    pub fn reader_options(mut self, options: SqlitePoolOptions) -> Self {
+        self.readers = options;
+        self
+    }
+
+    /// Set the options of the pool of the connection for writing, which always has at most one
+    /// connection.
+    pub fn writer_options(mut self, options: SqlitePoolOptions) -> Self {
+        self.writer = options;
+        self
+    }
+
+    /// Create the pool from a URL, and open the connection for writing and one connection for
+    /// reading. Refer to [`SqliteConnectOptions`] for the format of the URL.
+    pub async fn connect(self, url: &str) -> Result<SqliteRwPool, Error> {
+        self.connect_with(url.parse()?).await
+    }
+
+    /// Create the pool from the options of the connections, and open the connection for writing
+    /// and one connection for reading.
+    ///
+    /// The connections use the [WAL journal mode](https://www.sqlite.org/wal.html), so reading
+    /// does not block writing or the other way around, and the connections for reading are
+    /// [read-only][SqliteConnectOptions::read_only]. As they need to share a database file,
+    /// `options` cannot be for an in-memory database.
+    pub async fn connect_with(self, options: SqliteConnectOptions) -> Result<SqliteRwPool, Error> {
+        if options.in_memory || options.filename.as_os_str() == ":memory:" {
+            return Err(Error::Configuration(
+                "a SqliteRwPool needs a database file, not an in-memory database".into(),
+            ));
+        }
+
+        let options = options.journal_mode(SqliteJournalMode::Wal);
+
+        let min_writers = cmp::min(self.writer.get_min_connections(), 1);
+
+        // the writer goes first, as it creates the database file and switches it to WAL
+        let writer = self
+            .writer
+            .max_connections(1)
+            .min_connections(min_writers)
+            .connect_with(options.clone())
+            .await?;
+
+        let readers = match self
+            .readers
+            .connect_with(options.read_only(true).create_if_missing(false))
+            .await
+        {
+            Ok(readers) => readers,
+            Err(e) => {
+                writer.close().await;
+                return Err(e);
+            }
+        };
+
+        Ok(SqliteRwPool { readers, writer })
+    }
+}
+
+/// A pool with exactly one connection for writing, and any number of read-only connections, to
+/// a database in the [WAL journal mode](https://www.sqlite.org/wal.html).
+///
+/// SQLite only allows one writer at a time, so a [`SqlitePool`] with multiple connections often
+/// runs into `SQLITE_BUSY` errors if many tasks write at once. This pool queues the writers
+/// instead, while the readers run concurrently with them.
+///
+/// As an [`Executor`], [`execute()`][Executor::execute] and
+/// [`execute_many()`][Executor::execute_many] use the connection for writing, and the other
+/// methods, like [`fetch_all()`][Executor::fetch_all], use a connection for reading. A query that
+/// writes and returns rows, e.g. with `RETURNING`, and a transaction must use the connection
+/// for writing explicitly, with [`write()`][Self::write] or [`begin()`][Self::begin].
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use sqlx::sqlite::SqliteRwPoolOptions;
+///
+/// let pool = SqliteRwPoolOptions::new()
+///     .max_readers(4)
+///     .connect("sqlite://data.db?mode=rwc")
+///     .await?;
+///
+/// sqlx::query("INSERT INTO item (name) VALUES (?)")
+///     .bind("a")
+///     .execute(&pool)
+///     .await?;
+///
+/// let names: Vec<String> = sqlx::query_scalar("SELECT name FROM item")
+///     .fetch_all(&pool)
+///     .await?;
+///
+/// let id: i64 = sqlx::query_scalar("INSERT INTO item (name) VALUES (?) RETURNING id")
+///     .bind("b")
+///     .fetch_one(&mut *pool.write().await?)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SqliteRwPool {
+    readers: SqlitePool,
+    writer: SqlitePool,
+}
+
+impl SqliteRwPool {
+    /// Create a pool with the default [`SqliteRwPoolOptions`] from a URL.
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        SqliteRwPoolOptions::new().connect(url).await
+    }
+
+    /// Create a pool with the default [`SqliteRwPoolOptions`] from the options of the
+    /// connections.
+    pub async fn connect_with(options: SqliteConnectOptions) -> Result<Self, Error> {
+        SqliteRwPoolOptions::new().connect_with(options).await
+    }
+
+    /// Retrieve a read-only connection, waiting until one is available.
+    pub fn read(&self) -> impl Future<Output = Result<PoolConnection<Sqlite>, Error>> + 'static {
+        self.readers.acquire()
+    }
+
+    /// Retrieve the connection for writing, waiting until the other writers are done with it.
+    pub fn write(&self) -> impl Future<Output = Result<PoolConnection<Sqlite>, Error>> + 'static {
+        self.writer.acquire()
+    }
+
+    /// Start a transaction on the connection for writing.
+    pub async fn begin(&self) -> Result<Transaction<'static, Sqlite>, Error> {
+        self.writer.begin().await
+    }
+
+    /// Returns the pool of the read-only connections.
+    pub fn readers(&self) -> &SqlitePool {
+        &self.readers
+    }
+
+    /// Returns the pool of the connection for writing.
+    pub fn writer(&self) -> &SqlitePool {
+        &self.writer
+    }
+
+    /// Close both pools, and wait until all the connections are closed.
+    ///
+    /// Refer to [`Pool::close()`][crate::pool::Pool::close] for details.
+    pub async fn close(&self) {
+        futures_util::future::join(self.readers.close(), self.writer.close()).await;
+    }
+
+    /// Returns `true` if [`close()`][Self::close] was called.
+    pub fn is_closed(&self) -> bool {
+        self.writer.is_closed()
+    }
+}
+
+impl<'p> Executor<'p> for &'_ SqliteRwPool {
+    type Database = Sqlite;
+
+    fn execute<'e, 'q: 'e, E>(self, query: E) -> BoxFuture<'e, Result<SqliteQueryResult, Error>>
+    where
+        'p: 'e,
+        E: 'q + Execute<'q, Sqlite>,
+    {
+        self.writer.execute(query)
+    }
+
+    fn execute_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<SqliteQueryResult, Error>>
+    where
+        'p: 'e,
+        E: 'q + Execute<'q, Sqlite>,
+    {
+        self.writer.execute_many(query)
+    }
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<Either<SqliteQueryResult, SqliteRow>, Error>>
+    where
+        'p: 'e,
+        E: 'q + Execute<'q, Sqlite>,
+    {
+        self.readers.fetch_many(query)
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<SqliteRow>, Error>>
+    where
+        'p: 'e,
+        E: 'q + Execute<'q, Sqlite>,
+    {
+        self.readers.fetch_optional(query)
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [SqliteTypeInfo],
+    ) -> BoxFuture<'e, Result<SqliteStatement<'q>, Error>>
+    where
+        'p: 'e,
+    {
+        self.readers.prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<Sqlite>, Error>>
+    where
+        'p: 'e,
+    {
+        self.readers.describe(sql)
+    }
+}
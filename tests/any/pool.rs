@@ -1,11 +1,12 @@
 use sqlx::any::{AnyConnectOptions, AnyPoolOptions};
+use sqlx::pool::{ReplicaPool, ReusePolicy};
 use sqlx::Executor;
-use std::sync::atomic::AtomicI32;
+use std::sync::atomic::{AtomicBool, AtomicI32};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
-    Arc,
+    Arc, Mutex,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[sqlx_macros::test]
 async fn pool_should_invoke_after_connect() -> anyhow::Result<()> {
@@ -216,3 +217,484 @@ async fn test_pool_callbacks() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn pool_should_record_metrics() -> anyhow::Result<()> {
+    use sqlx::pool::PoolMetricsObserver;
+
+    sqlx::any::install_default_drivers();
+
+    #[derive(Default)]
+    struct Counts {
+        acquired: AtomicUsize,
+        timed_out: AtomicUsize,
+    }
+
+    struct Observer(Arc<Counts>);
+
+    impl PoolMetricsObserver for Observer {
+        fn acquired(&self, _wait: Duration) {
+            self.0.acquired.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn acquire_timed_out(&self, _wait: Duration) {
+            self.0.timed_out.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let counts = Arc::new(Counts::default());
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_millis(100))
+        .metrics_observer(Observer(counts.clone()))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let conn = pool.acquire().await?;
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.size, 1);
+    assert_eq!(metrics.in_use, 1);
+    assert_eq!(metrics.connections_opened, 1);
+
+    // the only connection is in use
+    assert!(matches!(
+        pool.acquire().await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+
+    conn.close().await?;
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.size, 0);
+    assert_eq!(metrics.connections_closed, 1);
+    assert_eq!(metrics.acquire_timeouts, 1);
+
+    // `connect()` acquires a connection as well
+    assert_eq!(metrics.acquired, 2);
+    assert_eq!(metrics.acquire_wait.count(), 2);
+    assert_eq!(metrics.acquire_wait.buckets().len(), 13);
+    assert!(metrics.acquire_wait.buckets().last().unwrap().0.is_none());
+
+    assert_eq!(counts.acquired.load(Ordering::SeqCst), 2);
+    assert_eq!(counts.timed_out.load(Ordering::SeqCst), 1);
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_resize() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(Duration::from_millis(200))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let a = pool.acquire().await?;
+    let b = pool.acquire().await?;
+    assert_eq!(pool.size(), 2);
+
+    pool.set_max_connections(1)?;
+    assert_eq!(pool.max_connections(), 1);
+    assert_eq!(pool.options().get_max_connections(), 2);
+
+    // the connections in use are closed as they are returned, down to the new maximum
+    drop(a);
+    drop(b);
+
+    for _ in 0..100 {
+        if pool.size() <= 1 {
+            break;
+        }
+
+        sqlx_core::rt::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert!(pool.size() <= 1);
+
+    let a = pool.acquire().await?;
+    assert!(matches!(
+        pool.acquire().await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+
+    // invalid limits are rejected and leave the pool unchanged
+    assert!(matches!(
+        pool.set_max_connections(0),
+        Err(sqlx::Error::Configuration(_))
+    ));
+    assert!(matches!(
+        pool.set_min_connections(4),
+        Err(sqlx::Error::Configuration(_))
+    ));
+    assert_eq!(pool.max_connections(), 1);
+    assert_eq!(pool.min_connections(), 0);
+
+    pool.set_max_connections(3)?;
+
+    let b = pool.acquire().await?;
+    let c = pool.acquire().await?;
+    assert_eq!(pool.size(), 3);
+
+    drop((a, b, c));
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_close_after_shrinking_and_growing() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(3)
+        .acquire_timeout(Duration::from_millis(200))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let a = pool.acquire().await?;
+    let b = pool.acquire().await?;
+    let c = pool.acquire().await?;
+
+    // grow again while the shrink is still waiting for the connections to be returned
+    pool.set_max_connections(1)?;
+    pool.set_max_connections(3)?;
+
+    drop((a, b, c));
+
+    // the shrink gave up, so the capacity is back to exactly `max_connections`
+    let a = pool.acquire().await?;
+    let b = pool.acquire().await?;
+    let c = pool.acquire().await?;
+    assert!(matches!(
+        pool.acquire().await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+    drop((a, b, c));
+
+    sqlx_core::rt::timeout(Duration::from_secs(30), pool.close()).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_override_acquire_timeout() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(30))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let conn = pool.acquire().await?;
+
+    let started_at = Instant::now();
+    assert!(matches!(
+        pool.acquire_timeout(Duration::from_millis(50)).await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+    assert!(started_at.elapsed() < Duration::from_secs(30));
+
+    drop(conn);
+
+    let conn = pool.acquire_timeout(Duration::from_secs(5)).await?;
+    drop(conn);
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_run_health_check_query() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let connect = |health_check_query: &'static str, after_idle: Option<Duration>| {
+        let counter = counter.clone();
+
+        AnyPoolOptions::new()
+            .max_connections(1)
+            .health_check_query(health_check_query)
+            .health_check_after_idle(after_idle)
+            .after_connect(move |_conn, _meta| {
+                counter.fetch_add(1, Ordering::AcqRel);
+                Box::pin(async { Ok(()) })
+            })
+            .connect_lazy(&dotenvy::var("DATABASE_URL").unwrap())
+    };
+
+    // a failing health check closes the idle connection and opens a new one
+    let pool = connect("SELECT * FROM sqlx_no_such_table", None)?;
+    assert_eq!(
+        pool.options().get_health_check_query(),
+        Some("SELECT * FROM sqlx_no_such_table")
+    );
+
+    drop(pool.acquire().await?);
+    sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+    drop(pool.acquire().await?);
+    assert_eq!(counter.load(Ordering::Acquire), 2);
+    pool.close().await;
+
+    // recently used connections are not checked
+    counter.store(0, Ordering::Release);
+    let pool = connect(
+        "SELECT * FROM sqlx_no_such_table",
+        Some(Duration::from_secs(60)),
+    )?;
+
+    drop(pool.acquire().await?);
+    sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+    drop(pool.acquire().await?);
+    assert_eq!(counter.load(Ordering::Acquire), 1);
+    pool.close().await;
+
+    // a passing health check keeps the connection
+    counter.store(0, Ordering::Release);
+    let pool = connect("SELECT 1", None)?;
+
+    drop(pool.acquire().await?);
+    sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+    drop(pool.acquire().await?);
+    assert_eq!(counter.load(Ordering::Acquire), 1);
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_warm_up() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let pool = AnyPoolOptions::new()
+        .min_connections(3)
+        .max_connections(5)
+        .warm_up(true)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    assert!(pool.size() >= 3);
+    pool.close().await;
+
+    let pool = AnyPoolOptions::new()
+        .min_connections(3)
+        .max_connections(5)
+        .connect_lazy(&dotenvy::var("DATABASE_URL")?)?;
+
+    pool.warm_up().await?;
+    assert!(pool.size() >= 3);
+
+    // nothing left to open
+    pool.warm_up().await?;
+    assert!(pool.size() <= 5);
+
+    pool.close().await;
+    assert!(matches!(pool.warm_up().await, Err(sqlx::Error::PoolClosed)));
+
+    assert_eq!(
+        sqlx::Error::PoolWarmUp(Vec::new()).to_string(),
+        "failed to open 0 connection(s) while warming up the pool"
+    );
+    assert_eq!(
+        sqlx::Error::PoolWarmUp(vec![sqlx::Error::PoolTimedOut]).to_string(),
+        "failed to open 1 connection(s) while warming up the pool; \
+         first error: pool timed out while waiting for an open connection"
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_follow_reuse_policy() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    for policy in [ReusePolicy::Fifo, ReusePolicy::Lifo] {
+        let last_idle_for = Arc::new(Mutex::new(Duration::ZERO));
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(2)
+            .reuse_policy(policy)
+            .before_acquire({
+                let last_idle_for = last_idle_for.clone();
+                move |_conn, meta| {
+                    *last_idle_for.lock().unwrap() = meta.idle_for;
+                    Box::pin(async { Ok(true) })
+                }
+            })
+            .connect(&dotenvy::var("DATABASE_URL")?)
+            .await?;
+
+        let a = pool.acquire().await?;
+        let b = pool.acquire().await?;
+
+        // `a` has been idle the longest, `b` was returned most recently
+        drop(a);
+        sqlx_core::rt::sleep(Duration::from_millis(200)).await;
+        drop(b);
+        sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+
+        let _conn = pool.acquire().await?;
+        let idle_for = *last_idle_for.lock().unwrap();
+
+        match policy {
+            ReusePolicy::Fifo => assert!(idle_for >= Duration::from_millis(200)),
+            ReusePolicy::Lifo => assert!(idle_for < Duration::from_millis(200)),
+        }
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn replica_pool_should_route_reads() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let url = dotenvy::var("DATABASE_URL")?;
+
+    let primary = AnyPoolOptions::new()
+        .max_connections(2)
+        .connect(&url)
+        .await?;
+    let replica = AnyPoolOptions::new()
+        .max_connections(2)
+        .connect(&url)
+        .await?;
+
+    let pool = ReplicaPool::new(primary.clone(), [replica.clone()]);
+
+    // reads go to the replica, writes to the primary
+    let conn = pool.read().await?;
+    assert_eq!(replica.num_idle(), 0);
+    drop(conn);
+
+    let conn = pool.write().await?;
+    assert_eq!(primary.num_idle(), 0);
+    drop(conn);
+
+    // a lagging replica is skipped
+    let lag = Arc::new(Mutex::new(Duration::from_secs(60)));
+
+    let pool = pool
+        .max_replica_lag(Duration::from_secs(5), {
+            let lag = lag.clone();
+            move |_conn| {
+                let lag = *lag.lock().unwrap();
+                Box::pin(async move { Ok(lag) })
+            }
+        })
+        .replica_check_interval(Duration::ZERO);
+
+    sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+
+    let conn = pool.read().await?;
+    assert_eq!(primary.num_idle(), 0);
+    drop(conn);
+
+    *lag.lock().unwrap() = Duration::ZERO;
+
+    sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+
+    let conn = pool.read().await?;
+    assert_eq!(replica.num_idle(), 0);
+    drop(conn);
+
+    // reads fail over to the primary if the replica is down
+    replica.close().await;
+
+    let conn = pool.read().await?;
+    assert_eq!(primary.num_idle(), 0);
+    drop(conn);
+
+    pool.close().await;
+    assert!(pool.is_closed());
+    assert!(primary.is_closed());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn replica_pool_should_skip_unavailable_replicas() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let url = dotenvy::var("DATABASE_URL")?;
+
+    let primary = AnyPoolOptions::new()
+        .max_connections(2)
+        .connect(&url)
+        .await?;
+
+    // a replica that is busy is only skipped for the one call
+    let replica = AnyPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(30))
+        .connect(&url)
+        .await?;
+
+    let pool = ReplicaPool::new(primary.clone(), [replica.clone()])
+        .replica_acquire_timeout(Duration::from_millis(100))
+        .replica_check_interval(Duration::from_secs(60));
+
+    let held = replica.acquire().await?;
+
+    let started_at = Instant::now();
+    drop(pool.read().await?);
+    assert!(started_at.elapsed() < Duration::from_secs(5));
+
+    drop(held);
+
+    let conn = pool.read().await?;
+    assert_eq!(replica.num_idle(), 0);
+    drop(conn);
+
+    // a replica that can't open a connection is skipped until the next check
+    let failing = Arc::new(AtomicBool::new(true));
+    let attempts = Arc::new(AtomicUsize::new(0));
+
+    let replica = AnyPoolOptions::new()
+        .max_connections(1)
+        .after_connect({
+            let failing = failing.clone();
+            let attempts = attempts.clone();
+
+            move |_conn, _meta| {
+                attempts.fetch_add(1, Ordering::AcqRel);
+                let failing = failing.load(Ordering::Acquire);
+
+                Box::pin(async move {
+                    if failing {
+                        Err(sqlx::Error::Protocol("replica is down".into()))
+                    } else {
+                        Ok(())
+                    }
+                })
+            }
+        })
+        .connect_lazy(&url)?;
+
+    let pool = ReplicaPool::new(primary.clone(), [replica.clone()])
+        .replica_acquire_timeout(Duration::from_millis(100))
+        .replica_check_interval(Duration::from_secs(60));
+
+    drop(pool.read().await?);
+
+    let tried = attempts.load(Ordering::Acquire);
+    assert!(tried > 0);
+
+    failing.store(false, Ordering::Release);
+
+    drop(pool.read().await?);
+    assert_eq!(attempts.load(Ordering::Acquire), tried);
+    assert_eq!(replica.size(), 0);
+
+    pool.close().await;
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use futures::TryStreamExt;
-use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
+use sqlx::mysql::{
+    MySql, MySqlBinlogEventData, MySqlBinlogOptions, MySqlBinlogStream, MySqlBinlogValue,
+    MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlQueryExt, MySqlRow,
+};
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, setup_if_needed};
 use std::env;
@@ -99,381 +102,1542 @@ async fn it_executes_with_pool() -> anyhow::Result<()> {
 }
 
 #[sqlx_macros::test]
-async fn it_works_with_cache_disabled() -> anyhow::Result<()> {
+async fn it_executes_multiple_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let results: Vec<_> = conn
+        .execute_many(
+            "CREATE TEMPORARY TABLE notes (id INT PRIMARY KEY, body TEXT);
+             INSERT INTO notes VALUES (1, 'a; b'), (2, 'c');
+             DELETE FROM notes WHERE id = 2",
+        )
+        .try_collect()
+        .await?;
+
+    let affected: Vec<_> = results.iter().map(|r| r.rows_affected()).collect();
+    assert_eq!(affected, [0, 2, 1]);
+
+    let body: String = conn.fetch_one("SELECT body FROM notes").await?.get(0);
+    assert_eq!(body, "a; b");
+
     setup_if_needed();
 
     let mut url = url::Url::parse(&env::var("DATABASE_URL")?)?;
     url.query_pairs_mut()
-        .append_pair("statement-cache-capacity", "0");
+        .append_pair("multi-statements", "false");
 
     let mut conn = MySqlConnection::connect(url.as_ref()).await?;
 
-    for index in 1..=10_i32 {
-        let _ = sqlx::query("SELECT ?")
-            .bind(index)
-            .execute(&mut conn)
-            .await?;
-    }
+    assert!(conn.execute("SELECT 1; SELECT 2").await.is_err());
+    assert_eq!(conn.fetch_one("SELECT 1").await?.get::<i32, _>(0), 1);
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_drops_results_in_affected_rows() -> anyhow::Result<()> {
+async fn it_fetches_result_sets_of_procedures() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
 
-    // ~1800 rows should be iterated and dropped
-    let done = conn
-        .execute("select * from mysql.time_zone limit 1575")
+    conn.execute("DROP PROCEDURE IF EXISTS sqlx_result_sets")
         .await?;
+    conn.execute(
+        "CREATE PROCEDURE sqlx_result_sets(n INT)
+         BEGIN
+             SELECT n AS a;
+             SELECT n + 1 AS b, 'x' AS c;
+         END",
+    )
+    .await?;
 
-    // In MySQL, rows being returned isn't enough to flag it as an _affected_ row
-    assert_eq!(0, done.rows_affected());
+    for query in [
+        sqlx::query("CALL sqlx_result_sets(?)").bind(1),
+        sqlx::query("CALL sqlx_result_sets(1)"),
+    ] {
+        let results: Vec<_> = query.fetch_many(&mut conn).try_collect().await?;
 
-    Ok(())
-}
+        // the rows of both sets, the end of both sets, and the status of the procedure
+        assert_eq!(results.len(), 5);
 
-#[sqlx_macros::test]
-async fn it_selects_null() -> anyhow::Result<()> {
-    let mut conn = new::<MySql>().await?;
+        let first = results[0].as_ref().right().unwrap();
+        assert_eq!(first.columns().len(), 1);
+        assert_eq!(first.try_get::<i32, _>("a")?, 1);
 
-    let (val,): (Option<i32>,) = sqlx::query_as("SELECT NULL").fetch_one(&mut conn).await?;
+        assert!(results[1].is_left());
 
-    assert!(val.is_none());
+        let second = results[2].as_ref().right().unwrap();
+        assert_eq!(second.columns().len(), 2);
+        assert_eq!(second.try_get::<i64, _>("b")?, 2);
+        assert_eq!(second.try_get::<String, _>("c")?, "x");
 
-    let val: Option<i32> = conn.fetch_one("SELECT NULL").await?.try_get(0)?;
+        assert!(results[3].is_left() && results[4].is_left());
+    }
 
-    assert!(val.is_none());
+    conn.execute("DROP PROCEDURE sqlx_result_sets").await?;
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_fetch_one_and_ping() -> anyhow::Result<()> {
+async fn it_streams_long_data_parameters() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
 
-    let (_id,): (i32,) = sqlx::query_as("SELECT 1 as id")
-        .fetch_one(&mut conn)
+    conn.execute("CREATE TEMPORARY TABLE attachments (id INT PRIMARY KEY, data LONGBLOB)")
         .await?;
 
-    conn.ping().await?;
+    // larger than a single `COM_STMT_SEND_LONG_DATA` packet
+    let data: &'static [u8] = Vec::leak((0..3_000_000).map(|i| (i % 251) as u8).collect());
 
-    let (_id,): (i32,) = sqlx::query_as("SELECT 1 as id")
-        .fetch_one(&mut conn)
+    sqlx::query("INSERT INTO attachments (id, data) VALUES (?, ?), (?, ?)")
+        .bind(1)
+        .bind_long_data(data)
+        .bind(2)
+        .bind_long_data(&b""[..])
+        .execute(&mut conn)
         .await?;
 
+    let rows: Vec<(i32, Vec<u8>)> = sqlx::query_as("SELECT id, data FROM attachments ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows, [(1, data.to_vec()), (2, Vec::new())]);
+
     Ok(())
 }
 
-/// Test that we can interleave reads and writes to the database in one simple query.
 #[sqlx_macros::test]
-async fn it_interleaves_reads_and_writes() -> anyhow::Result<()> {
+async fn it_tracks_session_state() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
 
-    let mut s = conn.fetch(
-        "
-CREATE TEMPORARY TABLE messages (
-    id BIGINT PRIMARY KEY AUTO_INCREMENT,
-    text TEXT NOT NULL
-);
-
-SELECT 'Hello World' as _1;
+    conn.execute("SET SESSION session_track_state_change = ON")
+        .await?;
+    conn.clear_session_changed();
 
-INSERT INTO messages (text) VALUES ('this is a test');
+    conn.execute("SET SESSION time_zone = '+01:00'").await?;
 
-SELECT id, text FROM messages;
-        ",
+    assert_eq!(
+        conn.session_state().system_variable("time_zone"),
+        Some("+01:00")
     );
+    assert!(conn.session_state().is_changed());
 
-    let row = s.try_next().await?.unwrap();
+    conn.clear_session_changed();
+    conn.execute("SELECT 1").await?;
 
-    assert!("Hello World" == row.try_get::<&str, _>("_1")?);
+    assert!(!conn.session_state().is_changed());
 
-    let row = s.try_next().await?.unwrap();
+    Ok(())
+}
 
-    let id: i64 = row.try_get("id")?;
-    let text: &str = row.try_get("text")?;
+#[sqlx_macros::test]
+async fn it_sends_query_attributes() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
 
-    assert_eq!(1_i64, id);
-    assert_eq!("this is a test", text);
+    let res = sqlx::query("SELECT 1")
+        .with_attribute("trace_id", "abc")
+        .execute(&mut conn)
+        .await;
+
+    match res {
+        Ok(_) => {
+            // the function needs the `query_attributes` component
+            let value: Result<Option<String>, _> =
+                sqlx::query_scalar("SELECT mysql_query_attribute_string('trace_id')")
+                    .with_attribute("trace_id", "abc")
+                    .fetch_one(&mut conn)
+                    .await;
+
+            if let Ok(value) = value {
+                assert_eq!(value.as_deref(), Some("abc"));
+            }
+        }
+
+        // MariaDB and MySQL before 8.0.23
+        Err(e) => assert!(matches!(e, sqlx::Error::Configuration(_)), "{e:?}"),
+    }
+
+    let one: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(one, 1);
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_caches_statements() -> anyhow::Result<()> {
+async fn it_streams_binlog_events() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
 
-    for i in 0..2 {
-        let row = sqlx::query("SELECT ? AS val")
-            .bind(i)
-            .persistent(true)
-            .fetch_one(&mut conn)
-            .await?;
-
-        let val: u32 = row.get("val");
+    // binary logging may be disabled, or we may lack the privilege to read its status
+    let status = match conn.fetch_optional("SHOW MASTER STATUS").await {
+        Ok(Some(status)) => status,
+        _ => return Ok(()),
+    };
 
-        assert_eq!(i, val);
-    }
+    let file: String = status.try_get(0)?;
+    let position: u64 = status.try_get(1)?;
 
-    assert_eq!(1, conn.cached_statements_size());
-    conn.clear_cached_statements().await?;
-    assert_eq!(0, conn.cached_statements_size());
+    conn.execute(
+        r#"
+CREATE TABLE IF NOT EXISTS _sqlx_binlog_test (id INT PRIMARY KEY AUTO_INCREMENT, text TEXT);
+INSERT INTO _sqlx_binlog_test (text) VALUES ('streamed');
+    "#,
+    )
+    .await?;
 
-    for i in 0..2 {
-        let row = sqlx::query("SELECT ? AS val")
-            .bind(i)
-            .persistent(false)
-            .fetch_one(&mut conn)
-            .await?;
+    let options = MySqlBinlogOptions::new(4242)
+        .position(file, position)
+        .non_blocking(true);
 
-        let val: u32 = row.get("val");
+    let mut stream = MySqlBinlogStream::start(new::<MySql>().await?, options).await?;
+    let mut inserted = None;
 
-        assert_eq!(i, val);
+    while let Some(event) = stream.recv().await? {
+        if let MySqlBinlogEventData::WriteRows(rows) = event.data() {
+            if rows.table().name() == "_sqlx_binlog_test" {
+                inserted = rows.rows()[0].get(1).cloned();
+            }
+        }
     }
 
-    assert_eq!(0, conn.cached_statements_size());
+    stream.close().await?;
+    conn.execute("DROP TABLE _sqlx_binlog_test").await?;
+
+    // `binlog_format` may be `STATEMENT`, which logs no rows
+    if let Some(value) = inserted {
+        assert!(matches!(value, MySqlBinlogValue::Bytes(text) if &text[..] == b"streamed"));
+    }
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_bind_null_and_non_null_issue_540() -> anyhow::Result<()> {
+async fn it_can_change_user() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
     let mut conn = new::<MySql>().await?;
 
-    let row = sqlx::query("SELECT ?, ?")
-        .bind(50_i32)
-        .bind(None::<i32>)
+    conn.execute("SET @sqlx_change_user = 1").await?;
+
+    let value: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(1_i32)
         .fetch_one(&mut conn)
         .await?;
+    assert_eq!(value, 1);
+    assert_eq!(conn.cached_statements_size(), 1);
 
-    let v0: Option<i32> = row.get(0);
-    let v1: Option<i32> = row.get(1);
+    conn.change_user(&options).await?;
 
-    assert_eq!(v0, Some(50));
-    assert_eq!(v1, None);
+    // the session was reset
+    let variable: Option<i32> = sqlx::query_scalar("SELECT @sqlx_change_user")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(variable, None);
+
+    let value: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(2_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 2);
+
+    let wrong_password = options.clone().password("not the password");
+    assert!(conn.change_user(&wrong_password).await.is_err());
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_bind_only_null_issue_540() -> anyhow::Result<()> {
+async fn it_fetches_rows_through_a_cursor() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
 
-    let row = sqlx::query("SELECT ?")
-        .bind(None::<i32>)
-        .fetch_one(&mut conn)
+    let sql =
+        "SELECT 1 AS n UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4 UNION ALL SELECT 5";
+
+    let rows: Vec<i64> = sqlx::query_scalar(sql)
+        .fetch_size(2)
+        .fetch_all(&mut conn)
         .await?;
 
-    let v0: Option<i32> = row.get(0);
+    assert_eq!(rows, [1, 2, 3, 4, 5]);
 
-    assert_eq!(v0, None);
+    // stop in the middle of the cursor
+    {
+        let mut s = sqlx::query_scalar::<_, i64>(sql)
+            .fetch_size(2)
+            .fetch(&mut conn);
+
+        assert_eq!(s.try_next().await?, Some(1));
+        assert_eq!(s.try_next().await?, Some(2));
+        assert_eq!(s.try_next().await?, Some(3));
+    }
+
+    let value: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(7_i32)
+        .fetch_size(10)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 7);
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_bind_and_return_years() -> anyhow::Result<()> {
-    let mut conn = new::<MySql>().await?;
+async fn it_sends_connect_attributes() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .connect_attribute("program_name", "sqlx-test")
+        .connect_attribute("deployment", "ci")
+        .connect()
+        .await?;
 
-    conn.execute(
-        r#"
-CREATE TEMPORARY TABLE too_many_years (
-    id INT PRIMARY KEY AUTO_INCREMENT,
-    the YEAR NOT NULL
-);
-    "#,
+    // the performance schema may be disabled
+    let attributes: Vec<(String, String)> = match sqlx::query_as(
+        "SELECT ATTR_NAME, ATTR_VALUE FROM performance_schema.session_connect_attrs \
+         WHERE PROCESSLIST_ID = CONNECTION_ID()",
     )
-    .await?;
+    .fetch_all(&mut conn)
+    .await
+    {
+        Ok(attributes) if !attributes.is_empty() => attributes,
+        _ => return Ok(()),
+    };
 
-    sqlx::query(
-        r#"
-INSERT INTO too_many_years ( the ) VALUES ( ? );
-    "#,
-    )
-    .bind(2142)
-    .execute(&mut conn)
-    .await?;
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    };
 
-    let the: u16 = sqlx::query_scalar("SELECT the FROM too_many_years")
-        .fetch_one(&mut conn)
+    assert_eq!(attribute("_client_name"), Some("sqlx"));
+    assert_eq!(attribute("program_name"), Some("sqlx-test"));
+    assert_eq!(attribute("deployment"), Some("ci"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_waits_for_own_gtid() -> anyhow::Result<()> {
+    use sqlx::mysql::{MySqlConnectOptions, MySqlPoolGtidExt};
+    use sqlx::ConnectOptions;
+    use std::time::Duration;
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .track_gtids(true);
+
+    // MariaDB has no `session_track_gtids`
+    let mut conn = match options.connect().await {
+        Ok(conn) => conn,
+        Err(_) => return Ok(()),
+    };
+
+    conn.execute("CREATE TABLE IF NOT EXISTS _sqlx_gtid_test (id INT)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_gtid_test (id) VALUES (1)")
         .await?;
 
-    assert_eq!(the, 2142);
+    // `gtid_mode` may be off
+    let Some(gtid) = conn.last_gtid().map(ToOwned::to_owned) else {
+        return Ok(());
+    };
+
+    // the server executed its own transaction already
+    assert!(conn.wait_for_gtid(&gtid, Duration::from_secs(1)).await?);
+
+    let pool = MySqlPoolOptions::new().connect_with(options).await?;
+    assert!(pool
+        .acquire_after_gtid(&gtid, Duration::from_secs(1))
+        .await?
+        .is_some());
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
-    let mut conn = new::<MySql>().await?;
-    let mut tx = conn.begin().await?;
+async fn it_fetches_warnings() -> anyhow::Result<()> {
+    use sqlx::mysql::{MySqlConnectOptions, MySqlWarningLevel};
+    use sqlx::ConnectOptions;
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .fetch_warnings(true)
+        .connect()
+        .await?;
 
-    let tweet_id: u64 = sqlx::query("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' )")
-        .execute(&mut *tx)
-        .await?
-        .last_insert_id();
+    // without strict mode, a value that is too long is truncated with a warning
+    conn.execute("SET SESSION sql_mode = ''").await?;
+    conn.execute("CREATE TEMPORARY TABLE warnings (name VARCHAR(3))")
+        .await?;
 
-    let statement = tx.prepare("SELECT * FROM tweet WHERE id = ?").await?;
+    let result = conn
+        .execute("INSERT INTO warnings (name) VALUES ('abcdef')")
+        .await?;
 
-    assert_eq!(statement.column(0).name(), "id");
-    assert_eq!(statement.column(1).name(), "created_at");
-    assert_eq!(statement.column(2).name(), "text");
-    assert_eq!(statement.column(3).name(), "owner_id");
+    assert_eq!(result.warning_count(), 1);
+    assert_eq!(result.warnings().len(), 1);
+    assert_eq!(result.warnings()[0].level(), MySqlWarningLevel::Warning);
+    assert_eq!(result.warnings()[0].code(), 1265);
 
-    assert_eq!(statement.column(0).type_info().name(), "BIGINT");
-    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMP");
-    assert_eq!(statement.column(2).type_info().name(), "TEXT");
-    assert_eq!(statement.column(3).type_info().name(), "BIGINT");
+    // prepared statements report their warnings as well
+    let result = sqlx::query("INSERT INTO warnings (name) VALUES (?)")
+        .bind("ghijkl")
+        .execute(&mut conn)
+        .await?;
 
-    let row = statement.query().bind(tweet_id).fetch_one(&mut *tx).await?;
-    let tweet_text: &str = row.try_get("text")?;
+    assert_eq!(result.warnings().len(), 1);
 
-    assert_eq!(tweet_text, "Hello, World");
+    let result = conn
+        .execute("INSERT INTO warnings (name) VALUES ('abc')")
+        .await?;
+
+    assert_eq!(result.warning_count(), 0);
+    assert!(result.warnings().is_empty());
 
     Ok(())
 }
 
-// repro is more reliable with the basic scheduler used by `#[tokio::test]`
-#[cfg(feature = "_rt-tokio")]
-#[tokio::test]
-async fn test_issue_622() -> anyhow::Result<()> {
-    use std::time::Instant;
+#[sqlx_macros::test]
+async fn it_commits_xa_transactions() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlXid;
 
-    setup_if_needed();
+    let mut conn = new::<MySql>().await?;
 
-    let pool = MySqlPoolOptions::new()
-        .max_connections(1) // also fails with higher counts, e.g. 5
-        .connect(&std::env::var("DATABASE_URL").unwrap())
+    conn.execute("CREATE TABLE IF NOT EXISTS xa_test (id INT PRIMARY KEY)")
         .await?;
+    conn.execute("DELETE FROM xa_test").await?;
 
-    println!("pool state: {pool:?}");
+    let xid = MySqlXid::new("sqlx-xa-test")?
+        .with_branch(vec![0, 1, 0xff])?
+        .with_format_id(42);
 
-    let mut handles = vec![];
+    conn.xa_start(&xid).await?;
+    conn.execute("INSERT INTO xa_test (id) VALUES (1)").await?;
+    conn.xa_end(&xid).await?;
+    conn.xa_prepare(&xid).await?;
 
-    // given repro spawned 100 tasks but I found it reliably reproduced with 3
-    for i in 0..3 {
-        let pool = pool.clone();
+    // a prepared branch outlives the connection
+    conn.close().await?;
+    let mut conn = new::<MySql>().await?;
 
-        handles.push(sqlx_core::rt::spawn(async move {
-            {
-                let mut conn = pool.acquire().await.unwrap();
+    let recovered = conn.xa_recover().await?;
+    assert!(recovered.contains(&xid));
 
-                let _ = sqlx::query("SELECT 1").fetch_one(&mut *conn).await.unwrap();
+    conn.xa_commit(&xid).await?;
+    assert!(!conn.xa_recover().await?.contains(&xid));
 
-                // conn gets dropped here and should be returned to the pool
-            }
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM xa_test")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
 
-            // (do some other work here without holding on to a connection)
-            // this actually fixes the issue, depending on the timeout used
-            // sqlx_core::rt::sleep(Duration::from_millis(500)).await;
+    // a rolled back branch leaves no trace
+    let xid = MySqlXid::new("sqlx-xa-test-2")?;
 
-            {
-                let start = Instant::now();
-                match pool.acquire().await {
-                    Ok(conn) => {
-                        println!("{} acquire took {:?}", i, start.elapsed());
-                        drop(conn);
-                    }
-                    Err(e) => panic!("{i} acquire returned error: {e} pool state: {pool:?}"),
-                }
-            }
+    conn.xa_start(&xid).await?;
+    conn.execute("INSERT INTO xa_test (id) VALUES (2)").await?;
+    conn.xa_end(&xid).await?;
+    conn.xa_rollback(&xid).await?;
 
-            Result::<(), anyhow::Error>::Ok(())
-        }));
-    }
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM xa_test")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
 
-    futures::future::try_join_all(handles).await?;
+    conn.execute("DROP TABLE xa_test").await?;
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_work_with_transactions() -> anyhow::Result<()> {
+async fn it_cancels_a_query() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
-    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY);")
+
+    let id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+        .fetch_one(&mut conn)
         .await?;
+    assert_eq!(id, u64::from(conn.connection_id()));
 
-    // begin .. rollback
+    let token = conn.cancel_token();
 
-    let mut tx = conn.begin().await?;
-    sqlx::query("INSERT INTO users (id) VALUES (?)")
-        .bind(1_i32)
-        .execute(&mut *tx)
+    let cancel = sqlx_core::rt::spawn(async move {
+        sqlx_core::rt::sleep(std::time::Duration::from_secs(1)).await;
+        token.cancel().await
+    });
+
+    let started = std::time::Instant::now();
+
+    // an interrupted `SLEEP()` returns 1 instead of failing
+    let interrupted: i64 = sqlx::query_scalar("SELECT SLEEP(30)")
+        .fetch_one(&mut conn)
         .await?;
-    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+
+    cancel.await?;
+
+    assert_eq!(interrupted, 1);
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+
+    // the connection can still be used
+    let one: i64 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reconnects_with_the_session_setup() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .auto_reconnect(true)
+        .connect()
+        .await?;
+
+    conn.execute("SET @tenant = 42").await?;
+    sqlx::query("SET time_zone = ?")
+        .bind("+02:00")
+        .execute(&mut conn)
+        .await?;
+
+    let setup_id = conn.connection_id();
+
+    // the server closes the connection, as after `wait_timeout`
+    let mut other = new::<MySql>().await?;
+    other.execute(&*format!("KILL {setup_id}")).await?;
+    sqlx_core::rt::sleep(std::time::Duration::from_millis(100)).await;
+
+    let (tenant, time_zone): (i64, String) = sqlx::query_as("SELECT @tenant, @@time_zone")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_ne!(conn.connection_id(), setup_id);
+    assert_eq!(tenant, 42);
+    assert_eq!(time_zone, "+02:00");
+
+    // a lost transaction is not hidden
+    let mut tx = conn.begin().await?;
+    let id = tx.connection_id();
+    other.execute(&*format!("KILL {id}")).await?;
+    sqlx_core::rt::sleep(std::time::Duration::from_millis(100)).await;
+
+    assert!(tx.execute("SELECT 1").await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reconnects_for_a_cached_statement() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .auto_reconnect(true)
+        .connect()
+        .await?;
+
+    let query = "SELECT ? + 1";
+
+    let value: i64 = sqlx::query_scalar(query)
+        .bind(1_i64)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 2);
+    assert_eq!(conn.cached_statements_size(), 1);
+
+    let id = conn.connection_id();
+
+    // the server closes the connection, so the cached statement is executed on a lost connection
+    let mut other = new::<MySql>().await?;
+    other.execute(&*format!("KILL {id}")).await?;
+    sqlx_core::rt::sleep(std::time::Duration::from_millis(100)).await;
+
+    let value: i64 = sqlx::query_scalar(query)
+        .bind(2_i64)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 3);
+    assert_ne!(conn.connection_id(), id);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reconnects_for_a_text_query() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .auto_reconnect(true)
+        .connect()
+        .await?;
+
+    let id = conn.connection_id();
+
+    let mut other = new::<MySql>().await?;
+    other.execute(&*format!("KILL {id}")).await?;
+    sqlx_core::rt::sleep(std::time::Duration::from_millis(100)).await;
+
+    // without arguments, this is sent as a text query, with nothing to prepare first
+    let value: i64 = conn.fetch_one("SELECT 42").await?.get(0);
+    assert_eq!(value, 42);
+    assert_ne!(conn.connection_id(), id);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_works_with_cache_disabled() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let mut url = url::Url::parse(&env::var("DATABASE_URL")?)?;
+    url.query_pairs_mut()
+        .append_pair("statement-cache-capacity", "0");
+
+    let mut conn = MySqlConnection::connect(url.as_ref()).await?;
+
+    for index in 1..=10_i32 {
+        let _ = sqlx::query("SELECT ?")
+            .bind(index)
+            .execute(&mut conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_drops_results_in_affected_rows() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // ~1800 rows should be iterated and dropped
+    let done = conn
+        .execute("select * from mysql.time_zone limit 1575")
+        .await?;
+
+    // In MySQL, rows being returned isn't enough to flag it as an _affected_ row
+    assert_eq!(0, done.rows_affected());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_selects_null() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let (val,): (Option<i32>,) = sqlx::query_as("SELECT NULL").fetch_one(&mut conn).await?;
+
+    assert!(val.is_none());
+
+    let val: Option<i32> = conn.fetch_one("SELECT NULL").await?.try_get(0)?;
+
+    assert!(val.is_none());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_fetch_one_and_ping() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let (_id,): (i32,) = sqlx::query_as("SELECT 1 as id")
+        .fetch_one(&mut conn)
+        .await?;
+
+    conn.ping().await?;
+
+    let (_id,): (i32,) = sqlx::query_as("SELECT 1 as id")
+        .fetch_one(&mut conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Test that we can interleave reads and writes to the database in one simple query.
+#[sqlx_macros::test]
+async fn it_interleaves_reads_and_writes() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let mut s = conn.fetch(
+        "
+CREATE TEMPORARY TABLE messages (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    text TEXT NOT NULL
+);
+
+SELECT 'Hello World' as _1;
+
+INSERT INTO messages (text) VALUES ('this is a test');
+
+SELECT id, text FROM messages;
+        ",
+    );
+
+    let row = s.try_next().await?.unwrap();
+
+    assert!("Hello World" == row.try_get::<&str, _>("_1")?);
+
+    let row = s.try_next().await?.unwrap();
+
+    let id: i64 = row.try_get("id")?;
+    let text: &str = row.try_get("text")?;
+
+    assert_eq!(1_i64, id);
+    assert_eq!("this is a test", text);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caches_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    for i in 0..2 {
+        let row = sqlx::query("SELECT ? AS val")
+            .bind(i)
+            .persistent(true)
+            .fetch_one(&mut conn)
+            .await?;
+
+        let val: u32 = row.get("val");
+
+        assert_eq!(i, val);
+    }
+
+    assert_eq!(1, conn.cached_statements_size());
+    conn.clear_cached_statements().await?;
+    assert_eq!(0, conn.cached_statements_size());
+
+    for i in 0..2 {
+        let row = sqlx::query("SELECT ? AS val")
+            .bind(i)
+            .persistent(false)
+            .fetch_one(&mut conn)
+            .await?;
+
+        let val: u32 = row.get("val");
+
+        assert_eq!(i, val);
+    }
+
+    assert_eq!(0, conn.cached_statements_size());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_bind_null_and_non_null_issue_540() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let row = sqlx::query("SELECT ?, ?")
+        .bind(50_i32)
+        .bind(None::<i32>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    let v0: Option<i32> = row.get(0);
+    let v1: Option<i32> = row.get(1);
+
+    assert_eq!(v0, Some(50));
+    assert_eq!(v1, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_bind_only_null_issue_540() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let row = sqlx::query("SELECT ?")
+        .bind(None::<i32>)
+        .fetch_one(&mut conn)
+        .await?;
+
+    let v0: Option<i32> = row.get(0);
+
+    assert_eq!(v0, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_bind_and_return_years() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE too_many_years (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    the YEAR NOT NULL
+);
+    "#,
+    )
+    .await?;
+
+    sqlx::query(
+        r#"
+INSERT INTO too_many_years ( the ) VALUES ( ? );
+    "#,
+    )
+    .bind(2142)
+    .execute(&mut conn)
+    .await?;
+
+    let the: u16 = sqlx::query_scalar("SELECT the FROM too_many_years")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(the, 2142);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+    let mut tx = conn.begin().await?;
+
+    let tweet_id: u64 = sqlx::query("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' )")
+        .execute(&mut *tx)
+        .await?
+        .last_insert_id();
+
+    let statement = tx.prepare("SELECT * FROM tweet WHERE id = ?").await?;
+
+    assert_eq!(statement.column(0).name(), "id");
+    assert_eq!(statement.column(1).name(), "created_at");
+    assert_eq!(statement.column(2).name(), "text");
+    assert_eq!(statement.column(3).name(), "owner_id");
+
+    assert_eq!(statement.column(0).type_info().name(), "BIGINT");
+    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMP");
+    assert_eq!(statement.column(2).type_info().name(), "TEXT");
+    assert_eq!(statement.column(3).type_info().name(), "BIGINT");
+
+    let row = statement.query().bind(tweet_id).fetch_one(&mut *tx).await?;
+    let tweet_text: &str = row.try_get("text")?;
+
+    assert_eq!(tweet_text, "Hello, World");
+
+    Ok(())
+}
+
+// repro is more reliable with the basic scheduler used by `#[tokio::test]`
+#[cfg(feature = "_rt-tokio")]
+#[tokio::test]
+async fn test_issue_622() -> anyhow::Result<()> {
+    use std::time::Instant;
+
+    setup_if_needed();
+
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1) // also fails with higher counts, e.g. 5
+        .connect(&std::env::var("DATABASE_URL").unwrap())
+        .await?;
+
+    println!("pool state: {pool:?}");
+
+    let mut handles = vec![];
+
+    // given repro spawned 100 tasks but I found it reliably reproduced with 3
+    for i in 0..3 {
+        let pool = pool.clone();
+
+        handles.push(sqlx_core::rt::spawn(async move {
+            {
+                let mut conn = pool.acquire().await.unwrap();
+
+                let _ = sqlx::query("SELECT 1").fetch_one(&mut *conn).await.unwrap();
+
+                // conn gets dropped here and should be returned to the pool
+            }
+
+            // (do some other work here without holding on to a connection)
+            // this actually fixes the issue, depending on the timeout used
+            // sqlx_core::rt::sleep(Duration::from_millis(500)).await;
+
+            {
+                let start = Instant::now();
+                match pool.acquire().await {
+                    Ok(conn) => {
+                        println!("{} acquire took {:?}", i, start.elapsed());
+                        drop(conn);
+                    }
+                    Err(e) => panic!("{i} acquire returned error: {e} pool state: {pool:?}"),
+                }
+            }
+
+            Result::<(), anyhow::Error>::Ok(())
+        }));
+    }
+
+    futures::future::try_join_all(handles).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_work_with_transactions() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY);")
+        .await?;
+
+    // begin .. rollback
+
+    let mut tx = conn.begin().await?;
+    sqlx::query("INSERT INTO users (id) VALUES (?)")
+        .bind(1_i32)
+        .execute(&mut *tx)
+        .await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
         .fetch_one(&mut *tx)
         .await?;
-    assert_eq!(count, 1);
-    tx.rollback().await?;
-    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
-        .fetch_one(&mut conn)
+    assert_eq!(count, 1);
+    tx.rollback().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 0);
+
+    // begin .. commit
+
+    let mut tx = conn.begin().await?;
+    sqlx::query("INSERT INTO users (id) VALUES (?)")
+        .bind(1_i32)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
+
+    // begin .. (drop)
+
+    {
+        let mut tx = conn.begin().await?;
+
+        sqlx::query("INSERT INTO users (id) VALUES (?)")
+            .bind(2)
+            .execute(&mut *tx)
+            .await?;
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&mut *tx)
+            .await?;
+        assert_eq!(count, 2);
+        // tx is dropped
+    }
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_shrink_buffers() -> anyhow::Result<()> {
+    // We don't really have a good way to test that `.shrink_buffers()` functions as expected
+    // without exposing a lot of internals, but we can at least be sure it doesn't
+    // materially affect the operation of the connection.
+
+    let mut conn = new::<MySql>().await?;
+
+    // The connection buffer is only 8 KiB by default so this should definitely force it to grow.
+    let data = "This string should be 32 bytes!\n".repeat(1024);
+    assert_eq!(data.len(), 32 * 1024);
+
+    let ret: String = sqlx::query_scalar("SELECT ?")
+        .bind(&data)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ret, data);
+
+    conn.shrink_buffers();
+
+    let ret: i64 = sqlx::query_scalar("SELECT ?")
+        .bind(&12345678i64)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ret, 12345678i64);
+
+    Ok(())
+}
+
+/// The connect options of `DATABASE_URL` with `LOAD DATA LOCAL INFILE` enabled, which is off
+/// by default.
+fn local_infile_options() -> anyhow::Result<sqlx::mysql::MySqlConnectOptions> {
+    setup_if_needed();
+
+    Ok(env::var("DATABASE_URL")?
+        .parse::<sqlx::mysql::MySqlConnectOptions>()?
+        .enable_local_infile(true))
+}
+
+async fn new_with_local_infile() -> anyhow::Result<MySqlConnection> {
+    use sqlx::ConnectOptions;
+
+    Ok(local_infile_options()?.connect().await?)
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id, name)")
+        .await?;
+
+    assert_eq!(infile.filename(), "users.tsv");
+
+    infile.write(b"1\tAlice\n").await?;
+    infile.write(b"2\tBob\n").await?;
+    let rows = infile.finish().await?.rows_affected();
+    assert_eq!(rows, 2);
+
+    // conn is safe for reuse
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_through_async_write() -> anyhow::Result<()> {
+    use futures::AsyncWriteExt;
+
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    let data: String = (1..=1000).map(|id| format!("{id}\n")).collect();
+    futures::io::copy(data.as_bytes(), &mut infile).await?;
+    infile.close().await?;
+
+    let rows = infile.finish().await?.rows_affected();
+    assert_eq!(rows, 1000);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_from_reader() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
         .await?;
-    assert_eq!(count, 0);
 
-    // begin .. commit
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
 
-    let mut tx = conn.begin().await?;
-    sqlx::query("INSERT INTO users (id) VALUES (?)")
-        .bind(1_i32)
-        .execute(&mut *tx)
+    let data: String = (1..=100_000).map(|id| format!("{id}\n")).collect();
+    infile.send_from_reader(data.as_bytes()).await?;
+
+    let rows = infile.finish().await?.rows_affected();
+    assert_eq!(rows, 100_000);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_with_csv_writer() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlInfileCsvWriter;
+
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT)")
         .await?;
-    tx.commit().await?;
+
+    let mut infile = conn
+        .load_local_infile(
+            "LOAD DATA LOCAL INFILE 'users.csv' INTO TABLE users \
+             FIELDS TERMINATED BY ',' ENCLOSED BY '\"' (id, name)",
+        )
+        .await?;
+
+    let mut writer = MySqlInfileCsvWriter::new(&mut infile)
+        .field_terminator(",")
+        .enclosed_by(Some(b'"'));
+
+    writer.write_row((1_i32, "Alice, \"the\" first\n")).await?;
+    writer.write_row((2_i32, "back\\slash\t")).await?;
+    writer.write_row((3_i32, None::<&str>)).await?;
+
+    let rows = infile.finish().await?.rows_affected();
+    assert_eq!(rows, 3);
+
+    let names: Vec<Option<String>> = sqlx::query_scalar("SELECT name FROM users ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(
+        names,
+        [
+            Some("Alice, \"the\" first\n".to_owned()),
+            Some("back\\slash\t".to_owned()),
+            None
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_from_serde_records() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlInfileCsvWriter;
+
+    #[derive(serde::Serialize)]
+    struct User<'a> {
+        name: Option<&'a str>,
+        id: i32,
+    }
+
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (name, id)")
+        .await?;
+
+    infile
+        .send_record(&User {
+            name: Some("Alice"),
+            id: 1,
+        })
+        .await?;
+
+    let mut writer = MySqlInfileCsvWriter::new(&mut infile).columns(["name", "id"]);
+    writer.write_record(&User { name: None, id: 2 }).await?;
+
+    let rows = infile.finish().await?.rows_affected();
+    assert_eq!(rows, 2);
+
+    let names: Vec<Option<String>> = sqlx::query_scalar("SELECT name FROM users ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(names, [Some("Alice".to_owned()), None]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_abort_local_infile() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    // buffered, never sent
+    infile.write(b"1\n2\n").await?;
+    infile.abort().await?;
+
+    // conn is safe for reuse
     let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
         .fetch_one(&mut conn)
         .await?;
-    assert_eq!(count, 1);
 
-    // begin .. (drop)
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_in_transaction() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL) ENGINE = InnoDB")
+        .await?;
 
     {
         let mut tx = conn.begin().await?;
 
-        sqlx::query("INSERT INTO users (id) VALUES (?)")
-            .bind(2)
+        sqlx::query("INSERT INTO users (id) VALUES (1)")
             .execute(&mut *tx)
             .await?;
+
+        let mut infile = tx
+            .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+            .await?;
+
+        infile.write(b"2\n3\n").await?;
+        assert_eq!(infile.finish().await?.rows_affected(), 2);
+
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
             .fetch_one(&mut *tx)
             .await?;
-        assert_eq!(count, 2);
-        // tx is dropped
+        assert_eq!(count, 3);
+
+        tx.rollback().await?;
     }
+
     let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
         .fetch_one(&mut conn)
         .await?;
-    assert_eq!(count, 1);
+    assert_eq!(count, 0);
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn test_shrink_buffers() -> anyhow::Result<()> {
-    // We don't really have a good way to test that `.shrink_buffers()` functions as expected
-    // without exposing a lot of internals, but we can at least be sure it doesn't
-    // materially affect the operation of the connection.
+async fn it_can_load_local_infile_through_handler() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
 
-    let mut conn = new::<MySql>().await?;
+    setup_if_needed();
 
-    // The connection buffer is only 8 KiB by default so this should definitely force it to grow.
-    let data = "This string should be 32 bytes!\n".repeat(1024);
-    assert_eq!(data.len(), 32 * 1024);
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .enable_local_infile(true)
+        .local_infile_handler(|infile| {
+            Box::pin(async move {
+                match infile.filename() {
+                    "users.tsv" => infile.write(b"1\tAlice\n2\tBob\n").await,
+                    other => Err(sqlx::Error::Protocol(format!("unexpected file {other:?}"))),
+                }
+            })
+        })
+        .connect()
+        .await?;
 
-    let ret: String = sqlx::query_scalar("SELECT ?")
-        .bind(&data)
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL)")
+        .await?;
+
+    let res = sqlx::query("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id, name)")
+        .execute(&mut conn)
+        .await?;
+
+    assert_eq!(res.rows_affected(), 2);
+
+    // an error from the handler fails the query but leaves the connection usable
+    let res = conn
+        .execute("LOAD DATA LOCAL INFILE 'other.tsv' INTO TABLE users (id, name)")
+        .await;
+
+    assert!(matches!(res, Err(sqlx::Error::Protocol(_))));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(ret, data);
+    assert_eq!(count, 2);
 
-    conn.shrink_buffers();
+    Ok(())
+}
 
-    let ret: i64 = sqlx::query_scalar("SELECT ?")
-        .bind(&12345678i64)
+#[sqlx_macros::test]
+async fn it_fails_local_infile_without_handler() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let res = conn
+        .execute("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await;
+
+    assert!(res.is_err());
+
+    // conn is safe for reuse
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(ret, 12345678i64);
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_local_infile_progress() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    infile.write(b"1\n2\n").await?;
+
+    // data is buffered until a packet is full or flushed
+    assert_eq!(infile.bytes_sent(), 0);
+    assert_eq!(infile.packets_sent(), 0);
+
+    infile.flush().await?;
+    infile.write(b"3\n").await?;
+    infile.flush().await?;
+
+    assert_eq!(infile.bytes_sent(), 6);
+    assert_eq!(infile.packets_sent(), 2);
+
+    assert_eq!(infile.finish().await?.rows_affected(), 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_with_small_packets() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    let max_packet_size = infile.packet_size();
+    assert!(max_packet_size > 0 && max_packet_size <= 0xFF_FF_FE);
+
+    // sizes above the server limit are capped
+    infile.set_packet_size(usize::MAX);
+    assert_eq!(infile.packet_size(), max_packet_size);
+
+    infile.set_packet_size(4);
+    infile.write(b"1\n2\n3\n4\n").await?;
+    infile.flush().await?;
+
+    assert_eq!(infile.packets_sent(), 2);
+    assert_eq!(infile.finish().await?.rows_affected(), 4);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_cannot_load_local_infile_if_disabled() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlConnectOptions;
+    use sqlx::ConnectOptions;
+
+    setup_if_needed();
+
+    let mut conn = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .enable_local_infile(false)
+        .local_infile_handler(|_| Box::pin(async { panic!("handler must not be invoked") }))
+        .connect()
+        .await?;
+
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let res = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await
+        .map(drop);
+
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    // the server refuses the statement itself
+    let res = conn
+        .execute("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await;
+
+    assert!(res.is_err());
+
+    // conn is safe for reuse
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_local_infile_warnings() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute(
+        "CREATE TEMPORARY TABLE users (id INTEGER AUTO_INCREMENT PRIMARY KEY, name VARCHAR(3))",
+    )
+    .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (name)")
+        .await?;
+
+    infile.write(b"Ann\nBobby\n").await?;
+    let res = infile.finish().await?;
+
+    assert_eq!(res.rows_affected(), 2);
+    assert_eq!(res.last_insert_id(), 1);
+
+    // "Bobby" is truncated
+    assert_eq!(res.warnings(), 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_local_infile_through_acquire() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlInfileExt;
+
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1)
+        .connect_with(local_infile_options()?)
+        .await?;
+
+    pool.execute("SET GLOBAL local_infile = 1").await?;
+    pool.execute("CREATE TABLE IF NOT EXISTS infile_acquire (id INTEGER NOT NULL)")
+        .await?;
+    pool.execute("TRUNCATE TABLE infile_acquire").await?;
+
+    let statement = "LOAD DATA LOCAL INFILE 'ids.tsv' INTO TABLE infile_acquire (id)";
+
+    // pool
+    let mut infile = pool.load_local_infile(statement).await?;
+    infile.write(b"1\n").await?;
+    infile.finish().await?;
+
+    // pool connection
+    let mut conn = pool.acquire().await?;
+    let mut infile = (&mut conn).load_local_infile(statement).await?;
+    infile.write(b"2\n").await?;
+    infile.finish().await?;
+
+    // plain connection
+    let mut infile = (&mut *conn).load_local_infile(statement).await?;
+    infile.write(b"3\n").await?;
+    infile.finish().await?;
+
+    drop(conn);
+
+    // transaction
+    let mut tx = pool.begin().await?;
+    let mut infile = tx.load_local_infile(statement).await?;
+    infile.write(b"4\n").await?;
+    infile.finish().await?;
+    tx.rollback().await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM infile_acquire ORDER BY id")
+        .fetch_all(&pool)
+        .await?;
+
+    assert_eq!(ids, [1, 2, 3]);
+
+    pool.execute("DROP TABLE infile_acquire").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_ends_local_infile_on_drop() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    infile.write(b"1\n2\n").await?;
+    infile.flush().await?;
+
+    // never sent
+    infile.write(b"3\n").await?;
+
+    drop(infile);
+
+    // conn is safe for reuse
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    // the pool connection is cleaned up before it is returned
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1)
+        .connect_with(local_infile_options()?)
+        .await?;
+
+    let mut conn = pool.acquire().await?;
+    let mut infile = conn
+        .load_local_infile("LOAD DATA LOCAL INFILE 'users.tsv' INTO TABLE users (id)")
+        .await?;
+
+    infile.write(b"1\n").await?;
+    drop(infile);
+    drop(conn);
+
+    let one: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await?;
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_bulk_load() -> anyhow::Result<()> {
+    let mut conn = new_with_local_infile().await?;
+
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER NOT NULL PRIMARY KEY, name TEXT NULL)")
+        .await?;
+
+    let users = (1..=100).map(|id| (id, (id % 2 == 0).then(|| format!("user\t{id}"))));
+
+    let result = conn.bulk_load("users", &["id", "name"], users).await?;
+    assert_eq!(result.rows_affected(), 100);
+
+    let (id, name): (i32, Option<String>) =
+        sqlx::query_as("SELECT id, name FROM users WHERE id = 42")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(id, 42);
+    assert_eq!(name.as_deref(), Some("user\t42"));
+
+    let nulls: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE name IS NULL")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(nulls, 50);
 
     Ok(())
 }
@@ -66,10 +66,23 @@ test_type!(uuid_simple<sqlx::types::uuid::fmt::Simple>(MySql,
         == sqlx::types::Uuid::parse_str("00000000000000000000000000000000").unwrap().simple()
 ));
 
+test_type!(mysql_time<sqlx::mysql::types::MySqlTime>(MySql,
+    "TIME '00:00:00'" == sqlx::mysql::types::MySqlTime::ZERO,
+    "TIME '-838:59:59'" == sqlx::mysql::types::MySqlTime::MIN,
+    "TIME '123:04:05.000006'"
+        == sqlx::mysql::types::MySqlTime::new(false, 123, 4, 5, 6).unwrap(),
+    "TIME '-00:00:01.500000'"
+        == sqlx::mysql::types::MySqlTime::from_microseconds(-1_500_000).unwrap()
+));
+
+test_type!(std_duration<std::time::Duration>(MySql,
+    "TIME '100:00:00.500000'" == std::time::Duration::from_millis(360_000_500)
+));
+
 #[cfg(feature = "chrono")]
 mod chrono {
     use super::*;
-    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    use sqlx::types::chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 
     test_type!(chrono_date<NaiveDate>(MySql,
         "DATE '2001-01-05'" == NaiveDate::from_ymd(2001, 1, 5),
@@ -84,6 +97,10 @@ mod chrono {
         "TIME '05:10:20.115100'" == NaiveTime::from_hms_micro(5, 10, 20, 115100)
     ));
 
+    test_type!(chrono_duration<Duration>(MySql,
+        "TIME '-25:00:00.000001'" == -(Duration::hours(25) + Duration::microseconds(1))
+    ));
+
     test_type!(chrono_date_time<NaiveDateTime>(MySql,
         "TIMESTAMP '2019-01-02 05:10:20'" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20)
     ));
@@ -157,6 +174,12 @@ mod time_tests {
         "TIME '05:10:20.115100'" == time!(5:10:20.115100)
     ));
 
+    test_type!(time_duration<time::Duration>(
+        MySql,
+        "TIME '-25:00:00.000001'"
+            == -(time::Duration::hours(25) + time::Duration::microseconds(1))
+    ));
+
     test_type!(time_date_time<PrimitiveDateTime>(
         MySql,
         "TIMESTAMP '2019-01-02 05:10:20'" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
@@ -234,6 +257,26 @@ test_type!(decimal<sqlx::types::Decimal>(MySql,
     "CAST(12345.6789 AS DECIMAL(9, 4))" == sqlx::types::Decimal::from_str("12345.6789").unwrap(),
 ));
 
+#[cfg(feature = "geo-types")]
+mod geo_types_tests {
+    use super::*;
+    use sqlx::types::geo_types::{Geometry, LineString, Point, Polygon};
+
+    test_type!(point<Point<f64>>(MySql,
+        "ST_GeomFromText('POINT(1 2)')" == Point::new(1.0, 2.0),
+    ));
+
+    test_type!(polygon<Polygon<f64>>(MySql,
+        "ST_GeomFromText('POLYGON((0 0, 4 0, 4 4, 0 0))')"
+            == Polygon::new(LineString::from(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 0.0)]), vec![]),
+    ));
+
+    test_type!(geometry<Geometry<f64>>(MySql,
+        "ST_GeomFromText('LINESTRING(0 0, 1 1)')"
+            == Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)])),
+    ));
+}
+
 #[cfg(feature = "json")]
 mod json_tests {
     use super::*;
@@ -276,6 +319,83 @@ mod json_tests {
         MySql,
         "\'{\"json_column\":[1,2]}\'" == Json(Customer { json_column: Json(vec![1, 2]) })
     ));
+
+    #[sqlx_macros::test]
+    async fn it_decodes_extracted_json() -> anyhow::Result<()> {
+        let mut conn = new::<MySql>().await?;
+
+        let doc = r#"{"friend": {"name": "Joe", "age": 33}, "tags": ["a", "b"]}"#;
+
+        let (friend, tags): (Json<Friend>, Json<Vec<String>>) = sqlx::query_as(
+            "SELECT JSON_EXTRACT(CAST(? AS JSON), '$.friend'), JSON_EXTRACT(CAST(? AS JSON), '$.tags')",
+        )
+        .bind(doc)
+        .bind(doc)
+        .fetch_one(&mut conn)
+        .await?;
+
+        assert_eq!(
+            friend.0,
+            Friend {
+                name: "Joe".to_string(),
+                age: 33
+            }
+        );
+        assert_eq!(tags.0, ["a", "b"]);
+
+        Ok(())
+    }
+}
+
+#[sqlx_macros::test]
+async fn test_set() -> anyhow::Result<()> {
+    use sqlx::mysql::types::MySqlSet;
+
+    #[derive(sqlx::Type, Debug, PartialEq)]
+    #[sqlx(rename_all = "lowercase")]
+    enum Permission {
+        Read,
+        Write,
+        Admin,
+    }
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE with_sets (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    permissions SET('read', 'write', 'admin') NOT NULL
+);
+    "#,
+    )
+    .await?;
+
+    sqlx::query("INSERT INTO with_sets (permissions) VALUES (?), (?)")
+        .bind(MySqlSet::from(vec![Permission::Write, Permission::Read]))
+        .bind(MySqlSet::<Permission>::default())
+        .execute(&mut conn)
+        .await?;
+
+    // BINARY
+    let sets: Vec<MySqlSet<Permission>> =
+        sqlx::query_scalar("SELECT permissions FROM with_sets ORDER BY id")
+            .fetch_all(&mut conn)
+            .await?;
+
+    // in the order of the definition
+    assert_eq!(sets[0].as_slice(), [Permission::Read, Permission::Write]);
+    assert!(sets[1].is_empty());
+
+    // TEXT
+    let row = conn
+        .fetch_one("SELECT permissions FROM with_sets ORDER BY id")
+        .await?;
+    let set: MySqlSet<String> = row.try_get(0)?;
+
+    assert_eq!(set.as_slice(), ["read", "write"]);
+
+    Ok(())
 }
 
 #[sqlx_macros::test]
@@ -319,3 +439,126 @@ CREATE TEMPORARY TABLE with_bits (
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn test_bits_wide() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE with_wide_bits (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    value_12 BIT(12) NOT NULL,
+    value_64 BIT(64) NOT NULL
+);
+    "#,
+    )
+    .await?;
+
+    sqlx::query("INSERT INTO with_wide_bits (value_12, value_64) VALUES (?, ?)")
+        .bind(0b1010_0000_0101_u16)
+        .bind(u64::MAX)
+        .execute(&mut conn)
+        .await?;
+
+    // BINARY
+    let (v12, v64): (u16, u64) = sqlx::query_as("SELECT value_12, value_64 FROM with_wide_bits")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(v12, 0b1010_0000_0101);
+    assert_eq!(v64, u64::MAX);
+
+    // `BIT(12)` doesn't fit
+    assert!(
+        sqlx::query_as::<_, (u8,)>("SELECT value_12 FROM with_wide_bits")
+            .fetch_one(&mut conn)
+            .await
+            .is_err()
+    );
+
+    // TEXT
+    let row = conn
+        .fetch_one("SELECT value_12, value_64 FROM with_wide_bits")
+        .await?;
+    let v12: Vec<u8> = row.try_get(0)?;
+    let v64: u64 = row.try_get(1)?;
+
+    assert_eq!(v12, [0b1010, 0b0000_0101]);
+    assert_eq!(v64, u64::MAX);
+
+    Ok(())
+}
+
+#[cfg(feature = "bit-vec")]
+#[sqlx_macros::test]
+async fn test_bit_vec() -> anyhow::Result<()> {
+    use sqlx::types::BitVec;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE with_bit_vec (value BIT(12) NOT NULL);")
+        .await?;
+
+    let mut bits = BitVec::from_elem(12, false);
+    bits.set(0, true);
+    bits.set(2, true);
+    bits.set(11, true);
+
+    sqlx::query("INSERT INTO with_bit_vec (value) VALUES (?)")
+        .bind(&bits)
+        .execute(&mut conn)
+        .await?;
+
+    let (value,): (u16,) = sqlx::query_as("SELECT value FROM with_bit_vec")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 0b1010_0000_0001);
+
+    let (value,): (BitVec,) = sqlx::query_as("SELECT value FROM with_bit_vec")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, bits);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_vector() -> anyhow::Result<()> {
+    use sqlx::mysql::types::MySqlVector;
+
+    let mut conn = new::<MySql>().await?;
+
+    // `VECTOR` needs MySQL 9 or MariaDB 11.7
+    if conn
+        .execute("CREATE TEMPORARY TABLE with_vector (value VECTOR(3) NOT NULL);")
+        .await
+        .is_err()
+    {
+        return Ok(());
+    }
+
+    let vector = MySqlVector::from(vec![1.0, -2.5, 0.125]);
+
+    sqlx::query("INSERT INTO with_vector (value) VALUES (?)")
+        .bind(&vector)
+        .execute(&mut conn)
+        .await?;
+
+    // BINARY
+    let (value,): (MySqlVector,) = sqlx::query_as("SELECT value FROM with_vector")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, vector);
+
+    // TEXT
+    let row = conn.fetch_one("SELECT value FROM with_vector").await?;
+    let value: MySqlVector = row.try_get(0)?;
+
+    assert_eq!(value.as_slice(), [1.0, -2.5, 0.125]);
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use futures::TryStreamExt;
-use sqlx::postgres::types::PgRange;
+use sqlx::postgres::types::{PgRange, PgRecord};
+use sqlx::postgres::{PgHasArrayType, PgTypeInfo};
 use sqlx::{Connection, Executor, FromRow, Postgres};
 use sqlx_test::{new, test_type};
 use std::fmt::Debug;
@@ -124,13 +125,28 @@ enum Mood {
 // Records must map to a custom type
 // Note that all types are types in Postgres
 #[derive(PartialEq, Debug, sqlx::Type)]
-#[sqlx(type_name = "inventory_item")]
+#[sqlx(type_name = "inventory_item", pg_array)]
 struct InventoryItem {
     name: String,
     supplier_id: Option<i32>,
     price: Option<i64>,
 }
 
+// Without `#[sqlx(pg_array)]`, the array type may still be implemented by hand
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "inventory_item")]
+struct ManualArrayItem {
+    name: String,
+    supplier_id: Option<i32>,
+    price: Option<i64>,
+}
+
+impl PgHasArrayType for ManualArrayItem {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_inventory_item")
+    }
+}
+
 // Custom range type
 #[derive(sqlx::Type, Debug, PartialEq)]
 #[sqlx(type_name = "float_range")]
@@ -386,6 +402,73 @@ SELECT $1 = ROW('fuzzy dice', 42, 199)::inventory_item, $1
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_record_array_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let values = vec![
+        InventoryItem {
+            name: "fuzzy dice".to_owned(),
+            supplier_id: Some(42),
+            price: Some(199),
+        },
+        InventoryItem {
+            name: "dummy".to_owned(),
+            supplier_id: None,
+            price: None,
+        },
+    ];
+
+    let rec: (bool, Vec<InventoryItem>) = sqlx::query_as(
+        "
+SELECT $1 = ARRAY[ROW('fuzzy dice', 42, 199), ROW('dummy', NULL, NULL)]::inventory_item[], $1
+        ",
+    )
+    .bind(&values)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, values);
+
+    let items: Vec<InventoryItem> = sqlx::query_scalar(
+        "
+SELECT array_agg(item ORDER BY (item).name DESC)
+FROM (VALUES (ROW('fuzzy dice', 42, 199)::inventory_item), (ROW('dummy', NULL, NULL)::inventory_item)) v(item)
+        ",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(items, values);
+
+    let items: Vec<ManualArrayItem> =
+        sqlx::query_scalar("SELECT ARRAY[ROW('fuzzy dice', 42, 199)::inventory_item]")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(
+        items,
+        [ManualArrayItem {
+            name: "fuzzy dice".to_owned(),
+            supplier_id: Some(42),
+            price: Some(199),
+        }]
+    );
+
+    // and dynamically, without a Rust type for the composite type
+    let records: Vec<PgRecord> =
+        sqlx::query_scalar("SELECT ARRAY[ROW('fuzzy dice', 42, 199)::inventory_item]")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].try_get::<String>(0)?, "fuzzy dice");
+    assert_eq!(records[0].try_get::<Option<i64>>(2)?, Some(199));
+
+    Ok(())
+}
+
 #[cfg(feature = "macros")]
 #[sqlx_macros::test]
 async fn test_new_type() {
@@ -85,6 +85,87 @@ async fn test_text_var_char_char_n() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_json_path() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let path = sqlx::postgres::types::PgJsonPath::new("$.a[*] ? (@ > 1)");
+
+    let values = sqlx::query!(
+        r#"SELECT jsonb_path_query('{"a": [1, 2, 3]}', $1)::int4 AS "value!", $1 AS "path!""#,
+        path
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0].value, 2);
+    assert_eq!(values[1].value, 3);
+    assert_eq!(values[0].path.as_str(), "$.\"a\"[*]?(@ > 1)");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_money() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let row = sqlx::query!(
+        r#"SELECT '12.34'::money AS "price!", $1::money + '0.66'::money AS "total!""#,
+        sqlx::postgres::types::PgMoney(1234)
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(row.price, sqlx::postgres::types::PgMoney(1234));
+    assert_eq!(row.total, sqlx::postgres::types::PgMoney(1300));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_citext() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let rec = sqlx::query!(
+        "SELECT $1::citext as greeting, array['Hello', 'World']::citext[] as greetings",
+        "Hello"
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let greeting: Option<String> = rec.greeting;
+    let greetings: Option<Vec<String>> = rec.greetings;
+
+    assert_eq!(greeting.as_deref(), Some("Hello"));
+    assert_eq!(
+        greetings,
+        Some(vec!["Hello".to_owned(), "World".to_owned()])
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_domain() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let rec = sqlx::query!(
+        "SELECT $1::email as email, array[$1]::email[] as emails",
+        "a@example.com"
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let email: Option<String> = rec.email;
+    let emails: Option<Vec<String>> = rec.emails;
+
+    assert_eq!(email.as_deref(), Some("a@example.com"));
+    assert_eq!(emails, Some(vec!["a@example.com".to_owned()]));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_void() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -1,10 +1,11 @@
 use futures::{StreamExt, TryStreamExt};
-use sqlx::postgres::types::Oid;
+use sqlx::postgres::types::{Oid, PgRecord};
 use sqlx::postgres::{
-    PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgListener,
-    PgPoolOptions, PgRow, PgSeverity, Postgres,
+    PgAdvisoryLock, PgAdvisoryLockKey, PgConnectOptions, PgConnection, PgDatabaseError,
+    PgErrorPosition, PgLargeObject, PgLargeObjectMode, PgListener, PgListenerEvent, PgPoolOptions,
+    PgQueryExt, PgRow, PgSeverity, PgTargetSessionAttrs, PgTypedListener, Postgres,
 };
-use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
+use sqlx::{Column, Connection, Executor, Row, Statement, StatementCacheEviction, TypeInfo};
 use sqlx_test::{new, pool, setup_if_needed};
 use std::env;
 use std::sync::Arc;
@@ -1550,6 +1551,756 @@ async fn it_can_copy_out() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_copy_out_to_writer() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut out = Vec::new();
+    let rows = conn
+        .copy_out_to(
+            "COPY (SELECT generate_series(1, 3) AS id) TO STDOUT WITH (FORMAT CSV, HEADER)",
+            &mut out,
+        )
+        .await?;
+
+    assert_eq!(rows, 3);
+    assert_eq!(out, b"id\n1\n2\n3\n");
+
+    // conn is safe for reuse
+    let value = sqlx::query("select 1 + 1")
+        .try_map(|row: PgRow| row.try_get::<i32, _>(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(2i32, value);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_copy_binary_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    conn.execute(
+        r#"
+        CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT, tags TEXT[] NOT NULL);
+    "#,
+    )
+    .await?;
+
+    let mut copy = conn
+        .copy_in_raw("COPY users (id, name, tags) FROM STDIN (FORMAT binary)")
+        .await?;
+
+    for id in 1..=1000 {
+        let name = (id % 2 == 0).then(|| format!("user {id}"));
+        copy.send_row((id, name, vec!["a", "b"])).await?;
+    }
+
+    let rows = copy.finish().await?;
+    assert_eq!(rows, 1000);
+
+    let mut rows = conn
+        .copy_out_rows::<(i32, Option<String>, Vec<String>)>(
+            "COPY (SELECT id, name, tags FROM users ORDER BY id) TO STDOUT (FORMAT binary)",
+        )
+        .await?;
+
+    let mut count = 0;
+
+    while let Some((id, name, tags)) = rows.try_next().await? {
+        count += 1;
+        assert_eq!(id, count);
+        assert_eq!(name, (id % 2 == 0).then(|| format!("user {id}")));
+        assert_eq!(tags, ["a", "b"]);
+    }
+
+    drop(rows);
+    assert_eq!(count, 1000);
+
+    // rows decoded with too few fields are an error
+    let mut rows = conn
+        .copy_out_rows::<(i32,)>("COPY (SELECT 1, 2) TO STDOUT (FORMAT binary)")
+        .await?;
+    assert!(rows.try_next().await.is_err());
+    drop(rows);
+
+    // text COPY is rejected
+    let mut copy = conn.copy_in_raw("COPY users (id) FROM STDIN").await?;
+    assert!(copy.send_row((1_i32,)).await.is_err());
+    copy.abort("not binary").await?;
+
+    // conn is safe for reuse
+    let value = sqlx::query("select 1 + 1")
+        .try_map(|row: PgRow| row.try_get::<i32, _>(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(2i32, value);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_copy_in_records() -> anyhow::Result<()> {
+    #[derive(serde::Serialize)]
+    struct User {
+        id: i32,
+        name: Option<String>,
+        active: bool,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+    conn.execute(
+        r#"
+        CREATE TEMPORARY TABLE users (id INTEGER NOT NULL, name TEXT, active BOOLEAN NOT NULL);
+    "#,
+    )
+    .await?;
+
+    let users = futures::stream::iter(1..=100).map(|id| User {
+        id,
+        name: match id % 3 {
+            0 => None,
+            1 => Some(format!("say \"hi\",\n{id}")),
+            _ => Some(String::new()),
+        },
+        active: id % 2 == 0,
+    });
+
+    let rows = conn
+        .copy_in_records(
+            "COPY users (id, name, active) FROM STDIN (FORMAT csv)",
+            users,
+        )
+        .await?;
+    assert_eq!(rows, 100);
+
+    let (nulls, empty, active): (i64, i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE name IS NULL), COUNT(*) FILTER (WHERE name = ''), \
+         COUNT(*) FILTER (WHERE active) FROM users",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!((nulls, empty, active), (33, 33, 50));
+
+    let name: String = sqlx::query_scalar("SELECT name FROM users WHERE id = 4")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(name, "say \"hi\",\n4");
+
+    // a record which cannot be serialized aborts the COPY
+    let res = conn
+        .copy_in_records(
+            "COPY users (id, active) FROM STDIN (FORMAT csv)",
+            futures::stream::iter([
+                serde_json::json!([101, true]),
+                serde_json::json!([102, [true]]),
+            ]),
+        )
+        .await;
+    assert!(res.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 100);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_stream_logical_replication() -> anyhow::Result<()> {
+    use sqlx::postgres::{PgConnectOptions, PgReplicationConnection, PgReplicationMessage};
+
+    let mut conn = new::<Postgres>().await?;
+
+    // logical replication must be enabled on the server
+    let wal_level: String = sqlx::query_scalar("SHOW wal_level")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if wal_level != "logical" {
+        return Ok(());
+    }
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS replicated (id INTEGER NOT NULL);
+    "#,
+    )
+    .await?;
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut repl = PgReplicationConnection::connect_with(&options).await?;
+
+    let system = repl.identify_system().await?;
+    assert_eq!(system.dbname(), options.get_database());
+
+    let slot = repl
+        .create_replication_slot("sqlx_test_slot", "test_decoding", true)
+        .await?;
+
+    assert_eq!(slot.slot_name(), "sqlx_test_slot");
+    assert_eq!(slot.output_plugin(), Some("test_decoding"));
+    assert!(slot.consistent_point() >= system.xlog_pos());
+
+    conn.execute("INSERT INTO replicated (id) VALUES (42)")
+        .await?;
+
+    let mut stream = repl
+        .start_replication(
+            "sqlx_test_slot",
+            slot.consistent_point(),
+            &[("include-xids", "0")],
+        )
+        .await?;
+
+    // other tests may change other tables in the meantime
+    let change = loop {
+        match stream.recv().await?.expect("stream ended early") {
+            PgReplicationMessage::XLogData(data) => {
+                let lsn = data.wal_end();
+                stream.send_status_update(lsn, lsn, lsn, false).await?;
+
+                let change = String::from_utf8(data.data().to_vec())?;
+
+                if change.starts_with("table public.replicated:") {
+                    break change;
+                }
+            }
+            PgReplicationMessage::PrimaryKeepalive(_) => {}
+        }
+    };
+
+    assert_eq!(change, "table public.replicated: INSERT: id[integer]:42");
+
+    stream.finish().await?;
+
+    // a dropped stream is ended before the next command
+    let stream = repl
+        .start_replication("sqlx_test_slot", slot.consistent_point(), &[])
+        .await?;
+    drop(stream);
+
+    let system = repl.identify_system().await?;
+    assert_eq!(system.dbname(), options.get_database());
+
+    repl.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_copy_both() -> anyhow::Result<()> {
+    use futures::SinkExt;
+    use sqlx::postgres::{PgConnectOptions, PgReplicationConnection};
+
+    let mut conn = new::<Postgres>().await?;
+
+    // other statements are rejected
+    assert!(conn.copy_both("SELECT 1").await.is_err());
+    assert!(conn.copy_both("COPY tweet FROM STDIN").await.is_err());
+    assert_eq!(conn.fetch_one("SELECT 1").await?.get::<i32, _>(0), 1);
+
+    // logical replication must be enabled on the server
+    let wal_level: String = sqlx::query_scalar("SHOW wal_level")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if wal_level != "logical" {
+        return Ok(());
+    }
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut repl = PgReplicationConnection::connect_with(&options).await?;
+
+    let slot = repl
+        .create_replication_slot("sqlx_copy_both_slot", "test_decoding", true)
+        .await?;
+
+    let copy = repl
+        .copy_both(&format!(
+            r#"START_REPLICATION SLOT "sqlx_copy_both_slot" LOGICAL {}"#,
+            slot.consistent_point()
+        ))
+        .await?;
+
+    let (mut sink, mut stream) = copy.split();
+
+    // a standby status update that asks for a keepalive right away
+    let lsn = slot.consistent_point().to_u64().to_be_bytes();
+    let status_update = [&b"r"[..], &lsn, &lsn, &lsn, &[0; 8], &[1]].concat();
+
+    let receive = async {
+        loop {
+            let data = stream.try_next().await?.expect("copy ended early");
+
+            if data[0] == b'k' {
+                return anyhow::Ok(data);
+            }
+        }
+    };
+
+    let send = async {
+        sink.send(status_update.as_slice()).await?;
+        anyhow::Ok(())
+    };
+
+    let (keepalive, ()) = futures::try_join!(receive, send)?;
+    assert_eq!(keepalive.len(), 18);
+
+    sink.reunite(stream).unwrap().finish().await?;
+
+    let system = repl.identify_system().await?;
+    assert_eq!(system.dbname(), options.get_database());
+
+    repl.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_decode_pgoutput_messages() -> anyhow::Result<()> {
+    use sqlx::postgres::{
+        PgConnectOptions, PgOutputMessage, PgReplicaIdentity, PgReplicationConnection,
+        PgReplicationMessage,
+    };
+
+    let mut conn = new::<Postgres>().await?;
+
+    // logical replication must be enabled on the server
+    let wal_level: String = sqlx::query_scalar("SHOW wal_level")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if wal_level != "logical" {
+        return Ok(());
+    }
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS published (id INTEGER PRIMARY KEY, name TEXT);
+        DROP PUBLICATION IF EXISTS sqlx_test_publication;
+        CREATE PUBLICATION sqlx_test_publication FOR TABLE published;
+    "#,
+    )
+    .await?;
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut repl = PgReplicationConnection::connect_with(&options).await?;
+
+    let slot = repl
+        .create_replication_slot("sqlx_test_pgoutput_slot", "pgoutput", true)
+        .await?;
+
+    conn.execute(
+        r#"
+        INSERT INTO published (id, name) VALUES (1, 'first'), (2, NULL);
+        UPDATE published SET name = 'second' WHERE id = 2;
+        DELETE FROM published;
+    "#,
+    )
+    .await?;
+
+    let mut stream = repl
+        .start_replication(
+            "sqlx_test_pgoutput_slot",
+            slot.consistent_point(),
+            &[
+                ("proto_version", "1"),
+                ("publication_names", "sqlx_test_publication"),
+            ],
+        )
+        .await?;
+
+    let mut relation = None;
+    let mut changes = Vec::new();
+
+    // other tests may commit transactions in the meantime
+    while changes.len() < 5 {
+        let data = match stream.recv().await?.expect("stream ended early") {
+            PgReplicationMessage::XLogData(data) => data,
+            PgReplicationMessage::PrimaryKeepalive(_) => continue,
+        };
+
+        match data.decode_pgoutput()? {
+            PgOutputMessage::Relation(r) => {
+                assert_eq!(r.namespace(), "public");
+                assert_eq!(r.name(), "published");
+                assert_eq!(r.replica_identity(), PgReplicaIdentity::Default);
+                assert_eq!(r.columns().len(), 2);
+                assert!(r.columns()[0].is_key());
+
+                relation = Some(r);
+            }
+
+            PgOutputMessage::Insert(insert) => {
+                let relation = relation.as_ref().expect("relation is sent first");
+                assert_eq!(insert.relation_id(), relation.id());
+
+                changes.push((
+                    "insert",
+                    insert.new_tuple().try_get::<i32>(relation, 0)?,
+                    insert.new_tuple().try_get::<Option<String>>(relation, 1)?,
+                ));
+            }
+
+            PgOutputMessage::Update(update) => {
+                let relation = relation.as_ref().expect("relation is sent first");
+
+                // the key did not change
+                assert!(update.key_tuple().is_none());
+
+                changes.push((
+                    "update",
+                    update.new_tuple().try_get(relation, 0)?,
+                    update.new_tuple().try_get(relation, 1)?,
+                ));
+            }
+
+            PgOutputMessage::Delete(delete) => {
+                let relation = relation.as_ref().expect("relation is sent first");
+                let key = delete.key_tuple().expect("key of the deleted row");
+
+                assert!(key.is_null(1));
+
+                changes.push(("delete", key.try_get(relation, 0)?, None));
+            }
+
+            PgOutputMessage::Commit(commit) => {
+                let lsn = commit.end_lsn();
+                stream.send_status_update(lsn, lsn, lsn, false).await?;
+            }
+
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        changes,
+        [
+            ("insert", 1, Some("first".to_owned())),
+            ("insert", 2, None),
+            ("update", 2, Some("second".to_owned())),
+            ("delete", 1, None),
+            ("delete", 2, None),
+        ]
+    );
+
+    stream.finish().await?;
+    repl.close().await?;
+
+    conn.execute("DROP PUBLICATION sqlx_test_publication")
+        .await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_pipeline_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE pipelined (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let statement = conn
+        .prepare("INSERT INTO pipelined (id, name) VALUES ($1, $2)")
+        .await?;
+
+    let results = conn
+        .pipeline()
+        .push(statement.query().bind(1_i32).bind("Alice"))
+        .push(statement.query().bind(2_i32).bind("Bob"))
+        .push(sqlx::query("SELECT id, name FROM pipelined ORDER BY id"))
+        .push(sqlx::query("SELECT $1::text").bind("done"))
+        .run()
+        .await?;
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].rows_affected(), 1);
+    assert_eq!(results[1].rows_affected(), 1);
+
+    let rows = results[2].rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].try_get::<i32, _>("id")?, 2);
+    assert_eq!(rows[1].try_get::<&str, _>("name")?, "Bob");
+
+    assert_eq!(results[3].rows()[0].try_get::<&str, _>(0)?, "done");
+
+    assert!(conn.pipeline().run().await?.is_empty());
+
+    // a failing query rolls back the queries before it
+    let res = conn
+        .pipeline()
+        .push(sqlx::query("INSERT INTO pipelined (id) VALUES (3)"))
+        .push(sqlx::query("INSERT INTO pipelined (id) VALUES (1)"))
+        .push(sqlx::query("INSERT INTO pipelined (id) VALUES (4)"))
+        .run()
+        .await;
+
+    let err = res.unwrap_err().into_database_error().unwrap();
+    assert_eq!(err.code().as_deref(), Some("23505"));
+
+    // conn is safe for reuse
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM pipelined")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_cancel_a_running_query() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let token = conn.cancel_token();
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(token.process_id(), pid as u32);
+
+    // nothing happens without a running query
+    token.clone().cancel().await?;
+
+    sqlx_core::rt::spawn(async move {
+        sqlx_core::rt::sleep(Duration::from_millis(200)).await;
+        token.cancel().await
+    });
+
+    let err = sqlx::query("SELECT pg_sleep(10)")
+        .execute(&mut conn)
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+
+    assert_eq!(err.code().as_deref(), Some("57014"));
+
+    // conn is safe for reuse
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_fetch_rows_through_a_cursor() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // a cursor requires a transaction
+    let res = conn
+        .cursor(sqlx::query("SELECT 1"))
+        .fetch()
+        .try_next()
+        .await;
+
+    let err = res.err().unwrap().into_database_error().unwrap();
+    assert_eq!(err.code().as_deref(), Some("25P01"));
+
+    let mut tx = conn.begin().await?;
+
+    let mut rows = tx
+        .cursor(sqlx::query("SELECT i, i::text AS s FROM generate_series(1, $1) i").bind(2500_i32))
+        .fetch_size(1000)
+        .fetch();
+
+    let mut count = 0;
+
+    while let Some(row) = rows.try_next().await? {
+        count += 1;
+
+        assert_eq!(row.try_get::<i32, _>("i")?, count);
+        assert_eq!(row.try_get::<String, _>("s")?, count.to_string());
+    }
+
+    drop(rows);
+    assert_eq!(count, 2500);
+
+    // the cursor was closed; the unnamed portal of this query is listed as well
+    let open: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_cursors WHERE name <> ''")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    assert_eq!(open, 0);
+
+    // the batches may line up with the end of the rows
+    let rows = tx
+        .cursor(sqlx::query("SELECT generate_series(1, 4)"))
+        .fetch_size(2)
+        .fetch()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    assert_eq!(rows.len(), 4);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+// the binary format of `lquery` requires Postgres 13
+#[cfg(any(postgres_14, postgres_15))]
+#[sqlx_macros::test]
+async fn it_matches_ltree_with_lquery() -> anyhow::Result<()> {
+    use sqlx::postgres::types::{PgLQuery, PgLTree};
+    use std::str::FromStr;
+
+    let mut conn = new::<Postgres>().await?;
+
+    let queries = [
+        ("Top.*{1,}.Astronomy", true),
+        ("Top.*{,1}.Astronomy", false),
+        ("Top.Hobbies|Science.*", true),
+        ("!Top.*", false),
+    ];
+
+    for (query, expected) in queries {
+        let matches: bool = sqlx::query_scalar("SELECT $1 ~ $2")
+            .bind(PgLTree::from_str("Top.Science.Physics.Astronomy")?)
+            .bind(PgLQuery::from_str(query)?)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(matches, expected, "{query}");
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_citext_in_composites() -> anyhow::Result<()> {
+    #[derive(sqlx::Type, Debug, PartialEq)]
+    #[sqlx(type_name = "citext_pair")]
+    struct CitextPair {
+        one: String,
+        two: String,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "
+DROP TYPE IF EXISTS citext_pair;
+
+CREATE TYPE citext_pair AS (one CITEXT, two CITEXT);
+",
+    )
+    .await?;
+
+    let pair: CitextPair = sqlx::query_scalar("SELECT ROW('Hello', 'World')::citext_pair")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(
+        pair,
+        CitextPair {
+            one: "Hello".into(),
+            two: "World".into(),
+        }
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_anonymous_records() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let sql = "SELECT (1, 'a b'::text, 2.5::float8, NULL::int4), ROW(1, ROW(2, 'é'))";
+
+    // the binary format of prepared queries, and the text format of unprepared queries
+    for row in [
+        sqlx::query(sql).fetch_one(&mut conn).await?,
+        conn.fetch_one(sql).await?,
+    ] {
+        let tuple: (i32, String, f64, Option<i32>) = row.try_get(0)?;
+        assert_eq!(tuple, (1, "a b".to_owned(), 2.5, None));
+
+        let nested: (i32, (i32, String)) = row.try_get(1)?;
+        assert_eq!(nested, (1, (2, "é".to_owned())));
+
+        let record: PgRecord = row.try_get(0)?;
+        assert_eq!(record.len(), 4);
+        assert_eq!(record.try_get::<i32>(0)?, 1);
+        assert_eq!(record.try_get::<&str>(1)?, "a b");
+        assert_eq!(record.try_get::<f64>(2)?, 2.5);
+        assert_eq!(record.try_get::<Option<i32>>(3)?, None);
+        assert!(record.try_get::<i32>(4).is_err());
+
+        let record: PgRecord = row.try_get(1)?;
+        let inner: PgRecord = record.try_get(1)?;
+        assert_eq!(inner.try_get::<String>(1)?, "é");
+    }
+
+    // the types of the fields are checked in the binary format
+    let record: PgRecord = sqlx::query_scalar("SELECT ROW(1)")
+        .fetch_one(&mut conn)
+        .await?;
+    assert!(record.try_get::<String>(0).is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_domains_as_their_base_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let row = sqlx::query("SELECT $1::email, ARRAY[$1]::email[]")
+        .bind("a@example.com")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.try_get::<String, _>(0)?, "a@example.com");
+    assert_eq!(row.try_get::<Vec<String>, _>(1)?, ["a@example.com"]);
+
+    // a domain over a type with the default `PgHasArrayType::array_compatible`
+    conn.execute("CREATE DOMAIN pg_temp.positive AS INT4 CHECK (VALUE > 0)")
+        .await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT ARRAY[1, 2]::pg_temp.positive[]")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [1, 2]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_requires_newtypes_for_strict_domains() -> anyhow::Result<()> {
+    #[derive(sqlx::Type, Debug, PartialEq)]
+    #[sqlx(type_name = "email")]
+    struct Email(String);
+
+    impl sqlx::postgres::PgHasArrayType for Email {
+        fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+            sqlx::postgres::PgTypeInfo::with_name("_email")
+        }
+    }
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.strict_domains(true)).await?;
+
+    let row = sqlx::query("SELECT ARRAY[$1]::email[]")
+        .bind("a@example.com")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(row.try_get::<Vec<String>, _>(0).is_err());
+
+    assert_eq!(
+        row.try_get::<Vec<Email>, _>(0)?,
+        [Email("a@example.com".into())]
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_encodes_custom_array_issue_1504() -> anyhow::Result<()> {
     use sqlx::encode::IsNull;
@@ -1771,6 +2522,568 @@ async fn test_advisory_locks() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_transaction_advisory_locks() -> anyhow::Result<()> {
+    let mut conn1 = new::<Postgres>().await?;
+    let mut conn2 = new::<Postgres>().await?;
+
+    let lock1 = PgAdvisoryLock::new("sqlx-postgres-tests-xact-1");
+    let lock2 = PgAdvisoryLock::with_key(PgAdvisoryLockKey::IntPair(1504, 2520));
+
+    let mut tx1 = conn1.begin().await?;
+    let mut tx1_lock1 = lock1.acquire_xact(&mut tx1).await?;
+
+    // the guard can still be used to run queries
+    let one: i32 = sqlx::query_scalar("SELECT 1")
+        .fetch_one(&mut *tx1_lock1)
+        .await?;
+    assert_eq!(one, 1);
+
+    // take another lock in the same transaction
+    drop(lock2.acquire_xact(tx1_lock1.into_inner()).await?);
+
+    // the locks are still held after the guards are dropped
+    for lock in [&lock1, &lock2] {
+        let mut tx2 = conn2.begin().await?;
+        assert!(lock.try_acquire_xact(&mut tx2).await?.is_right());
+        assert!(lock.try_acquire(&mut *tx2).await?.is_right());
+        tx2.rollback().await?;
+    }
+
+    tx1.commit().await?;
+
+    // committing the transaction released the locks
+    let mut tx2 = conn2.begin().await?;
+    assert!(lock1.try_acquire_xact(&mut tx2).await?.is_left());
+    assert!(lock2.try_acquire_xact(&mut tx2).await?.is_left());
+
+    let mut tx1 = conn1.begin().await?;
+    assert!(lock1.try_acquire_xact(&mut tx1).await?.is_right());
+    tx1.rollback().await?;
+
+    tx2.rollback().await?;
+
+    // rolling back the transaction released the locks too
+    let mut tx1 = conn1.begin().await?;
+    assert!(lock1.try_acquire_xact(&mut tx1).await?.is_left());
+    tx1.commit().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_supports_two_phase_commits() -> anyhow::Result<()> {
+    let mut conn1 = new::<Postgres>().await?;
+    let mut conn2 = new::<Postgres>().await?;
+
+    conn1
+        .execute("CREATE TABLE IF NOT EXISTS two_phase_commits (gid TEXT NOT NULL)")
+        .await?;
+
+    for gid in ["sqlx-2pc-commit", "sqlx-2pc-'rollback'"] {
+        let mut tx = conn1.begin().await?;
+        sqlx::query("INSERT INTO two_phase_commits VALUES ($1)")
+            .bind(gid)
+            .execute(&mut *tx)
+            .await?;
+        tx.prepare_2pc(gid).await?;
+    }
+
+    // the prepared transactions are no longer tied to the connection
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM two_phase_commits")
+        .fetch_one(&mut conn1)
+        .await?;
+    assert_eq!(count, 0);
+
+    let prepared = conn2.prepared_transactions().await?;
+    let gids: Vec<_> = prepared.iter().map(|xact| xact.gid.as_str()).collect();
+    assert!(gids.contains(&"sqlx-2pc-commit"));
+    assert!(gids.contains(&"sqlx-2pc-'rollback'"));
+
+    conn2.commit_prepared("sqlx-2pc-commit").await?;
+    conn2.rollback_prepared("sqlx-2pc-'rollback'").await?;
+
+    let gids: Vec<String> = sqlx::query_scalar("SELECT gid FROM two_phase_commits")
+        .fetch_all(&mut conn1)
+        .await?;
+    assert_eq!(gids, ["sqlx-2pc-commit"]);
+    assert!(conn1.prepared_transactions().await?.is_empty());
+
+    // a savepoint can't be prepared on its own
+    let mut tx = conn1.begin().await?;
+    let savepoint = tx.begin().await?;
+    assert!(savepoint.prepare_2pc("sqlx-2pc-savepoint").await.is_err());
+    tx.rollback().await?;
+
+    conn1.execute("DROP TABLE two_phase_commits").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_large_objects() -> anyhow::Result<()> {
+    use futures::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+    use std::io::SeekFrom;
+
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    // larger than a single read or write
+    let data: Vec<u8> = (0..(3 << 20) + 5).map(|i| (i % 251) as u8).collect();
+
+    let mut object = PgLargeObject::create(&mut tx).await?;
+    let oid = object.oid();
+    object.write_all(&data).await?;
+
+    assert_eq!(object.seek(SeekFrom::Start(10)).await?, 10);
+    let mut buf = [0; 4];
+    object.read_exact(&mut buf).await?;
+    assert_eq!(buf, data[10..14]);
+
+    // overwrite the bytes after the ones that were read
+    object.write_all(b"sqlx").await?;
+    assert_eq!(object.seek(SeekFrom::Current(0)).await?, 18);
+
+    object.truncate(20).await?;
+    assert_eq!(object.seek(SeekFrom::End(0)).await?, 20);
+    object.close().await?;
+
+    let mut object = PgLargeObject::open(&mut tx, oid, PgLargeObjectMode::Read).await?;
+    let mut contents = Vec::new();
+    object.read_to_end(&mut contents).await?;
+    assert_eq!(contents[..14], data[..14]);
+    assert_eq!(&contents[14..18], b"sqlx");
+    assert_eq!(contents[18..], data[18..20]);
+    object.close().await?;
+
+    PgLargeObject::unlink(&mut tx, oid).await?;
+    assert!(PgLargeObject::open(&mut tx, oid, PgLargeObjectMode::Read)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_rows_in_chunks() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let ids: Vec<i32> = conn
+        .fetch_chunked(
+            sqlx::query("SELECT generate_series(1, $1)").bind(1000_i32),
+            64,
+        )
+        .map_ok(|row| row.get::<i32, _>(0))
+        .try_collect()
+        .await?;
+    assert_eq!(ids, (1..=1000).collect::<Vec<_>>());
+
+    // without arguments, and with fewer rows than a chunk
+    let ids: Vec<i32> = conn
+        .fetch_chunked(sqlx::query("SELECT generate_series(1, 3)"), 0)
+        .map_ok(|row| row.get::<i32, _>(0))
+        .try_collect()
+        .await?;
+    assert_eq!(ids, [1, 2, 3]);
+
+    // the rest of the rows are skipped when the stream is dropped early
+    {
+        let mut rows = conn.fetch_chunked(sqlx::query("SELECT generate_series(1, 1000)"), 10);
+        let first: i32 = rows.try_next().await?.unwrap().get(0);
+        assert_eq!(first, 1);
+    }
+
+    let value: i32 = sqlx::query_scalar("SELECT 42").fetch_one(&mut conn).await?;
+    assert_eq!(value, 42);
+
+    // the connection can be used after an error
+    let res = conn
+        .fetch_chunked(sqlx::query("SELECT 1 / (3 - generate_series(1, 5))"), 1)
+        .try_collect::<Vec<_>>()
+        .await;
+    assert!(res.is_err());
+
+    let value: i32 = sqlx::query_scalar("SELECT 42").fetch_one(&mut conn).await?;
+    assert_eq!(value, 42);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_parameter_statuses() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    assert!(conn.parameter_status("server_version").is_some());
+    assert_eq!(
+        conn.parameter_status("standard_conforming_strings"),
+        Some("on")
+    );
+    assert!(conn
+        .parameter_statuses()
+        .any(|(name, _)| name == "integer_datetimes"));
+
+    let mut changes = conn.watch_parameter_statuses();
+
+    conn.execute("SET application_name = 'sqlx-parameter-status'")
+        .await?;
+    assert_eq!(
+        conn.parameter_status("application_name"),
+        Some("sqlx-parameter-status")
+    );
+
+    let change = changes.next().await.unwrap();
+    assert_eq!(change.name(), "application_name");
+    assert_eq!(change.value(), "sqlx-parameter-status");
+
+    // the stream ends with the connection
+    conn.close().await?;
+    assert!(changes.next().await.is_none());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_passes_notices_to_the_handler() -> anyhow::Result<()> {
+    let notices = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options.notice_handler({
+        let notices = Arc::clone(&notices);
+        move |notice| {
+            notices.lock().unwrap().push((
+                notice.severity(),
+                notice.message().to_owned(),
+                notice.hint().map(str::to_owned),
+            ))
+        }
+    });
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    conn.execute(
+        r#"
+DO $$
+BEGIN
+    RAISE NOTICE 'hello %', 42;
+    RAISE WARNING 'careful' USING HINT = 'be careful';
+END
+$$
+        "#,
+    )
+    .await?;
+
+    assert_eq!(
+        *notices.lock().unwrap(),
+        [
+            (PgSeverity::Notice, "hello 42".to_owned(), None),
+            (
+                PgSeverity::Warning,
+                "careful".to_owned(),
+                Some("be careful".to_owned())
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_connects_to_hosts_with_target_session_attrs() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    for attrs in [
+        PgTargetSessionAttrs::Any,
+        PgTargetSessionAttrs::ReadWrite,
+        PgTargetSessionAttrs::Primary,
+        PgTargetSessionAttrs::PreferStandby,
+    ] {
+        let mut conn =
+            PgConnection::connect_with(&options.clone().target_session_attrs(attrs)).await?;
+
+        let read_only: String = sqlx::query_scalar("SHOW transaction_read_only")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(read_only, "off");
+
+        conn.close().await?;
+    }
+
+    // the test database is not a standby, and neither is the fallback host which doesn't exist
+    for attrs in [
+        PgTargetSessionAttrs::ReadOnly,
+        PgTargetSessionAttrs::Standby,
+    ] {
+        let options = options
+            .clone()
+            .fallback_host("/nonexistent-sqlx-socket-dir", None)
+            .target_session_attrs(attrs);
+
+        assert!(PgConnection::connect_with(&options).await.is_err());
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_listener_reconnects() -> anyhow::Result<()> {
+    let pool = pool::<Postgres>().await?;
+    let mut notify_conn = new::<Postgres>().await?;
+
+    let mut listener = PgListener::connect_with(&pool).await?;
+    listener.listen("resilient_channel").await?;
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut listener)
+        .await?;
+
+    notify_conn
+        .execute("NOTIFY resilient_channel, 'before'")
+        .await?;
+
+    match listener.recv_event().await? {
+        PgListenerEvent::Notification(notification) => {
+            assert_eq!(notification.payload(), "before")
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+
+    sqlx::query("SELECT pg_terminate_backend($1)")
+        .bind(pid)
+        .execute(&mut notify_conn)
+        .await?;
+
+    assert!(matches!(
+        listener.recv_event().await?,
+        PgListenerEvent::ConnectionLost
+    ));
+
+    assert!(matches!(
+        listener.recv_event().await?,
+        PgListenerEvent::Resubscribed
+    ));
+
+    notify_conn
+        .execute("NOTIFY resilient_channel, 'after'")
+        .await?;
+
+    match listener.recv_event().await? {
+        PgListenerEvent::Notification(notification) => {
+            assert_eq!(notification.payload(), "after")
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_and_receives_typed_notifications() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct OrderPlaced {
+        order_id: i64,
+        items: Vec<String>,
+    }
+
+    let pool = pool::<Postgres>().await?;
+    let mut conn = new::<Postgres>().await?;
+
+    let mut listener = PgTypedListener::<OrderPlaced>::new(PgListener::connect_with(&pool).await?);
+    listener.listen("typed_channel").await?;
+
+    let order = OrderPlaced {
+        order_id: 7,
+        items: vec!["apple".into(), "pear".into()],
+    };
+
+    conn.notify_as("typed_channel", &order).await?;
+    assert_eq!(listener.recv().await?, order);
+
+    conn.execute("NOTIFY typed_channel, 'not json'").await?;
+    assert!(matches!(listener.recv().await, Err(sqlx::Error::Decode(_))));
+
+    let too_large = "x".repeat(8000);
+    assert!(matches!(
+        conn.notify_as("typed_channel", &too_large).await,
+        Err(sqlx::Error::Encode(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_configures_the_statement_cache() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    // without a cache, only the unnamed statement is used
+    let mut conn = PgConnection::connect_with(&options.clone().statement_cache_capacity(0)).await?;
+
+    for i in 0..3_i32 {
+        let value: i32 = sqlx::query_scalar("SELECT $1 + 1")
+            .bind(i)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(value, i + 1);
+    }
+
+    let results = conn
+        .pipeline()
+        .push(sqlx::query("SELECT $1::text").bind("a"))
+        .push(sqlx::query("SELECT $1::int8").bind(1_i64))
+        .run()
+        .await?;
+
+    assert_eq!(results.len(), 2);
+
+    let prepared: i64 = conn
+        .fetch_one("SELECT count(*) FROM pg_prepared_statements")
+        .await?
+        .get(0);
+
+    assert_eq!(prepared, 0);
+    assert_eq!(conn.statement_cache_stats().hits, 0);
+
+    // statements are evicted once they are too old
+    let mut conn = PgConnection::connect_with(
+        &options
+            .clone()
+            .statement_cache_eviction(StatementCacheEviction::MaxAge(Duration::ZERO)),
+    )
+    .await?;
+
+    for _ in 0..3 {
+        sqlx::query("SELECT $1::int4")
+            .bind(1_i32)
+            .execute(&mut conn)
+            .await?;
+    }
+
+    let stats = conn.statement_cache_stats();
+    assert_eq!((stats.hits, stats.misses), (0, 3));
+    assert_eq!(stats.evictions, 2);
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    for _ in 0..3 {
+        sqlx::query("SELECT $1::int4")
+            .bind(1_i32)
+            .execute(&mut conn)
+            .await?;
+    }
+
+    let stats = conn.statement_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 1, 0));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_metadata_between_connections() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options.shared_metadata_cache_capacity(10);
+
+    let sql = "SELECT $1::status AS status, ROW('fuzzy dice', 42, 199)::inventory_item AS item";
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+    sqlx::query(sql).bind("open").fetch_one(&mut conn).await?;
+    sqlx::query("SELECT $1 AS value")
+        .bind("text")
+        .fetch_one(&mut conn)
+        .await?;
+    conn.close().await?;
+
+    // a new connection uses the metadata described by the first one, also with the unnamed
+    // statement
+    for options in [options.clone(), options.statement_cache_capacity(0)] {
+        let mut conn = PgConnection::connect_with(&options).await?;
+
+        let row = sqlx::query(sql).bind("open").fetch_one(&mut conn).await?;
+
+        assert_eq!(row.column(0).type_info().name(), "status");
+        assert_eq!(row.column(1).type_info().name(), "inventory_item");
+
+        let item: PgRecord = row.try_get("item")?;
+        assert_eq!(item.try_get::<String>(0)?, "fuzzy dice");
+
+        // statements with other parameter types are described again
+        let row = sqlx::query("SELECT $1 AS value")
+            .bind(1_i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.column(0).type_info().name(), "INT4");
+
+        conn.close().await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_applies_settings_to_single_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let timeout: String = sqlx::query_scalar("SELECT current_setting('statement_timeout')")
+        .with_setting("statement_timeout", "5s")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(timeout, "5s");
+
+    // the setting is reset once the query is done
+    let timeout: String = sqlx::query_scalar("SELECT current_setting('statement_timeout')")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(timeout, "0");
+
+    let err = sqlx::query("SELECT pg_sleep(5)")
+        .with_setting("statement_timeout", "100ms")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.into_database_error().unwrap().code().as_deref(),
+        Some("57014")
+    );
+
+    let err = sqlx::query("SELECT 1")
+        .with_setting("no_such_setting", "1")
+        .execute(&mut conn)
+        .await;
+
+    assert!(err.is_err());
+
+    // inside of a transaction, the settings last until it ends
+    let mut tx = conn.begin().await?;
+
+    let (lock_timeout,): (String,) = sqlx::query_as("SELECT current_setting('lock_timeout')")
+        .with_setting("lock_timeout", "1s")
+        .with_setting("statement_timeout", "5s")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    assert_eq!(lock_timeout, "1s");
+
+    let timeout: String = sqlx::query_scalar("SELECT current_setting('statement_timeout')")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    assert_eq!(timeout, "5s");
+
+    tx.rollback().await?;
+
+    let timeout: String = sqlx::query_scalar("SELECT current_setting('statement_timeout')")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(timeout, "0");
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -0,0 +1,3 @@
+#!/bin/sh
+# Allow replication connections from outside the container, for the logical replication tests.
+echo "host replication all all ${POSTGRES_HOST_AUTH_METHOD:-scram-sha-256}" >> "$PGDATA/pg_hba.conf"
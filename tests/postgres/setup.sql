@@ -1,6 +1,12 @@
 -- https://www.postgresql.org/docs/current/ltree.html
 CREATE EXTENSION IF NOT EXISTS ltree;
 
+-- https://www.postgresql.org/docs/current/hstore.html
+CREATE EXTENSION IF NOT EXISTS hstore;
+
+-- https://www.postgresql.org/docs/current/citext.html
+CREATE EXTENSION IF NOT EXISTS citext;
+
 -- https://www.postgresql.org/docs/current/sql-createtype.html
 CREATE TYPE status AS ENUM ('new', 'open', 'closed');
 
@@ -44,3 +50,5 @@ CREATE TABLE products (
 
 CREATE OR REPLACE PROCEDURE forty_two(INOUT forty_two INT = NULL)
     LANGUAGE plpgsql AS 'begin forty_two := 42; end;';
+
+CREATE DOMAIN email AS TEXT CHECK (VALUE LIKE '%@%');
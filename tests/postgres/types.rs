@@ -515,6 +515,27 @@ test_type!(int4range<PgRange<i32>>(Postgres,
     "'[1,2]'::int4range" == PgRange::from((INC1, EXC3)),
 ));
 
+#[cfg(any(postgres_14, postgres_15))]
+test_type!(int4multirange<sqlx::postgres::types::PgMultiRange<i32>>(Postgres,
+    "'{}'::int4multirange" == sqlx::postgres::types::PgMultiRange::<i32>::default(),
+    "'{[1,3), [5,7)}'::int4multirange" == sqlx::postgres::types::PgMultiRange::from_iter([1..3, 5..7]),
+    "'{(,2), [4,)}'::int4multirange" == sqlx::postgres::types::PgMultiRange::from(vec![PgRange::from((UNB, EXC2)), PgRange::from(4..)]),
+));
+
+#[cfg(any(postgres_14, postgres_15))]
+test_type!(int8multirange<sqlx::postgres::types::PgMultiRange<i64>>(Postgres,
+    "'{[1,3), [5,7)}'::int8multirange" == sqlx::postgres::types::PgMultiRange::from_iter([1_i64..3, 5..7]),
+));
+
+#[cfg(all(feature = "chrono", any(postgres_14, postgres_15)))]
+test_prepared_type!(tsmultirange_chrono<sqlx::postgres::types::PgMultiRange<sqlx::types::chrono::NaiveDateTime>>(Postgres,
+    "'{[2020-01-01 00:00:00,2020-01-02 00:00:00), [2020-01-03 00:00:00,2020-01-04 12:00:00)}'::tsmultirange"
+        == sqlx::postgres::types::PgMultiRange::<sqlx::types::chrono::NaiveDateTime>::from_iter([
+            "2020-01-01T00:00:00".parse().unwrap().."2020-01-02T00:00:00".parse().unwrap(),
+            "2020-01-03T00:00:00".parse().unwrap().."2020-01-04T12:00:00".parse().unwrap(),
+        ]),
+));
+
 test_prepared_type!(interval<PgInterval>(
     Postgres,
     "INTERVAL '1h'"
@@ -543,9 +564,13 @@ test_prepared_type!(interval<PgInterval>(
         },
 ));
 
-test_prepared_type!(money<PgMoney>(Postgres, "123.45::money" == PgMoney(12345)));
+test_type!(money<PgMoney>(Postgres,
+    "123.45::money" == PgMoney(12345),
+    "(-1234.56)::money" == PgMoney(-123456),
+    "0::money" == PgMoney(0),
+));
 
-test_prepared_type!(money_vec<Vec<PgMoney>>(Postgres,
+test_type!(money_vec<Vec<PgMoney>>(Postgres,
     "array[123.45,420.00,666.66]::money[]" == vec![PgMoney(12345), PgMoney(42000), PgMoney(66666)],
 ));
 
@@ -557,6 +582,71 @@ test_type!(ltree<sqlx::postgres::types::PgLTree>(Postgres,
     "'Alpha.Beta.Delta.Gamma'::ltree" == sqlx::postgres::types::PgLTree::from_iter(["Alpha", "Beta", "Delta", "Gamma"]).unwrap(),
 ));
 
+#[cfg(any(postgres_14, postgres_15))]
+test_decode_type!(lquery<sqlx::postgres::types::PgLQuery>(Postgres,
+    "'Foo.*{2,}.Bar|Baz*@.!Quux'::lquery" == sqlx::postgres::types::PgLQuery::from_str("Foo.*{2,}.Bar|Baz*@.!Quux").unwrap(),
+    "'*{,3}.foo_bar%'::lquery" == sqlx::postgres::types::PgLQuery::from_str("*{,3}.foo_bar%").unwrap(),
+));
+
+#[cfg(any(postgres_14, postgres_15))]
+test_decode_type!(lquery_vec<Vec<sqlx::postgres::types::PgLQuery>>(Postgres,
+    "array['Foo.*', '*{1}.Bar']::lquery[]" ==
+        vec![
+            sqlx::postgres::types::PgLQuery::from_str("Foo.*").unwrap(),
+            sqlx::postgres::types::PgLQuery::from_str("*{1}.Bar").unwrap(),
+        ]
+));
+
+test_type!(citext<String>(Postgres,
+    "'Hello'::citext" == "Hello",
+));
+
+// there is no equality operator between `citext[]` and `text[]`
+test_decode_type!(citext_vec<Vec<String>>(Postgres,
+    "array['Hello', 'World']::citext[]" == vec!["Hello", "World"],
+));
+
+// `jsonpath` has no equality operator, and Postgres normalizes the expressions it returns
+test_decode_type!(json_path<sqlx::postgres::types::PgJsonPath>(Postgres,
+    "'$.a[*] ? (@ > 1)'::jsonpath" == sqlx::postgres::types::PgJsonPath::new("$.\"a\"[*]?(@ > 1)"),
+    "'strict $.a'::jsonpath" == sqlx::postgres::types::PgJsonPath::new("strict $.\"a\""),
+));
+
+test_type!(pg_lsn<sqlx::postgres::types::PgLsn>(Postgres,
+    "'0/0'::pg_lsn" == sqlx::postgres::types::PgLsn::default(),
+    "'16/B374D848'::pg_lsn" == sqlx::postgres::types::PgLsn::from_u64(0x16_B374_D848),
+));
+
+test_type!(pg_lsn_vec<Vec<sqlx::postgres::types::PgLsn>>(Postgres,
+    "array['0/1','1/0']::pg_lsn[]" == vec![
+        sqlx::postgres::types::PgLsn::from_u64(1),
+        sqlx::postgres::types::PgLsn::from_u64(1 << 32),
+    ],
+));
+
+test_type!(tid<sqlx::postgres::types::PgTid>(Postgres,
+    "'(0,1)'::tid" == sqlx::postgres::types::PgTid { block: 0, offset: 1 },
+    "'(4294967295,65535)'::tid" == sqlx::postgres::types::PgTid { block: u32::MAX, offset: u16::MAX },
+));
+
+test_type!(tid_vec<Vec<sqlx::postgres::types::PgTid>>(Postgres,
+    "array['(0,1)','(2,3)']::tid[]" == vec![
+        sqlx::postgres::types::PgTid { block: 0, offset: 1 },
+        sqlx::postgres::types::PgTid { block: 2, offset: 3 },
+    ],
+));
+
+test_type!(hstore<sqlx::postgres::types::PgHstore>(Postgres,
+    "''::hstore" == sqlx::postgres::types::PgHstore::default(),
+    "'a=>1, b=>NULL, \"c d\"=>\"e\\\"f\"'::hstore" ==
+        sqlx::postgres::types::PgHstore::from_iter([("a", Some("1")), ("b", None), ("c d", Some("e\"f"))]),
+));
+
+test_type!(hstore_hash_map<std::collections::HashMap<String, Option<String>>>(Postgres,
+    "'a=>1, b=>NULL'::hstore" ==
+        std::collections::HashMap::from([("a".to_owned(), Some("1".to_owned())), ("b".to_owned(), None)]),
+));
+
 // FIXME: needed to disable `ltree` tests in version that don't have a binary format for it
 // but `PgLTree` should just fall back to text format
 #[cfg(any(postgres_14, postgres_15))]
@@ -201,3 +201,47 @@ async fn it_allows_to_rekey_the_db() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_encrypts_pool_connections_with_a_key_and_rekeys() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    let (url, _dir) = new_db_url().await?;
+
+    let options = SqliteConnectOptions::from_str(&url)?
+        .key("it's a secret")
+        .create_if_missing(true);
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options.clone())
+        .await?;
+
+    fill_db(&mut *pool.acquire().await?).await?;
+
+    let mut conns = vec![pool.acquire().await?, pool.acquire().await?];
+
+    for conn in &mut conns {
+        let rows = query("SELECT * FROM Company")
+            .fetch_all(&mut **conn)
+            .await?;
+        assert_eq!(rows.len(), 2);
+    }
+
+    conns[0].rekey("new 'secret'").await?;
+    drop(conns);
+    pool.close().await;
+
+    // the old key does not work anymore
+    let mut conn = options.connect().await?;
+    assert!(query("SELECT * FROM Company")
+        .fetch_all(&mut conn)
+        .await
+        .is_err());
+
+    let mut conn = options.key("new 'secret'").connect().await?;
+    let rows = query("SELECT * FROM Company").fetch_all(&mut conn).await?;
+    assert_eq!(rows.len(), 2);
+
+    Ok(())
+}
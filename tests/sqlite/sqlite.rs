@@ -1,7 +1,8 @@
 use futures::TryStreamExt;
 use rand::{Rng, SeedableRng};
 use rand_xoshiro::Xoshiro256PlusPlus;
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteAggregate, SqliteConnectOptions, SqliteFunctionArgs, SqlitePoolOptions};
 use sqlx::{
     query, sqlite::Sqlite, sqlite::SqliteRow, Column, ConnectOptions, Connection, Executor, Row,
     SqliteConnection, SqlitePool, Statement, TypeInfo,
@@ -220,6 +221,33 @@ async fn it_opens_with_extension() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_checks_the_allowed_extensions() -> anyhow::Result<()> {
+    use std::str::FromStr;
+
+    let opts = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .allowed_extensions(["ipaddr"])
+        .extension("ipaddr")
+        .extension("/tmp/untrusted");
+
+    let err = SqliteConnection::connect_with(&opts).await.unwrap_err();
+    assert!(
+        matches!(err, sqlx::Error::Configuration(_)),
+        "expected a configuration error, got {err:?}"
+    );
+    assert!(err.to_string().contains("/tmp/untrusted"), "{err}");
+
+    // an allowed extension is loaded, which fails here
+    let opts = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .allowed_extensions(["sqlx-missing-extension"])
+        .extension("sqlx-missing-extension");
+
+    let err = SqliteConnection::connect_with(&opts).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::Database(_)), "{err:?}");
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_opens_in_memory() -> anyhow::Result<()> {
     // If the filename is ":memory:", then a private, temporary in-memory database
@@ -794,3 +822,1263 @@ async fn test_multiple_set_progress_handler_calls_drop_old_handler() -> anyhow::
     assert_eq!(1, Arc::strong_count(&ref_counted_object));
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_backs_up_a_database() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    conn.execute("CREATE TABLE item (id INTEGER PRIMARY KEY, data BLOB NOT NULL)")
+        .await?;
+
+    for _ in 0..100 {
+        sqlx::query("INSERT INTO item (data) VALUES (zeroblob(4096))")
+            .execute(&mut conn)
+            .await?;
+    }
+
+    let dir = tempdir::TempDir::new("sqlx-backup")?;
+
+    let mut dest = SqliteConnectOptions::new()
+        .filename(dir.path().join("backup.db"))
+        .create_if_missing(true)
+        .connect()
+        .await?;
+
+    let mut steps = Vec::new();
+
+    conn.backup_to(&mut dest)
+        .pages_per_step(10)
+        .run_with_progress(|progress| steps.push(progress))
+        .await?;
+
+    let last = steps.last().unwrap();
+    assert!(steps.len() > 10);
+    assert_eq!(last.remaining(), 0);
+    assert!(steps.iter().all(|p| p.page_count() == last.page_count()));
+
+    // the source connection can still be used
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 100);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut dest)
+        .await?;
+    assert_eq!(count, 100);
+
+    // and back into memory, in one step
+    let mut memory = SqliteConnection::connect("sqlite::memory:").await?;
+    dest.backup_to(&mut memory).run().await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut memory)
+        .await?;
+    assert_eq!(count, 100);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_supports_user_defined_functions() -> anyhow::Result<()> {
+    use std::str::FromStr;
+
+    #[derive(Default)]
+    struct Concat(Vec<String>);
+
+    impl SqliteAggregate for Concat {
+        type Output = Option<String>;
+
+        fn step(&mut self, args: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError> {
+            self.0.push(args.get(0)?);
+            Ok(())
+        }
+
+        fn finish(self) -> Result<Option<String>, BoxDynError> {
+            Ok((!self.0.is_empty()).then(|| self.0.join("+")))
+        }
+    }
+
+    let mut conn = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .create_scalar_function("slug", 1, true, |args| {
+            let title: Option<String> = args.get(0)?;
+            Ok(title.map(|title| title.to_lowercase().replace(' ', "-")))
+        })
+        .create_scalar_function("add_all", -1, true, |args| {
+            (0..args.len()).try_fold(0_i64, |sum, i| Ok(sum + args.get::<i64>(i)?))
+        })
+        .create_aggregate_function("concat_all", 1, true, Concat::default)
+        .connect()
+        .await?;
+
+    let slug: String = sqlx::query_scalar("SELECT slug(?)")
+        .bind("Hello World")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(slug, "hello-world");
+
+    let slug: Option<String> = sqlx::query_scalar("SELECT slug(NULL)")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(slug, None);
+
+    let sum: i64 = sqlx::query_scalar("SELECT add_all(1, 2, 3)")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(sum, 6);
+
+    // deterministic functions can be used in an index
+    conn.execute(
+        r#"
+CREATE TABLE posts (title TEXT NOT NULL);
+CREATE UNIQUE INDEX posts_slug ON posts (slug(title));
+INSERT INTO posts (title) VALUES ('B post'), ('A post');
+        "#,
+    )
+    .await?;
+
+    let duplicate = sqlx::query("INSERT INTO posts (title) VALUES ('a Post')")
+        .execute(&mut conn)
+        .await;
+    assert!(duplicate.is_err());
+
+    let titles: String = sqlx::query_scalar("SELECT concat_all(title) FROM posts")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(titles, "B post+A post");
+
+    // an empty group
+    let titles: Option<String> =
+        sqlx::query_scalar("SELECT concat_all(title) FROM posts WHERE title = ''")
+            .fetch_one(&mut conn)
+            .await?;
+    assert_eq!(titles, None);
+
+    // errors of the function are errors of the statement
+    let err = sqlx::query_scalar::<_, String>("SELECT slug(1)")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("mismatched types"), "{err}");
+
+    // functions can also be added to an open connection
+    conn.lock_handle()
+        .await?
+        .create_scalar_function("double", 1, false, |args| Ok(args.get::<f64>(0)? * 2.0))?;
+
+    let double: f64 = sqlx::query_scalar("SELECT double(1.5)")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(double, 3.0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_supports_virtual_tables() -> anyhow::Result<()> {
+    use sqlx::sqlite::{
+        SqliteIndexConstraintOp, SqliteIndexInfo, SqliteVirtualTable, SqliteVirtualTableColumn,
+        SqliteVirtualTableCursor, SqliteVirtualTableKind,
+    };
+    use std::str::FromStr;
+
+    // the integers from `start` to `stop`, as a table-valued function
+    struct Series;
+
+    struct SeriesCursor {
+        value: i64,
+        stop: i64,
+    }
+
+    impl SqliteVirtualTable for Series {
+        type Aux = ();
+        type Cursor = SeriesCursor;
+
+        fn connect(_: &(), _: &[&str]) -> Result<(String, Self), BoxDynError> {
+            Ok((
+                "CREATE TABLE x(value INTEGER, start HIDDEN, stop HIDDEN)".into(),
+                Series,
+            ))
+        }
+
+        fn best_index(&self, info: &mut SqliteIndexInfo<'_>) -> Result<(), BoxDynError> {
+            let mut args = Vec::new();
+
+            for (i, constraint) in info.constraints().enumerate() {
+                if constraint.is_usable()
+                    && constraint.op() == SqliteIndexConstraintOp::Eq
+                    && constraint.column() > 0
+                {
+                    args.push((constraint.column(), i));
+                }
+            }
+
+            if args.len() != 2 {
+                return Err("series() requires a start and a stop".into());
+            }
+
+            args.sort();
+
+            for (arg, (_, i)) in args.into_iter().enumerate() {
+                info.use_constraint(i, arg, true);
+            }
+
+            Ok(())
+        }
+
+        fn open(&self) -> Result<SeriesCursor, BoxDynError> {
+            Ok(SeriesCursor { value: 0, stop: -1 })
+        }
+    }
+
+    impl SqliteVirtualTableCursor for SeriesCursor {
+        fn filter(&mut self, _: i32, args: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError> {
+            self.value = args.get(0)?;
+            self.stop = args.get(1)?;
+            Ok(())
+        }
+
+        fn next(&mut self) -> Result<(), BoxDynError> {
+            self.value += 1;
+            Ok(())
+        }
+
+        fn eof(&self) -> bool {
+            self.value > self.stop
+        }
+
+        fn column(
+            &self,
+            column: usize,
+            value: &mut SqliteVirtualTableColumn<'_>,
+        ) -> Result<(), BoxDynError> {
+            if column == 0 {
+                value.set(self.value);
+            }
+
+            Ok(())
+        }
+
+        fn rowid(&self) -> Result<i64, BoxDynError> {
+            Ok(self.value)
+        }
+    }
+
+    // the words of the argument of the module
+    struct Words(Vec<String>);
+
+    struct WordsCursor {
+        words: Vec<String>,
+        row: usize,
+    }
+
+    impl SqliteVirtualTable for Words {
+        type Aux = String;
+        type Cursor = WordsCursor;
+
+        fn connect(separator: &String, args: &[&str]) -> Result<(String, Self), BoxDynError> {
+            let words = args
+                .iter()
+                .flat_map(|arg| arg.split(separator.as_str()))
+                .map(|word| word.trim().to_owned())
+                .collect();
+
+            Ok(("CREATE TABLE x(word TEXT)".into(), Words(words)))
+        }
+
+        fn open(&self) -> Result<WordsCursor, BoxDynError> {
+            Ok(WordsCursor {
+                words: self.0.clone(),
+                row: 0,
+            })
+        }
+    }
+
+    impl SqliteVirtualTableCursor for WordsCursor {
+        fn filter(&mut self, _: i32, _: &SqliteFunctionArgs<'_>) -> Result<(), BoxDynError> {
+            self.row = 0;
+            Ok(())
+        }
+
+        fn next(&mut self) -> Result<(), BoxDynError> {
+            self.row += 1;
+            Ok(())
+        }
+
+        fn eof(&self) -> bool {
+            self.row >= self.words.len()
+        }
+
+        fn column(
+            &self,
+            _: usize,
+            value: &mut SqliteVirtualTableColumn<'_>,
+        ) -> Result<(), BoxDynError> {
+            if self.words[self.row] == "panic" {
+                panic!("a panic in a virtual table");
+            }
+
+            value.set(self.words[self.row].as_str());
+            Ok(())
+        }
+
+        fn rowid(&self) -> Result<i64, BoxDynError> {
+            Ok(self.row as i64)
+        }
+    }
+
+    let mut conn = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .create_module::<Series>("series", SqliteVirtualTableKind::EponymousOnly, ())
+        .connect()
+        .await?;
+
+    let values: Vec<i64> = sqlx::query_scalar("SELECT value FROM series(?, ?)")
+        .bind(3)
+        .bind(6)
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(values, [3, 4, 5, 6]);
+
+    let sum: i64 =
+        sqlx::query_scalar("SELECT SUM(value) FROM series WHERE start = 1 AND stop = 10")
+            .fetch_one(&mut conn)
+            .await?;
+    assert_eq!(sum, 55);
+
+    // errors of the table are errors of the statement
+    let err = conn.execute("SELECT * FROM series").await.unwrap_err();
+    assert!(err.to_string().contains("requires a start"), "{err}");
+
+    conn.lock_handle().await?.create_module::<Words>(
+        "words",
+        SqliteVirtualTableKind::Regular,
+        ",".to_owned(),
+    )?;
+
+    conn.execute("CREATE VIRTUAL TABLE fruit USING words(apple, pear, plum)")
+        .await?;
+
+    let fruit: Vec<String> = sqlx::query_scalar("SELECT word FROM fruit WHERE word LIKE 'p%'")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(fruit, ["pear", "plum"]);
+
+    // a regular module has no eponymous table
+    assert!(conn.execute("SELECT * FROM words").await.is_err());
+
+    conn.execute("CREATE VIRTUAL TABLE broken USING words(panic)")
+        .await?;
+
+    let err = conn.execute("SELECT * FROM broken").await.unwrap_err();
+    assert!(err.to_string().contains("panicked"), "{err}");
+
+    conn.execute("DROP TABLE fruit").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_serializes_and_deserializes_a_database() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // an empty database
+    let empty = conn.serialize(None).await?;
+
+    conn.execute("CREATE TABLE item (name TEXT NOT NULL); INSERT INTO item VALUES ('a'), ('b')")
+        .await?;
+
+    let snapshot = conn.serialize(None).await?;
+    assert!(snapshot.starts_with(b"SQLite format 3\0"));
+
+    let mut restored = SqliteConnection::connect("sqlite::memory:").await?;
+    restored.deserialize(None, &snapshot, false).await?;
+
+    sqlx::query("INSERT INTO item VALUES ('c')")
+        .execute(&mut restored)
+        .await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM item ORDER BY name")
+        .fetch_all(&mut restored)
+        .await?;
+    assert_eq!(names, ["a", "b", "c"]);
+
+    // the snapshot is not changed by the restored connection
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 2);
+
+    // a read-only copy
+    let mut read_only = SqliteConnection::connect("sqlite::memory:").await?;
+    read_only.deserialize(None, &snapshot, true).await?;
+
+    assert!(read_only
+        .execute("INSERT INTO item VALUES ('d')")
+        .await
+        .is_err());
+
+    // an attached database
+    restored
+        .execute("ATTACH DATABASE ':memory:' AS other")
+        .await?;
+    restored
+        .deserialize(Some("other"), &snapshot, false)
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM other.item")
+        .fetch_one(&mut restored)
+        .await?;
+    assert_eq!(count, 2);
+
+    assert!(restored.serialize(Some("missing")).await.is_err());
+
+    restored.deserialize(None, &empty, false).await?;
+
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master")
+        .fetch_one(&mut restored)
+        .await?;
+    assert_eq!(tables, 0);
+
+    // a file database in the WAL journal mode
+    let dir = tempdir::TempDir::new("sqlx-serialize")?;
+
+    let mut file = SqliteConnectOptions::new()
+        .filename(dir.path().join("wal.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .connect()
+        .await?;
+
+    file.execute("CREATE TABLE item (name TEXT NOT NULL); INSERT INTO item VALUES ('a')")
+        .await?;
+
+    let snapshot = file.serialize(None).await?;
+
+    let mut restored = SqliteConnection::connect("sqlite::memory:").await?;
+    restored.deserialize(None, &snapshot, false).await?;
+
+    sqlx::query("INSERT INTO item VALUES ('b')")
+        .execute(&mut restored)
+        .await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checkpoints_the_wal() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteCheckpointMode, SqliteJournalMode};
+    use std::sync::Mutex;
+
+    let dir = tempdir::TempDir::new("sqlx-checkpoint")?;
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(dir.path().join("wal.db"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .connect()
+        .await?;
+
+    let commits = Arc::new(Mutex::new(Vec::new()));
+    let hook_commits = Arc::clone(&commits);
+
+    conn.lock_handle()
+        .await?
+        .set_wal_hook(move |db_name, frames| {
+            hook_commits
+                .lock()
+                .unwrap()
+                .push((db_name.to_owned(), frames))
+        });
+
+    conn.execute("CREATE TABLE item (data BLOB NOT NULL)")
+        .await?;
+
+    for _ in 0..10 {
+        sqlx::query("INSERT INTO item (data) VALUES (zeroblob(4096))")
+            .execute(&mut conn)
+            .await?;
+    }
+
+    let frames = {
+        let commits = commits.lock().unwrap();
+        assert_eq!(commits.len(), 11);
+        assert!(commits.iter().all(|(db_name, _)| db_name == "main"));
+        assert!(commits.windows(2).all(|w| w[0].1 < w[1].1));
+
+        commits.last().unwrap().1
+    };
+
+    // without automatic checkpoints, all frames are still in the WAL
+    let checkpoint = conn.checkpoint(SqliteCheckpointMode::Passive).await?;
+    assert!(!checkpoint.is_busy());
+    assert_eq!(checkpoint.log_frames(), frames);
+    assert_eq!(checkpoint.checkpointed_frames(), frames);
+
+    let checkpoint = conn.checkpoint(SqliteCheckpointMode::Truncate).await?;
+    assert!(!checkpoint.is_busy());
+    assert_eq!(checkpoint.log_frames(), 0);
+
+    let wal_len = std::fs::metadata(dir.path().join("wal.db-wal"))?.len();
+    assert_eq!(wal_len, 0);
+
+    conn.lock_handle().await?.remove_wal_hook();
+
+    sqlx::query("INSERT INTO item (data) VALUES (zeroblob(4096))")
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(commits.lock().unwrap().len(), 11);
+
+    // a database without a WAL
+    let mut memory = SqliteConnection::connect("sqlite::memory:").await?;
+    let checkpoint = memory.checkpoint(SqliteCheckpointMode::Full).await?;
+    assert_eq!(checkpoint.log_frames(), 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_calls_the_commit_rollback_and_update_hooks() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteOperation;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    conn.execute("CREATE TABLE item (name TEXT NOT NULL)")
+        .await?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let commits = Arc::new(AtomicUsize::new(0));
+    let rollbacks = Arc::new(AtomicUsize::new(0));
+    let allow_commit = Arc::new(AtomicBool::new(true));
+
+    {
+        let mut handle = conn.lock_handle().await?;
+
+        let hook_changes = Arc::clone(&changes);
+        handle.set_update_hook(move |change| {
+            hook_changes.lock().unwrap().push((
+                change.operation(),
+                change.database().to_owned(),
+                change.table().to_owned(),
+                change.rowid(),
+            ))
+        });
+
+        let hook_commits = Arc::clone(&commits);
+        let hook_allow_commit = Arc::clone(&allow_commit);
+        handle.set_commit_hook(move || {
+            hook_commits.fetch_add(1, Ordering::SeqCst);
+            hook_allow_commit.load(Ordering::SeqCst)
+        });
+
+        let hook_rollbacks = Arc::clone(&rollbacks);
+        handle.set_rollback_hook(move || {
+            hook_rollbacks.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    conn.execute("INSERT INTO item (name) VALUES ('a'), ('b')")
+        .await?;
+    conn.execute("UPDATE item SET name = 'c' WHERE rowid = 2")
+        .await?;
+    conn.execute("DELETE FROM item WHERE rowid = 1").await?;
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            (SqliteOperation::Insert, "main".into(), "item".into(), 1),
+            (SqliteOperation::Insert, "main".into(), "item".into(), 2),
+            (SqliteOperation::Update, "main".into(), "item".into(), 2),
+            (SqliteOperation::Delete, "main".into(), "item".into(), 1),
+        ]
+    );
+    assert_eq!(commits.load(Ordering::SeqCst), 3);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 0);
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO item (name) VALUES ('d')").await?;
+    tx.rollback().await?;
+
+    assert_eq!(commits.load(Ordering::SeqCst), 3);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 1);
+    assert_eq!(changes.lock().unwrap().len(), 5);
+
+    // the commit hook turns the commit into a rollback
+    allow_commit.store(false, Ordering::SeqCst);
+    conn.execute("INSERT INTO item (name) VALUES ('e')")
+        .await
+        .unwrap_err();
+
+    assert_eq!(commits.load(Ordering::SeqCst), 4);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 2);
+    assert_eq!(changes.lock().unwrap().len(), 6);
+
+    {
+        let mut handle = conn.lock_handle().await?;
+        handle.remove_update_hook();
+        handle.remove_commit_hook();
+        handle.remove_rollback_hook();
+    }
+
+    conn.execute("INSERT INTO item (name) VALUES ('f')").await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 2);
+    assert_eq!(changes.lock().unwrap().len(), 6);
+    assert_eq!(commits.load(Ordering::SeqCst), 4);
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-preupdate-hook")]
+#[sqlx_macros::test]
+async fn it_calls_the_preupdate_hook() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteOperation;
+    use sqlx::{Value, ValueRef};
+    use std::sync::Mutex;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    conn.execute("CREATE TABLE item (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let hook_changes = Arc::clone(&changes);
+
+    conn.lock_handle().await?.set_preupdate_hook(move |change| {
+        let name = |value: sqlx::Result<sqlx::sqlite::SqliteValue>| {
+            value
+                .ok()
+                .and_then(|value| value.as_ref().to_owned().try_decode::<String>().ok())
+        };
+
+        assert_eq!(change.column_count(), 2);
+        assert!(change.old_value(2).is_err());
+
+        hook_changes.lock().unwrap().push((
+            change.operation(),
+            change.table().to_owned(),
+            change.old_rowid(),
+            change.new_rowid(),
+            name(change.old_value(1)),
+            name(change.new_value(1)),
+            change.depth(),
+        ));
+    });
+
+    conn.execute("INSERT INTO item (id, name) VALUES (1, 'a')")
+        .await?;
+    conn.execute("UPDATE item SET id = 2, name = 'b'").await?;
+    conn.execute("DELETE FROM item").await?;
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            (
+                SqliteOperation::Insert,
+                "item".to_owned(),
+                None,
+                Some(1),
+                None,
+                Some("a".to_owned()),
+                0
+            ),
+            (
+                SqliteOperation::Update,
+                "item".to_owned(),
+                Some(1),
+                Some(2),
+                Some("a".to_owned()),
+                Some("b".to_owned()),
+                0
+            ),
+            (
+                SqliteOperation::Delete,
+                "item".to_owned(),
+                Some(2),
+                None,
+                Some("b".to_owned()),
+                None,
+                0
+            ),
+        ]
+    );
+
+    conn.lock_handle().await?.remove_preupdate_hook();
+
+    conn.execute("INSERT INTO item (name) VALUES ('c')").await?;
+    assert_eq!(changes.lock().unwrap().len(), 3);
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-session")]
+#[sqlx_macros::test]
+async fn it_applies_and_inverts_changesets() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteConflictAction, SqliteConflictKind, SqliteOperation, SqliteSession};
+    use sqlx::Value;
+
+    const SCHEMA: &str = "CREATE TABLE item (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+        CREATE TABLE untracked (id INTEGER PRIMARY KEY)";
+
+    async fn names(conn: &mut SqliteConnection) -> anyhow::Result<Vec<(i64, String)>> {
+        Ok(sqlx::query_as("SELECT id, name FROM item ORDER BY id")
+            .fetch_all(conn)
+            .await?)
+    }
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut replica = SqliteConnection::connect("sqlite::memory:").await?;
+    conn.execute(SCHEMA).await?;
+    replica.execute(SCHEMA).await?;
+
+    conn.execute("INSERT INTO item (id, name) VALUES (1, 'a')")
+        .await?;
+    replica
+        .execute("INSERT INTO item (id, name) VALUES (1, 'a')")
+        .await?;
+
+    let session = conn.create_session(None).await?;
+    session.attach(&mut conn, Some("item")).await?;
+
+    conn.execute(
+        "INSERT INTO item (id, name) VALUES (2, 'b'), (3, 'c');
+        UPDATE item SET name = 'd' WHERE id = 1;
+        DELETE FROM item WHERE id = 3;
+        INSERT INTO untracked (id) VALUES (1)",
+    )
+    .await?;
+
+    let changeset = session.changeset(&mut conn).await?;
+    let patchset = session.patchset(&mut conn).await?;
+    assert!(!changeset.is_empty());
+    assert!(patchset.len() < changeset.len());
+
+    // a session only works with its own connection
+    assert!(session.changeset(&mut replica).await.is_err());
+
+    replica
+        .apply_changeset(&changeset, |_| SqliteConflictAction::Abort)
+        .await?;
+    assert_eq!(
+        names(&mut replica).await?,
+        [(1, "d".to_owned()), (2, "b".to_owned())]
+    );
+
+    let untracked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM untracked")
+        .fetch_one(&mut replica)
+        .await?;
+    assert_eq!(untracked, 0);
+
+    // applying the changeset again conflicts with the rows it inserted and updated
+    let mut conflicts = Vec::new();
+    replica
+        .apply_changeset(&changeset, |conflict| {
+            let name = conflict
+                .conflicting_value(1)
+                .ok()
+                .flatten()
+                .and_then(|value| value.try_decode::<String>().ok());
+
+            conflicts.push((conflict.kind(), conflict.operation(), name));
+            SqliteConflictAction::Omit
+        })
+        .await?;
+    assert_eq!(
+        conflicts,
+        [
+            (
+                SqliteConflictKind::Data,
+                SqliteOperation::Update,
+                Some("d".to_owned())
+            ),
+            (
+                SqliteConflictKind::Conflict,
+                SqliteOperation::Insert,
+                Some("b".to_owned())
+            ),
+        ]
+    );
+
+    // aborting undoes the changes that were already applied
+    replica.execute("DELETE FROM item WHERE id = 2").await?;
+    replica
+        .apply_changeset(&changeset, |_| SqliteConflictAction::Abort)
+        .await
+        .unwrap_err();
+    assert_eq!(names(&mut replica).await?, [(1, "d".to_owned())]);
+
+    let inverted = SqliteSession::invert_changeset(&changeset)?;
+    conn.apply_changeset(&inverted, |_| SqliteConflictAction::Abort)
+        .await?;
+    assert_eq!(names(&mut conn).await?, [(1, "a".to_owned())]);
+
+    session.delete(&mut conn).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_registers_collations_on_every_pool_connection() -> anyhow::Result<()> {
+    use std::str::FromStr;
+
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .collation("unicase", |a: &str, b: &str| {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        });
+
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .min_connections(2)
+        .max_connections(2)
+        .test_before_acquire(false)
+        .connect_with(options)
+        .await?;
+
+    let mut conns = vec![pool.acquire().await?, pool.acquire().await?];
+
+    for conn in &mut conns {
+        conn.execute("CREATE TEMPORARY TABLE item (name TEXT NOT NULL UNIQUE COLLATE unicase)")
+            .await?;
+        conn.execute("INSERT INTO item (name) VALUES ('b'), ('Äc'), ('A')")
+            .await?;
+
+        // the collation folds the case of both ASCII and other letters
+        let err = conn
+            .execute("INSERT INTO item (name) VALUES ('äC')")
+            .await
+            .unwrap_err();
+        assert!(err
+            .as_database_error()
+            .map_or(false, |e| e.is_unique_violation()));
+
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM item ORDER BY name")
+            .fetch_all(&mut **conn)
+            .await?;
+        assert_eq!(names, ["A", "b", "Äc"]);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_calls_the_busy_handler() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let dir = tempdir::TempDir::new("sqlx-busy")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("busy.db"))
+        .create_if_missing(true);
+
+    let mut writer = options.connect().await?;
+    writer.execute("CREATE TABLE item (id INTEGER)").await?;
+
+    let calls = Arc::new(AtomicU32::new(0));
+    let handler_calls = Arc::clone(&calls);
+
+    let mut conn = options
+        .clone()
+        .busy_handler(move |attempts| {
+            assert_eq!(handler_calls.fetch_add(1, Ordering::SeqCst), attempts);
+            attempts < 3
+        })
+        .connect()
+        .await?;
+
+    let mut tx = writer.begin().await?;
+    tx.execute("INSERT INTO item (id) VALUES (1)").await?;
+
+    let err = conn
+        .execute("INSERT INTO item (id) VALUES (2)")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.as_database_error().and_then(|e| e.code()).as_deref(),
+        Some("5")
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    tx.commit().await?;
+
+    conn.execute("INSERT INTO item (id) VALUES (2)").await?;
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_attaches_and_detaches_databases() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlx-attach")?;
+    let archive = dir.path().join("archive.db");
+
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("main.db"))
+        .create_if_missing(true);
+
+    let mut conn = options.connect().await?;
+    assert!(conn.attached_databases().await?.is_empty());
+
+    conn.attach(&archive, "my \"archive\"").await?;
+    conn.execute(r#"CREATE TABLE "my ""archive""".item (id INTEGER)"#)
+        .await?;
+
+    let attached = conn.attached_databases().await?;
+    assert_eq!(attached.len(), 1);
+    assert_eq!(attached[0].0, "my \"archive\"");
+    assert_eq!(attached[0].1.file_name(), archive.file_name());
+
+    // the schema is in use already
+    assert!(conn.attach(&archive, "my \"archive\"").await.is_err());
+
+    conn.detach("my \"archive\"").await?;
+    assert!(conn.attached_databases().await?.is_empty());
+    assert!(conn.detach("my \"archive\"").await.is_err());
+
+    // every connection of a pool attaches the database
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options.attach(&archive, "archive"))
+        .await?;
+
+    let mut conns = vec![pool.acquire().await?, pool.acquire().await?];
+
+    for conn in &mut conns {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM archive.item")
+            .fetch_one(&mut **conn)
+            .await?;
+        assert_eq!(count, 0);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reads_and_writes_blobs_incrementally() -> anyhow::Result<()> {
+    use futures::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+    use std::io::{ErrorKind, SeekFrom};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE file (data BLOB NOT NULL)")
+        .await?;
+    conn.execute("INSERT INTO file (rowid, data) VALUES (1, zeroblob(10)), (2, x'0102')")
+        .await?;
+
+    {
+        let mut blob = conn
+            .open_blob(Some("temp"), "file", "data", 1, false)
+            .await?;
+        assert_eq!(blob.len(), 10);
+
+        blob.write_all(b"hello").await?;
+        blob.write_all(b"world").await?;
+
+        // the value cannot grow
+        let err = blob.write_all(b"!").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+
+        assert_eq!(blob.seek(SeekFrom::End(-5)).await?, 5);
+        let mut buf = Vec::new();
+        blob.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"world");
+
+        assert!(blob.seek(SeekFrom::Current(-11)).await.is_err());
+
+        blob.reopen(2)?;
+        assert_eq!(blob.len(), 2);
+        let mut buf = Vec::new();
+        blob.read_to_end(&mut buf).await?;
+        assert_eq!(buf, [1, 2]);
+    }
+
+    let data: Vec<u8> = sqlx::query_scalar("SELECT data FROM file WHERE rowid = 1")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(data, b"helloworld");
+
+    let mut blob = conn
+        .open_blob(Some("temp"), "file", "data", 1, true)
+        .await?;
+    assert!(blob.write(b"bye").await.is_err());
+    drop(blob);
+
+    assert!(conn
+        .open_blob(None, "file", "data", 1, false)
+        .await
+        .is_err());
+    assert!(conn
+        .open_blob(Some("temp"), "file", "data", 3, false)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_the_status_of_the_connection() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let before = conn.status().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE item (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    for i in 0..100_i64 {
+        sqlx::query("INSERT INTO item (id, name) VALUES (?, ?)")
+            .bind(i)
+            .bind(format!("item {i}"))
+            .execute(&mut conn)
+            .await?;
+    }
+
+    sqlx::query("SELECT * FROM item")
+        .fetch_all(&mut conn)
+        .await?;
+
+    let after = conn.status().await?;
+
+    assert!(after.memory_used() > 0);
+    assert!(after.memory_highwater() >= after.memory_used());
+    assert!(after.cache_used() > 0);
+    assert!(after.schema_used() > before.schema_used());
+    assert!(after.statements() >= 2);
+    assert!(after.statements_used() > 0);
+    assert!(after.cache_hits() > before.cache_hits());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_routes_reads_and_writes_of_a_rw_pool() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteRwPool, SqliteRwPoolOptions};
+
+    let dir = tempdir::TempDir::new("sqlx-rw-pool")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("data.db"))
+        .create_if_missing(true);
+
+    let pool = SqliteRwPoolOptions::new()
+        .max_readers(3)
+        .connect_with(options)
+        .await?;
+
+    assert_eq!(pool.writer().options().get_max_connections(), 1);
+
+    pool.execute("CREATE TABLE item (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let mut tasks = Vec::new();
+
+    for i in 0..20_i64 {
+        let pool = pool.clone();
+
+        tasks.push(sqlx_core::rt::spawn(async move {
+            sqlx::query("INSERT INTO item (id, name) VALUES (?, ?)")
+                .bind(i)
+                .bind(format!("item {i}"))
+                .execute(&pool)
+                .await?;
+
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM item")
+                .fetch_one(&pool)
+                .await
+        }));
+    }
+
+    for task in tasks {
+        assert!(task.await? >= 1);
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(count, 20);
+
+    // the readers cannot write, so a query that writes and returns rows needs the writer
+    let insert = "INSERT INTO item (id, name) VALUES (100, 'last') RETURNING id";
+    assert!(sqlx::query(insert).fetch_one(&pool).await.is_err());
+
+    let id: i64 = sqlx::query_scalar(insert)
+        .fetch_one(&mut *pool.write().await?)
+        .await?;
+    assert_eq!(id, 100);
+
+    let mut tx = pool.begin().await?;
+    tx.execute("DELETE FROM item").await?;
+    tx.rollback().await?;
+
+    let mut reader = pool.read().await?;
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut *reader)
+        .await?;
+    assert_eq!(journal_mode, "wal");
+    drop(reader);
+
+    pool.close().await;
+    assert!(pool.is_closed());
+
+    assert!(SqliteRwPool::connect("sqlite::memory:").await.is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-snapshot")]
+#[sqlx_macros::test]
+async fn it_reads_from_a_snapshot_on_another_connection() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteJournalMode, SqlitePoolOptions};
+
+    let dir = tempdir::TempDir::new("sqlx-snapshot")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("data.db"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(3)
+        .connect_with(options)
+        .await?;
+
+    pool.execute("CREATE TABLE item (id INTEGER PRIMARY KEY); INSERT INTO item VALUES (1), (2)")
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut *tx)
+        .await?;
+    assert_eq!(count, 2);
+    let snapshot = tx.snapshot(None).await?;
+    tx.commit().await?;
+
+    // not in a read transaction
+    assert!(pool.acquire().await?.snapshot(None).await.is_err());
+
+    pool.execute("INSERT INTO item VALUES (3)").await?;
+
+    let mut tx = pool.begin().await?;
+    let mut other = pool.begin().await?;
+
+    for tx in [&mut tx, &mut other] {
+        tx.open_snapshot(None, &snapshot).await?;
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+            .fetch_one(&mut **tx)
+            .await?;
+        assert_eq!(count, 2);
+    }
+
+    drop((tx, other));
+
+    // not in a transaction
+    assert!(pool
+        .acquire()
+        .await?
+        .open_snapshot(None, &snapshot)
+        .await
+        .is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(count, 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_vacuums_into_a_new_file() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let dir = tempdir::TempDir::new("sqlx-vacuum")?;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let copy = dir.path().join("copy.db");
+    conn.vacuum_into(&copy).await?;
+
+    let mut copied = SqliteConnectOptions::new()
+        .filename(&copy)
+        .connect()
+        .await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tweet")
+        .fetch_one(&mut copied)
+        .await?;
+    let expected: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tweet")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, expected);
+
+    // the file exists already, and is kept
+    assert!(conn.vacuum_into(&copy).await.is_err());
+    assert!(copy.exists());
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let progress = calls.clone();
+    let second = dir.path().join("second.db");
+    conn.vacuum_into_with_progress(&second, 1, move || {
+        progress.fetch_add(1, Ordering::SeqCst);
+        true
+    })
+    .await?;
+    assert!(calls.load(Ordering::SeqCst) > 0);
+    assert!(second.exists());
+
+    // an interrupted copy is removed
+    let interrupted = dir.path().join("interrupted.db");
+    assert!(conn
+        .vacuum_into_with_progress(&interrupted, 1, || false)
+        .await
+        .is_err());
+    assert!(!interrupted.exists());
+
+    // the progress handler is removed afterwards
+    conn.execute("SELECT 1").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_keeps_a_shared_in_memory_database_while_the_options_exist() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::str::FromStr;
+
+    let options = SqliteConnectOptions::new().shared_in_memory("it_keeps_a_shared_database");
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options.clone())
+        .await?;
+
+    let mut conns = vec![pool.acquire().await?, pool.acquire().await?];
+    conns[0].execute("CREATE TABLE item (id INTEGER)").await?;
+    conns[1].execute("INSERT INTO item VALUES (1)").await?;
+    drop(conns);
+
+    // all connections are closed, but the options keep the database
+    pool.close().await;
+
+    let mut conn = options.connect().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 1);
+    conn.close().await?;
+
+    drop((pool, options));
+
+    let mut conn = SqliteConnectOptions::new()
+        .shared_in_memory("it_keeps_a_shared_database")
+        .connect()
+        .await?;
+    assert!(conn.execute("SELECT * FROM item").await.is_err());
+
+    // the same for a URL
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE item (id INTEGER)").await?;
+    conn.close().await?;
+
+    let mut conn = options.connect().await?;
+    conn.execute("SELECT * FROM item").await?;
+
+    Ok(())
+}